@@ -1,3 +1,4 @@
+pub mod network;
 pub mod pci_slot;
 
 use anyhow::{bail, Context, Result};
@@ -14,19 +15,61 @@ use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::Display;
 use std::fs::File;
 use std::io::{Read, Write};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 use std::os::linux::fs::MetadataExt;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::sync::{LazyLock, RwLock};
 use std::time::SystemTime;
+use uzers::os::unix::UserExt;
 
 const STAT_OFFSET: usize = 2; // we split the stat contents where the executable name ends, which is the second element
+const STAT_STATE: usize = 2 - STAT_OFFSET;
 const STAT_PARENT_PID: usize = 3 - STAT_OFFSET;
 const STAT_USER_CPU_TIME: usize = 13 - STAT_OFFSET;
 const STAT_SYSTEM_CPU_TIME: usize = 14 - STAT_OFFSET;
 const STAT_NICE: usize = 18 - STAT_OFFSET;
 const STAT_STARTTIME: usize = 21 - STAT_OFFSET;
 
+/// Makes `resources-processes` output the threads of a single process instead of all processes, the PID has to follow
+/// as little-endian bytes
+pub const THREADS_REQUEST: u8 = b't';
+
+/// Makes `resources-processes` output the raw DRM fdinfo fields of a single process, the PID has to follow as
+/// little-endian bytes
+pub const DRM_FDINFO_REQUEST: u8 = b'd';
+
+/// Makes `resources-processes` output the resource limits of a single process, the PID has to follow as little-endian
+/// bytes
+pub const LIMITS_REQUEST: u8 = b'l';
+
+/// Makes `resources-processes` output the open sockets of a single process, the PID has to follow as little-endian
+/// bytes
+pub const SOCKETS_REQUEST: u8 = b's';
+
+/// Makes `resources-processes` output the environment variables of a single process, the PID has to follow as
+/// little-endian bytes
+pub const ENVIRONMENT_REQUEST: u8 = b'e';
+
+/// Makes `resources-processes` output the open files of a single process, the PID has to follow as little-endian bytes
+pub const OPEN_FILES_REQUEST: u8 = b'f';
+
+/// Makes `resources-processes` output the wait channel of a single process, the PID has to follow as little-endian bytes
+pub const WAIT_CHANNEL_REQUEST: u8 = b'c';
+
+/// Makes `resources-processes` output the scheduler statistics of a single process, the PID has to follow as
+/// little-endian bytes
+pub const SCHEDSTAT_REQUEST: u8 = b'S';
+
+/// Makes `resources-processes` output the addresses of all network interfaces, nothing has to follow
+pub const INTERFACE_ADDRESSES_REQUEST: u8 = b'a';
+
+/// Makes `resources-processes` output the links of all Wi-Fi interfaces, nothing has to follow
+pub const WIFI_LINKS_REQUEST: u8 = b'w';
+
+/// Flag of Unix domain sockets in /proc/net/unix that are listening, see `__SO_ACCEPTCON` in include/linux/net.h
+const UNIX_SOCKET_LISTENING: u32 = 1 << 16;
+
 static USERS_CACHE: LazyLock<HashMap<libc::uid_t, String>> = LazyLock::new(|| unsafe {
     uzers::all_users()
         .map(|user| (user.uid(), user.name().to_string_lossy().to_string()))
@@ -43,6 +86,10 @@ static RE_AFFINITY: Lazy<Regex> = lazy_regex!(r"Cpus_allowed:\s*([0-9A-Fa-f]+)")
 
 static RE_SWAP_USAGGE: Lazy<Regex> = lazy_regex!(r"VmSwap:\s*([0-9]+)\s*kB");
 
+static RE_LOCKED_MEMORY: Lazy<Regex> = lazy_regex!(r"VmLck:\s*([0-9]+)\s*kB");
+
+static RE_PINNED_MEMORY: Lazy<Regex> = lazy_regex!(r"VmPin:\s*([0-9]+)\s*kB");
+
 static RE_IO_READ: Lazy<Regex> = lazy_regex!(r"read_bytes:\s*(\d+)");
 
 static RE_IO_WRITE: Lazy<Regex> = lazy_regex!(r"write_bytes:\s*(\d+)");
@@ -76,9 +123,46 @@ static RE_DRM_ENGINE_RENDER: Lazy<Regex> = lazy_regex!(r"drm-engine-render:\s*(\
 // Intel only
 static RE_DRM_ENGINE_VIDEO: Lazy<Regex> = lazy_regex!(r"drm-engine-video:\s*(\d+)\s*ns");
 
+// v3d only, the binner, texture formatting unit, compute shader dispatch and cache cleaning queues besides the render
+// queue that v3d shares with Intel
+static RE_DRM_ENGINE_V3D: Lazy<Regex> =
+    lazy_regex!(r"drm-engine-(?:bin|tfu|csd|cache_clean):\s*(\d+)\s*ns");
+
 // v3d only
 static RE_DRM_TOTAL_MEMORY: Lazy<Regex> = lazy_regex!(r"drm-total-memory:\s*(\d+)\s*KiB");
 
+// Intel only, i915 calls its VRAM regions "local0", "local1", … while xe calls them "vram0", "vram1", …
+static RE_DRM_RESIDENT_LOCAL: Lazy<Regex> =
+    lazy_regex!(r"drm-resident-(?:local|vram)\d+:\s*(\d+)(?:\s*(KiB|MiB))?");
+
+// Intel only, "system0" on i915 and "system" as well as "gtt" on xe
+static RE_DRM_RESIDENT_SYSTEM: Lazy<Regex> =
+    lazy_regex!(r"drm-resident-(?:system\d*|gtt):\s*(\d+)(?:\s*(KiB|MiB))?");
+
+// xe only
+static RE_DRM_CYCLES_RCS: Lazy<Regex> = lazy_regex!(r"drm-cycles-rcs:\s*(\d+)");
+
+// xe only
+static RE_DRM_CYCLES_CCS: Lazy<Regex> = lazy_regex!(r"drm-cycles-ccs:\s*(\d+)");
+
+// xe only
+static RE_DRM_CYCLES_VCS: Lazy<Regex> = lazy_regex!(r"drm-cycles-vcs:\s*(\d+)");
+
+// xe only
+static RE_DRM_CYCLES_VECS: Lazy<Regex> = lazy_regex!(r"drm-cycles-vecs:\s*(\d+)");
+
+// xe only
+static RE_DRM_TOTAL_CYCLES_RCS: Lazy<Regex> = lazy_regex!(r"drm-total-cycles-rcs:\s*(\d+)");
+
+// xe only
+static RE_DRM_TOTAL_CYCLES_VCS: Lazy<Regex> = lazy_regex!(r"drm-total-cycles-vcs:\s*(\d+)");
+
+static RE_CPU_STAT_NR_PERIODS: Lazy<Regex> = lazy_regex!(r"nr_periods\s+(\d+)");
+
+static RE_CPU_STAT_NR_THROTTLED: Lazy<Regex> = lazy_regex!(r"nr_throttled\s+(\d+)");
+
+static RE_CPU_STAT_THROTTLED_USEC: Lazy<Regex> = lazy_regex!(r"throttled_usec\s+(\d+)");
+
 static NVML: Lazy<Result<Nvml, NvmlError>> = Lazy::new(Nvml::init);
 
 static NVML_DEVICES: Lazy<Vec<(PciSlot, Device)>> = Lazy::new(|| {
@@ -99,6 +183,28 @@ static NVML_DEVICES: Lazy<Vec<(PciSlot, Device)>> = Lazy::new(|| {
     }
 });
 
+/// Names of Docker and Podman containers by their ID, they're cached since looking them up means reading the runtime's
+/// state on every refresh otherwise. Containers whose name couldn't be found (yet) aren't cached and containers without
+/// processes are dropped after every refresh.
+static CONTAINER_NAMES: LazyLock<RwLock<HashMap<String, String>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+static RE_DOCKER_CONTAINER_NAME: Lazy<Regex> = lazy_regex!(r#""Name"\s*:\s*"/([^"]+)""#);
+
+static RE_PODMAN_CONTAINER: Lazy<Regex> =
+    lazy_regex!(r#""id"\s*:\s*"([0-9a-f]{64})"\s*,\s*"names"\s*:\s*\[\s*"([^"]+)""#);
+
+const PATH_DOCKER_CONTAINERS: &str = "/var/lib/docker/containers";
+
+/// Where Podman keeps its containers when running as root, rootless containers are in the user's
+/// `~/.local/share/containers` instead
+const PATH_PODMAN_STORAGE: &str = "/var/lib/containers/storage";
+
+/// CPU throttling statistics that apply to a cgroup by its path, for cgroups without a quota of their own these are the
+/// ones of the closest ancestor with one. Processes commonly share their cgroups, so this is kept for one refresh
+static CGROUP_CPU_THROTTLING: LazyLock<RwLock<HashMap<PathBuf, Option<CpuThrottling>>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
 static NVIDIA_PROCESSES_STATS: Lazy<RwLock<HashMap<PciSlot, Vec<ProcessUtilizationSample>>>> =
     Lazy::new(|| RwLock::new(HashMap::new()));
 
@@ -121,6 +227,183 @@ pub enum Containerization {
     Snap,
 }
 
+#[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize, Deserialize, Copy)]
+pub enum ContainerRuntime {
+    Docker,
+    Podman,
+    Lxc,
+}
+
+impl Display for ContainerRuntime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ContainerRuntime::Docker => write!(f, "Docker"),
+            ContainerRuntime::Podman => write!(f, "Podman"),
+            ContainerRuntime::Lxc => write!(f, "LXC"),
+        }
+    }
+}
+
+/// A container that processes are running in, as determined by their cgroup
+#[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Container {
+    pub runtime: ContainerRuntime,
+    /// The full container ID for Docker and Podman, LXC containers are identified by their name
+    pub id: String,
+    pub name: Option<String>,
+}
+
+impl Container {
+    /// Determines the container a process is in from the contents of its cgroup file. The container's name is only
+    /// known for LXC at this point.
+    fn from_cgroup<S: AsRef<str>>(cgroup: S) -> Option<Self> {
+        let cgroups_v2_path = cgroup
+            .as_ref()
+            .split('\n')
+            .find_map(|s| s.strip_prefix("0::"))?;
+
+        // with the systemd cgroup driver, containers get their own scope (e.g. `/system.slice/docker-<id>.scope`)
+        for segment in cgroups_v2_path.split('/') {
+            let segment = segment.strip_suffix(".scope").unwrap_or(segment);
+
+            if let Some(name) = segment.strip_prefix("lxc.payload.") {
+                return Some(Self::lxc(name));
+            }
+
+            // conmon is what monitors a Podman container from outside of it
+            if segment.starts_with("libpod-conmon-") {
+                return None;
+            }
+
+            let (runtime, id) = if let Some(id) = segment.strip_prefix("docker-") {
+                (ContainerRuntime::Docker, id)
+            } else if let Some(id) = segment.strip_prefix("libpod-") {
+                (ContainerRuntime::Podman, id)
+            } else {
+                continue;
+            };
+
+            if Self::is_container_id(id) {
+                return Some(Self {
+                    runtime,
+                    id: id.to_string(),
+                    name: None,
+                });
+            }
+        }
+
+        // with the cgroupfs driver, containers are below a cgroup named after the runtime (e.g. `/docker/<id>`)
+        let mut segments = cgroups_v2_path.split('/').filter(|s| !s.is_empty());
+        match (segments.next(), segments.next()) {
+            (Some("docker"), Some(id)) if Self::is_container_id(id) => Some(Self {
+                runtime: ContainerRuntime::Docker,
+                id: id.to_string(),
+                name: None,
+            }),
+            (Some("lxc"), Some(name)) => Some(Self::lxc(name)),
+            _ => None,
+        }
+    }
+
+    fn lxc(name: &str) -> Self {
+        Self {
+            runtime: ContainerRuntime::Lxc,
+            id: name.to_string(),
+            name: Some(name.to_string()),
+        }
+    }
+
+    fn is_container_id(id: &str) -> bool {
+        id.len() == 64 && id.bytes().all(|byte| byte.is_ascii_hexdigit())
+    }
+
+    /// The abbreviated ID as shown by `docker ps` and `podman ps`
+    pub fn short_id(&self) -> &str {
+        match self.runtime {
+            ContainerRuntime::Docker | ContainerRuntime::Podman => {
+                self.id.get(..12).unwrap_or(&self.id)
+            }
+            ContainerRuntime::Lxc => &self.id,
+        }
+    }
+
+    /// The container's name if it's known, its abbreviated ID otherwise
+    pub fn display_name(&self) -> &str {
+        self.name.as_deref().unwrap_or_else(|| self.short_id())
+    }
+
+    /// Looks up the name of the container in the state of its runtime, `uid` is the owner of the process in the
+    /// container, whose home contains the state of rootless Podman containers
+    fn resolve_name(&mut self, uid: libc::uid_t) {
+        if self.name.is_some() {
+            return;
+        }
+
+        if let Some(name) = CONTAINER_NAMES
+            .read()
+            .ok()
+            .and_then(|names| names.get(&self.id).cloned())
+        {
+            self.name = Some(name);
+            return;
+        }
+
+        let name = match self.runtime {
+            ContainerRuntime::Docker => std::fs::read_to_string(
+                Path::new(PATH_DOCKER_CONTAINERS)
+                    .join(&self.id)
+                    .join("config.v2.json"),
+            )
+            .ok()
+            .and_then(|config| Self::docker_name(&config)),
+            ContainerRuntime::Podman => {
+                let storage = if uid == 0 {
+                    Some(Path::new(PATH_PODMAN_STORAGE).to_path_buf())
+                } else {
+                    uzers::get_user_by_uid(uid)
+                        .map(|user| user.home_dir().join(".local/share/containers/storage"))
+                };
+
+                storage
+                    .and_then(|storage| {
+                        std::fs::read_to_string(storage.join("overlay-containers/containers.json"))
+                            .ok()
+                    })
+                    .and_then(|containers| Self::podman_names(&containers).remove(&self.id))
+            }
+            ContainerRuntime::Lxc => None,
+        };
+
+        // the runtime might not have written its state yet if the container has only just been started
+        if let (Some(name), Ok(mut names)) = (&name, CONTAINER_NAMES.write()) {
+            names.insert(self.id.clone(), name.clone());
+        }
+
+        self.name = name;
+    }
+
+    /// Returns the name of a Docker container from its `config.v2.json`
+    fn docker_name(config: &str) -> Option<String> {
+        RE_DOCKER_CONTAINER_NAME
+            .captures(config)
+            .and_then(|captures| captures.get(1))
+            .map(|capture| capture.as_str().to_string())
+    }
+
+    /// Returns the names of all Podman containers by their ID from Podman's `containers.json`
+    fn podman_names(containers: &str) -> HashMap<String, String> {
+        RE_PODMAN_CONTAINER
+            .captures_iter(containers)
+            .filter_map(|captures| {
+                Some((
+                    captures.get(1)?.as_str().to_string(),
+                    captures.get(2)?.as_str().to_string(),
+                ))
+            })
+            .collect()
+    }
+}
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize, Deserialize, Copy, PartialOrd, Ord)]
 pub enum GpuIdentifier {
     PciSlot(PciSlot),
@@ -142,6 +425,136 @@ impl Display for GpuIdentifier {
     }
 }
 
+impl FromStr for GpuIdentifier {
+    type Err = pci_slot::ParseError;
+
+    /// Parses the output of `Display`, i.e. either a PCI slot or a plain number
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(enumerator) = s.parse() {
+            Ok(GpuIdentifier::Enumerator(enumerator))
+        } else {
+            PciSlot::from_str(s).map(GpuIdentifier::PciSlot)
+        }
+    }
+}
+
+/// CPU bandwidth statistics of a cgroup that has a CPU quota set, as found in its `cpu.stat`
+#[derive(Debug, Default, Clone, Hash, PartialEq, Eq, Serialize, Deserialize, Copy)]
+pub struct CpuThrottling {
+    pub nr_periods: u64,
+    pub nr_throttled: u64,
+    pub throttled_usec: u64,
+}
+
+/// What a process is doing from the kernel's point of view. It's read on demand since only the process dialog shows it.
+#[derive(Debug, Default, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WaitChannel {
+    Running,
+    /// The process is sleeping in the kernel function with this name
+    Sleeping(String),
+    /// The process is neither running nor does the kernel tell us what it's waiting for
+    #[default]
+    Unknown,
+}
+
+impl WaitChannel {
+    /// Returns the wait channel of the process with the given `pid`
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the stat file of the process can't be read or parsed, e.g. because the process has exited
+    pub fn of_process(pid: libc::pid_t) -> Result<Self> {
+        let stat = std::fs::read_to_string(format!("/proc/{pid}/stat"))?;
+
+        let state = Self::state_from_stat(&stat).context("wrong stat file format")?;
+
+        // wchan is only worth reading for sleeping processes
+        let wchan = if state == 'S' || state == 'D' {
+            std::fs::read_to_string(format!("/proc/{pid}/wchan")).ok()
+        } else {
+            None
+        };
+
+        Ok(Self::from_state(state, wchan.as_deref()))
+    }
+
+    /// The executable name in the stat file may contain spaces and parentheses, so look for the state after its end
+    fn state_from_stat(stat: &str) -> Option<char> {
+        stat.rsplit_once(')')?.1.trim_start().chars().next()
+    }
+
+    fn from_state(state: char, wchan: Option<&str>) -> Self {
+        // wchan is "0" if the process isn't blocked in the kernel or if the kernel doesn't want to tell us
+        match (state, wchan.map(str::trim)) {
+            ('R', _) => Self::Running,
+            ('S' | 'D', Some(wchan)) if !wchan.is_empty() && wchan != "0" => {
+                Self::Sleeping(wchan.to_string())
+            }
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// Scheduler statistics of a process as found in /proc/<pid>/schedstat
+#[derive(Debug, Default, Clone, Hash, PartialEq, Eq, Serialize, Deserialize, Copy)]
+pub struct SchedStat {
+    /// Nanoseconds spent on the CPU
+    pub run_time: u64,
+    /// Nanoseconds spent waiting on a runqueue to be run
+    pub wait_time: u64,
+    pub timeslices: u64,
+}
+
+impl SchedStat {
+    /// Returns the scheduler statistics of the process with the given `pid`, `None` if the kernel doesn't collect them
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the schedstat file of the process can't be read, e.g. because the process has exited
+    pub fn of_process(pid: libc::pid_t) -> Result<Option<Self>> {
+        let schedstat = std::fs::read_to_string(format!("/proc/{pid}/schedstat"))?;
+
+        Ok(Self::parse(schedstat))
+    }
+
+    /// Returns `None` for processes that haven't run yet and if the kernel doesn't collect scheduler statistics, in
+    /// which case it reports zero timeslices
+    fn parse<S: AsRef<str>>(schedstat: S) -> Option<Self> {
+        let mut fields = schedstat
+            .as_ref()
+            .split_whitespace()
+            .map(|field| field.parse::<u64>().ok());
+
+        let schedstat = Self {
+            run_time: fields.next()??,
+            wait_time: fields.next()??,
+            timeslices: fields.next()??,
+        };
+
+        (schedstat.timeslices > 0).then_some(schedstat)
+    }
+
+    /// Average nanoseconds a timeslice lasted
+    pub fn average_run_time(&self) -> f64 {
+        self.run_time as f64 / self.timeslices as f64
+    }
+
+    /// Average nanoseconds the process had to wait before it could run
+    pub fn average_wait_time(&self) -> f64 {
+        self.wait_time as f64 / self.timeslices as f64
+    }
+
+    /// Ratio between the time spent waiting to be run and the time spent running, values well above 1 indicate that
+    /// the process is starved of CPU time
+    pub fn wait_ratio(&self) -> f64 {
+        if self.run_time == 0 {
+            0.0
+        } else {
+            self.wait_time as f64 / self.run_time as f64
+        }
+    }
+}
+
 /// Represents GPU usage statistics per-process. Depending on the GPU manufacturer (which should be determined in
 /// Resources itself), these numbers need to interpreted differently
 ///
@@ -150,14 +563,615 @@ impl Display for GpuIdentifier {
 /// Nvidia: Process info is gathered through NVML, thus gfx, enc and dec are percentages from 0-100 (timestamps
 /// are irrelevant, nvidia bool is set to true)
 ///
-/// Intel: enc and dec are not separated, both are accumulated in enc, also mem is always going to be 0
+/// Intel: enc and dec are not separated, both are accumulated in enc
+///
+/// Intel (xe): like Intel, but gfx and enc are the GPU cycles the engines were busy for. The total amount of cycles that
+/// have passed is in `gfx_total_cycles` and `media_total_cycles`
+///
+/// `mem` is the total amount of memory used by the process on that GPU, `mem_system` is the part of it that resides in
+/// system memory instead of VRAM (GTT on AMD, the system memory region on Intel). It's always 0 for other GPUs.
 #[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize, Deserialize, Copy)]
 pub struct GpuUsageStats {
     pub gfx: u64,
     pub mem: u64,
+    pub mem_system: u64,
     pub enc: u64,
     pub dec: u64,
     pub nvidia: bool,
+    pub gfx_total_cycles: Option<u64>,
+    pub media_total_cycles: Option<u64>,
+}
+
+impl GpuUsageStats {
+    /// Returns the fraction of time the gfx engines were in use between `old` and `self`, with `millis_passed` being
+    /// the amount of milliseconds between both
+    pub fn gfx_fraction(&self, old: &Self, millis_passed: u64) -> f32 {
+        self.fraction(
+            (self.gfx, old.gfx),
+            (self.gfx_total_cycles, old.gfx_total_cycles),
+            millis_passed,
+        )
+    }
+
+    /// Returns the fraction of time the encoder was in use between `old` and `self`, with `millis_passed` being the
+    /// amount of milliseconds between both
+    pub fn enc_fraction(&self, old: &Self, millis_passed: u64) -> f32 {
+        self.fraction(
+            (self.enc, old.enc),
+            (self.media_total_cycles, old.media_total_cycles),
+            millis_passed,
+        )
+    }
+
+    /// Returns the fraction of time the decoder was in use between `old` and `self`, with `millis_passed` being the
+    /// amount of milliseconds between both
+    pub fn dec_fraction(&self, old: &Self, millis_passed: u64) -> f32 {
+        self.fraction(
+            (self.dec, old.dec),
+            (self.media_total_cycles, old.media_total_cycles),
+            millis_passed,
+        )
+    }
+
+    fn fraction(
+        &self,
+        (busy, old_busy): (u64, u64),
+        total_cycles: (Option<u64>, Option<u64>),
+        millis_passed: u64,
+    ) -> f32 {
+        let fraction = if self.nvidia {
+            busy as f32 / 100.0
+        } else if old_busy == 0 {
+            0.0
+        } else if let (Some(total_cycles), Some(old_total_cycles)) = total_cycles {
+            busy.saturating_sub(old_busy) as f32
+                / total_cycles.saturating_sub(old_total_cycles) as f32
+        } else {
+            (busy.saturating_sub(old_busy) as f32 / millis_passed as f32) / 1_000_000.0
+        };
+
+        // engines that run in parallel (like v3d's) are added up, so their busy time may exceed the time that has passed
+        if fraction.is_finite() {
+            fraction.min(1.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+/// A single thread (task) of a process as found in /proc/<pid>/task/<tid>
+#[derive(Debug, Default, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThreadData {
+    pub tid: libc::pid_t,
+    pub name: String,
+    pub state: char,
+    pub user_cpu_time: u64,
+    pub system_cpu_time: u64,
+}
+
+impl ThreadData {
+    /// Returns the threads of the process with the given `pid`, sorted by their TID
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the task directory of the process can't be read, e.g. because the process has exited
+    pub fn all_of_process(pid: libc::pid_t) -> Result<Vec<Self>> {
+        let mut threads: Vec<Self> = std::fs::read_dir(format!("/proc/{pid}/task"))?
+            .flatten()
+            .filter_map(|entry| {
+                let tid = entry.file_name().to_str()?.parse().ok()?;
+                let stat = std::fs::read_to_string(entry.path().join("stat")).ok()?;
+                Self::parse_stat(tid, stat).ok()
+            })
+            .collect();
+
+        threads.sort_by_key(|thread| thread.tid);
+
+        Ok(threads)
+    }
+
+    fn parse_stat<S: AsRef<str>>(tid: libc::pid_t, stat: S) -> Result<Self> {
+        let stat = stat.as_ref();
+
+        // thread names may contain parentheses themselves, so look for the last one
+        let (head, tail) = stat.rsplit_once(')').context("stat doesn't have ')'")?;
+
+        let name = head
+            .split_once('(')
+            .map(|(_, name)| name.to_string())
+            .context("stat doesn't have '('")?;
+
+        let fields = tail.split_whitespace().collect::<Vec<_>>();
+
+        let state = fields
+            .get(STAT_STATE)
+            .and_then(|x| x.chars().next())
+            .context("wrong stat file format")?;
+        let user_cpu_time = fields
+            .get(STAT_USER_CPU_TIME)
+            .context("wrong stat file format")
+            .and_then(|x| x.parse().context("couldn't parse stat file content"))?;
+        let system_cpu_time = fields
+            .get(STAT_SYSTEM_CPU_TIME)
+            .context("wrong stat file format")
+            .and_then(|x| x.parse().context("couldn't parse stat file content"))?;
+
+        Ok(Self {
+            tid,
+            name,
+            state,
+            user_cpu_time,
+            system_cpu_time,
+        })
+    }
+}
+
+/// The raw `drm-*` fields of a single DRM file descriptor of a process, as found in /proc/<pid>/fdinfo/<fd>. These are
+/// what `GpuUsageStats` are calculated from.
+#[derive(Debug, Default, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DrmFdinfo {
+    pub fd: usize,
+    pub gpu_identifier: GpuIdentifier,
+    /// Key: name of the field, e.g. "drm-engine-gfx"
+    pub fields: BTreeMap<String, String>,
+}
+
+impl DrmFdinfo {
+    /// Returns the DRM fdinfos of the process with the given `pid`, sorted by their file descriptor. File descriptors
+    /// referring to the same DRM file are only included once.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the fdinfo directory of the process can't be read, e.g. because the process has exited
+    pub fn all_of_process(pid: libc::pid_t) -> Result<Vec<Self>> {
+        let mut fdinfos = Vec::new();
+
+        ProcessData::for_each_drm_fdinfo(
+            Path::new(&format!("/proc/{pid}")),
+            pid,
+            |fd, content, drm_device| {
+                let gpu_identifier = ProcessData::parse_fdinfo(content, || {
+                    drm_device.and_then(ProcessData::gpu_identifier_from_drm_device)
+                })
+                .map(|(gpu_identifier, _)| gpu_identifier)
+                .unwrap_or_default();
+
+                fdinfos.push(Self {
+                    fd,
+                    gpu_identifier,
+                    fields: Self::parse_fields(content),
+                });
+            },
+        )?;
+
+        fdinfos.sort_by_key(|fdinfo| fdinfo.fd);
+
+        Ok(fdinfos)
+    }
+
+    /// Collects the `drm-*` fields of an fdinfo file, leaving out generic ones like `pos` or `flags`
+    fn parse_fields<S: AsRef<str>>(content: S) -> BTreeMap<String, String> {
+        content
+            .as_ref()
+            .lines()
+            .filter(|line| line.starts_with("drm-"))
+            .filter_map(|line| line.split_once(':'))
+            .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+            .collect()
+    }
+}
+
+/// A single resource limit of a process as found in /proc/<pid>/limits, limits that are `None` are unlimited
+#[derive(Debug, Default, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceLimit {
+    /// Name of the limit as given by the kernel, e.g. "Max open files"
+    pub name: String,
+    pub soft_limit: Option<u64>,
+    pub hard_limit: Option<u64>,
+    pub unit: Option<String>,
+}
+
+/// The resource limits of a process along with the number of file descriptors it currently has open
+#[derive(Debug, Default, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProcessLimits {
+    pub limits: Vec<ResourceLimit>,
+    /// `None` if the fd directory of the process isn't accessible
+    pub open_fds: Option<usize>,
+}
+
+impl ProcessLimits {
+    /// Returns the resource limits of the process with the given `pid`
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the limits file of the process can't be read or parsed, e.g. because the process has exited
+    pub fn of_process(pid: libc::pid_t) -> Result<Self> {
+        let limits = Self::parse_limits(std::fs::read_to_string(format!("/proc/{pid}/limits"))?)?;
+
+        let open_fds = std::fs::read_dir(format!("/proc/{pid}/fd"))
+            .ok()
+            .map(|entries| entries.count());
+
+        Ok(Self { limits, open_fds })
+    }
+
+    /// The limits file is a table with fixed-width columns whose positions are taken from its header, the unit column
+    /// may be empty
+    fn parse_limits<S: AsRef<str>>(limits: S) -> Result<Vec<ResourceLimit>> {
+        let mut lines = limits.as_ref().lines();
+
+        let header = lines.next().context("limits file is empty")?;
+        let soft_start = header
+            .find("Soft Limit")
+            .context("limits file has no soft limit column")?;
+        let hard_start = header
+            .find("Hard Limit")
+            .context("limits file has no hard limit column")?;
+        let unit_start = header
+            .find("Units")
+            .context("limits file has no units column")?;
+
+        let parse_limit = |value: &str| match value {
+            "unlimited" => Some(None),
+            value => value.parse().ok().map(Some),
+        };
+
+        let limits = lines
+            .filter_map(|line| {
+                let column = |start: usize, end: usize| {
+                    line.get(start.min(line.len())..end.min(line.len()))
+                        .unwrap_or_default()
+                        .trim()
+                };
+
+                let name = column(0, soft_start);
+                if name.is_empty() {
+                    return None;
+                }
+
+                let unit = column(unit_start, line.len());
+
+                Some(ResourceLimit {
+                    name: name.to_string(),
+                    soft_limit: parse_limit(column(soft_start, hard_start))?,
+                    hard_limit: parse_limit(column(hard_start, unit_start))?,
+                    unit: (!unit.is_empty()).then(|| unit.to_string()),
+                })
+            })
+            .collect();
+
+        Ok(limits)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SocketProtocol {
+    Tcp,
+    Udp,
+    Unix,
+}
+
+/// The state of a socket, TCP sockets use all of these (see include/net/tcp_states.h), UDP and Unix domain sockets
+/// only a subset of them
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SocketState {
+    Established,
+    SynSent,
+    SynReceived,
+    FinWait1,
+    FinWait2,
+    TimeWait,
+    Close,
+    CloseWait,
+    LastAck,
+    Listen,
+    Closing,
+    Unknown,
+}
+
+impl SocketState {
+    fn from_tcp_state(state: u8) -> Self {
+        match state {
+            0x01 => Self::Established,
+            0x02 => Self::SynSent,
+            0x03 => Self::SynReceived,
+            0x04 => Self::FinWait1,
+            0x05 => Self::FinWait2,
+            0x06 => Self::TimeWait,
+            0x07 => Self::Close,
+            0x08 => Self::CloseWait,
+            0x09 => Self::LastAck,
+            0x0A => Self::Listen,
+            0x0B => Self::Closing,
+            _ => Self::Unknown,
+        }
+    }
+
+    /// Unix domain sockets have their own states (see `socket_state` in include/uapi/linux/net.h), they are mapped to
+    /// their closest TCP counterpart
+    fn from_unix_state(state: u8, flags: u32) -> Self {
+        if flags & UNIX_SOCKET_LISTENING != 0 {
+            return Self::Listen;
+        }
+
+        match state {
+            0x01 => Self::Close,
+            0x02 => Self::SynSent,
+            0x03 => Self::Established,
+            0x04 => Self::Closing,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// A socket opened by a process, found by matching the inodes of its file descriptors with the sockets in
+/// /proc/<pid>/net/{tcp,tcp6,udp,udp6,unix}
+#[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SocketData {
+    pub fd: usize,
+    pub protocol: SocketProtocol,
+    /// `None` for Unix domain sockets
+    pub local_address: Option<SocketAddr>,
+    /// `None` for Unix domain sockets and for sockets that aren't connected to a remote
+    pub remote_address: Option<SocketAddr>,
+    /// Path of Unix domain sockets, `None` for unnamed ones and for TCP and UDP sockets
+    pub path: Option<String>,
+    pub state: SocketState,
+}
+
+impl SocketData {
+    /// Returns the sockets opened by the process with the given `pid`, sorted by their file descriptor
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the fd directory of the process can't be read, e.g. because the process has exited
+    pub fn all_of_process(pid: libc::pid_t) -> Result<Vec<Self>> {
+        let fds_by_inode: HashMap<u64, usize> = std::fs::read_dir(format!("/proc/{pid}/fd"))?
+            .flatten()
+            .filter_map(|entry| {
+                let fd = entry.file_name().to_str()?.parse().ok()?;
+                let target = std::fs::read_link(entry.path()).ok()?;
+                let inode = Self::parse_socket_link(target.to_str()?)?;
+                Some((inode, fd))
+            })
+            .collect();
+
+        if fds_by_inode.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // the files in /proc/<pid>/net describe the network namespace of the process, which is not necessarily ours
+        let mut sockets = Vec::new();
+
+        for (file_name, protocol) in [
+            ("tcp", SocketProtocol::Tcp),
+            ("tcp6", SocketProtocol::Tcp),
+            ("udp", SocketProtocol::Udp),
+            ("udp6", SocketProtocol::Udp),
+        ] {
+            if let Ok(table) = std::fs::read_to_string(format!("/proc/{pid}/net/{file_name}")) {
+                sockets.extend(Self::parse_inet_table(table, protocol, &fds_by_inode));
+            }
+        }
+
+        if let Ok(table) = std::fs::read_to_string(format!("/proc/{pid}/net/unix")) {
+            sockets.extend(Self::parse_unix_table(table, &fds_by_inode));
+        }
+
+        sockets.sort_by_key(|socket| socket.fd);
+
+        Ok(sockets)
+    }
+
+    /// Links of socket file descriptors look like `socket:[12345]` where 12345 is the inode of the socket
+    fn parse_socket_link<S: AsRef<str>>(link: S) -> Option<u64> {
+        link.as_ref()
+            .strip_prefix("socket:[")?
+            .strip_suffix(']')?
+            .parse()
+            .ok()
+    }
+
+    fn parse_inet_table<S: AsRef<str>>(
+        table: S,
+        protocol: SocketProtocol,
+        fds_by_inode: &HashMap<u64, usize>,
+    ) -> Vec<Self> {
+        table
+            .as_ref()
+            .lines()
+            .skip(1)
+            .filter_map(|line| {
+                let fields = line.split_whitespace().collect::<Vec<_>>();
+
+                let inode = fields.get(9)?.parse().ok()?;
+                let fd = *fds_by_inode.get(&inode)?;
+
+                let local_address = Self::parse_inet_address(fields.get(1)?)?;
+                let remote_address = Self::parse_inet_address(fields.get(2)?)
+                    .filter(|address| !address.ip().is_unspecified() || address.port() != 0);
+
+                let state =
+                    SocketState::from_tcp_state(u8::from_str_radix(fields.get(3)?, 16).ok()?);
+
+                Some(Self {
+                    fd,
+                    protocol,
+                    local_address: Some(local_address),
+                    remote_address,
+                    path: None,
+                    state,
+                })
+            })
+            .collect()
+    }
+
+    /// Addresses are given as `ADDRESS:PORT` in hexadecimal, the address is printed as one (IPv4) or four (IPv6)
+    /// 32-bit words in the byte order of the machine while the port is in host byte order
+    fn parse_inet_address<S: AsRef<str>>(address: S) -> Option<SocketAddr> {
+        let (address, port) = address.as_ref().split_once(':')?;
+
+        let port = u16::from_str_radix(port, 16).ok()?;
+
+        let words = (0..address.len())
+            .step_by(8)
+            .map(|i| {
+                address
+                    .get(i..i + 8)
+                    .and_then(|word| u32::from_str_radix(word, 16).ok())
+                    .map(u32::to_ne_bytes)
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        let ip = match words.as_slice() {
+            [word] => IpAddr::V4(Ipv4Addr::from(*word)),
+            [_, _, _, _] => {
+                let mut octets = [0; 16];
+                for (chunk, word) in octets.chunks_exact_mut(4).zip(&words) {
+                    chunk.copy_from_slice(word);
+                }
+                IpAddr::V6(Ipv6Addr::from(octets))
+            }
+            _ => return None,
+        };
+
+        Some(SocketAddr::new(ip, port))
+    }
+
+    /// The columns of /proc/net/unix are `Num RefCount Protocol Flags Type St Inode Path`, all but the inode and the
+    /// optional path are in hexadecimal
+    fn parse_unix_table<S: AsRef<str>>(table: S, fds_by_inode: &HashMap<u64, usize>) -> Vec<Self> {
+        table
+            .as_ref()
+            .lines()
+            .skip(1)
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+
+                let flags = u32::from_str_radix(fields.nth(3)?, 16).ok()?;
+                let state = u8::from_str_radix(fields.nth(1)?, 16).ok()?;
+                let inode = fields.next()?.parse().ok()?;
+                let fd = *fds_by_inode.get(&inode)?;
+
+                // paths may contain spaces, so everything after the inode belongs to it
+                let path = fields.collect::<Vec<_>>().join(" ");
+
+                Some(Self {
+                    fd,
+                    protocol: SocketProtocol::Unix,
+                    local_address: None,
+                    remote_address: None,
+                    path: (!path.is_empty()).then_some(path),
+                    state: SocketState::from_unix_state(state, flags),
+                })
+            })
+            .collect()
+    }
+}
+
+/// An environment variable of a process as found in /proc/<pid>/environ, which is the environment the process was
+/// started with
+#[derive(Debug, Default, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EnvironmentVariable {
+    pub name: String,
+    pub value: String,
+}
+
+impl EnvironmentVariable {
+    /// Returns the environment variables of the process with the given `pid`, sorted by their name
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the environ file of the process can't be read, e.g. because the process has exited or
+    /// belongs to another user
+    pub fn all_of_process(pid: libc::pid_t) -> Result<Vec<Self>> {
+        Ok(Self::parse_environ(std::fs::read(format!(
+            "/proc/{pid}/environ"
+        ))?))
+    }
+
+    /// The variables are separated by NUL bytes and aren't necessarily valid UTF-8
+    fn parse_environ<B: AsRef<[u8]>>(environ: B) -> Vec<Self> {
+        let mut variables = environ
+            .as_ref()
+            .split(|byte| *byte == 0)
+            .filter(|variable| !variable.is_empty())
+            .map(|variable| {
+                let variable = String::from_utf8_lossy(variable);
+                let (name, value) = variable.split_once('=').unwrap_or((&variable, ""));
+                Self {
+                    name: name.to_string(),
+                    value: value.to_string(),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        variables.sort_by(|a, b| a.name.cmp(&b.name));
+
+        variables
+    }
+}
+
+/// A file descriptor of a process along with what it refers to, as found in /proc/<pid>/fd
+#[derive(Debug, Default, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpenFile {
+    pub fd: usize,
+    /// Path of the file, or a description like `socket:[12345]` or `anon_inode:[eventfd]` for things that aren't files
+    pub target: String,
+    /// Device number of the block device the file is on, or of the block device itself if the file is one
+    pub block_device: Option<u64>,
+}
+
+impl OpenFile {
+    /// Returns the open files of the process with the given `pid`, sorted by their file descriptor
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the fd directory of the process can't be read, e.g. because the process has exited or
+    /// belongs to another user
+    pub fn all_of_process(pid: libc::pid_t) -> Result<Vec<Self>> {
+        let mut open_files = std::fs::read_dir(format!("/proc/{pid}/fd"))?
+            .flatten()
+            .filter_map(|entry| {
+                let fd = entry.file_name().to_str()?.parse().ok()?;
+                let target = std::fs::read_link(entry.path()).ok()?;
+                let block_device = std::fs::metadata(entry.path())
+                    .ok()
+                    .and_then(|metadata| Self::block_device(&metadata));
+                Some(Self {
+                    fd,
+                    target: target.to_string_lossy().into_owned(),
+                    block_device,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        open_files.sort_by_key(|open_file| open_file.fd);
+
+        Ok(open_files)
+    }
+
+    fn block_device(metadata: &std::fs::Metadata) -> Option<u64> {
+        let device = match metadata.st_mode() & libc::S_IFMT {
+            libc::S_IFBLK => metadata.st_rdev(),
+            // for files and directories, take the device backing the file system they live on
+            libc::S_IFREG | libc::S_IFDIR => metadata.st_dev(),
+            _ => return None,
+        };
+
+        // major 0 is used for virtual file systems like procfs or tmpfs that aren't backed by a block device
+        (libc::major(device) != 0).then_some(device)
+    }
+
+    /// Whether this is a file on a file system as opposed to e.g. a socket, a pipe or an anonymous inode
+    pub fn is_path(&self) -> bool {
+        self.target.starts_with('/')
+    }
+
+    /// Whether the file has been deleted while the process still had it open
+    pub fn is_deleted(&self) -> bool {
+        self.is_path() && self.target.ends_with(" (deleted)")
+    }
 }
 
 /// Data that could be transferred using `resources-processes`, separated from
@@ -170,15 +1184,29 @@ pub struct ProcessData {
     pub user: String,
     pub comm: String,
     pub commandline: String,
+    pub state: char,
     pub user_cpu_time: u64,
     pub system_cpu_time: u64,
     pub niceness: Niceness,
     pub affinity: Vec<bool>,
+    /// Resident memory that isn't shared with other processes
     pub memory_usage: usize,
+    /// Resident memory that is backed by files (including shared libraries) or otherwise shared, as reported by statm
+    pub shared_memory_usage: usize,
     pub swap_usage: usize,
+    /// Memory locked into RAM with `mlock()` and friends, it can't be swapped out
+    pub locked_memory_usage: usize,
+    /// Memory pinned into RAM because its pages need to stay at their physical address (e.g. for DMA)
+    pub pinned_memory_usage: usize,
     pub starttime: u64, // in clock ticks, see man proc(5)!
     pub cgroup: Option<String>,
+    /// Throttling statistics of the closest cgroup (starting at the process' own) that has a CPU quota
+    pub cpu_throttling: Option<CpuThrottling>,
     pub containerization: Containerization,
+    /// The Docker, Podman or LXC container the process runs in, if any
+    pub container: Option<Container>,
+    /// Path of the AppImage the process has been started from, if any
+    pub appimage: Option<String>,
     pub read_bytes: Option<u64>,
     pub write_bytes: Option<u64>,
     pub timestamp: u64,
@@ -187,7 +1215,21 @@ pub struct ProcessData {
 }
 
 impl ProcessData {
+    /// Returns the value of a field of /proc/<pid>/status that is given in kB (which are actually KiB), 0 if it's missing
+    fn status_kib(status: &str, regex: &Regex) -> usize {
+        regex
+            .captures(status)
+            .and_then(|captures| captures.get(1))
+            .and_then(|capture| capture.as_str().parse::<usize>().ok())
+            .unwrap_or_default()
+    }
+
     fn sanitize_cgroup<S: AsRef<str>>(cgroup: S) -> Option<String> {
+        // the scopes of containers are named after their full ID, which would otherwise be mangled below
+        if let Some(container) = Container::from_cgroup(cgroup.as_ref()) {
+            return Some(container.short_id().to_string());
+        }
+
         let cgroups_v2_line = cgroup.as_ref().split('\n').find(|s| s.starts_with("0::"))?;
         if cgroups_v2_line.ends_with(".scope") {
             let cgroups_segments: Vec<&str> = cgroups_v2_line.split('-').collect();
@@ -249,6 +1291,10 @@ impl ProcessData {
     pub fn all_process_data() -> Result<Vec<Self>> {
         Self::update_nvidia_stats();
 
+        if let Ok(mut cgroup_cpu_throttling) = CGROUP_CPU_THROTTLING.write() {
+            cgroup_cpu_throttling.clear();
+        }
+
         let mut process_data = vec![];
         for entry in glob("/proc/[0-9]*/").context("unable to glob")?.flatten() {
             let data = ProcessData::try_from_path(&entry);
@@ -258,6 +1304,16 @@ impl ProcessData {
             }
         }
 
+        if let Ok(mut container_names) = CONTAINER_NAMES.write() {
+            let container_ids: HashSet<&str> = process_data
+                .iter()
+                .filter_map(|data| data.container.as_ref())
+                .map(|container| container.id.as_str())
+                .collect();
+
+            container_names.retain(|id, _| container_ids.contains(id.as_str()));
+        }
+
         Ok(process_data)
     }
 
@@ -277,8 +1333,10 @@ impl ProcessData {
             .context("can't turn OsStr to str")?
             .parse()?;
 
+        let uid = Self::get_uid(proc_path)?;
+
         let user = USERS_CACHE
-            .get(&Self::get_uid(proc_path)?)
+            .get(&uid)
             .cloned()
             .unwrap_or(String::from("root"));
 
@@ -295,6 +1353,10 @@ impl ProcessData {
         let comm = comm.replace('\n', "");
 
         // -2 to accommodate for only collecting after the second item (which is the executable name as mentioned above)
+        let state = stat
+            .get(STAT_STATE)
+            .and_then(|x| x.chars().next())
+            .context("wrong stat file format")?;
         let parent_pid = stat
             .get(STAT_PARENT_PID)
             .context("wrong stat file format")
@@ -344,27 +1406,46 @@ impl ProcessData {
             .unwrap_or_default() // kworkers don't have swap usage
             .saturating_mul(1000);
 
-        let memory_usage = statm
+        let locked_memory_usage = Self::status_kib(&status, &RE_LOCKED_MEMORY).saturating_mul(1024);
+
+        let pinned_memory_usage = Self::status_kib(&status, &RE_PINNED_MEMORY).saturating_mul(1024);
+
+        let resident_pages = statm
             .get(1)
             .context("wrong statm file format")
             .and_then(|x| {
                 x.parse::<usize>()
                     .context("couldn't parse statm file content")
-            })?
-            .saturating_sub(
-                statm
-                    .get(2)
-                    .context("wrong statm file format")
-                    .and_then(|x| {
-                        x.parse::<usize>()
-                            .context("couldn't parse statm file content")
-                    })?,
-            )
+            })?;
+
+        let shared_pages = statm
+            .get(2)
+            .context("wrong statm file format")
+            .and_then(|x| {
+                x.parse::<usize>()
+                    .context("couldn't parse statm file content")
+            })?;
+
+        let memory_usage = resident_pages
+            .saturating_sub(shared_pages)
             .saturating_mul(*PAGESIZE);
 
-        let cgroup = std::fs::read_to_string(proc_path.join("cgroup"))
-            .ok()
-            .and_then(Self::sanitize_cgroup);
+        let shared_memory_usage = shared_pages.saturating_mul(*PAGESIZE);
+
+        let cgroup_file = std::fs::read_to_string(proc_path.join("cgroup")).ok();
+
+        let cgroup = cgroup_file.as_ref().and_then(Self::sanitize_cgroup);
+
+        let cpu_throttling = cgroup_file.as_ref().and_then(Self::cpu_throttling);
+
+        let container =
+            cgroup_file
+                .as_ref()
+                .and_then(Container::from_cgroup)
+                .map(|mut container| {
+                    container.resolve_name(uid);
+                    container
+                });
 
         let containerization = if commandline.starts_with("/snap/") {
             Containerization::Snap
@@ -374,6 +1455,15 @@ impl ProcessData {
             Containerization::None
         };
 
+        // AppImages are run from a FUSE mount like /tmp/.mount_KritaXXXXXX, only the environment knows the actual file
+        let appimage = if commandline.contains("/.mount_") {
+            std::fs::read(proc_path.join("environ"))
+                .ok()
+                .and_then(|environ| Self::environment_variable(&environ, "APPIMAGE"))
+        } else {
+            None
+        };
+
         let read_bytes = io.as_ref().and_then(|io| {
             RE_IO_READ
                 .captures(io)
@@ -398,15 +1488,22 @@ impl ProcessData {
             user,
             comm,
             commandline,
+            state,
             user_cpu_time,
             system_cpu_time,
             niceness: nice,
             affinity,
             memory_usage,
+            shared_memory_usage,
             swap_usage,
+            locked_memory_usage,
+            pinned_memory_usage,
             starttime,
             cgroup,
+            cpu_throttling,
             containerization,
+            container,
+            appimage,
             read_bytes,
             write_bytes,
             timestamp,
@@ -414,6 +1511,81 @@ impl ProcessData {
         })
     }
 
+    fn cpu_throttling<S: AsRef<str>>(cgroup: S) -> Option<CpuThrottling> {
+        let cgroups_v2_path = cgroup
+            .as_ref()
+            .split('\n')
+            .find_map(|s| s.strip_prefix("0::"))?
+            .trim_start_matches('/');
+
+        let cgroup_root = Path::new("/sys/fs/cgroup");
+
+        let mut cgroup_cpu_throttling = CGROUP_CPU_THROTTLING.write().ok()?;
+
+        // the quota might be set on a parent (e.g. a container's scope), so walk up until we find one or reach a cgroup
+        // we've already looked at during this refresh
+        let mut unresolved_paths = Vec::new();
+        let mut cpu_throttling = None;
+
+        for cgroup_path in cgroup_root.join(cgroups_v2_path).ancestors() {
+            if cgroup_path == cgroup_root || !cgroup_path.starts_with(cgroup_root) {
+                break;
+            }
+
+            if let Some(cached) = cgroup_cpu_throttling.get(cgroup_path) {
+                cpu_throttling = *cached;
+                break;
+            }
+
+            unresolved_paths.push(cgroup_path.to_path_buf());
+
+            // cpu.max looks like "$MAX $PERIOD" where $MAX is "max" if there is no quota
+            let Ok(cpu_max) = std::fs::read_to_string(cgroup_path.join("cpu.max")) else {
+                continue;
+            };
+
+            if cpu_max.split_whitespace().next().unwrap_or("max") == "max" {
+                continue;
+            }
+
+            cpu_throttling = std::fs::read_to_string(cgroup_path.join("cpu.stat"))
+                .ok()
+                .and_then(|cpu_stat| Self::parse_cpu_stat(&cpu_stat));
+            break;
+        }
+
+        // every cgroup we've walked through is subject to the same quota
+        for cgroup_path in unresolved_paths {
+            cgroup_cpu_throttling.insert(cgroup_path, cpu_throttling);
+        }
+
+        cpu_throttling
+    }
+
+    fn parse_cpu_stat(cpu_stat: &str) -> Option<CpuThrottling> {
+        let parse = |regex: &Regex| {
+            regex
+                .captures(cpu_stat)
+                .and_then(|captures| captures.get(1))
+                .and_then(|capture| capture.as_str().parse::<u64>().ok())
+        };
+
+        Some(CpuThrottling {
+            nr_periods: parse(&RE_CPU_STAT_NR_PERIODS)?,
+            nr_throttled: parse(&RE_CPU_STAT_NR_THROTTLED)?,
+            throttled_usec: parse(&RE_CPU_STAT_THROTTLED_USEC).unwrap_or_default(),
+        })
+    }
+
+    /// Looks up the value of the variable `name` in the contents of an environ file, whose entries are separated by NUL
+    fn environment_variable(environ: &[u8], name: &str) -> Option<String> {
+        environ
+            .split(|byte| *byte == 0)
+            .find_map(|entry| entry.strip_prefix(name.as_bytes())?.strip_prefix(b"="))
+            .map(|value| String::from_utf8_lossy(value).to_string())
+            .filter(|value| !value.is_empty())
+    }
+
     fn gpu_usage_stats(proc_path: &Path, pid: i32) -> BTreeMap<GpuIdentifier, GpuUsageStats> {
         let nvidia_stats = Self::nvidia_gpu_stats_all(pid);
         let mut other_stats = Self::other_gpu_usage_stats(proc_path, pid).unwrap_or_default();
@@ -425,11 +1597,57 @@ impl ProcessData {
         proc_path: &Path,
         pid: i32,
     ) -> Result<BTreeMap<GpuIdentifier, GpuUsageStats>> {
+        let mut return_map = BTreeMap::new();
+
+        Self::for_each_drm_fdinfo(proc_path, pid, |_, content, drm_device| {
+            let stats = Self::parse_fdinfo(content, || {
+                drm_device.and_then(Self::gpu_identifier_from_drm_device)
+            });
+
+            if let Ok(stats) = stats {
+                return_map
+                    .entry(stats.0)
+                    .and_modify(|existing_value: &mut GpuUsageStats| {
+                        if stats.1.gfx > existing_value.gfx {
+                            existing_value.gfx = stats.1.gfx;
+                        }
+                        if stats.1.dec > existing_value.dec {
+                            existing_value.dec = stats.1.dec;
+                        }
+                        if stats.1.enc > existing_value.enc {
+                            existing_value.enc = stats.1.enc;
+                        }
+                        if stats.1.mem > existing_value.mem {
+                            existing_value.mem = stats.1.mem;
+                        }
+                        if stats.1.mem_system > existing_value.mem_system {
+                            existing_value.mem_system = stats.1.mem_system;
+                        }
+                        if stats.1.gfx_total_cycles > existing_value.gfx_total_cycles {
+                            existing_value.gfx_total_cycles = stats.1.gfx_total_cycles;
+                        }
+                        if stats.1.media_total_cycles > existing_value.media_total_cycles {
+                            existing_value.media_total_cycles = stats.1.media_total_cycles;
+                        }
+                    })
+                    .or_insert(stats.1);
+            }
+        })?;
+
+        Ok(return_map)
+    }
+
+    /// Calls `f` with the file descriptor number, the fdinfo contents and the device number of every unique DRM file
+    /// that the process has open
+    fn for_each_drm_fdinfo<F: FnMut(usize, &str, Option<u64>)>(
+        proc_path: &Path,
+        pid: i32,
+        mut f: F,
+    ) -> Result<()> {
         let fdinfo_dir = proc_path.join("fdinfo");
 
         let mut seen_fds = HashSet::new();
 
-        let mut return_map = BTreeMap::new();
         for entry in std::fs::read_dir(fdinfo_dir)? {
             let entry = entry?;
             let fdinfo_path = entry.path();
@@ -463,13 +1681,15 @@ impl ProcessData {
 
             // Adapted from nvtop's `is_drm_fd()`
             // https://github.com/Syllo/nvtop/blob/master/src/extract_processinfo_fdinfo.c
+            let mut drm_device = None;
             let fd_path = fdinfo_path.to_str().map(|s| s.replace("fdinfo", "fd"));
             if let Some(fd_path) = fd_path {
                 if let Ok(fd_metadata) = std::fs::metadata(fd_path) {
-                    let major = unsafe { libc::major(fd_metadata.st_rdev()) };
+                    let major = libc::major(fd_metadata.st_rdev());
                     if (fd_metadata.st_mode() & libc::S_IFMT) != libc::S_IFCHR || major != 226 {
                         continue;
                     }
+                    drm_device = Some(fd_metadata.st_rdev());
                 }
             }
 
@@ -487,114 +1707,201 @@ impl ProcessData {
 
             seen_fds.insert(fd_num);
 
-            if let Ok(stats) = Self::read_fdinfo(&mut file, metadata.len() as usize) {
-                return_map
-                    .entry(stats.0)
-                    .and_modify(|existing_value: &mut GpuUsageStats| {
-                        if stats.1.gfx > existing_value.gfx {
-                            existing_value.gfx = stats.1.gfx;
-                        }
-                        if stats.1.dec > existing_value.dec {
-                            existing_value.dec = stats.1.dec;
-                        }
-                        if stats.1.enc > existing_value.enc {
-                            existing_value.enc = stats.1.enc;
-                        }
-                        if stats.1.mem > existing_value.mem {
-                            existing_value.mem = stats.1.mem;
-                        }
-                    })
-                    .or_insert(stats.1);
+            if let Ok(content) = Self::read_fdinfo(&mut file, metadata.len() as usize) {
+                f(fd_num, &content, drm_device);
             }
         }
 
-        Ok(return_map)
+        Ok(())
     }
 
-    fn read_fdinfo(
-        fdinfo_file: &mut File,
-        file_size: usize,
-    ) -> Result<(GpuIdentifier, GpuUsageStats)> {
+    fn read_fdinfo(fdinfo_file: &mut File, file_size: usize) -> Result<String> {
         let mut content = String::with_capacity(file_size);
         fdinfo_file.read_to_string(&mut content)?;
         fdinfo_file.flush()?;
 
+        Ok(content)
+    }
+
+    /// Resolves the GPU behind a DRM device number (e.g. of `/dev/dri/card0` or `/dev/dri/renderD128`)
+    /// using sysfs, for drivers whose fdinfo doesn't contain `drm-pdev`
+    fn gpu_identifier_from_drm_device(drm_device: u64) -> Option<GpuIdentifier> {
+        let (major, minor) = (libc::major(drm_device), libc::minor(drm_device));
+
+        Self::gpu_identifier_from_sysfs_device(format!("/sys/dev/char/{major}:{minor}/device"))
+    }
+
+    /// Identifies the GPU behind a sysfs device directory the same way Resources enumerates GPUs, i.e. by its PCI slot
+    /// or, for platform devices that don't have one, by the number of its DRM card
+    fn gpu_identifier_from_sysfs_device<P: AsRef<Path>>(device_path: P) -> Option<GpuIdentifier> {
+        let device_path = device_path.as_ref();
+
+        if let Some(pci_slot) = std::fs::read_to_string(device_path.join("uevent"))
+            .ok()
+            .and_then(|uevent| Self::pci_slot_from_uevent(&uevent))
+        {
+            return Some(GpuIdentifier::PciSlot(pci_slot));
+        }
+
+        // besides the card, drm/ also contains the device's render node, which isn't numbered like the card
+        device_path
+            .join("drm")
+            .read_dir()
+            .ok()?
+            .flatten()
+            .find_map(|entry| {
+                entry
+                    .file_name()
+                    .to_str()?
+                    .strip_prefix("card")?
+                    .parse()
+                    .ok()
+            })
+            .map(GpuIdentifier::Enumerator)
+    }
+
+    fn pci_slot_from_uevent<S: AsRef<str>>(uevent: S) -> Option<PciSlot> {
+        uevent
+            .as_ref()
+            .lines()
+            .find_map(|line| line.strip_prefix("PCI_SLOT_NAME="))
+            .and_then(|pci_slot| PciSlot::from_str(pci_slot.trim()).ok())
+    }
+
+    /// Parses the contents of a DRM fdinfo file. `fallback_gpu_identifier` is only called if the
+    /// fdinfo doesn't tell us which GPU it belongs to.
+    fn parse_fdinfo<F: FnOnce() -> Option<GpuIdentifier>>(
+        content: &str,
+        fallback_gpu_identifier: F,
+    ) -> Result<(GpuIdentifier, GpuUsageStats)> {
         let driver = RE_DRM_DRIVER
-            .captures(&content)
+            .captures(content)
             .and_then(|captures| captures.get(1))
             .map(|capture| capture.as_str());
 
         if driver.is_some() {
             let gpu_identifier = RE_DRM_PDEV
-                .captures(&content)
+                .captures(content)
                 .and_then(|captures| captures.get(1))
                 .and_then(|capture| PciSlot::from_str(capture.as_str()).ok())
                 .map(|pci_slot| GpuIdentifier::PciSlot(pci_slot))
+                .or_else(fallback_gpu_identifier)
                 .unwrap_or_default();
 
             let gfx = RE_DRM_ENGINE_GFX
-                .captures(&content)
+                .captures(content)
                 .and_then(|captures| captures.get(1))
                 .and_then(|capture| capture.as_str().parse::<u64>().ok())
                 .unwrap_or_default();
 
             let render = RE_DRM_ENGINE_RENDER
-                .captures(&content)
+                .captures(content)
                 .and_then(|captures| captures.get(1))
                 .and_then(|capture| capture.as_str().parse::<u64>().ok())
                 .unwrap_or_default();
 
             let compute = RE_DRM_ENGINE_COMPUTE
-                .captures(&content)
+                .captures(content)
                 .and_then(|captures| captures.get(1))
                 .and_then(|capture| capture.as_str().parse::<u64>().ok())
                 .unwrap_or_default();
 
+            let v3d_engines = RE_DRM_ENGINE_V3D
+                .captures_iter(content)
+                .filter_map(|captures| captures.get(1)?.as_str().parse::<u64>().ok())
+                .fold(0, u64::saturating_add);
+
             let enc = RE_DRM_ENGINE_ENC
-                .captures(&content)
+                .captures(content)
                 .and_then(|captures| captures.get(1))
                 .and_then(|capture| capture.as_str().parse::<u64>().ok())
                 .unwrap_or_default();
 
             let video = RE_DRM_ENGINE_VIDEO
-                .captures(&content)
+                .captures(content)
                 .and_then(|captures| captures.get(1))
                 .and_then(|capture| capture.as_str().parse::<u64>().ok())
                 .unwrap_or_default();
 
             let dec = RE_DRM_ENGINE_DEC
-                .captures(&content)
+                .captures(content)
                 .and_then(|captures| captures.get(1))
                 .and_then(|capture| capture.as_str().parse::<u64>().ok())
                 .unwrap_or_default();
 
             let vram = RE_DRM_MEMORY_VRAM
-                .captures(&content)
+                .captures(content)
                 .and_then(|captures| captures.get(1))
                 .and_then(|capture| capture.as_str().parse::<u64>().ok())
                 .unwrap_or_default()
                 .saturating_mul(1024);
 
             let gtt = RE_DRM_MEMORY_GTT
-                .captures(&content)
+                .captures(content)
                 .and_then(|captures| captures.get(1))
                 .and_then(|capture| capture.as_str().parse::<u64>().ok())
                 .unwrap_or_default()
                 .saturating_mul(1024);
 
             let total_memory = RE_DRM_TOTAL_MEMORY
-                .captures(&content)
+                .captures(content)
                 .and_then(|captures| captures.get(1))
                 .and_then(|capture| capture.as_str().parse::<u64>().ok())
                 .unwrap_or_default()
                 .saturating_mul(1024);
 
+            // i915 and xe split their memory into regions, the local ones are VRAM and the others are system memory
+            let (local_memory, system_memory) =
+                if matches!(driver.map(str::trim), Some("i915" | "xe")) {
+                    (
+                        Self::drm_memory_regions_size(content, &RE_DRM_RESIDENT_LOCAL),
+                        Self::drm_memory_regions_size(content, &RE_DRM_RESIDENT_SYSTEM),
+                    )
+                } else {
+                    (0, 0)
+                };
+
+            // xe counts GPU cycles instead of nanoseconds, the total cycles are needed to turn them into a fraction
+            let cycles = |regex: &Regex| {
+                regex
+                    .captures(content)
+                    .and_then(|captures| captures.get(1))
+                    .and_then(|capture| capture.as_str().parse::<u64>().ok())
+            };
+
+            let gfx_total_cycles = cycles(&RE_DRM_TOTAL_CYCLES_RCS);
+            let gfx = if gfx_total_cycles.is_some() {
+                cycles(&RE_DRM_CYCLES_RCS)
+                    .unwrap_or_default()
+                    .saturating_add(cycles(&RE_DRM_CYCLES_CCS).unwrap_or_default())
+            } else {
+                gfx.saturating_add(render)
+                    .saturating_add(compute)
+                    .saturating_add(v3d_engines)
+            };
+
+            let media_total_cycles = cycles(&RE_DRM_TOTAL_CYCLES_VCS);
+            let enc = if media_total_cycles.is_some() {
+                cycles(&RE_DRM_CYCLES_VCS)
+                    .unwrap_or_default()
+                    .saturating_add(cycles(&RE_DRM_CYCLES_VECS).unwrap_or_default())
+            } else {
+                enc.saturating_add(video)
+            };
+
             let stats = GpuUsageStats {
-                gfx: gfx.saturating_add(render).saturating_add(compute),
-                mem: vram.saturating_add(gtt).saturating_add(total_memory),
-                enc: enc.saturating_add(video),
+                gfx,
+                mem: vram
+                    .saturating_add(gtt)
+                    .saturating_add(total_memory)
+                    .saturating_add(local_memory)
+                    .saturating_add(system_memory),
+                mem_system: gtt.saturating_add(system_memory),
+                enc,
                 dec,
                 nvidia: false,
+                gfx_total_cycles,
+                media_total_cycles,
             };
 
             return Ok((gpu_identifier, stats));
@@ -603,6 +1910,23 @@ impl ProcessData {
         bail!("unable to find gpu information in this fdinfo");
     }
 
+    /// Sums up the sizes of all memory regions matched by `regex` in bytes, respecting the unit the kernel chose for
+    /// each of them
+    fn drm_memory_regions_size(content: &str, regex: &Regex) -> u64 {
+        regex
+            .captures_iter(content)
+            .filter_map(|captures| {
+                let size = captures.get(1)?.as_str().parse::<u64>().ok()?;
+                let multiplier = match captures.get(2).map(|capture| capture.as_str()) {
+                    Some("KiB") => 1024,
+                    Some("MiB") => 1024 * 1024,
+                    _ => 1,
+                };
+                Some(size.saturating_mul(multiplier))
+            })
+            .fold(0, u64::saturating_add)
+    }
+
     fn nvidia_gpu_stats_all(pid: i32) -> BTreeMap<GpuIdentifier, GpuUsageStats> {
         let mut return_map = BTreeMap::new();
 
@@ -626,7 +1950,7 @@ impl ProcessData {
             .map(|stats| (stats.sm_util, stats.enc_util, stats.dec_util))
             .reduce(|acc, curr| (acc.0 + curr.0, acc.1 + curr.1, acc.2 + curr.2));
 
-        let this_process_mem_stats: u64 = NVIDIA_PROCESS_INFOS
+        let this_process_mem_stats: Option<u64> = NVIDIA_PROCESS_INFOS
             .read()
             .unwrap()
             .get(&pci_slot)
@@ -637,14 +1961,23 @@ impl ProcessData {
                 UsedGpuMemory::Unavailable => 0,
                 UsedGpuMemory::Used(bytes) => bytes,
             })
-            .sum();
+            .reduce(|acc, curr| acc + curr);
+
+        // NVML lists every process that uses a GPU, so if it doesn't know about this process, the process doesn't use
+        // this GPU and shouldn't be attributed to it
+        if this_process_stats.is_none() && this_process_mem_stats.is_none() {
+            bail!("process {pid} doesn't use the GPU at {pci_slot}");
+        }
 
         let gpu_stats = GpuUsageStats {
             gfx: this_process_stats.unwrap_or_default().0 as u64,
-            mem: this_process_mem_stats,
+            mem: this_process_mem_stats.unwrap_or_default(),
+            mem_system: 0,
             enc: this_process_stats.unwrap_or_default().1 as u64,
             dec: this_process_stats.unwrap_or_default().2 as u64,
             nvidia: true,
+            gfx_total_cycles: None,
+            media_total_cycles: None,
         };
         Ok(gpu_stats)
     }
@@ -687,3 +2020,771 @@ pub fn unix_as_millis() -> u64 {
         .unwrap()
         .as_millis() as u64
 }
+
+#[cfg(test)]
+mod test {
+    use std::{
+        collections::{BTreeMap, HashMap},
+        str::FromStr,
+    };
+
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        pci_slot::PciSlot, Container, ContainerRuntime, CpuThrottling, DrmFdinfo,
+        EnvironmentVariable, GpuIdentifier, GpuUsageStats, OpenFile, ProcessData, ProcessLimits,
+        ResourceLimit, SchedStat, SocketData, SocketProtocol, SocketState, ThreadData, WaitChannel,
+    };
+
+    const FDINFO_WITHOUT_PDEV: &str = concat!(
+        "pos:\t0\n",
+        "flags:\t02100002\n",
+        "mnt_id:\t26\n",
+        "drm-driver:\tmsm\n",
+        "drm-client-id:\t7\n",
+        "drm-engine-gpu:\t1234 ns\n",
+        "drm-engine-gfx:\t5000000 ns\n",
+        "drm-total-memory:\t2048 KiB\n",
+    );
+
+    const FDINFO_WITH_PDEV: &str = concat!(
+        "pos:\t0\n",
+        "flags:\t02100002\n",
+        "mnt_id:\t26\n",
+        "drm-driver:\tamdgpu\n",
+        "drm-pdev:\t0000:03:00.0\n",
+        "drm-client-id:\t12\n",
+        "drm-engine-gfx:\t5000000 ns\n",
+        "drm-memory-vram:\t1024 KiB\n",
+    );
+
+    const UEVENT: &str = concat!(
+        "DRIVER=amdgpu\n",
+        "PCI_CLASS=30000\n",
+        "PCI_ID=1002:73BF\n",
+        "PCI_SUBSYS_ID=1002:0E3A\n",
+        "PCI_SLOT_NAME=0000:0a:00.0\n",
+        "MODALIAS=pci:v00001002d000073BFsv00001002sd00000E3Abc03sc00i00\n",
+    );
+
+    #[test]
+    fn parse_fdinfo_missing_pdev_uses_fallback() {
+        let fallback = GpuIdentifier::PciSlot(PciSlot::from_str("0000:0a:00.0").unwrap());
+
+        let (gpu_identifier, stats) =
+            ProcessData::parse_fdinfo(FDINFO_WITHOUT_PDEV, || Some(fallback)).unwrap();
+
+        assert_eq!(fallback, gpu_identifier);
+        assert_eq!(
+            GpuUsageStats {
+                gfx: 5_000_000,
+                mem: 2048 * 1024,
+                mem_system: 0,
+                enc: 0,
+                dec: 0,
+                nvidia: false,
+                gfx_total_cycles: None,
+                media_total_cycles: None,
+            },
+            stats
+        );
+    }
+
+    #[test]
+    fn parse_fdinfo_v3d_engines() {
+        let content = concat!(
+            "drm-driver:\tv3d\n",
+            "drm-client-id:\t42\n",
+            "drm-engine-bin:\t1000000 ns\n",
+            "v3d-jobs-bin:\t10 jobs\n",
+            "drm-engine-render:\t4000000 ns\n",
+            "v3d-jobs-render:\t10 jobs\n",
+            "drm-engine-tfu:\t200000 ns\n",
+            "v3d-jobs-tfu:\t2 jobs\n",
+            "drm-engine-csd:\t3000000 ns\n",
+            "v3d-jobs-csd:\t5 jobs\n",
+            "drm-engine-cache_clean:\t50000 ns\n",
+            "v3d-jobs-cache_clean:\t5 jobs\n",
+            "drm-total-memory:\t4096 KiB\n",
+        );
+
+        let (_, stats) = ProcessData::parse_fdinfo(content, || None).unwrap();
+
+        assert_eq!(
+            GpuUsageStats {
+                gfx: 8_250_000,
+                mem: 4096 * 1024,
+                mem_system: 0,
+                enc: 0,
+                dec: 0,
+                nvidia: false,
+                gfx_total_cycles: None,
+                media_total_cycles: None,
+            },
+            stats
+        );
+    }
+
+    #[test]
+    fn parse_fdinfo_xe_cycles() {
+        let content = concat!(
+            "drm-driver:\txe\n",
+            "drm-pdev:\t0000:03:00.0\n",
+            "drm-cycles-rcs:\t3000\n",
+            "drm-total-cycles-rcs:\t100000\n",
+            "drm-cycles-bcs:\t500\n",
+            "drm-total-cycles-bcs:\t100000\n",
+            "drm-cycles-vcs:\t700\n",
+            "drm-total-cycles-vcs:\t90000\n",
+            "drm-cycles-vecs:\t300\n",
+            "drm-total-cycles-vecs:\t90000\n",
+            "drm-cycles-ccs:\t2000\n",
+            "drm-total-cycles-ccs:\t100000\n",
+        );
+
+        let (_, stats) = ProcessData::parse_fdinfo(content, || None).unwrap();
+
+        assert_eq!(
+            GpuUsageStats {
+                gfx: 5000,
+                mem: 0,
+                mem_system: 0,
+                enc: 1000,
+                dec: 0,
+                nvidia: false,
+                gfx_total_cycles: Some(100_000),
+                media_total_cycles: Some(90_000),
+            },
+            stats
+        );
+    }
+
+    #[test]
+    fn parse_fdinfo_i915_memory_regions() {
+        let content = concat!(
+            "drm-driver:\ti915\n",
+            "drm-pdev:\t0000:03:00.0\n",
+            "drm-total-system0:\t8 MiB\n",
+            "drm-resident-system0:\t6 MiB\n",
+            "drm-total-stolen-system0:\t0\n",
+            "drm-resident-stolen-system0:\t0\n",
+            "drm-total-local0:\t512 MiB\n",
+            "drm-resident-local0:\t256 MiB\n",
+            "drm-total-stolen-local0:\t4 MiB\n",
+            "drm-resident-stolen-local0:\t4 MiB\n",
+        );
+
+        let (_, stats) = ProcessData::parse_fdinfo(content, || None).unwrap();
+
+        assert_eq!(262 * 1024 * 1024, stats.mem);
+        assert_eq!(6 * 1024 * 1024, stats.mem_system);
+    }
+
+    #[test]
+    fn parse_fdinfo_xe_memory_regions() {
+        let content = concat!(
+            "drm-driver:\txe\n",
+            "drm-pdev:\t0000:03:00.0\n",
+            "drm-resident-system:\t1536 KiB\n",
+            "drm-resident-gtt:\t4096\n",
+            "drm-resident-vram0:\t128 MiB\n",
+            "drm-resident-vram1:\t2 MiB\n",
+            "drm-resident-stolen:\t0\n",
+        );
+
+        let (_, stats) = ProcessData::parse_fdinfo(content, || None).unwrap();
+
+        assert_eq!(130 * 1024 * 1024 + 1536 * 1024 + 4096, stats.mem);
+        assert_eq!(1536 * 1024 + 4096, stats.mem_system);
+    }
+
+    #[test]
+    fn parse_fdinfo_amdgpu_memory_split() {
+        let content = concat!(
+            "drm-driver:\tamdgpu\n",
+            "drm-pdev:\t0000:03:00.0\n",
+            "drm-memory-vram:\t1024 KiB\n",
+            "drm-memory-gtt:\t512 KiB\n",
+            "drm-resident-vram:\t1024 KiB\n",
+            "drm-resident-gtt:\t512 KiB\n",
+        );
+
+        let (_, stats) = ProcessData::parse_fdinfo(content, || None).unwrap();
+
+        assert_eq!(1536 * 1024, stats.mem);
+        assert_eq!(512 * 1024, stats.mem_system);
+    }
+
+    #[test]
+    fn gpu_usage_stats_fraction_cycles() {
+        let stats = |gfx, gfx_total_cycles| GpuUsageStats {
+            gfx,
+            mem: 0,
+            mem_system: 0,
+            enc: 0,
+            dec: 0,
+            nvidia: false,
+            gfx_total_cycles,
+            media_total_cycles: None,
+        };
+
+        // 25 busy cycles out of 100, no matter how much time has passed in between
+        assert_eq!(
+            0.25,
+            stats(125, Some(1100)).gfx_fraction(&stats(100, Some(1000)), 1000)
+        );
+
+        // 50 ms busy within 1000 ms
+        assert_eq!(
+            0.05,
+            stats(100_000_000, None).gfx_fraction(&stats(50_000_000, None), 1000)
+        );
+
+        assert_eq!(
+            0.0,
+            stats(125, Some(1000)).gfx_fraction(&stats(100, Some(1000)), 1000)
+        );
+
+        // 1500 ms busy within 1000 ms across engines that ran in parallel
+        assert_eq!(
+            1.0,
+            stats(2_000_000_000, None).gfx_fraction(&stats(500_000_000, None), 1000)
+        );
+    }
+
+    #[test]
+    fn parse_fdinfo_missing_pdev_without_fallback() {
+        let (gpu_identifier, _) = ProcessData::parse_fdinfo(FDINFO_WITHOUT_PDEV, || None).unwrap();
+
+        assert_eq!(GpuIdentifier::default(), gpu_identifier);
+    }
+
+    #[test]
+    fn parse_fdinfo_pdev_takes_precedence() {
+        let (gpu_identifier, _) = ProcessData::parse_fdinfo(FDINFO_WITH_PDEV, || {
+            panic!("fallback shouldn't be called when drm-pdev is present")
+        })
+        .unwrap();
+
+        assert_eq!(
+            GpuIdentifier::PciSlot(PciSlot::from_str("0000:03:00.0").unwrap()),
+            gpu_identifier
+        );
+    }
+
+    #[test]
+    fn drm_fdinfo_parse_fields() {
+        let content = concat!(
+            "pos:\t0\n",
+            "flags:\t02100002\n",
+            "drm-driver:\tamdgpu\n",
+            "drm-engine-gfx:\t5000000 ns\n",
+            "drm-memory-vram:\t1024 KiB\n",
+        );
+
+        assert_eq!(
+            BTreeMap::from([
+                ("drm-driver".to_string(), "amdgpu".to_string()),
+                ("drm-engine-gfx".to_string(), "5000000 ns".to_string()),
+                ("drm-memory-vram".to_string(), "1024 KiB".to_string()),
+            ]),
+            DrmFdinfo::parse_fields(content)
+        );
+    }
+
+    #[test]
+    fn drm_fdinfo_parse_fields_not_drm() {
+        assert!(DrmFdinfo::parse_fields("pos:\t0\nflags:\t02100002\n").is_empty());
+    }
+
+    #[test]
+    fn environment_variable_present() {
+        let environ =
+            b"HOME=/home/user\0APPIMAGE=/opt/Krita-5.2.2-x86_64.AppImage\0ARGV0=./krita\0";
+
+        assert_eq!(
+            Some("/opt/Krita-5.2.2-x86_64.AppImage".to_string()),
+            ProcessData::environment_variable(environ, "APPIMAGE")
+        );
+    }
+
+    #[test]
+    fn environment_variable_absent() {
+        let environ = b"HOME=/home/user\0APPIMAGE_EXTRACT_AND_RUN=1\0APPIMAGE=\0";
+
+        assert_eq!(None, ProcessData::environment_variable(environ, "APPIMAGE"));
+        assert_eq!(None, ProcessData::environment_variable(b"", "APPIMAGE"));
+    }
+
+    #[test]
+    fn parse_limits_valid() {
+        let limits = concat!(
+            "Limit                     Soft Limit           Hard Limit           Units     \n",
+            "Max cpu time              unlimited            unlimited            seconds   \n",
+            "Max open files            1024                 524288               files     \n",
+            "Max nice priority         0                    0                    \n",
+        );
+
+        let expected = vec![
+            ResourceLimit {
+                name: "Max cpu time".to_string(),
+                soft_limit: None,
+                hard_limit: None,
+                unit: Some("seconds".to_string()),
+            },
+            ResourceLimit {
+                name: "Max open files".to_string(),
+                soft_limit: Some(1024),
+                hard_limit: Some(524_288),
+                unit: Some("files".to_string()),
+            },
+            ResourceLimit {
+                name: "Max nice priority".to_string(),
+                soft_limit: Some(0),
+                hard_limit: Some(0),
+                unit: None,
+            },
+        ];
+
+        assert_eq!(expected, ProcessLimits::parse_limits(limits).unwrap());
+    }
+
+    #[test]
+    fn parse_limits_invalid() {
+        assert!(ProcessLimits::parse_limits("").is_err());
+        assert!(ProcessLimits::parse_limits("Max open files 1024 524288 files\n").is_err());
+    }
+
+    #[test]
+    fn pci_slot_from_uevent_valid() {
+        assert_eq!(
+            Some(PciSlot::from_str("0000:0a:00.0").unwrap()),
+            ProcessData::pci_slot_from_uevent(UEVENT)
+        );
+    }
+
+    #[test]
+    fn pci_slot_from_uevent_platform_device() {
+        assert_eq!(
+            None,
+            ProcessData::pci_slot_from_uevent("DRIVER=v3d\nOF_NAME=v3d\n")
+        );
+    }
+
+    #[test]
+    fn gpu_identifier_from_sysfs_platform_device() {
+        let device_path = std::env::temp_dir().join(format!(
+            "resources-process-data-platform-gpu-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&device_path);
+        std::fs::create_dir_all(device_path.join("drm/card1")).unwrap();
+        std::fs::create_dir_all(device_path.join("drm/renderD128")).unwrap();
+        std::fs::write(device_path.join("uevent"), "DRIVER=v3d\nOF_NAME=v3d\n").unwrap();
+
+        let gpu_identifier = ProcessData::gpu_identifier_from_sysfs_device(&device_path);
+
+        std::fs::remove_dir_all(&device_path).unwrap();
+
+        assert_eq!(Some(GpuIdentifier::Enumerator(1)), gpu_identifier);
+    }
+
+    #[test]
+    fn gpu_identifier_from_sysfs_pci_device() {
+        let device_path = std::env::temp_dir().join(format!(
+            "resources-process-data-pci-gpu-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&device_path);
+        std::fs::create_dir_all(device_path.join("drm/card0")).unwrap();
+        std::fs::write(device_path.join("uevent"), UEVENT).unwrap();
+
+        let gpu_identifier = ProcessData::gpu_identifier_from_sysfs_device(&device_path);
+
+        std::fs::remove_dir_all(&device_path).unwrap();
+
+        assert_eq!(
+            Some(GpuIdentifier::PciSlot(
+                PciSlot::from_str("0000:0a:00.0").unwrap()
+            )),
+            gpu_identifier
+        );
+    }
+
+    #[test]
+    fn thread_parse_stat_valid() {
+        let stat = "4242 (worker (1)) R 4200 4200 4200 0 -1 4194368 91 0 0 0 1337 42 0 0 20 0 12 0 9001 0 0\n";
+
+        let expected = ThreadData {
+            tid: 4242,
+            name: "worker (1)".to_string(),
+            state: 'R',
+            user_cpu_time: 1337,
+            system_cpu_time: 42,
+        };
+
+        assert_eq!(expected, ThreadData::parse_stat(4242, stat).unwrap());
+    }
+
+    #[test]
+    fn thread_parse_stat_invalid() {
+        assert!(ThreadData::parse_stat(1, "").is_err());
+        assert!(ThreadData::parse_stat(1, "1 (init) S 0").is_err());
+    }
+
+    #[test]
+    fn wait_channel_from_stat() {
+        let stat =
+            "1234 (my (weird) app) S 1 1234 1234 0 -1 4194560 1000 0 0 0 10 5 0 0 20 0 1 0 100";
+        let state = WaitChannel::state_from_stat(stat).unwrap();
+        assert_eq!('S', state);
+
+        assert_eq!(
+            WaitChannel::Sleeping("do_epoll_wait".to_string()),
+            WaitChannel::from_state(state, Some("do_epoll_wait"))
+        );
+        assert_eq!(
+            WaitChannel::Unknown,
+            WaitChannel::from_state(state, Some("0"))
+        );
+        assert_eq!(WaitChannel::Running, WaitChannel::from_state('R', None));
+        assert_eq!(WaitChannel::Unknown, WaitChannel::from_state('Z', None));
+    }
+
+    #[test]
+    fn parse_schedstat_valid() {
+        let schedstat = SchedStat::parse("6000000 2000000 4\n").unwrap();
+
+        assert_eq!(
+            SchedStat {
+                run_time: 6_000_000,
+                wait_time: 2_000_000,
+                timeslices: 4,
+            },
+            schedstat
+        );
+        assert_eq!(1_500_000.0, schedstat.average_run_time());
+        assert_eq!(500_000.0, schedstat.average_wait_time());
+        assert_eq!(2.0 / 6.0, schedstat.wait_ratio());
+    }
+
+    #[test]
+    fn parse_schedstat_disabled() {
+        assert_eq!(None, SchedStat::parse("6000000 0 0\n"));
+    }
+
+    #[test]
+    fn parse_schedstat_invalid() {
+        assert_eq!(None, SchedStat::parse(""));
+        assert_eq!(None, SchedStat::parse("6000000 2000000"));
+        assert_eq!(None, SchedStat::parse("a b c"));
+    }
+
+    #[test]
+    fn status_locked_and_pinned_memory() {
+        let status = concat!(
+            "Name:\tpostgres\n",
+            "VmPeak:\t  226776 kB\n",
+            "VmLck:\t   65536 kB\n",
+            "VmPin:\t    1024 kB\n",
+            "VmHWM:\t   27464 kB\n",
+            "VmSwap:\t       0 kB\n",
+        );
+
+        assert_eq!(
+            65536,
+            ProcessData::status_kib(status, &super::RE_LOCKED_MEMORY)
+        );
+        assert_eq!(
+            1024,
+            ProcessData::status_kib(status, &super::RE_PINNED_MEMORY)
+        );
+    }
+
+    #[test]
+    fn status_kernel_thread_without_memory() {
+        let status = concat!("Name:\tkworker/0:1\n", "State:\tI (idle)\n");
+
+        assert_eq!(0, ProcessData::status_kib(status, &super::RE_LOCKED_MEMORY));
+        assert_eq!(0, ProcessData::status_kib(status, &super::RE_PINNED_MEMORY));
+    }
+
+    #[test]
+    fn socket_link() {
+        assert_eq!(Some(48213), SocketData::parse_socket_link("socket:[48213]"));
+        assert_eq!(None, SocketData::parse_socket_link("pipe:[48213]"));
+        assert_eq!(None, SocketData::parse_socket_link("/dev/null"));
+    }
+
+    // the addresses are printed in the byte order of the machine, so these tables only look like this on little-endian
+    // machines
+    #[cfg(target_endian = "little")]
+    #[test]
+    fn inet_sockets() {
+        let tcp = concat!(
+            "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode\n",
+            "   0: 0100007F:0277 00000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 1001 1 0000000000000000 100 0 0 10 0\n",
+            "   1: 0201A8C0:D1F2 2EE5D48E:01BB 01 00000000:00000000 02:000A1B2C 00000000  1000        0 1002 2 0000000000000000 20 4 30 10 -1\n",
+            "   2: 0201A8C0:D1F4 2EE5D48E:01BB 01 00000000:00000000 02:000A1B2C 00000000  1000        0 9999 2 0000000000000000 20 4 30 10 -1\n",
+        );
+
+        let tcp6 = concat!(
+            "  sl  local_address                         remote_address                        st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode\n",
+            "   0: 00000000000000000000000001000000:0016 00000000000000000000000000000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 1003 1 0000000000000000 100 0 0 10 0\n",
+        );
+
+        let fds_by_inode = HashMap::from([(1001, 3), (1002, 7), (1003, 4)]);
+
+        let mut sockets = SocketData::parse_inet_table(tcp, SocketProtocol::Tcp, &fds_by_inode);
+        sockets.extend(SocketData::parse_inet_table(
+            tcp6,
+            SocketProtocol::Tcp,
+            &fds_by_inode,
+        ));
+
+        let expected = vec![
+            SocketData {
+                fd: 3,
+                protocol: SocketProtocol::Tcp,
+                local_address: Some("127.0.0.1:631".parse().unwrap()),
+                remote_address: None,
+                path: None,
+                state: SocketState::Listen,
+            },
+            SocketData {
+                fd: 7,
+                protocol: SocketProtocol::Tcp,
+                local_address: Some("192.168.1.2:53746".parse().unwrap()),
+                remote_address: Some("142.212.229.46:443".parse().unwrap()),
+                path: None,
+                state: SocketState::Established,
+            },
+            SocketData {
+                fd: 4,
+                protocol: SocketProtocol::Tcp,
+                local_address: Some("[::1]:22".parse().unwrap()),
+                remote_address: None,
+                path: None,
+                state: SocketState::Listen,
+            },
+        ];
+
+        assert_eq!(expected, sockets);
+    }
+
+    #[test]
+    fn unix_sockets() {
+        let unix = concat!(
+            "Num       RefCount Protocol Flags    Type St Inode Path\n",
+            "0000000000000000: 00000002 00000000 00010000 0001 01 2001 /run/user/1000/bus\n",
+            "0000000000000000: 00000003 00000000 00000000 0001 03 2002\n",
+            "0000000000000000: 00000003 00000000 00000000 0001 03 2003 @/tmp/.X11-unix/X0\n",
+            "0000000000000000: 00000002 00000000 00000000 0002 01 2004 /tmp/with space\n",
+        );
+
+        let fds_by_inode = HashMap::from([(2001, 5), (2002, 6), (2004, 8)]);
+
+        let expected = vec![
+            SocketData {
+                fd: 5,
+                protocol: SocketProtocol::Unix,
+                local_address: None,
+                remote_address: None,
+                path: Some("/run/user/1000/bus".to_string()),
+                state: SocketState::Listen,
+            },
+            SocketData {
+                fd: 6,
+                protocol: SocketProtocol::Unix,
+                local_address: None,
+                remote_address: None,
+                path: None,
+                state: SocketState::Established,
+            },
+            SocketData {
+                fd: 8,
+                protocol: SocketProtocol::Unix,
+                local_address: None,
+                remote_address: None,
+                path: Some("/tmp/with space".to_string()),
+                state: SocketState::Close,
+            },
+        ];
+
+        assert_eq!(expected, SocketData::parse_unix_table(unix, &fds_by_inode));
+    }
+
+    #[test]
+    fn parse_environ_valid() {
+        let environ =
+            b"PATH=/usr/bin:/bin\0LANG=en_US.UTF-8\0EMPTY=\0EQUATION=a=b\0NO_VALUE\0INVALID=\xff\0";
+
+        let variable = |name: &str, value: &str| EnvironmentVariable {
+            name: name.to_string(),
+            value: value.to_string(),
+        };
+
+        let expected = vec![
+            variable("EMPTY", ""),
+            variable("EQUATION", "a=b"),
+            variable("INVALID", "\u{FFFD}"),
+            variable("LANG", "en_US.UTF-8"),
+            variable("NO_VALUE", ""),
+            variable("PATH", "/usr/bin:/bin"),
+        ];
+
+        assert_eq!(expected, EnvironmentVariable::parse_environ(environ));
+    }
+
+    #[test]
+    fn parse_environ_empty() {
+        assert!(EnvironmentVariable::parse_environ(b"").is_empty());
+    }
+
+    #[test]
+    fn open_file_kinds() {
+        let open_file = |target: &str| OpenFile {
+            fd: 3,
+            target: target.to_string(),
+            block_device: None,
+        };
+
+        assert!(open_file("/home/user/notes.txt").is_path());
+        assert!(!open_file("/home/user/notes.txt").is_deleted());
+        assert!(open_file("/tmp/scratch (deleted)").is_deleted());
+        assert!(!open_file("socket:[48213]").is_path());
+        assert!(!open_file("anon_inode:[eventfd]").is_deleted());
+    }
+
+    const CONTAINER_ID: &str = "4f2a9c1e8b7d6a5f4e3d2c1b0a9f8e7d6c5b4a3f2e1d0c9b8a7f6e5d4c3b2a1f";
+
+    #[test]
+    fn container_from_systemd_cgroup() {
+        assert_eq!(
+            Some(Container {
+                runtime: ContainerRuntime::Docker,
+                id: CONTAINER_ID.to_string(),
+                name: None,
+            }),
+            Container::from_cgroup(format!("0::/system.slice/docker-{CONTAINER_ID}.scope\n"))
+        );
+        assert_eq!(
+            Some(Container {
+                runtime: ContainerRuntime::Podman,
+                id: CONTAINER_ID.to_string(),
+                name: None,
+            }),
+            Container::from_cgroup(format!(
+                "0::/user.slice/user-1000.slice/user@1000.service/user.slice/libpod-{CONTAINER_ID}.scope/container\n"
+            ))
+        );
+    }
+
+    #[test]
+    fn container_from_cgroupfs_cgroup() {
+        assert_eq!(
+            Some(Container {
+                runtime: ContainerRuntime::Docker,
+                id: CONTAINER_ID.to_string(),
+                name: None,
+            }),
+            Container::from_cgroup(format!("0::/docker/{CONTAINER_ID}\n"))
+        );
+        assert_eq!(
+            Some(Container {
+                runtime: ContainerRuntime::Lxc,
+                id: "web".to_string(),
+                name: Some("web".to_string()),
+            }),
+            Container::from_cgroup("0::/lxc.payload.web/system.slice/nginx.service\n")
+        );
+    }
+
+    #[test]
+    fn container_from_other_cgroups() {
+        assert_eq!(
+            None,
+            Container::from_cgroup(format!(
+                "0::/user.slice/user-1000.slice/user@1000.service/user.slice/libpod-conmon-{CONTAINER_ID}.scope\n"
+            ))
+        );
+        assert_eq!(
+            None,
+            Container::from_cgroup("0::/system.slice/docker.service\n")
+        );
+        assert_eq!(
+            None,
+            Container::from_cgroup(
+                "0::/user.slice/user-1000.slice/user@1000.service/app.slice/app-gnome-org.gnome.Nautilus-1234.scope\n"
+            )
+        );
+    }
+
+    #[test]
+    fn sanitize_container_cgroup() {
+        assert_eq!(
+            Some("4f2a9c1e8b7d".to_string()),
+            ProcessData::sanitize_cgroup(format!("0::/system.slice/docker-{CONTAINER_ID}.scope\n"))
+        );
+    }
+
+    #[test]
+    fn container_names() {
+        let config = format!(
+            r#"{{"ID":"{CONTAINER_ID}","Name":"/web","Driver":"overlay2","MountPoints":{{"/data":{{"Name":"data"}}}}}}"#
+        );
+        assert_eq!(Some("web".to_string()), Container::docker_name(&config));
+
+        let containers = format!(
+            r#"[{{"id":"{CONTAINER_ID}","names":["db"],"image":"postgres"}},{{"id":"{}","names":["cache"]}}]"#,
+            "0".repeat(64)
+        );
+        assert_eq!(
+            HashMap::from([
+                (CONTAINER_ID.to_string(), "db".to_string()),
+                ("0".repeat(64), "cache".to_string())
+            ]),
+            Container::podman_names(&containers)
+        );
+    }
+
+    #[test]
+    fn gpu_identifier_from_display() {
+        let pci_slot = GpuIdentifier::PciSlot(PciSlot::from_str("0000:0a:00.0").unwrap());
+        let enumerator = GpuIdentifier::Enumerator(1);
+
+        assert_eq!(
+            pci_slot,
+            GpuIdentifier::from_str(&pci_slot.to_string()).unwrap()
+        );
+        assert_eq!(
+            enumerator,
+            GpuIdentifier::from_str(&enumerator.to_string()).unwrap()
+        );
+        assert!(GpuIdentifier::from_str("GPU 1").is_err());
+    }
+
+    #[test]
+    fn parse_cpu_stat() {
+        let cpu_stat = concat!(
+            "usage_usec 8112345\n",
+            "user_usec 6000000\n",
+            "system_usec 2112345\n",
+            "nr_periods 1200\n",
+            "nr_throttled 37\n",
+            "throttled_usec 4100000\n",
+        );
+
+        assert_eq!(
+            Some(CpuThrottling {
+                nr_periods: 1200,
+                nr_throttled: 37,
+                throttled_usec: 4_100_000,
+            }),
+            ProcessData::parse_cpu_stat(cpu_stat)
+        );
+
+        // cgroups without a quota don't report any periods
+        assert_eq!(None, ProcessData::parse_cpu_stat("usage_usec 8112345\n"));
+    }
+}
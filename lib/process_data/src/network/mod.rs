@@ -0,0 +1,109 @@
+//! Network information that has to be read from the host's network namespace, the Flatpak sandbox only sees its own
+//! interfaces
+
+pub mod wifi;
+
+use std::{collections::HashMap, fmt::Display, net::IpAddr};
+
+use anyhow::{Context, Result};
+use nix::{ifaddrs::getifaddrs, sys::socket::SockaddrStorage};
+use serde::{Deserialize, Serialize};
+
+/// An IP address assigned to a network interface
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct InterfaceAddress {
+    pub address: IpAddr,
+    /// The length of the network prefix, e.g. 24 for a netmask of 255.255.255.0
+    pub prefix_length: u32,
+}
+
+impl InterfaceAddress {
+    /// Returns the IPv4 and IPv6 addresses of all interfaces by their interface names, IPv4 addresses first
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the addresses of the system's interfaces couldn't be retrieved
+    pub fn all() -> Result<HashMap<String, Vec<Self>>> {
+        let mut addresses: HashMap<String, Vec<Self>> = HashMap::new();
+
+        for interface_address in getifaddrs().context("unable to get interface addresses")? {
+            let Some(address) = interface_address.address.as_ref().and_then(|address| {
+                Self::from_sockaddrs(address, interface_address.netmask.as_ref())
+            }) else {
+                continue;
+            };
+
+            addresses
+                .entry(interface_address.interface_name)
+                .or_default()
+                .push(address);
+        }
+
+        for interface_addresses in addresses.values_mut() {
+            interface_addresses.sort();
+        }
+
+        Ok(addresses)
+    }
+
+    fn from_sockaddrs(
+        address: &SockaddrStorage,
+        netmask: Option<&SockaddrStorage>,
+    ) -> Option<Self> {
+        if let Some(address) = address.as_sockaddr_in() {
+            Some(Self {
+                address: address.ip().into(),
+                prefix_length: netmask
+                    .and_then(SockaddrStorage::as_sockaddr_in)
+                    .map_or(32, |netmask| u32::from(netmask.ip()).count_ones()),
+            })
+        } else {
+            address.as_sockaddr_in6().map(|address| Self {
+                address: address.ip().into(),
+                prefix_length: netmask
+                    .and_then(SockaddrStorage::as_sockaddr_in6)
+                    .map_or(128, |netmask| u128::from(netmask.ip()).count_ones()),
+            })
+        }
+    }
+}
+
+impl Display for InterfaceAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.address, self.prefix_length)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+    use nix::sys::socket::SockaddrStorage;
+    use pretty_assertions::assert_eq;
+
+    use super::InterfaceAddress;
+
+    #[test]
+    fn interface_address_prefix_length() {
+        let sockaddr = |address: IpAddr| SockaddrStorage::from(SocketAddr::new(address, 0));
+
+        let ipv4_address = InterfaceAddress::from_sockaddrs(
+            &sockaddr(Ipv4Addr::new(192, 168, 1, 23).into()),
+            Some(&sockaddr(Ipv4Addr::new(255, 255, 255, 0).into())),
+        )
+        .unwrap();
+        assert_eq!("192.168.1.23/24", ipv4_address.to_string());
+
+        let ipv6_address = InterfaceAddress::from_sockaddrs(
+            &sockaddr(Ipv6Addr::new(0xfe80, 0, 0, 0, 0x1, 0x2, 0x3, 0x4).into()),
+            Some(&sockaddr(
+                Ipv6Addr::new(0xffff, 0xffff, 0xffff, 0xffff, 0, 0, 0, 0).into(),
+            )),
+        )
+        .unwrap();
+        assert_eq!("fe80::1:2:3:4/64", ipv6_address.to_string());
+
+        // IPv4 addresses are sorted before IPv6 addresses
+        assert!(ipv4_address < ipv6_address);
+    }
+}
@@ -0,0 +1,496 @@
+//! The link of Wi-Fi interfaces to their access point as reported by nl80211, the generic netlink interface of the
+//! kernel's wireless stack
+
+use std::{
+    collections::HashMap,
+    io,
+    os::fd::{AsRawFd, FromRawFd, OwnedFd},
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+const NL80211_FAMILY_NAME: &[u8] = b"nl80211\0";
+
+// commands and attributes as defined in include/uapi/linux/nl80211.h
+const NL80211_CMD_GET_INTERFACE: u8 = 5;
+const NL80211_CMD_GET_STATION: u8 = 17;
+
+const NL80211_ATTR_IFINDEX: u16 = 3;
+const NL80211_ATTR_IFNAME: u16 = 4;
+const NL80211_ATTR_STA_INFO: u16 = 21;
+const NL80211_ATTR_WIPHY_FREQ: u16 = 38;
+const NL80211_ATTR_SSID: u16 = 52;
+
+const NL80211_STA_INFO_SIGNAL: u16 = 7;
+const NL80211_STA_INFO_TX_BITRATE: u16 = 8;
+const NL80211_STA_INFO_RX_BITRATE: u16 = 14;
+
+const NL80211_RATE_INFO_BITRATE: u16 = 1;
+const NL80211_RATE_INFO_BITRATE32: u16 = 5;
+
+const NLMSG_HEADER_LENGTH: usize = 16;
+const GENL_HEADER_LENGTH: usize = 4;
+const NLA_HEADER_LENGTH: usize = 4;
+
+const RECEIVE_BUFFER_SIZE: usize = 32 * 1024;
+
+/// The kernel answers nl80211 requests right away, this only guards against the refresh thread hanging forever
+const RECEIVE_TIMEOUT: libc::timeval = libc::timeval {
+    tv_sec: 1,
+    tv_usec: 0,
+};
+
+/// The current state of the link between a Wi-Fi interface and its access point, everything is `None` while the
+/// interface isn't connected
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WifiLink {
+    pub ssid: Option<String>,
+    /// Frequency of the channel in MHz
+    pub frequency: Option<u32>,
+    /// Signal strength in dBm
+    pub signal: Option<i8>,
+    /// Bitrate of the last received frame in Mb/s
+    pub rx_bitrate: Option<f64>,
+    /// Bitrate of the last sent frame in Mb/s
+    pub tx_bitrate: Option<f64>,
+}
+
+impl WifiLink {
+    /// Returns the links of all Wi-Fi interfaces by their interface names, the netlink socket is opened and the
+    /// nl80211 family is looked up only once for all of them
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if nl80211 isn't available or if there are problems communicating with it
+    pub fn all() -> Result<HashMap<String, Self>> {
+        let mut socket = NetlinkSocket::open()?;
+        let family = socket.family_id(NL80211_FAMILY_NAME)?;
+
+        let interfaces = socket.request(
+            family,
+            libc::NLM_F_DUMP as u16,
+            NL80211_CMD_GET_INTERFACE,
+            &[],
+        )?;
+
+        let mut links = HashMap::with_capacity(interfaces.len());
+
+        for interface in &interfaces {
+            let interface_attributes = parse_attributes(interface);
+
+            let Some(name) =
+                find_attribute(&interface_attributes, NL80211_ATTR_IFNAME).map(|name| {
+                    String::from_utf8_lossy(name)
+                        .trim_end_matches('\0')
+                        .to_string()
+                })
+            else {
+                continue;
+            };
+
+            let Some(ifindex) = find_attribute(&interface_attributes, NL80211_ATTR_IFINDEX)
+                .and_then(read_u32)
+                .map(u32::to_ne_bytes)
+            else {
+                continue;
+            };
+
+            // a Wi-Fi interface in station mode has exactly one station, its access point
+            let station_info = socket
+                .request(
+                    family,
+                    libc::NLM_F_DUMP as u16,
+                    NL80211_CMD_GET_STATION,
+                    &[(NL80211_ATTR_IFINDEX, &ifindex)],
+                )
+                .unwrap_or_default();
+            let station_info = station_info
+                .first()
+                .and_then(|payload| {
+                    find_attribute(&parse_attributes(payload), NL80211_ATTR_STA_INFO)
+                })
+                .map(parse_attributes)
+                .unwrap_or_default();
+
+            let link = Self {
+                ssid: find_attribute(&interface_attributes, NL80211_ATTR_SSID)
+                    .map(|ssid| String::from_utf8_lossy(ssid).to_string()),
+                frequency: find_attribute(&interface_attributes, NL80211_ATTR_WIPHY_FREQ)
+                    .and_then(read_u32),
+                signal: find_attribute(&station_info, NL80211_STA_INFO_SIGNAL)
+                    .and_then(|signal| signal.first())
+                    .map(|signal| *signal as i8),
+                rx_bitrate: find_attribute(&station_info, NL80211_STA_INFO_RX_BITRATE)
+                    .and_then(Self::parse_bitrate),
+                tx_bitrate: find_attribute(&station_info, NL80211_STA_INFO_TX_BITRATE)
+                    .and_then(Self::parse_bitrate),
+            };
+
+            links.insert(name, link);
+        }
+
+        Ok(links)
+    }
+
+    /// Bitrates are given in units of 100 kb/s, the 32-bit variant is preferred since the 16-bit one overflows for
+    /// very high rates
+    fn parse_bitrate(rate_info: &[u8]) -> Option<f64> {
+        let attributes = parse_attributes(rate_info);
+
+        find_attribute(&attributes, NL80211_RATE_INFO_BITRATE32)
+            .and_then(read_u32)
+            .or_else(|| {
+                find_attribute(&attributes, NL80211_RATE_INFO_BITRATE)
+                    .and_then(|bitrate| {
+                        Some(u16::from_ne_bytes(bitrate.get(..2)?.try_into().ok()?))
+                    })
+                    .map(u32::from)
+            })
+            .map(|bitrate| f64::from(bitrate) / 10.0)
+    }
+
+    /// Returns the channel number belonging to the frequency of the link
+    pub fn channel(&self) -> Option<u32> {
+        self.frequency.and_then(channel_from_frequency)
+    }
+
+    /// Maps the signal strength to a quality between 0.0 and 1.0, -100 dBm and below are considered unusable while
+    /// -50 dBm and above are considered perfect
+    pub fn signal_quality(&self) -> Option<f64> {
+        self.signal
+            .map(|signal| ((f64::from(signal) + 100.0) / 50.0).clamp(0.0, 1.0))
+    }
+
+    /// The inverse of [`WifiLink::signal_quality`], returns the signal strength in dBm
+    pub fn signal_from_quality(quality: f64) -> f64 {
+        quality * 50.0 - 100.0
+    }
+}
+
+/// See `ieee80211_freq_khz_to_channel()` in net/wireless/util.c
+fn channel_from_frequency(frequency: u32) -> Option<u32> {
+    match frequency {
+        2484 => Some(14),
+        2412..=2483 => Some((frequency - 2407) / 5),
+        5955..=7115 => Some((frequency - 5950) / 5),
+        5935 => Some(2),
+        4910..=4980 => Some((frequency - 4000) / 5),
+        5000..=5924 => Some((frequency - 5000) / 5),
+        58320..=70200 => Some((frequency - 56160) / 2160),
+        _ => None,
+    }
+}
+
+struct NetlinkSocket {
+    fd: OwnedFd,
+    sequence: u32,
+}
+
+impl NetlinkSocket {
+    fn open() -> Result<Self> {
+        let fd = unsafe {
+            libc::socket(
+                libc::AF_NETLINK,
+                libc::SOCK_RAW | libc::SOCK_CLOEXEC,
+                libc::NETLINK_GENERIC,
+            )
+        };
+
+        if fd < 0 {
+            return Err(io::Error::last_os_error()).context("unable to open netlink socket");
+        }
+
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+
+        let result = unsafe {
+            libc::setsockopt(
+                fd.as_raw_fd(),
+                libc::SOL_SOCKET,
+                libc::SO_RCVTIMEO,
+                std::ptr::from_ref(&RECEIVE_TIMEOUT).cast(),
+                std::mem::size_of::<libc::timeval>() as libc::socklen_t,
+            )
+        };
+
+        if result < 0 {
+            return Err(io::Error::last_os_error())
+                .context("unable to set receive timeout of netlink socket");
+        }
+
+        Ok(Self { fd, sequence: 0 })
+    }
+
+    /// Generic netlink families don't have fixed IDs, they have to be looked up by their name first
+    fn family_id(&mut self, name: &[u8]) -> Result<u16> {
+        let reply = self.request(
+            libc::GENL_ID_CTRL as u16,
+            0,
+            libc::CTRL_CMD_GETFAMILY as u8,
+            &[(libc::CTRL_ATTR_FAMILY_NAME as u16, name)],
+        )?;
+
+        reply
+            .first()
+            .and_then(|payload| {
+                let attributes = parse_attributes(payload);
+                let id = find_attribute(&attributes, libc::CTRL_ATTR_FAMILY_ID as u16)?;
+                Some(u16::from_ne_bytes(id.get(..2)?.try_into().ok()?))
+            })
+            .context("generic netlink family not found")
+    }
+
+    /// Sends a generic netlink request and returns the payloads (without the generic netlink header) of all messages
+    /// of the reply
+    fn request(
+        &mut self,
+        family: u16,
+        flags: u16,
+        command: u8,
+        attributes: &[(u16, &[u8])],
+    ) -> Result<Vec<Vec<u8>>> {
+        self.sequence += 1;
+
+        let message = build_message(
+            family,
+            flags | libc::NLM_F_REQUEST as u16,
+            self.sequence,
+            command,
+            attributes,
+        );
+
+        let sent = unsafe {
+            libc::send(
+                self.fd.as_raw_fd(),
+                message.as_ptr().cast(),
+                message.len(),
+                0,
+            )
+        };
+
+        if sent < 0 {
+            return Err(io::Error::last_os_error()).context("unable to send netlink request");
+        }
+
+        // only dumps consist of multiple messages that are terminated by NLMSG_DONE
+        let mut done = flags & libc::NLM_F_DUMP as u16 == 0;
+        let mut payloads = Vec::new();
+        let mut buffer = vec![0_u8; RECEIVE_BUFFER_SIZE];
+
+        loop {
+            let received = unsafe {
+                libc::recv(
+                    self.fd.as_raw_fd(),
+                    buffer.as_mut_ptr().cast(),
+                    buffer.len(),
+                    0,
+                )
+            };
+
+            if received < 0 {
+                return Err(io::Error::last_os_error()).context("unable to receive netlink reply");
+            }
+
+            for (message_type, payload) in parse_messages(&buffer[..received as usize]) {
+                match i32::from(message_type) {
+                    libc::NLMSG_DONE => done = true,
+                    libc::NLMSG_ERROR => {
+                        let error = payload
+                            .get(..4)
+                            .and_then(|error| error.try_into().ok())
+                            .map_or(0, i32::from_ne_bytes);
+
+                        if error != 0 {
+                            return Err(io::Error::from_raw_os_error(-error))
+                                .context("netlink request failed");
+                        }
+
+                        done = true;
+                    }
+                    _ => payloads.push(
+                        payload
+                            .get(GENL_HEADER_LENGTH..)
+                            .unwrap_or_default()
+                            .to_vec(),
+                    ),
+                }
+            }
+
+            if done {
+                return Ok(payloads);
+            }
+        }
+    }
+}
+
+fn align(length: usize) -> usize {
+    (length + 3) & !3
+}
+
+fn read_u32(value: &[u8]) -> Option<u32> {
+    Some(u32::from_ne_bytes(value.get(..4)?.try_into().ok()?))
+}
+
+/// Builds a netlink message consisting of the netlink header, the generic netlink header and the attributes
+fn build_message(
+    family: u16,
+    flags: u16,
+    sequence: u32,
+    command: u8,
+    attributes: &[(u16, &[u8])],
+) -> Vec<u8> {
+    let mut message = vec![0_u8; NLMSG_HEADER_LENGTH];
+
+    // generic netlink header: command, version and two reserved bytes
+    message.extend_from_slice(&[command, 1, 0, 0]);
+
+    for (attribute_type, value) in attributes {
+        let length = NLA_HEADER_LENGTH + value.len();
+        message.extend_from_slice(&(length as u16).to_ne_bytes());
+        message.extend_from_slice(&attribute_type.to_ne_bytes());
+        message.extend_from_slice(value);
+        message.resize(align(message.len()), 0);
+    }
+
+    let length = message.len() as u32;
+    message[0..4].copy_from_slice(&length.to_ne_bytes());
+    message[4..6].copy_from_slice(&family.to_ne_bytes());
+    message[6..8].copy_from_slice(&flags.to_ne_bytes());
+    message[8..12].copy_from_slice(&sequence.to_ne_bytes());
+    // the port ID stays 0, the kernel fills it in
+
+    message
+}
+
+/// Splits a buffer received from a netlink socket into its messages, returning their types and payloads
+fn parse_messages(buffer: &[u8]) -> Vec<(u16, &[u8])> {
+    let mut messages = Vec::new();
+    let mut offset = 0;
+
+    while let Some(header) = buffer.get(offset..offset + NLMSG_HEADER_LENGTH) {
+        let length = u32::from_ne_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        let message_type = u16::from_ne_bytes([header[4], header[5]]);
+
+        let Some(payload) = buffer.get(offset + NLMSG_HEADER_LENGTH..offset + length) else {
+            break;
+        };
+
+        messages.push((message_type, payload));
+        offset += align(length);
+    }
+
+    messages
+}
+
+/// Splits the payload of a message or of a nested attribute into its attributes, returning their types and values
+fn parse_attributes(payload: &[u8]) -> Vec<(u16, &[u8])> {
+    let mut attributes = Vec::new();
+    let mut offset = 0;
+
+    while let Some(header) = payload.get(offset..offset + NLA_HEADER_LENGTH) {
+        let length = u16::from_ne_bytes([header[0], header[1]]) as usize;
+        let attribute_type =
+            u16::from_ne_bytes([header[2], header[3]]) & libc::NLA_TYPE_MASK as u16;
+
+        let Some(value) = payload
+            .get(offset + NLA_HEADER_LENGTH..offset + length)
+            .filter(|_| length >= NLA_HEADER_LENGTH)
+        else {
+            break;
+        };
+
+        attributes.push((attribute_type, value));
+        offset += align(length);
+    }
+
+    attributes
+}
+
+fn find_attribute<'a>(attributes: &[(u16, &'a [u8])], attribute_type: u16) -> Option<&'a [u8]> {
+    attributes
+        .iter()
+        .find(|(other_type, _)| *other_type == attribute_type)
+        .map(|(_, value)| *value)
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::{
+        build_message, channel_from_frequency, parse_attributes, parse_messages, WifiLink,
+        GENL_HEADER_LENGTH, NL80211_RATE_INFO_BITRATE, NL80211_RATE_INFO_BITRATE32,
+    };
+
+    #[test]
+    fn message_roundtrip() {
+        let message = build_message(0x1c, 1, 7, 17, &[(3, &4_u32.to_ne_bytes()), (52, b"abc")]);
+
+        // the attributes are padded to four bytes
+        assert_eq!(16 + 4 + 8 + 8, message.len());
+
+        let messages = parse_messages(&message);
+        assert_eq!(1, messages.len());
+
+        let (message_type, payload) = messages[0];
+        assert_eq!(0x1c, message_type);
+        assert_eq!(17, payload[0]);
+
+        let attributes = parse_attributes(&payload[GENL_HEADER_LENGTH..]);
+        assert_eq!(
+            vec![(3, &4_u32.to_ne_bytes()[..]), (52, &b"abc"[..])],
+            attributes
+        );
+    }
+
+    #[test]
+    fn parse_truncated_attributes() {
+        // the second attribute claims to be longer than the payload
+        let payload = [8, 0, 1, 0, 1, 2, 3, 4, 12, 0, 2, 0, 1, 2];
+
+        assert_eq!(vec![(1, &[1, 2, 3, 4][..])], parse_attributes(&payload));
+    }
+
+    #[test]
+    fn bitrate() {
+        let mut rate_info = Vec::new();
+        rate_info.extend_from_slice(&6_u16.to_ne_bytes());
+        rate_info.extend_from_slice(&NL80211_RATE_INFO_BITRATE.to_ne_bytes());
+        rate_info.extend_from_slice(&8667_u16.to_ne_bytes());
+        rate_info.extend_from_slice(&[0, 0]);
+
+        assert_eq!(Some(866.7), WifiLink::parse_bitrate(&rate_info));
+
+        rate_info.extend_from_slice(&8_u16.to_ne_bytes());
+        rate_info.extend_from_slice(&NL80211_RATE_INFO_BITRATE32.to_ne_bytes());
+        rate_info.extend_from_slice(&28820_u32.to_ne_bytes());
+
+        assert_eq!(Some(2882.0), WifiLink::parse_bitrate(&rate_info));
+    }
+
+    #[test]
+    fn channels() {
+        assert_eq!(Some(1), channel_from_frequency(2412));
+        assert_eq!(Some(14), channel_from_frequency(2484));
+        assert_eq!(Some(36), channel_from_frequency(5180));
+        assert_eq!(Some(165), channel_from_frequency(5825));
+        assert_eq!(Some(1), channel_from_frequency(5955));
+        assert_eq!(Some(2), channel_from_frequency(60480));
+        assert_eq!(None, channel_from_frequency(900));
+    }
+
+    #[test]
+    fn signal_quality() {
+        let link = |signal| WifiLink {
+            signal: Some(signal),
+            ..Default::default()
+        };
+
+        assert_eq!(Some(1.0), link(-40).signal_quality());
+        assert_eq!(Some(0.5), link(-75).signal_quality());
+        assert_eq!(Some(0.0), link(-105).signal_quality());
+        assert_eq!(None, WifiLink::default().signal_quality());
+
+        assert_eq!(-75.0, WifiLink::signal_from_quality(0.5));
+    }
+}
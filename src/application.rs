@@ -5,14 +5,20 @@ use glib::clone;
 use gtk::{gdk, gio, glib};
 
 use crate::config::{self, APP_ID, PKGDATADIR, PROFILE, VERSION};
+use crate::gui::ARGS;
 use crate::i18n::i18n;
 use crate::ui::dialogs::settings_dialog::ResSettingsDialog;
 use crate::ui::window::MainWindow;
+use crate::utils::gpu::Gpu;
 use crate::utils::os::OsInfo;
 use crate::utils::process::ProcessAction;
+use crate::utils::settings::SETTINGS;
 
 mod imp {
-    use std::{cell::Cell, sync::OnceLock};
+    use std::{
+        cell::{Cell, RefCell},
+        sync::OnceLock,
+    };
 
     use super::*;
     use glib::WeakRef;
@@ -22,6 +28,9 @@ mod imp {
         pub window: OnceLock<WeakRef<MainWindow>>,
 
         pub settings_window_opened: Cell<bool>,
+
+        /// Keeps the application alive while it's been started hidden and no window is shown yet
+        pub hidden_hold: RefCell<Option<gio::ApplicationHoldGuard>>,
     }
 
     #[glib::object_subclass]
@@ -41,6 +50,7 @@ mod imp {
             if let Some(window) = self.window.get() {
                 let window = window.upgrade().unwrap();
                 window.present();
+                self.hidden_hold.take();
                 return;
             }
 
@@ -49,7 +59,12 @@ mod imp {
                 .set(window.downgrade())
                 .expect("Window already set.");
 
-            app.main_window().present();
+            if ARGS.start_hidden || SETTINGS.start_hidden() {
+                info!("Starting hidden, launch Resources again to show the window");
+                self.hidden_hold.replace(Some(app.hold()));
+            } else {
+                app.main_window().present();
+            }
         }
 
         fn startup(&self) {
@@ -116,6 +131,39 @@ impl Application {
         ));
         self.add_action(&action_search);
 
+        // Expand/Collapse All Sections
+        let action_toggle_expanders = gio::SimpleAction::new("toggle-expanders", None);
+        action_toggle_expanders.connect_activate(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |_, _| {
+                this.main_window().shortcut_toggle_expanders();
+            }
+        ));
+        self.add_action(&action_toggle_expanders);
+
+        // Copy All Stats of the Current Page
+        let action_copy_page_stats = gio::SimpleAction::new("copy-page-stats", None);
+        action_copy_page_stats.connect_activate(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |_, _| {
+                this.main_window().shortcut_copy_page_stats();
+            }
+        ));
+        self.add_action(&action_copy_page_stats);
+
+        // Rename the Device of the Current Page
+        let action_rename_page = gio::SimpleAction::new("rename-page", None);
+        action_rename_page.connect_activate(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |_, _| {
+                this.main_window().shortcut_rename_page();
+            }
+        ));
+        self.add_action(&action_rename_page);
+
         // Show Settings
         let action_settings = gio::SimpleAction::new("settings", None);
         action_settings.connect_activate(clone!(
@@ -215,6 +263,9 @@ impl Application {
         self.set_accels_for_action("app.quit", &["<Control>q", "<Control>w"]);
         self.set_accels_for_action("app.settings", &["<Control>comma"]);
         self.set_accels_for_action("app.toggle-search", &["<Control>f", "F3"]);
+        self.set_accels_for_action("app.toggle-expanders", &["<Control><Alt>e"]);
+        self.set_accels_for_action("app.copy-page-stats", &["<Control><Shift>c"]);
+        self.set_accels_for_action("app.rename-page", &["F2"]);
         self.set_accels_for_action("app.end-app-process", &["<Control>E", "Delete"]);
         self.set_accels_for_action("app.kill-app-process", &["<Control>K", "<Shift>Delete"]);
         self.set_accels_for_action("app.halt-app-process", &["<Control>H"]);
@@ -281,9 +332,64 @@ impl Application {
         about.set_translator_credits(&i18n("translator-credits"));
         about.add_credit_section(Some(&i18n("Icon by")), &["Avhiren"]);
 
+        about.set_debug_info(&Self::debug_info());
+        about.set_debug_info_filename("resources-debug-info.txt");
+
         about.present(Some(&self.main_window()));
     }
 
+    fn debug_info() -> String {
+        let os_info = OsInfo::get();
+
+        let gpus = Gpu::get_gpus()
+            .unwrap_or_default()
+            .iter()
+            .map(|gpu| {
+                let connected_displays = gpu.connected_displays();
+                let display_role = if connected_displays.is_empty() {
+                    "render only".to_string()
+                } else {
+                    let displays = connected_displays
+                        .iter()
+                        .map(|display| {
+                            format!(
+                                "{} {}",
+                                display.connector,
+                                display.preferred_mode.as_deref().unwrap_or("unknown mode")
+                            )
+                        })
+                        .collect::<Vec<_>>();
+                    format!("display ({})", displays.join(", "))
+                };
+
+                format!(
+                    "- {} ({}, {}, {}): {display_role}\n",
+                    gpu.name().unwrap_or_else(|_| "N/A".to_string()),
+                    gpu.driver(),
+                    gpu.card_node(),
+                    gpu.render_node().as_deref().unwrap_or("no render node"),
+                )
+            })
+            .collect::<String>();
+
+        let info = format!(
+            "Resources {VERSION} ({PROFILE})\n\nOperating system: {}\nHypervisor: {}\nKernel version: {}\nLoaded kernel modules: {}\nKernel command line: {}\n",
+            os_info.name.as_deref().unwrap_or("N/A"),
+            os_info.hypervisor.as_deref().unwrap_or("None"),
+            os_info.kernel_version.as_deref().unwrap_or("N/A"),
+            os_info
+                .kernel_modules
+                .map_or_else(|| "N/A".to_string(), |modules| modules.to_string()),
+            os_info.kernel_cmdline.as_deref().unwrap_or("N/A"),
+        );
+
+        if gpus.is_empty() {
+            info
+        } else {
+            format!("{info}\nGPUs:\n{gpus}")
+        }
+    }
+
     pub fn run(&self) {
         trace!("Starting the application");
         info!("Resources ({APP_ID})");
@@ -296,10 +402,24 @@ impl Application {
                 "Operating system: {}",
                 os_info.name.as_deref().unwrap_or("N/A")
             );
+            debug!(
+                "Hypervisor: {}",
+                os_info.hypervisor.as_deref().unwrap_or("None")
+            );
             debug!(
                 "Kernel version: {}",
                 os_info.kernel_version.as_deref().unwrap_or("N/A")
             );
+            debug!(
+                "Kernel command line: {}",
+                os_info.kernel_cmdline.as_deref().unwrap_or("N/A")
+            );
+            debug!(
+                "Loaded kernel modules: {}",
+                os_info
+                    .kernel_modules
+                    .map_or_else(|| "N/A".to_string(), |modules| modules.to_string())
+            );
         }
 
         if PROFILE == "Devel" {
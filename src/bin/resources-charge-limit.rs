@@ -0,0 +1,73 @@
+use std::{env, path::Path};
+
+const PATH_SYSFS: &str = "/sys/class/power_supply";
+
+fn main() {
+    if let (Some(battery), Some(start), Some(end)) =
+        (env::args().nth(1), env::args().nth(2), env::args().nth(3))
+    {
+        let Some(end) = parse_threshold(&end) else {
+            std::process::exit(254);
+        };
+
+        // batteries that only support an end threshold get "-" as their start threshold
+        let start = if start == "-" {
+            None
+        } else if let Some(start) = parse_threshold(&start).filter(|start| *start < end) {
+            Some(start)
+        } else {
+            std::process::exit(254);
+        };
+
+        if !is_valid_battery(&battery) {
+            std::process::exit(253);
+        }
+
+        let battery_path = Path::new(PATH_SYSFS).join(battery);
+        let start_path = battery_path.join("charge_control_start_threshold");
+        let end_path = battery_path.join("charge_control_end_threshold");
+
+        // some drivers reject thresholds that would make the start threshold reach the end threshold in between, so
+        // write the end threshold first if the new start threshold isn't below the current end threshold
+        let current_end = std::fs::read_to_string(&end_path)
+            .ok()
+            .and_then(|current_end| parse_threshold(current_end.trim()));
+
+        match start {
+            Some(start) if current_end.is_some_and(|current_end| start >= current_end) => {
+                write(&end_path, end);
+                write(&start_path, start);
+            }
+            Some(start) => {
+                write(&start_path, start);
+                write(&end_path, end);
+            }
+            None => write(&end_path, end),
+        }
+
+        std::process::exit(0)
+    }
+    std::process::exit(255);
+}
+
+fn parse_threshold(s: &str) -> Option<u8> {
+    s.parse::<u8>()
+        .ok()
+        .filter(|threshold| (1..=100).contains(threshold))
+}
+
+/// Only allow names of batteries that exist, so that this can't be used to write anywhere else in sysfs
+fn is_valid_battery(battery: &str) -> bool {
+    !battery.is_empty()
+        && battery
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || byte == b'_' || byte == b'-')
+        && std::fs::read_to_string(Path::new(PATH_SYSFS).join(battery).join("type"))
+            .is_ok_and(|supply_type| supply_type.trim() == "Battery")
+}
+
+fn write(path: &Path, threshold: u8) {
+    if let Err(err) = std::fs::write(path, threshold.to_string()) {
+        std::process::exit(err.raw_os_error().unwrap_or(252))
+    }
+}
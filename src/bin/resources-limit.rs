@@ -0,0 +1,66 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+fn main() {
+    if let Some(pid) = env::args()
+        .nth(1)
+        .and_then(|s| s.trim().parse::<i32>().ok())
+    {
+        if let (Some(memory_max), Some(cpu_max)) = (env::args().nth(2), env::args().nth(3)) {
+            if !is_valid_memory_max(&memory_max) || !is_valid_cpu_max(&cpu_max) {
+                std::process::exit(254);
+            }
+
+            let Some(cgroup_path) = cgroup_path(pid) else {
+                std::process::exit(253);
+            };
+
+            write(&cgroup_path.join("memory.max"), &memory_max);
+            write(&cgroup_path.join("cpu.max"), &cpu_max);
+
+            std::process::exit(0)
+        }
+    }
+    std::process::exit(255);
+}
+
+/// Returns the path of the cgroup v2 the process belongs to, unless it's the root cgroup
+fn cgroup_path(pid: i32) -> Option<PathBuf> {
+    let cgroup = std::fs::read_to_string(format!("/proc/{pid}/cgroup")).ok()?;
+
+    let path = cgroup
+        .lines()
+        .find_map(|line| line.strip_prefix("0::"))?
+        .trim()
+        .trim_start_matches('/');
+
+    if path.is_empty() || path.split('/').any(|segment| segment == "..") {
+        None
+    } else {
+        Some(Path::new("/sys/fs/cgroup").join(path))
+    }
+}
+
+fn is_number(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(|byte| byte.is_ascii_digit())
+}
+
+/// `memory.max` is either "max" or an amount of bytes
+fn is_valid_memory_max(memory_max: &str) -> bool {
+    memory_max == "max" || is_number(memory_max)
+}
+
+/// `cpu.max` is "$MAX $PERIOD" where $MAX is either "max" or the quota
+fn is_valid_cpu_max(cpu_max: &str) -> bool {
+    cpu_max
+        .split_once(' ')
+        .is_some_and(|(max, period)| (max == "max" || is_number(max)) && is_number(period))
+}
+
+fn write(path: &Path, value: &str) {
+    if let Err(err) = std::fs::write(path, value) {
+        std::process::exit(err.raw_os_error().unwrap_or(252))
+    }
+}
@@ -0,0 +1,96 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+use nvml_wrapper::{error::NvmlError, Nvml};
+use process_data::pci_slot::PciSlot;
+
+const PATH_SYSFS_PCI: &str = "/sys/bus/pci/devices";
+
+fn main() {
+    if let (Some(pci_slot), Some(power_cap)) = (env::args().nth(1), env::args().nth(2)) {
+        // the power cap is given in microwatts, just like hwmon expects it
+        let Some(power_cap) = power_cap.parse::<u64>().ok().filter(|cap| *cap > 0) else {
+            std::process::exit(254);
+        };
+
+        let Some(device_path) = gpu_device_path(&pci_slot) else {
+            std::process::exit(253);
+        };
+
+        // NVIDIA's proprietary driver doesn't register an hwmon device, so its power cap can only be set through NVML
+        match power_cap_hwmon(&device_path) {
+            Some(hwmon_path) => set_hwmon_power_cap(&hwmon_path, power_cap),
+            None => set_nvml_power_cap(&pci_slot, power_cap),
+        }
+
+        std::process::exit(0)
+    }
+    std::process::exit(255);
+}
+
+/// Only allow PCI slots of GPUs, so that this can't be used to write to any other device
+fn gpu_device_path(pci_slot: &str) -> Option<PathBuf> {
+    let pci_slot = pci_slot.parse::<PciSlot>().ok()?;
+    let device_path = Path::new(PATH_SYSFS_PCI).join(pci_slot.to_string());
+
+    device_path.join("drm").is_dir().then_some(device_path)
+}
+
+fn power_cap_hwmon(device_path: &Path) -> Option<PathBuf> {
+    let mut hwmon_paths = std::fs::read_dir(device_path.join("hwmon"))
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .collect::<Vec<_>>();
+    hwmon_paths.sort();
+
+    hwmon_paths
+        .into_iter()
+        .find(|hwmon_path| hwmon_path.join("power1_cap").exists())
+}
+
+fn read_hwmon_int(path: &Path) -> Option<u64> {
+    std::fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+fn set_hwmon_power_cap(hwmon_path: &Path, power_cap: u64) {
+    let min = read_hwmon_int(&hwmon_path.join("power1_cap_min")).unwrap_or_default();
+    let max = read_hwmon_int(&hwmon_path.join("power1_cap_max")).unwrap_or(u64::MAX);
+
+    if !(min..=max).contains(&power_cap) {
+        std::process::exit(254);
+    }
+
+    if let Err(err) = std::fs::write(hwmon_path.join("power1_cap"), power_cap.to_string()) {
+        std::process::exit(err.raw_os_error().unwrap_or(252))
+    }
+}
+
+fn set_nvml_power_cap(pci_slot: &str, power_cap: u64) {
+    let Ok(nvml) = Nvml::init() else {
+        std::process::exit(252);
+    };
+
+    let Ok(mut device) = nvml.device_by_pci_bus_id(pci_slot) else {
+        std::process::exit(253);
+    };
+
+    // NVML works with milliwatts
+    let Ok(power_cap) = u32::try_from(power_cap / 1000) else {
+        std::process::exit(254);
+    };
+
+    if let Ok(constraints) = device.power_management_limit_constraints() {
+        if !(constraints.min_limit..=constraints.max_limit).contains(&power_cap) {
+            std::process::exit(254);
+        }
+    }
+
+    match device.set_power_management_limit(power_cap) {
+        Ok(()) => {}
+        Err(NvmlError::NoPermission) => std::process::exit(libc::EPERM),
+        Err(_) => std::process::exit(252),
+    }
+}
@@ -1,6 +1,13 @@
 use anyhow::Result;
-use process_data::ProcessData;
+use process_data::{
+    network::{wifi::WifiLink, InterfaceAddress},
+    DrmFdinfo, EnvironmentVariable, OpenFile, ProcessData, ProcessLimits, SchedStat, SocketData,
+    ThreadData, WaitChannel, DRM_FDINFO_REQUEST, ENVIRONMENT_REQUEST, INTERFACE_ADDRESSES_REQUEST,
+    LIMITS_REQUEST, OPEN_FILES_REQUEST, SCHEDSTAT_REQUEST, SOCKETS_REQUEST, THREADS_REQUEST,
+    WAIT_CHANNEL_REQUEST, WIFI_LINKS_REQUEST,
+};
 use ron::ser::PrettyConfig;
+use serde::Serialize;
 use std::io::{Read, Write};
 
 use clap::Parser;
@@ -15,13 +22,75 @@ struct Args {
     /// Use Rusty Object Notation (use this only for debugging this binary on its own, Resources won't be able to decode RON)
     #[arg(short, long, default_value_t = false)]
     ron: bool,
+
+    /// Output the threads of the process with this PID instead of all processes
+    #[arg(short, long)]
+    threads: Option<i32>,
+
+    /// Output the raw DRM fdinfo fields of the process with this PID instead of all processes
+    #[arg(short, long)]
+    drm_fdinfo: Option<i32>,
+
+    /// Output the resource limits of the process with this PID instead of all processes
+    #[arg(short, long)]
+    limits: Option<i32>,
+
+    /// Output the open sockets of the process with this PID instead of all processes
+    #[arg(short, long)]
+    sockets: Option<i32>,
+
+    /// Output the environment variables of the process with this PID instead of all processes
+    #[arg(short, long)]
+    environment: Option<i32>,
+
+    /// Output the open files of the process with this PID instead of all processes
+    #[arg(short, long)]
+    files: Option<i32>,
+
+    /// Output the wait channel of the process with this PID instead of all processes
+    #[arg(short = 'c', long)]
+    wait_channel: Option<i32>,
+
+    /// Output the scheduler statistics of the process with this PID instead of all processes
+    #[arg(short = 'S', long)]
+    schedstat: Option<i32>,
+
+    /// Output the addresses of all network interfaces instead of all processes
+    #[arg(short, long, default_value_t = false)]
+    addresses: bool,
+
+    /// Output the links of all Wi-Fi interfaces instead of all processes
+    #[arg(short, long, default_value_t = false)]
+    wifi: bool,
 }
 
 fn main() -> Result<()> {
     let args = Args::parse();
 
     if args.once {
-        output(args.ron)?;
+        if let Some(pid) = args.threads {
+            output(&ThreadData::all_of_process(pid)?, args.ron)?;
+        } else if let Some(pid) = args.drm_fdinfo {
+            output(&DrmFdinfo::all_of_process(pid)?, args.ron)?;
+        } else if let Some(pid) = args.limits {
+            output(&ProcessLimits::of_process(pid)?, args.ron)?;
+        } else if let Some(pid) = args.sockets {
+            output(&SocketData::all_of_process(pid)?, args.ron)?;
+        } else if let Some(pid) = args.environment {
+            output(&EnvironmentVariable::all_of_process(pid)?, args.ron)?;
+        } else if let Some(pid) = args.files {
+            output(&OpenFile::all_of_process(pid)?, args.ron)?;
+        } else if let Some(pid) = args.wait_channel {
+            output(&WaitChannel::of_process(pid)?, args.ron)?;
+        } else if let Some(pid) = args.schedstat {
+            output(&SchedStat::of_process(pid)?, args.ron)?;
+        } else if args.addresses {
+            output(&InterfaceAddress::all()?, args.ron)?;
+        } else if args.wifi {
+            output(&WifiLink::all()?, args.ron)?;
+        } else {
+            output(&ProcessData::all_process_data()?, args.ron)?;
+        }
         return Ok(());
     }
 
@@ -30,19 +99,69 @@ fn main() -> Result<()> {
 
         std::io::stdin().read_exact(&mut buffer)?;
 
-        output(args.ron)?;
+        if buffer[0] == THREADS_REQUEST {
+            // the process might have exited in the meantime, an empty list is the best answer in that case
+            let threads = ThreadData::all_of_process(read_pid()?).unwrap_or_default();
+
+            output(&threads, args.ron)?;
+        } else if buffer[0] == DRM_FDINFO_REQUEST {
+            let fdinfos = DrmFdinfo::all_of_process(read_pid()?).unwrap_or_default();
+
+            output(&fdinfos, args.ron)?;
+        } else if buffer[0] == LIMITS_REQUEST {
+            let limits = ProcessLimits::of_process(read_pid()?).unwrap_or_default();
+
+            output(&limits, args.ron)?;
+        } else if buffer[0] == SOCKETS_REQUEST {
+            let sockets = SocketData::all_of_process(read_pid()?).unwrap_or_default();
+
+            output(&sockets, args.ron)?;
+        } else if buffer[0] == ENVIRONMENT_REQUEST {
+            let environment = EnvironmentVariable::all_of_process(read_pid()?).unwrap_or_default();
+
+            output(&environment, args.ron)?;
+        } else if buffer[0] == OPEN_FILES_REQUEST {
+            let open_files = OpenFile::all_of_process(read_pid()?).unwrap_or_default();
+
+            output(&open_files, args.ron)?;
+        } else if buffer[0] == WAIT_CHANNEL_REQUEST {
+            let wait_channel = WaitChannel::of_process(read_pid()?).unwrap_or_default();
+
+            output(&wait_channel, args.ron)?;
+        } else if buffer[0] == SCHEDSTAT_REQUEST {
+            let schedstat = SchedStat::of_process(read_pid()?).unwrap_or_default();
+
+            output(&schedstat, args.ron)?;
+        } else if buffer[0] == INTERFACE_ADDRESSES_REQUEST {
+            let addresses = InterfaceAddress::all().unwrap_or_default();
+
+            output(&addresses, args.ron)?;
+        } else if buffer[0] == WIFI_LINKS_REQUEST {
+            // machines without Wi-Fi don't have nl80211 at all
+            let wifi_links = WifiLink::all().unwrap_or_default();
+
+            output(&wifi_links, args.ron)?;
+        } else {
+            output(&ProcessData::all_process_data()?, args.ron)?;
+        }
     }
 }
 
-fn output(ron: bool) -> Result<()> {
-    let data = ProcessData::all_process_data()?;
+/// Reads the PID that follows a per-process request from stdin
+fn read_pid() -> Result<i32> {
+    let mut pid_bytes = [0; (i32::BITS / 8) as usize];
+    std::io::stdin().read_exact(&mut pid_bytes)?;
+
+    Ok(i32::from_le_bytes(pid_bytes))
+}
 
+fn output<T: Serialize>(data: &T, ron: bool) -> Result<()> {
     let encoded = if ron {
-        ron::ser::to_string_pretty(&data, PrettyConfig::default())?
+        ron::ser::to_string_pretty(data, PrettyConfig::default())?
             .as_bytes()
             .to_vec()
     } else {
-        rmp_serde::to_vec(&data)?
+        rmp_serde::to_vec(data)?
     };
 
     let len_byte_array = encoded.len().to_le_bytes();
@@ -1,16 +1,21 @@
 use std::ffi::OsString;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
 use std::sync::LazyLock;
+use std::time::Duration;
 
 use crate::application;
 #[rustfmt::skip]
 use crate::config;
+use crate::ui::window::MainWindow;
 use crate::utils::app::DATA_DIRS;
+use crate::utils::metrics_log::MetricsLog;
 use crate::utils::IS_FLATPAK;
 
 use clap::{command, Parser};
 use gettextrs::{gettext, LocaleCategory};
 use gtk::{gio, glib};
-use log::trace;
+use log::{error, trace, warn};
 
 use self::application::Application;
 use self::config::{GETTEXT_PACKAGE, LOCALEDIR, RESOURCES_FILE};
@@ -58,6 +63,92 @@ pub struct Args {
     /// "battery-$MANUFACTURER$-$MODEL_NAME$-$DEVICE_NAME$"
     #[arg(short = 't', long)]
     pub open_tab_id: Option<String>,
+
+    /// Refresh every this many seconds during this session instead of using the refresh speed from the settings.
+    /// Must be between 0.25 and 60 seconds, otherwise the settings are used
+    #[arg(long, value_name = "SECONDS")]
+    pub refresh_interval: Option<f32>,
+
+    /// Start in the background without showing the main window.
+    /// Launch Resources again to open it. Since Wayland has no way for apps to restore a window themselves,
+    /// this is the only way to bring it up there
+    #[arg(long, default_value_t = false)]
+    pub start_hidden: bool,
+
+    /// Periodically append snapshots of all metrics to this file as CSV ("timestamp,device,metric,value").
+    /// The file is rotated once it gets too large, keeping up to 5 old files
+    #[arg(long, value_name = "FILE")]
+    pub log_metrics: Option<PathBuf>,
+
+    /// Seconds between two snapshots written to the metrics log
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        default_value_t = 5.0,
+        requires = "log_metrics"
+    )]
+    pub log_interval: f32,
+
+    /// Size in MiB after which the metrics log is rotated, at least 1
+    #[arg(
+        long,
+        value_name = "MIB",
+        default_value_t = 10,
+        value_parser = clap::value_parser!(u64).range(1..),
+        requires = "log_metrics"
+    )]
+    pub log_max_size: u64,
+
+    /// Don't open a window and only write the metrics log
+    #[arg(long, default_value_t = false, requires = "log_metrics")]
+    pub headless: bool,
+
+    /// Don't open a window and print a snapshot of all metrics and processes as JSON to stdout instead
+    #[arg(long, default_value_t = false, conflicts_with = "headless")]
+    pub json: bool,
+
+    /// Print a new JSON snapshot every this many seconds (one per line) instead of exiting after the first one
+    #[arg(long, value_name = "SECONDS", requires = "json")]
+    pub json_interval: Option<f32>,
+}
+
+impl Args {
+    /// The refresh intervals that may be passed via `--refresh-interval`, the lower bound is the interval of the
+    /// fastest refresh speed available in the settings
+    const REFRESH_INTERVAL_RANGE: RangeInclusive<f32> = 0.25..=60.0;
+
+    /// The refresh interval passed via `--refresh-interval` if it's valid, `None` otherwise
+    pub fn refresh_interval(&self) -> Option<f32> {
+        self.refresh_interval
+            .filter(|interval| Self::REFRESH_INTERVAL_RANGE.contains(interval))
+    }
+
+    /// The shortest interval between two metrics snapshots that we allow to avoid hammering the system
+    const MIN_METRICS_INTERVAL: f32 = 0.5;
+
+    /// Opens the metrics log if it has been requested
+    pub fn metrics_log(&self) -> Option<anyhow::Result<MetricsLog>> {
+        self.log_metrics
+            .as_ref()
+            .map(|path| MetricsLog::open(path, self.log_max_size.saturating_mul(1024 * 1024)))
+    }
+
+    pub fn metrics_interval(&self) -> Duration {
+        Self::clamp_metrics_interval(self.log_interval)
+    }
+
+    /// The interval between two JSON snapshots, `None` if only a single one should be printed
+    pub fn json_interval(&self) -> Option<Duration> {
+        self.json_interval.map(Self::clamp_metrics_interval)
+    }
+
+    fn clamp_metrics_interval(interval: f32) -> Duration {
+        if interval.is_finite() {
+            Duration::from_secs_f32(interval.max(Self::MIN_METRICS_INTERVAL))
+        } else {
+            Duration::from_secs_f32(Self::MIN_METRICS_INTERVAL)
+        }
+    }
 }
 
 pub fn main() {
@@ -68,6 +159,14 @@ pub fn main() {
     pretty_env_logger::init();
     trace!("Trace logs activated. Brace yourself for *lots* of logs. Slowdowns may occur.");
 
+    if let (Some(interval), None) = (ARGS.refresh_interval, ARGS.refresh_interval()) {
+        warn!(
+            "Refresh interval of {interval} seconds is out of range ({} to {} seconds), using the settings instead",
+            Args::REFRESH_INTERVAL_RANGE.start(),
+            Args::REFRESH_INTERVAL_RANGE.end()
+        );
+    }
+
     // reset XDG_DATA_DIRS to use absolute paths instead of relative paths because Flatpak seemingly cannot resolve them
     // this must happen now because once the GTK app is loaded, it's too late
     if *IS_FLATPAK {
@@ -88,6 +187,21 @@ pub fn main() {
 
     glib::set_application_name(&gettext("Resources"));
 
+    if ARGS.json {
+        MainWindow::print_json(ARGS.json_interval());
+    }
+
+    if ARGS.headless {
+        match ARGS.metrics_log() {
+            Some(Ok(metrics_log)) => MainWindow::log_metrics(metrics_log, ARGS.metrics_interval()),
+            Some(Err(e)) => {
+                error!("Unable to open metrics log: {e}");
+                std::process::exit(1);
+            }
+            None => unreachable!("--headless requires --log-metrics"),
+        }
+    }
+
     let res = gio::Resource::load(RESOURCES_FILE).expect("Could not load gresource file");
     gio::resources_register(&res);
 
@@ -1,7 +1,7 @@
 use crate::config::PROFILE;
 use crate::i18n::i18n;
 use crate::ui::pages::applications::application_entry::ApplicationEntry;
-use crate::utils::units::{convert_speed, convert_storage};
+use crate::utils::units::{convert_speed, convert_storage, format_percentage};
 use adw::{prelude::*, subclass::prelude::*};
 use gtk::gio::ThemedIcon;
 use gtk::glib;
@@ -51,7 +51,17 @@ mod imp {
         #[template_child]
         pub processes_amount: TemplateChild<adw::ActionRow>,
         #[template_child]
+        pub storage_usage: TemplateChild<adw::ActionRow>,
+        #[template_child]
         pub containerized: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub flatpak_branch: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub flatpak_runtime: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub snap_revision: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub snap_version: TemplateChild<adw::ActionRow>,
     }
 
     #[glib::object_subclass]
@@ -154,6 +164,19 @@ impl ResAppDialog {
 
         imp.containerized.set_subtitle(&app.containerization());
 
+        for (row, value) in [
+            (&imp.flatpak_branch, app.flatpak_branch()),
+            (&imp.flatpak_runtime, app.flatpak_runtime()),
+            (&imp.snap_revision, app.snap_revision()),
+            (&imp.snap_version, app.snap_version()),
+        ] {
+            if let Some(value) = &value {
+                row.set_subtitle(value);
+            } else {
+                row.set_visible(false);
+            }
+        }
+
         self.update(app);
     }
 
@@ -163,7 +186,7 @@ impl ResAppDialog {
         let imp = self.imp();
 
         imp.cpu_usage
-            .set_subtitle(&format!("{:.1} %", app.cpu_usage() * 100.0));
+            .set_subtitle(&format_percentage(app.cpu_usage() * 100.0));
 
         imp.memory_usage
             .set_subtitle(&convert_storage(app.memory_usage() as f64, false));
@@ -184,18 +207,25 @@ impl ResAppDialog {
             .set_subtitle(&convert_storage(app.write_total() as f64, false));
 
         imp.gpu_usage
-            .set_subtitle(&format!("{:.1} %", app.gpu_usage() * 100.0));
+            .set_subtitle(&format_percentage(app.gpu_usage() * 100.0));
 
         imp.vram_usage
             .set_subtitle(&convert_storage(app.gpu_mem_usage() as f64, false));
 
         imp.encoder_usage
-            .set_subtitle(&format!("{:.1} %", app.enc_usage() * 100.0));
+            .set_subtitle(&format_percentage(app.enc_usage() * 100.0));
 
         imp.decoder_usage
-            .set_subtitle(&format!("{:.1} %", app.dec_usage() * 100.0));
+            .set_subtitle(&format_percentage(app.dec_usage() * 100.0));
 
         imp.processes_amount
             .set_subtitle(&app.running_processes().to_string());
+
+        imp.storage_usage
+            .set_subtitle(&if app.storage_usage() == 0 {
+                i18n("N/A")
+            } else {
+                convert_storage(app.storage_usage() as f64, false)
+            });
     }
 }
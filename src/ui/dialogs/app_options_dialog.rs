@@ -0,0 +1,324 @@
+use crate::{
+    config::PROFILE,
+    i18n::{i18n, i18n_f, ni18n_f},
+    ui::{
+        pages::applications::application_entry::ApplicationEntry,
+        window::{Action, MainWindow},
+    },
+    utils::{settings::SETTINGS, units::format_percentage, NUM_CPUS},
+};
+use adw::{prelude::*, subclass::prelude::*, ResponseAppearance, ToastOverlay};
+use async_channel::Sender;
+use gtk::glib::{self, clone, MainContext};
+use log::trace;
+use process_data::Niceness;
+
+mod imp {
+
+    use std::cell::{Cell, RefCell};
+
+    use super::*;
+
+    use gtk::CompositeTemplate;
+
+    #[derive(Debug, CompositeTemplate, Default)]
+    #[template(resource = "/net/nokyan/Resources/ui/dialogs/app_options_dialog.ui")]
+    pub struct ResAppOptionsDialog {
+        #[template_child]
+        pub name: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub apply_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub priority_expander_row: TemplateChild<adw::ExpanderRow>,
+        #[template_child]
+        pub nice_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub priority_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub cpu_threshold_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub cpu_flow_box: TemplateChild<gtk::FlowBox>,
+        #[template_child]
+        pub select_all_button: TemplateChild<gtk::Button>,
+
+        pub cpu_buttons: RefCell<Vec<gtk::ToggleButton>>,
+
+        pub id: RefCell<String>,
+
+        pub display_name: RefCell<String>,
+
+        pub running_processes: Cell<u32>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ResAppOptionsDialog {
+        const NAME: &'static str = "ResAppOptionsDialog";
+        type Type = super::ResAppOptionsDialog;
+        type ParentType = adw::Dialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            Self::bind_template(klass);
+        }
+
+        // You must call `Widget`'s `init_template()` within `instance_init()`.
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for ResAppOptionsDialog {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+
+            // Devel Profile
+            if PROFILE == "Devel" {
+                obj.add_css_class("devel");
+            }
+        }
+    }
+
+    impl WidgetImpl for ResAppOptionsDialog {}
+    impl WindowImpl for ResAppOptionsDialog {}
+    impl AdwDialogImpl for ResAppOptionsDialog {}
+}
+
+glib::wrapper! {
+    pub struct ResAppOptionsDialog(ObjectSubclass<imp::ResAppOptionsDialog>)
+        @extends gtk::Widget, adw::Dialog;
+}
+
+impl Default for ResAppOptionsDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ResAppOptionsDialog {
+    pub fn new() -> Self {
+        trace!("Creating ResAppOptionsDialog GObject…");
+        glib::Object::new::<Self>()
+    }
+
+    pub fn init(
+        &self,
+        app: &ApplicationEntry,
+        sender: Sender<Action>,
+        toast_overlay: &ToastOverlay,
+    ) {
+        self.setup_widgets(app);
+        self.setup_signals(sender, toast_overlay);
+    }
+
+    /// Returns the niceness all processes of the app should get or `None` if they should keep their current ones
+    fn get_selected_niceness(&self) -> Option<Niceness> {
+        let imp = self.imp();
+
+        if !imp.priority_expander_row.enables_expansion() {
+            return None;
+        }
+
+        let niceness = if imp.priority_row.is_visible() {
+            match imp.priority_row.selected() {
+                0 => Niceness::try_from(-19).unwrap_or_default(),
+                1 => Niceness::try_from(-5).unwrap_or_default(),
+                3 => Niceness::try_from(5).unwrap_or_default(),
+                4 => Niceness::try_from(19).unwrap_or_default(),
+                _ => Niceness::default(),
+            }
+        } else {
+            Niceness::try_from(imp.nice_row.value() as i8).unwrap_or_default()
+        };
+
+        Some(niceness)
+    }
+
+    /// Returns the processor usage as a fraction of all CPUs from which a process gets the selected niceness
+    fn get_selected_cpu_threshold(&self) -> f32 {
+        let mut threshold = self.imp().cpu_threshold_row.value() as f32 / 100.0;
+        if !SETTINGS.normalize_cpu_usage() {
+            threshold /= *NUM_CPUS as f32;
+        }
+        threshold
+    }
+
+    pub fn setup_widgets(&self, app: &ApplicationEntry) {
+        trace!("Setting up ResAppOptionsDialog widgets…");
+
+        let imp = self.imp();
+
+        imp.name.set_label(&app.name());
+
+        if SETTINGS.detailed_priority() {
+            imp.priority_row.set_visible(false);
+        } else {
+            imp.nice_row.set_visible(false);
+        }
+
+        // without normalization, a process can use up to 100 % per CPU
+        if !SETTINGS.normalize_cpu_usage() {
+            imp.cpu_threshold_row
+                .adjustment()
+                .set_upper(100.0 * *NUM_CPUS as f64);
+        }
+
+        for (i, affinity) in app.affinity().iter().enumerate() {
+            let toggle_button = gtk::ToggleButton::builder()
+                .label(i18n_f("CPU {}", &[&(i + 1).to_string()]))
+                .active(*affinity)
+                .build();
+
+            toggle_button.connect_toggled(clone!(
+                #[weak(rename_to = this)]
+                self,
+                move |_| {
+                    let imp = this.imp();
+
+                    // if all cores are disabled, disable the apply button
+                    let setting = imp
+                        .cpu_buttons
+                        .borrow()
+                        .iter()
+                        .any(gtk::ToggleButton::is_active);
+                    imp.apply_button.set_sensitive(setting);
+                }
+            ));
+
+            imp.cpu_flow_box.append(&toggle_button);
+
+            imp.cpu_buttons.borrow_mut().push(toggle_button);
+        }
+
+        *imp.id.borrow_mut() = app.id().unwrap_or_default().to_string();
+        *imp.display_name.borrow_mut() = app.name().to_string();
+        imp.running_processes.set(app.running_processes());
+    }
+
+    /// Asks the user to confirm the adjustment since it affects every process of the app at once
+    fn confirm_adjustment(&self, sender: Sender<Action>, toast_overlay: &ToastOverlay) {
+        let imp = self.imp();
+
+        let running_processes = imp.running_processes.get();
+        let niceness = self.get_selected_niceness();
+        let cpu_threshold = self.get_selected_cpu_threshold();
+
+        let body = if niceness.is_some() && cpu_threshold > 0.0 {
+            ni18n_f(
+                "The processor affinity of the app's process will be changed, its priority only if it uses at least {} of the processor.",
+                "The processor affinity of all {} processes of the app will be changed, the priority only of those using at least {} of the processor.",
+                running_processes,
+                &[
+                    &running_processes.to_string(),
+                    &format_percentage(imp.cpu_threshold_row.value()),
+                ],
+            )
+        } else if niceness.is_some() {
+            ni18n_f(
+                "The priority and processor affinity of the app's process will be changed.",
+                "The priority and processor affinity of all {} processes of the app will be changed.",
+                running_processes,
+                &[&running_processes.to_string()],
+            )
+        } else {
+            ni18n_f(
+                "The processor affinity of the app's process will be changed.",
+                "The processor affinity of all {} processes of the app will be changed.",
+                running_processes,
+                &[&running_processes.to_string()],
+            )
+        };
+
+        let dialog = adw::AlertDialog::builder()
+            .heading(i18n_f("Adjust {}?", &[&imp.display_name.borrow()]))
+            .body(body)
+            .build();
+
+        dialog.add_response("yes", &i18n("Adjust"));
+        dialog.set_response_appearance("yes", ResponseAppearance::Suggested);
+
+        dialog.add_response("no", &i18n("Cancel"));
+        dialog.set_default_response(Some("no"));
+        dialog.set_close_response("no");
+
+        dialog.connect_response(
+            None,
+            clone!(
+                #[weak(rename_to = this)]
+                self,
+                #[weak]
+                toast_overlay,
+                move |_, response| {
+                    if response != "yes" {
+                        return;
+                    }
+
+                    let main_context = MainContext::default();
+                    main_context.spawn_local(clone!(
+                        #[weak]
+                        this,
+                        #[weak]
+                        toast_overlay,
+                        #[strong]
+                        sender,
+                        async move {
+                            let imp = this.imp();
+
+                            let affinity: Vec<_> = imp
+                                .cpu_buttons
+                                .borrow()
+                                .iter()
+                                .map(gtk::ToggleButton::is_active)
+                                .collect();
+
+                            let id = imp.id.borrow().clone();
+
+                            let _ = sender
+                                .send(Action::AdjustApp(
+                                    id,
+                                    niceness,
+                                    cpu_threshold,
+                                    affinity,
+                                    toast_overlay,
+                                ))
+                                .await;
+                        }
+                    ));
+                }
+            ),
+        );
+
+        dialog.present(Some(&MainWindow::default()));
+    }
+
+    pub fn setup_signals(&self, sender: Sender<Action>, toast_overlay: &ToastOverlay) {
+        trace!("Setting up ResAppOptionsDialog signals…");
+
+        let imp = self.imp();
+
+        imp.select_all_button.connect_clicked(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |_| {
+                let cpu_buttons = this.imp().cpu_buttons.borrow();
+
+                let setting = !cpu_buttons.iter().all(gtk::ToggleButton::is_active);
+
+                cpu_buttons
+                    .iter()
+                    .for_each(|toggle_button| toggle_button.set_active(setting));
+            }
+        ));
+
+        imp.apply_button.connect_clicked(clone!(
+            #[weak(rename_to = this)]
+            self,
+            #[weak]
+            toast_overlay,
+            #[strong]
+            sender,
+            move |_| {
+                this.confirm_adjustment(sender.clone(), &toast_overlay);
+            }
+        ));
+    }
+}
@@ -1,4 +1,6 @@
 pub mod app_dialog;
+pub mod app_options_dialog;
 pub mod process_dialog;
 pub mod process_options_dialog;
 pub mod settings_dialog;
+pub mod smart_dialog;
@@ -1,13 +1,24 @@
+use std::collections::{BTreeSet, HashMap};
+use std::time::Instant;
+
 use adw::{prelude::*, subclass::prelude::*};
-use gtk::glib::{self, GString};
+use gtk::glib::{self, clone, GString};
 use log::trace;
+use process_data::{
+    DrmFdinfo, EnvironmentVariable, OpenFile, ProcessLimits, SchedStat, SocketData, SocketProtocol,
+    SocketState, ThreadData, WaitChannel,
+};
 
 use crate::config::PROFILE;
-use crate::i18n::i18n;
+use crate::i18n::{i18n, i18n_f};
 use crate::ui::pages::processes::process_entry::ProcessEntry;
-use crate::utils::units::{convert_speed, convert_storage, format_time};
+use crate::utils::drive::Drive;
+use crate::utils::settings::SETTINGS;
+use crate::utils::units::{convert_speed, convert_storage, format_percentage, format_time};
+use crate::utils::{NUM_CPUS, TICK_RATE};
 
 mod imp {
+    use std::cell::{Cell, RefCell};
 
     use super::*;
 
@@ -23,6 +34,8 @@ mod imp {
         #[template_child]
         pub memory_usage: TemplateChild<adw::ActionRow>,
         #[template_child]
+        pub shared_memory_usage: TemplateChild<adw::ActionRow>,
+        #[template_child]
         pub swap_usage: TemplateChild<adw::ActionRow>,
         #[template_child]
         pub drive_read_speed: TemplateChild<adw::ActionRow>,
@@ -37,6 +50,8 @@ mod imp {
         #[template_child]
         pub vram_usage: TemplateChild<adw::ActionRow>,
         #[template_child]
+        pub shared_vram_usage: TemplateChild<adw::ActionRow>,
+        #[template_child]
         pub encoder_usage: TemplateChild<adw::ActionRow>,
         #[template_child]
         pub decoder_usage: TemplateChild<adw::ActionRow>,
@@ -47,6 +62,16 @@ mod imp {
         #[template_child]
         pub system_cpu_time: TemplateChild<adw::ActionRow>,
         #[template_child]
+        pub wchan: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub open_block_devices: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub average_timeslice: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub average_wait_time: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub responsiveness: TemplateChild<adw::ActionRow>,
+        #[template_child]
         pub pid: TemplateChild<adw::ActionRow>,
         #[template_child]
         pub running_since: TemplateChild<adw::ActionRow>,
@@ -58,6 +83,51 @@ mod imp {
         pub cgroup: TemplateChild<adw::ActionRow>,
         #[template_child]
         pub containerized: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub threads_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub copy_threads_button: TemplateChild<gtk::Button>,
+
+        pub thread_rows: RefCell<Vec<adw::ActionRow>>,
+        pub threads: RefCell<Vec<ThreadData>>,
+        /// CPU times (in clock ticks) of the threads at the last update, keyed by their TID
+        pub old_thread_cpu_times: RefCell<HashMap<libc::pid_t, u64>>,
+        pub last_threads_timestamp: Cell<Option<Instant>>,
+
+        #[template_child]
+        pub limits_group: TemplateChild<adw::PreferencesGroup>,
+
+        pub limit_rows: RefCell<Vec<adw::ActionRow>>,
+
+        #[template_child]
+        pub sockets_group: TemplateChild<adw::PreferencesGroup>,
+
+        pub socket_rows: RefCell<Vec<adw::ActionRow>>,
+        /// The sockets shown in `socket_rows`, in the same order, `None` before the first update
+        pub sockets: RefCell<Option<Vec<SocketData>>>,
+
+        #[template_child]
+        pub drm_fdinfos_group: TemplateChild<adw::PreferencesGroup>,
+
+        pub drm_fdinfo_rows: RefCell<Vec<adw::ActionRow>>,
+
+        #[template_child]
+        pub environment_search_entry: TemplateChild<gtk::SearchEntry>,
+        #[template_child]
+        pub environment_group: TemplateChild<adw::PreferencesGroup>,
+
+        pub environment_rows: RefCell<Vec<adw::ActionRow>>,
+        /// The variables shown in `environment_rows`, in the same order, `None` before the first update
+        pub environment: RefCell<Option<Vec<EnvironmentVariable>>>,
+
+        #[template_child]
+        pub open_files_search_entry: TemplateChild<gtk::SearchEntry>,
+        #[template_child]
+        pub open_files_group: TemplateChild<adw::PreferencesGroup>,
+
+        pub open_file_rows: RefCell<Vec<adw::ActionRow>>,
+        /// The files shown in `open_file_rows`, in the same order, `None` before the first update
+        pub open_files: RefCell<Option<Vec<OpenFile>>>,
     }
 
     #[glib::object_subclass]
@@ -112,6 +182,37 @@ impl ResProcessDialog {
 
     pub fn init<S: AsRef<str>>(&self, process: &ProcessEntry, user: S) {
         self.setup_widgets(process, user.as_ref());
+        self.setup_signals();
+    }
+
+    pub fn setup_signals(&self) {
+        trace!("Setting up ResProcessDialog signals…");
+
+        let imp = self.imp();
+
+        imp.copy_threads_button.connect_clicked(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |_| {
+                this.clipboard().set_text(&this.threads_as_folded_stacks());
+            }
+        ));
+
+        imp.environment_search_entry.connect_search_changed(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |_| {
+                this.filter_environment_rows();
+            }
+        ));
+
+        imp.open_files_search_entry.connect_search_changed(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |_| {
+                this.filter_open_file_rows();
+            }
+        ));
     }
 
     pub fn setup_widgets(&self, process: &ProcessEntry, user: &str) {
@@ -157,11 +258,16 @@ impl ResProcessDialog {
         let imp = self.imp();
 
         imp.cpu_usage
-            .set_subtitle(&format!("{:.1} %", process.cpu_usage() * 100.0));
+            .set_subtitle(&format_percentage(process.cpu_usage() * 100.0));
 
         imp.memory_usage
             .set_subtitle(&convert_storage(process.memory_usage() as f64, false));
 
+        imp.shared_memory_usage.set_subtitle(&convert_storage(
+            process.shared_memory_usage() as f64,
+            false,
+        ));
+
         imp.swap_usage
             .set_subtitle(&convert_storage(process.swap_usage() as f64, false));
 
@@ -194,16 +300,21 @@ impl ResProcessDialog {
         }
 
         imp.gpu_usage
-            .set_subtitle(&format!("{:.1} %", process.gpu_usage() * 100.0));
+            .set_subtitle(&format_percentage(process.gpu_usage() * 100.0));
 
         imp.vram_usage
             .set_subtitle(&convert_storage(process.gpu_mem_usage() as f64, false));
 
+        imp.shared_vram_usage.set_subtitle(&convert_storage(
+            process.gpu_mem_system_usage() as f64,
+            false,
+        ));
+
         imp.encoder_usage
-            .set_subtitle(&format!("{:.1} %", process.enc_usage() * 100.0));
+            .set_subtitle(&format_percentage(process.enc_usage() * 100.0));
 
         imp.decoder_usage
-            .set_subtitle(&format!("{:.1} %", process.dec_usage() * 100.0));
+            .set_subtitle(&format_percentage(process.dec_usage() * 100.0));
 
         imp.total_cpu_time
             .set_subtitle(&format_time(process.total_cpu_time()));
@@ -214,4 +325,478 @@ impl ResProcessDialog {
         imp.system_cpu_time
             .set_subtitle(&format_time(process.system_cpu_time()));
     }
+
+    /// Formats scheduler times, which are usually well below a second
+    fn format_scheduling_time(nanoseconds: f64) -> String {
+        if nanoseconds < 1_000.0 {
+            i18n_f("{} ns", &[&format!("{nanoseconds:.0}")])
+        } else if nanoseconds < 1_000_000.0 {
+            i18n_f("{} µs", &[&format!("{:.2}", nanoseconds / 1_000.0)])
+        } else {
+            i18n_f("{} ms", &[&format!("{:.2}", nanoseconds / 1_000_000.0)])
+        }
+    }
+
+    pub fn update_threads(&self, threads: &[ThreadData]) {
+        trace!("Refreshing ResProcessDialog threads…");
+
+        let imp = self.imp();
+
+        let now = Instant::now();
+        let time_passed = imp
+            .last_threads_timestamp
+            .get()
+            .map(|timestamp| now.duration_since(timestamp).as_secs_f64());
+
+        let mut old_thread_cpu_times = imp.old_thread_cpu_times.borrow_mut();
+        let mut thread_rows = imp.thread_rows.borrow_mut();
+
+        for row in thread_rows.drain(..) {
+            imp.threads_group.remove(&row);
+        }
+
+        for thread in threads {
+            let cpu_time = thread.user_cpu_time.saturating_add(thread.system_cpu_time);
+
+            // threads that have just been spawned don't have a usage yet
+            let usage = time_passed.zip(old_thread_cpu_times.get(&thread.tid)).map(
+                |(time_passed, old_cpu_time)| {
+                    let mut usage = cpu_time.saturating_sub(*old_cpu_time) as f64
+                        / (time_passed * *TICK_RATE as f64);
+
+                    if SETTINGS.normalize_cpu_usage() {
+                        usage /= *NUM_CPUS as f64;
+                    }
+
+                    usage
+                },
+            );
+
+            let usage_string =
+                usage.map_or_else(|| i18n("N/A"), |usage| format_percentage(usage * 100.0));
+
+            let row = adw::ActionRow::builder()
+                .title(glib::markup_escape_text(&format!(
+                    "{} ({})",
+                    thread.name, thread.tid
+                )))
+                .subtitle(i18n_f(
+                    "State: {} · Processor: {} · CPU Time: {}",
+                    &[
+                        &thread.state.to_string(),
+                        &usage_string,
+                        &format_time(cpu_time as f64 / *TICK_RATE as f64),
+                    ],
+                ))
+                .css_classes(["property"])
+                .subtitle_selectable(true)
+                .build();
+
+            imp.threads_group.add(&row);
+            thread_rows.push(row);
+        }
+
+        *old_thread_cpu_times = threads
+            .iter()
+            .map(|thread| {
+                (
+                    thread.tid,
+                    thread.user_cpu_time.saturating_add(thread.system_cpu_time),
+                )
+            })
+            .collect();
+
+        imp.last_threads_timestamp.set(Some(now));
+
+        imp.threads_group.set_visible(!thread_rows.is_empty());
+
+        *imp.threads.borrow_mut() = threads.to_vec();
+    }
+
+    pub fn update_limits(&self, limits: &ProcessLimits) {
+        trace!("Refreshing ResProcessDialog limits…");
+
+        let imp = self.imp();
+
+        let mut limit_rows = imp.limit_rows.borrow_mut();
+
+        for row in limit_rows.drain(..) {
+            imp.limits_group.remove(&row);
+        }
+
+        for limit in &limits.limits {
+            let unit = limit.unit.as_deref();
+
+            let mut subtitle = i18n_f(
+                "Soft Limit: {} · Hard Limit: {}",
+                &[
+                    &Self::format_limit(limit.soft_limit, unit),
+                    &Self::format_limit(limit.hard_limit, unit),
+                ],
+            );
+
+            // this is what's usually hit when a process runs into "too many open files"
+            if limit.name == "Max open files" {
+                if let Some(open_fds) = limits.open_fds {
+                    subtitle.push_str(&i18n_f(" · Currently Open: {}", &[&open_fds.to_string()]));
+                }
+            }
+
+            let row = adw::ActionRow::builder()
+                .title(glib::markup_escape_text(&limit.name))
+                .subtitle(subtitle)
+                .css_classes(["property"])
+                .subtitle_selectable(true)
+                .build();
+
+            imp.limits_group.add(&row);
+            limit_rows.push(row);
+        }
+
+        imp.limits_group.set_visible(!limit_rows.is_empty());
+    }
+
+    fn format_limit(limit: Option<u64>, unit: Option<&str>) -> String {
+        match (limit, unit) {
+            (None, _) => i18n("Unlimited"),
+            (Some(limit), Some("bytes")) => convert_storage(limit as f64, true),
+            (Some(limit), Some(unit)) => format!("{limit} {unit}"),
+            (Some(limit), None) => limit.to_string(),
+        }
+    }
+
+    pub fn update_sockets(&self, sockets: &[SocketData]) {
+        trace!("Refreshing ResProcessDialog sockets…");
+
+        let imp = self.imp();
+
+        if imp.sockets.borrow().as_deref() == Some(sockets) {
+            return;
+        }
+
+        let mut socket_rows = imp.socket_rows.borrow_mut();
+
+        for row in socket_rows.drain(..) {
+            imp.sockets_group.remove(&row);
+        }
+
+        for socket in sockets {
+            let state = Self::format_socket_state(socket.protocol, socket.state);
+
+            let subtitle = if socket.protocol == SocketProtocol::Unix {
+                i18n_f(
+                    "Path: {} · State: {}",
+                    &[socket.path.as_deref().unwrap_or(&i18n("Unnamed")), &state],
+                )
+            } else {
+                let format_address = |address: Option<std::net::SocketAddr>| {
+                    address.map_or_else(|| i18n("N/A"), |address| address.to_string())
+                };
+
+                i18n_f(
+                    "Local: {} · Remote: {} · State: {}",
+                    &[
+                        &format_address(socket.local_address),
+                        &format_address(socket.remote_address),
+                        &state,
+                    ],
+                )
+            };
+
+            let protocol = match socket.protocol {
+                SocketProtocol::Tcp => i18n("TCP"),
+                SocketProtocol::Udp => i18n("UDP"),
+                SocketProtocol::Unix => i18n("Unix Domain Socket"),
+            };
+
+            let row = adw::ActionRow::builder()
+                .title(i18n_f(
+                    "File Descriptor {} · {}",
+                    &[&socket.fd.to_string(), &protocol],
+                ))
+                .subtitle(glib::markup_escape_text(&subtitle))
+                .css_classes(["property"])
+                .subtitle_selectable(true)
+                .build();
+
+            imp.sockets_group.add(&row);
+            socket_rows.push(row);
+        }
+
+        imp.sockets_group.set_visible(!socket_rows.is_empty());
+
+        *imp.sockets.borrow_mut() = Some(sockets.to_vec());
+    }
+
+    fn format_socket_state(protocol: SocketProtocol, state: SocketState) -> String {
+        match (protocol, state) {
+            // UDP and Unix domain sockets are connectionless unless they've been connected to a specific peer
+            (SocketProtocol::Udp | SocketProtocol::Unix, SocketState::Close) => i18n("Unconnected"),
+            (SocketProtocol::Udp | SocketProtocol::Unix, SocketState::Established) => {
+                i18n("Connected")
+            }
+            (SocketProtocol::Unix, SocketState::SynSent) => i18n("Connecting"),
+            (SocketProtocol::Unix, SocketState::Closing) => i18n("Disconnecting"),
+            (_, SocketState::Established) => i18n("Established"),
+            (_, SocketState::SynSent) => i18n("SYN Sent"),
+            (_, SocketState::SynReceived) => i18n("SYN Received"),
+            (_, SocketState::FinWait1) => i18n("FIN Wait 1"),
+            (_, SocketState::FinWait2) => i18n("FIN Wait 2"),
+            (_, SocketState::TimeWait) => i18n("Time Wait"),
+            (_, SocketState::Close) => i18n("Closed"),
+            (_, SocketState::CloseWait) => i18n("Close Wait"),
+            (_, SocketState::LastAck) => i18n("Last ACK"),
+            (_, SocketState::Listen) => i18n("Listening"),
+            (_, SocketState::Closing) => i18n("Closing"),
+            (_, SocketState::Unknown) => i18n("Unknown"),
+        }
+    }
+
+    pub fn update_drm_fdinfos(&self, fdinfos: &[DrmFdinfo]) {
+        trace!("Refreshing ResProcessDialog DRM fdinfos…");
+
+        let imp = self.imp();
+
+        let mut drm_fdinfo_rows = imp.drm_fdinfo_rows.borrow_mut();
+
+        for row in drm_fdinfo_rows.drain(..) {
+            imp.drm_fdinfos_group.remove(&row);
+        }
+
+        for fdinfo in fdinfos {
+            let fields = fdinfo
+                .fields
+                .iter()
+                .map(|(key, value)| format!("{key}: {value}"))
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            let row = adw::ActionRow::builder()
+                .title(i18n_f(
+                    "File Descriptor {} · {}",
+                    &[&fdinfo.fd.to_string(), &fdinfo.gpu_identifier.to_string()],
+                ))
+                .subtitle(glib::markup_escape_text(&fields))
+                .css_classes(["property"])
+                .subtitle_selectable(true)
+                .build();
+
+            imp.drm_fdinfos_group.add(&row);
+            drm_fdinfo_rows.push(row);
+        }
+
+        imp.drm_fdinfos_group
+            .set_visible(!drm_fdinfo_rows.is_empty());
+    }
+
+    pub fn update_environment(&self, environment: &[EnvironmentVariable]) {
+        trace!("Refreshing ResProcessDialog environment…");
+
+        let imp = self.imp();
+
+        // the environment of a process rarely changes, so don't rebuild the rows (and lose the selection) needlessly
+        if imp.environment.borrow().as_deref() == Some(environment) {
+            return;
+        }
+
+        let mut environment_rows = imp.environment_rows.borrow_mut();
+
+        for row in environment_rows.drain(..) {
+            imp.environment_group.remove(&row);
+        }
+
+        for variable in environment {
+            let row = adw::ActionRow::builder()
+                .title(glib::markup_escape_text(&variable.name))
+                .subtitle(glib::markup_escape_text(&variable.value))
+                .css_classes(["property"])
+                .subtitle_selectable(true)
+                .build();
+
+            imp.environment_group.add(&row);
+            environment_rows.push(row);
+        }
+
+        // processes of other users don't let us read their environment
+        imp.environment_group.set_description(
+            environment_rows
+                .is_empty()
+                .then(|| i18n("The environment of this process is empty or not accessible"))
+                .as_deref(),
+        );
+
+        drop(environment_rows);
+
+        *imp.environment.borrow_mut() = Some(environment.to_vec());
+
+        self.filter_environment_rows();
+    }
+
+    /// Only shows the environment variables whose name or value contains the search text
+    fn filter_environment_rows(&self) {
+        let imp = self.imp();
+
+        let search_string = imp.environment_search_entry.text().to_lowercase();
+
+        for (row, variable) in imp
+            .environment_rows
+            .borrow()
+            .iter()
+            .zip(imp.environment.borrow().iter().flatten())
+        {
+            row.set_visible(
+                variable.name.to_lowercase().contains(&search_string)
+                    || variable.value.to_lowercase().contains(&search_string),
+            );
+        }
+    }
+
+    pub fn update_wait_channel(&self, wait_channel: &WaitChannel) {
+        trace!("Refreshing ResProcessDialog wait channel…");
+
+        let subtitle = match wait_channel {
+            WaitChannel::Sleeping(wchan) => wchan.clone(),
+            WaitChannel::Running => i18n("Running"),
+            WaitChannel::Unknown => i18n("N/A"),
+        };
+
+        self.imp().wchan.set_subtitle(&subtitle);
+    }
+
+    pub fn update_schedstat(&self, schedstat: Option<SchedStat>) {
+        trace!("Refreshing ResProcessDialog scheduler statistics…");
+
+        let imp = self.imp();
+
+        if let Some(schedstat) = schedstat {
+            imp.average_timeslice
+                .set_subtitle(&Self::format_scheduling_time(schedstat.average_run_time()));
+            imp.average_wait_time
+                .set_subtitle(&Self::format_scheduling_time(schedstat.average_wait_time()));
+
+            let wait_ratio = schedstat.wait_ratio();
+            let responsiveness = if wait_ratio < 0.1 {
+                i18n("Good")
+            } else if wait_ratio < 1.0 {
+                i18n("Fair")
+            } else {
+                i18n("Poor")
+            };
+            imp.responsiveness.set_subtitle(&i18n_f(
+                "{} (Wait-to-Run Ratio: {})",
+                &[&responsiveness, &format!("{wait_ratio:.2}")],
+            ));
+        } else {
+            // the kernel doesn't collect scheduler statistics
+            imp.average_timeslice.set_subtitle(&i18n("N/A"));
+            imp.average_wait_time.set_subtitle(&i18n("N/A"));
+            imp.responsiveness.set_subtitle(&i18n("N/A"));
+        }
+    }
+
+    pub fn update_open_files(&self, open_files: &[OpenFile]) {
+        trace!("Refreshing ResProcessDialog open files…");
+
+        let imp = self.imp();
+
+        if imp.open_files.borrow().as_deref() == Some(open_files) {
+            return;
+        }
+
+        let mut open_file_rows = imp.open_file_rows.borrow_mut();
+
+        for row in open_file_rows.drain(..) {
+            imp.open_files_group.remove(&row);
+        }
+
+        for open_file in open_files {
+            let subtitle = if open_file.is_deleted() {
+                i18n_f(
+                    "{} (Deleted)",
+                    &[open_file.target.trim_end_matches(" (deleted)")],
+                )
+            } else {
+                open_file.target.clone()
+            };
+
+            let row = adw::ActionRow::builder()
+                .title(i18n_f("File Descriptor {}", &[&open_file.fd.to_string()]))
+                .subtitle(glib::markup_escape_text(&subtitle))
+                .css_classes(["property"])
+                .subtitle_selectable(true)
+                .build();
+
+            imp.open_files_group.add(&row);
+            open_file_rows.push(row);
+        }
+
+        imp.open_files_group.set_description(
+            open_file_rows
+                .is_empty()
+                .then(|| i18n("This process has no open files or they're not accessible"))
+                .as_deref(),
+        );
+
+        drop(open_file_rows);
+
+        let open_block_devices = open_files
+            .iter()
+            .filter_map(|open_file| open_file.block_device)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .filter_map(Drive::block_device_name_from_dev)
+            .collect::<Vec<_>>();
+
+        if open_block_devices.is_empty() {
+            imp.open_block_devices.set_subtitle(&i18n("None"));
+        } else {
+            imp.open_block_devices
+                .set_subtitle(&open_block_devices.join(", "));
+        }
+
+        *imp.open_files.borrow_mut() = Some(open_files.to_vec());
+
+        self.filter_open_file_rows();
+    }
+
+    /// Only shows the open files whose path (or description) or file descriptor contains the search text
+    fn filter_open_file_rows(&self) {
+        let imp = self.imp();
+
+        let search_string = imp.open_files_search_entry.text().to_lowercase();
+
+        for (row, open_file) in imp
+            .open_file_rows
+            .borrow()
+            .iter()
+            .zip(imp.open_files.borrow().iter().flatten())
+        {
+            row.set_visible(
+                open_file.target.to_lowercase().contains(&search_string)
+                    || open_file.fd.to_string() == search_string,
+            );
+        }
+    }
+
+    /// Formats the CPU time of every thread in the folded stack format that's understood by flame graph tools, i.e.
+    /// "process;thread ticks" per line
+    fn threads_as_folded_stacks(&self) -> String {
+        let imp = self.imp();
+
+        // semicolons separate the frames, so they can't be part of the names
+        let process_name = imp.name.label().replace(';', "_");
+
+        imp.threads
+            .borrow()
+            .iter()
+            .map(|thread| {
+                format!(
+                    "{process_name};{} ({}) {}\n",
+                    thread.name.replace(';', "_"),
+                    thread.tid,
+                    thread.user_cpu_time.saturating_add(thread.system_cpu_time)
+                )
+            })
+            .collect()
+    }
 }
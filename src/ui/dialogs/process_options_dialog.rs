@@ -1,11 +1,11 @@
 use crate::{
     config::PROFILE,
-    i18n::i18n_f,
+    i18n::{i18n_f, ni18n_f},
     ui::{
         pages::{processes::process_entry::ProcessEntry, NICE_TO_LABEL},
         window::Action,
     },
-    utils::settings::SETTINGS,
+    utils::{app::App, process::CgroupLimits, settings::SETTINGS, NUM_CPUS},
 };
 use adw::{prelude::*, subclass::prelude::*, ToastOverlay};
 use async_channel::Sender;
@@ -13,6 +13,8 @@ use gtk::glib::{self, clone, MainContext};
 use log::trace;
 use process_data::Niceness;
 
+const MIB: u64 = 1024 * 1024;
+
 mod imp {
 
     use std::cell::{Cell, RefCell};
@@ -36,10 +38,26 @@ mod imp {
         pub affinity_row: TemplateChild<adw::ExpanderRow>,
         #[template_child]
         pub select_all_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub limits_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub memory_limit_row: TemplateChild<adw::ExpanderRow>,
+        #[template_child]
+        pub memory_limit_spin_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub cpu_limit_row: TemplateChild<adw::ExpanderRow>,
+        #[template_child]
+        pub cpu_limit_spin_row: TemplateChild<adw::SpinRow>,
 
         pub cpu_rows: RefCell<Vec<adw::SwitchRow>>,
 
-        pub pid: Cell<libc::pid_t>,
+        pub pids: RefCell<Vec<libc::pid_t>>,
+
+        /// The niceness as initially shown, which is only the first process' one when adjusting multiple processes
+        pub initial_niceness: Cell<Niceness>,
+
+        /// The cgroup limits as initially shown, `None` if they can't be changed
+        pub initial_limits: Cell<Option<CgroupLimits>>,
     }
 
     #[glib::object_subclass]
@@ -92,14 +110,15 @@ impl ResProcessOptionsDialog {
         glib::Object::new::<Self>()
     }
 
+    /// Sets the dialog up for adjusting `processes`, which must not be empty
     pub fn init(
         &self,
-        process: &ProcessEntry,
+        processes: &[ProcessEntry],
         sender: Sender<Action>,
         toast_overlay: &ToastOverlay,
     ) {
-        self.setup_widgets(process);
-        self.setup_signals(process, sender, toast_overlay);
+        self.setup_widgets(processes);
+        self.setup_signals(processes, sender, toast_overlay);
     }
 
     fn get_current_niceness(&self) -> Niceness {
@@ -119,18 +138,47 @@ impl ResProcessOptionsDialog {
         }
     }
 
-    pub fn setup_widgets(&self, process: &ProcessEntry) {
+    fn get_selected_limits(&self) -> CgroupLimits {
+        let imp = self.imp();
+
+        CgroupLimits {
+            memory_max: imp
+                .memory_limit_row
+                .enables_expansion()
+                .then(|| imp.memory_limit_spin_row.value() as u64 * MIB),
+            cpu_max: imp
+                .cpu_limit_row
+                .enables_expansion()
+                .then(|| imp.cpu_limit_spin_row.value() as u32),
+        }
+    }
+
+    pub fn setup_widgets(&self, processes: &[ProcessEntry]) {
         trace!("Setting up ResProcessOptionsDialog widgets…");
 
         let imp = self.imp();
 
-        imp.name.set_label(&process.name());
+        let Some(first_process) = processes.first() else {
+            return;
+        };
+
+        if processes.len() == 1 {
+            imp.name.set_label(&first_process.name());
+        } else {
+            imp.name.set_label(&ni18n_f(
+                "{} Process",
+                "{} Processes",
+                processes.len() as u32,
+                &[&processes.len().to_string()],
+            ));
+        }
 
-        imp.nice_row.set_value(process.niceness() as f64);
+        // with multiple processes, their niceness levels may differ, so just start off with the first one's
+        imp.nice_row.set_value(first_process.niceness() as f64);
 
         imp.priority_row.set_selected(
             NICE_TO_LABEL
-                .get(&Niceness::try_from(process.niceness()).unwrap_or_default())
+                .get(&Niceness::try_from(first_process.niceness()).unwrap_or_default())
                 .map_or(2, |(_, i)| *i),
         );
 
@@ -140,7 +188,17 @@ impl ResProcessOptionsDialog {
             imp.nice_row.set_visible(false);
         }
 
-        for (i, affinity) in process.affinity().iter().enumerate() {
+        imp.initial_niceness.set(self.get_current_niceness());
+
+        let affinities = processes
+            .iter()
+            .map(ProcessEntry::affinity)
+            .collect::<Vec<_>>();
+
+        for (i, affinity) in App::combined_affinity(affinities.iter().map(Vec::as_slice))
+            .iter()
+            .enumerate()
+        {
             let switch_row = adw::SwitchRow::builder()
                 .title(i18n_f("CPU {}", &[&(i + 1).to_string()]))
                 .active(*affinity)
@@ -163,12 +221,47 @@ impl ResProcessOptionsDialog {
             imp.cpu_rows.borrow_mut().push(switch_row);
         }
 
-        imp.pid.set(process.pid());
+        *imp.pids.borrow_mut() = processes.iter().map(ProcessEntry::pid).collect();
+
+        // the processes might belong to different cgroups, so limits are only offered for single processes
+        if let [process] = processes {
+            if let Some(limits) = CgroupLimits::of_pid(process.pid()) {
+                self.setup_limits(process, limits);
+            }
+        }
+    }
+
+    fn setup_limits(&self, process: &ProcessEntry, limits: CgroupLimits) {
+        let imp = self.imp();
+
+        imp.limits_group.set_visible(true);
+
+        imp.cpu_limit_spin_row
+            .adjustment()
+            .set_upper((*NUM_CPUS * 100) as f64);
+
+        if let Some(memory_max) = limits.memory_max {
+            imp.memory_limit_row.set_enable_expansion(true);
+            imp.memory_limit_spin_row
+                .set_value((memory_max / MIB) as f64);
+        } else {
+            // suggest twice of what the process currently uses
+            imp.memory_limit_spin_row
+                .set_value((process.memory_usage() * 2 / MIB) as f64);
+        }
+
+        if let Some(cpu_max) = limits.cpu_max {
+            imp.cpu_limit_row.set_enable_expansion(true);
+            imp.cpu_limit_spin_row.set_value(cpu_max.into());
+        }
+
+        // compare against what's shown so that rounding to whole MiB doesn't count as a change
+        imp.initial_limits.set(Some(self.get_selected_limits()));
     }
 
     pub fn setup_signals(
         &self,
-        process: &ProcessEntry,
+        processes: &[ProcessEntry],
         sender: Sender<Action>,
         toast_overlay: &ToastOverlay,
     ) {
@@ -190,12 +283,16 @@ impl ResProcessOptionsDialog {
             }
         ));
 
+        // a single process is adjusted by its name so that the toast can mention it
+        let display_name = match processes {
+            [process] => Some(process.name().to_string()),
+            _ => None,
+        };
+
         imp.apply_button.connect_clicked(clone!(
             #[weak(rename_to = this)]
             self,
             #[weak]
-            process,
-            #[weak]
             toast_overlay,
             #[strong]
             sender,
@@ -206,6 +303,8 @@ impl ResProcessOptionsDialog {
                     this,
                     #[strong]
                     sender,
+                    #[strong]
+                    display_name,
                     async move {
                         let imp = this.imp();
 
@@ -216,15 +315,34 @@ impl ResProcessOptionsDialog {
                             .map(adw::SwitchRow::is_active)
                             .collect();
 
-                        let _ = sender
-                            .send(Action::AdjustProcess(
-                                process.pid(),
+                        let pids = imp.pids.borrow().clone();
+
+                        // only touch the cgroup if necessary since it might require elevated privileges
+                        let limits = imp.initial_limits.get().and_then(|initial_limits| {
+                            let selected_limits = this.get_selected_limits();
+                            (selected_limits != initial_limits).then_some(selected_limits)
+                        });
+
+                        let action = match (pids.as_slice(), display_name) {
+                            ([pid], Some(display_name)) => Action::AdjustProcess(
+                                *pid,
                                 this.get_current_niceness(),
                                 affinity,
-                                process.name().to_string(),
+                                limits,
+                                display_name,
                                 toast_overlay.clone(),
-                            ))
-                            .await;
+                            ),
+                            // the other processes keep their niceness unless the user actually changed it
+                            _ => Action::AdjustProcesses(
+                                pids,
+                                Some(this.get_current_niceness())
+                                    .filter(|niceness| *niceness != imp.initial_niceness.get()),
+                                affinity,
+                                toast_overlay.clone(),
+                            ),
+                        };
+
+                        let _ = sender.send(action).await;
                     }
                 ));
             }
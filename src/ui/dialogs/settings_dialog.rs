@@ -1,13 +1,29 @@
-use adw::{prelude::*, subclass::prelude::*};
-use gtk::glib;
-use log::trace;
+use adw::{prelude::*, subclass::prelude::*, Toast};
+use anyhow::{Context, Result};
+use gtk::{
+    gio,
+    glib::{self, clone, MainContext},
+};
+use log::{trace, warn};
 
 use crate::{
     config::PROFILE,
-    utils::settings::{Base, RefreshSpeed, SidebarMeterType, TemperatureUnit, SETTINGS},
+    gui::ARGS,
+    i18n::{i18n, i18n_f, ni18n_f},
+    ui::window::MainWindow,
+    utils::{
+        custom_gauge::CustomGauge,
+        drive::{Drive, DriveType},
+        network::NetworkInterface,
+        settings::{
+            Base, GpuTemperatureSensor, ProcessNameSource, RefreshSpeed, SidebarMeterType,
+            TemperatureUnit, SETTINGS,
+        },
+    },
 };
 
 mod imp {
+    use std::cell::RefCell;
 
     use super::*;
 
@@ -22,16 +38,68 @@ mod imp {
         pub network_bits_row: TemplateChild<adw::SwitchRow>,
         #[template_child]
         pub temperature_combo_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub decimal_precision_row: TemplateChild<adw::SpinRow>,
 
         #[template_child]
         pub refresh_speed_combo_row: TemplateChild<adw::ComboRow>,
         #[template_child]
+        pub processes_refresh_speed_combo_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub drives_refresh_speed_combo_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub network_refresh_speed_combo_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub sensors_refresh_speed_combo_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub battery_refresh_speed_combo_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
         pub show_graph_grids_row: TemplateChild<adw::SwitchRow>,
         #[template_child]
+        pub record_history_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
         pub graph_data_points_row: TemplateChild<adw::SpinRow>,
         #[template_child]
+        pub usage_warning_threshold_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub usage_critical_threshold_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub cpu_temperature_warning_threshold_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub cpu_temperature_critical_threshold_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub gpu_temperature_warning_threshold_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub gpu_temperature_critical_threshold_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub npu_temperature_warning_threshold_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub npu_temperature_critical_threshold_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub drive_temperature_warning_threshold_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub drive_temperature_critical_threshold_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub alert_notifications_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub alert_duration_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub alert_processor_threshold_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub alert_memory_threshold_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub alert_drive_temperature_threshold_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub alert_battery_threshold_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
         pub show_search_on_start_row: TemplateChild<adw::SwitchRow>,
         #[template_child]
+        pub always_on_top_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub start_hidden_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub show_tray_indicator_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
         pub sidebar_details_row: TemplateChild<adw::SwitchRow>,
         #[template_child]
         pub sidebar_description_row: TemplateChild<adw::SwitchRow>,
@@ -39,7 +107,38 @@ mod imp {
         pub sidebar_meter_type_row: TemplateChild<adw::ComboRow>,
         #[template_child]
         pub normalize_cpu_usage_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub confirm_end_action_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub merge_media_columns_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub highlight_changes_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub highlight_changes_threshold_row: TemplateChild<adw::SpinRow>,
+
+        #[template_child]
+        pub show_cpu_page_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub show_memory_page_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub show_gpu_pages_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub show_npu_pages_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub show_drive_pages_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub show_network_pages_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub show_battery_pages_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub show_applications_page_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub show_processes_page_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub show_os_page_row: TemplateChild<adw::SwitchRow>,
 
+        #[template_child]
+        pub apps_group_by_category_row: TemplateChild<adw::SwitchRow>,
         #[template_child]
         pub apps_show_memory_row: TemplateChild<adw::SwitchRow>,
         #[template_child]
@@ -55,6 +154,8 @@ mod imp {
         #[template_child]
         pub apps_show_gpu_row: TemplateChild<adw::SwitchRow>,
         #[template_child]
+        pub apps_show_usage_per_gpu_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
         pub apps_show_gpu_mem_row: TemplateChild<adw::SwitchRow>,
         #[template_child]
         pub apps_show_encoder_row: TemplateChild<adw::SwitchRow>,
@@ -62,10 +163,30 @@ mod imp {
         pub apps_show_decoder_row: TemplateChild<adw::SwitchRow>,
         #[template_child]
         pub apps_show_swap_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub apps_show_autostart_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub apps_show_rendering_gpu_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub apps_show_storage_row: TemplateChild<adw::SwitchRow>,
 
         #[template_child]
         pub processes_niceness: TemplateChild<adw::SwitchRow>,
         #[template_child]
+        pub processes_show_graphs_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub processes_name_source_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub processes_show_full_path_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub processes_show_tree_depth_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub processes_group_by_container_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub processes_show_drm_fdinfo_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub commandline_tooltip_length_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
         pub processes_show_id_row: TemplateChild<adw::SwitchRow>,
         #[template_child]
         pub processes_show_user_row: TemplateChild<adw::SwitchRow>,
@@ -84,6 +205,8 @@ mod imp {
         #[template_child]
         pub processes_show_gpu_row: TemplateChild<adw::SwitchRow>,
         #[template_child]
+        pub processes_show_usage_per_gpu_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
         pub processes_show_gpu_mem_row: TemplateChild<adw::SwitchRow>,
         #[template_child]
         pub processes_show_encoder_row: TemplateChild<adw::SwitchRow>,
@@ -99,11 +222,62 @@ mod imp {
         pub processes_show_priority_row: TemplateChild<adw::SwitchRow>,
         #[template_child]
         pub processes_show_swap_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub processes_show_shared_memory_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub processes_show_locked_memory_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub processes_show_pinned_memory_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub processes_show_cpu_throttling_row: TemplateChild<adw::SwitchRow>,
 
         #[template_child]
         pub show_virtual_drives_row: TemplateChild<adw::SwitchRow>,
         #[template_child]
+        pub hide_member_drives_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub show_loop_devices_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub drive_full_notification_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub drive_full_threshold_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub gpu_temperature_sensor_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub gpu_vram_warning_threshold_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
         pub show_virtual_network_interfaces_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub show_container_network_interfaces_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub show_network_total_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub network_billing_cycle_day_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub export_settings_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub import_settings_row: TemplateChild<adw::ActionRow>,
+
+        #[template_child]
+        pub drive_visibility_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub network_visibility_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub custom_gauges_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub custom_gauges_placeholder_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub add_custom_gauge_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub custom_gauge_label_row: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        pub custom_gauge_path_row: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        pub custom_gauge_unit_row: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        pub custom_gauge_scale_row: TemplateChild<adw::EntryRow>,
+
+        pub custom_gauge_rows: RefCell<Vec<adw::ActionRow>>,
     }
 
     #[glib::object_subclass]
@@ -175,13 +349,74 @@ impl ResSettingsDialog {
         imp.network_bits_row.set_active(SETTINGS.network_bits());
         imp.temperature_combo_row
             .set_selected((SETTINGS.temperature_unit() as u8) as u32);
+        imp.decimal_precision_row
+            .set_value(SETTINGS.decimal_precision() as f64);
 
         imp.refresh_speed_combo_row
             .set_selected((SETTINGS.refresh_speed() as u8) as u32);
+        // the first item means that processes are refreshed at the general refresh speed
+        imp.processes_refresh_speed_combo_row.set_selected(
+            SETTINGS
+                .processes_refresh_speed()
+                .map_or(0, |refresh_speed| (refresh_speed as u8) as u32 + 1),
+        );
+        imp.drives_refresh_speed_combo_row.set_selected(
+            SETTINGS
+                .drives_refresh_speed()
+                .map_or(0, |refresh_speed| (refresh_speed as u8) as u32 + 1),
+        );
+        imp.network_refresh_speed_combo_row.set_selected(
+            SETTINGS
+                .network_refresh_speed()
+                .map_or(0, |refresh_speed| (refresh_speed as u8) as u32 + 1),
+        );
+        imp.sensors_refresh_speed_combo_row.set_selected(
+            SETTINGS
+                .sensors_refresh_speed()
+                .map_or(0, |refresh_speed| (refresh_speed as u8) as u32 + 1),
+        );
+        imp.battery_refresh_speed_combo_row.set_selected(
+            SETTINGS
+                .battery_refresh_speed()
+                .map_or(0, |refresh_speed| (refresh_speed as u8) as u32 + 1),
+        );
         imp.show_graph_grids_row
             .set_active(SETTINGS.show_graph_grids());
+        imp.record_history_row.set_active(SETTINGS.record_history());
         imp.graph_data_points_row
             .set_value(SETTINGS.graph_data_points() as f64);
+        imp.usage_warning_threshold_row
+            .set_value(SETTINGS.usage_warning_threshold() as f64);
+        imp.usage_critical_threshold_row
+            .set_value(SETTINGS.usage_critical_threshold() as f64);
+        imp.cpu_temperature_warning_threshold_row
+            .set_value(SETTINGS.cpu_temperature_warning_threshold() as f64);
+        imp.cpu_temperature_critical_threshold_row
+            .set_value(SETTINGS.cpu_temperature_critical_threshold() as f64);
+        imp.gpu_temperature_warning_threshold_row
+            .set_value(SETTINGS.gpu_temperature_warning_threshold() as f64);
+        imp.gpu_temperature_critical_threshold_row
+            .set_value(SETTINGS.gpu_temperature_critical_threshold() as f64);
+        imp.npu_temperature_warning_threshold_row
+            .set_value(SETTINGS.npu_temperature_warning_threshold() as f64);
+        imp.npu_temperature_critical_threshold_row
+            .set_value(SETTINGS.npu_temperature_critical_threshold() as f64);
+        imp.drive_temperature_warning_threshold_row
+            .set_value(SETTINGS.drive_temperature_warning_threshold() as f64);
+        imp.drive_temperature_critical_threshold_row
+            .set_value(SETTINGS.drive_temperature_critical_threshold() as f64);
+        imp.alert_notifications_row
+            .set_active(SETTINGS.alert_notifications());
+        imp.alert_duration_row
+            .set_value(SETTINGS.alert_duration() as f64);
+        imp.alert_processor_threshold_row
+            .set_value(SETTINGS.alert_processor_threshold() as f64);
+        imp.alert_memory_threshold_row
+            .set_value(SETTINGS.alert_memory_threshold() as f64);
+        imp.alert_drive_temperature_threshold_row
+            .set_value(SETTINGS.alert_drive_temperature_threshold() as f64);
+        imp.alert_battery_threshold_row
+            .set_value(SETTINGS.alert_battery_threshold() as f64);
         imp.sidebar_details_row
             .set_active(SETTINGS.sidebar_details());
         imp.sidebar_description_row
@@ -190,9 +425,44 @@ impl ResSettingsDialog {
             .set_selected((SETTINGS.sidebar_meter_type() as u8) as u32);
         imp.show_search_on_start_row
             .set_active(SETTINGS.show_search_on_start());
+
+        imp.always_on_top_row
+            .set_visible(MainWindow::always_on_top_supported());
+        imp.always_on_top_row.set_active(SETTINGS.always_on_top());
+
+        imp.start_hidden_row.set_active(SETTINGS.start_hidden());
+        imp.show_tray_indicator_row
+            .set_active(SETTINGS.show_tray_indicator());
         imp.normalize_cpu_usage_row
             .set_active(SETTINGS.normalize_cpu_usage());
-
+        imp.confirm_end_action_row
+            .set_active(SETTINGS.confirm_end_action());
+        imp.merge_media_columns_row
+            .set_active(SETTINGS.merge_media_columns());
+        imp.highlight_changes_row
+            .set_active(SETTINGS.highlight_changes());
+        imp.highlight_changes_threshold_row
+            .set_value(SETTINGS.highlight_changes_threshold() as f64);
+
+        imp.show_cpu_page_row.set_active(SETTINGS.show_cpu_page());
+        imp.show_memory_page_row
+            .set_active(SETTINGS.show_memory_page());
+        imp.show_gpu_pages_row.set_active(SETTINGS.show_gpu_pages());
+        imp.show_npu_pages_row.set_active(SETTINGS.show_npu_pages());
+        imp.show_drive_pages_row
+            .set_active(SETTINGS.show_drive_pages());
+        imp.show_network_pages_row
+            .set_active(SETTINGS.show_network_pages());
+        imp.show_battery_pages_row
+            .set_active(SETTINGS.show_battery_pages());
+        imp.show_applications_page_row
+            .set_active(SETTINGS.show_applications_page());
+        imp.show_processes_page_row
+            .set_active(SETTINGS.show_processes_page());
+        imp.show_os_page_row.set_active(SETTINGS.show_os_page());
+
+        imp.apps_group_by_category_row
+            .set_active(SETTINGS.apps_group_by_category());
         imp.apps_show_memory_row
             .set_active(SETTINGS.apps_show_memory());
         imp.apps_show_cpu_row.set_active(SETTINGS.apps_show_cpu());
@@ -205,6 +475,8 @@ impl ResSettingsDialog {
         imp.apps_show_drive_write_total_row
             .set_active(SETTINGS.apps_show_drive_write_total());
         imp.apps_show_gpu_row.set_active(SETTINGS.apps_show_gpu());
+        imp.apps_show_usage_per_gpu_row
+            .set_active(SETTINGS.apps_show_usage_per_gpu());
         imp.apps_show_gpu_mem_row
             .set_active(SETTINGS.apps_show_gpu_memory());
         imp.apps_show_encoder_row
@@ -212,9 +484,29 @@ impl ResSettingsDialog {
         imp.apps_show_decoder_row
             .set_active(SETTINGS.apps_show_decoder());
         imp.apps_show_swap_row.set_active(SETTINGS.apps_show_swap());
+        imp.apps_show_autostart_row
+            .set_active(SETTINGS.apps_show_autostart());
+        imp.apps_show_rendering_gpu_row
+            .set_active(SETTINGS.apps_show_rendering_gpu());
+        imp.apps_show_storage_row
+            .set_active(SETTINGS.apps_show_storage());
 
         imp.processes_niceness
             .set_active(SETTINGS.detailed_priority());
+        imp.processes_show_graphs_row
+            .set_active(SETTINGS.processes_show_graphs());
+        imp.processes_name_source_row
+            .set_selected((SETTINGS.processes_name_source() as u8) as u32);
+        imp.processes_show_full_path_row
+            .set_active(SETTINGS.processes_show_full_path());
+        imp.processes_show_tree_depth_row
+            .set_active(SETTINGS.processes_show_tree_depth());
+        imp.processes_group_by_container_row
+            .set_active(SETTINGS.processes_group_by_container());
+        imp.processes_show_drm_fdinfo_row
+            .set_active(SETTINGS.processes_show_drm_fdinfo());
+        imp.commandline_tooltip_length_row
+            .set_value(SETTINGS.commandline_tooltip_length() as f64);
         imp.processes_show_id_row
             .set_active(SETTINGS.processes_show_id());
         imp.processes_show_user_row
@@ -233,6 +525,8 @@ impl ResSettingsDialog {
             .set_active(SETTINGS.processes_show_drive_write_total());
         imp.processes_show_gpu_row
             .set_active(SETTINGS.processes_show_gpu());
+        imp.processes_show_usage_per_gpu_row
+            .set_active(SETTINGS.processes_show_usage_per_gpu());
         imp.processes_show_gpu_mem_row
             .set_active(SETTINGS.processes_show_gpu_memory());
         imp.processes_show_encoder_row
@@ -249,11 +543,287 @@ impl ResSettingsDialog {
             .set_active(SETTINGS.processes_show_system_cpu_time());
         imp.processes_show_swap_row
             .set_active(SETTINGS.processes_show_swap());
+        imp.processes_show_shared_memory_row
+            .set_active(SETTINGS.processes_show_shared_memory());
+        imp.processes_show_locked_memory_row
+            .set_active(SETTINGS.processes_show_locked_memory());
+        imp.processes_show_pinned_memory_row
+            .set_active(SETTINGS.processes_show_pinned_memory());
+        imp.processes_show_cpu_throttling_row
+            .set_active(SETTINGS.processes_show_cpu_throttling());
 
         imp.show_virtual_drives_row
             .set_active(SETTINGS.show_virtual_drives());
+        imp.hide_member_drives_row
+            .set_active(SETTINGS.hide_member_drives());
+        imp.show_loop_devices_row
+            .set_active(SETTINGS.show_loop_devices());
+        imp.drive_full_notification_row
+            .set_active(SETTINGS.drive_full_notification());
+        imp.drive_full_threshold_row
+            .set_value(SETTINGS.drive_full_threshold() as f64);
+        imp.gpu_temperature_sensor_row
+            .set_selected((SETTINGS.gpu_temperature_sensor() as u8) as u32);
+        imp.gpu_vram_warning_threshold_row
+            .set_value(SETTINGS.gpu_vram_warning_threshold() as f64);
         imp.show_virtual_network_interfaces_row
             .set_active(SETTINGS.show_virtual_network_interfaces());
+        imp.show_container_network_interfaces_row
+            .set_active(SETTINGS.show_container_network_interfaces());
+        imp.show_network_total_row
+            .set_active(SETTINGS.show_network_total());
+        imp.network_billing_cycle_day_row
+            .set_value(SETTINGS.network_billing_cycle_day() as f64);
+
+        self.refresh_custom_gauge_rows(&SETTINGS.custom_gauges());
+
+        self.setup_device_visibility_rows();
+    }
+
+    /// Adds a row for every drive and network interface that lets the user decide whether it's always shown, always
+    /// hidden or shown depending on the other options. Loop devices and container network interfaces are left out
+    /// since they come and go too often for an individual choice to make sense.
+    fn setup_device_visibility_rows(&self) {
+        let imp = self.imp();
+
+        if !ARGS.disable_drive_monitoring {
+            let mut drives: Vec<Drive> = Drive::get_sysfs_paths()
+                .unwrap_or_default()
+                .iter()
+                .map(Drive::from_sysfs)
+                .filter(|drive| drive.drive_type != DriveType::LoopDevice)
+                .collect();
+            drives.sort_by(|a, b| a.block_device.cmp(&b.block_device));
+
+            for drive in drives {
+                let row = Self::device_visibility_row(
+                    &format!("{} ({})", drive.description(), drive.display_name()),
+                    &drive.block_device,
+                    drive.stable_id(),
+                );
+                imp.drive_visibility_group.add(&row);
+                imp.drive_visibility_group.set_visible(true);
+            }
+        }
+
+        if !ARGS.disable_network_interface_monitoring {
+            let mut interfaces: Vec<NetworkInterface> = NetworkInterface::get_sysfs_paths()
+                .unwrap_or_default()
+                .iter()
+                .map(|path| NetworkInterface::from_sysfs(path))
+                .filter(|interface| !interface.is_container())
+                .collect();
+            interfaces.sort_by(|a, b| a.interface_name.cmp(&b.interface_name));
+
+            for interface in interfaces {
+                let row = Self::device_visibility_row(
+                    &interface.display_name(),
+                    &interface.interface_name.to_string_lossy(),
+                    interface.stable_id(),
+                );
+                imp.network_visibility_group.add(&row);
+                imp.network_visibility_group.set_visible(true);
+            }
+        }
+    }
+
+    fn device_visibility_row(title: &str, subtitle: &str, device_id: String) -> adw::ComboRow {
+        let row = adw::ComboRow::builder()
+            .title(glib::markup_escape_text(title))
+            .subtitle(glib::markup_escape_text(subtitle))
+            .model(&gtk::StringList::new(&[
+                i18n("Automatic").as_str(),
+                i18n("Always Shown").as_str(),
+                i18n("Always Hidden").as_str(),
+            ]))
+            .build();
+
+        row.set_selected(match SETTINGS.device_visibility(&device_id) {
+            None => 0,
+            Some(true) => 1,
+            Some(false) => 2,
+        });
+
+        row.connect_selected_notify(move |row| {
+            let visibility = match row.selected() {
+                1 => Some(true),
+                2 => Some(false),
+                _ => None,
+            };
+            let _ = SETTINGS.set_device_visibility(&device_id, visibility);
+        });
+
+        row
+    }
+
+    /// Lets the user choose a file to export the settings to, returns `false` if they've cancelled
+    async fn export_settings(&self) -> Result<bool> {
+        let file_dialog = gtk::FileDialog::builder()
+            .title(i18n("Export Settings"))
+            .initial_name("resources-settings.conf")
+            .build();
+
+        let Ok(file) = file_dialog
+            .save_future(self.root().and_downcast_ref::<gtk::Window>())
+            .await
+        else {
+            return Ok(false);
+        };
+
+        file.replace_contents_future(
+            SETTINGS.export(),
+            None,
+            false,
+            gio::FileCreateFlags::REPLACE_DESTINATION,
+        )
+        .await
+        .map_err(|(_, e)| e)
+        .context("unable to write settings export")?;
+
+        Ok(true)
+    }
+
+    /// Lets the user choose a settings export to import and returns a message about the outcome, `None` if they've
+    /// cancelled
+    async fn import_settings(&self) -> Result<Option<String>> {
+        let file_dialog = gtk::FileDialog::builder()
+            .title(i18n("Import Settings"))
+            .build();
+
+        let Ok(file) = file_dialog
+            .open_future(self.root().and_downcast_ref::<gtk::Window>())
+            .await
+        else {
+            return Ok(None);
+        };
+
+        let (contents, _) = file
+            .load_contents_future()
+            .await
+            .context("unable to read settings export")?;
+
+        let import = SETTINGS.import(String::from_utf8_lossy(&contents))?;
+
+        // the widgets still show the old values
+        self.setup_widgets();
+
+        let message = if import.ignored.is_empty() {
+            i18n("Settings imported")
+        } else {
+            ni18n_f(
+                "Settings imported, {} setting was ignored",
+                "Settings imported, {} settings were ignored",
+                import.ignored.len() as u32,
+                &[&import.ignored.len().to_string()],
+            )
+        };
+
+        Ok(Some(message))
+    }
+
+    fn refresh_custom_gauge_rows(&self, gauges: &[CustomGauge]) {
+        let imp = self.imp();
+
+        for row in imp.custom_gauge_rows.borrow_mut().drain(..) {
+            imp.custom_gauges_group.remove(&row);
+        }
+
+        imp.custom_gauges_placeholder_row
+            .set_visible(gauges.is_empty());
+
+        for (i, gauge) in gauges.iter().enumerate() {
+            let row = adw::ActionRow::builder()
+                .title(&gauge.label)
+                .subtitle(gauge.path.to_string_lossy())
+                .subtitle_selectable(true)
+                .build();
+
+            let remove_button = gtk::Button::builder()
+                .icon_name("user-trash-symbolic")
+                .tooltip_text(i18n("Remove"))
+                .valign(gtk::Align::Center)
+                .css_classes(["flat"])
+                .build();
+
+            remove_button.connect_clicked(move |_| {
+                let mut gauges = SETTINGS.custom_gauges();
+                if i < gauges.len() {
+                    gauges.remove(i);
+                    let _ = SETTINGS.set_custom_gauges(&gauges);
+                }
+            });
+
+            row.add_suffix(&remove_button);
+
+            imp.custom_gauges_group.add(&row);
+            imp.custom_gauge_rows.borrow_mut().push(row);
+        }
+    }
+
+    fn add_custom_gauge(&self) {
+        let imp = self.imp();
+
+        imp.custom_gauge_path_row.remove_css_class("error");
+        imp.custom_gauge_scale_row.remove_css_class("error");
+
+        let Ok(scale) = imp.custom_gauge_scale_row.text().trim().parse::<f64>() else {
+            imp.custom_gauge_scale_row.add_css_class("error");
+            return;
+        };
+
+        let path = imp.custom_gauge_path_row.text().trim().to_string();
+
+        let label = imp.custom_gauge_label_row.text().trim().to_string();
+        let label = if label.is_empty() {
+            path.rsplit('/').next().unwrap_or_default().to_string()
+        } else {
+            label
+        };
+
+        match CustomGauge::new(label, path, imp.custom_gauge_unit_row.text().trim(), scale) {
+            Ok(gauge) => {
+                let mut gauges = SETTINGS.custom_gauges();
+                gauges.push(gauge);
+                let _ = SETTINGS.set_custom_gauges(&gauges);
+
+                imp.custom_gauge_label_row.set_text("");
+                imp.custom_gauge_path_row.set_text("");
+                imp.custom_gauge_unit_row.set_text("");
+                imp.custom_gauge_scale_row.set_text("1");
+            }
+            Err(e) => {
+                warn!("Unable to add custom gauge: {e}");
+                imp.custom_gauge_path_row.add_css_class("error");
+            }
+        }
+    }
+
+    /// Keeps the warning threshold of `warning_row` below the critical threshold of `critical_row` by limiting how far
+    /// either of them can be adjusted, values that are already out of order are clamped right away
+    fn link_threshold_rows(warning_row: &adw::SpinRow, critical_row: &adw::SpinRow) {
+        let update_bounds = |warning_row: &adw::SpinRow, critical_row: &adw::SpinRow| {
+            let warning_adjustment = warning_row.adjustment();
+            let critical_adjustment = critical_row.adjustment();
+
+            warning_adjustment
+                .set_upper((critical_row.value() - 1.0).max(warning_adjustment.lower()));
+            critical_adjustment
+                .set_lower((warning_row.value() + 1.0).min(critical_adjustment.upper()));
+        };
+
+        update_bounds(warning_row, critical_row);
+
+        warning_row.connect_value_notify(clone!(
+            #[weak]
+            critical_row,
+            move |warning_row| update_bounds(warning_row, &critical_row)
+        ));
+
+        critical_row.connect_value_notify(clone!(
+            #[weak]
+            warning_row,
+            move |critical_row| update_bounds(&warning_row, critical_row)
+        ));
     }
 
     pub fn setup_signals(&self) {
@@ -280,6 +850,11 @@ impl ResSettingsDialog {
                 }
             });
 
+        imp.decimal_precision_row.connect_output(|spin_row| {
+            let _ = SETTINGS.set_decimal_precision(spin_row.value() as u32);
+            false
+        });
+
         imp.refresh_speed_combo_row
             .connect_selected_item_notify(|combo_row| {
                 if let Some(refresh_speed) = RefreshSpeed::from_repr(combo_row.selected() as u8) {
@@ -287,16 +862,180 @@ impl ResSettingsDialog {
                 }
             });
 
+        imp.processes_refresh_speed_combo_row
+            .connect_selected_item_notify(|combo_row| {
+                let refresh_speed = combo_row
+                    .selected()
+                    .checked_sub(1)
+                    .and_then(|repr| RefreshSpeed::from_repr(repr as u8));
+                let _ = SETTINGS.set_processes_refresh_speed(refresh_speed);
+            });
+
+        imp.drives_refresh_speed_combo_row
+            .connect_selected_item_notify(|combo_row| {
+                let refresh_speed = combo_row
+                    .selected()
+                    .checked_sub(1)
+                    .and_then(|repr| RefreshSpeed::from_repr(repr as u8));
+                let _ = SETTINGS.set_drives_refresh_speed(refresh_speed);
+            });
+
+        imp.network_refresh_speed_combo_row
+            .connect_selected_item_notify(|combo_row| {
+                let refresh_speed = combo_row
+                    .selected()
+                    .checked_sub(1)
+                    .and_then(|repr| RefreshSpeed::from_repr(repr as u8));
+                let _ = SETTINGS.set_network_refresh_speed(refresh_speed);
+            });
+
+        imp.sensors_refresh_speed_combo_row
+            .connect_selected_item_notify(|combo_row| {
+                let refresh_speed = combo_row
+                    .selected()
+                    .checked_sub(1)
+                    .and_then(|repr| RefreshSpeed::from_repr(repr as u8));
+                let _ = SETTINGS.set_sensors_refresh_speed(refresh_speed);
+            });
+
+        imp.battery_refresh_speed_combo_row
+            .connect_selected_item_notify(|combo_row| {
+                let refresh_speed = combo_row
+                    .selected()
+                    .checked_sub(1)
+                    .and_then(|repr| RefreshSpeed::from_repr(repr as u8));
+                let _ = SETTINGS.set_battery_refresh_speed(refresh_speed);
+            });
+
         imp.show_graph_grids_row
             .connect_active_notify(|switch_row| {
                 let _ = SETTINGS.set_show_graph_grids(switch_row.is_active());
             });
 
+        imp.record_history_row.connect_active_notify(|switch_row| {
+            let _ = SETTINGS.set_record_history(switch_row.is_active());
+        });
+
         imp.graph_data_points_row.connect_output(|spin_row| {
             let _ = SETTINGS.set_graph_data_points(spin_row.value() as u32);
             false
         });
 
+        imp.usage_warning_threshold_row.connect_output(|spin_row| {
+            let _ = SETTINGS.set_usage_warning_threshold(spin_row.value() as u32);
+            false
+        });
+
+        imp.usage_critical_threshold_row.connect_output(|spin_row| {
+            let _ = SETTINGS.set_usage_critical_threshold(spin_row.value() as u32);
+            false
+        });
+
+        imp.cpu_temperature_warning_threshold_row
+            .connect_output(|spin_row| {
+                let _ = SETTINGS.set_cpu_temperature_warning_threshold(spin_row.value() as u32);
+                false
+            });
+
+        imp.cpu_temperature_critical_threshold_row
+            .connect_output(|spin_row| {
+                let _ = SETTINGS.set_cpu_temperature_critical_threshold(spin_row.value() as u32);
+                false
+            });
+
+        imp.gpu_temperature_warning_threshold_row
+            .connect_output(|spin_row| {
+                let _ = SETTINGS.set_gpu_temperature_warning_threshold(spin_row.value() as u32);
+                false
+            });
+
+        imp.gpu_temperature_critical_threshold_row
+            .connect_output(|spin_row| {
+                let _ = SETTINGS.set_gpu_temperature_critical_threshold(spin_row.value() as u32);
+                false
+            });
+
+        imp.npu_temperature_warning_threshold_row
+            .connect_output(|spin_row| {
+                let _ = SETTINGS.set_npu_temperature_warning_threshold(spin_row.value() as u32);
+                false
+            });
+
+        imp.npu_temperature_critical_threshold_row
+            .connect_output(|spin_row| {
+                let _ = SETTINGS.set_npu_temperature_critical_threshold(spin_row.value() as u32);
+                false
+            });
+
+        imp.drive_temperature_warning_threshold_row
+            .connect_output(|spin_row| {
+                let _ = SETTINGS.set_drive_temperature_warning_threshold(spin_row.value() as u32);
+                false
+            });
+
+        imp.drive_temperature_critical_threshold_row
+            .connect_output(|spin_row| {
+                let _ = SETTINGS.set_drive_temperature_critical_threshold(spin_row.value() as u32);
+                false
+            });
+
+        for (warning_row, critical_row) in [
+            (
+                &imp.usage_warning_threshold_row,
+                &imp.usage_critical_threshold_row,
+            ),
+            (
+                &imp.cpu_temperature_warning_threshold_row,
+                &imp.cpu_temperature_critical_threshold_row,
+            ),
+            (
+                &imp.gpu_temperature_warning_threshold_row,
+                &imp.gpu_temperature_critical_threshold_row,
+            ),
+            (
+                &imp.npu_temperature_warning_threshold_row,
+                &imp.npu_temperature_critical_threshold_row,
+            ),
+            (
+                &imp.drive_temperature_warning_threshold_row,
+                &imp.drive_temperature_critical_threshold_row,
+            ),
+        ] {
+            Self::link_threshold_rows(warning_row, critical_row);
+        }
+
+        imp.alert_notifications_row
+            .connect_active_notify(|switch_row| {
+                let _ = SETTINGS.set_alert_notifications(switch_row.is_active());
+            });
+
+        imp.alert_duration_row.connect_output(|spin_row| {
+            let _ = SETTINGS.set_alert_duration(spin_row.value() as u32);
+            false
+        });
+
+        imp.alert_processor_threshold_row
+            .connect_output(|spin_row| {
+                let _ = SETTINGS.set_alert_processor_threshold(spin_row.value() as u32);
+                false
+            });
+
+        imp.alert_memory_threshold_row.connect_output(|spin_row| {
+            let _ = SETTINGS.set_alert_memory_threshold(spin_row.value() as u32);
+            false
+        });
+
+        imp.alert_drive_temperature_threshold_row
+            .connect_output(|spin_row| {
+                let _ = SETTINGS.set_alert_drive_temperature_threshold(spin_row.value() as u32);
+                false
+            });
+
+        imp.alert_battery_threshold_row.connect_output(|spin_row| {
+            let _ = SETTINGS.set_alert_battery_threshold(spin_row.value() as u32);
+            false
+        });
+
         imp.sidebar_details_row.connect_active_notify(|switch_row| {
             let _ = SETTINGS.set_sidebar_details(switch_row.is_active());
         });
@@ -318,11 +1057,96 @@ impl ResSettingsDialog {
                 let _ = SETTINGS.set_show_search_on_start(switch_row.is_active());
             });
 
+        imp.always_on_top_row.connect_active_notify(|switch_row| {
+            let _ = SETTINGS.set_always_on_top(switch_row.is_active());
+        });
+
+        imp.start_hidden_row.connect_active_notify(|switch_row| {
+            let _ = SETTINGS.set_start_hidden(switch_row.is_active());
+        });
+
+        imp.show_tray_indicator_row
+            .connect_active_notify(|switch_row| {
+                let _ = SETTINGS.set_show_tray_indicator(switch_row.is_active());
+            });
+
         imp.normalize_cpu_usage_row
             .connect_active_notify(|switch_row| {
                 let _ = SETTINGS.set_normalize_cpu_usage(switch_row.is_active());
             });
 
+        imp.confirm_end_action_row
+            .connect_active_notify(|switch_row| {
+                let _ = SETTINGS.set_confirm_end_action(switch_row.is_active());
+            });
+
+        imp.merge_media_columns_row
+            .connect_active_notify(|switch_row| {
+                let _ = SETTINGS.set_merge_media_columns(switch_row.is_active());
+            });
+
+        imp.highlight_changes_row
+            .connect_active_notify(|switch_row| {
+                let _ = SETTINGS.set_highlight_changes(switch_row.is_active());
+            });
+
+        imp.highlight_changes_threshold_row
+            .connect_output(|spin_row| {
+                let _ = SETTINGS.set_highlight_changes_threshold(spin_row.value() as u32);
+                false
+            });
+
+        imp.show_cpu_page_row.connect_active_notify(|switch_row| {
+            let _ = SETTINGS.set_show_cpu_page(switch_row.is_active());
+        });
+
+        imp.show_memory_page_row
+            .connect_active_notify(|switch_row| {
+                let _ = SETTINGS.set_show_memory_page(switch_row.is_active());
+            });
+
+        imp.show_gpu_pages_row.connect_active_notify(|switch_row| {
+            let _ = SETTINGS.set_show_gpu_pages(switch_row.is_active());
+        });
+
+        imp.show_npu_pages_row.connect_active_notify(|switch_row| {
+            let _ = SETTINGS.set_show_npu_pages(switch_row.is_active());
+        });
+
+        imp.show_drive_pages_row
+            .connect_active_notify(|switch_row| {
+                let _ = SETTINGS.set_show_drive_pages(switch_row.is_active());
+            });
+
+        imp.show_network_pages_row
+            .connect_active_notify(|switch_row| {
+                let _ = SETTINGS.set_show_network_pages(switch_row.is_active());
+            });
+
+        imp.show_battery_pages_row
+            .connect_active_notify(|switch_row| {
+                let _ = SETTINGS.set_show_battery_pages(switch_row.is_active());
+            });
+
+        imp.show_applications_page_row
+            .connect_active_notify(|switch_row| {
+                let _ = SETTINGS.set_show_applications_page(switch_row.is_active());
+            });
+
+        imp.show_processes_page_row
+            .connect_active_notify(|switch_row| {
+                let _ = SETTINGS.set_show_processes_page(switch_row.is_active());
+            });
+
+        imp.show_os_page_row.connect_active_notify(|switch_row| {
+            let _ = SETTINGS.set_show_os_page(switch_row.is_active());
+        });
+
+        imp.apps_group_by_category_row
+            .connect_active_notify(|switch_row| {
+                let _ = SETTINGS.set_apps_group_by_category(switch_row.is_active());
+            });
+
         imp.apps_show_cpu_row.connect_active_notify(|switch_row| {
             let _ = SETTINGS.set_apps_show_cpu(switch_row.is_active());
         });
@@ -346,6 +1170,11 @@ impl ResSettingsDialog {
             let _ = SETTINGS.set_apps_show_gpu(switch_row.is_active());
         });
 
+        imp.apps_show_usage_per_gpu_row
+            .connect_active_notify(|switch_row| {
+                let _ = SETTINGS.set_apps_show_usage_per_gpu(switch_row.is_active());
+            });
+
         imp.apps_show_gpu_mem_row
             .connect_active_notify(|switch_row| {
                 let _ = SETTINGS.set_apps_show_gpu_memory(switch_row.is_active());
@@ -375,10 +1204,63 @@ impl ResSettingsDialog {
             let _ = SETTINGS.set_apps_show_swap(switch_row.is_active());
         });
 
+        imp.apps_show_autostart_row
+            .connect_active_notify(|switch_row| {
+                let _ = SETTINGS.set_apps_show_autostart(switch_row.is_active());
+            });
+
+        imp.apps_show_rendering_gpu_row
+            .connect_active_notify(|switch_row| {
+                let _ = SETTINGS.set_apps_show_rendering_gpu(switch_row.is_active());
+            });
+
+        imp.apps_show_storage_row
+            .connect_active_notify(|switch_row| {
+                let _ = SETTINGS.set_apps_show_storage(switch_row.is_active());
+            });
+
         imp.processes_niceness.connect_active_notify(|switch_row| {
             let _ = SETTINGS.set_detailed_priority(switch_row.is_active());
         });
 
+        imp.processes_show_graphs_row
+            .connect_active_notify(|switch_row| {
+                let _ = SETTINGS.set_processes_show_graphs(switch_row.is_active());
+            });
+
+        imp.processes_show_drm_fdinfo_row
+            .connect_active_notify(|switch_row| {
+                let _ = SETTINGS.set_processes_show_drm_fdinfo(switch_row.is_active());
+            });
+
+        imp.processes_name_source_row
+            .connect_selected_item_notify(|combo_row| {
+                if let Some(source) = ProcessNameSource::from_repr(combo_row.selected() as u8) {
+                    let _ = SETTINGS.set_processes_name_source(source);
+                }
+            });
+
+        imp.processes_show_full_path_row
+            .connect_active_notify(|switch_row| {
+                let _ = SETTINGS.set_processes_show_full_path(switch_row.is_active());
+            });
+
+        imp.processes_show_tree_depth_row
+            .connect_active_notify(|switch_row| {
+                let _ = SETTINGS.set_processes_show_tree_depth(switch_row.is_active());
+            });
+
+        imp.processes_group_by_container_row
+            .connect_active_notify(|switch_row| {
+                let _ = SETTINGS.set_processes_group_by_container(switch_row.is_active());
+            });
+
+        imp.commandline_tooltip_length_row
+            .connect_output(|spin_row| {
+                let _ = SETTINGS.set_commandline_tooltip_length(spin_row.value() as u32);
+                false
+            });
+
         imp.processes_show_id_row
             .connect_active_notify(|switch_row| {
                 let _ = SETTINGS.set_processes_show_id(switch_row.is_active());
@@ -424,6 +1306,11 @@ impl ResSettingsDialog {
                 let _ = SETTINGS.set_processes_show_gpu(switch_row.is_active());
             });
 
+        imp.processes_show_usage_per_gpu_row
+            .connect_active_notify(|switch_row| {
+                let _ = SETTINGS.set_processes_show_usage_per_gpu(switch_row.is_active());
+            });
+
         imp.processes_show_gpu_mem_row
             .connect_active_notify(|switch_row| {
                 let _ = SETTINGS.set_processes_show_gpu_memory(switch_row.is_active());
@@ -464,14 +1351,152 @@ impl ResSettingsDialog {
                 let _ = SETTINGS.set_processes_show_swap(switch_row.is_active());
             });
 
+        imp.processes_show_shared_memory_row
+            .connect_active_notify(|switch_row| {
+                let _ = SETTINGS.set_processes_show_shared_memory(switch_row.is_active());
+            });
+
+        imp.processes_show_locked_memory_row
+            .connect_active_notify(|switch_row| {
+                let _ = SETTINGS.set_processes_show_locked_memory(switch_row.is_active());
+            });
+
+        imp.processes_show_pinned_memory_row
+            .connect_active_notify(|switch_row| {
+                let _ = SETTINGS.set_processes_show_pinned_memory(switch_row.is_active());
+            });
+
+        imp.processes_show_cpu_throttling_row
+            .connect_active_notify(|switch_row| {
+                let _ = SETTINGS.set_processes_show_cpu_throttling(switch_row.is_active());
+            });
+
         imp.show_virtual_drives_row
             .connect_active_notify(|switch_row| {
                 let _ = SETTINGS.set_show_virtual_drives(switch_row.is_active());
             });
 
+        imp.hide_member_drives_row
+            .connect_active_notify(|switch_row| {
+                let _ = SETTINGS.set_hide_member_drives(switch_row.is_active());
+            });
+
+        imp.show_loop_devices_row
+            .connect_active_notify(|switch_row| {
+                let _ = SETTINGS.set_show_loop_devices(switch_row.is_active());
+            });
+
+        imp.drive_full_notification_row
+            .connect_active_notify(|switch_row| {
+                let _ = SETTINGS.set_drive_full_notification(switch_row.is_active());
+            });
+
+        imp.drive_full_threshold_row.connect_output(|spin_row| {
+            let _ = SETTINGS.set_drive_full_threshold(spin_row.value() as u32);
+            false
+        });
+
+        imp.gpu_temperature_sensor_row
+            .connect_selected_item_notify(|combo_row| {
+                if let Some(sensor) = GpuTemperatureSensor::from_repr(combo_row.selected() as u8) {
+                    let _ = SETTINGS.set_gpu_temperature_sensor(sensor);
+                }
+            });
+
+        imp.gpu_vram_warning_threshold_row
+            .connect_output(|spin_row| {
+                let _ = SETTINGS.set_gpu_vram_warning_threshold(spin_row.value() as u32);
+                false
+            });
+
         imp.show_virtual_network_interfaces_row
             .connect_active_notify(|switch_row| {
                 let _ = SETTINGS.set_show_virtual_network_interfaces(switch_row.is_active());
             });
+
+        imp.show_container_network_interfaces_row
+            .connect_active_notify(|switch_row| {
+                let _ = SETTINGS.set_show_container_network_interfaces(switch_row.is_active());
+            });
+
+        imp.show_network_total_row
+            .connect_active_notify(|switch_row| {
+                let _ = SETTINGS.set_show_network_total(switch_row.is_active());
+            });
+
+        imp.network_billing_cycle_day_row
+            .connect_output(|spin_row| {
+                let _ = SETTINGS.set_network_billing_cycle_day(spin_row.value() as u32);
+                false
+            });
+
+        imp.add_custom_gauge_button.connect_clicked(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |_| {
+                this.add_custom_gauge();
+            }
+        ));
+
+        imp.custom_gauge_path_row.connect_entry_activated(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |_| {
+                this.add_custom_gauge();
+            }
+        ));
+
+        imp.export_settings_row.connect_activated(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |_| {
+                MainContext::default().spawn_local(clone!(
+                    #[weak]
+                    this,
+                    async move {
+                        match this.export_settings().await {
+                            Ok(true) => this.add_toast(Toast::new(&i18n("Settings exported"))),
+                            Ok(false) => {}
+                            Err(e) => {
+                                warn!("Unable to export settings: {e}");
+                                this.add_toast(Toast::new(&i18n("Unable to export settings")));
+                            }
+                        }
+                    }
+                ));
+            }
+        ));
+
+        imp.import_settings_row.connect_activated(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |_| {
+                MainContext::default().spawn_local(clone!(
+                    #[weak]
+                    this,
+                    async move {
+                        match this.import_settings().await {
+                            Ok(Some(message)) => this.add_toast(Toast::new(&message)),
+                            Ok(None) => {}
+                            Err(e) => {
+                                warn!("Unable to import settings: {e}");
+                                this.add_toast(Toast::new(&i18n_f(
+                                    "Unable to import settings: {}",
+                                    &[&e.to_string()],
+                                )));
+                            }
+                        }
+                    }
+                ));
+            }
+        ));
+
+        SETTINGS.connect_custom_gauges(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |gauges| {
+                this.refresh_custom_gauge_rows(&gauges);
+            }
+        ));
     }
 }
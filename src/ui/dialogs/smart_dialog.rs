@@ -0,0 +1,157 @@
+use crate::config::PROFILE;
+use crate::i18n::{i18n, i18n_f, ni18n_f};
+use crate::utils::drive::smart::{SmartAttribute, SmartValue};
+use crate::utils::units::{convert_storage, convert_temperature, format_percentage};
+use adw::{prelude::*, subclass::prelude::*};
+use gtk::glib;
+use log::trace;
+
+mod imp {
+
+    use super::*;
+
+    use gtk::CompositeTemplate;
+
+    #[derive(Debug, CompositeTemplate, Default)]
+    #[template(resource = "/net/nokyan/Resources/ui/dialogs/smart_dialog.ui")]
+    pub struct ResSmartDialog {
+        #[template_child]
+        pub stack: TemplateChild<gtk::Stack>,
+        #[template_child]
+        pub name: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub attributes_group: TemplateChild<adw::PreferencesGroup>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ResSmartDialog {
+        const NAME: &'static str = "ResSmartDialog";
+        type Type = super::ResSmartDialog;
+        type ParentType = adw::Dialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            Self::bind_template(klass);
+        }
+
+        // You must call `Widget`'s `init_template()` within `instance_init()`.
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for ResSmartDialog {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+
+            // Devel Profile
+            if PROFILE == "Devel" {
+                obj.add_css_class("devel");
+            }
+        }
+    }
+
+    impl WidgetImpl for ResSmartDialog {}
+    impl WindowImpl for ResSmartDialog {}
+    impl AdwDialogImpl for ResSmartDialog {}
+}
+
+glib::wrapper! {
+    pub struct ResSmartDialog(ObjectSubclass<imp::ResSmartDialog>)
+        @extends gtk::Widget, adw::Dialog;
+}
+
+impl Default for ResSmartDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ResSmartDialog {
+    pub fn new() -> Self {
+        trace!("Creating ResSmartDialog GObject…");
+
+        glib::Object::new::<Self>()
+    }
+
+    /// Lists `attributes` of the drive called `drive_name`, an empty list is shown as unavailable
+    pub fn init(&self, drive_name: &str, attributes: &[SmartAttribute]) {
+        let imp = self.imp();
+
+        imp.name.set_label(drive_name);
+
+        if attributes.is_empty() {
+            imp.stack.set_visible_child_name("unavailable");
+            return;
+        }
+
+        for attribute in attributes {
+            let title = if let Some(id) = attribute.id {
+                format!("{id} · {}", attribute.name)
+            } else {
+                attribute.name.clone()
+            };
+
+            let value = Self::format_value(&attribute.value);
+
+            let subtitle = if let Some((normalized, worst, threshold)) = attribute.normalized {
+                i18n_f(
+                    "{} · Normalized: {} · Worst: {} · Threshold: {}",
+                    &[
+                        &value,
+                        &normalized.to_string(),
+                        &worst.to_string(),
+                        &threshold.to_string(),
+                    ],
+                )
+            } else {
+                value
+            };
+
+            let row = adw::ActionRow::builder()
+                .title(glib::markup_escape_text(&title))
+                .subtitle(glib::markup_escape_text(&subtitle))
+                .subtitle_selectable(true)
+                .css_classes(["property"])
+                .build();
+
+            if attribute.failing {
+                let icon = gtk::Image::from_icon_name("dialog-warning-symbolic");
+                icon.set_tooltip_text(Some(&i18n("Failing")));
+                icon.add_css_class("error");
+                row.add_suffix(&icon);
+            }
+
+            imp.attributes_group.add(&row);
+        }
+    }
+
+    fn format_value(value: &SmartValue) -> String {
+        match value {
+            SmartValue::Count(count) => count.to_string(),
+            SmartValue::Seconds(seconds) => {
+                let hours = (seconds / 3600.0).floor();
+                if hours >= 1.0 {
+                    i18n_f("{} h", &[&hours.to_string()])
+                } else {
+                    i18n_f("{} min", &[&(seconds / 60.0).round().to_string()])
+                }
+            }
+            SmartValue::Temperatures(temperatures) if temperatures.is_empty() => i18n("N/A"),
+            SmartValue::Temperatures(temperatures) => temperatures
+                .iter()
+                .map(|temperature| convert_temperature(*temperature))
+                .collect::<Vec<_>>()
+                .join(", "),
+            SmartValue::Sectors(sectors) => ni18n_f(
+                "{} sector",
+                "{} sectors",
+                u32::try_from(*sectors).unwrap_or(u32::MAX),
+                &[&sectors.to_string()],
+            ),
+            SmartValue::Bytes(bytes) => convert_storage(*bytes as f64, false),
+            SmartValue::Percentage(percentage) => format_percentage(*percentage),
+            SmartValue::Other(other) => other.clone(),
+        }
+    }
+}
@@ -40,5 +40,6 @@ macro_rules! gstring_option_getter_setter {
 
 pub mod dialogs;
 pub mod pages;
+pub mod status_notifier;
 pub mod widgets;
 pub mod window;
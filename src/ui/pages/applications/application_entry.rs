@@ -1,14 +1,22 @@
-use gtk::glib::{self};
+use gtk::{
+    gio,
+    glib::{self, MainContext},
+    prelude::ObjectExt,
+    subclass::prelude::ObjectSubclassIsExt,
+};
 use log::trace;
-use process_data::Containerization;
+use process_data::{Containerization, GpuIdentifier};
 
 use crate::{
-    i18n::i18n,
-    utils::app::{App, AppsContext},
+    i18n::{i18n, i18n_f},
+    utils::app::{App, AppFootprint, AppsContext, PackageInfo},
 };
 
 mod imp {
-    use std::cell::Cell;
+    use std::{
+        cell::{Cell, RefCell},
+        collections::BTreeMap,
+    };
 
     use glib::object::Cast;
     use gtk::{
@@ -67,22 +75,58 @@ mod imp {
         #[property(get, set)]
         dec_usage: Cell<f32>,
 
+        #[property(get, set)]
+        media_usage: Cell<f32>,
+
         #[property(get, set)]
         gpu_mem_usage: Cell<u64>,
 
+        #[property(get = Self::rendering_gpu, set = Self::set_rendering_gpu)]
+        rendering_gpu: Cell<glib::GString>,
+
         #[property(get = Self::running_since, set = Self::set_running_since)]
         running_since: Cell<Option<glib::GString>>,
 
         #[property(get = Self::containerization, set = Self::set_containerization)]
         containerization: Cell<glib::GString>,
 
+        #[property(get = Self::flatpak_branch, set = Self::set_flatpak_branch)]
+        flatpak_branch: Cell<Option<glib::GString>>,
+
+        #[property(get = Self::flatpak_runtime, set = Self::set_flatpak_runtime)]
+        flatpak_runtime: Cell<Option<glib::GString>>,
+
+        #[property(get = Self::snap_revision, set = Self::set_snap_revision)]
+        snap_revision: Cell<Option<glib::GString>>,
+
+        #[property(get = Self::snap_version, set = Self::set_snap_version)]
+        snap_version: Cell<Option<glib::GString>>,
+
         #[property(get, set)]
         running_processes: Cell<u32>,
 
+        #[property(get = Self::category, set = Self::set_category)]
+        category: Cell<glib::GString>,
+
+        #[property(get, set)]
+        autostart: Cell<bool>,
+
+        /// How many bytes the app occupies on the disk, 0 as long as this is unknown
+        #[property(get, set)]
+        storage_usage: Cell<u64>,
+
         // TODO: Make this properly dynamic, don't use a variable that's never read
         #[property(get = Self::symbolic)]
         #[allow(dead_code)]
         symbolic: Cell<bool>,
+
+        pub affinity: RefCell<Vec<bool>>,
+
+        pub gpu_usages: RefCell<BTreeMap<GpuIdentifier, f32>>,
+
+        pub footprint: RefCell<Option<AppFootprint>>,
+
+        pub storage_usage_requested: Cell<bool>,
     }
 
     impl Default for ApplicationEntry {
@@ -102,19 +146,40 @@ mod imp {
                 gpu_usage: Cell::new(0.0),
                 enc_usage: Cell::new(0.0),
                 dec_usage: Cell::new(0.0),
+                media_usage: Cell::new(0.0),
                 gpu_mem_usage: Cell::new(0),
+                rendering_gpu: Cell::new(glib::GString::default()),
                 symbolic: Cell::new(false),
                 running_since: Cell::new(None),
                 containerization: Cell::new(glib::GString::default()),
+                flatpak_branch: Cell::new(None),
+                flatpak_runtime: Cell::new(None),
+                snap_revision: Cell::new(None),
+                snap_version: Cell::new(None),
                 running_processes: Cell::new(0),
+                category: Cell::new(glib::GString::default()),
+                autostart: Cell::new(false),
+                storage_usage: Cell::new(0),
+                affinity: Default::default(),
+                gpu_usages: Default::default(),
+                footprint: Default::default(),
+                storage_usage_requested: Cell::new(false),
             }
         }
     }
 
     impl ApplicationEntry {
-        gstring_getter_setter!(name, containerization);
-
-        gstring_option_getter_setter!(description, id, running_since);
+        gstring_getter_setter!(name, containerization, category, rendering_gpu);
+
+        gstring_option_getter_setter!(
+            description,
+            id,
+            running_since,
+            flatpak_branch,
+            flatpak_runtime,
+            snap_revision,
+            snap_version
+        );
 
         pub fn icon(&self) -> Icon {
             let icon = self.icon.replace(ThemedIcon::new("generic-process").into());
@@ -182,10 +247,20 @@ impl ApplicationEntry {
     pub fn new(app: &App, apps_context: &AppsContext) -> Self {
         trace!("Creating ApplicationEntry ({}) GObject…", app.display_name);
 
-        let containerization = match app.containerization {
-            Containerization::None => i18n("No"),
-            Containerization::Flatpak => i18n("Yes (Flatpak)"),
-            Containerization::Snap => i18n("Yes (Snap)"),
+        let containerization = match (&app.container, app.containerization) {
+            (Some(container), _) => i18n_f("Yes ({})", &[&container.runtime.to_string()]),
+            (None, Containerization::None) => i18n("No"),
+            (None, Containerization::Flatpak) => i18n("Yes (Flatpak)"),
+            (None, Containerization::Snap) => i18n("Yes (Snap)"),
+        };
+
+        let (flatpak_branch, flatpak_runtime, snap_revision, snap_version) = match app
+            .package_info
+            .clone()
+        {
+            Some(PackageInfo::Flatpak { branch, runtime }) => (Some(branch), runtime, None, None),
+            Some(PackageInfo::Snap { revision, version }) => (None, None, Some(revision), version),
+            None => (None, None, None, None),
         };
 
         let this: Self = glib::Object::builder()
@@ -194,8 +269,15 @@ impl ApplicationEntry {
             .property("id", &app.id)
             .property("description", &app.description)
             .property("containerization", containerization)
+            .property("flatpak_branch", flatpak_branch)
+            .property("flatpak_runtime", flatpak_runtime)
+            .property("snap_revision", snap_revision)
+            .property("snap_version", snap_version)
+            .property("category", app.category_name())
+            .property("autostart", app.autostart)
             .property("running_since", app.running_since(apps_context).ok())
             .build();
+        *this.imp().footprint.borrow_mut() = app.footprint.clone();
         this.update(app, apps_context);
         this
     }
@@ -210,10 +292,82 @@ impl ApplicationEntry {
         self.set_read_total(app.read_total(apps_context));
         self.set_write_speed(app.write_speed(apps_context));
         self.set_write_total(app.write_total(apps_context));
+        // the per-GPU usages are read whenever gpu_usage changes, so they need to be up to date before that
+        *self.imp().gpu_usages.borrow_mut() = app.gpu_usages(apps_context);
         self.set_gpu_usage(app.gpu_usage(apps_context));
         self.set_enc_usage(app.enc_usage(apps_context));
         self.set_dec_usage(app.dec_usage(apps_context));
+        self.set_media_usage(f32::max(self.enc_usage(), self.dec_usage()));
         self.set_gpu_mem_usage(app.gpu_mem_usage(apps_context));
+        self.set_rendering_gpu(
+            app.rendering_gpu(apps_context)
+                .map(|gpu_identifier| apps_context.gpu_label(&gpu_identifier))
+                .unwrap_or_default(),
+        );
         self.set_running_processes(app.running_processes() as u32);
+        *self.imp().affinity.borrow_mut() = app.affinity(apps_context);
+    }
+
+    /// Calculates how much disk space the app takes up in the background and sets `storage_usage` once that's done.
+    ///
+    /// Since this can take a while, it only happens once per entry.
+    pub fn request_storage_usage(&self) {
+        Self::request_storage_usages(std::slice::from_ref(self));
+    }
+
+    /// Like [`Self::request_storage_usage`], but the disk usages of all `apps` are calculated one after another in a
+    /// single background task instead of walking through many directories at once
+    pub fn request_storage_usages(apps: &[Self]) {
+        let (apps, footprints): (Vec<_>, Vec<_>) = apps
+            .iter()
+            .filter(|app| !app.imp().storage_usage_requested.replace(true))
+            .filter_map(|app| Some((app.downgrade(), app.imp().footprint.borrow().clone()?)))
+            .unzip();
+
+        if footprints.is_empty() {
+            return;
+        }
+
+        trace!(
+            "Calculating the storage usage of {} apps…",
+            footprints.len()
+        );
+
+        let (sender, receiver) = async_channel::unbounded();
+
+        gio::spawn_blocking(move || {
+            for (i, footprint) in footprints.iter().enumerate() {
+                if let Some(storage_usage) = footprint.disk_usage() {
+                    // the receiver is gone if the apps page has been dropped in the meantime
+                    if sender.send_blocking((i, storage_usage)).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+
+        MainContext::default().spawn_local(async move {
+            while let Ok((i, storage_usage)) = receiver.recv().await {
+                if let Some(app) = apps[i].upgrade() {
+                    app.set_storage_usage(storage_usage);
+                }
+            }
+        });
+    }
+
+    /// Returns the usage of the GPU `gpu_identifier` alone as calculated by [`App::gpu_usages`] during the last update,
+    /// 0 if the app doesn't use it
+    pub fn gpu_usage_of(&self, gpu_identifier: &GpuIdentifier) -> f32 {
+        self.imp()
+            .gpu_usages
+            .borrow()
+            .get(gpu_identifier)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Returns which logical CPUs at least one of the app's processes is allowed to run on
+    pub fn affinity(&self) -> Vec<bool> {
+        self.imp().affinity.borrow().clone()
     }
 }
@@ -2,6 +2,7 @@ pub mod application_entry;
 mod application_name_cell;
 
 use std::collections::HashSet;
+use std::str::FromStr;
 
 use adw::ResponseAppearance;
 use adw::{prelude::*, subclass::prelude::*};
@@ -11,15 +12,21 @@ use gtk::{
     gio, ColumnView, ColumnViewColumn, EventControllerKey, FilterChange, ListItem, NumericSorter,
     SortType, StringSorter, Widget,
 };
+use log::warn;
+use process_data::GpuIdentifier;
 
 use crate::config::PROFILE;
 use crate::i18n::{i18n, i18n_f};
 use crate::ui::dialogs::app_dialog::ResAppDialog;
+use crate::ui::dialogs::app_options_dialog::ResAppOptionsDialog;
 use crate::ui::window::{Action, MainWindow};
 use crate::utils::app::AppsContext;
 use crate::utils::process::ProcessAction;
 use crate::utils::settings::SETTINGS;
-use crate::utils::units::{convert_speed, convert_storage};
+use crate::utils::table::{
+    copy_column_menu, export_table, highlight_changes, tsv_line, visible_columns, ChangeScale,
+};
+use crate::utils::units::{convert_speed, convert_storage, format_percentage};
 use crate::utils::NUM_CPUS;
 
 use self::application_entry::ApplicationEntry;
@@ -27,6 +34,9 @@ use self::application_name_cell::ResApplicationNameCell;
 
 pub const TAB_ID: &str = "applications";
 
+/// Prefix of the IDs of the columns that show the usage of a single GPU, followed by the GPU's identifier
+const GPU_USAGE_COLUMN_PREFIX: &str = "gpu_usage:";
+
 mod imp {
     use std::{
         cell::{Cell, RefCell},
@@ -62,6 +72,8 @@ mod imp {
         #[template_child]
         pub information_button: TemplateChild<gtk::Button>,
         #[template_child]
+        pub options_button: TemplateChild<gtk::Button>,
+        #[template_child]
         pub end_application_button: TemplateChild<adw::SplitButton>,
 
         pub store: RefCell<gio::ListStore>,
@@ -71,6 +83,8 @@ mod imp {
         pub column_view: RefCell<gtk::ColumnView>,
         pub open_info_dialog: RefCell<Option<(Option<String>, ResAppDialog)>>,
         pub info_dialog_closed: Cell<bool>,
+        pub open_options_dialog: RefCell<Option<(Option<String>, ResAppOptionsDialog)>>,
+        pub options_dialog_closed: Cell<bool>,
 
         pub sender: OnceLock<Sender<Action>>,
 
@@ -119,6 +133,7 @@ mod imp {
                 search_entry: Default::default(),
                 search_button: Default::default(),
                 information_button: Default::default(),
+                options_button: Default::default(),
                 store: gio::ListStore::new::<ApplicationEntry>().into(),
                 selection_model: Default::default(),
                 filter_model: Default::default(),
@@ -126,6 +141,8 @@ mod imp {
                 column_view: Default::default(),
                 open_info_dialog: Default::default(),
                 info_dialog_closed: Default::default(),
+                open_options_dialog: Default::default(),
+                options_dialog_closed: Default::default(),
                 sender: Default::default(),
                 applications_scrolled_window: Default::default(),
                 end_application_button: Default::default(),
@@ -215,6 +232,18 @@ mod imp {
                 },
             );
 
+            klass.install_action(
+                "applications.context-options",
+                None,
+                move |res_applications, _, _| {
+                    if let Some(application_entry) =
+                        res_applications.imp().popped_over_app.borrow().as_ref()
+                    {
+                        res_applications.open_options_dialog(application_entry);
+                    }
+                },
+            );
+
             klass.install_action(
                 "applications.kill-app",
                 None,
@@ -245,6 +274,38 @@ mod imp {
                 },
             );
 
+            klass.install_action(
+                "applications.copy-rows",
+                None,
+                move |res_applications, _, _| {
+                    res_applications.copy_selected_rows();
+                },
+            );
+
+            klass.add_binding_action(
+                gtk::gdk::Key::c,
+                gtk::gdk::ModifierType::CONTROL_MASK,
+                "applications.copy-rows",
+            );
+
+            klass.install_action(
+                "applications.export",
+                None,
+                move |res_applications, _, _| {
+                    res_applications.export();
+                },
+            );
+
+            klass.install_action(
+                "applications.copy-column",
+                Some(glib::VariantTy::STRING),
+                move |res_applications, _, parameter| {
+                    if let Some(column_id) = parameter.and_then(|p| p.get::<String>()) {
+                        res_applications.copy_column(&column_id);
+                    }
+                },
+            );
+
             Self::bind_template(klass);
         }
 
@@ -308,11 +369,13 @@ impl ResApplications {
         imp.search_button.set_active(false);
     }
 
-    pub fn init(&self, sender: Sender<Action>) {
+    /// `gpus` are the identifiers and names of the GPUs in the system, they get a usage column each if there's more
+    /// than one
+    pub fn init(&self, sender: Sender<Action>, gpus: &[(GpuIdentifier, String)]) {
         let imp = self.imp();
         imp.sender.set(sender).unwrap();
 
-        self.setup_widgets();
+        self.setup_widgets(gpus);
         self.setup_signals();
     }
 
@@ -354,7 +417,7 @@ impl ResApplications {
         widget.add_controller(secondary_click);
     }
 
-    pub fn setup_widgets(&self) {
+    pub fn setup_widgets(&self, gpus: &[(GpuIdentifier, String)]) {
         let imp = self.imp();
 
         imp.popover_menu.set_parent(self);
@@ -376,6 +439,48 @@ impl ResApplications {
         columns.push(self.add_encoder_column(&column_view));
         columns.push(self.add_decoder_column(&column_view));
         columns.push(self.add_swap_column(&column_view));
+        columns.push(self.add_category_column(&column_view));
+        columns.push(self.add_autostart_column(&column_view));
+        columns.push(self.add_media_column(&column_view));
+        columns.push(self.add_rendering_gpu_column(&column_view));
+        columns.push(self.add_storage_column(&column_view));
+
+        // with a single GPU, its usage column would just be a copy of the combined one
+        let gpu_usage_columns: Vec<ColumnViewColumn> = if gpus.len() > 1 {
+            gpus.iter()
+                .map(|(gpu_identifier, label)| {
+                    self.add_gpu_usage_of_column(&column_view, *gpu_identifier, label)
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        columns.extend(gpu_usage_columns.iter().cloned());
+
+        // new columns are added last so that the indices of the other columns (which the sort column is saved as)
+        // don't change, but some of them belong next to related columns. They're moved back to front so that moving one
+        // doesn't shift the position of the next one's neighbour.
+        let column_position = |id: &str| {
+            columns
+                .iter()
+                .position(|column| column.id().as_deref() == Some(id))
+        };
+        for (previous_id, id) in [("decoder", "media"), ("gpu", "rendering_gpu")] {
+            if let (Some(previous_position), Some(position)) =
+                (column_position(previous_id), column_position(id))
+            {
+                column_view.insert_column(previous_position as u32 + 1, &columns[position]);
+            }
+        }
+        if let Some(gpu_position) = column_position("gpu") {
+            for gpu_usage_column in gpu_usage_columns.iter().rev() {
+                column_view.insert_column(gpu_position as u32 + 1, gpu_usage_column);
+            }
+        }
+
+        for column in columns.iter() {
+            column.set_header_menu(Some(&copy_column_menu("applications.copy-column", column)));
+        }
 
         let store = gio::ListStore::new::<ApplicationEntry>();
 
@@ -388,7 +493,39 @@ impl ResApplications {
             ))),
         );
 
-        let sort_model = gtk::SortListModel::new(Some(filter_model.clone()), column_view.sorter());
+        // when grouping by category is enabled, the category takes precedence over whatever column the user
+        // has chosen to sort by
+        let category_sorter = gtk::CustomSorter::new(|a, b| {
+            if !SETTINGS.apps_group_by_category() {
+                return gtk::Ordering::Equal;
+            }
+
+            let a = a.downcast_ref::<ApplicationEntry>().unwrap();
+            let b = b.downcast_ref::<ApplicationEntry>().unwrap();
+
+            let other = i18n("Other");
+            let a_category = a.category();
+            let b_category = b.category();
+
+            // uncategorized apps should always be at the bottom
+            ((a_category == other), a_category.to_lowercase())
+                .cmp(&((b_category == other), b_category.to_lowercase()))
+                .into()
+        });
+
+        let sorter = gtk::MultiSorter::new();
+        sorter.append(category_sorter.clone());
+        if let Some(column_view_sorter) = column_view.sorter() {
+            sorter.append(column_view_sorter);
+        }
+
+        SETTINGS.connect_apps_group_by_category(clone!(
+            #[weak]
+            category_sorter,
+            move |_| category_sorter.changed(gtk::SorterChange::Different)
+        ));
+
+        let sort_model = gtk::SortListModel::new(Some(filter_model.clone()), Some(sorter));
 
         let selection_model = gtk::SingleSelection::new(Some(sort_model.clone()));
         selection_model.set_can_unselect(true);
@@ -433,6 +570,8 @@ impl ResApplications {
                     });
                     imp.information_button
                         .set_sensitive(model.selected() != u32::MAX);
+                    imp.options_button
+                        .set_sensitive(model.selected() != u32::MAX && !is_system_processes);
                     imp.end_application_button
                         .set_sensitive(model.selected() != u32::MAX && !is_system_processes);
                 }
@@ -492,6 +631,16 @@ impl ResApplications {
             }
         ));
 
+        imp.options_button.connect_clicked(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |_| {
+                if let Some(app) = this.get_selected_app_entry() {
+                    this.open_options_dialog(&app);
+                }
+            }
+        ));
+
         imp.end_application_button.connect_clicked(clone!(
             #[weak(rename_to = this)]
             self,
@@ -549,6 +698,8 @@ impl ResApplications {
 
         dialog.init(app);
 
+        app.request_storage_usage();
+
         dialog.present(Some(&MainWindow::default()));
 
         dialog.connect_closed(clone!(
@@ -565,6 +716,36 @@ impl ResApplications {
         ));
     }
 
+    pub fn open_options_dialog(&self, app: &ApplicationEntry) {
+        let imp = self.imp();
+
+        // System Processes is no real app, its processes can only be adjusted one by one
+        if app.id().is_none() || imp.open_options_dialog.borrow().is_some() {
+            return;
+        }
+
+        imp.options_dialog_closed.set(false);
+
+        let dialog = ResAppOptionsDialog::new();
+
+        dialog.init(app, imp.sender.get().unwrap().clone(), &imp.toast_overlay);
+
+        dialog.connect_closed(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |_| {
+                this.imp().options_dialog_closed.set(true);
+            }
+        ));
+
+        dialog.present(Some(&MainWindow::default()));
+
+        *imp.open_options_dialog.borrow_mut() = Some((
+            app.id().as_ref().map(std::string::ToString::to_string),
+            dialog,
+        ));
+    }
+
     fn search_filter(&self, obj: &Object) -> bool {
         let imp = self.imp();
         let item = obj.downcast_ref::<ApplicationEntry>().unwrap();
@@ -589,6 +770,65 @@ impl ResApplications {
             .and_then(|object| object.downcast::<ApplicationEntry>().ok())
     }
 
+    /// Copies the visible columns of the selected app to the clipboard as tab-separated values
+    fn copy_selected_rows(&self) {
+        let Some(app) = self.get_selected_app_entry() else {
+            return;
+        };
+
+        let text = tsv_line(
+            visible_columns(&self.imp().column_view.borrow())
+                .iter()
+                .map(|column| column_text(&app, &column.id().unwrap_or_default())),
+        );
+
+        self.clipboard().set_text(&text);
+    }
+
+    /// Copies the values of the column with the ID `column_id` of all apps that are currently listed to the
+    /// clipboard, in the order they're displayed in
+    fn copy_column(&self, column_id: &str) {
+        let text = self
+            .imp()
+            .sort_model
+            .borrow()
+            .iter::<ApplicationEntry>()
+            .flatten()
+            .map(|app| tsv_line([column_text(&app, column_id)]))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        self.clipboard().set_text(&text);
+    }
+
+    /// Lets the user export the visible columns of all apps that are currently listed to a CSV or JSON file
+    fn export(&self) {
+        MainContext::default().spawn_local(clone!(
+            #[weak(rename_to = this)]
+            self,
+            async move {
+                let imp = this.imp();
+
+                let sort_model = imp.sort_model.borrow().clone();
+                let column_view = imp.column_view.borrow().clone();
+
+                let toast_message =
+                    match export_table(&this, "apps.csv", &column_view, &sort_model, column_text)
+                        .await
+                    {
+                        Ok(true) => i18n("Table exported"),
+                        Ok(false) => return,
+                        Err(e) => {
+                            warn!("Unable to export table: {e}");
+                            i18n("Unable to export table")
+                        }
+                    };
+
+                imp.toast_overlay.add_toast(adw::Toast::new(&toast_message));
+            }
+        ));
+    }
+
     pub fn refresh_apps_list(&self, apps_context: &AppsContext) {
         let imp = self.imp();
 
@@ -597,8 +837,14 @@ impl ResApplications {
             imp.info_dialog_closed.set(false);
         }
 
+        if imp.options_dialog_closed.get() {
+            let _ = imp.open_options_dialog.take();
+            imp.options_dialog_closed.set(false);
+        }
+
         let store = imp.store.borrow_mut();
         let mut dialog_opt = &*imp.open_info_dialog.borrow_mut();
+        let mut options_dialog_opt = &*imp.open_options_dialog.borrow_mut();
 
         let mut ids_to_remove = HashSet::new();
         let mut already_existing_ids = HashSet::new();
@@ -622,6 +868,12 @@ impl ResApplications {
                             dialog_opt = &None;
                         }
                     }
+                    if let Some((dialog_id, dialog)) = options_dialog_opt {
+                        if dialog_id.as_deref() == app_id.as_deref() {
+                            dialog.close();
+                            options_dialog_opt = &None;
+                        }
+                    }
                     *imp.popped_over_app.borrow_mut() = None;
                     ids_to_remove.insert(app_id.clone());
                 }
@@ -657,6 +909,9 @@ impl ResApplications {
             })
             .map(|new_item| ApplicationEntry::new(new_item, apps_context))
             .collect();
+        if SETTINGS.apps_show_storage() {
+            ApplicationEntry::request_storage_usages(&items);
+        }
         store.extend_from_slice(&items);
 
         if let Some(sorter) = imp.column_view.borrow().sorter() {
@@ -671,8 +926,11 @@ impl ResApplications {
     }
 
     pub fn open_app_action_dialog(&self, app: &ApplicationEntry, action: ProcessAction) {
-        // Nothing too bad can happen on Continue so dont show the dialog
-        if action == ProcessAction::CONT {
+        // Nothing too bad can happen on Continue so dont show the dialog, ending can be confirmed without it if the
+        // user wishes so
+        if action == ProcessAction::CONT
+            || (action == ProcessAction::TERM && !SETTINGS.confirm_end_action())
+        {
             let main_context = MainContext::default();
             main_context.spawn_local(clone!(
                 #[weak(rename_to = this)]
@@ -754,6 +1012,7 @@ impl ResApplications {
             gtk::ColumnViewColumn::new(Some(&i18n("App")), Some(name_col_factory.clone()));
 
         name_col.set_resizable(true);
+        name_col.set_id(Some("name"));
 
         name_col.set_expand(true);
 
@@ -811,6 +1070,7 @@ impl ResApplications {
             gtk::ColumnViewColumn::new(Some(&i18n("Memory")), Some(memory_col_factory.clone()));
 
         memory_col.set_resizable(true);
+        memory_col.set_id(Some("memory"));
 
         memory_col_factory.connect_setup(clone!(
             #[weak(rename_to = this)]
@@ -831,6 +1091,13 @@ impl ResApplications {
                     }))
                     .bind(&row, "text", Widget::NONE);
 
+                highlight_changes::<ApplicationEntry>(
+                    item,
+                    &row,
+                    "memory_usage",
+                    ChangeScale::Relative,
+                );
+
                 this.add_gestures(item);
             }
         ));
@@ -870,6 +1137,7 @@ impl ResApplications {
             gtk::ColumnViewColumn::new(Some(&i18n("Processor")), Some(cpu_col_factory.clone()));
 
         cpu_col.set_resizable(true);
+        cpu_col.set_id(Some("cpu"));
 
         cpu_col_factory.connect_setup(clone!(
             #[weak(rename_to = this)]
@@ -885,15 +1153,17 @@ impl ResApplications {
                 item.property_expression("item")
                     .chain_property::<ApplicationEntry>("cpu_usage")
                     .chain_closure::<String>(closure!(|_: Option<Object>, cpu_usage: f32| {
-                        let mut percentage = cpu_usage * 100.0;
-                        if !SETTINGS.normalize_cpu_usage() {
-                            percentage *= *NUM_CPUS as f32;
-                        }
-
-                        format!("{percentage:.1} %")
+                        format_cpu_usage(cpu_usage)
                     }))
                     .bind(&row, "text", Widget::NONE);
 
+                highlight_changes::<ApplicationEntry>(
+                    item,
+                    &row,
+                    "cpu_usage",
+                    ChangeScale::Fraction,
+                );
+
                 this.add_gestures(item);
             }
         ));
@@ -935,6 +1205,7 @@ impl ResApplications {
         );
 
         read_speed_col.set_resizable(true);
+        read_speed_col.set_id(Some("read_speed"));
 
         read_speed_col_factory.connect_setup(clone!(
             #[weak(rename_to = this)]
@@ -950,14 +1221,17 @@ impl ResApplications {
                 item.property_expression("item")
                     .chain_property::<ApplicationEntry>("read_speed")
                     .chain_closure::<String>(closure!(|_: Option<Object>, read_speed: f64| {
-                        if read_speed == -1.0 {
-                            i18n("N/A")
-                        } else {
-                            convert_speed(read_speed, false)
-                        }
+                        format_io_speed(read_speed)
                     }))
                     .bind(&row, "text", Widget::NONE);
 
+                highlight_changes::<ApplicationEntry>(
+                    item,
+                    &row,
+                    "read_speed",
+                    ChangeScale::Relative,
+                );
+
                 this.add_gestures(item);
             }
         ));
@@ -999,6 +1273,7 @@ impl ResApplications {
         );
 
         read_total_col.set_resizable(true);
+        read_total_col.set_id(Some("read_total"));
 
         read_total_col_factory.connect_setup(clone!(
             #[weak(rename_to = this)]
@@ -1059,6 +1334,7 @@ impl ResApplications {
         );
 
         write_speed_col.set_resizable(true);
+        write_speed_col.set_id(Some("write_speed"));
 
         write_speed_col_factory.connect_setup(clone!(
             #[weak(rename_to = this)]
@@ -1074,14 +1350,17 @@ impl ResApplications {
                 item.property_expression("item")
                     .chain_property::<ApplicationEntry>("write_speed")
                     .chain_closure::<String>(closure!(|_: Option<Object>, write_speed: f64| {
-                        if write_speed == -1.0 {
-                            i18n("N/A")
-                        } else {
-                            convert_speed(write_speed, false)
-                        }
+                        format_io_speed(write_speed)
                     }))
                     .bind(&row, "text", Widget::NONE);
 
+                highlight_changes::<ApplicationEntry>(
+                    item,
+                    &row,
+                    "write_speed",
+                    ChangeScale::Relative,
+                );
+
                 this.add_gestures(item);
             }
         ));
@@ -1125,6 +1404,7 @@ impl ResApplications {
         );
 
         write_total_col.set_resizable(true);
+        write_total_col.set_id(Some("write_total"));
 
         write_total_col_factory.connect_setup(clone!(
             #[weak(rename_to = this)]
@@ -1184,6 +1464,7 @@ impl ResApplications {
         let gpu_col = gtk::ColumnViewColumn::new(Some(&i18n("GPU")), Some(gpu_col_factory.clone()));
 
         gpu_col.set_resizable(true);
+        gpu_col.set_id(Some("gpu"));
 
         gpu_col_factory.connect_setup(clone!(
             #[weak(rename_to = this)]
@@ -1199,10 +1480,17 @@ impl ResApplications {
                 item.property_expression("item")
                     .chain_property::<ApplicationEntry>("gpu_usage")
                     .chain_closure::<String>(closure!(|_: Option<Object>, gpu_usage: f32| {
-                        format!("{:.1} %", gpu_usage * 100.0)
+                        format_percentage(gpu_usage * 100.0)
                     }))
                     .bind(&row, "text", Widget::NONE);
 
+                highlight_changes::<ApplicationEntry>(
+                    item,
+                    &row,
+                    "gpu_usage",
+                    ChangeScale::Fraction,
+                );
+
                 this.add_gestures(item);
             }
         ));
@@ -1244,6 +1532,7 @@ impl ResApplications {
         );
 
         encoder_col.set_resizable(true);
+        encoder_col.set_id(Some("encoder"));
 
         encoder_col_factory.connect_setup(clone!(
             #[weak(rename_to = this)]
@@ -1259,10 +1548,17 @@ impl ResApplications {
                 item.property_expression("item")
                     .chain_property::<ApplicationEntry>("enc_usage")
                     .chain_closure::<String>(closure!(|_: Option<Object>, enc_usage: f32| {
-                        format!("{:.1} %", enc_usage * 100.0)
+                        format_percentage(enc_usage * 100.0)
                     }))
                     .bind(&row, "text", Widget::NONE);
 
+                highlight_changes::<ApplicationEntry>(
+                    item,
+                    &row,
+                    "enc_usage",
+                    ChangeScale::Fraction,
+                );
+
                 this.add_gestures(item);
             }
         ));
@@ -1282,14 +1578,20 @@ impl ResApplications {
             .build();
 
         encoder_col.set_sorter(Some(&encoder_col_sorter));
-        encoder_col.set_visible(SETTINGS.apps_show_encoder());
+        encoder_col.set_visible(SETTINGS.apps_show_encoder() && !SETTINGS.merge_media_columns());
 
         column_view.append_column(&encoder_col);
 
         SETTINGS.connect_apps_show_encoder(clone!(
             #[weak]
             encoder_col,
-            move |visible| encoder_col.set_visible(visible)
+            move |visible| encoder_col.set_visible(visible && !SETTINGS.merge_media_columns())
+        ));
+
+        SETTINGS.connect_merge_media_columns(clone!(
+            #[weak]
+            encoder_col,
+            move |merge| encoder_col.set_visible(SETTINGS.apps_show_encoder() && !merge)
         ));
 
         encoder_col
@@ -1304,6 +1606,7 @@ impl ResApplications {
         );
 
         decoder_col.set_resizable(true);
+        decoder_col.set_id(Some("decoder"));
 
         decoder_col_factory.connect_setup(clone!(
             #[weak(rename_to = this)]
@@ -1319,10 +1622,17 @@ impl ResApplications {
                 item.property_expression("item")
                     .chain_property::<ApplicationEntry>("dec_usage")
                     .chain_closure::<String>(closure!(|_: Option<Object>, dec_usage: f32| {
-                        format!("{:.1} %", dec_usage * 100.0)
+                        format_percentage(dec_usage * 100.0)
                     }))
                     .bind(&row, "text", Widget::NONE);
 
+                highlight_changes::<ApplicationEntry>(
+                    item,
+                    &row,
+                    "dec_usage",
+                    ChangeScale::Fraction,
+                );
+
                 this.add_gestures(item);
             }
         ));
@@ -1342,19 +1652,110 @@ impl ResApplications {
             .build();
 
         decoder_col.set_sorter(Some(&decoder_col_sorter));
-        decoder_col.set_visible(SETTINGS.apps_show_decoder());
+        decoder_col.set_visible(SETTINGS.apps_show_decoder() && !SETTINGS.merge_media_columns());
 
         column_view.append_column(&decoder_col);
 
         SETTINGS.connect_apps_show_decoder(clone!(
             #[weak]
             decoder_col,
-            move |visible| decoder_col.set_visible(visible)
+            move |visible| decoder_col.set_visible(visible && !SETTINGS.merge_media_columns())
+        ));
+
+        SETTINGS.connect_merge_media_columns(clone!(
+            #[weak]
+            decoder_col,
+            move |merge| decoder_col.set_visible(SETTINGS.apps_show_decoder() && !merge)
         ));
 
         decoder_col
     }
 
+    fn add_media_column(&self, column_view: &ColumnView) -> ColumnViewColumn {
+        let media_col_factory = gtk::SignalListItemFactory::new();
+
+        let media_col =
+            gtk::ColumnViewColumn::new(Some(&i18n("Media")), Some(media_col_factory.clone()));
+
+        media_col.set_resizable(true);
+        media_col.set_id(Some("media"));
+
+        media_col_factory.connect_setup(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |_factory, item| {
+                let item = item.downcast_ref::<gtk::ListItem>().unwrap();
+
+                let row = gtk::Inscription::new(None);
+                row.set_min_chars(7);
+
+                item.set_child(Some(&row));
+
+                item.property_expression("item")
+                    .chain_property::<ApplicationEntry>("media_usage")
+                    .chain_closure::<String>(closure!(|_: Option<Object>, media_usage: f32| {
+                        format_percentage(media_usage * 100.0)
+                    }))
+                    .bind(&row, "text", Widget::NONE);
+
+                highlight_changes::<ApplicationEntry>(
+                    item,
+                    &row,
+                    "media_usage",
+                    ChangeScale::Fraction,
+                );
+
+                this.add_gestures(item);
+            }
+        ));
+
+        media_col_factory.connect_teardown(move |_factory, item| {
+            let item = item.downcast_ref::<gtk::ListItem>().unwrap();
+            item.set_child(None::<&gtk::Inscription>);
+        });
+
+        let media_col_sorter = NumericSorter::builder()
+            .sort_order(SortType::Ascending)
+            .expression(gtk::PropertyExpression::new(
+                ApplicationEntry::static_type(),
+                None::<&gtk::Expression>,
+                "media_usage",
+            ))
+            .build();
+
+        media_col.set_sorter(Some(&media_col_sorter));
+
+        // the media column takes the place of the encoder and decoder columns, so it's shown if any of them would be
+        let media_visible = || {
+            SETTINGS.merge_media_columns()
+                && (SETTINGS.apps_show_encoder() || SETTINGS.apps_show_decoder())
+        };
+
+        media_col.set_visible(media_visible());
+
+        column_view.append_column(&media_col);
+
+        SETTINGS.connect_merge_media_columns(clone!(
+            #[weak]
+            media_col,
+            move |_| media_col.set_visible(media_visible())
+        ));
+
+        SETTINGS.connect_apps_show_encoder(clone!(
+            #[weak]
+            media_col,
+            move |_| media_col.set_visible(media_visible())
+        ));
+
+        SETTINGS.connect_apps_show_decoder(clone!(
+            #[weak]
+            media_col,
+            move |_| media_col.set_visible(media_visible())
+        ));
+
+        media_col
+    }
+
     fn add_gpu_mem_column(&self, column_view: &ColumnView) -> ColumnViewColumn {
         let gpu_mem_col_factory = gtk::SignalListItemFactory::new();
         let gpu_mem_col = gtk::ColumnViewColumn::new(
@@ -1362,6 +1763,7 @@ impl ResApplications {
             Some(gpu_mem_col_factory.clone()),
         );
         gpu_mem_col.set_resizable(true);
+        gpu_mem_col.set_id(Some("gpu_mem"));
 
         gpu_mem_col_factory.connect_setup(clone!(
             #[weak(rename_to = this)]
@@ -1380,6 +1782,13 @@ impl ResApplications {
                     }))
                     .bind(&row, "text", Widget::NONE);
 
+                highlight_changes::<ApplicationEntry>(
+                    item,
+                    &row,
+                    "gpu_mem_usage",
+                    ChangeScale::Relative,
+                );
+
                 this.add_gestures(item);
             }
         ));
@@ -1419,6 +1828,7 @@ impl ResApplications {
             gtk::ColumnViewColumn::new(Some(&i18n("Swap")), Some(swap_col_factory.clone()));
 
         swap_col.set_resizable(true);
+        swap_col.set_id(Some("swap"));
 
         swap_col_factory.connect_setup(clone!(
             #[weak(rename_to = this)]
@@ -1439,6 +1849,13 @@ impl ResApplications {
                     }))
                     .bind(&row, "text", Widget::NONE);
 
+                highlight_changes::<ApplicationEntry>(
+                    item,
+                    &row,
+                    "swap_usage",
+                    ChangeScale::Relative,
+                );
+
                 this.add_gestures(item);
             }
         ));
@@ -1470,6 +1887,335 @@ impl ResApplications {
 
         swap_col
     }
+
+    fn add_category_column(&self, column_view: &ColumnView) -> ColumnViewColumn {
+        let category_col_factory = gtk::SignalListItemFactory::new();
+
+        let category_col =
+            gtk::ColumnViewColumn::new(Some(&i18n("Category")), Some(category_col_factory.clone()));
+
+        category_col.set_resizable(true);
+        category_col.set_id(Some("category"));
+
+        category_col_factory.connect_setup(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |_factory, item| {
+                let item = item.downcast_ref::<gtk::ListItem>().unwrap();
+
+                let row = gtk::Inscription::new(None);
+                row.set_min_chars(10);
+
+                item.set_child(Some(&row));
+
+                item.property_expression("item")
+                    .chain_property::<ApplicationEntry>("category")
+                    .bind(&row, "text", Widget::NONE);
+
+                this.add_gestures(item);
+            }
+        ));
+
+        category_col_factory.connect_teardown(move |_factory, item| {
+            let item = item.downcast_ref::<gtk::ListItem>().unwrap();
+            item.set_child(None::<&gtk::Inscription>);
+        });
+
+        let category_col_sorter = StringSorter::builder()
+            .ignore_case(true)
+            .expression(gtk::PropertyExpression::new(
+                ApplicationEntry::static_type(),
+                None::<&gtk::Expression>,
+                "category",
+            ))
+            .build();
+
+        category_col.set_sorter(Some(&category_col_sorter));
+        category_col.set_visible(SETTINGS.apps_group_by_category());
+
+        column_view.append_column(&category_col);
+
+        SETTINGS.connect_apps_group_by_category(clone!(
+            #[weak]
+            category_col,
+            move |visible| category_col.set_visible(visible)
+        ));
+
+        category_col
+    }
+
+    fn add_rendering_gpu_column(&self, column_view: &ColumnView) -> ColumnViewColumn {
+        let rendering_gpu_col_factory = gtk::SignalListItemFactory::new();
+
+        let rendering_gpu_col = gtk::ColumnViewColumn::new(
+            Some(&i18n("Rendering GPU")),
+            Some(rendering_gpu_col_factory.clone()),
+        );
+
+        rendering_gpu_col.set_resizable(true);
+        rendering_gpu_col.set_id(Some("rendering_gpu"));
+
+        rendering_gpu_col_factory.connect_setup(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |_factory, item| {
+                let item = item.downcast_ref::<gtk::ListItem>().unwrap();
+
+                let row = gtk::Inscription::new(None);
+                row.set_min_chars(6);
+
+                item.set_child(Some(&row));
+
+                item.property_expression("item")
+                    .chain_property::<ApplicationEntry>("rendering_gpu")
+                    .bind(&row, "text", Widget::NONE);
+
+                this.add_gestures(item);
+            }
+        ));
+
+        rendering_gpu_col_factory.connect_teardown(move |_factory, item| {
+            let item = item.downcast_ref::<gtk::ListItem>().unwrap();
+            item.set_child(None::<&gtk::Inscription>);
+        });
+
+        let rendering_gpu_col_sorter = StringSorter::builder()
+            .ignore_case(true)
+            .expression(gtk::PropertyExpression::new(
+                ApplicationEntry::static_type(),
+                None::<&gtk::Expression>,
+                "rendering_gpu",
+            ))
+            .build();
+
+        rendering_gpu_col.set_sorter(Some(&rendering_gpu_col_sorter));
+        rendering_gpu_col.set_visible(SETTINGS.apps_show_rendering_gpu());
+
+        column_view.append_column(&rendering_gpu_col);
+
+        SETTINGS.connect_apps_show_rendering_gpu(clone!(
+            #[weak]
+            rendering_gpu_col,
+            move |visible| rendering_gpu_col.set_visible(visible)
+        ));
+
+        rendering_gpu_col
+    }
+
+    fn add_autostart_column(&self, column_view: &ColumnView) -> ColumnViewColumn {
+        let autostart_col_factory = gtk::SignalListItemFactory::new();
+
+        let autostart_col = gtk::ColumnViewColumn::new(
+            Some(&i18n("Autostart")),
+            Some(autostart_col_factory.clone()),
+        );
+
+        autostart_col.set_resizable(true);
+        autostart_col.set_id(Some("autostart"));
+
+        autostart_col_factory.connect_setup(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |_factory, item| {
+                let item = item.downcast_ref::<gtk::ListItem>().unwrap();
+
+                let row = gtk::Inscription::new(None);
+                row.set_min_chars(5);
+
+                item.set_child(Some(&row));
+
+                item.property_expression("item")
+                    .chain_property::<ApplicationEntry>("autostart")
+                    .chain_closure::<String>(closure!(|_: Option<Object>, autostart: bool| {
+                        format_autostart(autostart)
+                    }))
+                    .bind(&row, "text", Widget::NONE);
+
+                this.add_gestures(item);
+            }
+        ));
+
+        autostart_col_factory.connect_teardown(move |_factory, item| {
+            let item = item.downcast_ref::<gtk::ListItem>().unwrap();
+            item.set_child(None::<&gtk::Inscription>);
+        });
+
+        let autostart_col_sorter = NumericSorter::builder()
+            .sort_order(SortType::Ascending)
+            .expression(gtk::PropertyExpression::new(
+                ApplicationEntry::static_type(),
+                None::<&gtk::Expression>,
+                "autostart",
+            ))
+            .build();
+
+        autostart_col.set_sorter(Some(&autostart_col_sorter));
+        autostart_col.set_visible(SETTINGS.apps_show_autostart());
+
+        column_view.append_column(&autostart_col);
+
+        SETTINGS.connect_apps_show_autostart(clone!(
+            #[weak]
+            autostart_col,
+            move |visible| autostart_col.set_visible(visible)
+        ));
+
+        autostart_col
+    }
+
+    fn add_storage_column(&self, column_view: &ColumnView) -> ColumnViewColumn {
+        let storage_col_factory = gtk::SignalListItemFactory::new();
+
+        let storage_col =
+            gtk::ColumnViewColumn::new(Some(&i18n("Storage")), Some(storage_col_factory.clone()));
+
+        storage_col.set_resizable(true);
+        storage_col.set_id(Some("storage"));
+
+        storage_col_factory.connect_setup(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |_factory, item| {
+                let item = item.downcast_ref::<gtk::ListItem>().unwrap();
+
+                let row = gtk::Inscription::new(None);
+                row.set_min_chars(9);
+
+                item.set_child(Some(&row));
+
+                item.property_expression("item")
+                    .chain_property::<ApplicationEntry>("storage_usage")
+                    .chain_closure::<String>(closure!(|_: Option<Object>, storage_usage: u64| {
+                        format_storage_usage(storage_usage)
+                    }))
+                    .bind(&row, "text", Widget::NONE);
+
+                this.add_gestures(item);
+            }
+        ));
+
+        storage_col_factory.connect_teardown(move |_factory, item| {
+            let item = item.downcast_ref::<gtk::ListItem>().unwrap();
+            item.set_child(None::<&gtk::Inscription>);
+        });
+
+        let storage_col_sorter = NumericSorter::builder()
+            .sort_order(SortType::Ascending)
+            .expression(gtk::PropertyExpression::new(
+                ApplicationEntry::static_type(),
+                None::<&gtk::Expression>,
+                "storage_usage",
+            ))
+            .build();
+
+        storage_col.set_sorter(Some(&storage_col_sorter));
+        storage_col.set_visible(SETTINGS.apps_show_storage());
+
+        column_view.append_column(&storage_col);
+
+        // the storage usage is only calculated once it's needed since that can be expensive
+        SETTINGS.connect_apps_show_storage(clone!(
+            #[weak(rename_to = this)]
+            self,
+            #[weak]
+            storage_col,
+            move |visible| {
+                storage_col.set_visible(visible);
+                if visible {
+                    let apps: Vec<ApplicationEntry> = this
+                        .imp()
+                        .store
+                        .borrow()
+                        .iter::<ApplicationEntry>()
+                        .flatten()
+                        .collect();
+                    ApplicationEntry::request_storage_usages(&apps);
+                }
+            }
+        ));
+
+        storage_col
+    }
+
+    fn add_gpu_usage_of_column(
+        &self,
+        column_view: &ColumnView,
+        gpu_identifier: GpuIdentifier,
+        label: &str,
+    ) -> ColumnViewColumn {
+        let gpu_usage_col_factory = gtk::SignalListItemFactory::new();
+
+        let gpu_usage_col =
+            gtk::ColumnViewColumn::new(Some(label), Some(gpu_usage_col_factory.clone()));
+
+        gpu_usage_col.set_resizable(true);
+        gpu_usage_col.set_id(Some(&format!("{GPU_USAGE_COLUMN_PREFIX}{gpu_identifier}")));
+
+        gpu_usage_col_factory.connect_setup(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |_factory, item| {
+                let item = item.downcast_ref::<gtk::ListItem>().unwrap();
+
+                let row = gtk::Inscription::new(None);
+                row.set_min_chars(7);
+
+                item.set_child(Some(&row));
+
+                // the usage of a single GPU is no property of its own, but it's refreshed together with the combined
+                // usage, so watch that instead
+                gtk::PropertyExpression::new(
+                    ApplicationEntry::static_type(),
+                    Some(item.property_expression("item")),
+                    "gpu_usage",
+                )
+                .watch(
+                    Some(item),
+                    clone!(
+                        #[weak]
+                        item,
+                        #[weak]
+                        row,
+                        move || {
+                            let text = item.item().and_downcast::<ApplicationEntry>().map(|app| {
+                                format_percentage(app.gpu_usage_of(&gpu_identifier) * 100.0)
+                            });
+                            row.set_text(text.as_deref());
+                        }
+                    ),
+                );
+
+                this.add_gestures(item);
+            }
+        ));
+
+        gpu_usage_col_factory.connect_teardown(move |_factory, item| {
+            let item = item.downcast_ref::<gtk::ListItem>().unwrap();
+            item.set_child(None::<&gtk::Inscription>);
+        });
+
+        let gpu_usage_col_sorter = gtk::CustomSorter::new(move |a, b| {
+            let a = a.downcast_ref::<ApplicationEntry>().unwrap();
+            let b = b.downcast_ref::<ApplicationEntry>().unwrap();
+
+            a.gpu_usage_of(&gpu_identifier)
+                .total_cmp(&b.gpu_usage_of(&gpu_identifier))
+                .into()
+        });
+
+        gpu_usage_col.set_sorter(Some(&gpu_usage_col_sorter));
+        gpu_usage_col.set_visible(SETTINGS.apps_show_usage_per_gpu());
+
+        column_view.append_column(&gpu_usage_col);
+
+        SETTINGS.connect_apps_show_usage_per_gpu(clone!(
+            #[weak]
+            gpu_usage_col,
+            move |visible| gpu_usage_col.set_visible(visible)
+        ));
+
+        gpu_usage_col
+    }
 }
 
 fn get_action_name(action: ProcessAction, name: &str) -> String {
@@ -1498,3 +2244,64 @@ fn get_action_description(action: ProcessAction) -> String {
         ProcessAction::CONT => i18n("Continue App"),
     }
 }
+
+fn format_cpu_usage(cpu_usage: f32) -> String {
+    let mut percentage = cpu_usage * 100.0;
+    if !SETTINGS.normalize_cpu_usage() {
+        percentage *= *NUM_CPUS as f32;
+    }
+
+    format_percentage(percentage)
+}
+
+fn format_io_speed(speed: f64) -> String {
+    if speed == -1.0 {
+        i18n("N/A")
+    } else {
+        convert_speed(speed, false)
+    }
+}
+
+fn format_autostart(autostart: bool) -> String {
+    if autostart {
+        i18n("Yes")
+    } else {
+        i18n("No")
+    }
+}
+
+fn format_storage_usage(storage_usage: u64) -> String {
+    if storage_usage == 0 {
+        i18n("N/A")
+    } else {
+        convert_storage(storage_usage as f64, false)
+    }
+}
+
+/// Returns the text that the column with the ID `column_id` displays for `app`
+fn column_text(app: &ApplicationEntry, column_id: &str) -> String {
+    match column_id {
+        "name" => app.name().to_string(),
+        "memory" => convert_storage(app.memory_usage() as f64, false),
+        "cpu" => format_cpu_usage(app.cpu_usage()),
+        "read_speed" => format_io_speed(app.read_speed()),
+        "read_total" => convert_storage(app.read_total() as f64, false),
+        "write_speed" => format_io_speed(app.write_speed()),
+        "write_total" => convert_storage(app.write_total() as f64, false),
+        "gpu" => format_percentage(app.gpu_usage() * 100.0),
+        "gpu_mem" => convert_storage(app.gpu_mem_usage() as f64, false),
+        "encoder" => format_percentage(app.enc_usage() * 100.0),
+        "decoder" => format_percentage(app.dec_usage() * 100.0),
+        "media" => format_percentage(app.media_usage() * 100.0),
+        "swap" => convert_storage(app.swap_usage() as f64, false),
+        "category" => app.category().to_string(),
+        "autostart" => format_autostart(app.autostart()),
+        "rendering_gpu" => app.rendering_gpu().to_string(),
+        "storage" => format_storage_usage(app.storage_usage()),
+        _ => column_id
+            .strip_prefix(GPU_USAGE_COLUMN_PREFIX)
+            .and_then(|gpu_identifier| GpuIdentifier::from_str(gpu_identifier).ok())
+            .map(|gpu_identifier| format_percentage(app.gpu_usage_of(&gpu_identifier) * 100.0))
+            .unwrap_or_default(),
+    }
+}
@@ -1,18 +1,24 @@
-use adw::{prelude::*, subclass::prelude::*};
-use gtk::glib;
-use log::trace;
+use adw::{prelude::*, subclass::prelude::*, Toast};
+use gtk::{
+    gio,
+    glib::{self, clone, MainContext},
+};
+use log::{trace, warn};
 
 use crate::config::PROFILE;
 use crate::i18n::i18n;
-use crate::utils::battery::BatteryData;
-use crate::utils::units::{convert_energy, convert_power};
+use crate::utils::battery::{Battery, BatteryData, ChargeThresholds};
+use crate::utils::units::{convert_current, convert_energy, convert_power, convert_voltage};
 
 pub const TAB_ID_PREFIX: &str = "battery";
 
 mod imp {
     use std::cell::{Cell, RefCell};
 
-    use crate::ui::{pages::BATTERY_PRIMARY_ORD, widgets::graph_box::ResGraphBox};
+    use crate::ui::{
+        pages::BATTERY_PRIMARY_ORD,
+        widgets::{double_graph_box::ResDoubleGraphBox, graph_box::ResGraphBox},
+    };
 
     use super::*;
 
@@ -26,15 +32,21 @@ mod imp {
     #[template(resource = "/net/nokyan/Resources/ui/pages/battery.ui")]
     #[properties(wrapper_type = super::ResBattery)]
     pub struct ResBattery {
+        #[template_child]
+        pub toast_overlay: TemplateChild<adw::ToastOverlay>,
         #[template_child]
         pub charge: TemplateChild<ResGraphBox>,
         #[template_child]
         pub power_usage: TemplateChild<ResGraphBox>,
         #[template_child]
+        pub voltage_current: TemplateChild<ResDoubleGraphBox>,
+        #[template_child]
         pub health: TemplateChild<adw::ActionRow>,
         #[template_child]
         pub design_capacity: TemplateChild<adw::ActionRow>,
         #[template_child]
+        pub full_capacity: TemplateChild<adw::ActionRow>,
+        #[template_child]
         pub charge_cycles: TemplateChild<adw::ActionRow>,
         #[template_child]
         pub technology: TemplateChild<adw::ActionRow>,
@@ -44,6 +56,16 @@ mod imp {
         pub model_name: TemplateChild<adw::ActionRow>,
         #[template_child]
         pub device: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub charge_thresholds_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub apply_charge_thresholds_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub charge_start_threshold: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub charge_end_threshold: TemplateChild<adw::SpinRow>,
+        pub battery: RefCell<Option<Battery>>,
+        pub charge_thresholds: Cell<Option<ChargeThresholds>>,
 
         #[property(get)]
         uses_progress_bar: Cell<bool>,
@@ -86,15 +108,24 @@ mod imp {
     impl Default for ResBattery {
         fn default() -> Self {
             Self {
+                toast_overlay: Default::default(),
                 charge: Default::default(),
                 power_usage: Default::default(),
+                voltage_current: Default::default(),
                 health: Default::default(),
                 design_capacity: Default::default(),
+                full_capacity: Default::default(),
                 charge_cycles: Default::default(),
                 technology: Default::default(),
                 manufacturer: Default::default(),
                 model_name: Default::default(),
                 device: Default::default(),
+                charge_thresholds_group: Default::default(),
+                apply_charge_thresholds_button: Default::default(),
+                charge_start_threshold: Default::default(),
+                charge_end_threshold: Default::default(),
+                battery: Default::default(),
+                charge_thresholds: Default::default(),
                 uses_progress_bar: Cell::new(true),
                 main_graph_color: glib::Bytes::from_static(&super::ResBattery::MAIN_GRAPH_COLOR),
                 icon: RefCell::new(ThemedIcon::new("battery-symbolic").into()),
@@ -177,6 +208,7 @@ impl ResBattery {
     pub fn init(&self, battery_data: &BatteryData, secondary_ord: u32) {
         self.set_secondary_ord(secondary_ord);
         self.setup_widgets(battery_data);
+        self.setup_signals();
     }
 
     pub fn setup_widgets(&self, battery_data: &BatteryData) {
@@ -216,6 +248,25 @@ impl ResBattery {
         imp.power_usage.set_title_label(&i18n("Power Usage"));
         imp.power_usage.graph().set_graph_color(0x26, 0xa2, 0x69);
         imp.power_usage.graph().set_locked_max_y(None);
+        imp.power_usage.graph().set_value_formatter(convert_power);
+
+        imp.voltage_current.set_start_title_label(&i18n("Voltage"));
+        imp.voltage_current
+            .start_graph()
+            .set_graph_color(0x1c, 0x71, 0xd8);
+        imp.voltage_current.start_graph().set_locked_max_y(None);
+        imp.voltage_current
+            .start_graph()
+            .set_value_formatter(convert_voltage);
+
+        imp.voltage_current.set_end_title_label(&i18n("Current"));
+        imp.voltage_current
+            .end_graph()
+            .set_graph_color(0xe6, 0x61, 0x00);
+        imp.voltage_current.end_graph().set_locked_max_y(None);
+        imp.voltage_current
+            .end_graph()
+            .set_value_formatter(convert_current);
 
         imp.charge_cycles.set_subtitle(
             &battery_data
@@ -236,6 +287,125 @@ impl ResBattery {
             .set_subtitle(&battery.sysfs_path.file_name().unwrap().to_string_lossy());
 
         imp.set_tab_detail_string(&battery.sysfs_path.file_name().unwrap().to_string_lossy());
+
+        self.setup_charge_thresholds(battery.charge_thresholds().ok());
+
+        imp.battery.replace(Some(battery.clone()));
+    }
+
+    pub fn setup_signals(&self) {
+        trace!("Setting up ResBattery signals…");
+
+        let imp = self.imp();
+
+        imp.charge_start_threshold.connect_value_notify(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |_| {
+                this.refresh_apply_charge_thresholds_button();
+            }
+        ));
+
+        imp.charge_end_threshold.connect_value_notify(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |_| {
+                this.refresh_apply_charge_thresholds_button();
+            }
+        ));
+
+        imp.apply_charge_thresholds_button.connect_clicked(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |button| {
+                let Some(battery) = this.imp().battery.borrow().clone() else {
+                    return;
+                };
+
+                let thresholds = this.selected_charge_thresholds();
+
+                button.set_sensitive(false);
+
+                MainContext::default().spawn_local(clone!(
+                    #[weak]
+                    this,
+                    async move {
+                        // writing the thresholds may involve waiting for the user to authenticate
+                        let result = gio::spawn_blocking(move || {
+                            battery
+                                .set_charge_thresholds(thresholds)
+                                .map(|()| battery.charge_thresholds().ok())
+                        })
+                        .await;
+
+                        let toast_message = match result {
+                            Ok(Ok(new_thresholds)) => {
+                                this.setup_charge_thresholds(new_thresholds);
+                                i18n("Charge limits applied")
+                            }
+                            Ok(Err(e)) => {
+                                warn!("Unable to set charge thresholds: {e}");
+                                this.refresh_apply_charge_thresholds_button();
+                                i18n("There was a problem applying the charge limits")
+                            }
+                            Err(_) => {
+                                this.refresh_apply_charge_thresholds_button();
+                                i18n("There was a problem applying the charge limits")
+                            }
+                        };
+
+                        this.imp()
+                            .toast_overlay
+                            .add_toast(Toast::new(&toast_message));
+                    }
+                ));
+            }
+        ));
+    }
+
+    /// Shows the charge thresholds the battery currently has, the group is hidden if the battery doesn't support them
+    fn setup_charge_thresholds(&self, thresholds: Option<ChargeThresholds>) {
+        let imp = self.imp();
+
+        imp.charge_thresholds.set(thresholds);
+
+        imp.charge_thresholds_group
+            .set_visible(thresholds.is_some());
+
+        if let Some(thresholds) = thresholds {
+            imp.charge_start_threshold
+                .set_visible(thresholds.start.is_some());
+            if let Some(start) = thresholds.start {
+                imp.charge_start_threshold.set_value(start.into());
+            }
+
+            imp.charge_end_threshold.set_value(thresholds.end.into());
+        }
+
+        self.refresh_apply_charge_thresholds_button();
+    }
+
+    fn selected_charge_thresholds(&self) -> ChargeThresholds {
+        let imp = self.imp();
+
+        ChargeThresholds {
+            start: imp
+                .charge_start_threshold
+                .is_visible()
+                .then(|| imp.charge_start_threshold.value() as u8),
+            end: imp.charge_end_threshold.value() as u8,
+        }
+    }
+
+    /// The thresholds can only be applied if they differ from the current ones and charging starts below where it
+    /// ends
+    fn refresh_apply_charge_thresholds_button(&self) {
+        let imp = self.imp();
+
+        let selected = self.selected_charge_thresholds();
+
+        imp.apply_charge_thresholds_button
+            .set_sensitive(imp.charge_thresholds.get() != Some(selected) && selected.is_valid());
     }
 
     pub fn refresh_page(&self, battery_data: BatteryData) {
@@ -293,6 +463,50 @@ impl ResBattery {
 
         self.set_tab_usage_string(usage_string);
 
+        imp.voltage_current
+            .set_visible(battery_data.voltage.is_ok() || battery_data.current.is_ok());
+
+        if let Ok(voltage) = battery_data.voltage {
+            imp.voltage_current.start_graph().push_data_point(voltage);
+
+            let formatted_highest_voltage =
+                convert_voltage(imp.voltage_current.start_graph().get_highest_value());
+
+            imp.voltage_current.start_graph().set_visible(true);
+            imp.voltage_current.set_start_subtitle(&format!(
+                "{} · {} {formatted_highest_voltage}",
+                convert_voltage(voltage),
+                i18n("Highest:")
+            ));
+        } else {
+            imp.voltage_current.start_graph().set_visible(false);
+            imp.voltage_current.set_start_subtitle(&i18n("N/A"));
+        }
+
+        if let Ok(current) = battery_data.current {
+            imp.voltage_current.end_graph().push_data_point(current);
+
+            let formatted_highest_current =
+                convert_current(imp.voltage_current.end_graph().get_highest_value());
+
+            imp.voltage_current.end_graph().set_visible(true);
+            imp.voltage_current.set_end_subtitle(&format!(
+                "{} · {} {formatted_highest_current}",
+                convert_current(current),
+                i18n("Highest:")
+            ));
+        } else {
+            imp.voltage_current.end_graph().set_visible(false);
+            imp.voltage_current.set_end_subtitle(&i18n("N/A"));
+        }
+
+        if let Ok(full_capacity) = battery_data.full_capacity {
+            imp.full_capacity
+                .set_subtitle(&convert_energy(full_capacity, false));
+        } else {
+            imp.full_capacity.set_subtitle(&i18n("N/A"));
+        }
+
         if let Ok(health) = battery_data.health {
             imp.health
                 .set_subtitle(&format!("{} %", (health * 100.0).round()));
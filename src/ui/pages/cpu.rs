@@ -1,14 +1,18 @@
+use std::time::{Duration, SystemTime};
+
 use adw::{prelude::*, subclass::prelude::*};
-use gtk::glib::{self, clone};
-use gtk::FlowBoxChild;
-use log::trace;
+use gtk::glib::{self, clone, MainContext};
+use gtk::{gio, FlowBoxChild};
+use log::{debug, trace, warn};
 
 use crate::config::PROFILE;
 use crate::i18n::{i18n, i18n_f};
 use crate::ui::widgets::graph_box::ResGraphBox;
-use crate::utils::cpu::{CpuData, CpuInfo};
+use crate::utils::cpu::{CpuCache, CpuData, CpuInfo, KernelCounters, TaskCounts};
+use crate::utils::history;
+use crate::utils::power_profile::PowerProfile;
 use crate::utils::settings::SETTINGS;
-use crate::utils::units::{convert_frequency, convert_temperature};
+use crate::utils::units::{convert_frequency, convert_storage, convert_temperature};
 use crate::utils::{FiniteOr, NUM_CPUS};
 
 pub const TAB_ID: &str = "cpu";
@@ -53,13 +57,43 @@ mod imp {
         #[template_child]
         pub virtualization: TemplateChild<adw::ActionRow>,
         #[template_child]
+        pub caches_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
         pub architecture: TemplateChild<adw::ActionRow>,
         #[template_child]
+        pub frequency: TemplateChild<ResGraphBox>,
+        #[template_child]
+        pub power_profile: TemplateChild<adw::ComboRow>,
+        #[template_child]
         pub temperature: TemplateChild<ResGraphBox>,
+        #[template_child]
+        pub sensor_temperatures: TemplateChild<adw::ExpanderRow>,
+        #[template_child]
+        pub processes: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub threads: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub running_tasks: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub blocked_tasks: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub load_average: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub context_switches: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub interrupts: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub forks: TemplateChild<adw::ActionRow>,
         pub thread_graphs: RefCell<Vec<ResGraphBox>>,
+        pub sensor_rows: RefCell<Vec<adw::ActionRow>>,
         pub old_total_usage: Cell<(u64, u64)>,
         pub old_thread_usages: RefCell<Vec<(u64, u64)>>,
         pub logical_cpus_amount: Cell<usize>,
+        pub old_kernel_counters: Cell<KernelCounters>,
+        pub last_timestamp: Cell<SystemTime>,
+        /// The profiles offered in `power_profile`, in the same order
+        pub power_profiles: RefCell<Vec<PowerProfile>>,
+        pub active_power_profile: Cell<Option<PowerProfile>>,
 
         #[property(get)]
         uses_progress_bar: Cell<bool>,
@@ -113,9 +147,22 @@ mod imp {
                 physical_cpus: Default::default(),
                 sockets: Default::default(),
                 virtualization: Default::default(),
+                caches_group: Default::default(),
                 architecture: Default::default(),
+                frequency: Default::default(),
+                power_profile: Default::default(),
                 temperature: Default::default(),
+                sensor_temperatures: Default::default(),
+                processes: Default::default(),
+                threads: Default::default(),
+                running_tasks: Default::default(),
+                blocked_tasks: Default::default(),
+                load_average: Default::default(),
+                context_switches: Default::default(),
+                interrupts: Default::default(),
+                forks: Default::default(),
                 thread_graphs: Default::default(),
+                sensor_rows: Default::default(),
                 uses_progress_bar: Cell::new(true),
                 main_graph_color: glib::Bytes::from_static(&super::ResCPU::MAIN_GRAPH_COLOR),
                 icon: RefCell::new(ThemedIcon::new("processor-symbolic").into()),
@@ -127,6 +174,14 @@ mod imp {
                 old_total_usage: Cell::default(),
                 old_thread_usages: RefCell::default(),
                 logical_cpus_amount: Cell::default(),
+                old_kernel_counters: Cell::default(),
+                power_profiles: RefCell::default(),
+                active_power_profile: Cell::default(),
+                last_timestamp: Cell::new(
+                    SystemTime::now()
+                        .checked_sub(Duration::from_secs(1))
+                        .unwrap(),
+                ),
                 graph_locked_max_y: Cell::new(true),
                 primary_ord: Cell::new(CPU_PRIMARY_ORD),
                 secondary_ord: Default::default(),
@@ -190,7 +245,7 @@ impl Default for ResCPU {
 }
 
 impl ResCPU {
-    const MAIN_GRAPH_COLOR: [u8; 3] = [0x35, 0x84, 0xe4];
+    pub const MAIN_GRAPH_COLOR: [u8; 3] = [0x35, 0x84, 0xe4];
 
     pub fn new() -> Self {
         trace!("Creating ResCPU GObject…");
@@ -214,6 +269,10 @@ impl ResCPU {
             new_thread_usages,
             temperature: _,
             frequencies: _,
+            sensor_temperatures: _,
+            task_counts: _,
+            kernel_counters: _,
+            power_profile,
         } = CpuData::new(logical_cpus);
 
         let old_total_usage = new_thread_usages
@@ -236,6 +295,7 @@ impl ResCPU {
 
         imp.total_cpu.set_title_label(&i18n("Total Usage"));
         imp.total_cpu.set_subtitle(&i18n("N/A"));
+        imp.total_cpu.set_history_series(history::CPU_USAGE);
         imp.total_cpu.graph().set_graph_color(
             Self::MAIN_GRAPH_COLOR[0],
             Self::MAIN_GRAPH_COLOR[1],
@@ -262,9 +322,19 @@ impl ResCPU {
             imp.thread_graphs.borrow_mut().push(thread_box);
         }
 
+        imp.frequency.set_title_label(&i18n("Average Frequency"));
+        imp.frequency.graph().set_graph_color(0x1c, 0x71, 0xd8);
+        imp.frequency.graph().set_locked_max_y(None);
+        imp.frequency.graph().set_value_formatter(convert_frequency);
+
+        self.setup_power_profiles(power_profile);
+
         imp.temperature.set_title_label(&i18n("Temperature"));
         imp.temperature.graph().set_graph_color(0x1a, 0x5f, 0xb4);
         imp.temperature.graph().set_locked_max_y(None);
+        imp.temperature
+            .graph()
+            .set_value_formatter(convert_temperature);
 
         imp.max_speed.set_subtitle(
             &cpu_info
@@ -296,11 +366,92 @@ impl ResCPU {
         imp.architecture
             .set_subtitle(&cpu_info.architecture.unwrap_or_else(|| i18n("N/A")));
 
+        self.setup_caches(&cpu_info.caches);
+
         if let Some(model_name) = cpu_info.model_name {
             imp.set_tab_detail_string(&model_name);
         }
     }
 
+    /// Offers the power profiles of power-profiles-daemon, the row is hidden if it's not running
+    fn setup_power_profiles(&self, active_power_profile: Option<PowerProfile>) {
+        let imp = self.imp();
+
+        let power_profiles = match PowerProfile::available() {
+            Ok(power_profiles) => power_profiles,
+            Err(e) => {
+                debug!("Power profiles are unavailable: {e}");
+                return;
+            }
+        };
+
+        let names: Vec<String> = power_profiles
+            .iter()
+            .map(|power_profile| power_profile.to_string())
+            .collect();
+        let model = gtk::StringList::new(&names.iter().map(String::as_str).collect::<Vec<_>>());
+
+        imp.power_profiles.replace(power_profiles);
+        imp.power_profile.set_model(Some(&model));
+        imp.power_profile.set_visible(!names.is_empty());
+
+        self.select_power_profile(active_power_profile);
+    }
+
+    /// Shows `power_profile` as the active one without activating it again
+    fn select_power_profile(&self, power_profile: Option<PowerProfile>) {
+        let imp = self.imp();
+
+        imp.active_power_profile.set(power_profile);
+
+        let position = power_profile.and_then(|power_profile| {
+            imp.power_profiles
+                .borrow()
+                .iter()
+                .position(|available| *available == power_profile)
+        });
+
+        imp.power_profile.set_sensitive(position.is_some());
+        if let Some(position) = position {
+            imp.power_profile.set_selected(position as u32);
+        }
+    }
+
+    fn setup_caches(&self, caches: &[CpuCache]) {
+        let imp = self.imp();
+
+        for cache in caches {
+            let title = match cache.cache_type.as_str() {
+                "Data" => i18n_f("L{} Data Cache", &[&cache.level.to_string()]),
+                "Instruction" => i18n_f("L{} Instruction Cache", &[&cache.level.to_string()]),
+                _ => i18n_f("L{} Cache", &[&cache.level.to_string()]),
+            };
+
+            let sharing = if cache.shared_cpus > 1 {
+                i18n_f(
+                    "Shared by {} logical CPUs",
+                    &[&cache.shared_cpus.to_string()],
+                )
+            } else {
+                i18n("Not shared")
+            };
+
+            let row = adw::ActionRow::builder()
+                .title(title)
+                .subtitle(format!(
+                    "{} · {sharing}",
+                    convert_storage(cache.size as f64, false)
+                ))
+                .css_classes(["property"])
+                .subtitle_selectable(true)
+                .build();
+
+            imp.caches_group.add(&row);
+        }
+
+        imp.caches_group.set_visible(!caches.is_empty());
+    }
+
     pub fn setup_signals(&self) {
         trace!("Setting up ResCPU signals…");
 
@@ -320,6 +471,51 @@ impl ResCPU {
         ));
 
         imp.logical_switch.set_active(SETTINGS.show_logical_cpus());
+
+        imp.power_profile.connect_selected_notify(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |row| {
+                let imp = this.imp();
+
+                let Some(power_profile) = imp
+                    .power_profiles
+                    .borrow()
+                    .get(row.selected() as usize)
+                    .copied()
+                else {
+                    return;
+                };
+
+                // the selection also changes when a refresh notices a different profile
+                if imp.active_power_profile.get() == Some(power_profile) {
+                    return;
+                }
+
+                row.set_sensitive(false);
+
+                MainContext::default().spawn_local(clone!(
+                    #[weak]
+                    this,
+                    async move {
+                        // activating a profile may involve waiting for the user to authenticate
+                        let result = gio::spawn_blocking(move || power_profile.activate()).await;
+
+                        match result {
+                            // the next refresh picks up the new profile and annotates the frequency graph
+                            Ok(Ok(())) => this.imp().power_profile.set_sensitive(true),
+                            Ok(Err(e)) => {
+                                warn!("Unable to activate power profile {power_profile:?}: {e}");
+                                this.select_power_profile(this.imp().active_power_profile.get());
+                            }
+                            Err(_) => {
+                                this.select_power_profile(this.imp().active_power_profile.get());
+                            }
+                        }
+                    }
+                ));
+            }
+        ));
     }
 
     pub fn refresh_page(&self, cpu_data: &CpuData) {
@@ -329,6 +525,10 @@ impl ResCPU {
             new_thread_usages,
             temperature,
             frequencies,
+            sensor_temperatures,
+            task_counts,
+            kernel_counters,
+            power_profile,
         } = cpu_data;
 
         let imp = self.imp();
@@ -360,6 +560,11 @@ impl ResCPU {
 
         let mut percentage_string = format!("{} %", percentage.round());
         imp.total_cpu.set_subtitle(&percentage_string);
+        imp.total_cpu.set_warning_level(
+            Some(total_fraction * 100.0),
+            SETTINGS.usage_warning_threshold(),
+            SETTINGS.usage_critical_threshold(),
+        );
 
         imp.old_total_usage.set(new_total_usage);
 
@@ -383,6 +588,11 @@ impl ResCPU {
                     ((work_thread_time as f64) / (sum_thread_delta as f64)).finite_or_default();
 
                 curr_threadbox.graph().push_data_point(thread_fraction);
+                curr_threadbox.set_warning_level(
+                    Some(thread_fraction * 100.0),
+                    SETTINGS.usage_warning_threshold(),
+                    SETTINGS.usage_critical_threshold(),
+                );
                 curr_threadbox.set_subtitle(&format!("{} %", (thread_fraction * 100.0).round()));
 
                 if let Some(frequency) = frequencies[i] {
@@ -399,6 +609,14 @@ impl ResCPU {
         }
 
         imp.temperature.graph().set_visible(temperature.is_ok());
+        imp.temperature.set_warning_level(
+            temperature
+                .as_ref()
+                .ok()
+                .map(|temperature| *temperature as f64),
+            SETTINGS.cpu_temperature_warning_threshold(),
+            SETTINGS.cpu_temperature_critical_threshold(),
+        );
 
         if let Ok(temperature) = temperature {
             let temperature_string = convert_temperature(*temperature as f64);
@@ -420,8 +638,155 @@ impl ResCPU {
             imp.temperature.set_subtitle(&i18n("N/A"));
         }
 
+        self.refresh_frequency(frequencies, *power_profile);
+
+        self.refresh_sensor_temperatures(sensor_temperatures);
+
+        self.refresh_task_counts(task_counts);
+
+        self.refresh_kernel_counters(*kernel_counters);
+
         self.set_property("usage", total_fraction);
 
         self.set_property("tab_usage_string", percentage_string);
     }
+
+    /// Graphs the average frequency of all logical CPUs and marks changes of the power profile in the graph
+    fn refresh_frequency(&self, frequencies: &[Option<u64>], power_profile: Option<PowerProfile>) {
+        let imp = self.imp();
+
+        let known_frequencies: Vec<u64> = frequencies.iter().flatten().copied().collect();
+
+        imp.frequency
+            .graph()
+            .set_visible(!known_frequencies.is_empty());
+
+        if known_frequencies.is_empty() {
+            imp.frequency.set_subtitle(&i18n("N/A"));
+        } else {
+            let average_frequency =
+                known_frequencies.iter().sum::<u64>() as f64 / known_frequencies.len() as f64;
+
+            imp.frequency
+                .set_subtitle(&convert_frequency(average_frequency));
+            imp.frequency.graph().push_data_point(average_frequency);
+        }
+
+        if power_profile != imp.active_power_profile.get() {
+            if let Some(power_profile) = power_profile {
+                imp.frequency.graph().annotate_latest_data_point(i18n_f(
+                    "Power profile changed to {}",
+                    &[&power_profile.to_string()],
+                ));
+            }
+
+            self.select_power_profile(power_profile);
+        }
+    }
+
+    /// Lists the temperatures of the single sensors, hidden if there's only the one that's already graphed
+    fn refresh_sensor_temperatures(&self, sensor_temperatures: &[(String, f32)]) {
+        let imp = self.imp();
+
+        imp.sensor_temperatures
+            .set_visible(sensor_temperatures.len() > 1);
+
+        let mut sensor_rows = imp.sensor_rows.borrow_mut();
+
+        // sensors don't come and go, so the rows only have to be rebuilt if reading one of them failed
+        if sensor_rows.len() != sensor_temperatures.len() {
+            for row in sensor_rows.drain(..) {
+                imp.sensor_temperatures.remove(&row);
+            }
+
+            for (label, _) in sensor_temperatures {
+                let row = adw::ActionRow::builder()
+                    .title(label)
+                    .subtitle_selectable(true)
+                    .css_classes(["property"])
+                    .build();
+                imp.sensor_temperatures.add_row(&row);
+                sensor_rows.push(row);
+            }
+        }
+
+        for (row, (_, temperature)) in sensor_rows.iter().zip(sensor_temperatures) {
+            row.set_subtitle(&convert_temperature(*temperature as f64));
+        }
+
+        if let Some((hottest_label, hottest_temperature)) = sensor_temperatures
+            .iter()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        {
+            imp.sensor_temperatures.set_subtitle(&i18n_f(
+                "Hottest: {} ({})",
+                &[
+                    &convert_temperature(*hottest_temperature as f64),
+                    hottest_label,
+                ],
+            ));
+        }
+    }
+
+    fn refresh_kernel_counters(&self, kernel_counters: KernelCounters) {
+        let imp = self.imp();
+
+        let time_passed = SystemTime::now()
+            .duration_since(imp.last_timestamp.get())
+            .map_or(1.0f64, |timestamp| timestamp.as_secs_f64());
+
+        let old_kernel_counters = imp.old_kernel_counters.get();
+
+        // we need two readings to calculate a rate
+        let rate_string = |new: Option<u64>, old: Option<u64>| {
+            new.zip(old).map_or_else(
+                || i18n("N/A"),
+                |(new, old)| {
+                    let rate = new.saturating_sub(old) as f64 / time_passed;
+                    i18n_f("{} per second", &[&rate.round().to_string()])
+                },
+            )
+        };
+
+        imp.context_switches.set_subtitle(&rate_string(
+            kernel_counters.context_switches,
+            old_kernel_counters.context_switches,
+        ));
+        imp.interrupts.set_subtitle(&rate_string(
+            kernel_counters.interrupts,
+            old_kernel_counters.interrupts,
+        ));
+        imp.forks.set_subtitle(&rate_string(
+            kernel_counters.forks,
+            old_kernel_counters.forks,
+        ));
+
+        imp.old_kernel_counters.set(kernel_counters);
+        imp.last_timestamp.set(SystemTime::now());
+    }
+
+    fn refresh_task_counts(&self, task_counts: &TaskCounts) {
+        let imp = self.imp();
+
+        let TaskCounts {
+            processes,
+            threads,
+            running,
+            blocked,
+            load_average,
+        } = task_counts;
+
+        let count_string =
+            |count: &Option<usize>| count.map_or_else(|| i18n("N/A"), |count| count.to_string());
+
+        imp.processes.set_subtitle(&count_string(processes));
+        imp.threads.set_subtitle(&count_string(threads));
+        imp.running_tasks.set_subtitle(&count_string(running));
+        imp.blocked_tasks.set_subtitle(&count_string(blocked));
+
+        imp.load_average.set_subtitle(&load_average.map_or_else(
+            || i18n("N/A"),
+            |[one, five, fifteen]| format!("{one:.2} · {five:.2} · {fifteen:.2}"),
+        ));
+    }
 }
@@ -0,0 +1,225 @@
+use adw::{prelude::*, subclass::prelude::*};
+use gtk::glib;
+use log::trace;
+
+use crate::config::PROFILE;
+use crate::i18n::{i18n, ni18n_f};
+use crate::ui::widgets::graph_box::ResGraphBox;
+use crate::utils::custom_gauge::{CustomGauge, CustomGaugeData};
+
+pub const TAB_ID: &str = "custom-gauges";
+
+mod imp {
+    use std::cell::{Cell, RefCell};
+
+    use crate::ui::pages::CUSTOM_GAUGES_PRIMARY_ORD;
+
+    use super::*;
+
+    use gtk::{
+        gio::{Icon, ThemedIcon},
+        glib::{ParamSpec, Properties, Value},
+        CompositeTemplate,
+    };
+
+    #[derive(CompositeTemplate, Properties)]
+    #[template(resource = "/net/nokyan/Resources/ui/pages/custom_gauges.ui")]
+    #[properties(wrapper_type = super::ResCustomGauges)]
+    pub struct ResCustomGauges {
+        #[template_child]
+        pub gauges: TemplateChild<adw::PreferencesGroup>,
+
+        pub gauge_rows: RefCell<Vec<(CustomGauge, ResGraphBox)>>,
+
+        #[property(get)]
+        uses_progress_bar: Cell<bool>,
+
+        #[property(get)]
+        icon: RefCell<Icon>,
+
+        #[property(get, set)]
+        usage: Cell<f64>,
+
+        #[property(get = Self::tab_name, type = glib::GString)]
+        tab_name: Cell<glib::GString>,
+
+        #[property(get = Self::tab_detail_string, set = Self::set_tab_detail_string, type = glib::GString)]
+        tab_detail_string: Cell<glib::GString>,
+
+        #[property(get = Self::tab_usage_string, set = Self::set_tab_usage_string, type = glib::GString)]
+        tab_usage_string: Cell<glib::GString>,
+
+        #[property(get = Self::tab_id, type = glib::GString)]
+        tab_id: Cell<glib::GString>,
+
+        #[property(get)]
+        graph_locked_max_y: Cell<bool>,
+
+        #[property(get)]
+        primary_ord: Cell<u32>,
+
+        #[property(get)]
+        secondary_ord: Cell<u32>,
+    }
+
+    impl ResCustomGauges {
+        gstring_getter_setter!(tab_name, tab_detail_string, tab_usage_string, tab_id);
+    }
+
+    impl Default for ResCustomGauges {
+        fn default() -> Self {
+            Self {
+                gauges: Default::default(),
+                gauge_rows: Default::default(),
+                uses_progress_bar: Cell::new(false),
+                icon: RefCell::new(ThemedIcon::new("device-settings-symbolic").into()),
+                usage: Default::default(),
+                tab_name: Cell::new(glib::GString::from(i18n("Custom Gauges"))),
+                tab_detail_string: Cell::new(glib::GString::new()),
+                tab_usage_string: Cell::new(glib::GString::new()),
+                tab_id: Cell::new(glib::GString::from(TAB_ID)),
+                graph_locked_max_y: Cell::new(false),
+                primary_ord: Cell::new(CUSTOM_GAUGES_PRIMARY_ORD),
+                secondary_ord: Default::default(),
+            }
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ResCustomGauges {
+        const NAME: &'static str = "ResCustomGauges";
+        type Type = super::ResCustomGauges;
+        type ParentType = adw::Bin;
+
+        fn class_init(klass: &mut Self::Class) {
+            Self::bind_template(klass);
+        }
+
+        // You must call `Widget`'s `init_template()` within `instance_init()`.
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for ResCustomGauges {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+
+            // Devel Profile
+            if PROFILE == "Devel" {
+                obj.add_css_class("devel");
+            }
+        }
+
+        fn properties() -> &'static [ParamSpec] {
+            Self::derived_properties()
+        }
+
+        fn set_property(&self, id: usize, value: &Value, pspec: &ParamSpec) {
+            self.derived_set_property(id, value, pspec);
+        }
+
+        fn property(&self, id: usize, pspec: &ParamSpec) -> Value {
+            self.derived_property(id, pspec)
+        }
+    }
+
+    impl WidgetImpl for ResCustomGauges {}
+    impl BinImpl for ResCustomGauges {}
+}
+
+glib::wrapper! {
+    pub struct ResCustomGauges(ObjectSubclass<imp::ResCustomGauges>)
+        @extends gtk::Widget, adw::Bin;
+}
+
+impl Default for ResCustomGauges {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ResCustomGauges {
+    // gauges can be about anything, so just cycle through a few distinguishable colors
+    const GRAPH_COLORS: [[u8; 3]; 5] = [
+        [0x1c, 0x71, 0xd8],
+        [0x2e, 0xc2, 0x7e],
+        [0xe6, 0x61, 0x00],
+        [0x91, 0x41, 0xac],
+        [0xc0, 0x1c, 0x28],
+    ];
+
+    pub fn new() -> Self {
+        trace!("Creating ResCustomGauges GObject…");
+
+        glib::Object::new::<Self>()
+    }
+
+    /// Replaces the graph rows with one row per gauge in `gauges`
+    fn setup_gauge_rows(&self, gauges: &[&CustomGauge]) {
+        trace!("Setting up ResCustomGauges rows…");
+
+        let imp = self.imp();
+
+        let mut gauge_rows = imp.gauge_rows.borrow_mut();
+
+        for (_, row) in gauge_rows.drain(..) {
+            imp.gauges.remove(&row);
+        }
+
+        for (i, gauge) in gauges.iter().enumerate() {
+            let row = ResGraphBox::new();
+            row.set_title_label(&gauge.label);
+            row.set_tooltip(Some(&gauge.path.to_string_lossy()));
+
+            let color = Self::GRAPH_COLORS[i % Self::GRAPH_COLORS.len()];
+            row.graph().set_graph_color(color[0], color[1], color[2]);
+            row.graph().set_locked_max_y(None);
+
+            let formatter_gauge = (*gauge).clone();
+            row.graph()
+                .set_value_formatter(move |value| formatter_gauge.format_value(value));
+
+            imp.gauges.add(&row);
+            gauge_rows.push(((*gauge).clone(), row));
+        }
+    }
+
+    pub fn refresh_page(&self, custom_gauge_data: &[CustomGaugeData]) {
+        trace!("Refreshing ResCustomGauges…");
+
+        let imp = self.imp();
+
+        let gauges: Vec<&CustomGauge> = custom_gauge_data.iter().map(|data| &data.gauge).collect();
+
+        // the gauges might have been changed in the preferences since the last refresh
+        let gauges_changed = {
+            let gauge_rows = imp.gauge_rows.borrow();
+            gauge_rows.len() != gauges.len()
+                || gauge_rows
+                    .iter()
+                    .zip(&gauges)
+                    .any(|((old_gauge, _), new_gauge)| old_gauge != *new_gauge)
+        };
+
+        if gauges_changed {
+            self.setup_gauge_rows(&gauges);
+        }
+
+        for ((_, row), data) in imp.gauge_rows.borrow().iter().zip(custom_gauge_data) {
+            let CustomGaugeData { gauge, value } = data;
+
+            row.set_subtitle(&value.map_or_else(|| i18n("N/A"), |value| gauge.format_value(value)));
+            row.graph().push_data_point(value.unwrap_or(0.0));
+        }
+
+        let gauges_amount = custom_gauge_data.len() as u32;
+        imp.set_tab_usage_string(&ni18n_f(
+            "{} gauge",
+            "{} gauges",
+            gauges_amount,
+            &[&gauges_amount.to_string()],
+        ));
+    }
+}
@@ -1,13 +1,24 @@
-use std::time::{Duration, SystemTime};
+use std::{
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
 
 use adw::{glib::property::PropertySet, prelude::*, subclass::prelude::*};
-use gtk::glib;
-use log::trace;
+use gtk::{
+    gio,
+    glib::{self, clone, MainContext},
+};
+use log::{trace, warn};
 
 use crate::config::PROFILE;
-use crate::i18n::{i18n, i18n_f};
-use crate::utils::drive::{Drive, DriveData};
-use crate::utils::units::{convert_speed, convert_storage};
+use crate::i18n::{i18n, i18n_f, ni18n_f};
+use crate::ui::dialogs::smart_dialog::ResSmartDialog;
+use crate::ui::pages::bind_device_label;
+use crate::ui::window::MainWindow;
+use crate::utils::drive::smart::{SmartAttribute, SmartData};
+use crate::utils::drive::{Drive, DriveData, EncryptedDevice, Filesystem};
+use crate::utils::settings::SETTINGS;
+use crate::utils::units::{convert_speed, convert_storage, convert_temperature, format_percentage};
 
 pub const TAB_ID_PREFIX: &str = "drive";
 
@@ -46,11 +57,32 @@ mod imp {
         #[template_child]
         pub device: TemplateChild<adw::ActionRow>,
         #[template_child]
+        pub members: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub backing_file: TemplateChild<adw::ActionRow>,
+        #[template_child]
         pub capacity: TemplateChild<adw::ActionRow>,
         #[template_child]
         pub writable: TemplateChild<adw::ActionRow>,
         #[template_child]
         pub removable: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub encryption: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub health_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub health: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub drive_temperature: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub power_on_time: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub percentage_used: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub smart_attributes: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub filesystems_group: TemplateChild<adw::PreferencesGroup>,
+        pub filesystem_rows: RefCell<Vec<(PathBuf, adw::ActionRow)>>,
         pub old_stats: RefCell<HashMap<String, usize>>,
         pub last_timestamp: Cell<SystemTime>,
 
@@ -78,6 +110,9 @@ mod imp {
         #[property(get = Self::tab_id, set = Self::set_tab_id, type = glib::GString)]
         tab_id: Cell<glib::GString>,
 
+        #[property(get = Self::device_id, set = Self::set_device_id, type = glib::GString)]
+        device_id: Cell<glib::GString>,
+
         #[property(get)]
         graph_locked_max_y: Cell<bool>,
 
@@ -89,7 +124,13 @@ mod imp {
     }
 
     impl ResDrive {
-        gstring_getter_setter!(tab_name, tab_detail_string, tab_usage_string, tab_id);
+        gstring_getter_setter!(
+            tab_name,
+            tab_detail_string,
+            tab_usage_string,
+            tab_id,
+            device_id
+        );
 
         pub fn icon(&self) -> Icon {
             let icon = self.icon.replace_with(|_| Drive::default_icon());
@@ -113,9 +154,20 @@ mod imp {
                 total_read: Default::default(),
                 total_written: Default::default(),
                 device: Default::default(),
+                members: Default::default(),
+                backing_file: Default::default(),
                 capacity: Default::default(),
                 writable: Default::default(),
                 removable: Default::default(),
+                encryption: Default::default(),
+                health_group: Default::default(),
+                health: Default::default(),
+                drive_temperature: Default::default(),
+                power_on_time: Default::default(),
+                percentage_used: Default::default(),
+                smart_attributes: Default::default(),
+                filesystems_group: Default::default(),
+                filesystem_rows: Default::default(),
                 uses_progress_bar: Cell::new(true),
                 main_graph_color: glib::Bytes::from_static(&super::ResDrive::MAIN_GRAPH_COLOR),
                 icon: RefCell::new(Drive::default_icon()),
@@ -123,6 +175,7 @@ mod imp {
                 tab_name: Cell::new(glib::GString::from(i18n("Drive"))),
                 tab_detail_string: Cell::new(glib::GString::new()),
                 tab_id: Cell::new(glib::GString::new()),
+                device_id: Cell::new(glib::GString::new()),
                 old_stats: Default::default(),
                 last_timestamp: Cell::new(
                     SystemTime::now()
@@ -227,7 +280,7 @@ impl ResDrive {
         imp.set_tab_id(&tab_id);
 
         imp.set_icon(&drive.icon());
-        imp.set_tab_name(&drive.display_name());
+        bind_device_label(self, &drive.stable_id(), &drive.display_name());
 
         imp.total_usage.set_title_label(&i18n("Drive Activity"));
         imp.total_usage.graph().set_graph_color(
@@ -239,26 +292,75 @@ impl ResDrive {
         imp.read_speed.set_title_label(&i18n("Read Speed"));
         imp.read_speed.graph().set_graph_color(0xe6, 0x61, 0x00);
         imp.read_speed.graph().set_locked_max_y(None);
+        imp.read_speed
+            .graph()
+            .set_value_formatter(|speed| convert_speed(speed, false));
 
         imp.write_speed.set_title_label(&i18n("Write Speed"));
         imp.write_speed.graph().set_graph_color(0xc6, 0x46, 0x00);
         imp.write_speed.graph().set_locked_max_y(None);
+        imp.write_speed
+            .graph()
+            .set_value_formatter(|speed| convert_speed(speed, false));
 
         imp.drive_type.set_subtitle(&drive.drive_type.to_string());
 
         imp.device.set_subtitle(&drive.block_device);
 
+        if !drive.members.is_empty() {
+            imp.members.set_subtitle(&drive.members.join(", "));
+            imp.members.set_visible(true);
+        }
+
+        if let Some(backing_file) = &drive.backing_file {
+            imp.backing_file
+                .set_subtitle(&glib::markup_escape_text(&backing_file.to_string_lossy()));
+            imp.backing_file.set_visible(true);
+        }
+
+        let block_device = drive.block_device.clone();
+        imp.smart_attributes.connect_activated(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |row| {
+                let block_device = block_device.clone();
+
+                // asking UDisks may take a while, e.g. if the drive has to wake up first
+                row.set_sensitive(false);
+
+                MainContext::default().spawn_local(clone!(
+                    #[weak]
+                    this,
+                    #[weak]
+                    row,
+                    async move {
+                        let attributes = gio::spawn_blocking(move || {
+                            SmartAttribute::read_all(&block_device)
+                                .inspect_err(|e| {
+                                    warn!("Unable to read SMART attributes of {block_device}: {e}")
+                                })
+                                .unwrap_or_default()
+                        })
+                        .await
+                        .unwrap_or_default();
+
+                        row.set_sensitive(true);
+
+                        let dialog = ResSmartDialog::new();
+                        dialog.init(&this.tab_name(), &attributes);
+                        dialog.present(Some(&MainWindow::default()));
+                    }
+                ));
+            }
+        ));
+
         imp.last_timestamp.set(
             SystemTime::now()
                 .checked_sub(Duration::from_secs(1))
                 .unwrap(),
         );
 
-        if let Some(model_name) = &drive_data.inner.model {
-            imp.set_tab_detail_string(model_name);
-        } else {
-            imp.set_tab_detail_string(&drive_data.inner.block_device);
-        }
+        imp.set_tab_detail_string(&drive.description());
 
         imp.old_stats
             .borrow_mut()
@@ -271,12 +373,16 @@ impl ResDrive {
         let imp = self.imp();
 
         let DriveData {
-            inner: _,
+            inner,
             is_virtual: _,
             writable,
             removable,
             disk_stats,
             capacity,
+            temperature,
+            smart,
+            filesystems,
+            encrypted_devices,
         } = drive_data;
 
         let time_passed = SystemTime::now()
@@ -301,11 +407,17 @@ impl ResDrive {
             imp.total_usage.graph().set_visible(true);
             imp.total_usage.graph().push_data_point(total_usage);
             imp.total_usage.set_subtitle(&percentage_string);
+            imp.total_usage.set_warning_level(
+                Some(total_usage * 100.0),
+                SETTINGS.usage_warning_threshold(),
+                SETTINGS.usage_critical_threshold(),
+            );
 
             self.set_property("usage", total_usage);
         } else {
             imp.total_usage.graph().set_visible(false);
             imp.total_usage.set_subtitle(&i18n("N/A"));
+            imp.total_usage.set_warning_level(None, 0, 0);
 
             self.set_property("usage", 0.0);
         }
@@ -416,6 +528,12 @@ impl ResDrive {
             imp.removable.set_subtitle(&i18n("N/A"));
         }
 
+        self.refresh_health(temperature.ok(), smart.as_ref());
+
+        self.refresh_encryption(&inner.block_device, &encrypted_devices);
+
+        self.refresh_filesystems(&filesystems);
+
         self.set_property(
             "tab_usage_string",
             // Translators: This is an abbreviation for "Read" and "Write". This is displayed in the sidebar so your
@@ -426,4 +544,132 @@ impl ResDrive {
         *imp.old_stats.borrow_mut() = disk_stats;
         imp.last_timestamp.set(SystemTime::now());
     }
+
+    /// Shows the SMART data of the drive along with its temperature, the group stays hidden if there's neither
+    fn refresh_health(&self, temperature: Option<f64>, smart: Option<&SmartData>) {
+        let imp = self.imp();
+
+        // the drive's sensor is read more often than the SMART data, so prefer it
+        let temperature = temperature.or_else(|| smart.and_then(|smart| smart.temperature));
+
+        imp.health_group
+            .set_visible(smart.is_some() || temperature.is_some());
+
+        imp.drive_temperature.set_visible(temperature.is_some());
+        imp.drive_temperature.remove_css_class("warning");
+        imp.drive_temperature.remove_css_class("error");
+        if let Some(temperature) = temperature {
+            imp.drive_temperature
+                .set_subtitle(&convert_temperature(temperature));
+
+            if temperature >= SETTINGS.drive_temperature_critical_threshold() as f64 {
+                imp.drive_temperature.add_css_class("error");
+            } else if temperature >= SETTINGS.drive_temperature_warning_threshold() as f64 {
+                imp.drive_temperature.add_css_class("warning");
+            }
+        }
+
+        imp.health.set_visible(smart.is_some());
+        imp.smart_attributes.set_visible(smart.is_some());
+
+        let Some(smart) = smart else {
+            imp.power_on_time.set_visible(false);
+            imp.percentage_used.set_visible(false);
+            return;
+        };
+
+        if smart.failing {
+            imp.health.set_subtitle(&i18n("Failing"));
+            imp.health.add_css_class("error");
+        } else {
+            imp.health.set_subtitle(&i18n("Good"));
+            imp.health.remove_css_class("error");
+        }
+
+        imp.power_on_time
+            .set_visible(smart.power_on_hours.is_some());
+        if let Some(power_on_hours) = smart.power_on_hours {
+            imp.power_on_time.set_subtitle(&ni18n_f(
+                "{} hour",
+                "{} hours",
+                u32::try_from(power_on_hours).unwrap_or(u32::MAX),
+                &[&power_on_hours.to_string()],
+            ));
+        }
+
+        imp.percentage_used
+            .set_visible(smart.percentage_used.is_some());
+        if let Some(percentage_used) = smart.percentage_used {
+            imp.percentage_used
+                .set_subtitle(&format_percentage(percentage_used));
+        }
+    }
+
+    /// Shows which parts of the drive are encrypted, the row stays hidden if nothing is
+    fn refresh_encryption(&self, block_device: &str, encrypted_devices: &[EncryptedDevice]) {
+        let imp = self.imp();
+
+        imp.encryption.set_visible(!encrypted_devices.is_empty());
+
+        let subtitle = match encrypted_devices {
+            [] => return,
+            // the whole drive is encrypted, no need to name it
+            [device] if device.block_device == block_device => {
+                i18n_f("Encrypted ({})", &[&device.kind])
+            }
+            devices => devices
+                .iter()
+                .map(|device| i18n_f("{}: Encrypted ({})", &[&device.block_device, &device.kind]))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        };
+
+        imp.encryption.set_subtitle(&subtitle);
+    }
+
+    fn refresh_filesystems(&self, filesystems: &[Filesystem]) {
+        let imp = self.imp();
+
+        let mut filesystem_rows = imp.filesystem_rows.borrow_mut();
+
+        // filesystems can be (un)mounted at any time, in that case start over with the rows
+        if filesystem_rows.len() != filesystems.len()
+            || filesystem_rows
+                .iter()
+                .zip(filesystems)
+                .any(|((mount_point, _), filesystem)| *mount_point != filesystem.mount_point)
+        {
+            for (_, row) in filesystem_rows.drain(..) {
+                imp.filesystems_group.remove(&row);
+            }
+
+            for filesystem in filesystems {
+                let row = adw::ActionRow::builder()
+                    .title(glib::markup_escape_text(
+                        &filesystem.mount_point.to_string_lossy(),
+                    ))
+                    .css_classes(["property"])
+                    .subtitle_selectable(true)
+                    .build();
+
+                imp.filesystems_group.add(&row);
+                filesystem_rows.push((filesystem.mount_point.clone(), row));
+            }
+
+            imp.filesystems_group
+                .set_visible(!filesystem_rows.is_empty());
+        }
+
+        for ((_, row), filesystem) in filesystem_rows.iter().zip(filesystems) {
+            row.set_subtitle(&i18n_f(
+                "{} of {} used ({} %) · {}",
+                &[
+                    &convert_storage(filesystem.used_bytes() as f64, false),
+                    &convert_storage(filesystem.total_bytes as f64, false),
+                    &(filesystem.used_fraction() * 100.0).round().to_string(),
+                    &filesystem.block_device,
+                ],
+            ));
+        }
+    }
 }
@@ -1,15 +1,28 @@
-use adw::{prelude::*, subclass::prelude::*};
-use gtk::glib::{self};
-use log::trace;
+use adw::{prelude::*, subclass::prelude::*, Toast};
+use gtk::{
+    gio,
+    glib::{self, clone, MainContext},
+};
+use log::{trace, warn};
+use process_data::GpuIdentifier;
 
 use crate::config::PROFILE;
 use crate::i18n::{i18n, i18n_f};
-use crate::utils::gpu::{Gpu, GpuData};
-use crate::utils::units::{convert_frequency, convert_power, convert_storage, convert_temperature};
+use crate::utils::app::AppsContext;
+use crate::utils::gpu::{ConnectedDisplay, DpmStates, FanSpeed, Gpu, GpuData, GpuFan};
+use crate::utils::history;
+use crate::utils::process::ProcessAction;
+use crate::utils::session_history::SessionHistory;
+use crate::utils::settings::{GpuTemperatureSensor, SETTINGS};
+use crate::utils::units::{
+    convert_frequency, convert_power, convert_storage, convert_temperature, format_percentage,
+};
 use crate::utils::FiniteOr;
 
 pub const TAB_ID_PREFIX: &str = "gpu";
 
+const MAX_PROCESS_ROWS: usize = 10;
+
 mod imp {
     use std::cell::{Cell, RefCell};
 
@@ -30,8 +43,18 @@ mod imp {
     #[template(resource = "/net/nokyan/Resources/ui/pages/gpu.ui")]
     #[properties(wrapper_type = super::ResGPU)]
     pub struct ResGPU {
+        #[template_child]
+        pub vram_banner: TemplateChild<adw::Banner>,
+        #[template_child]
+        pub session_view_button: TemplateChild<gtk::ToggleButton>,
         #[template_child]
         pub gpu_usage: TemplateChild<ResGraphBox>,
+        #[template_child]
+        pub session_usage: TemplateChild<ResGraphBox>,
+
+        /// Usage over the whole session, kept apart from the live graph so that switching views doesn't lose either
+        pub session_history: RefCell<SessionHistory>,
+
         #[template_child]
         pub encode_decode_usage: TemplateChild<ResDoubleGraphBox>,
         #[template_child]
@@ -41,8 +64,12 @@ mod imp {
         #[template_child]
         pub temperature: TemplateChild<ResGraphBox>,
         #[template_child]
+        pub fan_speed: TemplateChild<ResGraphBox>,
+        #[template_child]
         pub power_usage: TemplateChild<adw::ActionRow>,
         #[template_child]
+        pub board_power_usage: TemplateChild<adw::ActionRow>,
+        #[template_child]
         pub gpu_clockspeed: TemplateChild<adw::ActionRow>,
         #[template_child]
         pub vram_clockspeed: TemplateChild<adw::ActionRow>,
@@ -53,7 +80,52 @@ mod imp {
         #[template_child]
         pub driver_used: TemplateChild<adw::ActionRow>,
         #[template_child]
+        pub drm_nodes: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub display_role: TemplateChild<adw::ActionRow>,
+        #[template_child]
         pub max_power_cap: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub ecc_errors: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub processes_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub processes_sort_by: TemplateChild<gtk::DropDown>,
+
+        pub process_rows: RefCell<Vec<adw::ActionRow>>,
+
+        #[template_child]
+        pub toast_overlay: TemplateChild<adw::ToastOverlay>,
+        #[template_child]
+        pub sensors_group: TemplateChild<adw::PreferencesGroup>,
+
+        /// Rows of the individual temperature sensors and fans, identified by their title
+        pub sensor_rows: RefCell<Vec<(String, adw::ActionRow)>>,
+
+        #[template_child]
+        pub power_cap_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub apply_power_cap_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub power_cap: TemplateChild<adw::SpinRow>,
+
+        /// The power cap (in W) the GPU currently has, `None` if it can't be changed
+        pub current_power_cap: Cell<Option<f64>>,
+
+        pub gpu: RefCell<Option<Gpu>>,
+
+        #[template_child]
+        pub displays_group: TemplateChild<adw::PreferencesGroup>,
+
+        pub displays: RefCell<Vec<(ConnectedDisplay, adw::ActionRow)>>,
+
+        pub gpu_identifier: Cell<GpuIdentifier>,
+
+        #[allow(clippy::type_complexity)]
+        pub process_action_handler: RefCell<Option<Box<dyn Fn(libc::pid_t, ProcessAction)>>>,
+
+        #[allow(clippy::type_complexity)]
+        pub show_vram_processes_handler: RefCell<Option<Box<dyn Fn(GpuIdentifier)>>>,
 
         #[property(get)]
         uses_progress_bar: Cell<bool>,
@@ -79,6 +151,9 @@ mod imp {
         #[property(get = Self::tab_id, set = Self::set_tab_id, type = glib::GString)]
         tab_id: Cell<glib::GString>,
 
+        #[property(get = Self::device_id, set = Self::set_device_id, type = glib::GString)]
+        device_id: Cell<glib::GString>,
+
         #[property(get)]
         graph_locked_max_y: Cell<bool>,
 
@@ -90,24 +165,55 @@ mod imp {
     }
 
     impl ResGPU {
-        gstring_getter_setter!(tab_name, tab_detail_string, tab_usage_string, tab_id);
+        gstring_getter_setter!(
+            tab_name,
+            tab_detail_string,
+            tab_usage_string,
+            tab_id,
+            device_id
+        );
     }
 
     impl Default for ResGPU {
         fn default() -> Self {
             Self {
+                vram_banner: Default::default(),
+                session_view_button: Default::default(),
                 gpu_usage: Default::default(),
+                session_usage: Default::default(),
+                session_history: Default::default(),
                 encode_decode_usage: Default::default(),
                 encode_decode_combined_usage: Default::default(),
                 vram_usage: Default::default(),
                 temperature: Default::default(),
+                fan_speed: Default::default(),
                 power_usage: Default::default(),
+                board_power_usage: Default::default(),
                 gpu_clockspeed: Default::default(),
                 vram_clockspeed: Default::default(),
                 manufacturer: Default::default(),
                 pci_slot: Default::default(),
                 driver_used: Default::default(),
+                drm_nodes: Default::default(),
+                display_role: Default::default(),
                 max_power_cap: Default::default(),
+                ecc_errors: Default::default(),
+                processes_group: Default::default(),
+                processes_sort_by: Default::default(),
+                process_rows: Default::default(),
+                toast_overlay: Default::default(),
+                sensors_group: Default::default(),
+                sensor_rows: Default::default(),
+                power_cap_group: Default::default(),
+                apply_power_cap_button: Default::default(),
+                power_cap: Default::default(),
+                current_power_cap: Default::default(),
+                gpu: Default::default(),
+                displays_group: Default::default(),
+                displays: Default::default(),
+                gpu_identifier: Default::default(),
+                process_action_handler: Default::default(),
+                show_vram_processes_handler: Default::default(),
                 uses_progress_bar: Cell::new(true),
                 main_graph_color: glib::Bytes::from_static(&super::ResGPU::MAIN_GRAPH_COLOR),
                 icon: RefCell::new(ThemedIcon::new("gpu-symbolic").into()),
@@ -116,6 +222,7 @@ mod imp {
                 tab_detail_string: Cell::new(glib::GString::new()),
                 tab_usage_string: Cell::new(glib::GString::new()),
                 tab_id: Cell::new(glib::GString::new()),
+                device_id: Cell::new(glib::GString::new()),
                 graph_locked_max_y: Cell::new(true),
                 primary_ord: Cell::new(GPU_PRIMARY_ORD),
                 secondary_ord: Default::default(),
@@ -179,7 +286,7 @@ impl Default for ResGPU {
 }
 
 impl ResGPU {
-    const MAIN_GRAPH_COLOR: [u8; 3] = [0xed, 0x33, 0x3b];
+    pub const MAIN_GRAPH_COLOR: [u8; 3] = [0xed, 0x33, 0x3b];
 
     pub fn new() -> Self {
         trace!("Creating ResGPU GObject…");
@@ -190,6 +297,125 @@ impl ResGPU {
     pub fn init(&self, gpu: &Gpu, secondary_ord: u32) {
         self.set_secondary_ord(secondary_ord);
         self.setup_widgets(gpu);
+        self.setup_signals();
+    }
+
+    pub fn setup_signals(&self) {
+        trace!("Setting up ResGPU signals…");
+
+        let imp = self.imp();
+
+        imp.session_view_button.connect_toggled(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |button| {
+                let imp = this.imp();
+                imp.gpu_usage.set_visible(!button.is_active());
+                imp.session_usage.set_visible(button.is_active());
+                this.refresh_session_usage();
+            }
+        ));
+
+        imp.vram_banner.connect_button_clicked(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |_| {
+                let imp = this.imp();
+                if let Some(handler) = &*imp.show_vram_processes_handler.borrow() {
+                    handler(imp.gpu_identifier.get());
+                };
+            }
+        ));
+
+        imp.power_cap.connect_value_notify(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |_| {
+                this.refresh_apply_power_cap_button();
+            }
+        ));
+
+        imp.apply_power_cap_button.connect_clicked(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |button| {
+                let Some(gpu) = this.imp().gpu.borrow().clone() else {
+                    return;
+                };
+
+                let power_cap = this.imp().power_cap.value();
+
+                button.set_sensitive(false);
+
+                MainContext::default().spawn_local(clone!(
+                    #[weak]
+                    this,
+                    async move {
+                        // setting the power cap may involve waiting for the user to authenticate
+                        let result = gio::spawn_blocking(move || {
+                            gpu.set_power_cap(power_cap)
+                                .map(|()| (gpu.power_cap().ok(), gpu.power_cap_max().ok()))
+                        })
+                        .await;
+
+                        let toast_message = match result {
+                            Ok(Ok((new_power_cap, power_cap_max))) => {
+                                this.setup_power_cap(new_power_cap, power_cap_max);
+                                i18n("Power cap applied")
+                            }
+                            Ok(Err(e)) => {
+                                warn!("Unable to set power cap: {e}");
+                                this.refresh_apply_power_cap_button();
+                                i18n("There was a problem applying the power cap")
+                            }
+                            Err(_) => {
+                                this.refresh_apply_power_cap_button();
+                                i18n("There was a problem applying the power cap")
+                            }
+                        };
+
+                        this.imp()
+                            .toast_overlay
+                            .add_toast(Toast::new(&toast_message));
+                    }
+                ));
+            }
+        ));
+    }
+
+    /// Shows the power cap the GPU currently has, the group is hidden if the GPU doesn't report both its current and
+    /// its maximum power cap
+    fn setup_power_cap(&self, power_cap: Option<f64>, power_cap_max: Option<f64>) {
+        let imp = self.imp();
+
+        let power_cap = power_cap
+            .zip(power_cap_max)
+            .filter(|(power_cap, power_cap_max)| *power_cap > 0.0 && *power_cap_max > 0.0);
+
+        imp.current_power_cap
+            .set(power_cap.map(|(power_cap, _)| power_cap.round()));
+
+        imp.power_cap_group.set_visible(power_cap.is_some());
+
+        if let Some((power_cap, power_cap_max)) = power_cap {
+            imp.power_cap
+                .adjustment()
+                .set_upper(power_cap_max.round().max(power_cap.round()));
+            imp.power_cap.set_value(power_cap.round());
+        }
+
+        self.refresh_apply_power_cap_button();
+    }
+
+    /// The power cap can only be applied if it differs from the current one
+    fn refresh_apply_power_cap_button(&self) {
+        let imp = self.imp();
+
+        imp.apply_power_cap_button.set_sensitive(
+            imp.current_power_cap
+                .get()
+                .is_some_and(|current| current != imp.power_cap.value()),
+        );
     }
 
     pub fn setup_widgets(&self, gpu: &Gpu) {
@@ -200,13 +426,27 @@ impl ResGPU {
         let tab_id = format!("{}-{}", TAB_ID_PREFIX, &gpu.gpu_identifier());
         imp.set_tab_id(&tab_id);
 
+        imp.gpu_identifier.set(gpu.gpu_identifier());
+
+        imp.gpu.replace(Some(gpu.clone()));
+
         imp.gpu_usage.set_title_label(&i18n("Total Usage"));
+        imp.gpu_usage
+            .set_history_series(history::gpu_usage(gpu.gpu_identifier()));
         imp.gpu_usage.graph().set_graph_color(
             Self::MAIN_GRAPH_COLOR[0],
             Self::MAIN_GRAPH_COLOR[1],
             Self::MAIN_GRAPH_COLOR[2],
         );
 
+        imp.session_usage
+            .set_title_label(&i18n("Total Usage During This Session"));
+        imp.session_usage.graph().set_graph_color(
+            Self::MAIN_GRAPH_COLOR[0],
+            Self::MAIN_GRAPH_COLOR[1],
+            Self::MAIN_GRAPH_COLOR[2],
+        );
+
         imp.encode_decode_combined_usage
             .set_title_label(&i18n("Video Encoder/Decoder Usage"));
         imp.encode_decode_combined_usage
@@ -225,11 +465,19 @@ impl ResGPU {
             .set_graph_color(0xe0, 0x1b, 0x24);
 
         imp.vram_usage.set_title_label(&i18n("Video Memory Usage"));
+        imp.vram_usage
+            .set_history_series(history::gpu_vram_usage(gpu.gpu_identifier()));
         imp.vram_usage.graph().set_graph_color(0xc0, 0x1c, 0x28);
 
         imp.temperature.set_title_label(&i18n("Temperature"));
         imp.temperature.graph().set_graph_color(0xa5, 0x1d, 0x2d);
         imp.temperature.graph().set_locked_max_y(None);
+        imp.temperature
+            .graph()
+            .set_value_formatter(convert_temperature);
+
+        imp.fan_speed.set_title_label(&i18n("Fan Speed"));
+        imp.fan_speed.graph().set_graph_color(0x1c, 0x71, 0xd8);
 
         imp.manufacturer.set_subtitle(
             &gpu.get_vendor()
@@ -245,6 +493,13 @@ impl ResGPU {
 
         imp.driver_used.set_subtitle(&gpu.driver());
 
+        let drm_nodes = [Some(gpu.card_node()), gpu.render_node()]
+            .into_iter()
+            .flatten()
+            .map(|node| format!("/dev/dri/{node}"))
+            .collect::<Vec<_>>();
+        imp.drm_nodes.set_subtitle(&drm_nodes.join(", "));
+
         if gpu.combined_media_engine().unwrap_or_default() {
             imp.encode_decode_combined_usage.set_visible(true);
             imp.encode_decode_usage.set_visible(false);
@@ -256,6 +511,249 @@ impl ResGPU {
         if let Ok(model_name) = gpu.name() {
             imp.set_tab_detail_string(&model_name);
         }
+
+        // the power cap is set through the GPU's PCI slot
+        if matches!(gpu.gpu_identifier(), GpuIdentifier::PciSlot(_)) {
+            self.setup_power_cap(gpu.power_cap().ok(), gpu.power_cap_max().ok());
+        } else {
+            self.setup_power_cap(None, None);
+        }
+    }
+
+    /// Sets the function that is called when the user wants to end or kill a process from this page's process list
+    pub fn connect_process_action<F: Fn(libc::pid_t, ProcessAction) + 'static>(&self, f: F) {
+        *self.imp().process_action_handler.borrow_mut() = Some(Box::new(f));
+    }
+
+    /// Sets the function that is called when the user wants to see the processes using this GPU's video memory
+    pub fn connect_show_vram_processes<F: Fn(GpuIdentifier) + 'static>(&self, f: F) {
+        *self.imp().show_vram_processes_handler.borrow_mut() = Some(Box::new(f));
+    }
+
+    /// Shows a warning if at least `SETTINGS.gpu_vram_warning_threshold()` percent of the video memory are used
+    fn refresh_vram_banner(&self, used_vram_fraction: Option<f64>) {
+        let imp = self.imp();
+
+        let threshold = f64::from(SETTINGS.gpu_vram_warning_threshold()) / 100.0;
+
+        let Some(used_vram_fraction) = used_vram_fraction.filter(|fraction| *fraction >= threshold)
+        else {
+            imp.vram_banner.set_revealed(false);
+            return;
+        };
+
+        imp.vram_banner.set_title(&i18n_f(
+            "Video memory is almost full ({})",
+            &[&format_percentage(used_vram_fraction * 100.0)],
+        ));
+
+        // there's nowhere to jump to if the processes page is unavailable
+        let processes_available =
+            imp.show_vram_processes_handler.borrow().is_some() && SETTINGS.show_processes_page();
+        let button_label = processes_available.then(|| i18n("Show Processes"));
+        imp.vram_banner.set_button_label(button_label.as_deref());
+
+        imp.vram_banner.set_revealed(true);
+    }
+
+    fn refresh_displays(&self, connected_displays: &[ConnectedDisplay]) {
+        let imp = self.imp();
+
+        let mut displays = imp.displays.borrow_mut();
+
+        // displays are rarely (dis)connected, so only rebuild the rows if something has changed
+        if displays.len() == connected_displays.len()
+            && displays
+                .iter()
+                .zip(connected_displays)
+                .all(|((old, _), new)| old == new)
+        {
+            return;
+        }
+
+        for (_, row) in displays.drain(..) {
+            imp.displays_group.remove(&row);
+        }
+
+        for display in connected_displays {
+            let mode = display
+                .preferred_mode
+                .clone()
+                .unwrap_or_else(|| i18n("Unknown mode"));
+
+            let subtitle = if display.enabled {
+                mode
+            } else {
+                i18n_f("{} · Turned off", &[&mode])
+            };
+
+            let title = display.monitor_name.as_ref().map_or_else(
+                || display.connector.clone(),
+                |monitor_name| format!("{monitor_name} ({})", display.connector),
+            );
+
+            let row = adw::ActionRow::builder()
+                .title(glib::markup_escape_text(&title))
+                .subtitle(subtitle)
+                .css_classes(["property"])
+                .subtitle_selectable(true)
+                .build();
+
+            imp.displays_group.add(&row);
+            displays.push((display.clone(), row));
+        }
+
+        imp.displays_group.set_visible(!displays.is_empty());
+    }
+
+    pub fn refresh_processes(&self, apps_context: &AppsContext) {
+        trace!("Refreshing ResGPU processes…");
+
+        let imp = self.imp();
+
+        let gpu_identifier = imp.gpu_identifier.get();
+
+        let mut processes = apps_context
+            .processes_iter()
+            .filter_map(|process| {
+                let usage = process.gpu_usage_of(&gpu_identifier)?;
+                let mem_usage = process.gpu_mem_usage_of(&gpu_identifier).unwrap_or(0);
+                Some((process, usage, mem_usage))
+            })
+            .filter(|(_, usage, mem_usage)| *usage > 0.0 || *mem_usage > 0)
+            .collect::<Vec<_>>();
+
+        // 0 = GPU usage, 1 = video memory usage
+        if imp.processes_sort_by.selected() == 1 {
+            processes.sort_by(|(_, usage_a, mem_a), (_, usage_b, mem_b)| {
+                mem_b.cmp(mem_a).then(usage_b.total_cmp(usage_a))
+            });
+        } else {
+            processes.sort_by(|(_, usage_a, mem_a), (_, usage_b, mem_b)| {
+                usage_b.total_cmp(usage_a).then(mem_b.cmp(mem_a))
+            });
+        }
+
+        processes.truncate(MAX_PROCESS_ROWS);
+
+        let mut process_rows = imp.process_rows.borrow_mut();
+
+        for row in process_rows.drain(..) {
+            imp.processes_group.remove(&row);
+        }
+
+        for (process, usage, mem_usage) in processes {
+            let row = adw::ActionRow::builder()
+                .title(glib::markup_escape_text(&process.display_name))
+                .subtitle(i18n_f(
+                    "GPU: {} % · Video Memory: {}",
+                    &[
+                        &(usage * 100.0).round().to_string(),
+                        &convert_storage(mem_usage as f64, false),
+                    ],
+                ))
+                .build();
+
+            row.add_prefix(&gtk::Image::from_gicon(&process.icon));
+
+            if !process.can_be_signalled() {
+                let lock = gtk::Image::builder()
+                    .icon_name("changes-prevent-symbolic")
+                    .tooltip_text(i18n(
+                        "This process belongs to another user, ending it requires administrator privileges",
+                    ))
+                    .build();
+                row.add_suffix(&lock);
+            }
+
+            let pid = process.data.pid;
+
+            for (label, action) in [
+                (i18n("End"), ProcessAction::TERM),
+                (i18n("Kill"), ProcessAction::KILL),
+            ] {
+                let button = gtk::Button::builder()
+                    .label(label)
+                    .valign(gtk::Align::Center)
+                    .css_classes(["flat"])
+                    .build();
+
+                button.connect_clicked(clone!(
+                    #[weak(rename_to = this)]
+                    self,
+                    move |_| {
+                        if let Some(handler) = &*this.imp().process_action_handler.borrow() {
+                            handler(pid, action);
+                        };
+                    }
+                ));
+
+                row.add_suffix(&button);
+            }
+
+            imp.processes_group.add(&row);
+            process_rows.push(row);
+        }
+
+        imp.processes_group.set_visible(!process_rows.is_empty());
+    }
+
+    /// Shows the downsampled session history in the session graph, if it's currently visible
+    fn refresh_session_usage(&self) {
+        let imp = self.imp();
+
+        if !imp.session_usage.is_visible() {
+            return;
+        }
+
+        let session_history = imp.session_history.borrow();
+
+        imp.session_usage
+            .set_subtitle(&session_history.average().map_or_else(
+                || i18n("N/A"),
+                |average| i18n_f("{} on average", &[&format_percentage(average * 100.0)]),
+            ));
+        imp.session_usage
+            .graph()
+            .set_data_points(&session_history.resample(SETTINGS.graph_data_points() as usize));
+    }
+
+    /// Formats the current clock along with the active DPM state and the range of all states, if the GPU lists them.
+    /// If `current` is unknown, the frequency of the active state is used instead.
+    fn clock_string(current: Option<f64>, dpm_states: Option<&DpmStates>) -> String {
+        let active_state = dpm_states.and_then(DpmStates::active_state);
+
+        let Some(current) = current.or_else(|| active_state.map(|state| state.frequency)) else {
+            return i18n("N/A");
+        };
+
+        // a single state doesn't make for much of a range
+        let Some((dpm_states, min, max)) = dpm_states
+            .filter(|dpm_states| dpm_states.states.len() > 1)
+            .and_then(|dpm_states| {
+                Some((
+                    dpm_states,
+                    dpm_states.min_frequency()?,
+                    dpm_states.max_frequency()?,
+                ))
+            })
+        else {
+            return convert_frequency(current);
+        };
+
+        let current = convert_frequency(current);
+        let min = convert_frequency(min);
+        let max = convert_frequency(max);
+
+        if let Some(active_state) = dpm_states.active_state() {
+            i18n_f(
+                // Translators: The second placeholder is the name of the active power state of the GPU, usually a number
+                "{} (State {}) · Min: {} · Max: {}",
+                &[&current, &active_state.label, &min, &max],
+            )
+        } else {
+            i18n_f("{} · Min: {} · Max: {}", &[&current, &min, &max])
+        }
     }
 
     pub fn refresh_page(&self, gpu_data: &GpuData) {
@@ -272,10 +770,21 @@ impl ResGPU {
             used_vram,
             clock_speed,
             vram_speed,
-            temperature,
+            clock_states,
+            vram_clock_states,
+            temperature: _,
+            sensor_temperatures: _,
             power_usage,
+            board_power_usage,
             power_cap,
             power_cap_max,
+            ecc_errors,
+            fan_speed: _,
+            fan_speed_max: _,
+            fans: _,
+            connected_displays,
+            boot_vga: _,
+            primary_display,
             nvidia: _,
         } = gpu_data;
 
@@ -290,6 +799,19 @@ impl ResGPU {
             .push_data_point(usage_fraction.unwrap_or(0.0));
         imp.gpu_usage.graph().set_visible(usage_fraction.is_some());
 
+        if let Some(usage_fraction) = usage_fraction {
+            imp.session_history.borrow_mut().push(*usage_fraction);
+        }
+        imp.session_usage
+            .graph()
+            .set_visible(usage_fraction.is_some());
+        self.refresh_session_usage();
+        imp.gpu_usage.set_warning_level(
+            usage_fraction.map(|fraction| fraction * 100.0),
+            SETTINGS.usage_warning_threshold(),
+            SETTINGS.usage_critical_threshold(),
+        );
+
         // encode_fraction could be the combined usage of encoder and decoder for Intel GPUs and newer AMD GPUs
         if let Some(encode_fraction) = encode_fraction {
             imp.encode_decode_usage
@@ -362,31 +884,85 @@ impl ResGPU {
             .graph()
             .set_visible(used_vram_fraction.is_some());
 
-        let mut power_string = power_usage.map_or_else(|| i18n("N/A"), convert_power);
+        self.refresh_vram_banner(used_vram_fraction);
 
-        if let Some(power_cap) = power_cap {
-            power_string.push_str(&format!(" / {}", convert_power(*power_cap)));
-        }
+        let power_cap_string = power_cap.map_or_else(String::new, |power_cap| {
+            format!(" / {}", convert_power(power_cap))
+        });
+
+        let power_string = power_usage.map_or_else(
+            || i18n("N/A"),
+            |power_usage| format!("{}{power_cap_string}", convert_power(power_usage)),
+        );
 
         imp.power_usage.set_subtitle(&power_string);
 
-        if let Some(gpu_clockspeed) = clock_speed {
-            imp.gpu_clockspeed
-                .set_subtitle(&convert_frequency(*gpu_clockspeed));
-        } else {
-            imp.gpu_clockspeed.set_subtitle(&i18n("N/A"));
-        }
+        // if the GPU reports both chip and board power, label them accordingly, otherwise just stick to the plain
+        // "Power Usage" row
+        if let Some(board_power_usage) = board_power_usage {
+            imp.board_power_usage.set_visible(true);
 
-        if let Some(vram_clockspeed) = vram_speed {
-            imp.vram_clockspeed
-                .set_subtitle(&convert_frequency(*vram_clockspeed));
+            if power_usage.is_some() {
+                imp.board_power_usage
+                    .set_subtitle(&convert_power(*board_power_usage));
+                imp.power_usage.set_title(&i18n("Chip Power Usage"));
+                imp.power_usage.set_visible(true);
+            } else {
+                imp.board_power_usage.set_subtitle(&format!(
+                    "{}{power_cap_string}",
+                    convert_power(*board_power_usage)
+                ));
+                imp.power_usage.set_visible(false);
+            }
         } else {
-            imp.vram_clockspeed.set_subtitle(&i18n("N/A"));
+            imp.power_usage.set_title(&i18n("Power Usage"));
+            imp.power_usage.set_visible(true);
+            imp.board_power_usage.set_visible(false);
         }
 
+        imp.gpu_clockspeed
+            .set_subtitle(&Self::clock_string(*clock_speed, clock_states.as_ref()));
+
+        imp.vram_clockspeed
+            .set_subtitle(&Self::clock_string(*vram_speed, vram_clock_states.as_ref()));
+
         imp.max_power_cap
             .set_subtitle(&power_cap_max.map_or_else(|| i18n("N/A"), convert_power));
 
+        // GPUs without ECC memory don't report anything, so don't bother showing the row for them
+        if let Some(ecc_errors) = ecc_errors {
+            imp.ecc_errors.set_visible(true);
+            imp.ecc_errors.set_subtitle(&i18n_f(
+                "{} corrected · {} uncorrected",
+                &[
+                    &ecc_errors.corrected.to_string(),
+                    &ecc_errors.uncorrected.to_string(),
+                ],
+            ));
+        } else {
+            imp.ecc_errors.set_visible(false);
+        }
+
+        let connectors = connected_displays
+            .iter()
+            .map(|display| display.connector.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let display_role = match (*primary_display, connectors.is_empty()) {
+            (true, true) => i18n("Primary Display"),
+            (true, false) => i18n_f("Primary Display ({})", &[&connectors]),
+            (false, true) => i18n("Render only"),
+            (false, false) => i18n_f("Display ({})", &[&connectors]),
+        };
+        imp.display_role.set_subtitle(&display_role);
+
+        self.refresh_displays(connected_displays);
+
+        self.refresh_fan_speed(gpu_data);
+
+        self.refresh_sensor_rows(gpu_data);
+
         self.set_property("usage", usage_fraction.unwrap_or(0.0));
 
         if used_vram_fraction.is_some() {
@@ -396,10 +972,17 @@ impl ResGPU {
             usage_percentage_string.push_str(&i18n_f("Memory: {}", &[&vram_percentage_string]));
         }
 
+        let temperature = gpu_data.headline_temperature(SETTINGS.gpu_temperature_sensor());
+
         imp.temperature.graph().set_visible(temperature.is_some());
+        imp.temperature.set_warning_level(
+            temperature,
+            SETTINGS.gpu_temperature_warning_threshold(),
+            SETTINGS.gpu_temperature_critical_threshold(),
+        );
 
         if let Some(temperature) = temperature {
-            let temperature_string = convert_temperature(*temperature);
+            let temperature_string = convert_temperature(temperature);
 
             let highest_temperature_string =
                 convert_temperature(imp.temperature.graph().get_highest_value());
@@ -410,7 +993,7 @@ impl ResGPU {
                 i18n("Highest:"),
                 highest_temperature_string
             ));
-            imp.temperature.graph().push_data_point(*temperature);
+            imp.temperature.graph().push_data_point(temperature);
 
             usage_percentage_string.push_str(" · ");
             usage_percentage_string.push_str(&temperature_string);
@@ -420,4 +1003,130 @@ impl ResGPU {
 
         self.set_property("tab_usage_string", &usage_percentage_string);
     }
+
+    fn refresh_fan_speed(&self, gpu_data: &GpuData) {
+        let imp = self.imp();
+
+        // GPUs without a fan (e.g. integrated ones) don't report a speed at all
+        imp.fan_speed.set_visible(gpu_data.fan_speed.is_some());
+
+        let Some(fan_speed) = gpu_data.fan_speed else {
+            return;
+        };
+
+        let fraction = gpu_data.fan_speed_fraction();
+        let percentage_string = fraction.map(|fraction| format_percentage(fraction * 100.0));
+
+        imp.fan_speed
+            .set_subtitle(&format_fan_speed(fan_speed, percentage_string));
+
+        // the graph shows the speed relative to the maximum whenever possible so that it can't be mistaken for an
+        // auto-scaled graph
+        let graph = imp.fan_speed.graph();
+        match (fan_speed, fraction) {
+            (_, Some(fraction)) => {
+                graph.set_locked_max_y(Some(1.0));
+                graph.set_value_formatter(|fraction| format_percentage(fraction * 100.0));
+                graph.push_data_point(fraction);
+            }
+            (FanSpeed::Rpm(rpm), None) => {
+                graph.set_locked_max_y(None);
+                graph.set_value_formatter(format_rpm);
+                graph.push_data_point(rpm);
+            }
+            (FanSpeed::Fraction(_), None) => {}
+        }
+    }
+
+    /// Shows every temperature sensor and fan in its own row, as long as there's more than one of them and the graphs
+    /// above can only show one
+    fn refresh_sensor_rows(&self, gpu_data: &GpuData) {
+        let imp = self.imp();
+
+        let mut rows = Vec::new();
+
+        if gpu_data.sensor_temperatures.len() > 1 {
+            rows.extend(
+                gpu_data
+                    .sensor_temperatures
+                    .iter()
+                    .map(|(sensor, temperature)| {
+                        (
+                            temperature_sensor_title(*sensor),
+                            convert_temperature(*temperature),
+                        )
+                    }),
+            );
+        }
+
+        if gpu_data.fans.len() > 1 {
+            rows.extend(
+                gpu_data
+                    .fans
+                    .iter()
+                    .enumerate()
+                    .map(|(i, fan)| (i18n_f("Fan {}", &[&(i + 1).to_string()]), format_fan(fan))),
+            );
+        }
+
+        let mut sensor_rows = imp.sensor_rows.borrow_mut();
+
+        // sensors don't come and go, so only rebuild the rows if something has changed
+        if sensor_rows.len() != rows.len()
+            || sensor_rows
+                .iter()
+                .zip(&rows)
+                .any(|((old, _), (new, _))| old != new)
+        {
+            for (_, row) in sensor_rows.drain(..) {
+                imp.sensors_group.remove(&row);
+            }
+
+            for (title, _) in &rows {
+                let row = adw::ActionRow::builder()
+                    .title(title)
+                    .css_classes(["property"])
+                    .subtitle_selectable(true)
+                    .build();
+
+                imp.sensors_group.add(&row);
+                sensor_rows.push((title.clone(), row));
+            }
+        }
+
+        for ((_, row), (_, subtitle)) in sensor_rows.iter().zip(rows) {
+            row.set_subtitle(&subtitle);
+        }
+    }
+}
+
+fn format_fan(fan: &GpuFan) -> String {
+    format_fan_speed(
+        fan.speed,
+        fan.fraction()
+            .map(|fraction| format_percentage(fraction * 100.0)),
+    )
+}
+
+fn format_fan_speed(fan_speed: FanSpeed, percentage_string: Option<String>) -> String {
+    match (fan_speed, percentage_string) {
+        (FanSpeed::Rpm(rpm), Some(percentage_string)) => {
+            format!("{} ({percentage_string})", format_rpm(rpm))
+        }
+        (FanSpeed::Rpm(rpm), None) => format_rpm(rpm),
+        (FanSpeed::Fraction(_), Some(percentage_string)) => percentage_string,
+        (FanSpeed::Fraction(_), None) => i18n("N/A"),
+    }
+}
+
+fn temperature_sensor_title(sensor: GpuTemperatureSensor) -> String {
+    match sensor {
+        GpuTemperatureSensor::Edge => i18n("Edge Temperature"),
+        GpuTemperatureSensor::Junction => i18n("Junction Temperature"),
+        GpuTemperatureSensor::Memory => i18n("Memory Temperature"),
+    }
+}
+
+fn format_rpm(rpm: f64) -> String {
+    i18n_f("{} RPM", &[&rpm.round().to_string()])
 }
@@ -0,0 +1,382 @@
+use std::time::{Duration, SystemTime};
+
+use adw::{prelude::*, subclass::prelude::*};
+use gtk::glib;
+use log::trace;
+
+use crate::config::PROFILE;
+use crate::i18n::{i18n, i18n_f};
+use crate::utils::infiniband::InfinibandData;
+use crate::utils::units::{convert_speed, convert_storage};
+
+pub const TAB_ID_PREFIX: &str = "infiniband";
+
+mod imp {
+    use std::cell::{Cell, RefCell};
+
+    use crate::ui::{pages::INFINIBAND_PRIMARY_ORD, widgets::graph_box::ResGraphBox};
+
+    use super::*;
+
+    use gtk::{
+        gio::{Icon, ThemedIcon},
+        glib::{ParamSpec, Properties, Value},
+        CompositeTemplate,
+    };
+
+    #[derive(CompositeTemplate, Properties)]
+    #[template(resource = "/net/nokyan/Resources/ui/pages/infiniband.ui")]
+    #[properties(wrapper_type = super::ResInfiniband)]
+    pub struct ResInfiniband {
+        #[template_child]
+        pub receiving: TemplateChild<ResGraphBox>,
+        #[template_child]
+        pub sending: TemplateChild<ResGraphBox>,
+        #[template_child]
+        pub total_received: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub total_sent: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub state: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub physical_state: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub rate: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub link_layer: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub manufacturer: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub driver: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub device: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub port: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub firmware_version: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub net_devices: TemplateChild<adw::ActionRow>,
+        pub old_received_bytes: Cell<Option<u64>>,
+        pub old_sent_bytes: Cell<Option<u64>>,
+        pub last_timestamp: Cell<SystemTime>,
+
+        #[property(get)]
+        uses_progress_bar: Cell<bool>,
+
+        #[property(get)]
+        main_graph_color: glib::Bytes,
+
+        #[property(get)]
+        icon: RefCell<Icon>,
+
+        #[property(get, set)]
+        usage: Cell<f64>,
+
+        #[property(get = Self::tab_name, set = Self::set_tab_name, type = glib::GString)]
+        tab_name: Cell<glib::GString>,
+
+        #[property(get = Self::tab_detail_string, set = Self::set_tab_detail_string, type = glib::GString)]
+        tab_detail_string: Cell<glib::GString>,
+
+        #[property(get = Self::tab_usage_string, set = Self::set_tab_usage_string, type = glib::GString)]
+        tab_usage_string: Cell<glib::GString>,
+
+        #[property(get = Self::tab_id, set = Self::set_tab_id, type = glib::GString)]
+        tab_id: Cell<glib::GString>,
+
+        #[property(get)]
+        graph_locked_max_y: Cell<bool>,
+
+        #[property(get)]
+        primary_ord: Cell<u32>,
+
+        #[property(get, set)]
+        secondary_ord: Cell<u32>,
+    }
+
+    impl ResInfiniband {
+        gstring_getter_setter!(tab_name, tab_detail_string, tab_usage_string, tab_id);
+    }
+
+    impl Default for ResInfiniband {
+        fn default() -> Self {
+            Self {
+                receiving: Default::default(),
+                sending: Default::default(),
+                total_received: Default::default(),
+                total_sent: Default::default(),
+                state: Default::default(),
+                physical_state: Default::default(),
+                rate: Default::default(),
+                link_layer: Default::default(),
+                manufacturer: Default::default(),
+                driver: Default::default(),
+                device: Default::default(),
+                port: Default::default(),
+                firmware_version: Default::default(),
+                net_devices: Default::default(),
+                uses_progress_bar: Cell::new(true),
+                main_graph_color: glib::Bytes::from_static(&super::ResInfiniband::MAIN_GRAPH_COLOR),
+                icon: RefCell::new(ThemedIcon::new("infiniband-symbolic").into()),
+                usage: Default::default(),
+                tab_name: Cell::new(glib::GString::from(i18n("InfiniBand Port"))),
+                tab_detail_string: Cell::new(glib::GString::new()),
+                tab_id: Cell::new(glib::GString::new()),
+                old_received_bytes: Cell::default(),
+                old_sent_bytes: Cell::default(),
+                last_timestamp: Cell::new(
+                    SystemTime::now()
+                        .checked_sub(Duration::from_secs(1))
+                        .unwrap(),
+                ),
+                tab_usage_string: Cell::new(glib::GString::new()),
+                graph_locked_max_y: Cell::new(false),
+                primary_ord: Cell::new(INFINIBAND_PRIMARY_ORD),
+                secondary_ord: Default::default(),
+            }
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ResInfiniband {
+        const NAME: &'static str = "ResInfiniband";
+        type Type = super::ResInfiniband;
+        type ParentType = adw::Bin;
+
+        fn class_init(klass: &mut Self::Class) {
+            Self::bind_template(klass);
+        }
+
+        // You must call `Widget`'s `init_template()` within `instance_init()`.
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for ResInfiniband {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+
+            // Devel Profile
+            if PROFILE == "Devel" {
+                obj.add_css_class("devel");
+            }
+        }
+
+        fn properties() -> &'static [ParamSpec] {
+            Self::derived_properties()
+        }
+
+        fn set_property(&self, id: usize, value: &Value, pspec: &ParamSpec) {
+            self.derived_set_property(id, value, pspec);
+        }
+
+        fn property(&self, id: usize, pspec: &ParamSpec) -> Value {
+            self.derived_property(id, pspec)
+        }
+    }
+
+    impl WidgetImpl for ResInfiniband {}
+    impl BinImpl for ResInfiniband {}
+}
+
+glib::wrapper! {
+    pub struct ResInfiniband(ObjectSubclass<imp::ResInfiniband>)
+        @extends gtk::Widget, adw::Bin;
+}
+
+impl Default for ResInfiniband {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ResInfiniband {
+    const MAIN_GRAPH_COLOR: [u8; 3] = [0x25, 0x9a, 0xab];
+
+    pub fn new() -> Self {
+        trace!("Creating ResInfiniband GObject…");
+
+        glib::Object::new::<Self>()
+    }
+
+    pub fn init(&self, infiniband_data: &InfinibandData, secondary_ord: u32) {
+        self.set_secondary_ord(secondary_ord);
+        self.setup_widgets(infiniband_data);
+    }
+
+    pub fn setup_widgets(&self, infiniband_data: &InfinibandData) {
+        trace!(
+            "Setting up ResInfiniband ({:?}) widgets…",
+            infiniband_data.inner.sysfs_path
+        );
+
+        let imp = self.imp();
+        let port = &infiniband_data.inner;
+
+        let tab_id = format!(
+            "{}-{}-{}",
+            TAB_ID_PREFIX, port.device_name, port.port_number
+        );
+        imp.set_tab_id(&tab_id);
+
+        imp.receiving.set_title_label(&i18n("Receiving"));
+        imp.receiving.graph().set_graph_color(0x34, 0xab, 0xaf);
+        imp.receiving.graph().set_locked_max_y(None);
+        imp.receiving
+            .graph()
+            .set_value_formatter(|speed| convert_speed(speed, true));
+
+        imp.sending.set_title_label(&i18n("Sending"));
+        imp.sending.graph().set_graph_color(0x20, 0x81, 0x8f);
+        imp.sending.graph().set_locked_max_y(None);
+        imp.sending
+            .graph()
+            .set_value_formatter(|speed| convert_speed(speed, true));
+
+        imp.manufacturer.set_subtitle(
+            &port
+                .device
+                .map_or_else(|| i18n("N/A"), |device| device.vendor().name().to_string()),
+        );
+
+        imp.driver
+            .set_subtitle(&port.driver_name.clone().unwrap_or_else(|| i18n("N/A")));
+
+        imp.device.set_subtitle(&port.device_name);
+
+        imp.port.set_subtitle(&port.port_number);
+
+        imp.link_layer
+            .set_subtitle(&port.link_layer.clone().unwrap_or_else(|| i18n("N/A")));
+
+        imp.firmware_version
+            .set_subtitle(&port.firmware_version.clone().unwrap_or_else(|| i18n("N/A")));
+
+        imp.net_devices.set_visible(!port.net_devices.is_empty());
+        imp.net_devices.set_subtitle(&port.net_devices.join(", "));
+
+        imp.last_timestamp.set(
+            SystemTime::now()
+                .checked_sub(Duration::from_secs(1))
+                .unwrap(),
+        );
+
+        imp.old_received_bytes
+            .set(infiniband_data.received_bytes.as_ref().ok().copied());
+        imp.old_sent_bytes
+            .set(infiniband_data.sent_bytes.as_ref().ok().copied());
+
+        imp.set_tab_detail_string(&infiniband_data.display_name);
+    }
+
+    pub fn refresh_page(&self, infiniband_data: InfinibandData) {
+        trace!(
+            "Refreshing ResInfiniband ({:?})…",
+            infiniband_data.inner.sysfs_path
+        );
+
+        let InfinibandData {
+            inner: _,
+            received_bytes,
+            sent_bytes,
+            state,
+            physical_state,
+            rate,
+            display_name: _,
+        } = infiniband_data;
+
+        let imp = self.imp();
+
+        // the link can go up or down (and be renegotiated) at any time
+        imp.state
+            .set_subtitle(&state.unwrap_or_else(|| i18n("N/A")));
+        imp.physical_state
+            .set_subtitle(&physical_state.unwrap_or_else(|| i18n("N/A")));
+        imp.rate.set_subtitle(&rate.unwrap_or_else(|| i18n("N/A")));
+
+        let time_passed = SystemTime::now()
+            .duration_since(imp.last_timestamp.get())
+            .map_or(1.0f64, |timestamp| timestamp.as_secs_f64());
+
+        let (received_delta, received_string) =
+            if let (Ok(received_bytes), Some(old_received_bytes)) =
+                (received_bytes, imp.old_received_bytes.get())
+            {
+                let received_delta =
+                    (received_bytes.saturating_sub(old_received_bytes)) as f64 / time_passed;
+
+                imp.total_received
+                    .set_subtitle(&convert_storage(received_bytes as f64, false));
+
+                imp.receiving.graph().set_visible(true);
+                imp.receiving.graph().push_data_point(received_delta);
+
+                let highest_received = imp.receiving.graph().get_highest_value();
+
+                let formatted_delta = convert_speed(received_delta, true);
+
+                imp.receiving.set_subtitle(&format!(
+                    "{} · {} {}",
+                    &formatted_delta,
+                    i18n("Highest:"),
+                    convert_speed(highest_received, true)
+                ));
+
+                imp.old_received_bytes.set(Some(received_bytes));
+
+                (received_delta, formatted_delta)
+            } else {
+                imp.total_received.set_subtitle(&i18n("N/A"));
+
+                imp.receiving.graph().set_visible(false);
+                imp.receiving.set_subtitle(&i18n("N/A"));
+
+                (0.0, i18n("N/A"))
+            };
+
+        let (sent_delta, sent_string) = if let (Ok(sent_bytes), Some(old_sent_bytes)) =
+            (sent_bytes, imp.old_sent_bytes.get())
+        {
+            let sent_delta = (sent_bytes.saturating_sub(old_sent_bytes)) as f64 / time_passed;
+
+            imp.total_sent
+                .set_subtitle(&convert_storage(sent_bytes as f64, false));
+
+            imp.sending.graph().set_visible(true);
+            imp.sending.graph().push_data_point(sent_delta);
+
+            let highest_sent = imp.sending.graph().get_highest_value();
+
+            let formatted_delta = convert_speed(sent_delta, true);
+
+            imp.sending.set_subtitle(&format!(
+                "{} · {} {}",
+                &formatted_delta,
+                i18n("Highest:"),
+                convert_speed(highest_sent, true)
+            ));
+
+            imp.old_sent_bytes.set(Some(sent_bytes));
+
+            (sent_delta, formatted_delta)
+        } else {
+            imp.total_sent.set_subtitle(&i18n("N/A"));
+
+            imp.sending.graph().set_visible(false);
+            imp.sending.set_subtitle(&i18n("N/A"));
+
+            (0.0, i18n("N/A"))
+        };
+
+        self.set_property("usage", f64::max(received_delta, sent_delta));
+
+        self.set_property(
+            "tab_usage_string",
+            i18n_f("R: {} · S: {}", &[&received_string, &sent_string]),
+        );
+
+        imp.last_timestamp.set(SystemTime::now());
+    }
+}
@@ -4,7 +4,9 @@ use log::trace;
 
 use crate::config::PROFILE;
 use crate::i18n::{i18n, i18n_f};
+use crate::utils::history;
 use crate::utils::memory::{MemoryData, MemoryDevice};
+use crate::utils::settings::SETTINGS;
 use crate::utils::units::convert_storage;
 use crate::utils::FiniteOr;
 
@@ -32,6 +34,34 @@ mod imp {
         #[template_child]
         pub swap: TemplateChild<ResGraphBox>,
         #[template_child]
+        pub memory_breakdown: TemplateChild<adw::ExpanderRow>,
+        #[template_child]
+        pub memory_used: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub memory_cached: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub memory_buffers: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub memory_free: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub memory_available: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub dirty: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub writeback: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub kernel_memory: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub slab: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub slab_reclaimable: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub slab_unreclaimable: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub kernel_stack: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub page_tables: TemplateChild<adw::ActionRow>,
+        #[template_child]
         pub authentication_banner: TemplateChild<adw::Banner>,
         #[template_child]
         pub properties: TemplateChild<adw::PreferencesGroup>,
@@ -91,6 +121,20 @@ mod imp {
             Self {
                 memory: Default::default(),
                 swap: Default::default(),
+                memory_breakdown: Default::default(),
+                memory_used: Default::default(),
+                memory_cached: Default::default(),
+                memory_buffers: Default::default(),
+                memory_free: Default::default(),
+                memory_available: Default::default(),
+                dirty: Default::default(),
+                writeback: Default::default(),
+                kernel_memory: Default::default(),
+                slab: Default::default(),
+                slab_reclaimable: Default::default(),
+                slab_unreclaimable: Default::default(),
+                kernel_stack: Default::default(),
+                page_tables: Default::default(),
                 authentication_banner: Default::default(),
                 properties: Default::default(),
                 slots_used: Default::default(),
@@ -170,7 +214,7 @@ impl Default for ResMemory {
 }
 
 impl ResMemory {
-    const MAIN_GRAPH_COLOR: [u8; 3] = [0xc5, 0x2f, 0x90];
+    pub const MAIN_GRAPH_COLOR: [u8; 3] = [0xc5, 0x2f, 0x90];
 
     pub fn new() -> Self {
         trace!("Creating ResMemory GObject…");
@@ -189,6 +233,7 @@ impl ResMemory {
         let imp = self.imp();
 
         imp.memory.set_title_label(&i18n("Memory"));
+        imp.memory.set_history_series(history::MEMORY_USAGE);
         imp.memory.graph().set_graph_color(
             Self::MAIN_GRAPH_COLOR[0],
             Self::MAIN_GRAPH_COLOR[1],
@@ -296,11 +341,22 @@ impl ResMemory {
 
         let imp = self.imp();
 
+        let slab = memdata.slab();
+
         let MemoryData {
             total_mem,
             available_mem,
             total_swap,
             free_swap,
+            free_mem,
+            buffers,
+            cached,
+            slab_reclaimable,
+            slab_unreclaimable,
+            kernel_stack,
+            page_tables,
+            dirty,
+            writeback,
         } = memdata;
 
         let used_mem = total_mem.saturating_sub(available_mem);
@@ -313,6 +369,11 @@ impl ResMemory {
         let formatted_total_mem = convert_storage(total_mem as f64, false);
 
         imp.memory.graph().push_data_point(memory_fraction);
+        imp.memory.set_warning_level(
+            Some(memory_fraction * 100.0),
+            SETTINGS.usage_warning_threshold(),
+            SETTINGS.usage_critical_threshold(),
+        );
         imp.memory.set_subtitle(&format!(
             "{} / {} · {} %",
             &formatted_used_mem,
@@ -352,6 +413,51 @@ impl ResMemory {
             );
         }
 
+        let format_memory = |bytes: Option<usize>| {
+            bytes.map_or_else(
+                || i18n("N/A"),
+                |bytes| {
+                    format!(
+                        "{} · {} %",
+                        convert_storage(bytes as f64, false),
+                        ((bytes as f64 / total_mem as f64) * 100.0)
+                            .finite_or_default()
+                            .round()
+                    )
+                },
+            )
+        };
+
+        // "used" as in MemTotal - MemAvailable already excludes reclaimable caches, so it's the memory that's
+        // actually in use
+        imp.memory_breakdown.set_subtitle(&i18n_f(
+            "{} used · {} cached",
+            &[
+                &formatted_used_mem,
+                &cached.map_or_else(
+                    || i18n("N/A"),
+                    |cached| convert_storage(cached as f64, false),
+                ),
+            ],
+        ));
+        imp.memory_used.set_subtitle(&format_memory(Some(used_mem)));
+        imp.memory_cached.set_subtitle(&format_memory(cached));
+        imp.memory_buffers.set_subtitle(&format_memory(buffers));
+        imp.memory_free.set_subtitle(&format_memory(free_mem));
+        imp.memory_available
+            .set_subtitle(&format_memory(Some(available_mem)));
+
+        imp.dirty.set_subtitle(&format_memory(dirty));
+        imp.writeback.set_subtitle(&format_memory(writeback));
+
+        imp.slab.set_subtitle(&format_memory(slab));
+        imp.slab_reclaimable
+            .set_subtitle(&format_memory(slab_reclaimable));
+        imp.slab_unreclaimable
+            .set_subtitle(&format_memory(slab_unreclaimable));
+        imp.kernel_stack.set_subtitle(&format_memory(kernel_stack));
+        imp.page_tables.set_subtitle(&format_memory(page_tables));
+
         let memory_devices = imp.memory_devices.borrow();
 
         let total_memory = memory_devices
@@ -1,17 +1,24 @@
 use std::{collections::HashMap, sync::LazyLock};
 
+use adw::prelude::*;
+use gtk::glib::{self, clone};
 use process_data::Niceness;
 
 use crate::i18n::pi18n;
+use crate::utils::settings::SETTINGS;
 
 pub mod applications;
 pub mod battery;
 pub mod cpu;
+pub mod custom_gauges;
 pub mod drive;
 pub mod gpu;
+pub mod infiniband;
 pub mod memory;
 pub mod network;
+pub mod network_total;
 pub mod npu;
+pub mod os;
 pub mod processes;
 
 const APPLICATIONS_PRIMARY_ORD: u32 = 0;
@@ -22,7 +29,37 @@ const GPU_PRIMARY_ORD: u32 = 4;
 const NPU_PRIMARY_ORD: u32 = 5;
 const DRIVE_PRIMARY_ORD: u32 = 6;
 const NETWORK_PRIMARY_ORD: u32 = 7;
-const BATTERY_PRIMARY_ORD: u32 = 8;
+const INFINIBAND_PRIMARY_ORD: u32 = 8;
+const BATTERY_PRIMARY_ORD: u32 = 9;
+const CUSTOM_GAUGES_PRIMARY_ORD: u32 = 10;
+const OS_PRIMARY_ORD: u32 = 11;
+
+/// Shows the name the user has given to the device with the stable ID `device_id` as the tab name of `page`, falling
+/// back to `default_name`, and keeps it up to date. `page` needs a `device_id` property, which allows renaming the page.
+pub fn bind_device_label<P: IsA<glib::Object>>(page: &P, device_id: &str, default_name: &str) {
+    let page = page.upcast_ref::<glib::Object>().clone();
+
+    page.set_property("device_id", device_id);
+
+    let refresh_tab_name = {
+        let device_id = device_id.to_string();
+        let default_name = default_name.to_string();
+        move |page: &glib::Object| {
+            let tab_name = SETTINGS
+                .device_label(&device_id)
+                .unwrap_or_else(|| default_name.clone());
+            page.set_property("tab_name", tab_name);
+        }
+    };
+
+    refresh_tab_name(&page);
+
+    SETTINGS.connect_device_labels(clone!(
+        #[weak]
+        page,
+        move || refresh_tab_name(&page)
+    ));
+}
 
 pub static NICE_TO_LABEL: LazyLock<HashMap<Niceness, (String, u32)>> = LazyLock::new(|| {
     let mut hash_map = HashMap::new();
@@ -2,12 +2,19 @@ use std::time::{Duration, SystemTime};
 
 use adw::{glib::property::PropertySet, prelude::*, subclass::prelude::*};
 use gtk::glib;
-use log::trace;
+use log::{trace, warn};
 
 use crate::config::PROFILE;
 use crate::i18n::{i18n, i18n_f};
-use crate::utils::network::{NetworkData, NetworkInterface};
-use crate::utils::units::{convert_speed, convert_storage};
+use crate::ui::pages::bind_device_label;
+use crate::utils::history;
+use crate::utils::network::accounting::{DataAccounting, DataUsage, Date};
+use crate::utils::network::{
+    InterfaceAddress, InterfaceType, NetworkData, NetworkInterface, WifiLink,
+};
+use crate::utils::settings::SETTINGS;
+use crate::utils::units::{convert_speed, convert_storage, format_link_speed, format_percentage};
+use crate::utils::FiniteOr;
 
 pub const TAB_ID_PREFIX: &str = "network";
 
@@ -33,10 +40,34 @@ mod imp {
         #[template_child]
         pub sending: TemplateChild<ResGraphBox>,
         #[template_child]
+        pub signal: TemplateChild<ResGraphBox>,
+        #[template_child]
         pub total_received: TemplateChild<adw::ActionRow>,
         #[template_child]
         pub total_sent: TemplateChild<adw::ActionRow>,
         #[template_child]
+        pub data_used: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub ipv4_traffic: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub ipv6_traffic: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub ssid: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub ipv4_addresses: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub ipv6_addresses: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub link_speed: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub duplex: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub mtu: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub frequency: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub bitrate: TemplateChild<adw::ActionRow>,
+        #[template_child]
         pub manufacturer: TemplateChild<adw::ActionRow>,
         #[template_child]
         pub driver: TemplateChild<adw::ActionRow>,
@@ -44,9 +75,12 @@ mod imp {
         pub interface: TemplateChild<adw::ActionRow>,
         #[template_child]
         pub hw_address: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub member_interfaces: TemplateChild<adw::ActionRow>,
         pub old_received_bytes: Cell<Option<usize>>,
         pub old_sent_bytes: Cell<Option<usize>>,
         pub last_timestamp: Cell<SystemTime>,
+        pub accounting: RefCell<Option<DataAccounting>>,
 
         #[property(get)]
         uses_progress_bar: Cell<bool>,
@@ -72,6 +106,9 @@ mod imp {
         #[property(get = Self::tab_id, set = Self::set_tab_id, type = glib::GString)]
         tab_id: Cell<glib::GString>,
 
+        #[property(get = Self::device_id, set = Self::set_device_id, type = glib::GString)]
+        device_id: Cell<glib::GString>,
+
         #[property(get)]
         graph_locked_max_y: Cell<bool>,
 
@@ -83,7 +120,13 @@ mod imp {
     }
 
     impl ResNetwork {
-        gstring_getter_setter!(tab_name, tab_detail_string, tab_usage_string, tab_id);
+        gstring_getter_setter!(
+            tab_name,
+            tab_detail_string,
+            tab_usage_string,
+            tab_id,
+            device_id
+        );
 
         pub fn icon(&self) -> Icon {
             let icon = self.icon.replace_with(|_| NetworkInterface::default_icon());
@@ -102,12 +145,25 @@ mod imp {
             Self {
                 receiving: Default::default(),
                 sending: Default::default(),
+                signal: Default::default(),
                 total_received: Default::default(),
                 total_sent: Default::default(),
+                data_used: Default::default(),
+                ipv4_traffic: Default::default(),
+                ipv6_traffic: Default::default(),
+                ssid: Default::default(),
+                ipv4_addresses: Default::default(),
+                ipv6_addresses: Default::default(),
+                link_speed: Default::default(),
+                duplex: Default::default(),
+                mtu: Default::default(),
+                frequency: Default::default(),
+                bitrate: Default::default(),
                 manufacturer: Default::default(),
                 driver: Default::default(),
                 interface: Default::default(),
                 hw_address: Default::default(),
+                member_interfaces: Default::default(),
                 uses_progress_bar: Cell::new(true),
                 main_graph_color: glib::Bytes::from_static(&super::ResNetwork::MAIN_GRAPH_COLOR),
                 icon: RefCell::new(ThemedIcon::new("unknown-network-type-symbolic").into()),
@@ -115,6 +171,7 @@ mod imp {
                 tab_name: Cell::new(glib::GString::from(i18n("Network Interface"))),
                 tab_detail_string: Cell::new(glib::GString::new()),
                 tab_id: Cell::new(glib::GString::new()),
+                device_id: Cell::new(glib::GString::new()),
                 old_received_bytes: Cell::default(),
                 old_sent_bytes: Cell::default(),
                 last_timestamp: Cell::new(
@@ -122,6 +179,7 @@ mod imp {
                         .checked_sub(Duration::from_secs(1))
                         .unwrap(),
                 ),
+                accounting: RefCell::default(),
                 tab_usage_string: Cell::new(glib::GString::new()),
                 graph_locked_max_y: Cell::new(false),
                 primary_ord: Cell::new(NETWORK_PRIMARY_ORD),
@@ -221,15 +279,39 @@ impl ResNetwork {
 
         self.imp().set_icon(&network_interface.icon());
 
-        imp.set_tab_name(&i18n(&network_interface.interface_type.to_string()));
+        bind_device_label(
+            self,
+            &network_interface.stable_id(),
+            &i18n(&network_interface.interface_type.to_string()),
+        );
 
         imp.receiving.set_title_label(&i18n("Receiving"));
+        imp.receiving
+            .set_history_series(history::network_received(&network_interface.interface_name));
         imp.receiving.graph().set_graph_color(0x34, 0xab, 0xaf);
         imp.receiving.graph().set_locked_max_y(None);
+        imp.receiving
+            .graph()
+            .set_value_formatter(|speed| convert_speed(speed, true));
 
         imp.sending.set_title_label(&i18n("Sending"));
+        imp.sending
+            .set_history_series(history::network_sent(&network_interface.interface_name));
         imp.sending.graph().set_graph_color(0x20, 0x81, 0x8f);
         imp.sending.graph().set_locked_max_y(None);
+        imp.sending
+            .graph()
+            .set_value_formatter(|speed| convert_speed(speed, true));
+
+        imp.signal.set_title_label(&i18n("Signal Strength"));
+        imp.signal.graph().set_graph_color(0x8f, 0x5a, 0xb5);
+        imp.signal.graph().set_locked_max_y(Some(1.0));
+        imp.signal.graph().set_value_formatter(|quality| {
+            i18n_f(
+                "{} dBm",
+                &[&WifiLink::signal_from_quality(quality).round().to_string()],
+            )
+        });
 
         imp.manufacturer.set_subtitle(
             &network_interface
@@ -262,6 +344,10 @@ impl ResNetwork {
             imp.hw_address.set_subtitle(&hw_address);
         }
 
+        if network_interface.interface_type == InterfaceType::Vlan {
+            imp.member_interfaces.set_title(&i18n("Parent Interface"));
+        }
+
         imp.last_timestamp.set(
             SystemTime::now()
                 .checked_sub(Duration::from_secs(1))
@@ -273,6 +359,9 @@ impl ResNetwork {
         imp.old_sent_bytes
             .set(network_data.sent_bytes.as_ref().ok().copied());
 
+        imp.accounting
+            .replace(Some(DataAccounting::open(network_interface.stable_id())));
+
         imp.set_tab_detail_string(&network_data.display_name);
     }
 
@@ -285,12 +374,32 @@ impl ResNetwork {
         let NetworkData {
             received_bytes,
             sent_bytes,
-            inner: _,
+            inner,
             is_virtual: _,
+            is_container: _,
             display_name: _,
+            ip_traffic,
+            addresses,
+            wifi_link,
         } = network_data;
 
         let imp = self.imp();
+
+        self.refresh_connection(&inner, &addresses);
+
+        self.refresh_wifi_link(wifi_link.as_ref());
+
+        self.refresh_data_used(
+            received_bytes.as_ref().ok().copied(),
+            sent_bytes.as_ref().ok().copied(),
+        );
+
+        // bonds and bridges can gain or lose members at any time
+        imp.member_interfaces
+            .set_visible(!inner.member_interfaces.is_empty());
+        imp.member_interfaces
+            .set_subtitle(&inner.member_interfaces.join(", "));
+
         let time_passed = SystemTime::now()
             .duration_since(imp.last_timestamp.get())
             .map_or(1.0f64, |timestamp| timestamp.as_secs_f64());
@@ -365,6 +474,38 @@ impl ResNetwork {
             (0.0, i18n("N/A"))
         };
 
+        if let Ok(ip_traffic) = ip_traffic {
+            let total_bytes = ip_traffic
+                .ipv4_total_bytes()
+                .saturating_add(ip_traffic.ipv6_total_bytes());
+
+            let ipv4_fraction =
+                (ip_traffic.ipv4_total_bytes() as f64 / total_bytes as f64).finite_or_default();
+            let ipv6_fraction =
+                (ip_traffic.ipv6_total_bytes() as f64 / total_bytes as f64).finite_or_default();
+
+            imp.ipv4_traffic.set_subtitle(&i18n_f(
+                "R: {} · S: {} · {} %",
+                &[
+                    &convert_storage(ip_traffic.ipv4_received_bytes as f64, false),
+                    &convert_storage(ip_traffic.ipv4_sent_bytes as f64, false),
+                    &(ipv4_fraction * 100.0).round().to_string(),
+                ],
+            ));
+
+            imp.ipv6_traffic.set_subtitle(&i18n_f(
+                "R: {} · S: {} · {} %",
+                &[
+                    &convert_storage(ip_traffic.ipv6_received_bytes as f64, false),
+                    &convert_storage(ip_traffic.ipv6_sent_bytes as f64, false),
+                    &(ipv6_fraction * 100.0).round().to_string(),
+                ],
+            ));
+        } else {
+            imp.ipv4_traffic.set_subtitle(&i18n("N/A"));
+            imp.ipv6_traffic.set_subtitle(&i18n("N/A"));
+        }
+
         self.set_property("usage", f64::max(received_delta, sent_delta));
 
         self.set_property(
@@ -379,4 +520,140 @@ impl ResNetwork {
 
         imp.last_timestamp.set(SystemTime::now());
     }
+
+    /// Adds the traffic since the last refresh to the data usage that's kept on disk and shows the usage within the
+    /// current billing cycle
+    fn refresh_data_used(&self, received_bytes: Option<usize>, sent_bytes: Option<usize>) {
+        let imp = self.imp();
+
+        let mut accounting = imp.accounting.borrow_mut();
+
+        let (Some(accounting), Some(received_bytes), Some(sent_bytes), Ok(today)) = (
+            accounting.as_mut(),
+            received_bytes,
+            sent_bytes,
+            Date::today(),
+        ) else {
+            imp.data_used.set_subtitle(&i18n("N/A"));
+            return;
+        };
+
+        accounting.record(
+            DataUsage {
+                received_bytes: received_bytes as u64,
+                sent_bytes: sent_bytes as u64,
+            },
+            today,
+        );
+
+        if let Err(error) = accounting.save_if_due() {
+            warn!(
+                "Unable to save the data usage of {}: {error}",
+                self.tab_id()
+            );
+        }
+
+        let usage = accounting.usage_in_billing_cycle(today, SETTINGS.network_billing_cycle_day());
+
+        imp.data_used.set_subtitle(&i18n_f(
+            "R: {} · S: {}",
+            &[
+                &convert_storage(usage.received_bytes as f64, false),
+                &convert_storage(usage.sent_bytes as f64, false),
+            ],
+        ));
+    }
+
+    /// Shows the addresses and the link properties of the interface, these can change at any time, e.g. when
+    /// reconnecting
+    fn refresh_connection(&self, inner: &NetworkInterface, addresses: &[InterfaceAddress]) {
+        let imp = self.imp();
+
+        let format_addresses = |ipv4: bool| {
+            let addresses = addresses
+                .iter()
+                .filter(|address| address.address.is_ipv4() == ipv4)
+                .map(ToString::to_string)
+                .collect::<Vec<_>>();
+
+            if addresses.is_empty() {
+                i18n("N/A")
+            } else {
+                addresses.join("\n")
+            }
+        };
+
+        imp.ipv4_addresses.set_subtitle(&format_addresses(true));
+        imp.ipv6_addresses.set_subtitle(&format_addresses(false));
+
+        imp.link_speed.set_subtitle(
+            &inner
+                .speed
+                .map_or_else(|| i18n("N/A"), |speed| format_link_speed(speed as f64)),
+        );
+
+        imp.duplex.set_subtitle(
+            &inner
+                .duplex
+                .map_or_else(|| i18n("N/A"), |duplex| duplex.to_string()),
+        );
+
+        imp.mtu.set_subtitle(
+            &inner
+                .mtu
+                .map_or_else(|| i18n("N/A"), |mtu| i18n_f("{} B", &[&mtu.to_string()])),
+        );
+    }
+
+    /// Shows the signal strength, the frequency and the bitrates of Wi-Fi interfaces, the rows stay hidden for other
+    /// interfaces
+    fn refresh_wifi_link(&self, wifi_link: Option<&WifiLink>) {
+        let imp = self.imp();
+
+        imp.ssid.set_visible(wifi_link.is_some());
+        imp.frequency.set_visible(wifi_link.is_some());
+        imp.bitrate.set_visible(wifi_link.is_some());
+
+        let Some(wifi_link) = wifi_link else {
+            imp.signal.set_visible(false);
+            return;
+        };
+
+        imp.ssid
+            .set_subtitle(&wifi_link.ssid.clone().unwrap_or_else(|| i18n("N/A")));
+
+        imp.frequency
+            .set_subtitle(&match (wifi_link.frequency, wifi_link.channel()) {
+                (Some(frequency), Some(channel)) => i18n_f(
+                    "{} MHz · Channel {}",
+                    &[&frequency.to_string(), &channel.to_string()],
+                ),
+                (Some(frequency), None) => i18n_f("{} MHz", &[&frequency.to_string()]),
+                _ => i18n("N/A"),
+            });
+
+        let format_bitrate =
+            |bitrate: Option<f64>| bitrate.map_or_else(|| i18n("N/A"), format_link_speed);
+
+        imp.bitrate.set_subtitle(&i18n_f(
+            "R: {} · S: {}",
+            &[
+                &format_bitrate(wifi_link.rx_bitrate),
+                &format_bitrate(wifi_link.tx_bitrate),
+            ],
+        ));
+
+        // the signal strength is only known while connected, keep the graph around once it has been shown though
+        if let (Some(signal), Some(quality)) = (wifi_link.signal, wifi_link.signal_quality()) {
+            imp.signal.set_visible(true);
+            imp.signal.graph().push_data_point(quality);
+            imp.signal.set_subtitle(&i18n_f(
+                "{} dBm · {}",
+                &[&signal.to_string(), &format_percentage(quality * 100.0)],
+            ));
+        } else if imp.signal.is_visible() {
+            imp.signal.graph().push_data_point(0.0);
+            imp.signal.set_subtitle(&i18n("Not Connected"));
+        }
+    }
 }
@@ -0,0 +1,323 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use adw::{prelude::*, subclass::prelude::*};
+use gtk::glib;
+use log::trace;
+
+use crate::config::PROFILE;
+use crate::i18n::{i18n, i18n_f};
+use crate::utils::network::NetworkData;
+use crate::utils::units::{convert_speed, convert_storage};
+
+pub const TAB_ID: &str = "network-total";
+
+mod imp {
+    use std::cell::{Cell, RefCell};
+
+    use crate::ui::{pages::NETWORK_PRIMARY_ORD, widgets::graph_box::ResGraphBox};
+
+    use super::*;
+
+    use gtk::{
+        gio::{Icon, ThemedIcon},
+        glib::{ParamSpec, Properties, Value},
+        CompositeTemplate,
+    };
+
+    #[derive(CompositeTemplate, Properties)]
+    #[template(resource = "/net/nokyan/Resources/ui/pages/network_total.ui")]
+    #[properties(wrapper_type = super::ResNetworkTotal)]
+    pub struct ResNetworkTotal {
+        #[template_child]
+        pub receiving: TemplateChild<ResGraphBox>,
+        #[template_child]
+        pub sending: TemplateChild<ResGraphBox>,
+        #[template_child]
+        pub total_received: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub total_sent: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub interfaces: TemplateChild<adw::ActionRow>,
+        /// Byte counters of every summed up interface at the last refresh, keyed by their sysfs path
+        pub old_received_bytes: RefCell<HashMap<PathBuf, usize>>,
+        pub old_sent_bytes: RefCell<HashMap<PathBuf, usize>>,
+        pub last_timestamp: Cell<SystemTime>,
+
+        #[property(get)]
+        uses_progress_bar: Cell<bool>,
+
+        #[property(get)]
+        main_graph_color: glib::Bytes,
+
+        #[property(get)]
+        icon: RefCell<Icon>,
+
+        #[property(get, set)]
+        usage: Cell<f64>,
+
+        #[property(get = Self::tab_name, type = glib::GString)]
+        tab_name: Cell<glib::GString>,
+
+        #[property(get = Self::tab_detail_string, set = Self::set_tab_detail_string, type = glib::GString)]
+        tab_detail_string: Cell<glib::GString>,
+
+        #[property(get = Self::tab_usage_string, set = Self::set_tab_usage_string, type = glib::GString)]
+        tab_usage_string: Cell<glib::GString>,
+
+        #[property(get = Self::tab_id, type = glib::GString)]
+        tab_id: Cell<glib::GString>,
+
+        #[property(get)]
+        graph_locked_max_y: Cell<bool>,
+
+        #[property(get)]
+        primary_ord: Cell<u32>,
+
+        #[property(get)]
+        secondary_ord: Cell<u32>,
+    }
+
+    impl ResNetworkTotal {
+        gstring_getter_setter!(tab_name, tab_detail_string, tab_usage_string, tab_id);
+    }
+
+    impl Default for ResNetworkTotal {
+        fn default() -> Self {
+            Self {
+                receiving: Default::default(),
+                sending: Default::default(),
+                total_received: Default::default(),
+                total_sent: Default::default(),
+                interfaces: Default::default(),
+                old_received_bytes: Default::default(),
+                old_sent_bytes: Default::default(),
+                last_timestamp: Cell::new(
+                    SystemTime::now()
+                        .checked_sub(Duration::from_secs(1))
+                        .unwrap(),
+                ),
+                uses_progress_bar: Cell::new(true),
+                main_graph_color: glib::Bytes::from_static(
+                    &super::ResNetworkTotal::MAIN_GRAPH_COLOR,
+                ),
+                icon: RefCell::new(ThemedIcon::new("unknown-network-type-symbolic").into()),
+                usage: Default::default(),
+                tab_name: Cell::new(glib::GString::from(i18n("All Networks"))),
+                tab_detail_string: Cell::new(glib::GString::new()),
+                tab_usage_string: Cell::new(glib::GString::new()),
+                tab_id: Cell::new(glib::GString::from(TAB_ID)),
+                graph_locked_max_y: Cell::new(false),
+                primary_ord: Cell::new(NETWORK_PRIMARY_ORD),
+                // make sure that this page comes before the pages of the single interfaces
+                secondary_ord: Cell::new(0),
+            }
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ResNetworkTotal {
+        const NAME: &'static str = "ResNetworkTotal";
+        type Type = super::ResNetworkTotal;
+        type ParentType = adw::Bin;
+
+        fn class_init(klass: &mut Self::Class) {
+            Self::bind_template(klass);
+        }
+
+        // You must call `Widget`'s `init_template()` within `instance_init()`.
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for ResNetworkTotal {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+
+            // Devel Profile
+            if PROFILE == "Devel" {
+                obj.add_css_class("devel");
+            }
+        }
+
+        fn properties() -> &'static [ParamSpec] {
+            Self::derived_properties()
+        }
+
+        fn set_property(&self, id: usize, value: &Value, pspec: &ParamSpec) {
+            self.derived_set_property(id, value, pspec);
+        }
+
+        fn property(&self, id: usize, pspec: &ParamSpec) -> Value {
+            self.derived_property(id, pspec)
+        }
+    }
+
+    impl WidgetImpl for ResNetworkTotal {}
+    impl BinImpl for ResNetworkTotal {}
+}
+
+glib::wrapper! {
+    pub struct ResNetworkTotal(ObjectSubclass<imp::ResNetworkTotal>)
+        @extends gtk::Widget, adw::Bin;
+}
+
+impl Default for ResNetworkTotal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ResNetworkTotal {
+    const MAIN_GRAPH_COLOR: [u8; 3] = [0x25, 0x9a, 0xab];
+
+    pub fn new() -> Self {
+        trace!("Creating ResNetworkTotal GObject…");
+
+        glib::Object::new::<Self>()
+    }
+
+    pub fn init(&self) {
+        self.setup_widgets();
+    }
+
+    pub fn setup_widgets(&self) {
+        trace!("Setting up ResNetworkTotal widgets…");
+
+        let imp = self.imp();
+
+        imp.receiving.set_title_label(&i18n("Receiving"));
+        imp.receiving.graph().set_graph_color(0x34, 0xab, 0xaf);
+        imp.receiving.graph().set_locked_max_y(None);
+        imp.receiving
+            .graph()
+            .set_value_formatter(|speed| convert_speed(speed, true));
+
+        imp.sending.set_title_label(&i18n("Sending"));
+        imp.sending.graph().set_graph_color(0x20, 0x81, 0x8f);
+        imp.sending.graph().set_locked_max_y(None);
+        imp.sending
+            .graph()
+            .set_value_formatter(|speed| convert_speed(speed, true));
+    }
+
+    /// Sums up the counters of `old` and `new` and returns the total of `new` and the delta between both. Only
+    /// interfaces that are present in both are part of the delta so that interfaces that appear or disappear don't
+    /// cause spikes.
+    fn sum_counters(
+        old: &HashMap<PathBuf, usize>,
+        new: &HashMap<PathBuf, usize>,
+    ) -> (usize, usize) {
+        let total = new.values().copied().fold(0, usize::saturating_add);
+
+        let delta = new
+            .iter()
+            .filter_map(|(path, bytes)| {
+                old.get(path)
+                    .map(|old_bytes| bytes.saturating_sub(*old_bytes))
+            })
+            .fold(0, usize::saturating_add);
+
+        (total, delta)
+    }
+
+    pub fn refresh_page(&self, network_data: &[NetworkData]) {
+        trace!("Refreshing ResNetworkTotal…");
+
+        let imp = self.imp();
+
+        // virtual interfaces like bridges or VPN tunnels usually carry traffic that also passes a physical interface
+        let physical_interfaces: Vec<&NetworkData> = network_data
+            .iter()
+            .filter(|network_data| !network_data.is_virtual)
+            .collect();
+
+        let received_bytes: HashMap<PathBuf, usize> = physical_interfaces
+            .iter()
+            .filter_map(|network_data| {
+                network_data
+                    .received_bytes
+                    .as_ref()
+                    .ok()
+                    .map(|bytes| (network_data.inner.sysfs_path.clone(), *bytes))
+            })
+            .collect();
+
+        let sent_bytes: HashMap<PathBuf, usize> = physical_interfaces
+            .iter()
+            .filter_map(|network_data| {
+                network_data
+                    .sent_bytes
+                    .as_ref()
+                    .ok()
+                    .map(|bytes| (network_data.inner.sysfs_path.clone(), *bytes))
+            })
+            .collect();
+
+        let time_passed = SystemTime::now()
+            .duration_since(imp.last_timestamp.get())
+            .map_or(1.0f64, |timestamp| timestamp.as_secs_f64());
+
+        let (total_received, received_delta) =
+            Self::sum_counters(&imp.old_received_bytes.borrow(), &received_bytes);
+        let (total_sent, sent_delta) =
+            Self::sum_counters(&imp.old_sent_bytes.borrow(), &sent_bytes);
+
+        let received_delta = received_delta as f64 / time_passed;
+        let sent_delta = sent_delta as f64 / time_passed;
+
+        imp.receiving.graph().push_data_point(received_delta);
+        imp.sending.graph().push_data_point(sent_delta);
+
+        let received_string = convert_speed(received_delta, true);
+        let sent_string = convert_speed(sent_delta, true);
+
+        imp.receiving.set_subtitle(&format!(
+            "{} · {} {}",
+            &received_string,
+            i18n("Highest:"),
+            convert_speed(imp.receiving.graph().get_highest_value(), true)
+        ));
+        imp.sending.set_subtitle(&format!(
+            "{} · {} {}",
+            &sent_string,
+            i18n("Highest:"),
+            convert_speed(imp.sending.graph().get_highest_value(), true)
+        ));
+
+        imp.total_received
+            .set_subtitle(&convert_storage(total_received as f64, false));
+        imp.total_sent
+            .set_subtitle(&convert_storage(total_sent as f64, false));
+
+        let interface_names = physical_interfaces
+            .iter()
+            .map(|network_data| network_data.inner.interface_name.to_string_lossy())
+            .collect::<Vec<_>>();
+
+        if interface_names.is_empty() {
+            imp.interfaces.set_subtitle(&i18n("N/A"));
+        } else {
+            imp.interfaces.set_subtitle(&interface_names.join(", "));
+        }
+
+        self.set_property("usage", f64::max(received_delta, sent_delta));
+
+        self.set_property(
+            "tab_usage_string",
+            i18n_f(
+                // Translators: This is an abbreviation for "Receive" and "Send". This is displayed in the sidebar so
+                // your translation should preferably be quite short or an abbreviation
+                "R: {} · S: {}",
+                &[&received_string, &sent_string],
+            ),
+        );
+
+        imp.old_received_bytes.replace(received_bytes);
+        imp.old_sent_bytes.replace(sent_bytes);
+        imp.last_timestamp.set(SystemTime::now());
+    }
+}
@@ -5,6 +5,7 @@ use log::trace;
 use crate::config::PROFILE;
 use crate::i18n::{i18n, i18n_f};
 use crate::utils::npu::{Npu, NpuData};
+use crate::utils::settings::SETTINGS;
 use crate::utils::units::{convert_frequency, convert_power, convert_storage, convert_temperature};
 use crate::utils::FiniteOr;
 
@@ -204,6 +205,9 @@ impl ResNPU {
         imp.temperature.set_title_label(&i18n("Temperature"));
         imp.temperature.graph().set_graph_color(0x83, 0x1c, 0xac);
         imp.temperature.graph().set_locked_max_y(None);
+        imp.temperature
+            .graph()
+            .set_value_formatter(convert_temperature);
 
         imp.manufacturer.set_subtitle(
             &npu.get_vendor()
@@ -247,6 +251,11 @@ impl ResNPU {
             .graph()
             .push_data_point(usage_fraction.unwrap_or(0.0));
         imp.npu_usage.graph().set_visible(usage_fraction.is_some());
+        imp.npu_usage.set_warning_level(
+            usage_fraction.map(|fraction| fraction * 100.0),
+            SETTINGS.usage_warning_threshold(),
+            SETTINGS.usage_critical_threshold(),
+        );
 
         let memory_subtitle = if let (Some(total_memory), Some(used_memory)) =
             (total_memory, used_memory)
@@ -301,6 +310,11 @@ impl ResNPU {
         imp.memory_usage.set_subtitle(&memory_subtitle);
 
         imp.temperature.graph().set_visible(temperature.is_some());
+        imp.temperature.set_warning_level(
+            *temperature,
+            SETTINGS.npu_temperature_warning_threshold(),
+            SETTINGS.npu_temperature_critical_threshold(),
+        );
 
         let mut power_string = power_usage.map_or_else(|| i18n("N/A"), convert_power);
 
@@ -0,0 +1,186 @@
+use adw::{prelude::*, subclass::prelude::*};
+use gtk::glib;
+use log::trace;
+
+use crate::config::PROFILE;
+use crate::i18n::i18n;
+use crate::utils::os::OsInfo;
+
+pub const TAB_ID: &str = "os";
+
+mod imp {
+    use std::cell::{Cell, RefCell};
+
+    use crate::ui::pages::OS_PRIMARY_ORD;
+
+    use super::*;
+
+    use gtk::{
+        gio::{Icon, ThemedIcon},
+        glib::{ParamSpec, Properties, Value},
+        CompositeTemplate,
+    };
+
+    #[derive(CompositeTemplate, Properties)]
+    #[template(resource = "/net/nokyan/Resources/ui/pages/os.ui")]
+    #[properties(wrapper_type = super::ResOs)]
+    pub struct ResOs {
+        #[template_child]
+        pub name: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub hypervisor: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub kernel_version: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub kernel_modules: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub kernel_cmdline: TemplateChild<adw::ActionRow>,
+
+        #[property(get)]
+        uses_progress_bar: Cell<bool>,
+
+        #[property(get)]
+        icon: RefCell<Icon>,
+
+        #[property(get, set)]
+        usage: Cell<f64>,
+
+        #[property(get = Self::tab_name, type = glib::GString)]
+        tab_name: Cell<glib::GString>,
+
+        #[property(get = Self::tab_detail_string, set = Self::set_tab_detail_string, type = glib::GString)]
+        tab_detail_string: Cell<glib::GString>,
+
+        #[property(get = Self::tab_usage_string, set = Self::set_tab_usage_string, type = glib::GString)]
+        tab_usage_string: Cell<glib::GString>,
+
+        #[property(get = Self::tab_id, type = glib::GString)]
+        tab_id: Cell<glib::GString>,
+
+        #[property(get)]
+        graph_locked_max_y: Cell<bool>,
+
+        #[property(get)]
+        primary_ord: Cell<u32>,
+
+        #[property(get)]
+        secondary_ord: Cell<u32>,
+    }
+
+    impl ResOs {
+        gstring_getter_setter!(tab_name, tab_detail_string, tab_usage_string, tab_id);
+    }
+
+    impl Default for ResOs {
+        fn default() -> Self {
+            Self {
+                name: Default::default(),
+                hypervisor: Default::default(),
+                kernel_version: Default::default(),
+                kernel_modules: Default::default(),
+                kernel_cmdline: Default::default(),
+                uses_progress_bar: Cell::new(false),
+                icon: RefCell::new(ThemedIcon::new("computer-symbolic").into()),
+                usage: Default::default(),
+                tab_name: Cell::new(glib::GString::from(i18n("Operating System"))),
+                tab_detail_string: Cell::new(glib::GString::new()),
+                tab_usage_string: Cell::new(glib::GString::new()),
+                tab_id: Cell::new(glib::GString::from(TAB_ID)),
+                graph_locked_max_y: Cell::new(false),
+                primary_ord: Cell::new(OS_PRIMARY_ORD),
+                secondary_ord: Default::default(),
+            }
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ResOs {
+        const NAME: &'static str = "ResOs";
+        type Type = super::ResOs;
+        type ParentType = adw::Bin;
+
+        fn class_init(klass: &mut Self::Class) {
+            Self::bind_template(klass);
+        }
+
+        // You must call `Widget`'s `init_template()` within `instance_init()`.
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for ResOs {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+
+            // Devel Profile
+            if PROFILE == "Devel" {
+                obj.add_css_class("devel");
+            }
+        }
+
+        fn properties() -> &'static [ParamSpec] {
+            Self::derived_properties()
+        }
+
+        fn set_property(&self, id: usize, value: &Value, pspec: &ParamSpec) {
+            self.derived_set_property(id, value, pspec);
+        }
+
+        fn property(&self, id: usize, pspec: &ParamSpec) -> Value {
+            self.derived_property(id, pspec)
+        }
+    }
+
+    impl WidgetImpl for ResOs {}
+    impl BinImpl for ResOs {}
+}
+
+glib::wrapper! {
+    pub struct ResOs(ObjectSubclass<imp::ResOs>)
+        @extends gtk::Widget, adw::Bin;
+}
+
+impl Default for ResOs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ResOs {
+    pub fn new() -> Self {
+        trace!("Creating ResOs GObject…");
+
+        glib::Object::new::<Self>()
+    }
+
+    pub fn init(&self) {
+        trace!("Initializing ResOs…");
+
+        let imp = self.imp();
+
+        let os_info = OsInfo::get();
+
+        let name = os_info.name.unwrap_or_else(|| i18n("N/A"));
+        imp.name.set_subtitle(&name);
+        imp.set_tab_usage_string(&name);
+
+        imp.hypervisor
+            .set_subtitle(&os_info.hypervisor.unwrap_or_else(|| i18n("None")));
+
+        imp.kernel_version
+            .set_subtitle(&os_info.kernel_version.unwrap_or_else(|| i18n("N/A")));
+
+        imp.kernel_modules.set_subtitle(
+            &os_info
+                .kernel_modules
+                .map_or_else(|| i18n("N/A"), |modules| modules.to_string()),
+        );
+
+        // the command line can get long, the subtitle wraps it
+        imp.kernel_cmdline.set_subtitle(&glib::markup_escape_text(
+            &os_info.kernel_cmdline.unwrap_or_else(|| i18n("N/A")),
+        ));
+    }
+}
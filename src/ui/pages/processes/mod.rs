@@ -1,7 +1,8 @@
 pub mod process_entry;
 mod process_name_cell;
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
 use std::sync::LazyLock;
 
 use adw::ResponseAppearance;
@@ -12,18 +13,28 @@ use gtk::{
     gio, BitsetIter, ColumnView, ColumnViewColumn, EventControllerKey, FilterChange, ListItem,
     NumericSorter, SortType, StringSorter, Widget,
 };
-use process_data::Niceness;
+use log::warn;
+use process_data::{
+    DrmFdinfo, EnvironmentVariable, GpuIdentifier, Niceness, OpenFile, ProcessLimits, SchedStat,
+    SocketData, ThreadData, WaitChannel,
+};
 
 use crate::config::PROFILE;
 use crate::i18n::{i18n, i18n_f, ni18n_f};
 use crate::ui::dialogs::process_dialog::ResProcessDialog;
 use crate::ui::dialogs::process_options_dialog::ResProcessOptionsDialog;
+use crate::ui::pages::cpu::ResCPU;
+use crate::ui::pages::gpu::ResGPU;
+use crate::ui::pages::memory::ResMemory;
 use crate::ui::pages::NICE_TO_LABEL;
 use crate::ui::window::{Action, MainWindow};
 use crate::utils::app::AppsContext;
-use crate::utils::process::ProcessAction;
+use crate::utils::process::{Process, ProcessAction};
 use crate::utils::settings::SETTINGS;
-use crate::utils::units::{convert_speed, convert_storage, format_time};
+use crate::utils::table::{
+    copy_column_menu, export_table, highlight_changes, tsv_line, visible_columns, ChangeScale,
+};
+use crate::utils::units::{convert_speed, convert_storage, format_percentage, format_time};
 use crate::utils::NUM_CPUS;
 
 use self::process_entry::ProcessEntry;
@@ -31,6 +42,9 @@ use self::process_name_cell::ResProcessNameCell;
 
 pub const TAB_ID: &str = "processes";
 
+/// Prefix of the IDs of the columns that show the usage of a single GPU, followed by the GPU's identifier
+const GPU_USAGE_COLUMN_PREFIX: &str = "gpu_usage:";
+
 static LONGEST_PRIORITY_LABEL: LazyLock<u32> = LazyLock::new(|| {
     // make sure that no matter how short the longest current locale's translation for a priority may be, a signed
     // two-digit number (+ 1 for more space) will always fit
@@ -70,6 +84,8 @@ mod imp {
         CompositeTemplate,
     };
 
+    use crate::ui::widgets::graph::ResGraph;
+
     #[derive(CompositeTemplate, Properties)]
     #[properties(wrapper_type = super::ResProcesses)]
     #[template(resource = "/net/nokyan/Resources/ui/pages/processes.ui")]
@@ -77,10 +93,26 @@ mod imp {
         #[template_child]
         pub toast_overlay: TemplateChild<adw::ToastOverlay>,
         #[template_child]
+        pub gpu_filter_banner: TemplateChild<adw::Banner>,
+        #[template_child]
         pub popover_menu: TemplateChild<gtk::PopoverMenu>,
         #[template_child]
         pub popover_menu_multiple: TemplateChild<gtk::PopoverMenu>,
         #[template_child]
+        pub graphs_revealer: TemplateChild<gtk::Revealer>,
+        #[template_child]
+        pub cpu_graph_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub cpu_graph: TemplateChild<ResGraph>,
+        #[template_child]
+        pub memory_graph_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub memory_graph: TemplateChild<ResGraph>,
+        #[template_child]
+        pub gpu_graph_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub gpu_graph: TemplateChild<ResGraph>,
+        #[template_child]
         pub search_revealer: TemplateChild<gtk::Revealer>,
         #[template_child]
         pub search_entry: TemplateChild<gtk::SearchEntry>,
@@ -105,7 +137,7 @@ mod imp {
         pub column_view: RefCell<gtk::ColumnView>,
 
         pub open_info_dialog: RefCell<Option<(i32, ResProcessDialog)>>,
-        pub open_options_dialog: RefCell<Option<(i32, ResProcessOptionsDialog)>>,
+        pub open_options_dialog: RefCell<Option<(Vec<i32>, ResProcessOptionsDialog)>>,
 
         pub info_dialog_closed: Cell<bool>,
         pub options_dialog_closed: Cell<bool>,
@@ -114,8 +146,15 @@ mod imp {
 
         pub popped_over_process: RefCell<Option<ProcessEntry>>,
 
+        /// The process whose graphs are shown below the list, it's the only one keeping its usage history
+        pub graphs_process: RefCell<Option<ProcessEntry>>,
+
         pub columns: RefCell<Vec<ColumnViewColumn>>,
 
+        /// If set, only the processes using video memory of this GPU are shown
+        pub gpu_filter: Cell<Option<GpuIdentifier>>,
+        pub gpu_filter_pids: RefCell<HashSet<libc::pid_t>>,
+
         #[property(get)]
         uses_progress_bar: Cell<bool>,
 
@@ -152,8 +191,16 @@ mod imp {
         fn default() -> Self {
             Self {
                 toast_overlay: Default::default(),
+                gpu_filter_banner: Default::default(),
                 popover_menu: Default::default(),
                 popover_menu_multiple: Default::default(),
+                graphs_revealer: Default::default(),
+                cpu_graph_label: Default::default(),
+                cpu_graph: Default::default(),
+                memory_graph_label: Default::default(),
+                memory_graph: Default::default(),
+                gpu_graph_label: Default::default(),
+                gpu_graph: Default::default(),
                 search_revealer: Default::default(),
                 search_entry: Default::default(),
                 processes_scrolled_window: Default::default(),
@@ -180,7 +227,10 @@ mod imp {
                 tab_usage_string: Cell::new(glib::GString::new()),
                 tab_id: Cell::new(glib::GString::from(TAB_ID)),
                 popped_over_process: Default::default(),
+                graphs_process: Default::default(),
                 columns: Default::default(),
+                gpu_filter: Default::default(),
+                gpu_filter_pids: Default::default(),
                 graph_locked_max_y: Cell::new(true),
                 primary_ord: Cell::new(PROCESSES_PRIMARY_ORD),
                 secondary_ord: Default::default(),
@@ -255,6 +305,21 @@ mod imp {
                 },
             );
 
+            klass.install_action(
+                "processes.context-copy-commandline",
+                None,
+                move |res_processes, _, _| {
+                    if let Some(process_entry) =
+                        res_processes.imp().popped_over_process.borrow().as_ref()
+                    {
+                        // copy the full command line, the tooltip might only show a shortened version of it
+                        res_processes
+                            .clipboard()
+                            .set_text(&process_entry.commandline());
+                    }
+                },
+            );
+
             klass.install_action(
                 "processes.context-information",
                 None,
@@ -274,7 +339,7 @@ mod imp {
                     if let Some(process_entry) =
                         res_processes.imp().popped_over_process.borrow().as_ref()
                     {
-                        res_processes.open_options_dialog(process_entry);
+                        res_processes.open_options_dialog(std::slice::from_ref(process_entry));
                     }
                 },
             );
@@ -319,6 +384,38 @@ mod imp {
                 },
             );
 
+            klass.install_action("processes.options", None, move |res_processes, _, _| {
+                let selected = res_processes.get_selected_process_entries();
+                if !selected.is_empty() {
+                    res_processes.open_options_dialog(&selected);
+                }
+            });
+
+            klass.install_action("processes.copy-rows", None, move |res_processes, _, _| {
+                res_processes.copy_selected_rows();
+            });
+
+            // only bound to the page so that copying text in e.g. the search entry keeps working
+            klass.add_binding_action(
+                gtk::gdk::Key::c,
+                gtk::gdk::ModifierType::CONTROL_MASK,
+                "processes.copy-rows",
+            );
+
+            klass.install_action("processes.export", None, move |res_processes, _, _| {
+                res_processes.export();
+            });
+
+            klass.install_action(
+                "processes.copy-column",
+                Some(glib::VariantTy::STRING),
+                move |res_processes, _, parameter| {
+                    if let Some(column_id) = parameter.and_then(|p| p.get::<String>()) {
+                        res_processes.copy_column(&column_id);
+                    }
+                },
+            );
+
             Self::bind_template(klass);
         }
 
@@ -382,11 +479,13 @@ impl ResProcesses {
         imp.search_button.set_active(false);
     }
 
-    pub fn init(&self, sender: Sender<Action>) {
+    /// `gpus` are the identifiers and names of the GPUs in the system, they get a usage column each if there's more
+    /// than one
+    pub fn init(&self, sender: Sender<Action>, gpus: &[(GpuIdentifier, String)]) {
         let imp = self.imp();
         imp.sender.set(sender).unwrap();
 
-        self.setup_widgets();
+        self.setup_widgets(gpus);
         self.setup_signals();
     }
 
@@ -435,7 +534,7 @@ impl ResProcesses {
         widget.add_controller(secondary_click);
     }
 
-    pub fn setup_widgets(&self) {
+    pub fn setup_widgets(&self, gpus: &[(GpuIdentifier, String)]) {
         let imp = self.imp();
 
         // i don't quite get why that's necessary
@@ -465,6 +564,46 @@ impl ResProcesses {
         columns.push(self.add_system_cpu_time_column(&column_view));
         columns.push(self.add_priority_column(&column_view));
         columns.push(self.add_swap_column(&column_view));
+        columns.push(self.add_cpu_throttling_column(&column_view));
+        columns.push(self.add_shared_memory_column(&column_view));
+        columns.push(self.add_media_column(&column_view));
+        columns.push(self.add_locked_memory_column(&column_view));
+        columns.push(self.add_pinned_memory_column(&column_view));
+        columns.push(self.add_container_column(&column_view));
+
+        // with a single GPU, its usage column would just be a copy of the combined one
+        let gpu_usage_columns: Vec<ColumnViewColumn> = if gpus.len() > 1 {
+            gpus.iter()
+                .map(|(gpu_identifier, label)| {
+                    self.add_gpu_usage_of_column(&column_view, *gpu_identifier, label)
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        columns.extend(gpu_usage_columns.iter().cloned());
+
+        // new columns are added last so that the indices of the other columns (which the sort column is saved as)
+        // don't change, but the media column belongs next to the encoder and decoder columns that it replaces
+        let column_position = |id: &str| {
+            columns
+                .iter()
+                .position(|column| column.id().as_deref() == Some(id))
+        };
+        if let (Some(decoder_position), Some(media_position)) =
+            (column_position("decoder"), column_position("media"))
+        {
+            column_view.insert_column(decoder_position as u32 + 1, &columns[media_position]);
+        }
+        if let Some(gpu_position) = column_position("gpu") {
+            for gpu_usage_column in gpu_usage_columns.iter().rev() {
+                column_view.insert_column(gpu_position as u32 + 1, gpu_usage_column);
+            }
+        }
+
+        for column in columns.iter() {
+            column.set_header_menu(Some(&copy_column_menu("processes.copy-column", column)));
+        }
 
         let store = gio::ListStore::new::<ProcessEntry>();
 
@@ -477,7 +616,38 @@ impl ResProcesses {
             ))),
         );
 
-        let sort_model = gtk::SortListModel::new(Some(filter_model.clone()), column_view.sorter());
+        // when grouping by container is enabled, the container takes precedence over whatever column the user has
+        // chosen to sort by
+        let container_sorter = gtk::CustomSorter::new(|a, b| {
+            if !SETTINGS.processes_group_by_container() {
+                return gtk::Ordering::Equal;
+            }
+
+            let a = a.downcast_ref::<ProcessEntry>().unwrap();
+            let b = b.downcast_ref::<ProcessEntry>().unwrap();
+
+            let a_container = a.container();
+            let b_container = b.container();
+
+            // processes that don't run in a container should always be at the bottom
+            (a_container.is_empty(), a_container.to_lowercase())
+                .cmp(&(b_container.is_empty(), b_container.to_lowercase()))
+                .into()
+        });
+
+        let sorter = gtk::MultiSorter::new();
+        sorter.append(container_sorter.clone());
+        if let Some(column_view_sorter) = column_view.sorter() {
+            sorter.append(column_view_sorter);
+        }
+
+        SETTINGS.connect_processes_group_by_container(clone!(
+            #[weak]
+            container_sorter,
+            move |_| container_sorter.changed(gtk::SorterChange::Different)
+        ));
+
+        let sort_model = gtk::SortListModel::new(Some(filter_model.clone()), Some(sorter));
 
         let selection_model = gtk::MultiSelection::new(Some(sort_model.clone()));
 
@@ -498,6 +668,25 @@ impl ResProcesses {
         *imp.filter_model.borrow_mut() = filter_model;
 
         imp.processes_scrolled_window.set_child(Some(&*column_view));
+
+        imp.cpu_graph.set_graph_color(
+            ResCPU::MAIN_GRAPH_COLOR[0],
+            ResCPU::MAIN_GRAPH_COLOR[1],
+            ResCPU::MAIN_GRAPH_COLOR[2],
+        );
+
+        imp.memory_graph.set_graph_color(
+            ResMemory::MAIN_GRAPH_COLOR[0],
+            ResMemory::MAIN_GRAPH_COLOR[1],
+            ResMemory::MAIN_GRAPH_COLOR[2],
+        );
+        imp.memory_graph.set_locked_max_y(None);
+
+        imp.gpu_graph.set_graph_color(
+            ResGPU::MAIN_GRAPH_COLOR[0],
+            ResGPU::MAIN_GRAPH_COLOR[1],
+            ResGPU::MAIN_GRAPH_COLOR[2],
+        );
     }
 
     pub fn setup_signals(&self) {
@@ -516,7 +705,7 @@ impl ResProcesses {
                     let bitset = model.selection();
 
                     imp.information_button.set_sensitive(bitset.size() == 1);
-                    imp.options_button.set_sensitive(bitset.size() == 1);
+                    imp.options_button.set_sensitive(bitset.size() > 0);
                     imp.end_process_button.set_sensitive(bitset.size() > 0);
 
                     if bitset.size() <= 1 {
@@ -528,9 +717,23 @@ impl ResProcesses {
                         imp.end_process_button
                             .set_menu_model(Some(&imp.end_process_menu_multiple.get()));
                     }
+
+                    this.refresh_graphs();
                 }
             ));
 
+        SETTINGS.connect_processes_show_graphs(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |_| this.refresh_graphs()
+        ));
+
+        imp.gpu_filter_banner.connect_button_clicked(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |_| this.show_all_processes()
+        ));
+
         imp.search_button.connect_toggled(clone!(
             #[weak(rename_to = this)]
             self,
@@ -573,15 +776,9 @@ impl ResProcesses {
             #[weak(rename_to = this)]
             self,
             move |_| {
-                let imp = this.imp();
-                let bitset = imp.selection_model.borrow().selection();
-                let selection_option = imp
-                    .selection_model
-                    .borrow()
-                    .item(bitset.maximum()) // the info button is only available when only 1 item is selected, so this should be fine
-                    .map(|object| object.downcast::<ProcessEntry>().unwrap());
-                if let Some(selection) = selection_option {
-                    this.open_options_dialog(&selection);
+                let selected = this.get_selected_process_entries();
+                if !selected.is_empty() {
+                    this.open_options_dialog(&selected);
                 }
             }
         ));
@@ -619,6 +816,11 @@ impl ResProcesses {
                 #[weak(rename_to = this)]
                 self,
                 move |sorter, _| {
+                    // the GPU filter sorts by video memory by itself, which isn't what the user chose
+                    if this.imp().gpu_filter.get().is_some() {
+                        return;
+                    }
+
                     if let Some(sorter) = sorter.downcast_ref::<gtk::ColumnViewSorter>() {
                         let current_column = sorter
                             .primary_sort_column()
@@ -648,7 +850,7 @@ impl ResProcesses {
         }
     }
 
-    pub fn open_options_dialog(&self, process: &ProcessEntry) {
+    pub fn open_options_dialog(&self, processes: &[ProcessEntry]) {
         let imp = self.imp();
 
         if imp.open_info_dialog.borrow().is_some() || imp.open_options_dialog.borrow().is_some() {
@@ -660,7 +862,7 @@ impl ResProcesses {
         let dialog = ResProcessOptionsDialog::new();
 
         dialog.init(
-            process,
+            processes,
             imp.sender.get().unwrap().clone(),
             &imp.toast_overlay,
         );
@@ -675,7 +877,8 @@ impl ResProcesses {
 
         dialog.present(Some(&MainWindow::default()));
 
-        *imp.open_options_dialog.borrow_mut() = Some((process.pid(), dialog));
+        *imp.open_options_dialog.borrow_mut() =
+            Some((processes.iter().map(ProcessEntry::pid).collect(), dialog));
     }
 
     pub fn open_info_dialog(&self, process: &ProcessEntry) {
@@ -708,9 +911,96 @@ impl ResProcesses {
         let imp = self.imp();
         let item = obj.downcast_ref::<ProcessEntry>().unwrap();
         let search_string = imp.search_entry.text().to_string().to_lowercase();
-        !imp.search_revealer.reveals_child()
-            || item.name().to_lowercase().contains(&search_string)
-            || item.commandline().to_lowercase().contains(&search_string)
+        let matches_gpu_filter =
+            imp.gpu_filter.get().is_none() || imp.gpu_filter_pids.borrow().contains(&item.pid());
+        matches_gpu_filter
+            && (!imp.search_revealer.reveals_child()
+                || item.name().to_lowercase().contains(&search_string)
+                || item.commandline().to_lowercase().contains(&search_string))
+    }
+
+    /// Only shows the processes that use video memory of the GPU with `gpu_identifier` and sorts them by their video
+    /// memory usage, `gpu_name` is how the GPU is called in the sidebar
+    pub fn show_gpu_memory_users(
+        &self,
+        gpu_identifier: GpuIdentifier,
+        gpu_name: &str,
+        apps_context: &AppsContext,
+    ) {
+        let imp = self.imp();
+
+        imp.gpu_filter.set(Some(gpu_identifier));
+        self.refresh_gpu_filter(apps_context);
+
+        imp.gpu_filter_banner.set_title(&i18n_f(
+            "Only showing processes using video memory of {}",
+            &[gpu_name],
+        ));
+        imp.gpu_filter_banner.set_revealed(true);
+
+        if let Some(gpu_mem_column) = imp
+            .columns
+            .borrow()
+            .iter()
+            .find(|column| column.id().as_deref() == Some("gpu_mem"))
+        {
+            gpu_mem_column.set_visible(true);
+            imp.column_view
+                .borrow()
+                .sort_by_column(Some(gpu_mem_column), SortType::Descending);
+        }
+    }
+
+    /// Removes the filter set by `show_gpu_memory_users()`
+    pub fn show_all_processes(&self) {
+        let imp = self.imp();
+
+        imp.gpu_filter.set(None);
+        imp.gpu_filter_pids.borrow_mut().clear();
+        imp.gpu_filter_banner.set_revealed(false);
+
+        let columns = imp.columns.borrow();
+
+        if let Some(gpu_mem_column) = columns
+            .iter()
+            .find(|column| column.id().as_deref() == Some("gpu_mem"))
+        {
+            gpu_mem_column.set_visible(SETTINGS.processes_show_gpu_memory());
+        }
+
+        // go back to the sorting that was in place before the GPU filter
+        imp.column_view.borrow().sort_by_column(
+            columns
+                .get(SETTINGS.processes_sort_by() as usize)
+                .or_else(|| columns.get(3)),
+            SETTINGS.processes_sort_by_ascending(),
+        );
+
+        if let Some(filter) = imp.filter_model.borrow().filter() {
+            filter.changed(FilterChange::LessStrict);
+        }
+    }
+
+    fn refresh_gpu_filter(&self, apps_context: &AppsContext) {
+        let imp = self.imp();
+
+        let Some(gpu_identifier) = imp.gpu_filter.get() else {
+            return;
+        };
+
+        *imp.gpu_filter_pids.borrow_mut() = apps_context
+            .processes_iter()
+            .filter(|process| {
+                process
+                    .gpu_mem_usage_of(&gpu_identifier)
+                    .is_some_and(|mem_usage| mem_usage > 0)
+            })
+            .map(|process| process.data.pid)
+            .collect();
+
+        if let Some(filter) = imp.filter_model.borrow().filter() {
+            filter.changed(FilterChange::Different);
+        }
     }
 
     pub fn get_selected_process_entries(&self) -> Vec<ProcessEntry> {
@@ -743,6 +1033,222 @@ impl ResProcesses {
         }
     }
 
+    /// Copies the visible columns of the selected processes to the clipboard as tab-separated values, one line per
+    /// process
+    fn copy_selected_rows(&self) {
+        let selected = self.get_selected_process_entries();
+        if selected.is_empty() {
+            return;
+        }
+
+        let columns = visible_columns(&self.imp().column_view.borrow());
+
+        let text = selected
+            .iter()
+            .map(|process| {
+                tsv_line(
+                    columns
+                        .iter()
+                        .map(|column| column_text(process, &column.id().unwrap_or_default())),
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        self.clipboard().set_text(&text);
+    }
+
+    /// Copies the values of the column with the ID `column_id` of all processes that are currently listed to the
+    /// clipboard, in the order they're displayed in
+    fn copy_column(&self, column_id: &str) {
+        let text = self
+            .imp()
+            .sort_model
+            .borrow()
+            .iter::<ProcessEntry>()
+            .flatten()
+            .map(|process| tsv_line([column_text(&process, column_id)]))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        self.clipboard().set_text(&text);
+    }
+
+    /// Lets the user export the visible columns of all processes that are currently listed to a CSV or JSON file
+    fn export(&self) {
+        MainContext::default().spawn_local(clone!(
+            #[weak(rename_to = this)]
+            self,
+            async move {
+                let imp = this.imp();
+
+                let sort_model = imp.sort_model.borrow().clone();
+                let column_view = imp.column_view.borrow().clone();
+
+                let toast_message = match export_table(
+                    &this,
+                    "processes.csv",
+                    &column_view,
+                    &sort_model,
+                    column_text,
+                )
+                .await
+                {
+                    Ok(true) => i18n("Table exported"),
+                    Ok(false) => return,
+                    Err(e) => {
+                        warn!("Unable to export table: {e}");
+                        i18n("Unable to export table")
+                    }
+                };
+
+                imp.toast_overlay.add_toast(adw::Toast::new(&toast_message));
+            }
+        ));
+    }
+
+    /// Shows the usage history of the selected process below the list if exactly one process is selected
+    fn refresh_graphs(&self) {
+        let imp = self.imp();
+
+        let selected = self.get_selected_process_entries();
+
+        let process = match selected.as_slice() {
+            [process] if SETTINGS.processes_show_graphs() => process,
+            _ => {
+                imp.graphs_revealer.set_reveal_child(false);
+                if let Some(previous) = imp.graphs_process.take() {
+                    previous.set_records_history(false);
+                }
+                return;
+            }
+        };
+
+        if let Some(previous) = imp.graphs_process.replace(Some(process.clone())) {
+            if &previous != process {
+                previous.set_records_history(false);
+            }
+        }
+        process.set_records_history(true);
+
+        let mut cpu_usage = process.cpu_usage() * 100.0;
+        if !SETTINGS.normalize_cpu_usage() {
+            cpu_usage *= *NUM_CPUS as f32;
+        }
+
+        imp.cpu_graph.set_data_points(&process.cpu_usage_history());
+        imp.cpu_graph_label.set_label(&format!(
+            "{} · {}",
+            i18n("Processor"),
+            format_percentage(cpu_usage)
+        ));
+
+        imp.memory_graph
+            .set_data_points(&process.memory_usage_history());
+        imp.memory_graph_label.set_label(&format!(
+            "{} · {}",
+            i18n("Memory"),
+            convert_storage(process.memory_usage() as f64, false)
+        ));
+
+        imp.gpu_graph.set_data_points(&process.gpu_usage_history());
+        imp.gpu_graph_label.set_label(&format!(
+            "{} · {}",
+            i18n("GPU"),
+            format_percentage(process.gpu_usage() * 100.0)
+        ));
+
+        imp.graphs_revealer.set_reveal_child(true);
+    }
+
+    /// Returns the PID of the process whose info dialog is currently open
+    pub fn info_dialog_pid(&self) -> Option<libc::pid_t> {
+        let imp = self.imp();
+
+        if imp.info_dialog_closed.get() {
+            None
+        } else {
+            imp.open_info_dialog.borrow().as_ref().map(|(pid, _)| *pid)
+        }
+    }
+
+    pub fn refresh_info_dialog_threads(&self, pid: libc::pid_t, threads: &[ThreadData]) {
+        if let Some((dialog_pid, dialog)) = &*self.imp().open_info_dialog.borrow() {
+            if *dialog_pid == pid {
+                dialog.update_threads(threads);
+            }
+        }
+    }
+
+    pub fn refresh_info_dialog_drm_fdinfos(&self, pid: libc::pid_t, fdinfos: &[DrmFdinfo]) {
+        if let Some((dialog_pid, dialog)) = &*self.imp().open_info_dialog.borrow() {
+            if *dialog_pid == pid {
+                dialog.update_drm_fdinfos(fdinfos);
+            }
+        }
+    }
+
+    pub fn refresh_info_dialog_limits(&self, pid: libc::pid_t, limits: &ProcessLimits) {
+        if let Some((dialog_pid, dialog)) = &*self.imp().open_info_dialog.borrow() {
+            if *dialog_pid == pid {
+                dialog.update_limits(limits);
+            }
+        }
+    }
+
+    pub fn refresh_info_dialog_sockets(&self, pid: libc::pid_t, sockets: &[SocketData]) {
+        if let Some((dialog_pid, dialog)) = &*self.imp().open_info_dialog.borrow() {
+            if *dialog_pid == pid {
+                dialog.update_sockets(sockets);
+            }
+        }
+    }
+
+    pub fn refresh_info_dialog_environment(
+        &self,
+        pid: libc::pid_t,
+        environment: &[EnvironmentVariable],
+    ) {
+        if let Some((dialog_pid, dialog)) = &*self.imp().open_info_dialog.borrow() {
+            if *dialog_pid == pid {
+                dialog.update_environment(environment);
+            }
+        }
+    }
+
+    pub fn refresh_info_dialog_open_files(&self, pid: libc::pid_t, open_files: &[OpenFile]) {
+        if let Some((dialog_pid, dialog)) = &*self.imp().open_info_dialog.borrow() {
+            if *dialog_pid == pid {
+                dialog.update_open_files(open_files);
+            }
+        }
+    }
+
+    pub fn refresh_info_dialog_wait_channel(&self, pid: libc::pid_t, wait_channel: &WaitChannel) {
+        if let Some((dialog_pid, dialog)) = &*self.imp().open_info_dialog.borrow() {
+            if *dialog_pid == pid {
+                dialog.update_wait_channel(wait_channel);
+            }
+        }
+    }
+
+    pub fn refresh_info_dialog_schedstat(&self, pid: libc::pid_t, schedstat: Option<SchedStat>) {
+        if let Some((dialog_pid, dialog)) = &*self.imp().open_info_dialog.borrow() {
+            if *dialog_pid == pid {
+                dialog.update_schedstat(schedstat);
+            }
+        }
+    }
+
+    pub fn get_process_entry(&self, pid: libc::pid_t) -> Option<ProcessEntry> {
+        self.imp()
+            .store
+            .borrow()
+            .iter::<ProcessEntry>()
+            .flatten()
+            .find(|entry| entry.pid() == pid)
+    }
+
     pub fn refresh_processes_list(&self, apps_context: &AppsContext) {
         let imp = self.imp();
 
@@ -756,6 +1262,8 @@ impl ResProcesses {
             imp.options_dialog_closed.set(false);
         }
 
+        self.refresh_gpu_filter(apps_context);
+
         let store = imp.store.borrow_mut();
         let mut info_dialog_opt = imp.open_info_dialog.borrow_mut();
         let mut options_dialog_opt = imp.open_options_dialog.borrow_mut();
@@ -763,11 +1271,22 @@ impl ResProcesses {
         let mut pids_to_remove = HashSet::new();
         let mut already_existing_pids = HashSet::new();
 
+        let depths = if SETTINGS.processes_show_tree_depth() {
+            Process::tree_depths(
+                apps_context
+                    .processes_iter()
+                    .map(|process| (process.data.pid, process.data.parent_pid)),
+            )
+        } else {
+            HashMap::new()
+        };
+
         // change process entries of processes that have existed before
         store.iter::<ProcessEntry>().flatten().for_each(|object| {
             let item_pid = object.pid();
             if let Some(process) = apps_context.get_process(item_pid) {
                 object.update(process);
+                object.set_depth(depths.get(&item_pid).copied().unwrap_or_default());
                 if let Some((dialog_pid, dialog)) = &*info_dialog_opt {
                     if *dialog_pid == item_pid {
                         dialog.update(&object);
@@ -782,8 +1301,10 @@ impl ResProcesses {
                         *info_dialog_opt = None;
                     }
                 }
-                if let Some((dialog_pid, dialog)) = &*options_dialog_opt {
-                    if *dialog_pid == item_pid {
+                // only close the options dialog once all of its processes have ended
+                if let Some((dialog_pids, dialog)) = &mut *options_dialog_opt {
+                    dialog_pids.retain(|pid| *pid != item_pid);
+                    if dialog_pids.is_empty() {
                         dialog.close();
                         *options_dialog_opt = None;
                     }
@@ -809,7 +1330,11 @@ impl ResProcesses {
                 !already_existing_pids.contains(&process.data.pid)
                     && !pids_to_remove.contains(&process.data.pid)
             })
-            .map(ProcessEntry::new)
+            .map(|process| {
+                let entry = ProcessEntry::new(process);
+                entry.set_depth(depths.get(&process.data.pid).copied().unwrap_or_default());
+                entry
+            })
             .collect();
         store.extend_from_slice(&items);
 
@@ -821,11 +1346,18 @@ impl ResProcesses {
             "Running Processes: {}",
             &[&(store.n_items()).to_string()],
         ));
+
+        std::mem::drop(store);
+
+        self.refresh_graphs();
     }
 
     pub fn open_process_action_dialog(&self, processes: Vec<ProcessEntry>, action: ProcessAction) {
-        // Nothing too bad can happen on Continue so dont show the dialog
-        if action == ProcessAction::CONT {
+        // Nothing too bad can happen on Continue so dont show the dialog, ending can be confirmed without it if the
+        // user wishes so
+        if action == ProcessAction::CONT
+            || (action == ProcessAction::TERM && !SETTINGS.confirm_end_action())
+        {
             let main_context = MainContext::default();
             main_context.spawn_local(clone!(
                 #[weak(rename_to = this)]
@@ -919,6 +1451,7 @@ impl ResProcesses {
             gtk::ColumnViewColumn::new(Some(&i18n("Process")), Some(name_col_factory.clone()));
 
         name_col.set_resizable(true);
+        name_col.set_id(Some("name"));
 
         name_col.set_expand(true);
 
@@ -948,6 +1481,10 @@ impl ResProcesses {
                     .chain_property::<ProcessEntry>("symbolic")
                     .bind(&row, "symbolic", Widget::NONE);
 
+                item.property_expression("item")
+                    .chain_property::<ProcessEntry>("depth")
+                    .bind(&row, "depth", Widget::NONE);
+
                 this.add_gestures(item);
             }
         ));
@@ -980,6 +1517,7 @@ impl ResProcesses {
             gtk::ColumnViewColumn::new(Some(&i18n("Process ID")), Some(pid_col_factory.clone()));
 
         pid_col.set_resizable(true);
+        pid_col.set_id(Some("pid"));
 
         pid_col_factory.connect_setup(clone!(
             #[weak(rename_to = this)]
@@ -1033,6 +1571,7 @@ impl ResProcesses {
             gtk::ColumnViewColumn::new(Some(&i18n("User")), Some(user_col_factory.clone()));
 
         user_col.set_resizable(true);
+        user_col.set_id(Some("user"));
 
         user_col_factory.connect_setup(clone!(
             #[weak(rename_to = this)]
@@ -1087,6 +1626,7 @@ impl ResProcesses {
             gtk::ColumnViewColumn::new(Some(&i18n("Memory")), Some(memory_col_factory.clone()));
 
         memory_col.set_resizable(true);
+        memory_col.set_id(Some("memory"));
 
         memory_col_factory.connect_setup(clone!(
             #[weak(rename_to = this)]
@@ -1106,6 +1646,13 @@ impl ResProcesses {
                     }))
                     .bind(&row, "text", Widget::NONE);
 
+                highlight_changes::<ProcessEntry>(
+                    item,
+                    &row,
+                    "memory_usage",
+                    ChangeScale::Relative,
+                );
+
                 this.add_gestures(item);
             }
         ));
@@ -1145,6 +1692,7 @@ impl ResProcesses {
             gtk::ColumnViewColumn::new(Some(&i18n("Processor")), Some(cpu_col_factory.clone()));
 
         cpu_col.set_resizable(true);
+        cpu_col.set_id(Some("cpu"));
 
         cpu_col_factory.connect_setup(clone!(
             #[weak(rename_to = this)]
@@ -1160,15 +1708,12 @@ impl ResProcesses {
                 item.property_expression("item")
                     .chain_property::<ProcessEntry>("cpu_usage")
                     .chain_closure::<String>(closure!(|_: Option<Object>, cpu_usage: f32| {
-                        let mut percentage = cpu_usage * 100.0;
-                        if !SETTINGS.normalize_cpu_usage() {
-                            percentage *= *NUM_CPUS as f32;
-                        }
-
-                        format!("{percentage:.1} %")
+                        format_cpu_usage(cpu_usage)
                     }))
                     .bind(&row, "text", Widget::NONE);
 
+                highlight_changes::<ProcessEntry>(item, &row, "cpu_usage", ChangeScale::Fraction);
+
                 this.add_gestures(item);
             }
         ));
@@ -1210,6 +1755,7 @@ impl ResProcesses {
         );
 
         read_speed_col.set_resizable(true);
+        read_speed_col.set_id(Some("read_speed"));
 
         read_speed_col_factory.connect_setup(clone!(
             #[weak(rename_to = this)]
@@ -1225,14 +1771,12 @@ impl ResProcesses {
                 item.property_expression("item")
                     .chain_property::<ProcessEntry>("read_speed")
                     .chain_closure::<String>(closure!(|_: Option<Object>, read_speed: f64| {
-                        if read_speed == -1.0 {
-                            i18n("N/A")
-                        } else {
-                            convert_speed(read_speed, false)
-                        }
+                        format_io_speed(read_speed)
                     }))
                     .bind(&row, "text", Widget::NONE);
 
+                highlight_changes::<ProcessEntry>(item, &row, "read_speed", ChangeScale::Relative);
+
                 this.add_gestures(item);
             }
         ));
@@ -1276,6 +1820,7 @@ impl ResProcesses {
         );
 
         read_total_col.set_resizable(true);
+        read_total_col.set_id(Some("read_total"));
 
         read_total_col_factory.connect_setup(clone!(
             #[weak(rename_to = this)]
@@ -1291,11 +1836,7 @@ impl ResProcesses {
                 item.property_expression("item")
                     .chain_property::<ProcessEntry>("read_total")
                     .chain_closure::<String>(closure!(|_: Option<Object>, read_total: i64| {
-                        if read_total == -1 {
-                            i18n("N/A")
-                        } else {
-                            convert_storage(read_total as f64, false)
-                        }
+                        format_io_total(read_total)
                     }))
                     .bind(&row, "text", Widget::NONE);
 
@@ -1342,6 +1883,7 @@ impl ResProcesses {
         );
 
         write_speed_col.set_resizable(true);
+        write_speed_col.set_id(Some("write_speed"));
 
         write_speed_col_factory.connect_setup(clone!(
             #[weak(rename_to = this)]
@@ -1357,14 +1899,12 @@ impl ResProcesses {
                 item.property_expression("item")
                     .chain_property::<ProcessEntry>("write_speed")
                     .chain_closure::<String>(closure!(|_: Option<Object>, write_speed: f64| {
-                        if write_speed == -1.0 {
-                            i18n("N/A")
-                        } else {
-                            convert_speed(write_speed, false)
-                        }
+                        format_io_speed(write_speed)
                     }))
                     .bind(&row, "text", Widget::NONE);
 
+                highlight_changes::<ProcessEntry>(item, &row, "write_speed", ChangeScale::Relative);
+
                 this.add_gestures(item);
             }
         ));
@@ -1408,6 +1948,7 @@ impl ResProcesses {
         );
 
         write_total_col.set_resizable(true);
+        write_total_col.set_id(Some("write_total"));
 
         write_total_col_factory.connect_setup(clone!(
             #[weak(rename_to = this)]
@@ -1423,11 +1964,7 @@ impl ResProcesses {
                 item.property_expression("item")
                     .chain_property::<ProcessEntry>("write_total")
                     .chain_closure::<String>(closure!(|_: Option<Object>, write_total: i64| {
-                        if write_total == -1 {
-                            i18n("N/A")
-                        } else {
-                            convert_storage(write_total as f64, false)
-                        }
+                        format_io_total(write_total)
                     }))
                     .bind(&row, "text", Widget::NONE);
 
@@ -1471,6 +2008,7 @@ impl ResProcesses {
         let gpu_col = gtk::ColumnViewColumn::new(Some(&i18n("GPU")), Some(gpu_col_factory.clone()));
 
         gpu_col.set_resizable(true);
+        gpu_col.set_id(Some("gpu"));
 
         gpu_col_factory.connect_setup(clone!(
             #[weak(rename_to = this)]
@@ -1486,10 +2024,12 @@ impl ResProcesses {
                 item.property_expression("item")
                     .chain_property::<ProcessEntry>("gpu_usage")
                     .chain_closure::<String>(closure!(|_: Option<Object>, gpu_usage: f32| {
-                        format!("{:.1} %", gpu_usage * 100.0)
+                        format_percentage(gpu_usage * 100.0)
                     }))
                     .bind(&row, "text", Widget::NONE);
 
+                highlight_changes::<ProcessEntry>(item, &row, "gpu_usage", ChangeScale::Fraction);
+
                 this.add_gestures(item);
             }
         ));
@@ -1531,6 +2071,7 @@ impl ResProcesses {
         );
 
         encoder_col.set_resizable(true);
+        encoder_col.set_id(Some("encoder"));
 
         encoder_col_factory.connect_setup(clone!(
             #[weak(rename_to = this)]
@@ -1546,10 +2087,12 @@ impl ResProcesses {
                 item.property_expression("item")
                     .chain_property::<ProcessEntry>("enc_usage")
                     .chain_closure::<String>(closure!(|_: Option<Object>, enc_usage: f32| {
-                        format!("{:.1} %", enc_usage * 100.0)
+                        format_percentage(enc_usage * 100.0)
                     }))
                     .bind(&row, "text", Widget::NONE);
 
+                highlight_changes::<ProcessEntry>(item, &row, "enc_usage", ChangeScale::Fraction);
+
                 this.add_gestures(item);
             }
         ));
@@ -1569,14 +2112,21 @@ impl ResProcesses {
             .build();
 
         encoder_col.set_sorter(Some(&encoder_col_sorter));
-        encoder_col.set_visible(SETTINGS.processes_show_encoder());
+        encoder_col
+            .set_visible(SETTINGS.processes_show_encoder() && !SETTINGS.merge_media_columns());
 
         column_view.append_column(&encoder_col);
 
         SETTINGS.connect_processes_show_encoder(clone!(
             #[weak]
             encoder_col,
-            move |visible| encoder_col.set_visible(visible)
+            move |visible| encoder_col.set_visible(visible && !SETTINGS.merge_media_columns())
+        ));
+
+        SETTINGS.connect_merge_media_columns(clone!(
+            #[weak]
+            encoder_col,
+            move |merge| encoder_col.set_visible(SETTINGS.processes_show_encoder() && !merge)
         ));
 
         encoder_col
@@ -1591,6 +2141,7 @@ impl ResProcesses {
         );
 
         decoder_col.set_resizable(true);
+        decoder_col.set_id(Some("decoder"));
 
         decoder_col_factory.connect_setup(clone!(
             #[weak(rename_to = this)]
@@ -1606,10 +2157,12 @@ impl ResProcesses {
                 item.property_expression("item")
                     .chain_property::<ProcessEntry>("dec_usage")
                     .chain_closure::<String>(closure!(|_: Option<Object>, dec_usage: f32| {
-                        format!("{:.1} %", dec_usage * 100.0)
+                        format_percentage(dec_usage * 100.0)
                     }))
                     .bind(&row, "text", Widget::NONE);
 
+                highlight_changes::<ProcessEntry>(item, &row, "dec_usage", ChangeScale::Fraction);
+
                 this.add_gestures(item);
             }
         ));
@@ -1629,30 +2182,118 @@ impl ResProcesses {
             .build();
 
         decoder_col.set_sorter(Some(&decoder_col_sorter));
-        decoder_col.set_visible(SETTINGS.processes_show_decoder());
+        decoder_col
+            .set_visible(SETTINGS.processes_show_decoder() && !SETTINGS.merge_media_columns());
 
         column_view.append_column(&decoder_col);
 
         SETTINGS.connect_processes_show_decoder(clone!(
             #[weak]
             decoder_col,
-            move |visible| decoder_col.set_visible(visible)
+            move |visible| decoder_col.set_visible(visible && !SETTINGS.merge_media_columns())
+        ));
+
+        SETTINGS.connect_merge_media_columns(clone!(
+            #[weak]
+            decoder_col,
+            move |merge| decoder_col.set_visible(SETTINGS.processes_show_decoder() && !merge)
         ));
 
         decoder_col
     }
 
-    fn add_gpu_mem_column(&self, column_view: &ColumnView) -> ColumnViewColumn {
-        let gpu_mem_col_factory = gtk::SignalListItemFactory::new();
+    fn add_media_column(&self, column_view: &ColumnView) -> ColumnViewColumn {
+        let media_col_factory = gtk::SignalListItemFactory::new();
 
-        let gpu_mem_col = gtk::ColumnViewColumn::new(
-            Some(&i18n("Video Memory")),
-            Some(gpu_mem_col_factory.clone()),
-        );
+        let media_col =
+            gtk::ColumnViewColumn::new(Some(&i18n("Media")), Some(media_col_factory.clone()));
 
-        gpu_mem_col.set_resizable(true);
+        media_col.set_resizable(true);
+        media_col.set_id(Some("media"));
 
-        gpu_mem_col_factory.connect_setup(clone!(
+        media_col_factory.connect_setup(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |_factory, item| {
+                let item = item.downcast_ref::<gtk::ListItem>().unwrap();
+
+                let row = gtk::Inscription::new(None);
+                row.set_min_chars(7);
+
+                item.set_child(Some(&row));
+
+                item.property_expression("item")
+                    .chain_property::<ProcessEntry>("media_usage")
+                    .chain_closure::<String>(closure!(|_: Option<Object>, media_usage: f32| {
+                        format_percentage(media_usage * 100.0)
+                    }))
+                    .bind(&row, "text", Widget::NONE);
+
+                highlight_changes::<ProcessEntry>(item, &row, "media_usage", ChangeScale::Fraction);
+
+                this.add_gestures(item);
+            }
+        ));
+
+        media_col_factory.connect_teardown(move |_factory, item| {
+            let item = item.downcast_ref::<gtk::ListItem>().unwrap();
+            item.set_child(None::<&gtk::Inscription>);
+        });
+
+        let media_col_sorter = NumericSorter::builder()
+            .sort_order(SortType::Ascending)
+            .expression(gtk::PropertyExpression::new(
+                ProcessEntry::static_type(),
+                None::<&gtk::Expression>,
+                "media_usage",
+            ))
+            .build();
+
+        media_col.set_sorter(Some(&media_col_sorter));
+
+        // the media column takes the place of the encoder and decoder columns, so it's shown if any of them would be
+        let media_visible = || {
+            SETTINGS.merge_media_columns()
+                && (SETTINGS.processes_show_encoder() || SETTINGS.processes_show_decoder())
+        };
+
+        media_col.set_visible(media_visible());
+
+        column_view.append_column(&media_col);
+
+        SETTINGS.connect_merge_media_columns(clone!(
+            #[weak]
+            media_col,
+            move |_| media_col.set_visible(media_visible())
+        ));
+
+        SETTINGS.connect_processes_show_encoder(clone!(
+            #[weak]
+            media_col,
+            move |_| media_col.set_visible(media_visible())
+        ));
+
+        SETTINGS.connect_processes_show_decoder(clone!(
+            #[weak]
+            media_col,
+            move |_| media_col.set_visible(media_visible())
+        ));
+
+        media_col
+    }
+
+    fn add_gpu_mem_column(&self, column_view: &ColumnView) -> ColumnViewColumn {
+        let gpu_mem_col_factory = gtk::SignalListItemFactory::new();
+
+        let gpu_mem_col = gtk::ColumnViewColumn::new(
+            Some(&i18n("Video Memory")),
+            Some(gpu_mem_col_factory.clone()),
+        );
+
+        gpu_mem_col.set_resizable(true);
+        gpu_mem_col.set_id(Some("gpu_mem"));
+
+        gpu_mem_col_factory.connect_setup(clone!(
             #[weak(rename_to = this)]
             self,
             move |_factory, item| {
@@ -1669,6 +2310,13 @@ impl ResProcesses {
                     }))
                     .bind(&row, "text", Widget::NONE);
 
+                highlight_changes::<ProcessEntry>(
+                    item,
+                    &row,
+                    "gpu_mem_usage",
+                    ChangeScale::Relative,
+                );
+
                 this.add_gestures(item);
             }
         ));
@@ -1710,6 +2358,7 @@ impl ResProcesses {
         );
 
         total_cpu_time_col.set_resizable(true);
+        total_cpu_time_col.set_id(Some("total_cpu_time"));
 
         total_cpu_time_col_factory.connect_setup(clone!(
             #[weak(rename_to = this)]
@@ -1769,6 +2418,7 @@ impl ResProcesses {
         );
 
         user_cpu_time_col.set_resizable(true);
+        user_cpu_time_col.set_id(Some("user_cpu_time"));
 
         user_cpu_time_col_factory.connect_setup(clone!(
             #[weak(rename_to = this)]
@@ -1828,6 +2478,7 @@ impl ResProcesses {
         );
 
         system_cpu_time_col.set_resizable(true);
+        system_cpu_time_col.set_id(Some("system_cpu_time"));
 
         system_cpu_time_col_factory.connect_setup(clone!(
             #[weak(rename_to = this)]
@@ -1885,6 +2536,7 @@ impl ResProcesses {
             gtk::ColumnViewColumn::new(Some(&i18n("Priority")), Some(priority_col_factory.clone()));
 
         priority_col.set_resizable(true);
+        priority_col.set_id(Some("priority"));
 
         priority_col_factory.connect_setup(clone!(
             #[weak(rename_to = this)]
@@ -1899,17 +2551,7 @@ impl ResProcesses {
                 item.property_expression("item")
                     .chain_property::<ProcessEntry>("niceness")
                     .chain_closure::<String>(closure!(|_: Option<Object>, niceness: i8| {
-                        if SETTINGS.detailed_priority() {
-                            niceness.to_string()
-                        } else if let Ok(niceness) = Niceness::try_from(niceness) {
-                            NICE_TO_LABEL
-                                .get(&niceness)
-                                .map(|(s, _)| s)
-                                .cloned()
-                                .unwrap_or_else(|| i18n("N/A"))
-                        } else {
-                            i18n("N/A")
-                        }
+                        format_priority(niceness)
                     }))
                     .bind(&row, "text", Widget::NONE);
 
@@ -1952,6 +2594,7 @@ impl ResProcesses {
             gtk::ColumnViewColumn::new(Some(&i18n("Swap")), Some(swap_col_factory.clone()));
 
         swap_col.set_resizable(true);
+        swap_col.set_id(Some("swap"));
 
         swap_col_factory.connect_setup(clone!(
             #[weak(rename_to = this)]
@@ -1971,6 +2614,8 @@ impl ResProcesses {
                     }))
                     .bind(&row, "text", Widget::NONE);
 
+                highlight_changes::<ProcessEntry>(item, &row, "swap_usage", ChangeScale::Relative);
+
                 this.add_gestures(item);
             }
         ));
@@ -2002,6 +2647,418 @@ impl ResProcesses {
 
         swap_col
     }
+
+    fn add_shared_memory_column(&self, column_view: &ColumnView) -> ColumnViewColumn {
+        let shared_memory_col_factory = gtk::SignalListItemFactory::new();
+
+        let shared_memory_col = gtk::ColumnViewColumn::new(
+            Some(&i18n("Shared Memory")),
+            Some(shared_memory_col_factory.clone()),
+        );
+
+        shared_memory_col.set_resizable(true);
+        shared_memory_col.set_id(Some("shared_memory"));
+
+        shared_memory_col_factory.connect_setup(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |_factory, item| {
+                let item = item.downcast_ref::<gtk::ListItem>().unwrap();
+
+                let row = gtk::Inscription::new(None);
+                row.set_min_chars(9);
+
+                item.set_child(Some(&row));
+
+                item.property_expression("item")
+                    .chain_property::<ProcessEntry>("shared_memory_usage")
+                    .chain_closure::<String>(closure!(
+                        |_: Option<Object>, shared_memory_usage: u64| {
+                            convert_storage(shared_memory_usage as f64, false)
+                        }
+                    ))
+                    .bind(&row, "text", Widget::NONE);
+
+                highlight_changes::<ProcessEntry>(
+                    item,
+                    &row,
+                    "shared_memory_usage",
+                    ChangeScale::Relative,
+                );
+
+                this.add_gestures(item);
+            }
+        ));
+
+        shared_memory_col_factory.connect_teardown(move |_factory, item| {
+            let item = item.downcast_ref::<gtk::ListItem>().unwrap();
+            item.set_child(None::<&gtk::Inscription>);
+        });
+
+        let shared_memory_col_sorter = NumericSorter::builder()
+            .sort_order(SortType::Ascending)
+            .expression(gtk::PropertyExpression::new(
+                ProcessEntry::static_type(),
+                None::<&gtk::Expression>,
+                "shared_memory_usage",
+            ))
+            .build();
+
+        shared_memory_col.set_sorter(Some(&shared_memory_col_sorter));
+        shared_memory_col.set_visible(SETTINGS.processes_show_shared_memory());
+
+        column_view.append_column(&shared_memory_col);
+
+        SETTINGS.connect_processes_show_shared_memory(clone!(
+            #[weak]
+            shared_memory_col,
+            move |visible| shared_memory_col.set_visible(visible)
+        ));
+
+        shared_memory_col
+    }
+
+    fn add_locked_memory_column(&self, column_view: &ColumnView) -> ColumnViewColumn {
+        let locked_memory_col_factory = gtk::SignalListItemFactory::new();
+
+        let locked_memory_col = gtk::ColumnViewColumn::new(
+            Some(&i18n("Locked Memory")),
+            Some(locked_memory_col_factory.clone()),
+        );
+
+        locked_memory_col.set_resizable(true);
+        locked_memory_col.set_id(Some("locked_memory"));
+
+        locked_memory_col_factory.connect_setup(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |_factory, item| {
+                let item = item.downcast_ref::<gtk::ListItem>().unwrap();
+
+                let row = gtk::Inscription::new(None);
+                row.set_min_chars(9);
+
+                item.set_child(Some(&row));
+
+                item.property_expression("item")
+                    .chain_property::<ProcessEntry>("locked_memory_usage")
+                    .chain_closure::<String>(closure!(
+                        |_: Option<Object>, locked_memory_usage: u64| {
+                            convert_storage(locked_memory_usage as f64, false)
+                        }
+                    ))
+                    .bind(&row, "text", Widget::NONE);
+
+                highlight_changes::<ProcessEntry>(
+                    item,
+                    &row,
+                    "locked_memory_usage",
+                    ChangeScale::Relative,
+                );
+
+                this.add_gestures(item);
+            }
+        ));
+
+        locked_memory_col_factory.connect_teardown(move |_factory, item| {
+            let item = item.downcast_ref::<gtk::ListItem>().unwrap();
+            item.set_child(None::<&gtk::Inscription>);
+        });
+
+        let locked_memory_col_sorter = NumericSorter::builder()
+            .sort_order(SortType::Ascending)
+            .expression(gtk::PropertyExpression::new(
+                ProcessEntry::static_type(),
+                None::<&gtk::Expression>,
+                "locked_memory_usage",
+            ))
+            .build();
+
+        locked_memory_col.set_sorter(Some(&locked_memory_col_sorter));
+        locked_memory_col.set_visible(SETTINGS.processes_show_locked_memory());
+
+        column_view.append_column(&locked_memory_col);
+
+        SETTINGS.connect_processes_show_locked_memory(clone!(
+            #[weak]
+            locked_memory_col,
+            move |visible| locked_memory_col.set_visible(visible)
+        ));
+
+        locked_memory_col
+    }
+
+    fn add_pinned_memory_column(&self, column_view: &ColumnView) -> ColumnViewColumn {
+        let pinned_memory_col_factory = gtk::SignalListItemFactory::new();
+
+        let pinned_memory_col = gtk::ColumnViewColumn::new(
+            Some(&i18n("Pinned Memory")),
+            Some(pinned_memory_col_factory.clone()),
+        );
+
+        pinned_memory_col.set_resizable(true);
+        pinned_memory_col.set_id(Some("pinned_memory"));
+
+        pinned_memory_col_factory.connect_setup(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |_factory, item| {
+                let item = item.downcast_ref::<gtk::ListItem>().unwrap();
+
+                let row = gtk::Inscription::new(None);
+                row.set_min_chars(9);
+
+                item.set_child(Some(&row));
+
+                item.property_expression("item")
+                    .chain_property::<ProcessEntry>("pinned_memory_usage")
+                    .chain_closure::<String>(closure!(
+                        |_: Option<Object>, pinned_memory_usage: u64| {
+                            convert_storage(pinned_memory_usage as f64, false)
+                        }
+                    ))
+                    .bind(&row, "text", Widget::NONE);
+
+                highlight_changes::<ProcessEntry>(
+                    item,
+                    &row,
+                    "pinned_memory_usage",
+                    ChangeScale::Relative,
+                );
+
+                this.add_gestures(item);
+            }
+        ));
+
+        pinned_memory_col_factory.connect_teardown(move |_factory, item| {
+            let item = item.downcast_ref::<gtk::ListItem>().unwrap();
+            item.set_child(None::<&gtk::Inscription>);
+        });
+
+        let pinned_memory_col_sorter = NumericSorter::builder()
+            .sort_order(SortType::Ascending)
+            .expression(gtk::PropertyExpression::new(
+                ProcessEntry::static_type(),
+                None::<&gtk::Expression>,
+                "pinned_memory_usage",
+            ))
+            .build();
+
+        pinned_memory_col.set_sorter(Some(&pinned_memory_col_sorter));
+        pinned_memory_col.set_visible(SETTINGS.processes_show_pinned_memory());
+
+        column_view.append_column(&pinned_memory_col);
+
+        SETTINGS.connect_processes_show_pinned_memory(clone!(
+            #[weak]
+            pinned_memory_col,
+            move |visible| pinned_memory_col.set_visible(visible)
+        ));
+
+        pinned_memory_col
+    }
+
+    fn add_cpu_throttling_column(&self, column_view: &ColumnView) -> ColumnViewColumn {
+        let cpu_throttling_col_factory = gtk::SignalListItemFactory::new();
+
+        let cpu_throttling_col = gtk::ColumnViewColumn::new(
+            Some(&i18n("CPU Throttling")),
+            Some(cpu_throttling_col_factory.clone()),
+        );
+
+        cpu_throttling_col.set_resizable(true);
+        cpu_throttling_col.set_id(Some("cpu_throttling"));
+
+        cpu_throttling_col_factory.connect_setup(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |_factory, item| {
+                let item = item.downcast_ref::<gtk::ListItem>().unwrap();
+
+                let row = gtk::Inscription::new(None);
+                row.set_min_chars(7);
+                row.set_tooltip_text(Some(&i18n(
+                    "Share of CPU bandwidth periods in which the cgroup of this process hit its CPU quota",
+                )));
+
+                item.set_child(Some(&row));
+
+                item.property_expression("item")
+                    .chain_property::<ProcessEntry>("cpu_throttled")
+                    .chain_closure::<String>(closure!(|_: Option<Object>, cpu_throttled: f32| {
+                        format_cpu_throttled(cpu_throttled)
+                    }))
+                    .bind(&row, "text", Widget::NONE);
+
+                this.add_gestures(item);
+            }
+        ));
+
+        cpu_throttling_col_factory.connect_teardown(move |_factory, item| {
+            let item = item.downcast_ref::<gtk::ListItem>().unwrap();
+            item.set_child(None::<&gtk::Inscription>);
+        });
+
+        let cpu_throttling_col_sorter = NumericSorter::builder()
+            .sort_order(SortType::Ascending)
+            .expression(gtk::PropertyExpression::new(
+                ProcessEntry::static_type(),
+                None::<&gtk::Expression>,
+                "cpu_throttled",
+            ))
+            .build();
+
+        cpu_throttling_col.set_sorter(Some(&cpu_throttling_col_sorter));
+        cpu_throttling_col.set_visible(SETTINGS.processes_show_cpu_throttling());
+
+        column_view.append_column(&cpu_throttling_col);
+
+        SETTINGS.connect_processes_show_cpu_throttling(clone!(
+            #[weak]
+            cpu_throttling_col,
+            move |visible| cpu_throttling_col.set_visible(visible)
+        ));
+
+        cpu_throttling_col
+    }
+
+    fn add_container_column(&self, column_view: &ColumnView) -> ColumnViewColumn {
+        let container_col_factory = gtk::SignalListItemFactory::new();
+
+        let container_col = gtk::ColumnViewColumn::new(
+            Some(&i18n("Container")),
+            Some(container_col_factory.clone()),
+        );
+
+        container_col.set_resizable(true);
+        container_col.set_id(Some("container"));
+
+        container_col_factory.connect_setup(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |_factory, item| {
+                let item = item.downcast_ref::<gtk::ListItem>().unwrap();
+
+                let row = gtk::Inscription::new(None);
+                row.set_min_chars(12);
+
+                item.set_child(Some(&row));
+
+                item.property_expression("item")
+                    .chain_property::<ProcessEntry>("container")
+                    .bind(&row, "text", Widget::NONE);
+
+                this.add_gestures(item);
+            }
+        ));
+
+        container_col_factory.connect_teardown(move |_factory, item| {
+            let item = item.downcast_ref::<gtk::ListItem>().unwrap();
+            item.set_child(None::<&gtk::Inscription>);
+        });
+
+        let container_col_sorter = StringSorter::builder()
+            .ignore_case(true)
+            .expression(gtk::PropertyExpression::new(
+                ProcessEntry::static_type(),
+                None::<&gtk::Expression>,
+                "container",
+            ))
+            .build();
+
+        container_col.set_sorter(Some(&container_col_sorter));
+        container_col.set_visible(SETTINGS.processes_group_by_container());
+
+        column_view.append_column(&container_col);
+
+        SETTINGS.connect_processes_group_by_container(clone!(
+            #[weak]
+            container_col,
+            move |visible| container_col.set_visible(visible)
+        ));
+
+        container_col
+    }
+
+    fn add_gpu_usage_of_column(
+        &self,
+        column_view: &ColumnView,
+        gpu_identifier: GpuIdentifier,
+        label: &str,
+    ) -> ColumnViewColumn {
+        let gpu_usage_col_factory = gtk::SignalListItemFactory::new();
+
+        let gpu_usage_col =
+            gtk::ColumnViewColumn::new(Some(label), Some(gpu_usage_col_factory.clone()));
+
+        gpu_usage_col.set_resizable(true);
+        gpu_usage_col.set_id(Some(&format!("{GPU_USAGE_COLUMN_PREFIX}{gpu_identifier}")));
+
+        gpu_usage_col_factory.connect_setup(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |_factory, item| {
+                let item = item.downcast_ref::<gtk::ListItem>().unwrap();
+
+                let row = gtk::Inscription::new(None);
+                row.set_min_chars(7);
+
+                item.set_child(Some(&row));
+
+                // the usage of a single GPU is no property of its own, but it's refreshed together with the combined
+                // usage, so watch that instead
+                gtk::PropertyExpression::new(
+                    ProcessEntry::static_type(),
+                    Some(item.property_expression("item")),
+                    "gpu_usage",
+                )
+                .watch(
+                    Some(item),
+                    clone!(
+                        #[weak]
+                        item,
+                        #[weak]
+                        row,
+                        move || {
+                            let text = item.item().and_downcast::<ProcessEntry>().map(|process| {
+                                format_percentage(process.gpu_usage_of(&gpu_identifier) * 100.0)
+                            });
+                            row.set_text(text.as_deref());
+                        }
+                    ),
+                );
+
+                this.add_gestures(item);
+            }
+        ));
+
+        gpu_usage_col_factory.connect_teardown(move |_factory, item| {
+            let item = item.downcast_ref::<gtk::ListItem>().unwrap();
+            item.set_child(None::<&gtk::Inscription>);
+        });
+
+        let gpu_usage_col_sorter = gtk::CustomSorter::new(move |a, b| {
+            let a = a.downcast_ref::<ProcessEntry>().unwrap();
+            let b = b.downcast_ref::<ProcessEntry>().unwrap();
+
+            a.gpu_usage_of(&gpu_identifier)
+                .total_cmp(&b.gpu_usage_of(&gpu_identifier))
+                .into()
+        });
+
+        gpu_usage_col.set_sorter(Some(&gpu_usage_col_sorter));
+        gpu_usage_col.set_visible(SETTINGS.processes_show_usage_per_gpu());
+
+        column_view.append_column(&gpu_usage_col);
+
+        SETTINGS.connect_processes_show_usage_per_gpu(clone!(
+            #[weak]
+            gpu_usage_col,
+            move |visible| gpu_usage_col.set_visible(visible)
+        ));
+
+        gpu_usage_col
+    }
 }
 
 fn get_action_name(action: ProcessAction, name: &str) -> String {
@@ -2059,3 +3116,85 @@ fn get_action_description(action: ProcessAction) -> String {
         ProcessAction::CONT => i18n("Continue Process"),
     }
 }
+
+fn format_cpu_usage(cpu_usage: f32) -> String {
+    let mut percentage = cpu_usage * 100.0;
+    if !SETTINGS.normalize_cpu_usage() {
+        percentage *= *NUM_CPUS as f32;
+    }
+
+    format_percentage(percentage)
+}
+
+fn format_io_speed(speed: f64) -> String {
+    if speed == -1.0 {
+        i18n("N/A")
+    } else {
+        convert_speed(speed, false)
+    }
+}
+
+fn format_io_total(total: i64) -> String {
+    if total == -1 {
+        i18n("N/A")
+    } else {
+        convert_storage(total as f64, false)
+    }
+}
+
+fn format_priority(niceness: i8) -> String {
+    if SETTINGS.detailed_priority() {
+        niceness.to_string()
+    } else if let Ok(niceness) = Niceness::try_from(niceness) {
+        NICE_TO_LABEL
+            .get(&niceness)
+            .map(|(s, _)| s)
+            .cloned()
+            .unwrap_or_else(|| i18n("N/A"))
+    } else {
+        i18n("N/A")
+    }
+}
+
+fn format_cpu_throttled(cpu_throttled: f32) -> String {
+    if cpu_throttled < 0.0 {
+        String::new()
+    } else {
+        format_percentage(cpu_throttled * 100.0)
+    }
+}
+
+/// Returns the text that the column with the ID `column_id` displays for `process`
+fn column_text(process: &ProcessEntry, column_id: &str) -> String {
+    match column_id {
+        "name" => process.name().to_string(),
+        "pid" => process.pid().to_string(),
+        "user" => process.user().to_string(),
+        "memory" => convert_storage(process.memory_usage() as f64, false),
+        "cpu" => format_cpu_usage(process.cpu_usage()),
+        "read_speed" => format_io_speed(process.read_speed()),
+        "read_total" => format_io_total(process.read_total()),
+        "write_speed" => format_io_speed(process.write_speed()),
+        "write_total" => format_io_total(process.write_total()),
+        "gpu" => format_percentage(process.gpu_usage() * 100.0),
+        "gpu_mem" => convert_storage(process.gpu_mem_usage() as f64, false),
+        "encoder" => format_percentage(process.enc_usage() * 100.0),
+        "decoder" => format_percentage(process.dec_usage() * 100.0),
+        "media" => format_percentage(process.media_usage() * 100.0),
+        "total_cpu_time" => format_time(process.total_cpu_time()),
+        "user_cpu_time" => format_time(process.user_cpu_time()),
+        "system_cpu_time" => format_time(process.system_cpu_time()),
+        "priority" => format_priority(process.niceness()),
+        "swap" => convert_storage(process.swap_usage() as f64, false),
+        "shared_memory" => convert_storage(process.shared_memory_usage() as f64, false),
+        "locked_memory" => convert_storage(process.locked_memory_usage() as f64, false),
+        "pinned_memory" => convert_storage(process.pinned_memory_usage() as f64, false),
+        "cpu_throttling" => format_cpu_throttled(process.cpu_throttled()),
+        "container" => process.container().to_string(),
+        _ => column_id
+            .strip_prefix(GPU_USAGE_COLUMN_PREFIX)
+            .and_then(|gpu_identifier| GpuIdentifier::from_str(gpu_identifier).ok())
+            .map(|gpu_identifier| format_percentage(process.gpu_usage_of(&gpu_identifier) * 100.0))
+            .unwrap_or_default(),
+    }
+}
@@ -1,17 +1,25 @@
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, VecDeque},
+};
+
 use gtk::{
     glib::{self, GString},
     subclass::prelude::ObjectSubclassIsExt,
 };
 use log::trace;
-use process_data::Containerization;
+use process_data::{Containerization, GpuIdentifier};
 
 use crate::{
-    i18n::i18n,
-    utils::{process::Process, TICK_RATE},
+    i18n::{i18n, i18n_f},
+    utils::{process::Process, settings::SETTINGS, TICK_RATE},
 };
 
 mod imp {
-    use std::cell::{Cell, RefCell};
+    use std::{
+        cell::{Cell, RefCell},
+        collections::VecDeque,
+    };
 
     use gtk::{
         gio::{Icon, ThemedIcon},
@@ -46,9 +54,18 @@ mod imp {
         #[property(get, set)]
         memory_usage: Cell<u64>,
 
+        #[property(get, set)]
+        shared_memory_usage: Cell<u64>,
+
         #[property(get, set)]
         swap_usage: Cell<u64>,
 
+        #[property(get, set)]
+        locked_memory_usage: Cell<u64>,
+
+        #[property(get, set)]
+        pinned_memory_usage: Cell<u64>,
+
         #[property(get, set)]
         read_speed: Cell<f64>, // will be -1.0 if read data is not available
 
@@ -70,9 +87,18 @@ mod imp {
         #[property(get, set)]
         dec_usage: Cell<f32>,
 
+        #[property(get, set)]
+        media_usage: Cell<f32>,
+
         #[property(get, set)]
         gpu_mem_usage: Cell<u64>,
 
+        #[property(get, set)]
+        gpu_mem_system_usage: Cell<u64>,
+
+        #[property(get, set)]
+        cpu_throttled: Cell<f32>, // will be -1.0 if the process' cgroup has no CPU quota
+
         #[property(get, set)]
         total_cpu_time: Cell<f64>,
 
@@ -85,12 +111,19 @@ mod imp {
         #[property(get, set)]
         niceness: Cell<i8>,
 
+        #[property(get, set)]
+        depth: Cell<u32>, // will be 0 for all processes if indentation by depth is disabled
+
         #[property(get = Self::cgroup, set = Self::set_cgroup)]
         cgroup: Cell<Option<glib::GString>>,
 
         #[property(get = Self::containerization, set = Self::set_containerization)]
         containerization: Cell<glib::GString>,
 
+        /// Name of the Docker, Podman or LXC container the process runs in, empty if it doesn't run in one
+        #[property(get = Self::container, set = Self::set_container)]
+        container: Cell<glib::GString>,
+
         #[property(get = Self::running_since, set = Self::set_running_since)]
         running_since: Cell<Option<glib::GString>>,
 
@@ -100,6 +133,17 @@ mod imp {
         symbolic: Cell<bool>,
 
         pub affinity: RefCell<Vec<bool>>,
+
+        /// Whether the usage histories are kept, this is only the case while the process' graphs are visible
+        pub records_history: Cell<bool>,
+
+        pub cpu_usage_history: RefCell<VecDeque<f64>>,
+
+        pub memory_usage_history: RefCell<VecDeque<f64>>,
+
+        pub gpu_usage_history: RefCell<VecDeque<f64>>,
+
+        pub gpu_usages: RefCell<BTreeMap<GpuIdentifier, f32>>,
     }
 
     impl Default for ProcessEntry {
@@ -112,7 +156,10 @@ mod imp {
                 pid: Cell::new(0),
                 cpu_usage: Cell::new(0.0),
                 memory_usage: Cell::new(0),
+                shared_memory_usage: Cell::new(0),
                 swap_usage: Cell::new(0),
+                locked_memory_usage: Cell::new(0),
+                pinned_memory_usage: Cell::new(0),
                 read_speed: Cell::new(0.0),
                 read_total: Cell::new(0),
                 write_speed: Cell::new(0.0),
@@ -120,22 +167,32 @@ mod imp {
                 gpu_usage: Cell::new(0.0),
                 enc_usage: Cell::new(0.0),
                 dec_usage: Cell::new(0.0),
+                media_usage: Cell::new(0.0),
                 gpu_mem_usage: Cell::new(0),
+                gpu_mem_system_usage: Cell::new(0),
+                cpu_throttled: Cell::new(-1.0),
                 total_cpu_time: Cell::new(0.0),
                 user_cpu_time: Cell::new(0.0),
                 system_cpu_time: Cell::new(0.0),
                 niceness: Cell::new(0),
+                depth: Cell::new(0),
                 cgroup: Cell::new(None),
                 containerization: Cell::new(glib::GString::default()),
+                container: Cell::new(glib::GString::default()),
                 running_since: Cell::new(None),
                 symbolic: Cell::new(false),
                 affinity: Default::default(),
+                records_history: Cell::new(false),
+                cpu_usage_history: Default::default(),
+                memory_usage_history: Default::default(),
+                gpu_usage_history: Default::default(),
+                gpu_usages: Default::default(),
             }
         }
     }
 
     impl ProcessEntry {
-        gstring_getter_setter!(user, commandline, name, containerization);
+        gstring_getter_setter!(user, commandline, name, containerization, container);
         gstring_option_getter_setter!(cgroup, running_since);
 
         pub fn icon(&self) -> Icon {
@@ -203,20 +260,36 @@ impl ProcessEntry {
     pub fn new(process: &Process) -> Self {
         trace!("Creating ProcessEntry GObject ({})…", process.data.pid);
 
-        let containerization = match process.data.containerization {
-            Containerization::None => i18n("No"),
-            Containerization::Flatpak => i18n("Yes (Flatpak)"),
-            Containerization::Snap => i18n("Yes (Snap)"),
+        let containerization = match (&process.data.container, process.data.containerization) {
+            (Some(container), _) => i18n_f("Yes ({})", &[&container.runtime.to_string()]),
+            (None, Containerization::None) => i18n("No"),
+            (None, Containerization::Flatpak) => i18n("Yes (Flatpak)"),
+            (None, Containerization::Snap) => i18n("Yes (Snap)"),
         };
 
         let this: Self = glib::Object::builder()
-            .property("name", &process.display_name)
+            .property(
+                "name",
+                process.name(
+                    SETTINGS.processes_name_source(),
+                    SETTINGS.processes_show_full_path(),
+                ),
+            )
             .property("commandline", process.data.commandline.replace('\0', " "))
             .property("user", &process.data.user)
             .property("icon", &process.icon)
             .property("pid", process.data.pid)
             .property("cgroup", process.data.cgroup.clone().map(GString::from))
             .property("containerization", containerization)
+            .property(
+                "container",
+                process
+                    .data
+                    .container
+                    .as_ref()
+                    .map(|container| container.display_name())
+                    .unwrap_or_default(),
+            )
             .property("running_since", process.running_since().ok())
             .build();
         this.update(process);
@@ -226,9 +299,21 @@ impl ProcessEntry {
     pub fn update(&self, process: &Process) {
         trace!("Refreshing ProcessEntry ({})…", process.data.pid);
 
+        // the settings might have been changed since the last refresh
+        let name = process.name(
+            SETTINGS.processes_name_source(),
+            SETTINGS.processes_show_full_path(),
+        );
+        if self.name() != name {
+            self.set_name(name);
+        }
+
         self.set_cpu_usage(process.cpu_time_ratio());
         self.set_memory_usage(process.data.memory_usage as u64);
+        self.set_shared_memory_usage(process.data.shared_memory_usage as u64);
         self.set_swap_usage(process.data.swap_usage as u64);
+        self.set_locked_memory_usage(process.data.locked_memory_usage as u64);
+        self.set_pinned_memory_usage(process.data.pinned_memory_usage as u64);
         self.set_read_speed(process.read_speed().unwrap_or(-1.0));
         self.set_read_total(
             process
@@ -243,15 +328,99 @@ impl ProcessEntry {
                 .write_bytes
                 .map_or(-1, |write_total| write_total as i64),
         );
+        // the per-GPU usages are read whenever gpu_usage changes, so they need to be up to date before that
+        *self.imp().gpu_usages.borrow_mut() = process.gpu_usages();
         self.set_gpu_usage(process.gpu_usage());
         self.set_enc_usage(process.enc_usage());
         self.set_dec_usage(process.dec_usage());
+        self.set_media_usage(f32::max(self.enc_usage(), self.dec_usage()));
         self.set_gpu_mem_usage(process.gpu_mem_usage());
+        self.set_gpu_mem_system_usage(process.gpu_mem_system_usage());
+        self.set_cpu_throttled(process.cpu_throttled_ratio().unwrap_or(-1.0));
         self.set_user_cpu_time((process.data.user_cpu_time as f64) / (*TICK_RATE as f64));
         self.set_system_cpu_time((process.data.system_cpu_time as f64) / (*TICK_RATE as f64));
         self.set_total_cpu_time(self.user_cpu_time() + self.system_cpu_time());
         self.set_niceness(*process.data.niceness);
         *self.imp().affinity.borrow_mut() = process.data.affinity.clone();
+
+        if self.imp().records_history.get() {
+            self.push_histories();
+        }
+    }
+
+    /// Starts or stops keeping the usage of the last refreshes for the graphs, stopping discards what has been kept
+    pub fn set_records_history(&self, records_history: bool) {
+        let imp = self.imp();
+
+        if imp.records_history.replace(records_history) == records_history {
+            return;
+        }
+
+        if records_history {
+            // start with the current values so there's something to show right away
+            self.push_histories();
+        } else {
+            imp.cpu_usage_history.take();
+            imp.memory_usage_history.take();
+            imp.gpu_usage_history.take();
+        }
+    }
+
+    fn push_histories(&self) {
+        let imp = self.imp();
+        Self::push_history(&imp.cpu_usage_history, self.cpu_usage() as f64);
+        Self::push_history(&imp.memory_usage_history, self.memory_usage() as f64);
+        Self::push_history(&imp.gpu_usage_history, self.gpu_usage() as f64);
+    }
+
+    fn push_history(history: &RefCell<VecDeque<f64>>, value: f64) {
+        let mut history = history.borrow_mut();
+        let max_len = SETTINGS.graph_data_points() as usize;
+        while history.len() >= max_len {
+            history.pop_front();
+        }
+        history.push_back(value);
+    }
+
+    /// Returns the processor usage of the last refreshes, oldest first
+    pub fn cpu_usage_history(&self) -> Vec<f64> {
+        self.imp()
+            .cpu_usage_history
+            .borrow()
+            .iter()
+            .copied()
+            .collect()
+    }
+
+    /// Returns the memory usage in bytes of the last refreshes, oldest first
+    pub fn memory_usage_history(&self) -> Vec<f64> {
+        self.imp()
+            .memory_usage_history
+            .borrow()
+            .iter()
+            .copied()
+            .collect()
+    }
+
+    /// Returns the GPU usage of the last refreshes, oldest first
+    pub fn gpu_usage_history(&self) -> Vec<f64> {
+        self.imp()
+            .gpu_usage_history
+            .borrow()
+            .iter()
+            .copied()
+            .collect()
+    }
+
+    /// Returns the usage of the GPU `gpu_identifier` alone as calculated by [`Process::gpu_usage_of`] during the last
+    /// update, 0 if the process doesn't use it
+    pub fn gpu_usage_of(&self, gpu_identifier: &GpuIdentifier) -> f32 {
+        self.imp()
+            .gpu_usages
+            .borrow()
+            .get(gpu_identifier)
+            .copied()
+            .unwrap_or_default()
     }
 
     pub fn affinity(&self) -> Vec<bool> {
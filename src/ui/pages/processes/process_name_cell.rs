@@ -2,6 +2,8 @@ use adw::{glib::property::PropertySet, prelude::*, subclass::prelude::*};
 use gtk::{gio::Icon, glib};
 use log::trace;
 
+use crate::utils::{process::Process, settings::SETTINGS};
+
 mod imp {
     use std::cell::{Cell, RefCell};
 
@@ -30,6 +32,8 @@ mod imp {
         icon: RefCell<Icon>,
         #[property(get, set = Self::set_symbolic)]
         symbolic: Cell<bool>,
+        #[property(get, set = Self::set_depth)]
+        depth: Cell<u32>,
     }
 
     impl Default for ResProcessNameCell {
@@ -41,6 +45,7 @@ mod imp {
                 tooltip: Default::default(),
                 icon: RefCell::new(ThemedIcon::new("generic-process").into()),
                 symbolic: Default::default(),
+                depth: Default::default(),
             }
         }
     }
@@ -65,7 +70,12 @@ mod imp {
 
         pub fn set_tooltip(&self, tooltip: &str) {
             self.tooltip.set(glib::GString::from(tooltip));
-            self.inscription.set_tooltip_text(Some(tooltip));
+            // huge command lines (e.g. of JVM apps) would otherwise result in tooltips covering the whole screen
+            self.inscription
+                .set_tooltip_text(Some(&Process::ellipsize_commandline(
+                    tooltip,
+                    SETTINGS.commandline_tooltip_length() as usize,
+                )));
         }
 
         pub fn icon(&self) -> Icon {
@@ -100,6 +110,14 @@ mod imp {
                 self.image.set_css_classes(&["lowres-icon"]);
             }
         }
+
+        pub fn set_depth(&self, depth: u32) {
+            self.depth.set(depth);
+
+            let indentation = depth.min(super::ResProcessNameCell::MAX_INDENTED_DEPTH)
+                * super::ResProcessNameCell::INDENTATION_PER_LEVEL;
+            self.obj().set_margin_start(indentation as i32);
+        }
     }
 
     #[glib::object_subclass]
@@ -153,6 +171,11 @@ impl Default for ResProcessNameCell {
 }
 
 impl ResProcessNameCell {
+    const INDENTATION_PER_LEVEL: u32 = 12;
+
+    // really deep process trees would otherwise push the names out of the column
+    const MAX_INDENTED_DEPTH: u32 = 16;
+
     pub fn new() -> Self {
         trace!("Creating ResProcessNameCell GObject…");
 
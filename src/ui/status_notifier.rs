@@ -0,0 +1,527 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use anyhow::{Context, Result};
+use gtk::{
+    gio::{self, prelude::*},
+    glib::{variant::ObjectPath, Variant},
+};
+use log::{debug, trace, warn};
+
+use crate::config::APP_ID;
+use crate::i18n::{i18n, i18n_f};
+
+const ITEM_PATH: &str = "/StatusNotifierItem";
+
+const MENU_PATH: &str = "/StatusNotifierItem/Menu";
+
+const ITEM_INTERFACE: &str = "org.kde.StatusNotifierItem";
+
+const MENU_INTERFACE: &str = "com.canonical.dbusmenu";
+
+const WATCHER_NAME: &str = "org.kde.StatusNotifierWatcher";
+
+const WATCHER_PATH: &str = "/StatusNotifierWatcher";
+
+const INTERFACES_XML: &str = r#"<node>
+  <interface name="org.kde.StatusNotifierItem">
+    <property name="Category" type="s" access="read"/>
+    <property name="Id" type="s" access="read"/>
+    <property name="Title" type="s" access="read"/>
+    <property name="Status" type="s" access="read"/>
+    <property name="IconName" type="s" access="read"/>
+    <property name="ToolTip" type="(sa(iiay)ss)" access="read"/>
+    <property name="ItemIsMenu" type="b" access="read"/>
+    <property name="Menu" type="o" access="read"/>
+    <method name="Activate">
+      <arg name="x" type="i" direction="in"/>
+      <arg name="y" type="i" direction="in"/>
+    </method>
+    <method name="SecondaryActivate">
+      <arg name="x" type="i" direction="in"/>
+      <arg name="y" type="i" direction="in"/>
+    </method>
+    <method name="ContextMenu">
+      <arg name="x" type="i" direction="in"/>
+      <arg name="y" type="i" direction="in"/>
+    </method>
+    <method name="Scroll">
+      <arg name="delta" type="i" direction="in"/>
+      <arg name="orientation" type="s" direction="in"/>
+    </method>
+    <signal name="NewTitle"/>
+    <signal name="NewIcon"/>
+    <signal name="NewToolTip"/>
+    <signal name="NewStatus">
+      <arg name="status" type="s"/>
+    </signal>
+  </interface>
+  <interface name="com.canonical.dbusmenu">
+    <property name="Version" type="u" access="read"/>
+    <property name="TextDirection" type="s" access="read"/>
+    <property name="Status" type="s" access="read"/>
+    <property name="IconThemePath" type="as" access="read"/>
+    <method name="GetLayout">
+      <arg name="parentId" type="i" direction="in"/>
+      <arg name="recursionDepth" type="i" direction="in"/>
+      <arg name="propertyNames" type="as" direction="in"/>
+      <arg name="revision" type="u" direction="out"/>
+      <arg name="layout" type="(ia{sv}av)" direction="out"/>
+    </method>
+    <method name="GetGroupProperties">
+      <arg name="ids" type="ai" direction="in"/>
+      <arg name="propertyNames" type="as" direction="in"/>
+      <arg name="properties" type="a(ia{sv})" direction="out"/>
+    </method>
+    <method name="GetProperty">
+      <arg name="id" type="i" direction="in"/>
+      <arg name="name" type="s" direction="in"/>
+      <arg name="value" type="v" direction="out"/>
+    </method>
+    <method name="Event">
+      <arg name="id" type="i" direction="in"/>
+      <arg name="eventId" type="s" direction="in"/>
+      <arg name="data" type="v" direction="in"/>
+      <arg name="timestamp" type="u" direction="in"/>
+    </method>
+    <method name="AboutToShow">
+      <arg name="id" type="i" direction="in"/>
+      <arg name="needUpdate" type="b" direction="out"/>
+    </method>
+    <signal name="LayoutUpdated">
+      <arg name="revision" type="u"/>
+      <arg name="parent" type="i"/>
+    </signal>
+  </interface>
+</node>"#;
+
+/// The entries of the menu, their IDs are their position in this list plus one since 0 is the root
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MenuItem {
+    ProcessorUsage,
+    MemoryUsage,
+    Separator,
+    Show,
+    Quit,
+}
+
+impl MenuItem {
+    const ALL: [MenuItem; 5] = [
+        MenuItem::ProcessorUsage,
+        MenuItem::MemoryUsage,
+        MenuItem::Separator,
+        MenuItem::Show,
+        MenuItem::Quit,
+    ];
+
+    fn id(self) -> i32 {
+        Self::ALL
+            .iter()
+            .position(|item| *item == self)
+            .unwrap_or_default() as i32
+            + 1
+    }
+
+    fn from_id(id: i32) -> Option<Self> {
+        Self::ALL
+            .get(usize::try_from(id).ok()?.checked_sub(1)?)
+            .copied()
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    processor_usage: f64,
+    memory_usage: f64,
+    /// Increased whenever the labels of the menu change so that hosts know their layout is outdated
+    revision: u32,
+    /// Whether a status notifier watcher has accepted the item, i.e. whether the icon is actually shown somewhere
+    registered: bool,
+}
+
+impl State {
+    fn processor_label(&self) -> String {
+        i18n_f(
+            "Processor: {} %",
+            &[&(self.processor_usage * 100.0).round().to_string()],
+        )
+    }
+
+    fn memory_label(&self) -> String {
+        i18n_f(
+            "Memory: {} %",
+            &[&(self.memory_usage * 100.0).round().to_string()],
+        )
+    }
+
+    fn menu_item_properties(&self, item: MenuItem) -> HashMap<String, Variant> {
+        let mut properties = HashMap::new();
+
+        match item {
+            MenuItem::ProcessorUsage => {
+                properties.insert("label".into(), self.processor_label().to_variant());
+                properties.insert("enabled".into(), false.to_variant());
+            }
+            MenuItem::MemoryUsage => {
+                properties.insert("label".into(), self.memory_label().to_variant());
+                properties.insert("enabled".into(), false.to_variant());
+            }
+            MenuItem::Separator => {
+                properties.insert("type".into(), "separator".to_variant());
+            }
+            MenuItem::Show => {
+                properties.insert("label".into(), i18n("Show Resources").to_variant());
+            }
+            MenuItem::Quit => {
+                properties.insert("label".into(), i18n("Quit").to_variant());
+            }
+        }
+
+        properties
+    }
+
+    /// Returns the whole menu as `(ia{sv}av)`, it's small enough to not bother with partial layouts
+    fn menu_layout(&self) -> Variant {
+        let children: Vec<Variant> = MenuItem::ALL
+            .iter()
+            .map(|item| {
+                (
+                    item.id(),
+                    self.menu_item_properties(*item),
+                    Vec::<Variant>::new(),
+                )
+                    .to_variant()
+            })
+            .collect();
+
+        let mut root_properties = HashMap::new();
+        root_properties.insert("children-display".to_string(), "submenu".to_variant());
+
+        (0i32, root_properties, children).to_variant()
+    }
+}
+
+/// An icon in the system tray (using the StatusNotifierItem D-Bus protocol) whose tooltip and menu show the current
+/// processor and memory usage. Activating it shows the main window. The icon disappears once this is dropped.
+pub struct StatusNotifier {
+    connection: gio::DBusConnection,
+    registrations: Vec<gio::RegistrationId>,
+    // gio doesn't export the type of the ID returned by `bus_watch_name_on_connection()`, so keep the call that
+    // stops watching instead
+    unwatch: Option<Box<dyn FnOnce()>>,
+    state: Rc<RefCell<State>>,
+}
+
+impl std::fmt::Debug for StatusNotifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StatusNotifier")
+            .field("connection", &self.connection)
+            .field("registrations", &self.registrations)
+            .field("state", &self.state)
+            .finish_non_exhaustive()
+    }
+}
+
+impl StatusNotifier {
+    /// Exports the item on `connection` and registers it with the tray of the desktop, now or whenever one appears.
+    /// `on_activate` is called when the user clicks the icon or chooses "Show Resources" and `on_quit` when they choose
+    /// "Quit".
+    pub fn register<A: Fn() + 'static, Q: Fn() + 'static>(
+        connection: &gio::DBusConnection,
+        on_activate: A,
+        on_quit: Q,
+    ) -> Result<Self> {
+        debug!("Registering status notifier item…");
+
+        let node_info = gio::DBusNodeInfo::for_xml(INTERFACES_XML)
+            .context("unable to parse status notifier interfaces")?;
+
+        let item_interface = node_info
+            .lookup_interface(ITEM_INTERFACE)
+            .context("status notifier item interface missing")?;
+        let menu_interface = node_info
+            .lookup_interface(MENU_INTERFACE)
+            .context("dbusmenu interface missing")?;
+
+        let state = Rc::new(RefCell::new(State::default()));
+        let on_activate: Rc<dyn Fn()> = Rc::new(on_activate);
+        let on_quit: Rc<dyn Fn()> = Rc::new(on_quit);
+
+        let mut registrations = Vec::with_capacity(2);
+
+        registrations.push(
+            connection
+                .register_object(ITEM_PATH, &item_interface)
+                .method_call({
+                    let on_activate = on_activate.clone();
+                    move |_, _, _, _, method, _, invocation| {
+                        trace!("Status notifier item method {method} called");
+                        if method == "Activate" || method == "SecondaryActivate" {
+                            on_activate();
+                        }
+                        invocation.return_value(None);
+                    }
+                })
+                .property({
+                    let state = state.clone();
+                    move |_, _, _, _, property| Self::item_property(&state.borrow(), property)
+                })
+                .build()
+                .context("unable to export status notifier item")?,
+        );
+
+        let menu_registration = connection
+            .register_object(MENU_PATH, &menu_interface)
+            .method_call({
+                let state = state.clone();
+                move |_, _, _, _, method, parameters, invocation| {
+                    Self::menu_method_call(
+                        &state.borrow(),
+                        method,
+                        &parameters,
+                        invocation,
+                        on_activate.as_ref(),
+                        on_quit.as_ref(),
+                    );
+                }
+            })
+            .property(|_, _, _, _, property| match property {
+                "Version" => 3u32.to_variant(),
+                "TextDirection" => "ltr".to_variant(),
+                "Status" => "normal".to_variant(),
+                _ => Vec::<String>::new().to_variant(),
+            })
+            .build();
+
+        match menu_registration {
+            Ok(registration) => registrations.push(registration),
+            Err(error) => {
+                for registration in registrations {
+                    let _ = connection.unregister_object(registration);
+                }
+                return Err(error).context("unable to export status notifier menu");
+            }
+        }
+
+        // the tray might only be started after us (or be restarted), so register again whenever it appears
+        let watcher = gio::bus_watch_name_on_connection(
+            connection,
+            WATCHER_NAME,
+            gio::BusNameWatcherFlags::NONE,
+            {
+                let state = state.clone();
+                move |connection, _, _| Self::register_with_watcher(&connection, state.clone())
+            },
+            {
+                let state = state.clone();
+                move |_, _| {
+                    debug!("Status notifier watcher has vanished");
+                    state.borrow_mut().registered = false;
+                }
+            },
+        );
+
+        Ok(Self {
+            connection: connection.clone(),
+            registrations,
+            unwatch: Some(Box::new(move || gio::bus_unwatch_name(watcher))),
+            state,
+        })
+    }
+
+    /// Updates the usages shown in the tooltip and the menu, both are fractions between 0 and 1
+    pub fn set_usages(&self, processor_usage: f64, memory_usage: f64) {
+        let revision = {
+            let mut state = self.state.borrow_mut();
+
+            let old_labels = (state.processor_label(), state.memory_label());
+
+            state.processor_usage = processor_usage;
+            state.memory_usage = memory_usage;
+
+            // avoid waking up the tray if nothing visible has changed
+            if old_labels == (state.processor_label(), state.memory_label()) {
+                return;
+            }
+
+            state.revision = state.revision.wrapping_add(1);
+            state.revision
+        };
+
+        let signals = [
+            (ITEM_PATH, ITEM_INTERFACE, "NewToolTip", None),
+            (
+                MENU_PATH,
+                MENU_INTERFACE,
+                "LayoutUpdated",
+                Some((revision, 0i32).to_variant()),
+            ),
+        ];
+
+        for (path, interface, signal, parameters) in signals {
+            if let Err(error) =
+                self.connection
+                    .emit_signal(None, path, interface, signal, parameters.as_ref())
+            {
+                warn!("Unable to emit {signal} of status notifier: {error}");
+            }
+        }
+    }
+
+    /// Returns whether the icon is currently shown in a tray
+    pub fn is_registered(&self) -> bool {
+        self.state.borrow().registered
+    }
+
+    fn register_with_watcher(connection: &gio::DBusConnection, state: Rc<RefCell<State>>) {
+        debug!("Registering with status notifier watcher…");
+
+        connection.call(
+            Some(WATCHER_NAME),
+            WATCHER_PATH,
+            WATCHER_NAME,
+            "RegisterStatusNotifierItem",
+            Some(&(ITEM_PATH,).to_variant()),
+            None,
+            gio::DBusCallFlags::NONE,
+            -1,
+            None::<&gio::Cancellable>,
+            move |result| {
+                if let Err(error) = &result {
+                    warn!("Unable to register with status notifier watcher: {error}");
+                }
+                state.borrow_mut().registered = result.is_ok();
+            },
+        );
+    }
+
+    fn item_property(state: &State, property: &str) -> Variant {
+        match property {
+            "Category" => "SystemServices".to_variant(),
+            "Id" => APP_ID.to_variant(),
+            "Title" => i18n("Resources").to_variant(),
+            "Status" => "Active".to_variant(),
+            "IconName" => APP_ID.to_variant(),
+            "ToolTip" => (
+                APP_ID.to_string(),
+                Vec::<(i32, i32, Vec<u8>)>::new(),
+                i18n("Resources"),
+                format!("{}\n{}", state.processor_label(), state.memory_label()),
+            )
+                .to_variant(),
+            "ItemIsMenu" => false.to_variant(),
+            _ => ObjectPath::try_from(MENU_PATH)
+                .map(|path| path.to_variant())
+                .unwrap_or_else(|_| "/".to_variant()),
+        }
+    }
+
+    fn menu_method_call(
+        state: &State,
+        method: &str,
+        parameters: &Variant,
+        invocation: gio::DBusMethodInvocation,
+        on_activate: &dyn Fn(),
+        on_quit: &dyn Fn(),
+    ) {
+        trace!("Status notifier menu method {method} called");
+
+        match method {
+            "GetLayout" => {
+                // a tuple of variants would box the layout, so the reply is assembled by hand
+                let reply =
+                    Variant::tuple_from_iter([state.revision.to_variant(), state.menu_layout()]);
+                invocation.return_value(Some(&reply));
+            }
+            "GetGroupProperties" => {
+                let ids = parameters
+                    .try_child_value(0)
+                    .and_then(|ids| ids.get::<Vec<i32>>())
+                    .unwrap_or_default();
+
+                let properties: Vec<(i32, HashMap<String, Variant>)> = ids
+                    .into_iter()
+                    .filter_map(|id| {
+                        let item = MenuItem::from_id(id)?;
+                        Some((id, state.menu_item_properties(item)))
+                    })
+                    .collect();
+
+                invocation.return_value(Some(&(properties,).to_variant()));
+            }
+            "GetProperty" => {
+                let property = parameters.get::<(i32, String)>().and_then(|(id, name)| {
+                    state
+                        .menu_item_properties(MenuItem::from_id(id)?)
+                        .remove(&name)
+                });
+
+                match property {
+                    Some(property) => invocation.return_value(Some(&(property,).to_variant())),
+                    None => invocation.return_dbus_error(
+                        "com.canonical.dbusmenu.Error.UnknownProperty",
+                        "unknown menu item or property",
+                    ),
+                }
+            }
+            "Event" => {
+                let event = parameters
+                    .try_child_value(0)
+                    .and_then(|id| id.get::<i32>())
+                    .zip(
+                        parameters
+                            .try_child_value(1)
+                            .and_then(|event| event.get::<String>()),
+                    );
+
+                invocation.return_value(None);
+
+                if let Some((id, event)) = event {
+                    if event == "clicked" {
+                        match MenuItem::from_id(id) {
+                            Some(MenuItem::Show) => on_activate(),
+                            Some(MenuItem::Quit) => on_quit(),
+                            _ => (),
+                        }
+                    }
+                }
+            }
+            "AboutToShow" => invocation.return_value(Some(&(false,).to_variant())),
+            _ => invocation
+                .return_dbus_error("org.freedesktop.DBus.Error.UnknownMethod", "unknown method"),
+        }
+    }
+}
+
+impl Drop for StatusNotifier {
+    fn drop(&mut self) {
+        debug!("Removing status notifier item…");
+
+        if let Some(unwatch) = self.unwatch.take() {
+            unwatch();
+        }
+
+        for registration in self.registrations.drain(..) {
+            if let Err(error) = self.connection.unregister_object(registration) {
+                warn!("Unable to unexport status notifier object: {error}");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::MenuItem;
+
+    #[test]
+    fn menu_item_ids() {
+        for item in MenuItem::ALL {
+            assert_eq!(Some(item), MenuItem::from_id(item.id()));
+        }
+
+        assert_eq!(None, MenuItem::from_id(0));
+        assert_eq!(None, MenuItem::from_id(-1));
+        assert_eq!(None, MenuItem::from_id(MenuItem::ALL.len() as i32 + 1));
+    }
+}
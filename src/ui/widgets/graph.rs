@@ -1,20 +1,32 @@
 use adw::prelude::WidgetExt;
-use gtk::glib::{self};
+use gtk::glib::{self, clone};
 use gtk::subclass::prelude::*;
 use log::trace;
 use plotters::style::RGBColor;
 
+use std::collections::VecDeque;
 use std::f64;
+use std::rc::Rc;
 
 use crate::utils::settings::SETTINGS;
 
 const MAX_DATA_POINTS: u32 = 600;
 
+/// The samples of a graph together with their timestamps, used to carry the history of a graph over to a newly
+/// created graph of the same device
+#[derive(Debug, Clone, Default)]
+pub struct GraphHistory {
+    data_points: VecDeque<f64>,
+    timestamps: VecDeque<Option<glib::DateTime>>,
+    annotations: VecDeque<Option<String>>,
+}
+
 mod imp {
     use std::{
         cell::{Cell, RefCell},
         collections::VecDeque,
         error::Error,
+        rc::Rc,
     };
 
     use adw::prelude::SnapshotExt;
@@ -22,7 +34,7 @@ mod imp {
     use gtk::{
         glib,
         subclass::{
-            prelude::{ObjectImpl, ObjectSubclass, ObjectSubclassExt},
+            prelude::{ObjectImpl, ObjectImplExt, ObjectSubclass, ObjectSubclassExt},
             widget::WidgetImpl,
         },
     };
@@ -37,11 +49,16 @@ mod imp {
 
     use super::MAX_DATA_POINTS;
 
-    #[derive(Debug)]
+    #[allow(clippy::type_complexity)]
     pub struct ResGraph {
         pub data_points: RefCell<VecDeque<f64>>,
+        pub timestamps: RefCell<VecDeque<Option<glib::DateTime>>>,
+        /// Labels of events that happened at a data point, e.g. a changed setting, drawn as vertical lines
+        pub annotations: RefCell<VecDeque<Option<String>>>,
         pub max_y: Cell<Option<f64>>,
         pub graph_color: Cell<RGBColor>,
+        pub hover_x: Cell<Option<f64>>,
+        pub value_formatter: RefCell<Option<Rc<dyn Fn(f64) -> String>>>,
     }
 
     impl Default for ResGraph {
@@ -53,8 +70,12 @@ mod imp {
 
             Self {
                 data_points: RefCell::new(empty_deque),
+                timestamps: RefCell::new(vec![None; MAX_DATA_POINTS as usize].into()),
+                annotations: RefCell::new(vec![None; MAX_DATA_POINTS as usize].into()),
                 max_y: Cell::new(Some(1.0)),
                 graph_color: Cell::default(),
+                hover_x: Cell::default(),
+                value_formatter: RefCell::default(),
             }
         }
     }
@@ -66,7 +87,12 @@ mod imp {
         type ParentType = gtk::Widget;
     }
 
-    impl ObjectImpl for ResGraph {}
+    impl ObjectImpl for ResGraph {
+        fn constructed(&self) {
+            self.parent_constructed();
+            self.obj().setup_hover();
+        }
+    }
 
     impl WidgetImpl for ResGraph {
         fn snapshot(&self, snapshot: &gtk::Snapshot) {
@@ -80,6 +106,54 @@ mod imp {
             let cr: gtk::cairo::Context = snapshot.append_cairo(&bounds);
             let backend = CairoBackend::new(&cr, (width, height)).unwrap();
             self.plot_graph(backend).unwrap();
+
+            let visible_points = SETTINGS.graph_data_points().clamp(2, MAX_DATA_POINTS) as usize;
+            let step = width as f64 / (visible_points - 1) as f64;
+
+            let start_point = MAX_DATA_POINTS as usize - visible_points;
+            let annotated_xs: Vec<f64> = self
+                .annotations
+                .borrow()
+                .iter()
+                .skip(start_point)
+                .enumerate()
+                .filter(|(_, annotation)| annotation.is_some())
+                .map(|(i, _)| (i as f64 * step).clamp(0.5, width as f64 - 0.5))
+                .collect();
+
+            if !annotated_xs.is_empty() {
+                let color = self.graph_color.get();
+                cr.set_source_rgba(
+                    color.0 as f64 / 255.0,
+                    color.1 as f64 / 255.0,
+                    color.2 as f64 / 255.0,
+                    0.8,
+                );
+                cr.set_line_width(1.0);
+                cr.set_dash(&[4.0, 4.0], 0.0);
+                for x in annotated_xs {
+                    cr.move_to(x, 0.0);
+                    cr.line_to(x, height as f64);
+                }
+                let _ = cr.stroke();
+                cr.set_dash(&[], 0.0);
+            }
+
+            if let Some(x) = self.hover_x.get() {
+                // snap the crosshair to the sample that the readout is showing
+                let snapped_x = ((x / step).round() * step).clamp(0.5, width as f64 - 0.5);
+
+                let color = self.graph_color.get();
+                cr.set_source_rgb(
+                    color.0 as f64 / 255.0,
+                    color.1 as f64 / 255.0,
+                    color.2 as f64 / 255.0,
+                );
+                cr.set_line_width(1.0);
+                cr.move_to(snapped_x, 0.0);
+                cr.line_to(snapped_x, height as f64);
+                let _ = cr.stroke();
+            }
         }
     }
 
@@ -164,6 +238,110 @@ impl ResGraph {
         imp.obj().queue_draw();
     }
 
+    /// Sets the function used to format a value in the hover readout. Without a formatter,
+    /// graphs locked to a maximum of 1.0 show percentages and all other graphs show no readout.
+    pub fn set_value_formatter<F: Fn(f64) -> String + 'static>(&self, formatter: F) {
+        self.imp().value_formatter.replace(Some(Rc::new(formatter)));
+    }
+
+    /// Takes over the color, the maximum and the value formatter of `other`, e.g. to show an older history of the same
+    /// value in another graph
+    pub fn copy_appearance(&self, other: &ResGraph) {
+        let imp = self.imp();
+        let other_imp = other.imp();
+
+        imp.graph_color.set(other_imp.graph_color.get());
+        imp.max_y.set(other_imp.max_y.get());
+        imp.value_formatter
+            .replace(other_imp.value_formatter.borrow().clone());
+
+        self.queue_draw();
+    }
+
+    fn setup_hover(&self) {
+        let motion_controller = gtk::EventControllerMotion::new();
+
+        motion_controller.connect_enter(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |_, x, _| {
+                this.imp().hover_x.set(Some(x));
+                this.queue_draw();
+            }
+        ));
+
+        motion_controller.connect_motion(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |_, x, _| {
+                this.imp().hover_x.set(Some(x));
+                this.queue_draw();
+            }
+        ));
+
+        motion_controller.connect_leave(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |_| {
+                this.imp().hover_x.set(None);
+                this.queue_draw();
+            }
+        ));
+
+        self.add_controller(motion_controller);
+
+        self.set_has_tooltip(true);
+        self.connect_query_tooltip(|graph, x, _, _, tooltip| {
+            if let Some(readout) = graph.hover_readout(x as f64) {
+                tooltip.set_text(Some(&readout));
+                true
+            } else {
+                false
+            }
+        });
+    }
+
+    /// Returns the index into the sample buffer that belongs to the horizontal position `x`
+    fn data_point_index_at(&self, x: f64) -> Option<usize> {
+        let width = self.width() as f64;
+        if width <= 0.0 {
+            return None;
+        }
+
+        let visible_points = SETTINGS.graph_data_points().clamp(2, MAX_DATA_POINTS) as usize;
+        let start_point = MAX_DATA_POINTS as usize - visible_points;
+
+        let offset = ((x / width).clamp(0.0, 1.0) * (visible_points - 1) as f64).round() as usize;
+
+        Some(start_point + offset)
+    }
+
+    fn hover_readout(&self, x: f64) -> Option<String> {
+        let imp = self.imp();
+
+        let index = self.data_point_index_at(x)?;
+        let value = *imp.data_points.borrow().get(index)?;
+        let timestamp = imp.timestamps.borrow().get(index).cloned().flatten()?;
+
+        let formatted_value = if let Some(formatter) = imp.value_formatter.borrow().as_ref() {
+            formatter(value)
+        } else if imp.max_y.get() == Some(1.0) {
+            format!("{} %", (value * 100.0).round())
+        } else {
+            return None;
+        };
+
+        let formatted_time = timestamp.format("%X").ok()?;
+
+        if let Some(annotation) = imp.annotations.borrow().get(index).cloned().flatten() {
+            Some(format!(
+                "{formatted_value} · {formatted_time}\n{annotation}"
+            ))
+        } else {
+            Some(format!("{formatted_value} · {formatted_time}"))
+        }
+    }
+
     pub fn set_locked_max_y(&self, y_max: Option<f64>) {
         let imp = self.imp();
         imp.max_y.set(y_max);
@@ -185,10 +363,30 @@ impl ResGraph {
     pub fn push_data_point(&self, data: f64) {
         let imp = self.imp();
         let mut data_points = imp.data_points.borrow_mut();
+        let mut timestamps = imp.timestamps.borrow_mut();
+        let mut annotations = imp.annotations.borrow_mut();
         if data_points.len() >= MAX_DATA_POINTS as usize {
             data_points.pop_front();
         }
+        if timestamps.len() >= MAX_DATA_POINTS as usize {
+            timestamps.pop_front();
+        }
+        if annotations.len() >= MAX_DATA_POINTS as usize {
+            annotations.pop_front();
+        }
         data_points.push_back(data);
+        timestamps.push_back(glib::DateTime::now_local().ok());
+        annotations.push_back(None);
+        imp.obj().queue_draw();
+    }
+
+    /// Marks the most recently pushed data point with `annotation`, which is then shown as a vertical line in the
+    /// graph and as part of the hover readout
+    pub fn annotate_latest_data_point<S: Into<String>>(&self, annotation: S) {
+        let imp = self.imp();
+        if let Some(latest) = imp.annotations.borrow_mut().back_mut() {
+            *latest = Some(annotation.into());
+        }
         imp.obj().queue_draw();
     }
 
@@ -196,19 +394,92 @@ impl ResGraph {
         self.imp().data_points.borrow().iter().copied().collect()
     }
 
+    /// Pushes several data points at once. Their timestamps are unknown, so they're left out
+    /// of the hover readout.
     pub fn push_data_points(&self, data: &[f64]) {
         let imp = self.imp();
         let mut data_points = imp.data_points.borrow_mut();
+        let mut timestamps = imp.timestamps.borrow_mut();
+        let mut annotations = imp.annotations.borrow_mut();
         for data_point in data {
             if data_points.len() >= MAX_DATA_POINTS as usize {
                 data_points.pop_front();
             }
+            if timestamps.len() >= MAX_DATA_POINTS as usize {
+                timestamps.pop_front();
+            }
+            if annotations.len() >= MAX_DATA_POINTS as usize {
+                annotations.pop_front();
+            }
             data_points.push_back(*data_point);
+            timestamps.push_back(None);
+            annotations.push_back(None);
+        }
+        imp.obj().queue_draw();
+    }
+
+    /// Replaces the whole history of the graph with `data`, padding it with zeroes at the front
+    pub fn set_data_points(&self, data: &[f64]) {
+        let imp = self.imp();
+        {
+            let mut data_points = imp.data_points.borrow_mut();
+            data_points.clear();
+            data_points.resize(MAX_DATA_POINTS as usize, 0.0);
+
+            let mut timestamps = imp.timestamps.borrow_mut();
+            timestamps.clear();
+            timestamps.resize(MAX_DATA_POINTS as usize, None);
+
+            let mut annotations = imp.annotations.borrow_mut();
+            annotations.clear();
+            annotations.resize(MAX_DATA_POINTS as usize, None);
+        }
+        self.push_data_points(data);
+    }
+
+    /// Replaces the whole history of the graph with `data` along with the time of each data point, padding it with
+    /// zeroes at the front
+    pub fn set_timed_data_points(&self, data: &[(f64, glib::DateTime)]) {
+        self.set_data_points(&[]);
+
+        let imp = self.imp();
+        {
+            let mut data_points = imp.data_points.borrow_mut();
+            let mut timestamps = imp.timestamps.borrow_mut();
+            let mut annotations = imp.annotations.borrow_mut();
+            for (data_point, timestamp) in data {
+                data_points.pop_front();
+                timestamps.pop_front();
+                annotations.pop_front();
+                data_points.push_back(*data_point);
+                timestamps.push_back(Some(timestamp.clone()));
+                annotations.push_back(None);
+            }
         }
         imp.obj().queue_draw();
     }
 
+    pub fn history(&self) -> GraphHistory {
+        let imp = self.imp();
+        GraphHistory {
+            data_points: imp.data_points.borrow().clone(),
+            timestamps: imp.timestamps.borrow().clone(),
+            annotations: imp.annotations.borrow().clone(),
+        }
+    }
+
+    /// Replaces the whole history of the graph (including timestamps and annotations) with `history`
+    pub fn restore_history(&self, history: &GraphHistory) {
+        let imp = self.imp();
+        imp.data_points.replace(history.data_points.clone());
+        imp.timestamps.replace(history.timestamps.clone());
+        imp.annotations.replace(history.annotations.clone());
+        imp.obj().queue_draw();
+    }
+
     pub fn clear_data_points(&self) {
         self.imp().data_points.borrow_mut().clear();
+        self.imp().timestamps.borrow_mut().clear();
+        self.imp().annotations.borrow_mut().clear();
     }
 }
@@ -1,12 +1,18 @@
+use std::time::Duration;
+
 use adw::{prelude::*, subclass::prelude::*};
-use gtk::glib;
-use log::trace;
+use gtk::glib::{self, clone, GString};
+use log::{trace, warn};
 
 use crate::config::PROFILE;
+use crate::utils::history;
+use crate::utils::settings::SETTINGS;
 
 use super::graph::ResGraph;
 
 mod imp {
+    use std::cell::RefCell;
+
     use crate::ui::widgets::graph::ResGraph;
 
     use super::*;
@@ -16,12 +22,21 @@ mod imp {
     #[derive(Debug, CompositeTemplate, Default)]
     #[template(resource = "/net/nokyan/Resources/ui/widgets/graph_box.ui")]
     pub struct ResGraphBox {
+        #[template_child]
+        pub graph_stack: TemplateChild<gtk::Stack>,
         #[template_child]
         pub graph: TemplateChild<ResGraph>,
         #[template_child]
+        pub history_graph: TemplateChild<ResGraph>,
+        #[template_child]
         pub title_label: TemplateChild<gtk::Label>,
         #[template_child]
         pub info_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub history_range: TemplateChild<gtk::DropDown>,
+
+        /// The history series of the value shown in the graph, if it's recorded
+        pub history_series: RefCell<Option<String>>,
     }
 
     #[glib::object_subclass]
@@ -81,6 +96,83 @@ impl ResGraphBox {
         self.imp().graph.get()
     }
 
+    /// Lets the user switch the graph to the recorded history of `series` (see `utils::history`) while recording the
+    /// history is enabled
+    pub fn set_history_series<S: Into<String>>(&self, series: S) {
+        let imp = self.imp();
+
+        if imp.history_series.replace(Some(series.into())).is_some() {
+            return;
+        }
+
+        imp.history_range.set_visible(SETTINGS.record_history());
+
+        imp.history_range.connect_selected_notify(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |_| this.refresh_history()
+        ));
+
+        // the history isn't updated live, so at least show the most recent one whenever the graph comes into view
+        self.connect_map(|graph_box| graph_box.refresh_history());
+
+        SETTINGS.connect_record_history(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |record_history| {
+                let imp = this.imp();
+                imp.history_range.set_visible(record_history);
+                if !record_history {
+                    imp.history_range.set_selected(0);
+                }
+            }
+        ));
+    }
+
+    /// The time span of the history that is shown, `None` if the live graph is shown
+    fn history_duration(&self) -> Option<Duration> {
+        // 0 = live, 1 = last hour, 2 = last day
+        match self.imp().history_range.selected() {
+            1 => Some(Duration::from_secs(60 * 60)),
+            2 => Some(history::MAX_AGE),
+            _ => None,
+        }
+    }
+
+    fn refresh_history(&self) {
+        let imp = self.imp();
+
+        let (Some(series), Some(duration)) =
+            (imp.history_series.borrow().clone(), self.history_duration())
+        else {
+            imp.history_graph.set_visible(false);
+            return;
+        };
+
+        let points = history::load(&series, duration, SETTINGS.graph_data_points() as usize)
+            .inspect_err(|e| warn!("Unable to load history of {series}: {e}"))
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|(timestamp, value)| {
+                let timestamp = glib::DateTime::from_unix_local(timestamp as i64).ok()?;
+                Some((value, timestamp))
+            })
+            .collect::<Vec<_>>();
+
+        imp.history_graph.copy_appearance(&imp.graph);
+        imp.history_graph.set_timed_data_points(&points);
+        imp.history_graph.set_visible(true);
+        imp.graph_stack.set_visible_child(&*imp.history_graph);
+    }
+
+    pub fn title_label(&self) -> GString {
+        self.imp().title_label.label()
+    }
+
+    pub fn subtitle(&self) -> GString {
+        self.imp().info_label.label()
+    }
+
     pub fn set_title_label(&self, str: &str) {
         let imp = self.imp();
         imp.title_label.set_label(str);
@@ -95,4 +187,26 @@ impl ResGraphBox {
         let imp = self.imp();
         imp.info_label.set_tooltip_text(str);
     }
+
+    /// Highlights the subtitle in yellow if `value` has reached `warning_threshold` and in red if it has reached
+    /// `critical_threshold`. `None` removes any highlighting.
+    pub fn set_warning_level(
+        &self,
+        value: Option<f64>,
+        warning_threshold: u32,
+        critical_threshold: u32,
+    ) {
+        let imp = self.imp();
+
+        imp.info_label.remove_css_class("warning");
+        imp.info_label.remove_css_class("error");
+
+        if let Some(value) = value {
+            if value >= critical_threshold as f64 {
+                imp.info_label.add_css_class("error");
+            } else if value >= warning_threshold as f64 {
+                imp.info_label.add_css_class("warning");
+            }
+        }
+    }
 }
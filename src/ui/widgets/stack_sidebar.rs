@@ -244,6 +244,10 @@ impl ResStackSidebar {
                 .can_target(true)
                 .build();
 
+            page.bind_property("visible", &row, "visible")
+                .sync_create()
+                .build();
+
             imp.list_box.append(&row);
 
             if let Some(visible_page) = imp.stack.borrow().visible_child() {
@@ -1,13 +1,21 @@
-use process_data::{Niceness, ProcessData};
-use std::path::PathBuf;
+use process_data::{
+    DrmFdinfo, EnvironmentVariable, GpuIdentifier, Niceness, OpenFile, ProcessData, ProcessLimits,
+    SchedStat, SocketData, ThreadData, WaitChannel,
+};
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsStr;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use adw::{prelude::*, subclass::prelude::*, ToolbarView};
 use adw::{Toast, ToastOverlay};
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use gtk::glib::{clone, timeout_future, GString, MainContext};
 use gtk::{gio, glib, Widget};
-use log::{info, trace, warn};
+use log::{debug, info, trace, warn};
 
 use crate::application::Application;
 use crate::config::PROFILE;
@@ -15,33 +23,69 @@ use crate::gui::ARGS;
 use crate::i18n::{i18n, i18n_f, ni18n_f};
 use crate::ui::pages::applications::ResApplications;
 use crate::ui::pages::battery::ResBattery;
+use crate::ui::pages::bind_device_label;
+use crate::ui::pages::cpu::ResCPU;
+use crate::ui::pages::custom_gauges::ResCustomGauges;
 use crate::ui::pages::drive::ResDrive;
+use crate::ui::pages::infiniband::ResInfiniband;
+use crate::ui::pages::memory::ResMemory;
+use crate::ui::pages::os::ResOs;
 use crate::ui::pages::processes::ResProcesses;
+use crate::ui::status_notifier::StatusNotifier;
+use crate::utils::alerts::{AlertEvent, Alerts, Metric, Reading, Rule};
 use crate::utils::app::AppsContext;
 use crate::utils::battery::{Battery, BatteryData};
 use crate::utils::cpu::{self, CpuData};
-use crate::utils::drive::{Drive, DriveData};
+use crate::utils::custom_gauge::CustomGaugeData;
+use crate::utils::drive::{Drive, DriveData, DriveType};
 use crate::utils::gpu::{Gpu, GpuData};
+use crate::utils::history::{HistoryRecorder, HISTORY_DIR};
+use crate::utils::infiniband::{InfinibandData, InfinibandPort};
+use crate::utils::json_output::JsonOutput;
 use crate::utils::memory::MemoryData;
+use crate::utils::metrics_log::{MetricsLog, MetricsSnapshot};
 use crate::utils::network::{NetworkData, NetworkInterface};
 use crate::utils::npu::{Npu, NpuData};
-use crate::utils::process::{Process, ProcessAction};
+use crate::utils::process::{CgroupLimits, Process, ProcessAction};
+use crate::utils::refresh_scheduler::{DueSubsystems, RefreshScheduler};
 use crate::utils::settings::SETTINGS;
+use crate::utils::units::{convert_storage, convert_temperature};
+use crate::utils::{FLATPAK_SPAWN, IS_FLATPAK, NUM_CPUS};
 
 use super::pages::gpu::ResGPU;
 use super::pages::network::ResNetwork;
+use super::pages::network_total::ResNetworkTotal;
 use super::pages::npu::ResNPU;
 use super::pages::{applications, processes};
+use super::widgets::graph::{GraphHistory, ResGraph};
+use super::widgets::graph_box::ResGraphBox;
+
+const PATH_WMCTRL_FLATPAK: &str = "/run/host/usr/bin/wmctrl";
+
+/// How many graph histories of removed device pages are remembered at most
+const MAX_SAVED_GRAPH_HISTORIES: usize = 32;
 
 #[derive(Debug, Clone)]
 pub enum Action {
     ManipulateProcesses(ProcessAction, Vec<libc::pid_t>, ToastOverlay),
     ManipulateApp(ProcessAction, String, ToastOverlay),
-    AdjustProcess(libc::pid_t, Niceness, Vec<bool>, String, ToastOverlay),
+    AdjustProcess(
+        libc::pid_t,
+        Niceness,
+        Vec<bool>,
+        Option<CgroupLimits>,
+        String,
+        ToastOverlay,
+    ),
+    AdjustProcesses(Vec<libc::pid_t>, Option<Niceness>, Vec<bool>, ToastOverlay),
+    AdjustApp(String, Option<Niceness>, f32, Vec<bool>, ToastOverlay),
 }
 
 mod imp {
-    use std::{cell::RefCell, collections::HashMap};
+    use std::{
+        cell::RefCell,
+        collections::{HashMap, HashSet},
+    };
 
     use crate::{
         config::VERSION,
@@ -59,7 +103,6 @@ mod imp {
 
     use async_channel::{unbounded, Receiver, Sender};
     use gtk::CompositeTemplate;
-    use log::debug;
     use process_data::{pci_slot::PciSlot, GpuIdentifier};
 
     #[derive(Debug, CompositeTemplate)]
@@ -92,16 +135,38 @@ mod imp {
 
         pub drive_pages: RefCell<HashMap<PathBuf, adw::ToolbarView>>,
 
+        /// Mount points of the filesystems we've already sent an "almost full" notification for
+        pub full_filesystems: RefCell<HashSet<PathBuf>>,
+
+        pub alerts: RefCell<Alerts>,
+
+        /// Readings of the drives and batteries for the alerts, kept across refreshes in which they aren't gathered
+        pub drive_readings: RefCell<Vec<Reading>>,
+        pub battery_readings: RefCell<Vec<Reading>>,
+
         pub network_pages: RefCell<HashMap<PathBuf, adw::ToolbarView>>,
 
+        pub network_total_page: RefCell<Option<adw::ToolbarView>>,
+
+        pub infiniband_pages: RefCell<HashMap<PathBuf, adw::ToolbarView>>,
+
         pub battery_pages: RefCell<HashMap<PathBuf, adw::ToolbarView>>,
 
         pub gpu_pages: RefCell<HashMap<GpuIdentifier, (Gpu, adw::ToolbarView)>>,
 
         pub npu_pages: RefCell<HashMap<PciSlot, (Npu, adw::ToolbarView)>>,
 
+        pub custom_gauges_page: RefCell<Option<adw::ToolbarView>>,
+
+        /// Graph histories of removed device pages by sysfs path, so that a page that's created again for the same
+        /// device continues where the old one left off, along with when they've been saved
+        pub graph_histories: RefCell<HashMap<PathBuf, (Instant, Vec<GraphHistory>)>>,
+
         pub apps_context: RefCell<AppsContext>,
 
+        /// The indicator in the system tray, only present while it's enabled in the settings
+        pub status_notifier: RefCell<Option<StatusNotifier>>,
+
         pub sender: Sender<Action>,
         pub receiver: RefCell<Option<Receiver<Action>>>,
     }
@@ -113,7 +178,12 @@ mod imp {
 
             Self {
                 drive_pages: RefCell::default(),
+                full_filesystems: RefCell::default(),
+                alerts: RefCell::default(),
+                drive_readings: RefCell::default(),
+                battery_readings: RefCell::default(),
                 network_pages: RefCell::default(),
+                infiniband_pages: RefCell::default(),
                 battery_pages: RefCell::default(),
                 split_view: TemplateChild::default(),
                 resources_sidebar: TemplateChild::default(),
@@ -132,6 +202,10 @@ mod imp {
                 processor_window_title: TemplateChild::default(),
                 gpu_pages: RefCell::default(),
                 npu_pages: RefCell::default(),
+                custom_gauges_page: RefCell::default(),
+                graph_histories: RefCell::default(),
+                network_total_page: RefCell::default(),
+                status_notifier: RefCell::default(),
             }
         }
     }
@@ -181,6 +255,19 @@ mod imp {
                 warn!("Failed to save window state, {}", &err);
             }
 
+            // with the indicator in the system tray, Resources keeps running in the background until it's quit. Without
+            // a tray to show it in, there'd be no way back to the window though
+            if self
+                .status_notifier
+                .borrow()
+                .as_ref()
+                .is_some_and(StatusNotifier::is_registered)
+            {
+                debug!("Hiding the window, the tray indicator keeps running");
+                self.obj().set_visible(false);
+                return glib::Propagation::Stop;
+            }
+
             // Pass close request on to the parent
             self.parent_close_request()
         }
@@ -202,13 +289,39 @@ struct RefreshData {
     mem_data: Option<Result<MemoryData>>,
     gpu_data: Vec<GpuData>,
     npu_data: Vec<NpuData>,
-    drive_paths: Vec<PathBuf>,
-    drive_data: Vec<DriveData>,
-    network_paths: Vec<PathBuf>,
-    network_data: Vec<NetworkData>,
-    battery_paths: Vec<PathBuf>,
-    battery_data: Vec<BatteryData>,
-    process_data: Vec<ProcessData>,
+    /// `None` if drives haven't been gathered this time because of a slower drives refresh speed
+    drive_data: Option<(Vec<PathBuf>, Vec<DriveData>)>,
+    /// `None` if network interfaces haven't been gathered this time because of a slower network refresh speed
+    network_data: Option<(Vec<PathBuf>, Vec<NetworkData>)>,
+    /// Gathered along with the network interfaces
+    infiniband_data: Option<(Vec<PathBuf>, Vec<InfinibandData>)>,
+    /// `None` if batteries haven't been gathered this time because of a slower sensors refresh speed
+    battery_data: Option<(Vec<PathBuf>, Vec<BatteryData>)>,
+    /// Gathered along with the batteries
+    custom_gauge_data: Option<Vec<CustomGaugeData>>,
+    /// `None` if processes haven't been gathered this time because of a slower processes refresh speed
+    process_data: Option<Vec<ProcessData>>,
+    /// Threads of the process whose info dialog is open, if there is one
+    thread_data: Option<(libc::pid_t, Vec<ThreadData>)>,
+    /// Raw DRM fdinfo fields of the process whose info dialog is open, if they're enabled in the settings
+    drm_fdinfo_data: Option<(libc::pid_t, Vec<DrmFdinfo>)>,
+    /// Resource limits of the process whose info dialog is open, if there is one
+    limits_data: Option<(libc::pid_t, ProcessLimits)>,
+    /// Open sockets of the process whose info dialog is open, if there is one
+    socket_data: Option<(libc::pid_t, Vec<SocketData>)>,
+    /// Environment variables of the process whose info dialog is open, if there is one
+    environment_data: Option<(libc::pid_t, Vec<EnvironmentVariable>)>,
+    /// Open files of the process whose info dialog is open, if there is one
+    open_file_data: Option<(libc::pid_t, Vec<OpenFile>)>,
+    /// Wait channel of the process whose info dialog is open, if there is one
+    wait_channel_data: Option<(libc::pid_t, WaitChannel)>,
+    /// Scheduler statistics of the process whose info dialog is open, if there is one
+    schedstat_data: Option<(libc::pid_t, Option<SchedStat>)>,
+}
+
+/// Returns the gathered data of a subsystem or nothing if it hasn't been gathered
+fn gathered<T>(data: &Option<(Vec<PathBuf>, Vec<T>)>) -> &[T] {
+    data.as_ref().map_or(&[], |(_, data)| data)
 }
 
 impl MainWindow {
@@ -257,6 +370,221 @@ impl MainWindow {
         }
     }
 
+    pub fn shortcut_toggle_expanders(&self) {
+        let Some(selected_page) = self.get_selected_page() else {
+            return;
+        };
+
+        let expander_rows = Self::expander_rows(&selected_page);
+
+        // with mixed states, expanding everything is more likely what the user is after
+        let expand = expander_rows.iter().any(|row| !row.is_expanded());
+
+        for expander_row in expander_rows {
+            expander_row.set_expanded(expand);
+        }
+    }
+
+    pub fn shortcut_copy_page_stats(&self) {
+        let Some(selected_page) = self.get_selected_page() else {
+            return;
+        };
+
+        // apps and processes are lists rather than devices, they don't have stats of their own
+        if selected_page.is::<ResApplications>() || selected_page.is::<ResProcesses>() {
+            return;
+        }
+
+        let tab_name = selected_page.property::<GString>("tab_name");
+        let tab_detail = selected_page.property::<GString>("tab_detail_string");
+
+        let mut lines = if tab_detail.is_empty() {
+            vec![tab_name.to_string()]
+        } else {
+            vec![format!("{tab_name} ({tab_detail})")]
+        };
+        lines.extend(Self::page_stats(&selected_page));
+
+        self.clipboard().set_text(&lines.join("\n"));
+    }
+
+    /// Lets the user give the device of the current page a name of their own, which is kept across restarts
+    pub fn shortcut_rename_page(&self) {
+        let Some(selected_page) = self
+            .get_selected_page()
+            .filter(|page| page.has_property("device_id", None))
+        else {
+            return;
+        };
+
+        let device_id = selected_page.property::<GString>("device_id").to_string();
+
+        let entry = gtk::Entry::builder()
+            .text(
+                SETTINGS
+                    .device_label(&device_id)
+                    .unwrap_or_else(|| selected_page.property::<GString>("tab_name").to_string()),
+            )
+            .activates_default(true)
+            .build();
+
+        let dialog = adw::AlertDialog::builder()
+            .heading(i18n("Rename Device"))
+            .body(i18n(
+                "The name is only used within Resources. Leave it empty to use the default name.",
+            ))
+            .extra_child(&entry)
+            .build();
+
+        dialog.add_response("cancel", &i18n("Cancel"));
+        dialog.add_response("rename", &i18n("Rename"));
+        dialog.set_response_appearance("rename", adw::ResponseAppearance::Suggested);
+        dialog.set_default_response(Some("rename"));
+        dialog.set_close_response("cancel");
+
+        dialog.connect_response(Some("rename"), move |_, _| {
+            let label = entry.text();
+            if let Err(e) = SETTINGS.set_device_label(&device_id, Some(&label)) {
+                warn!("Unable to rename {device_id}: {e}");
+            }
+        });
+
+        dialog.present(Some(self));
+    }
+
+    /// Returns the title and value of every visible graph box and property row that's (recursively) contained in
+    /// `widget` as "title: value" lines
+    fn page_stats(widget: &Widget) -> Vec<String> {
+        let mut stats = Vec::new();
+
+        let mut child = widget.first_child();
+        while let Some(current) = child {
+            if current.is_visible() {
+                if let Some(graph_box) = current.downcast_ref::<ResGraphBox>() {
+                    stats.push(format!(
+                        "{}: {}",
+                        Self::strip_markup(&graph_box.title_label()),
+                        Self::strip_markup(&graph_box.subtitle())
+                    ));
+                } else if let Some(row) = current.downcast_ref::<adw::ActionRow>() {
+                    if let Some(subtitle) = row.subtitle().filter(|subtitle| !subtitle.is_empty()) {
+                        stats.push(format!(
+                            "{}: {}",
+                            Self::strip_markup(&row.title()),
+                            Self::strip_markup(&subtitle)
+                        ));
+                    }
+                } else {
+                    stats.extend(Self::page_stats(&current));
+                }
+            }
+            child = current.next_sibling();
+        }
+
+        stats
+    }
+
+    /// Turns the Pango markup of a label into the plain text it shows, text that isn't valid markup is kept as it is
+    fn strip_markup(markup: &str) -> String {
+        gtk::pango::parse_markup(markup, '\0')
+            .map_or_else(|_| markup.to_string(), |(_, text, _)| text.to_string())
+    }
+
+    /// Returns all expander rows that are (recursively) contained in `widget`
+    fn expander_rows(widget: &Widget) -> Vec<adw::ExpanderRow> {
+        let mut expander_rows = Vec::new();
+
+        let mut child = widget.first_child();
+        while let Some(current) = child {
+            if let Some(expander_row) = current.downcast_ref::<adw::ExpanderRow>() {
+                expander_rows.push(expander_row.clone());
+            }
+            expander_rows.extend(Self::expander_rows(&current));
+            child = current.next_sibling();
+        }
+
+        expander_rows
+    }
+
+    /// Returns all graphs that are (recursively) contained in `widget`
+    fn graphs(widget: &Widget) -> Vec<ResGraph> {
+        let mut graphs = Vec::new();
+
+        let mut child = widget.first_child();
+        while let Some(current) = child {
+            if let Some(graph) = current.downcast_ref::<ResGraph>() {
+                graphs.push(graph.clone());
+            }
+            graphs.extend(Self::graphs(&current));
+            child = current.next_sibling();
+        }
+
+        graphs
+    }
+
+    /// Remembers the histories of all graphs of the device page `page` so they can be restored once a page for the
+    /// device at `sysfs_path` is created again
+    fn save_graph_histories(&self, sysfs_path: &Path, page: &ToolbarView) {
+        let histories = Self::graphs(page.upcast_ref())
+            .iter()
+            .map(ResGraph::history)
+            .collect();
+
+        let mut graph_histories = self.imp().graph_histories.borrow_mut();
+
+        // devices like USB drives or virtual network interfaces might come and go with a new path every time, so only
+        // the most recently removed pages are remembered
+        if graph_histories.len() >= MAX_SAVED_GRAPH_HISTORIES
+            && !graph_histories.contains_key(sysfs_path)
+        {
+            if let Some(oldest_path) = graph_histories
+                .iter()
+                .min_by_key(|(_, (saved, _))| *saved)
+                .map(|(path, _)| path.clone())
+            {
+                graph_histories.remove(&oldest_path);
+            }
+        }
+
+        graph_histories.insert(sysfs_path.to_path_buf(), (Instant::now(), histories));
+    }
+
+    /// Restores the graph histories of a previous page for the device at `sysfs_path` into the newly created `page`
+    fn restore_graph_histories(&self, sysfs_path: &Path, page: &Widget) {
+        let Some((_, histories)) = self.imp().graph_histories.borrow_mut().remove(sysfs_path)
+        else {
+            return;
+        };
+
+        let graphs = Self::graphs(page);
+
+        // the layout of the page might have changed in the meantime, in which case we can't tell which history belongs
+        // to which graph
+        if graphs.len() != histories.len() {
+            return;
+        }
+
+        for (graph, history) in graphs.iter().zip(&histories) {
+            graph.restore_history(history);
+        }
+    }
+
+    /// Restores whether the expander rows of `page` are expanded and remembers it from now on. The sections are
+    /// identified by the widget names of their expander rows, so sections of the same type share their state.
+    fn setup_expanders(&self, page: &Widget) {
+        let expanded_sections = SETTINGS.expanded_sections();
+
+        for expander_row in Self::expander_rows(page) {
+            let section = expander_row.widget_name();
+            expander_row.set_expanded(expanded_sections.iter().any(|s| s == section.as_str()));
+
+            expander_row.connect_expanded_notify(|expander_row| {
+                let _ = SETTINGS
+                    .set_section_expanded(&expander_row.widget_name(), expander_row.is_expanded());
+            });
+        }
+    }
+
     pub fn shortcut_manipulate_app_process(&self, process_action: ProcessAction) {
         let imp = self.imp();
 
@@ -300,25 +628,38 @@ impl MainWindow {
 
         if selected_page.is::<ResProcesses>() {
             let selected = imp.processes.get_selected_process_entries();
-            if selected.len() == 1 {
-                imp.processes.open_options_dialog(&selected[0]);
+            if !selected.is_empty() {
+                imp.processes.open_options_dialog(&selected);
             }
         }
     }
 
+    /// Returns the name the GPU with the index `i` is shown under out of `gpus_len` GPUs
+    fn gpu_tab_name(i: usize, gpus_len: usize) -> String {
+        if gpus_len > 1 {
+            i18n_f("GPU {}", &[&(i + 1).to_string()])
+        } else {
+            i18n("GPU")
+        }
+    }
+
+    /// Returns the names of the GPUs in the order they're shown in the sidebar
+    fn gpu_labels(gpus: &[Gpu]) -> Vec<(GpuIdentifier, String)> {
+        gpus.iter()
+            .enumerate()
+            .map(|(i, gpu)| (gpu.gpu_identifier(), Self::gpu_tab_name(i, gpus.len())))
+            .collect()
+    }
+
     fn init_gpu_pages(self: &MainWindow, gpus: &[Gpu]) {
         let imp = self.imp();
 
         for (i, gpu) in gpus.iter().enumerate() {
             let page = ResGPU::new();
 
-            let tab_name = if gpus.len() > 1 {
-                i18n_f("GPU {}", &[&(i + 1).to_string()])
-            } else {
-                i18n("GPU")
-            };
+            let tab_name = Self::gpu_tab_name(i, gpus.len());
 
-            page.set_tab_name(&*tab_name);
+            bind_device_label(&page, &gpu.stable_id(), &tab_name);
 
             let added_page = if let Ok(gpu_name) = gpu.name() {
                 self.add_page(&page, &gpu_name, &tab_name)
@@ -328,12 +669,48 @@ impl MainWindow {
 
             page.init(gpu, i as u32);
 
+            page.connect_process_action(clone!(
+                #[weak(rename_to = this)]
+                self,
+                move |pid, action| {
+                    if let Some(process_entry) = this.imp().processes.get_process_entry(pid) {
+                        this.imp()
+                            .processes
+                            .open_process_action_dialog(vec![process_entry], action);
+                    }
+                }
+            ));
+
+            if !ARGS.disable_process_monitoring {
+                page.connect_show_vram_processes(clone!(
+                    #[weak(rename_to = this)]
+                    self,
+                    #[strong]
+                    tab_name,
+                    move |gpu_identifier| this.show_gpu_memory_users(gpu_identifier, &tab_name)
+                ));
+            }
+
             imp.gpu_pages
                 .borrow_mut()
                 .insert(gpu.gpu_identifier(), (gpu.clone(), added_page));
         }
     }
 
+    /// Switches to the processes page, showing only the processes that use video memory of the GPU with
+    /// `gpu_identifier`
+    fn show_gpu_memory_users(&self, gpu_identifier: GpuIdentifier, gpu_name: &str) {
+        let imp = self.imp();
+
+        imp.processes
+            .show_gpu_memory_users(gpu_identifier, gpu_name, &imp.apps_context.borrow());
+
+        imp.content_stack
+            .set_visible_child(&imp.processes_page.child());
+        imp.resources_sidebar
+            .set_selected_list_item_by_tab_id(processes::TAB_ID);
+    }
+
     fn init_npu_pages(self: &MainWindow) -> Vec<Npu> {
         let imp = self.imp();
 
@@ -378,6 +755,29 @@ impl MainWindow {
 
         imp.resources_sidebar.set_stack(&imp.content_stack);
 
+        self.apply_page_visibility();
+        SETTINGS.connect_page_visibility(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move || this.apply_page_visibility()
+        ));
+
+        // the window needs to be mapped before a window manager can find it by its title
+        self.connect_map(|window| window.apply_always_on_top(SETTINGS.always_on_top()));
+
+        SETTINGS.connect_always_on_top(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |always_on_top| this.apply_always_on_top(always_on_top)
+        ));
+
+        self.apply_tray_indicator(SETTINGS.show_tray_indicator());
+        SETTINGS.connect_show_tray_indicator(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |show_tray_indicator| this.apply_tray_indicator(show_tray_indicator)
+        ));
+
         if SETTINGS.show_search_on_start() {
             // we want the search bar to show up for both but also let the last viewed page grab the focus, so order is
             // important here
@@ -399,9 +799,12 @@ impl MainWindow {
                     .filter(|gpu| gpu.combined_media_engine().unwrap_or_default())
                     .map(Gpu::gpu_identifier)
                     .collect(),
+                Self::gpu_labels(&gpus).into_iter().collect(),
             );
-            imp.applications.init(imp.sender.clone());
-            imp.processes.init(imp.sender.clone());
+            imp.applications
+                .init(imp.sender.clone(), &Self::gpu_labels(&gpus));
+            imp.processes
+                .init(imp.sender.clone(), &Self::gpu_labels(&gpus));
         }
 
         if ARGS.disable_cpu_monitoring {
@@ -415,18 +818,34 @@ impl MainWindow {
                 imp.processor_window_title.set_subtitle(&i18n("Processor"));
             }
             imp.cpu.init(cpu_info);
+            self.setup_expanders(imp.cpu.upcast_ref());
         }
 
         if ARGS.disable_memory_monitoring {
             self.remove_page(imp.memory_page.child().downcast_ref().unwrap());
         } else {
             imp.memory.init();
+            self.setup_expanders(imp.memory.upcast_ref());
         }
 
         if !ARGS.disable_npu_monitoring {
             self.init_npu_pages();
         }
 
+        let os_page = ResOs::new();
+        os_page.init();
+        let os_title = os_page.tab_name();
+        self.add_page(&os_page, &os_title, &os_title);
+
+        match ARGS.metrics_log() {
+            Some(Ok(metrics_log)) => {
+                let interval = ARGS.metrics_interval();
+                std::thread::spawn(move || Self::log_metrics(metrics_log, interval));
+            }
+            Some(Err(e)) => warn!("Unable to open metrics log, metrics won't be logged: {e}"),
+            None => (),
+        }
+
         let main_context = MainContext::default();
 
         main_context.spawn_local(clone!(
@@ -438,7 +857,151 @@ impl MainWindow {
         ));
     }
 
-    fn gather_refresh_data(logical_cpus: usize, gpus: &[Gpu], npus: &[Npu]) -> RefreshData {
+    /// Gathers the data of all devices every `interval` and appends it to `metrics_log`. This never returns, so it's
+    /// either run on its own thread next to the GUI or on the main thread in headless mode.
+    pub fn log_metrics(mut metrics_log: MetricsLog, interval: Duration) -> ! {
+        let gpus = if ARGS.disable_gpu_monitoring {
+            Vec::new()
+        } else {
+            Gpu::get_gpus().unwrap_or_default()
+        };
+
+        loop {
+            // processes aren't logged, so there's no need to walk through /proc
+            let refresh_data =
+                Self::gather_refresh_data(*NUM_CPUS, &gpus, &[], None, DueSubsystems::all(false));
+
+            let mem_data = refresh_data.mem_data.and_then(|mem_data| {
+                mem_data
+                    .inspect_err(|e| warn!("Unable to get memory data: {e}"))
+                    .ok()
+            });
+
+            let snapshot = MetricsSnapshot {
+                cpu_data: refresh_data.cpu_data.as_ref(),
+                mem_data: mem_data.as_ref(),
+                gpu_data: &refresh_data.gpu_data,
+                drive_data: gathered(&refresh_data.drive_data),
+                network_data: gathered(&refresh_data.network_data),
+                battery_data: gathered(&refresh_data.battery_data),
+            };
+
+            if let Err(e) = metrics_log.write_snapshot(&snapshot) {
+                warn!("Unable to write metrics snapshot: {e}");
+            }
+
+            std::thread::sleep(interval);
+        }
+    }
+
+    /// Records the usage of the processor, memory, GPUs and network interfaces in `refresh_data` to the history
+    fn record_history(history_recorder: &mut HistoryRecorder, refresh_data: &RefreshData) {
+        let snapshot = MetricsSnapshot {
+            cpu_data: refresh_data.cpu_data.as_ref(),
+            mem_data: refresh_data
+                .mem_data
+                .as_ref()
+                .and_then(|mem_data| mem_data.as_ref().ok()),
+            gpu_data: &refresh_data.gpu_data,
+            drive_data: &[],
+            network_data: gathered(&refresh_data.network_data),
+            battery_data: &[],
+        };
+
+        if let Err(e) = history_recorder.record(&snapshot) {
+            warn!("Unable to record history: {e}");
+        }
+    }
+
+    /// Prints a JSON snapshot of all devices and processes to stdout, either once or every `interval`. Like
+    /// `log_metrics()`, this never returns.
+    pub fn print_json(interval: Option<Duration>) -> ! {
+        let gpus = if ARGS.disable_gpu_monitoring {
+            Vec::new()
+        } else {
+            Gpu::get_gpus().unwrap_or_default()
+        };
+
+        let mut apps_context = (!ARGS.disable_process_monitoring).then(|| {
+            AppsContext::new(
+                gpus.iter()
+                    .filter(|gpu| gpu.combined_media_engine().unwrap_or_default())
+                    .map(Gpu::gpu_identifier)
+                    .collect(),
+                Self::gpu_labels(&gpus).into_iter().collect(),
+            )
+        });
+
+        let mut json_output = JsonOutput::default();
+
+        // rates like CPU usage are calculated between two snapshots, so the first one is only used as a baseline and a
+        // single snapshot is taken a second after it
+        let _ = Self::json_snapshot(&mut json_output, &gpus, apps_context.as_mut());
+
+        loop {
+            std::thread::sleep(interval.unwrap_or(Duration::from_secs(1)));
+
+            match Self::json_snapshot(&mut json_output, &gpus, apps_context.as_mut()) {
+                Ok(json) => {
+                    // the reader has gone away (e.g. `head` in a pipe), so there's nobody left to print to
+                    if writeln!(std::io::stdout(), "{json}").is_err() {
+                        std::process::exit(0);
+                    }
+                }
+                Err(e) => warn!("Unable to create JSON snapshot: {e}"),
+            }
+
+            if interval.is_none() {
+                std::process::exit(0);
+            }
+        }
+    }
+
+    fn json_snapshot(
+        json_output: &mut JsonOutput,
+        gpus: &[Gpu],
+        apps_context: Option<&mut AppsContext>,
+    ) -> Result<String> {
+        let refresh_data = Self::gather_refresh_data(
+            *NUM_CPUS,
+            gpus,
+            &[],
+            None,
+            DueSubsystems::all(apps_context.is_some()),
+        );
+
+        let apps_context = apps_context.map(|apps_context| {
+            if let Some(process_data) = refresh_data.process_data {
+                apps_context.refresh(process_data);
+            }
+            &*apps_context
+        });
+
+        let mem_data = refresh_data.mem_data.and_then(|mem_data| {
+            mem_data
+                .inspect_err(|e| warn!("Unable to get memory data: {e}"))
+                .ok()
+        });
+
+        let snapshot = MetricsSnapshot {
+            cpu_data: refresh_data.cpu_data.as_ref(),
+            mem_data: mem_data.as_ref(),
+            gpu_data: &refresh_data.gpu_data,
+            drive_data: gathered(&refresh_data.drive_data),
+            network_data: gathered(&refresh_data.network_data),
+            battery_data: gathered(&refresh_data.battery_data),
+        };
+
+        json_output.format_snapshot(&snapshot, apps_context)
+    }
+
+    fn gather_refresh_data(
+        logical_cpus: usize,
+        gpus: &[Gpu],
+        npus: &[Npu],
+        threads_pid: Option<libc::pid_t>,
+        due: DueSubsystems,
+    ) -> RefreshData {
         let start = Instant::now();
 
         trace!("Gathering refresh data of all devices…");
@@ -462,6 +1025,8 @@ impl MainWindow {
             gpu_data.push(data);
         }
 
+        GpuData::mark_primary_display(&mut gpu_data);
+
         let mut npu_data = Vec::with_capacity(npus.len());
         for npu in npus {
             let data = NpuData::new(npu);
@@ -469,61 +1034,173 @@ impl MainWindow {
             npu_data.push(data);
         }
 
-        let drive_paths = if ARGS.disable_drive_monitoring {
-            Vec::new()
-        } else {
-            Drive::get_sysfs_paths().unwrap_or_default()
-        };
-        let mut drive_data = Vec::with_capacity(drive_paths.len());
-        for path in &drive_paths {
-            drive_data.push(DriveData::new(path));
-        }
+        let drive_data = due.drives.then(|| {
+            let drive_paths = if ARGS.disable_drive_monitoring {
+                Vec::new()
+            } else {
+                Drive::get_sysfs_paths().unwrap_or_default()
+            };
+            let mut drive_data = Vec::with_capacity(drive_paths.len());
+            for path in &drive_paths {
+                drive_data.push(DriveData::new(path));
+            }
+            (drive_paths, drive_data)
+        });
 
-        let network_paths = if ARGS.disable_network_interface_monitoring {
-            Vec::new()
-        } else {
-            NetworkInterface::get_sysfs_paths().unwrap_or_default()
-        };
-        let mut network_data = Vec::with_capacity(network_paths.len());
-        for path in &network_paths {
-            network_data.push(NetworkData::new(path));
-        }
+        let network_data = due.network.then(|| {
+            let network_paths = if ARGS.disable_network_interface_monitoring {
+                Vec::new()
+            } else {
+                NetworkInterface::get_sysfs_paths().unwrap_or_default()
+            };
+            let (addresses, wifi_links) = if network_paths.is_empty() {
+                (HashMap::new(), HashMap::new())
+            } else {
+                (
+                    Process::interface_addresses()
+                        .inspect_err(|e| debug!("Unable to read interface addresses: {e}"))
+                        .unwrap_or_default(),
+                    Process::wifi_links()
+                        .inspect_err(|e| debug!("Unable to read Wi-Fi links: {e}"))
+                        .unwrap_or_default(),
+                )
+            };
+            let mut network_data = Vec::with_capacity(network_paths.len());
+            for path in &network_paths {
+                network_data.push(NetworkData::new(path, &addresses, &wifi_links));
+            }
+            (network_paths, network_data)
+        });
 
-        let battery_paths = if ARGS.disable_battery_monitoring {
-            Vec::new()
-        } else {
-            Battery::get_sysfs_paths().unwrap_or_default()
-        };
-        let mut battery_data = Vec::with_capacity(battery_paths.len());
-        for path in &battery_paths {
-            battery_data.push(BatteryData::new(path));
-        }
+        // RDMA ports are network devices too, so they're covered by the same switch
+        let infiniband_data = due.network.then(|| {
+            let infiniband_paths = if ARGS.disable_network_interface_monitoring {
+                Vec::new()
+            } else {
+                InfinibandPort::get_sysfs_paths().unwrap_or_default()
+            };
+            let mut infiniband_data = Vec::with_capacity(infiniband_paths.len());
+            for path in &infiniband_paths {
+                infiniband_data.push(InfinibandData::new(path));
+            }
+            (infiniband_paths, infiniband_data)
+        });
+
+        let battery_data = due.sensors.then(|| {
+            let battery_paths = if ARGS.disable_battery_monitoring {
+                Vec::new()
+            } else {
+                Battery::get_sysfs_paths().unwrap_or_default()
+            };
+            let mut battery_data = Vec::with_capacity(battery_paths.len());
+            for path in &battery_paths {
+                battery_data.push(BatteryData::new(path));
+            }
+            (battery_paths, battery_data)
+        });
+
+        let custom_gauge_data = due.sensors.then(|| {
+            SETTINGS
+                .custom_gauges()
+                .iter()
+                .map(CustomGaugeData::new)
+                .collect()
+        });
 
         let process_data = if ARGS.disable_process_monitoring {
-            Vec::new()
+            Some(Vec::new())
+        } else if due.processes {
+            Some(
+                Process::all_data()
+                    .inspect_err(|e| {
+                        warn!(
+                            "Unable to update process and app data! Is resources-processes running?\n{e}\n{}",
+                            e.backtrace()
+                        );
+                    })
+                    .unwrap_or_default(),
+            )
         } else {
-            Process::all_data()
-                .inspect_err(|e| {
-                    warn!(
-                        "Unable to update process and app data! Is resources-processes running?\n{e}\n{}",
-                        e.backtrace()
-                    );
-                })
-                .unwrap_or_default()
+            None
         };
 
+        let thread_data = threads_pid.and_then(|pid| {
+            Process::threads_of(pid)
+                .inspect_err(|e| warn!("Unable to get threads of process {pid}!\n{e}"))
+                .ok()
+                .map(|threads| (pid, threads))
+        });
+
+        let drm_fdinfo_data = threads_pid
+            .filter(|_| SETTINGS.processes_show_drm_fdinfo())
+            .and_then(|pid| {
+                Process::drm_fdinfos_of(pid)
+                    .inspect_err(|e| warn!("Unable to get DRM fdinfos of process {pid}!\n{e}"))
+                    .ok()
+                    .map(|fdinfos| (pid, fdinfos))
+            });
+
+        let limits_data = threads_pid.and_then(|pid| {
+            Process::limits_of(pid)
+                .inspect_err(|e| warn!("Unable to get limits of process {pid}!\n{e}"))
+                .ok()
+                .map(|limits| (pid, limits))
+        });
+
+        let socket_data = threads_pid.and_then(|pid| {
+            Process::sockets_of(pid)
+                .inspect_err(|e| warn!("Unable to get sockets of process {pid}!\n{e}"))
+                .ok()
+                .map(|sockets| (pid, sockets))
+        });
+
+        let environment_data = threads_pid.and_then(|pid| {
+            Process::environment_of(pid)
+                .inspect_err(|e| warn!("Unable to get environment of process {pid}!\n{e}"))
+                .ok()
+                .map(|environment| (pid, environment))
+        });
+
+        let open_file_data = threads_pid.and_then(|pid| {
+            Process::open_files_of(pid)
+                .inspect_err(|e| warn!("Unable to get open files of process {pid}!\n{e}"))
+                .ok()
+                .map(|open_files| (pid, open_files))
+        });
+
+        let wait_channel_data = threads_pid.and_then(|pid| {
+            Process::wait_channel_of(pid)
+                .inspect_err(|e| warn!("Unable to get wait channel of process {pid}!\n{e}"))
+                .ok()
+                .map(|wait_channel| (pid, wait_channel))
+        });
+
+        let schedstat_data = threads_pid.and_then(|pid| {
+            Process::schedstat_of(pid)
+                .inspect_err(|e| warn!("Unable to get scheduler statistics of process {pid}!\n{e}"))
+                .ok()
+                .map(|schedstat| (pid, schedstat))
+        });
+
         let refresh_data = RefreshData {
             cpu_data,
             mem_data,
             gpu_data,
             npu_data,
-            drive_paths,
             drive_data,
-            network_paths,
             network_data,
-            battery_paths,
+            infiniband_data,
             battery_data,
+            custom_gauge_data,
             process_data,
+            thread_data,
+            drm_fdinfo_data,
+            limits_data,
+            socket_data,
+            environment_data,
+            open_file_data,
+            wait_channel_data,
+            schedstat_data,
         };
 
         trace!("Finished gathering refresh data in {:.2?}", start.elapsed());
@@ -543,13 +1220,20 @@ impl MainWindow {
             mem_data,
             gpu_data,
             npu_data,
-            drive_paths,
             drive_data,
-            network_paths,
             network_data,
-            battery_paths,
+            infiniband_data,
             battery_data,
+            custom_gauge_data,
             process_data,
+            thread_data,
+            drm_fdinfo_data,
+            limits_data,
+            socket_data,
+            environment_data,
+            open_file_data,
+            wait_channel_data,
+            schedstat_data,
         } = refresh_data;
 
         /*
@@ -557,10 +1241,48 @@ impl MainWindow {
          */
 
         let mut apps_context = imp.apps_context.borrow_mut();
-        apps_context.refresh(process_data);
 
-        imp.applications.refresh_apps_list(&apps_context);
-        imp.processes.refresh_processes_list(&apps_context);
+        if let Some(process_data) = process_data {
+            apps_context.refresh(process_data);
+
+            imp.applications.refresh_apps_list(&apps_context);
+            imp.processes.refresh_processes_list(&apps_context);
+        }
+
+        if let Some((pid, threads)) = thread_data {
+            imp.processes.refresh_info_dialog_threads(pid, &threads);
+        }
+
+        if let Some((pid, fdinfos)) = drm_fdinfo_data {
+            imp.processes.refresh_info_dialog_drm_fdinfos(pid, &fdinfos);
+        }
+
+        if let Some((pid, limits)) = limits_data {
+            imp.processes.refresh_info_dialog_limits(pid, &limits);
+        }
+
+        if let Some((pid, sockets)) = socket_data {
+            imp.processes.refresh_info_dialog_sockets(pid, &sockets);
+        }
+
+        if let Some((pid, environment)) = environment_data {
+            imp.processes
+                .refresh_info_dialog_environment(pid, &environment);
+        }
+
+        if let Some((pid, open_files)) = open_file_data {
+            imp.processes
+                .refresh_info_dialog_open_files(pid, &open_files);
+        }
+
+        if let Some((pid, wait_channel)) = wait_channel_data {
+            imp.processes
+                .refresh_info_dialog_wait_channel(pid, &wait_channel);
+        }
+
+        if let Some((pid, schedstat)) = schedstat_data {
+            imp.processes.refresh_info_dialog_schedstat(pid, schedstat);
+        }
 
         /*
          *  Gpu
@@ -596,6 +1318,10 @@ impl MainWindow {
             }
 
             page.refresh_page(&gpu_data);
+
+            if !ARGS.disable_process_monitoring {
+                page.refresh_processes(&apps_context);
+            }
         }
 
         std::mem::drop(apps_context);
@@ -627,62 +1353,123 @@ impl MainWindow {
             }
         }
 
+        if let Some(status_notifier) = imp.status_notifier.borrow().as_ref() {
+            status_notifier.set_usages(imp.cpu.usage(), imp.memory.usage());
+        }
+
+        self.notify_alerts(
+            drive_data
+                .as_ref()
+                .map(|(_, drive_data)| drive_data.as_slice()),
+            battery_data
+                .as_ref()
+                .map(|(_, battery_data)| battery_data.as_slice()),
+        );
+
         /*
          *  Drives
          */
-        // Make sure there is a page for every drive that is shown
-        self.refresh_drive_pages(drive_paths, &drive_data);
+        if let Some((drive_paths, drive_data)) = drive_data {
+            let member_drives = Self::member_drives(&drive_data);
 
-        // Update drive pages
-        for drive_data in drive_data {
-            if drive_data.is_virtual && !SETTINGS.show_virtual_drives() {
-                continue;
-            }
+            // Make sure there is a page for every drive that is shown
+            self.refresh_drive_pages(drive_paths, &drive_data, &member_drives);
 
-            let drive_pages = imp.drive_pages.borrow();
-            let page = drive_pages.get(&drive_data.inner.sysfs_path).unwrap();
-            let page = page.content().and_downcast::<ResDrive>().unwrap();
+            // virtual drives are checked as well since e.g. encrypted root filesystems live on mapped devices
+            self.notify_full_filesystems(&drive_data);
 
-            page.refresh_page(drive_data);
+            // Update drive pages
+            for drive_data in drive_data {
+                if Self::is_drive_hidden(&drive_data, &member_drives) {
+                    continue;
+                }
+
+                let drive_pages = imp.drive_pages.borrow();
+                let page = drive_pages.get(&drive_data.inner.sysfs_path).unwrap();
+                let page = page.content().and_downcast::<ResDrive>().unwrap();
+
+                page.refresh_page(drive_data);
+            }
         }
 
         /*
          *  Network
          */
-        // Make sure there is a page for every network interface that is shown
-        self.refresh_network_pages(network_paths, &network_data);
+        if let Some((network_paths, network_data)) = network_data {
+            // Make sure there is a page for every network interface that is shown
+            self.refresh_network_pages(network_paths, &network_data);
 
-        // Update network pages
-        for network_data in network_data {
-            if network_data.is_virtual && !SETTINGS.show_virtual_network_interfaces() {
-                continue;
+            self.refresh_network_total_page(&network_data);
+
+            // Update network pages
+            for network_data in network_data {
+                if Self::is_network_interface_hidden(&network_data) {
+                    continue;
+                }
+
+                let network_pages = imp.network_pages.borrow();
+                let page = network_pages.get(&network_data.inner.sysfs_path).unwrap();
+                let page = page.content().and_downcast::<ResNetwork>().unwrap();
+
+                page.refresh_page(network_data);
             }
+        }
 
-            let network_pages = imp.network_pages.borrow();
-            let page = network_pages.get(&network_data.inner.sysfs_path).unwrap();
-            let page = page.content().and_downcast::<ResNetwork>().unwrap();
+        /*
+         *  InfiniBand
+         */
+        if let Some((infiniband_paths, infiniband_data)) = infiniband_data {
+            // Make sure there is a page for every InfiniBand port
+            self.refresh_infiniband_pages(infiniband_paths, &infiniband_data);
+
+            // Update InfiniBand pages
+            for infiniband_data in infiniband_data {
+                let infiniband_pages = imp.infiniband_pages.borrow();
+                let page = infiniband_pages
+                    .get(&infiniband_data.inner.sysfs_path)
+                    .unwrap();
+                let page = page.content().and_downcast::<ResInfiniband>().unwrap();
 
-            page.refresh_page(network_data);
+                page.refresh_page(infiniband_data);
+            }
         }
 
         /*
          *  Batteries
          */
-        // Make sure there is a page for every battery that is shown
-        self.refresh_battery_pages(battery_paths, &battery_data);
+        if let Some((battery_paths, battery_data)) = battery_data {
+            // Make sure there is a page for every battery that is shown
+            self.refresh_battery_pages(battery_paths, &battery_data);
 
-        // Update battery pages
-        for battery_data in battery_data {
-            let battery_pages = imp.battery_pages.borrow();
-            let page = battery_pages.get(&battery_data.inner.sysfs_path).unwrap();
-            let page = page.content().and_downcast::<ResBattery>().unwrap();
+            // Update battery pages
+            for battery_data in battery_data {
+                let battery_pages = imp.battery_pages.borrow();
+                let page = battery_pages.get(&battery_data.inner.sysfs_path).unwrap();
+                let page = page.content().and_downcast::<ResBattery>().unwrap();
 
-            page.refresh_page(battery_data);
+                page.refresh_page(battery_data);
+            }
+        }
+
+        /*
+         *  Custom gauges
+         */
+        if let Some(custom_gauge_data) = custom_gauge_data {
+            self.refresh_custom_gauges_page(&custom_gauge_data);
         }
 
         trace!("UI refresh done in {:.2?}", start.elapsed());
     }
 
+    /// Seconds between two refreshes, either as given via CLI or according to the refresh speed in the settings
+    fn refresh_interval(on_battery_power: bool) -> f32 {
+        ARGS.refresh_interval().unwrap_or_else(|| {
+            SETTINGS
+                .current_refresh_speed(on_battery_power)
+                .ui_refresh_interval()
+        })
+    }
+
     pub async fn periodic_refresh_all(&self) {
         let imp = self.imp();
 
@@ -713,16 +1500,79 @@ impl MainWindow {
         let (tx_data, rx_data) = std::sync::mpsc::sync_channel(1);
         let (tx_wait, rx_wait) = std::sync::mpsc::sync_channel(1);
 
+        // written by the refresh thread, which checks the power supplies before every refresh
+        let on_battery_power = Arc::new(AtomicBool::new(false));
+        let thread_on_battery_power = on_battery_power.clone();
+
         std::thread::spawn(move || {
             trace!("Spawning refresh thread");
 
+            let mut threads_pid = None;
+            let mut refresh_scheduler = RefreshScheduler::default();
+            let mut history_recorder = None;
+
             loop {
-                let data = Self::gather_refresh_data(logical_cpus, &gpus, &npus);
+                let on_battery = Battery::on_battery_power();
+                if thread_on_battery_power.swap(on_battery, Ordering::Relaxed) != on_battery {
+                    if on_battery {
+                        info!("System is now running on battery power");
+                    } else {
+                        info!("System is no longer running on battery power");
+                    }
+                }
+
+                let refresh_interval = Self::refresh_interval(on_battery);
+
+                // walking through /proc is by far the most expensive part and things like drives rarely change, so
+                // these may be gathered less often
+                let mut due = refresh_scheduler.due_subsystems(refresh_interval);
+
+                // the history is recorded from the data that's gathered for the UI anyway, the recorder (and with it
+                // its files) only exists while recording is enabled
+                if !SETTINGS.record_history() {
+                    history_recorder = None;
+                } else if history_recorder.is_none() {
+                    history_recorder = Some(HistoryRecorder::new(&*HISTORY_DIR));
+                }
+
+                let history_due = history_recorder
+                    .as_ref()
+                    .is_some_and(|history_recorder| history_recorder.is_due(refresh_interval));
+
+                // hidden pages are neither gathered nor refreshed, they catch up once they're shown again
+                Self::skip_hidden_subsystems(&mut due, !gpus.is_empty());
+
+                // network rates are calculated between two samples, so the interfaces are needed for every sample
+                due.network |= history_due;
+
+                let shown_gpus = if SETTINGS.show_gpu_pages() || history_due {
+                    gpus.as_slice()
+                } else {
+                    &[]
+                };
+                let shown_npus = if SETTINGS.show_npu_pages() {
+                    npus.as_slice()
+                } else {
+                    &[]
+                };
+
+                let data = Self::gather_refresh_data(
+                    logical_cpus,
+                    shown_gpus,
+                    shown_npus,
+                    threads_pid,
+                    due,
+                );
+
+                if let Some(history_recorder) = history_recorder.as_mut().filter(|_| history_due) {
+                    Self::record_history(history_recorder, &data);
+                }
+
                 tx_data.send(data).unwrap();
 
                 // Wait on delay so we don't gather data multiple times in a short time span
                 // Which usually just yields the same data and makes changes appear delayed by (up to) multiple refreshes
-                rx_wait.recv().unwrap();
+                threads_pid = rx_wait.recv().unwrap();
             }
         });
 
@@ -750,7 +1600,7 @@ impl MainWindow {
 
                     let child_id = toolbar.content().unwrap().property::<GString>("tab_id");
 
-                    if child_id == page_to_open {
+                    if child_id == page_to_open && page.is_visible() {
                         imp.content_stack.set_visible_child(&toolbar);
                         imp.resources_sidebar
                             .set_selected_list_item_by_tab_id(&child_id);
@@ -762,15 +1612,15 @@ impl MainWindow {
             }
 
             // Total time before next ui refresh
-            let total_delay = SETTINGS.refresh_speed().ui_refresh_interval();
+            let total_delay = Self::refresh_interval(on_battery_power.load(Ordering::Relaxed));
 
             // Reasonable timespan before total_delay ends to gather all data
             let gather_time = 0.2;
 
             timeout_future(Duration::from_secs_f32(total_delay - gather_time)).await;
 
-            // Tell other threads to start gethering data
-            tx_wait.send(()).unwrap();
+            // Tell other threads to start gethering data, threads are only gathered for an open process info dialog
+            tx_wait.send(imp.processes.info_dialog_pid()).unwrap();
 
             timeout_future(Duration::from_secs_f32(gather_time)).await;
         }
@@ -792,7 +1642,12 @@ impl MainWindow {
     }
 
     /// Create page for every drive that is shown
-    fn refresh_drive_pages(&self, mut paths: Vec<PathBuf>, drive_data: &[DriveData]) {
+    fn refresh_drive_pages(
+        &self,
+        mut paths: Vec<PathBuf>,
+        drive_data: &[DriveData],
+        member_drives: &HashSet<String>,
+    ) {
         trace!("Refreshing drive pages…");
 
         let imp = self.imp();
@@ -813,7 +1668,7 @@ impl MainWindow {
 
         // Filter hidden drives
         for data in drive_data {
-            if data.is_virtual && !SETTINGS.show_virtual_drives() {
+            if Self::is_drive_hidden(data, member_drives) {
                 let idx = paths
                     .iter()
                     .position(|p| **p == data.inner.sysfs_path)
@@ -832,6 +1687,7 @@ impl MainWindow {
                 );
 
                 let page = drive_pages.remove(page_path).unwrap();
+                self.save_graph_histories(page_path, &page);
                 self.remove_page(&page);
             }
         }
@@ -856,16 +1712,247 @@ impl MainWindow {
 
                 let page = ResDrive::new();
                 page.init(drive, highest_secondary_ord);
+                self.restore_graph_histories(&path, page.upcast_ref());
 
-                if let Some(model) = &drive.inner.model {
-                    self.add_page(&page, model, &display_name)
-                } else {
-                    self.add_page(&page, &drive.inner.block_device, &display_name)
-                }
+                self.add_page(&page, &drive.inner.description(), &display_name)
             });
         }
     }
 
+    /// Drives may be shown or hidden individually, otherwise loop devices have their own setting since there tend to be
+    /// lots of them (e.g. one per Snap package)
+    fn is_drive_hidden(drive_data: &DriveData, member_drives: &HashSet<String>) -> bool {
+        if let Some(visible) = SETTINGS.device_visibility(&drive_data.inner.stable_id()) {
+            !visible
+        } else if member_drives.contains(&drive_data.inner.block_device) {
+            true
+        } else if drive_data.inner.drive_type == DriveType::LoopDevice {
+            !SETTINGS.show_loop_devices()
+        } else {
+            drive_data.is_virtual && !SETTINGS.show_virtual_drives()
+        }
+    }
+
+    /// Returns the drives that are hidden because they're part of an array or a multi-device filesystem that is shown
+    /// in their stead
+    fn member_drives(drive_data: &[DriveData]) -> HashSet<String> {
+        if !SETTINGS.hide_member_drives() {
+            return HashSet::new();
+        }
+
+        drive_data
+            .iter()
+            .filter(|drive_data| {
+                drive_data.inner.members.len() > 1
+                    && !Self::is_drive_hidden(drive_data, &HashSet::new())
+            })
+            .flat_map(|drive_data| drive_data.inner.member_drives())
+            .collect()
+    }
+
+    fn is_network_interface_hidden(network_data: &NetworkData) -> bool {
+        if let Some(visible) = SETTINGS.device_visibility(&network_data.inner.stable_id()) {
+            !visible
+        } else if network_data.is_container {
+            !SETTINGS.show_container_network_interfaces()
+        } else {
+            network_data.is_virtual && !SETTINGS.show_virtual_network_interfaces()
+        }
+    }
+
+    /// Sends a notification for every filesystem that has crossed the "almost full" threshold since the last refresh
+    /// and withdraws it again once the filesystem's usage has dropped below the threshold
+    fn notify_full_filesystems(&self, drive_data: &[DriveData]) {
+        let imp = self.imp();
+
+        let Some(application) = self.application() else {
+            return;
+        };
+
+        let enabled = SETTINGS.drive_full_notification();
+        let threshold = f64::from(SETTINGS.drive_full_threshold()) / 100.0;
+
+        let mut full_filesystems = imp.full_filesystems.borrow_mut();
+        let mut still_full_filesystems = HashSet::new();
+
+        let filesystems = drive_data
+            .iter()
+            .flat_map(|drive_data| &drive_data.filesystems)
+            .filter(|filesystem| enabled && filesystem.used_fraction() >= threshold);
+
+        for filesystem in filesystems {
+            still_full_filesystems.insert(filesystem.mount_point.clone());
+
+            if full_filesystems.contains(&filesystem.mount_point) {
+                continue;
+            }
+
+            let mount_point = filesystem.mount_point.to_string_lossy();
+
+            info!("{mount_point} is almost full, sending notification");
+
+            let notification = gio::Notification::new(&i18n("Drive Almost Full"));
+            notification.set_body(Some(&i18n_f(
+                "{} is {} % full, only {} are left",
+                &[
+                    &mount_point,
+                    &(filesystem.used_fraction() * 100.0).round().to_string(),
+                    &convert_storage(filesystem.available_bytes as f64, false),
+                ],
+            )));
+
+            application.send_notification(
+                Some(&Self::full_filesystem_notification_id(
+                    &filesystem.mount_point,
+                )),
+                &notification,
+            );
+        }
+
+        for mount_point in full_filesystems.difference(&still_full_filesystems) {
+            debug!(
+                "{} is no longer almost full, withdrawing notification",
+                mount_point.display()
+            );
+            application.withdraw_notification(&Self::full_filesystem_notification_id(mount_point));
+        }
+
+        *full_filesystems = still_full_filesystems;
+    }
+
+    /// Sends a notification for every alert rule whose threshold has been crossed for long enough and withdraws it
+    /// again once the alert is resolved
+    /// `drive_data` and `battery_data` are `None` if they haven't been gathered in this refresh, their last readings
+    /// are used then
+    fn notify_alerts(
+        &self,
+        drive_data: Option<&[DriveData]>,
+        battery_data: Option<&[BatteryData]>,
+    ) {
+        let imp = self.imp();
+
+        let Some(application) = self.application() else {
+            return;
+        };
+
+        if let Some(drive_data) = drive_data {
+            let mut drive_readings = imp.drive_readings.borrow_mut();
+            drive_readings.clear();
+
+            for drive_data in drive_data {
+                if let Ok(temperature) = drive_data.temperature {
+                    let device_id = drive_data.inner.stable_id();
+                    let device_name = SETTINGS
+                        .device_label(&device_id)
+                        .unwrap_or_else(|| drive_data.inner.display_name());
+
+                    drive_readings.push(Reading {
+                        metric: Metric::DriveTemperature,
+                        device_id,
+                        device_name,
+                        value: temperature,
+                    });
+                }
+            }
+        }
+
+        if let Some(battery_data) = battery_data {
+            let mut battery_readings = imp.battery_readings.borrow_mut();
+            battery_readings.clear();
+
+            for battery_data in battery_data {
+                if let Ok(charge) = battery_data.charge {
+                    battery_readings.push(Reading {
+                        metric: Metric::BatteryCharge,
+                        device_id: battery_data
+                            .inner
+                            .sysfs_path
+                            .file_name()
+                            .unwrap_or_default()
+                            .to_string_lossy()
+                            .to_string(),
+                        device_name: battery_data.inner.display_name(),
+                        value: charge * 100.0,
+                    });
+                }
+            }
+        }
+
+        let mut readings = vec![
+            Reading {
+                metric: Metric::ProcessorUsage,
+                device_id: String::new(),
+                device_name: i18n("Processor"),
+                value: imp.cpu.usage() * 100.0,
+            },
+            Reading {
+                metric: Metric::MemoryUsage,
+                device_id: String::new(),
+                device_name: i18n("Memory"),
+                value: imp.memory.usage() * 100.0,
+            },
+        ];
+
+        readings.extend(imp.drive_readings.borrow().iter().cloned());
+        readings.extend(imp.battery_readings.borrow().iter().cloned());
+
+        let events =
+            imp.alerts
+                .borrow_mut()
+                .check(&Rule::from_settings(), &readings, Instant::now());
+
+        for event in events {
+            match event {
+                AlertEvent::Raised { reading, rule } => {
+                    info!(
+                        "{} crossed the alert threshold of {} with {}, sending notification",
+                        reading.device_name, rule.threshold, reading.value
+                    );
+
+                    let percentage = reading.value.round().to_string();
+
+                    let (title, body) = match reading.metric {
+                        Metric::ProcessorUsage => (
+                            i18n("High Processor Usage"),
+                            i18n_f("The processor is {} % busy", &[&percentage]),
+                        ),
+                        Metric::MemoryUsage => (
+                            i18n("High Memory Usage"),
+                            i18n_f("{} % of the memory is in use", &[&percentage]),
+                        ),
+                        Metric::DriveTemperature => (
+                            i18n("Drive Running Hot"),
+                            i18n_f(
+                                "{} has reached {}",
+                                &[&reading.device_name, &convert_temperature(reading.value)],
+                            ),
+                        ),
+                        Metric::BatteryCharge => (
+                            i18n("Battery Low"),
+                            i18n_f(
+                                "{} is only charged to {} %",
+                                &[&reading.device_name, &percentage],
+                            ),
+                        ),
+                    };
+
+                    let notification = gio::Notification::new(&title);
+                    notification.set_body(Some(&body));
+
+                    application.send_notification(Some(&reading.alert_id()), &notification);
+                }
+                AlertEvent::Resolved { alert_id } => {
+                    debug!("Alert {alert_id} resolved, withdrawing notification");
+                    application.withdraw_notification(&alert_id);
+                }
+            }
+        }
+    }
+
+    fn full_filesystem_notification_id(mount_point: &Path) -> String {
+        format!("drive-full-{}", mount_point.display())
+    }
+
     /// Create page for every network interface that is shown
     fn refresh_network_pages(&self, mut paths: Vec<PathBuf>, network_data: &[NetworkData]) {
         trace!("Refreshing network pages…");
@@ -888,7 +1975,7 @@ impl MainWindow {
 
         // Filter hidden networks
         for data in network_data {
-            if data.is_virtual && !SETTINGS.show_virtual_network_interfaces() {
+            if Self::is_network_interface_hidden(data) {
                 let idx = paths
                     .iter()
                     .position(|p| **p == data.inner.sysfs_path)
@@ -907,6 +1994,7 @@ impl MainWindow {
                 );
 
                 let page = network_pages.remove(page_path).unwrap();
+                self.save_graph_histories(page_path, &page);
                 self.remove_page(&page);
             }
         }
@@ -930,6 +2018,7 @@ impl MainWindow {
                 // Insert stub page, values will be updated in refresh_page()
                 let page = ResNetwork::new();
                 page.init(network_interface, highest_secondary_ord);
+                self.restore_graph_histories(&path, page.upcast_ref());
 
                 self.add_page(
                     &page,
@@ -940,6 +2029,71 @@ impl MainWindow {
         }
     }
 
+    /// Create page for every InfiniBand port
+    fn refresh_infiniband_pages(&self, paths: Vec<PathBuf>, infiniband_data: &[InfinibandData]) {
+        trace!("Refreshing InfiniBand pages…");
+
+        let imp = self.imp();
+
+        let mut infiniband_pages = imp.infiniband_pages.borrow_mut();
+
+        let mut highest_secondary_ord = infiniband_pages
+            .values()
+            .filter_map(adw::ToolbarView::content)
+            .map(|widget| widget.property::<u32>("secondary_ord"))
+            .max()
+            .unwrap_or_default();
+
+        let old_page_paths: Vec<PathBuf> = infiniband_pages
+            .keys()
+            .map(std::borrow::ToOwned::to_owned)
+            .collect();
+
+        // Delete old InfiniBand pages
+        for page_path in &old_page_paths {
+            if !paths.contains(page_path) {
+                // An InfiniBand port has been removed
+                info!(
+                    "An InfiniBand port has been removed: {}",
+                    page_path.display()
+                );
+
+                let page = infiniband_pages.remove(page_path).unwrap();
+                self.save_graph_histories(page_path, &page);
+                self.remove_page(&page);
+            }
+        }
+
+        // Add new InfiniBand pages
+        for path in paths {
+            infiniband_pages.entry(path.clone()).or_insert_with(|| {
+                // An InfiniBand port has been added
+                info!("An InfiniBand port has been added: {}", path.display());
+
+                highest_secondary_ord = highest_secondary_ord.saturating_add(1);
+
+                let port = infiniband_data
+                    .iter()
+                    .find(|d| d.inner.sysfs_path == path)
+                    .unwrap();
+
+                // Insert stub page, values will be updated in refresh_page()
+                let page = ResInfiniband::new();
+                page.init(port, highest_secondary_ord);
+                self.restore_graph_histories(&path, page.upcast_ref());
+
+                self.add_page(
+                    &page,
+                    &port.display_name,
+                    &i18n_f(
+                        "{} Port {}",
+                        &[&port.inner.device_name, &port.inner.port_number],
+                    ),
+                )
+            });
+        }
+    }
+
     /// Create page for every battery that is shown
     fn refresh_battery_pages(&self, paths: Vec<PathBuf>, battery_data: &[BatteryData]) {
         trace!("Refreshing battery pages…");
@@ -967,6 +2121,7 @@ impl MainWindow {
                 info!("A battery has been removed: {}", page_path.display());
 
                 let page = battery_pages.remove(page_path).unwrap();
+                self.save_graph_histories(page_path, &page);
                 self.remove_page(&page);
             }
         }
@@ -987,6 +2142,7 @@ impl MainWindow {
                 // Insert stub page, values will be updated in refresh_page()
                 let page = ResBattery::new();
                 page.init(battery, highest_secondary_ord);
+                self.restore_graph_histories(&path, page.upcast_ref());
 
                 self.add_page(
                     &page,
@@ -1002,6 +2158,59 @@ impl MainWindow {
         }
     }
 
+    /// Create the page with the combined throughput of all network interfaces if it's enabled, remove it otherwise
+    fn refresh_network_total_page(&self, network_data: &[NetworkData]) {
+        let imp = self.imp();
+
+        let mut network_total_page = imp.network_total_page.borrow_mut();
+
+        if !SETTINGS.show_network_total() || ARGS.disable_network_interface_monitoring {
+            if let Some(page) = network_total_page.take() {
+                info!("The combined network page has been disabled");
+                self.remove_page(&page);
+            }
+            return;
+        }
+
+        let page = network_total_page.get_or_insert_with(|| {
+            info!("The combined network page has been enabled");
+
+            let page = ResNetworkTotal::new();
+            page.init();
+            let title = page.tab_name();
+            self.add_page(&page, &title, &title)
+        });
+
+        let page = page.content().and_downcast::<ResNetworkTotal>().unwrap();
+        page.refresh_page(network_data);
+    }
+
+    /// Create the custom gauges page if there are any gauges, remove it otherwise
+    fn refresh_custom_gauges_page(&self, custom_gauge_data: &[CustomGaugeData]) {
+        let imp = self.imp();
+
+        let mut custom_gauges_page = imp.custom_gauges_page.borrow_mut();
+
+        if custom_gauge_data.is_empty() {
+            if let Some(page) = custom_gauges_page.take() {
+                info!("All custom gauges have been removed");
+                self.remove_page(&page);
+            }
+            return;
+        }
+
+        let page = custom_gauges_page.get_or_insert_with(|| {
+            info!("Custom gauges have been added");
+
+            let page = ResCustomGauges::new();
+            let title = page.tab_name();
+            self.add_page(&page, &title, &title)
+        });
+
+        let page = page.content().and_downcast::<ResCustomGauges>().unwrap();
+        page.refresh_page(custom_gauge_data);
+    }
+
     fn process_action(&self, action: Action) {
         let apps_context = self.imp().apps_context.borrow();
         match action {
@@ -1065,9 +2274,11 @@ impl MainWindow {
                 toast_overlay.add_toast(Toast::new(&toast_message));
             }
 
-            Action::AdjustProcess(pid, niceness, affinity, display_name, toast_overlay) => {
+            Action::AdjustProcess(pid, niceness, affinity, limits, display_name, toast_overlay) => {
                 if let Some(process) = apps_context.get_process(pid) {
-                    let result = process.adjust(niceness, affinity);
+                    let result = process.adjust(niceness, affinity).and_then(|()| {
+                        limits.map_or(Ok(()), |limits| process.set_cgroup_limits(limits))
+                    });
 
                     let toast_message = match result {
                         Ok(()) => i18n_f("Successfully adjusted {}", &[&display_name]),
@@ -1076,6 +2287,71 @@ impl MainWindow {
                     toast_overlay.add_toast(Toast::new(&toast_message));
                 }
             }
+
+            Action::AdjustProcesses(pids, niceness, affinity, toast_overlay) => {
+                let result = pids
+                    .iter()
+                    .filter_map(|pid| apps_context.get_process(*pid))
+                    .map(|process| {
+                        process.adjust(
+                            niceness.unwrap_or(process.data.niceness),
+                            affinity.iter().copied(),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+
+                let processes_unsuccessful = result.iter().filter(|r| r.is_err()).count();
+
+                let toast_message = if processes_unsuccessful > 0 {
+                    ni18n_f(
+                        "There was a problem adjusting a process",
+                        "There were problems adjusting {} of {} processes",
+                        processes_unsuccessful as u32,
+                        &[
+                            &processes_unsuccessful.to_string(),
+                            &result.len().to_string(),
+                        ],
+                    )
+                } else {
+                    ni18n_f(
+                        "Successfully adjusted {} process",
+                        "Successfully adjusted {} processes",
+                        result.len() as u32,
+                        &[&result.len().to_string()],
+                    )
+                };
+
+                toast_overlay.add_toast(Toast::new(&toast_message));
+            }
+
+            Action::AdjustApp(id, niceness, cpu_threshold, affinity, toast_overlay) => {
+                if let Some(app) = apps_context.get_app(&Some(id)) {
+                    let result = app.adjust(&apps_context, niceness, cpu_threshold, &affinity);
+
+                    let processes_unsuccessful = result.iter().filter(|r| r.is_err()).count();
+
+                    let toast_message = if processes_unsuccessful > 0 {
+                        ni18n_f(
+                            "There was a problem adjusting a process",
+                            "There were problems adjusting {} of {} processes",
+                            processes_unsuccessful as u32,
+                            &[
+                                &processes_unsuccessful.to_string(),
+                                &result.len().to_string(),
+                            ],
+                        )
+                    } else {
+                        ni18n_f(
+                            "Successfully adjusted {} process of {}",
+                            "Successfully adjusted {} processes of {}",
+                            result.len() as u32,
+                            &[&result.len().to_string(), &app.display_name],
+                        )
+                    };
+
+                    toast_overlay.add_toast(Toast::new(&toast_message));
+                }
+            }
         };
     }
 
@@ -1102,6 +2378,139 @@ impl MainWindow {
         }
     }
 
+    /// Whether the window can be kept above others, which is the case on X11 with `wmctrl` available
+    pub fn always_on_top_supported() -> bool {
+        let is_x11 = gtk::gdk::Display::default()
+            .is_some_and(|display| display.type_().name() == "GdkX11Display");
+
+        let wmctrl_installed = if *IS_FLATPAK {
+            Path::new(PATH_WMCTRL_FLATPAK).exists()
+        } else {
+            glib::find_program_in_path("wmctrl").is_some()
+        };
+
+        is_x11 && wmctrl_installed
+    }
+
+    /// GTK 4 has no API for keeping a window above others, so this is done on a best effort basis by asking `wmctrl`
+    /// on X11. Wayland compositors don't let clients do this at all, users have to use their compositor's window
+    /// menu there.
+    fn apply_always_on_top(&self, always_on_top: bool) {
+        if !Self::always_on_top_supported() {
+            return;
+        }
+
+        MainContext::default().spawn_local(async move {
+            match Self::wmctrl_always_on_top(always_on_top).await {
+                Ok(()) => debug!("Set always on top to {always_on_top} using wmctrl"),
+                Err(err) => warn!("Unable to set always on top using wmctrl, {err}"),
+            }
+        });
+    }
+
+    /// Windows are looked up by the PID GTK has set on them since their titles aren't necessarily unique
+    async fn wmctrl_always_on_top(always_on_top: bool) -> Result<()> {
+        let list = Self::wmctrl(&["-l", "-p"], gio::SubprocessFlags::STDOUT_PIPE)?;
+        let (stdout, _) = list.communicate_utf8_future(None).await?;
+
+        if !list.is_successful() {
+            bail!("wmctrl exited with {}", list.exit_status());
+        }
+
+        let pid = std::process::id().to_string();
+
+        // every line consists of the window ID, the desktop, the PID, the host and the title
+        let window_ids = stdout
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| {
+                let mut columns = line.split_whitespace();
+                let window_id = columns.next()?;
+                (columns.nth(1)? == pid).then(|| window_id.to_string())
+            })
+            .collect::<Vec<_>>();
+
+        if window_ids.is_empty() {
+            bail!("no window with PID {pid} found");
+        }
+
+        let state = if always_on_top {
+            "add,above"
+        } else {
+            "remove,above"
+        };
+
+        for window_id in window_ids {
+            Self::wmctrl(
+                &["-i", "-r", &window_id, "-b", state],
+                gio::SubprocessFlags::NONE,
+            )?
+            .wait_check_future()
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    fn wmctrl(args: &[&str], flags: gio::SubprocessFlags) -> Result<gio::Subprocess> {
+        let mut argv = if *IS_FLATPAK {
+            vec![FLATPAK_SPAWN, "--host", "wmctrl"]
+        } else {
+            vec!["wmctrl"]
+        };
+        argv.extend_from_slice(args);
+
+        let argv = argv.iter().map(OsStr::new).collect::<Vec<_>>();
+
+        gio::Subprocess::newv(&argv, flags).context("unable to run wmctrl")
+    }
+
+    fn apply_tray_indicator(&self, show_tray_indicator: bool) {
+        let imp = self.imp();
+
+        if !show_tray_indicator {
+            if imp.status_notifier.take().is_some() {
+                debug!("Removed the tray indicator");
+            }
+            return;
+        }
+
+        if imp.status_notifier.borrow().is_some() {
+            return;
+        }
+
+        let Some(connection) = self.application().and_then(|app| app.dbus_connection()) else {
+            warn!("Unable to show the tray indicator, the application has no D-Bus connection");
+            return;
+        };
+
+        let status_notifier = StatusNotifier::register(
+            &connection,
+            clone!(
+                #[weak(rename_to = this)]
+                self,
+                move || this.present()
+            ),
+            clone!(
+                #[weak(rename_to = this)]
+                self,
+                move || {
+                    if let Some(app) = this.application() {
+                        app.activate_action("quit", None);
+                    }
+                }
+            ),
+        );
+
+        match status_notifier {
+            Ok(status_notifier) => {
+                debug!("Showing the tray indicator");
+                imp.status_notifier.replace(Some(status_notifier));
+            }
+            Err(err) => warn!("Unable to show the tray indicator, {err}"),
+        }
+    }
+
     fn add_page(
         &self,
         widget: &impl IsA<Widget>,
@@ -1131,14 +2540,120 @@ impl MainWindow {
         header_bar.set_title_widget(Some(&title_widget));
         header_bar.pack_start(&sidebar_button);
 
+        // pages of devices that can be renamed show the device's current name
+        if widget.has_property("device_id", None) {
+            widget
+                .bind_property("tab_name", &title_widget, "subtitle")
+                .sync_create()
+                .build();
+
+            let rename_button = gtk::Button::from_icon_name("document-edit-symbolic");
+            rename_button.set_tooltip_text(Some(&i18n("Rename")));
+            rename_button.set_action_name(Some("app.rename-page"));
+            header_bar.pack_end(&rename_button);
+        }
+
+        let copy_stats_button = gtk::Button::from_icon_name("edit-copy-symbolic");
+        copy_stats_button.set_tooltip_text(Some(&i18n("Copy All Stats")));
+        copy_stats_button.set_action_name(Some("app.copy-page-stats"));
+        header_bar.pack_end(&copy_stats_button);
+
+        if !Self::expander_rows(widget.upcast_ref()).is_empty() {
+            let expanders_button = gtk::Button::from_icon_name("pan-down-symbolic");
+            expanders_button.set_tooltip_text(Some(&i18n("Expand or Collapse All Sections")));
+            expanders_button.set_action_name(Some("app.toggle-expanders"));
+            header_bar.pack_end(&expanders_button);
+
+            self.setup_expanders(widget.upcast_ref());
+        }
+
         let toolbar = adw::ToolbarView::new();
         toolbar.add_top_bar(&header_bar);
         toolbar.set_content(Some(widget));
 
-        imp.content_stack.add_child(&toolbar);
+        imp.content_stack
+            .add_child(&toolbar)
+            .set_visible(Self::is_page_shown(widget.upcast_ref()));
 
         toolbar
     }
+
+    /// Leaves out the subsystems whose pages are all hidden, unless notifications depend on them
+    fn skip_hidden_subsystems(due: &mut DueSubsystems, has_gpus: bool) {
+        // GPU pages show the GPU usage of the processes, so the processes are needed for them too
+        due.processes &= SETTINGS.show_applications_page()
+            || SETTINGS.show_processes_page()
+            || (has_gpus && SETTINGS.show_gpu_pages());
+
+        due.drives &= SETTINGS.show_drive_pages()
+            || SETTINGS.drive_full_notification()
+            || SETTINGS.alert_drive_temperature_threshold() > 0;
+
+        due.network &= SETTINGS.show_network_pages();
+
+        // custom gauges have no setting of their own, they're shown as long as there are any
+        due.sensors &= SETTINGS.show_battery_pages()
+            || SETTINGS.alert_battery_threshold() > 0
+            || !SETTINGS.custom_gauges().is_empty();
+    }
+
+    /// Whether the page with the content `content` should be shown according to the settings
+    fn is_page_shown(content: &Widget) -> bool {
+        if content.is::<ResCPU>() {
+            SETTINGS.show_cpu_page()
+        } else if content.is::<ResMemory>() {
+            SETTINGS.show_memory_page()
+        } else if content.is::<ResGPU>() {
+            SETTINGS.show_gpu_pages()
+        } else if content.is::<ResNPU>() {
+            SETTINGS.show_npu_pages()
+        } else if content.is::<ResDrive>() {
+            SETTINGS.show_drive_pages()
+        } else if content.is::<ResNetwork>()
+            || content.is::<ResNetworkTotal>()
+            || content.is::<ResInfiniband>()
+        {
+            SETTINGS.show_network_pages()
+        } else if content.is::<ResBattery>() {
+            SETTINGS.show_battery_pages()
+        } else if content.is::<ResApplications>() {
+            SETTINGS.show_applications_page()
+        } else if content.is::<ResProcesses>() {
+            SETTINGS.show_processes_page()
+        } else if content.is::<ResOs>() {
+            SETTINGS.show_os_page()
+        } else {
+            true
+        }
+    }
+
+    /// Shows or hides every page according to the settings. Hidden pages are kept around, but their data isn't gathered
+    /// until they're shown again.
+    fn apply_page_visibility(&self) {
+        let imp = self.imp();
+
+        for page in imp.content_stack.pages().iter::<gtk::StackPage>().flatten() {
+            if let Some(content) = page
+                .child()
+                .downcast::<adw::ToolbarView>()
+                .ok()
+                .and_then(|toolbar| toolbar.content())
+            {
+                page.set_visible(Self::is_page_shown(&content));
+            }
+        }
+
+        // the stack switches to another page by itself if the visible one has been hidden, let the sidebar follow it
+        if let Some(content) = imp
+            .content_stack
+            .visible_child()
+            .and_downcast::<adw::ToolbarView>()
+            .and_then(|toolbar| toolbar.content())
+        {
+            imp.resources_sidebar
+                .set_selected_list_item_by_tab_id(content.property::<GString>("tab_id"));
+        }
+    }
 }
 
 impl Default for MainWindow {
@@ -0,0 +1,279 @@
+use std::{
+    collections::{HashMap, HashSet},
+    time::{Duration, Instant},
+};
+
+use super::settings::SETTINGS;
+
+/// The metrics that alert rules can watch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Metric {
+    /// Total processor usage in percent
+    ProcessorUsage,
+    /// Memory usage in percent
+    MemoryUsage,
+    /// Temperature of a drive in °C
+    DriveTemperature,
+    /// Charge of a battery in percent
+    BatteryCharge,
+}
+
+impl Metric {
+    fn id(self) -> &'static str {
+        match self {
+            Metric::ProcessorUsage => "processor-usage",
+            Metric::MemoryUsage => "memory-usage",
+            Metric::DriveTemperature => "drive-temperature",
+            Metric::BatteryCharge => "battery-charge",
+        }
+    }
+
+    /// Whether values below the threshold are alarming rather than values above it
+    pub fn alerts_below(self) -> bool {
+        matches!(self, Metric::BatteryCharge)
+    }
+}
+
+/// Alert once `metric` has been above (or below, see [`Metric::alerts_below`]) `threshold` for at least `duration`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rule {
+    pub metric: Metric,
+    pub threshold: f64,
+    pub duration: Duration,
+}
+
+impl Rule {
+    /// Returns the rules configured in the settings, a threshold of 0 disables the respective rule
+    pub fn from_settings() -> Vec<Rule> {
+        if !SETTINGS.alert_notifications() {
+            return Vec::new();
+        }
+
+        let duration = Duration::from_secs(SETTINGS.alert_duration().into());
+
+        [
+            (Metric::ProcessorUsage, SETTINGS.alert_processor_threshold()),
+            (Metric::MemoryUsage, SETTINGS.alert_memory_threshold()),
+            (
+                Metric::DriveTemperature,
+                SETTINGS.alert_drive_temperature_threshold(),
+            ),
+            (Metric::BatteryCharge, SETTINGS.alert_battery_threshold()),
+        ]
+        .into_iter()
+        .filter(|(_, threshold)| *threshold > 0)
+        .map(|(metric, threshold)| Rule {
+            metric,
+            threshold: threshold.into(),
+            duration,
+        })
+        .collect()
+    }
+
+    fn is_crossed(&self, value: f64) -> bool {
+        if self.metric.alerts_below() {
+            value < self.threshold
+        } else {
+            value > self.threshold
+        }
+    }
+}
+
+/// The current value of a metric of one device
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reading {
+    pub metric: Metric,
+    /// Tells apart devices of the same kind, e.g. multiple drives, empty for the processor and memory
+    pub device_id: String,
+    pub device_name: String,
+    pub value: f64,
+}
+
+impl Reading {
+    /// An ID that stays the same for all readings of this metric and device, suitable as a notification ID
+    pub fn alert_id(&self) -> String {
+        if self.device_id.is_empty() {
+            format!("alert-{}", self.metric.id())
+        } else {
+            format!("alert-{}-{}", self.metric.id(), self.device_id)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AlertEvent {
+    /// The threshold of `rule` has been crossed by `reading` for long enough
+    Raised { reading: Reading, rule: Rule },
+    /// The alert with the ID `alert_id` is no longer valid, either because the value is back within the threshold or
+    /// because the device or the rule is gone
+    Resolved { alert_id: String },
+}
+
+/// Keeps track of how long the thresholds of the alert rules have been crossed for
+#[derive(Debug, Default)]
+pub struct Alerts {
+    /// Since when the threshold of an alert has been crossed continuously, by alert ID
+    crossed_since: HashMap<String, Instant>,
+    /// IDs of the alerts that have been raised and not been resolved yet
+    raised: HashSet<String>,
+}
+
+impl Alerts {
+    /// Checks `readings` against `rules` and returns the alerts that are to be raised or resolved at `now`. Every alert
+    /// is only raised once until it's resolved again.
+    pub fn check(&mut self, rules: &[Rule], readings: &[Reading], now: Instant) -> Vec<AlertEvent> {
+        let mut events = Vec::new();
+        let mut crossed_since = HashMap::new();
+
+        for reading in readings {
+            let Some(rule) = rules.iter().find(|rule| rule.metric == reading.metric) else {
+                continue;
+            };
+
+            if !rule.is_crossed(reading.value) {
+                continue;
+            }
+
+            let alert_id = reading.alert_id();
+            let since = self.crossed_since.get(&alert_id).copied().unwrap_or(now);
+
+            if now.duration_since(since) >= rule.duration && self.raised.insert(alert_id.clone()) {
+                events.push(AlertEvent::Raised {
+                    reading: reading.clone(),
+                    rule: *rule,
+                });
+            }
+
+            crossed_since.insert(alert_id, since);
+        }
+
+        self.raised.retain(|alert_id| {
+            let still_crossed = crossed_since.contains_key(alert_id);
+            if !still_crossed {
+                events.push(AlertEvent::Resolved {
+                    alert_id: alert_id.clone(),
+                });
+            }
+            still_crossed
+        });
+
+        self.crossed_since = crossed_since;
+
+        events
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use pretty_assertions::assert_eq;
+
+    use super::{AlertEvent, Alerts, Metric, Reading, Rule};
+
+    const PROCESSOR_RULE: Rule = Rule {
+        metric: Metric::ProcessorUsage,
+        threshold: 90.0,
+        duration: Duration::from_secs(60),
+    };
+
+    const BATTERY_RULE: Rule = Rule {
+        metric: Metric::BatteryCharge,
+        threshold: 15.0,
+        duration: Duration::ZERO,
+    };
+
+    fn reading(metric: Metric, device_id: &str, value: f64) -> Reading {
+        Reading {
+            metric,
+            device_id: device_id.to_string(),
+            device_name: String::new(),
+            value,
+        }
+    }
+
+    #[test]
+    fn raised_after_duration() {
+        let mut alerts = Alerts::default();
+        let start = Instant::now();
+        let high = [reading(Metric::ProcessorUsage, "", 95.0)];
+
+        assert_eq!(
+            Vec::<AlertEvent>::new(),
+            alerts.check(&[PROCESSOR_RULE], &high, start)
+        );
+        assert_eq!(
+            Vec::<AlertEvent>::new(),
+            alerts.check(&[PROCESSOR_RULE], &high, start + Duration::from_secs(30))
+        );
+        assert_eq!(
+            vec![AlertEvent::Raised {
+                reading: high[0].clone(),
+                rule: PROCESSOR_RULE
+            }],
+            alerts.check(&[PROCESSOR_RULE], &high, start + Duration::from_secs(60))
+        );
+
+        // only raised once
+        assert_eq!(
+            Vec::<AlertEvent>::new(),
+            alerts.check(&[PROCESSOR_RULE], &high, start + Duration::from_secs(90))
+        );
+
+        assert_eq!(
+            vec![AlertEvent::Resolved {
+                alert_id: "alert-processor-usage".to_string()
+            }],
+            alerts.check(
+                &[PROCESSOR_RULE],
+                &[reading(Metric::ProcessorUsage, "", 50.0)],
+                start + Duration::from_secs(120)
+            )
+        );
+    }
+
+    #[test]
+    fn interruption_restarts_duration() {
+        let mut alerts = Alerts::default();
+        let start = Instant::now();
+        let high = [reading(Metric::ProcessorUsage, "", 95.0)];
+        let low = [reading(Metric::ProcessorUsage, "", 50.0)];
+
+        alerts.check(&[PROCESSOR_RULE], &high, start);
+        alerts.check(&[PROCESSOR_RULE], &low, start + Duration::from_secs(30));
+        alerts.check(&[PROCESSOR_RULE], &high, start + Duration::from_secs(40));
+
+        assert_eq!(
+            Vec::<AlertEvent>::new(),
+            alerts.check(&[PROCESSOR_RULE], &high, start + Duration::from_secs(60))
+        );
+    }
+
+    #[test]
+    fn below_threshold_per_device() {
+        let mut alerts = Alerts::default();
+        let now = Instant::now();
+        let readings = [
+            reading(Metric::BatteryCharge, "BAT0", 10.0),
+            reading(Metric::BatteryCharge, "BAT1", 80.0),
+            reading(Metric::ProcessorUsage, "", 100.0),
+        ];
+
+        assert_eq!(
+            vec![AlertEvent::Raised {
+                reading: readings[0].clone(),
+                rule: BATTERY_RULE
+            }],
+            alerts.check(&[BATTERY_RULE], &readings, now)
+        );
+        assert_eq!("alert-battery-charge-BAT0", readings[0].alert_id());
+
+        // removing the rule resolves its alerts
+        assert_eq!(
+            vec![AlertEvent::Resolved {
+                alert_id: "alert-battery-charge-BAT0".to_string()
+            }],
+            alerts.check(&[], &readings, now)
+        );
+    }
+}
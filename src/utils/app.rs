@@ -1,6 +1,9 @@
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
+    ffi::OsStr,
+    os::unix::fs::MetadataExt,
     path::{Path, PathBuf},
+    process::Command,
     sync::LazyLock,
     time::Instant,
 };
@@ -12,20 +15,31 @@ use gtk::{
 };
 use lazy_regex::{lazy_regex, Lazy, Regex};
 use log::{debug, info, trace};
-use process_data::{Containerization, GpuIdentifier, ProcessData};
+use process_data::{Container, Containerization, GpuIdentifier, Niceness, ProcessData};
 
-use crate::i18n::i18n;
+use crate::i18n::{i18n, i18n_f};
 
 use super::{
     boot_time,
     process::{Process, ProcessAction},
-    FiniteOr,
+    FLATPAK_SPAWN, IS_FLATPAK,
 };
 
 /// This contains the cgroups of desktop environments. If a process has this as its cgroup, its parent's cgroup will be
 /// considered instead to enhance app detection
 const DESKTOP_ENVIRONMENT_CGROUPS: &[&str] = &["org.gnome.Shell"];
 
+/// Prefix of the IDs of apps that don't come from a desktop file but from a running AppImage
+const APPIMAGE_ID_PREFIX: &str = "appimage:";
+
+/// Prefix of the IDs of apps that represent a Docker, Podman or LXC container
+const CONTAINER_ID_PREFIX: &str = "container:";
+
+/// Parts of AppImage file names that denote the architecture rather than being part of the app's name
+const APPIMAGE_ARCHITECTURES: &[&str] = &[
+    "x86", "x64", "amd64", "i386", "i686", "aarch64", "arm64", "armhf", "linux",
+];
+
 // This contains executable names that are blocklisted from being recognized as applications
 const DESKTOP_EXEC_BLOCKLIST: &[&str] = &["bash", "zsh", "fish", "sh", "ksh", "flatpak"];
 
@@ -75,10 +89,36 @@ static APP_ID_BLOCKLIST: LazyLock<HashMap<&'static str, &'static str>> = LazyLoc
     ])
 });
 
+// Main categories as defined by the freedesktop.org Desktop Menu Specification, in the order in which they're
+// preferred if a desktop file lists more than one of them
+const MAIN_CATEGORIES: &[&str] = &[
+    "AudioVideo",
+    "Audio",
+    "Video",
+    "Development",
+    "Education",
+    "Game",
+    "Graphics",
+    "Network",
+    "Office",
+    "Science",
+    "Settings",
+    "System",
+    "Utility",
+];
+
 static RE_ENV_FILTER: Lazy<Regex> = lazy_regex!(r"env\s*\S*=\S*\s*(.*)");
 
 static RE_FLATPAK_FILTER: Lazy<Regex> = lazy_regex!(r"flatpak run .* --command=(\S*)");
 
+static RE_SNAP_VERSION: Lazy<Regex> =
+    lazy_regex!(r#"(?m)^version:[ \t]*['"]?([^'"\n]*?)['"]?[ \t]*$"#);
+
+const PATH_SNAPS: &str = "/snap";
+
+/// Where snapd keeps the compressed images of the installed Snap revisions
+const PATH_SNAPD_SNAPS: &str = "/var/lib/snapd/snaps";
+
 fn format_path(path: &str) -> String {
     if path.starts_with("~/") {
         // $HOME may not include a trailing /, so we must not remove the extra trailing /
@@ -104,6 +144,20 @@ pub static DATA_DIRS: LazyLock<Vec<PathBuf>> = LazyLock::new(|| {
     data_dirs
 });
 
+// Directories in which autostart desktop entries may be found, ordered by precedence (i. e. the user's own entries
+// override system-wide ones with the same file name)
+static AUTOSTART_DIRS: LazyLock<Vec<PathBuf>> = LazyLock::new(|| {
+    let mut autostart_dirs = vec![PathBuf::from(format_path("~/.config/autostart"))];
+    autostart_dirs.extend(
+        std::env::var("XDG_CONFIG_DIRS")
+            .unwrap_or_else(|_| "/etc/xdg".to_string())
+            .split(':')
+            .map(format_path)
+            .map(|path| PathBuf::from(path).join("autostart")),
+    );
+    autostart_dirs
+});
+
 // This contains known occurrences of processes having a too distinct name from the actual app
 // The HashMap is used like this:
 //   Key: The name of the executable of the process
@@ -164,6 +218,56 @@ pub struct AppsContext {
     apps: HashMap<Option<String>, App>,
     processes: HashMap<i32, Process>,
     gpus_with_combined_media_engine: Vec<GpuIdentifier>,
+    /// The names under which the GPUs are shown in the sidebar, e.g. "GPU 2"
+    gpu_labels: HashMap<GpuIdentifier, String>,
+}
+
+/// Details about how a containerized app has been packaged
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PackageInfo {
+    Flatpak {
+        branch: String,
+        /// The runtime's ref, e.g. `org.gnome.Platform/x86_64/47`
+        runtime: Option<String>,
+    },
+    Snap {
+        revision: String,
+        version: Option<String>,
+    },
+}
+
+/// The files and directories an app takes up on the disk
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AppFootprint {
+    /// The app and its data are located at these paths, e.g. a Flatpak's deployment and its directory in `~/.var/app`
+    Paths(Vec<PathBuf>),
+    /// The app has been installed using the system's package manager, the package is the one owning this file
+    Package(PathBuf),
+}
+
+impl AppFootprint {
+    /// Returns how many bytes the app occupies on the disk or `None` if that couldn't be determined.
+    ///
+    /// This walks through potentially large directories and asks the package manager, so it should not be called on
+    /// the main thread.
+    pub fn disk_usage(&self) -> Option<u64> {
+        match self {
+            AppFootprint::Paths(paths) => {
+                let mut seen_inodes = HashSet::new();
+                let usages: Vec<u64> = paths
+                    .iter()
+                    .filter_map(|path| disk_usage_of_path(path, &mut seen_inodes).ok())
+                    .collect();
+
+                if usages.is_empty() {
+                    None
+                } else {
+                    Some(usages.into_iter().sum())
+                }
+            }
+            AppFootprint::Package(owned_file) => package_size(owned_file),
+        }
+    }
 }
 
 /// Represents an application installed on the system. It doesn't
@@ -180,6 +284,12 @@ pub struct App {
     pub read_bytes_from_dead_processes: u64,
     pub write_bytes_from_dead_processes: u64,
     pub containerization: Containerization,
+    /// The container this app represents, its processes are all the processes running in it
+    pub container: Option<Container>,
+    pub package_info: Option<PackageInfo>,
+    pub footprint: Option<AppFootprint>,
+    pub category: Option<String>,
+    pub autostart: bool,
 }
 
 impl App {
@@ -212,6 +322,16 @@ impl App {
             .flatten()
             .collect();
 
+        let (autostart_ids, autostart_executables) = Self::autostart_entries();
+
+        for app in &mut apps {
+            app.autostart = app.id.as_ref().is_some_and(|id| autostart_ids.contains(id))
+                || app
+                    .executable_name
+                    .as_ref()
+                    .is_some_and(|executable_name| autostart_executables.contains(executable_name));
+        }
+
         let elapsed = start.elapsed();
 
         info!("Detected {} apps within {elapsed:.2?}", apps.len());
@@ -227,11 +347,79 @@ impl App {
             read_bytes_from_dead_processes: 0,
             write_bytes_from_dead_processes: 0,
             containerization: Containerization::None,
+            container: None,
+            package_info: None,
+            footprint: None,
+            category: None,
+            autostart: false,
         });
 
         apps
     }
 
+    /// Returns the IDs and executable names of all enabled autostart
+    /// entries found in `AUTOSTART_DIRS`
+    fn autostart_entries() -> (HashSet<String>, HashSet<String>) {
+        let mut seen_entries = HashSet::new();
+        let mut ids = HashSet::new();
+        let mut executables = HashSet::new();
+
+        for path in AUTOSTART_DIRS
+            .iter()
+            .filter_map(|dir| dir.read_dir().ok())
+            .flatten()
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        {
+            let Some(id) = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+            else {
+                continue;
+            };
+
+            // entries with the same name in directories of lower precedence are shadowed
+            if !seen_entries.insert(id.clone()) {
+                continue;
+            }
+
+            let Ok(ini) = ini::Ini::load_from_file(&path) else {
+                continue;
+            };
+
+            let Some(desktop_entry) = ini.section(Some("Desktop Entry")) else {
+                continue;
+            };
+
+            // GNOME disables autostart entries through its own key instead of hiding them
+            if desktop_entry
+                .get("Hidden")
+                .is_some_and(|hidden| hidden.trim() == "true")
+                || desktop_entry
+                    .get("X-GNOME-Autostart-enabled")
+                    .is_some_and(|enabled| enabled.trim() == "false")
+            {
+                trace!("Autostart entry {path:?} is disabled");
+                continue;
+            }
+
+            trace!("Found enabled autostart entry {path:?}");
+
+            if let Some(executable_name) = desktop_entry.get("Exec").and_then(|exec| {
+                exec.split(' ')
+                    .next()
+                    .and_then(|executable| executable.split('/').next_back())
+            }) {
+                executables.insert(executable_name.to_string());
+            }
+
+            ids.insert(id);
+        }
+
+        debug!("Found {} enabled autostart entries", ids.len());
+
+        (ids, executables)
+    }
+
     pub fn from_desktop_file<P: AsRef<Path>>(file_path: P) -> Result<App> {
         let file_path = file_path.as_ref();
         trace!("Reading {file_path:?}…");
@@ -328,6 +516,14 @@ impl App {
             .or_else(|| desktop_entry.get("Comment"))
             .map(str::to_string);
 
+        let category = desktop_entry.get("Categories").and_then(|categories| {
+            let categories: Vec<&str> = categories.split(';').map(str::trim).collect();
+            MAIN_CATEGORIES
+                .iter()
+                .find(|main_category| categories.contains(main_category))
+                .map(|main_category| main_category.to_string())
+        });
+
         let is_snap = desktop_entry.get("X-SnapInstanceName").is_some();
 
         let containerization = if is_flatpak {
@@ -356,6 +552,21 @@ impl App {
             Containerization::None
         };
 
+        let package_info = match containerization {
+            Containerization::Flatpak => Self::flatpak_info(file_path),
+            Containerization::Snap => Self::snap_info(&id),
+            Containerization::None => None,
+        };
+
+        let footprint = match (containerization, &package_info) {
+            (Containerization::Flatpak, _) => Self::flatpak_footprint(file_path, &id),
+            (Containerization::Snap, Some(PackageInfo::Snap { revision, .. })) => {
+                Some(Self::snap_footprint(&id, revision))
+            }
+            (Containerization::Snap, _) => None,
+            (Containerization::None, _) => Some(AppFootprint::Package(file_path.to_path_buf())),
+        };
+
         let id = Some(id);
 
         Ok(App {
@@ -369,14 +580,232 @@ impl App {
             read_bytes_from_dead_processes: 0,
             write_bytes_from_dead_processes: 0,
             containerization,
+            container: None,
+            package_info,
+            footprint,
+            category,
+            autostart: false,
         })
     }
 
+    /// Reads the branch and runtime of the Flatpak app whose exported desktop file is at `desktop_file_path`.
+    ///
+    /// Exported desktop files are symlinks into the app's deployment directory
+    /// (`<installation>/app/<id>/<arch>/<branch>/<commit>/export/share/applications/<id>.desktop`), which also
+    /// contains the app's metadata.
+    fn flatpak_info<P: AsRef<Path>>(desktop_file_path: P) -> Option<PackageInfo> {
+        let deploy_dir = Self::flatpak_deploy_dir(desktop_file_path)?;
+
+        let branch = deploy_dir
+            .parent()?
+            .file_name()?
+            .to_string_lossy()
+            .to_string();
+
+        let runtime = std::fs::read_to_string(deploy_dir.join("metadata"))
+            .ok()
+            .and_then(Self::flatpak_runtime);
+
+        Some(PackageInfo::Flatpak { branch, runtime })
+    }
+
+    /// Returns the deployment directory of the Flatpak app whose exported desktop file is at `desktop_file_path`
+    fn flatpak_deploy_dir<P: AsRef<Path>>(desktop_file_path: P) -> Option<PathBuf> {
+        std::fs::canonicalize(desktop_file_path)
+            .ok()?
+            .ancestors()
+            .nth(4)
+            .map(Path::to_path_buf)
+    }
+
+    /// The Flatpak app `id` takes up its deployment directory and its data directory in `~/.var/app`
+    fn flatpak_footprint<P: AsRef<Path>, S: AsRef<str>>(
+        desktop_file_path: P,
+        id: S,
+    ) -> Option<AppFootprint> {
+        let deploy_dir = Self::flatpak_deploy_dir(desktop_file_path)?;
+        let data_dir = PathBuf::from(format_path("~/.var/app")).join(id.as_ref());
+
+        Some(AppFootprint::Paths(vec![deploy_dir, data_dir]))
+    }
+
+    /// The Snap `snap_name` takes up the image of its current revision as well as its user and system data
+    fn snap_footprint<S: AsRef<str>>(snap_name: S, revision: S) -> AppFootprint {
+        let snap_name = snap_name.as_ref();
+
+        AppFootprint::Paths(vec![
+            Path::new(PATH_SNAPD_SNAPS).join(format!("{snap_name}_{}.snap", revision.as_ref())),
+            PathBuf::from(format_path("~/snap")).join(snap_name),
+            Path::new("/var/snap").join(snap_name),
+        ])
+    }
+
+    /// Returns the runtime the Flatpak app with the metadata `metadata` uses
+    fn flatpak_runtime<S: AsRef<str>>(metadata: S) -> Option<String> {
+        ini::Ini::load_from_str(metadata.as_ref())
+            .ok()?
+            .section(Some("Application"))?
+            .get("runtime")
+            .map(str::to_string)
+    }
+
+    /// Reads the currently active revision and the version of the Snap `snap_name`
+    fn snap_info<S: AsRef<str>>(snap_name: S) -> Option<PackageInfo> {
+        let current = Path::new(PATH_SNAPS)
+            .join(snap_name.as_ref())
+            .join("current");
+
+        let revision = std::fs::read_link(&current)
+            .ok()?
+            .file_name()?
+            .to_string_lossy()
+            .to_string();
+
+        let version = std::fs::read_to_string(current.join("meta/snap.yaml"))
+            .ok()
+            .and_then(Self::snap_version);
+
+        Some(PackageInfo::Snap { revision, version })
+    }
+
+    /// Returns the version stated in the Snap's `snap.yaml`
+    fn snap_version<S: AsRef<str>>(snap_yaml: S) -> Option<String> {
+        RE_SNAP_VERSION
+            .captures(snap_yaml.as_ref())
+            .and_then(|captures| captures.get(1))
+            .map(|capture| capture.as_str().to_string())
+            .filter(|version| !version.is_empty())
+    }
+
+    /// Returns an `App` for the AppImage at `appimage_path`, for AppImages that aren't integrated using a desktop file
+    pub fn from_appimage<S: AsRef<str>>(appimage_path: S) -> App {
+        let appimage_path = appimage_path.as_ref();
+
+        let display_name = Self::appimage_name(appimage_path);
+
+        debug!("Found AppImage \"{display_name}\" at {appimage_path}");
+
+        App {
+            processes: Vec::new(),
+            commandline: Some(appimage_path.to_string()),
+            executable_name: None,
+            display_name,
+            description: Some(appimage_path.to_string()),
+            icon: ThemedIcon::new("generic-process").into(),
+            id: Some(format!("{APPIMAGE_ID_PREFIX}{appimage_path}")),
+            read_bytes_from_dead_processes: 0,
+            write_bytes_from_dead_processes: 0,
+            containerization: Containerization::None,
+            container: None,
+            package_info: None,
+            footprint: Some(AppFootprint::Paths(vec![PathBuf::from(appimage_path)])),
+            category: None,
+            autostart: false,
+        }
+    }
+
+    /// Returns an `App` that groups the processes running in `container`
+    pub fn from_container(container: &Container) -> App {
+        debug!(
+            "Found {} container \"{}\" (ID: {})",
+            container.runtime,
+            container.display_name(),
+            container.id
+        );
+
+        App {
+            processes: Vec::new(),
+            commandline: None,
+            executable_name: None,
+            display_name: container.display_name().to_string(),
+            description: Some(i18n_f(
+                "{} container {}",
+                &[&container.runtime.to_string(), container.short_id()],
+            )),
+            icon: ThemedIcon::new("generic-process").into(),
+            id: Some(format!(
+                "{CONTAINER_ID_PREFIX}{}:{}",
+                container.runtime, container.id
+            )),
+            read_bytes_from_dead_processes: 0,
+            write_bytes_from_dead_processes: 0,
+            containerization: Containerization::None,
+            container: Some(container.clone()),
+            package_info: None,
+            footprint: None,
+            category: None,
+            autostart: false,
+        }
+    }
+
+    /// Turns the path of an AppImage like "/opt/Krita-5.2.2-x86_64.AppImage" into a friendlier name like "Krita" by
+    /// leaving out the extension as well as the version and architecture that usually follow the name
+    fn appimage_name<S: AsRef<str>>(appimage_path: S) -> String {
+        let appimage_path = appimage_path.as_ref();
+
+        let file_name = appimage_path.rsplit('/').next().unwrap_or(appimage_path);
+
+        let stem = file_name
+            .len()
+            .checked_sub(".appimage".len())
+            .and_then(|extension_start| file_name.split_at_checked(extension_start))
+            .filter(|(_, extension)| extension.eq_ignore_ascii_case(".appimage"))
+            .map_or(file_name, |(stem, _)| stem);
+
+        let mut name_end = stem.len();
+        let mut part_start = 0;
+
+        // the first part is always kept so that there's something left of the name
+        for part in stem.split(['-', '_']) {
+            let is_version = part
+                .strip_prefix(['v', 'V'])
+                .unwrap_or(part)
+                .starts_with(|c: char| c.is_ascii_digit());
+            let is_architecture = APPIMAGE_ARCHITECTURES.contains(&part.to_lowercase().as_str());
+
+            if part_start > 0 && (is_version || is_architecture) {
+                name_end = part_start - 1;
+                break;
+            }
+
+            part_start += part.len() + 1;
+        }
+
+        stem[..name_end].to_string()
+    }
+
+    /// Returns a human-readable name of the app's main category or
+    /// "Other" if the app has none
+    pub fn category_name(&self) -> String {
+        if self.container.is_some() {
+            return i18n("Containers");
+        }
+
+        match self.category.as_deref() {
+            Some("AudioVideo") => i18n("Multimedia"),
+            Some("Audio") => i18n("Audio"),
+            Some("Video") => i18n("Video"),
+            Some("Development") => i18n("Development"),
+            Some("Education") => i18n("Education"),
+            Some("Game") => i18n("Games"),
+            Some("Graphics") => i18n("Graphics"),
+            Some("Network") => i18n("Internet"),
+            Some("Office") => i18n("Office"),
+            Some("Science") => i18n("Science"),
+            Some("Settings") => i18n("Settings"),
+            Some("System") => i18n("System"),
+            Some("Utility") => i18n("Utilities"),
+            _ => i18n("Other"),
+        }
+    }
+
     /// Adds a process to the processes `HashMap` and also
-    /// updates the `Process`' icon to the one of this
-    /// `App`
+    /// updates the `Process`' icon and app name to the ones
+    /// of this `App`
     pub fn add_process(&mut self, process: &mut Process) {
         process.icon = self.icon.clone();
+        // "System Processes" is not a name a process should be shown with
+        process.app_name = self.id.as_ref().map(|_| self.display_name.clone());
         self.processes.push(process.data.pid);
     }
 
@@ -461,6 +890,20 @@ impl App {
         self.processes_iter(apps).map(Process::gpu_usage).sum()
     }
 
+    /// Returns the usage of every GPU used by the app's processes, summed up per GPU
+    #[must_use]
+    pub fn gpu_usages(&self, apps: &AppsContext) -> BTreeMap<GpuIdentifier, f32> {
+        let mut gpu_usages = BTreeMap::new();
+
+        for process in self.processes_iter(apps) {
+            for (gpu_identifier, usage) in process.gpu_usages() {
+                *gpu_usages.entry(gpu_identifier).or_default() += usage;
+            }
+        }
+
+        gpu_usages
+    }
+
     #[must_use]
     pub fn enc_usage(&self, apps: &AppsContext) -> f32 {
         self.processes_iter(apps).map(Process::enc_usage).sum()
@@ -476,6 +919,24 @@ impl App {
         self.processes_iter(apps).map(Process::gpu_mem_usage).sum()
     }
 
+    /// Returns the GPU this app mainly renders on, see `dominant_gpu()`
+    #[must_use]
+    pub fn rendering_gpu(&self, apps: &AppsContext) -> Option<GpuIdentifier> {
+        dominant_gpu(self.processes_iter(apps).flat_map(|process| {
+            process
+                .data
+                .gpu_usage_stats
+                .keys()
+                .map(move |gpu_identifier| {
+                    (
+                        *gpu_identifier,
+                        process.gpu_usage_of(gpu_identifier).unwrap_or_default(),
+                        process.gpu_mem_usage_of(gpu_identifier).unwrap_or_default(),
+                    )
+                })
+        }))
+    }
+
     #[must_use]
     pub fn starttime(&self, apps: &AppsContext) -> f64 {
         self.processes_iter(apps)
@@ -494,6 +955,52 @@ impl App {
             .collect()
     }
 
+    /// Returns which logical CPUs at least one of the processes of this app is allowed to run on
+    #[must_use]
+    pub fn affinity(&self, apps: &AppsContext) -> Vec<bool> {
+        Self::combined_affinity(
+            self.processes_iter(apps)
+                .map(|process| process.data.affinity.as_slice()),
+        )
+    }
+
+    /// Returns which logical CPUs at least one of `affinities` allows to run on
+    pub fn combined_affinity<'a, I: IntoIterator<Item = &'a [bool]>>(affinities: I) -> Vec<bool> {
+        affinities
+            .into_iter()
+            .fold(Vec::new(), |mut combined, affinity| {
+                if combined.len() < affinity.len() {
+                    combined.resize(affinity.len(), false);
+                }
+
+                for (combined_cpu, cpu) in combined.iter_mut().zip(affinity) {
+                    *combined_cpu |= cpu;
+                }
+
+                combined
+            })
+    }
+
+    /// Applies `affinity` to every process of this app and, if given, `niceness` to those whose CPU time ratio is at
+    /// least `cpu_threshold`. All other processes keep their current niceness.
+    pub fn adjust(
+        &self,
+        apps: &AppsContext,
+        niceness: Option<Niceness>,
+        cpu_threshold: f32,
+        affinity: &[bool],
+    ) -> Vec<Result<()>> {
+        self.processes_iter(apps)
+            .map(|process| {
+                let niceness = niceness
+                    .filter(|_| process.cpu_time_ratio() >= cpu_threshold)
+                    .unwrap_or(process.data.niceness);
+
+                process.adjust(niceness, affinity.iter().copied())
+            })
+            .collect()
+    }
+
     pub fn running_since(&self, apps: &AppsContext) -> Result<GString> {
         boot_time()
             .and_then(|boot_time| {
@@ -513,7 +1020,10 @@ impl AppsContext {
     /// Creates a new `AppsContext` object, this operation is quite expensive
     /// so try to do it only one time during the lifetime of the program.
     /// Please call `refresh()` immediately after this function.
-    pub fn new(gpus_with_combined_media_engine: Vec<GpuIdentifier>) -> AppsContext {
+    pub fn new(
+        gpus_with_combined_media_engine: Vec<GpuIdentifier>,
+        gpu_labels: HashMap<GpuIdentifier, String>,
+    ) -> AppsContext {
         let apps: HashMap<Option<String>, App> = App::all()
             .into_iter()
             .map(|app| (app.id.clone(), app))
@@ -523,119 +1033,35 @@ impl AppsContext {
             apps,
             processes: HashMap::new(),
             gpus_with_combined_media_engine,
+            gpu_labels,
         }
     }
 
+    /// Returns the name under which the GPU `gpu_identifier` is shown to the user
+    pub fn gpu_label(&self, gpu_identifier: &GpuIdentifier) -> String {
+        self.gpu_labels
+            .get(gpu_identifier)
+            .cloned()
+            .unwrap_or_else(|| gpu_identifier.to_string())
+    }
+
     pub fn gpu_fraction(&self, gpu_identifier: GpuIdentifier) -> f32 {
         self.processes_iter()
-            .map(|process| {
-                (
-                    &process.data.gpu_usage_stats,
-                    &process.gpu_usage_stats_last,
-                    process.data.timestamp,
-                    process.timestamp_last,
-                )
-            })
-            .map(|(new, old, timestamp, timestamp_last)| {
-                (
-                    new.get(&gpu_identifier),
-                    old.get(&gpu_identifier),
-                    timestamp,
-                    timestamp_last,
-                )
-            })
-            .filter_map(|(new, old, timestamp, timestamp_last)| match (new, old) {
-                (Some(new), Some(old)) => Some((new, old, timestamp, timestamp_last)),
-                _ => None,
-            })
-            .map(|(new, old, timestamp, timestamp_last)| {
-                if new.nvidia {
-                    new.gfx as f32 / 100.0
-                } else if old.gfx == 0 {
-                    0.0
-                } else {
-                    ((new.gfx.saturating_sub(old.gfx) as f32)
-                        / (timestamp.saturating_sub(timestamp_last) as f32))
-                        .finite_or_default()
-                        / 1_000_000.0
-                }
-            })
+            .filter_map(|process| process.gpu_usage_of(&gpu_identifier))
             .sum::<f32>()
             .clamp(0.0, 1.0)
     }
 
     pub fn encoder_fraction(&self, gpu_identifier: GpuIdentifier) -> f32 {
         self.processes_iter()
-            .map(|process| {
-                (
-                    &process.data.gpu_usage_stats,
-                    &process.gpu_usage_stats_last,
-                    process.data.timestamp,
-                    process.timestamp_last,
-                )
-            })
-            .map(|(new, old, timestamp, timestamp_last)| {
-                (
-                    new.get(&gpu_identifier),
-                    old.get(&gpu_identifier),
-                    timestamp,
-                    timestamp_last,
-                )
-            })
-            .filter_map(|(new, old, timestamp, timestamp_last)| match (new, old) {
-                (Some(new), Some(old)) => Some((new, old, timestamp, timestamp_last)),
-                _ => None,
-            })
-            .map(|(new, old, timestamp, timestamp_last)| {
-                if new.nvidia {
-                    new.enc as f32 / 100.0
-                } else if old.enc == 0 {
-                    0.0
-                } else {
-                    ((new.enc.saturating_sub(old.enc) as f32)
-                        / (timestamp.saturating_sub(timestamp_last) as f32))
-                        .finite_or_default()
-                        / 1_000_000.0
-                }
-            })
+            .filter_map(|process| process.enc_usage_of(&gpu_identifier))
             .sum::<f32>()
             .clamp(0.0, 1.0)
     }
 
     pub fn decoder_fraction(&self, gpu_identifier: GpuIdentifier) -> f32 {
         self.processes_iter()
-            .map(|process| {
-                (
-                    &process.data.gpu_usage_stats,
-                    &process.gpu_usage_stats_last,
-                    process.data.timestamp,
-                    process.timestamp_last,
-                )
-            })
-            .map(|(new, old, timestamp, timestamp_last)| {
-                (
-                    new.get(&gpu_identifier),
-                    old.get(&gpu_identifier),
-                    timestamp,
-                    timestamp_last,
-                )
-            })
-            .filter_map(|(new, old, timestamp, timestamp_last)| match (new, old) {
-                (Some(new), Some(old)) => Some((new, old, timestamp, timestamp_last)),
-                _ => None,
-            })
-            .map(|(new, old, timestamp, timestamp_last)| {
-                if new.nvidia {
-                    new.dec as f32 / 100.0
-                } else if old.dec == 0 {
-                    0.0
-                } else {
-                    ((new.dec.saturating_sub(old.dec) as f32)
-                        / (timestamp.saturating_sub(timestamp_last) as f32))
-                        .finite_or_default()
-                        / 1_000_000.0
-                }
-            })
+            .filter_map(|process| process.dec_usage_of(&gpu_identifier))
             .sum::<f32>()
             .clamp(0.0, 1.0)
     }
@@ -728,6 +1154,49 @@ impl AppsContext {
         }
     }
 
+    /// Returns the ID of the app that represents the AppImage `process` has been started from (if any), adding the app
+    /// if it doesn't exist yet
+    fn appimage_app_id(&mut self, process: &Process) -> Option<String> {
+        let appimage = process.data.appimage.as_ref()?;
+
+        let id = Some(format!("{APPIMAGE_ID_PREFIX}{appimage}"));
+
+        if !self.apps.contains_key(&id) {
+            self.apps.insert(id.clone(), App::from_appimage(appimage));
+        }
+
+        debug!(
+            "Associating process {} with AppImage {appimage}",
+            process.data.pid
+        );
+
+        id
+    }
+
+    /// Returns the ID of the app that represents the container `process` runs in (if any), adding the app if it
+    /// doesn't exist yet
+    fn container_app_id(&mut self, process: &Process) -> Option<String> {
+        let container = process.data.container.as_ref()?;
+
+        let id = Some(format!(
+            "{CONTAINER_ID_PREFIX}{}:{}",
+            container.runtime, container.id
+        ));
+
+        if !self.apps.contains_key(&id) {
+            self.apps.insert(id.clone(), App::from_container(container));
+        }
+
+        debug!(
+            "Associating process {} with {} container {}",
+            process.data.pid,
+            container.runtime,
+            container.display_name()
+        );
+
+        id
+    }
+
     pub fn get_process(&self, pid: i32) -> Option<&Process> {
         self.processes.get(&pid)
     }
@@ -797,6 +1266,7 @@ impl AppsContext {
                 old_process.read_bytes_last = old_process.data.read_bytes;
                 old_process.write_bytes_last = old_process.data.write_bytes;
                 old_process.gpu_usage_stats_last = old_process.data.gpu_usage_stats.clone();
+                old_process.cpu_throttling_last = old_process.data.cpu_throttling;
 
                 old_process.data = process_data.clone();
             } else {
@@ -805,8 +1275,14 @@ impl AppsContext {
 
                 let mut new_process = Process::from_process_data(process_data);
 
+                // whatever runs in a container belongs to the container, even if it looks like an installed app
+                let app_id = self
+                    .container_app_id(&new_process)
+                    .or_else(|| self.app_associated_with_process(&new_process))
+                    .or_else(|| self.appimage_app_id(&new_process));
+
                 self.apps
-                    .get_mut(&self.app_associated_with_process(&new_process))
+                    .get_mut(&app_id)
                     .unwrap()
                     .add_process(&mut new_process);
 
@@ -863,3 +1339,276 @@ impl AppsContext {
         trace!("AppsContext refresh done within {:.2?}", start.elapsed());
     }
 }
+
+/// Adds up the space occupied by `path` and, if it's a directory, everything inside it. Symlinks are not followed and
+/// files whose inode is already in `seen_inodes` are skipped, so that hard links (which e.g. Flatpak uses a lot for
+/// deduplication) are only counted once.
+fn disk_usage_of_path<P: AsRef<Path>>(
+    path: P,
+    seen_inodes: &mut HashSet<(u64, u64)>,
+) -> Result<u64> {
+    let path = path.as_ref();
+    let metadata = std::fs::symlink_metadata(path)
+        .with_context(|| format!("unable to read metadata of {}", path.to_string_lossy()))?;
+
+    let mut usage = 0;
+    let mut pending = vec![(path.to_path_buf(), metadata)];
+
+    while let Some((path, metadata)) = pending.pop() {
+        if seen_inodes.insert((metadata.dev(), metadata.ino())) {
+            // st_blocks is always counted in units of 512 bytes
+            usage += metadata.blocks() * 512;
+        }
+
+        if metadata.is_dir() {
+            if let Ok(read_dir) = std::fs::read_dir(&path) {
+                pending.extend(read_dir.flatten().filter_map(|entry| {
+                    entry
+                        .metadata()
+                        .ok()
+                        .map(|metadata| (entry.path(), metadata))
+                }));
+            }
+        }
+    }
+
+    Ok(usage)
+}
+
+/// Asks the system's package manager how large the package owning `owned_file` is once installed
+fn package_size<P: AsRef<Path>>(owned_file: P) -> Option<u64> {
+    let owned_file = owned_file.as_ref();
+
+    // inside of Flatpak's sandbox, the host's files are found in /run/host
+    let owned_file = owned_file
+        .strip_prefix("/run/host")
+        .map(|path| Path::new("/").join(path))
+        .unwrap_or_else(|_| owned_file.to_path_buf());
+    let owned_file = owned_file.to_string_lossy();
+
+    host_command_output("rpm", ["-qf", "--queryformat", "%{SIZE}\\n", &owned_file])
+        .ok()
+        .and_then(parse_rpm_size)
+        .or_else(|| {
+            let package = host_command_output("dpkg-query", ["-S", &owned_file])
+                .ok()
+                .and_then(parse_dpkg_owner)?;
+
+            host_command_output("dpkg-query", ["-W", "-f=${Installed-Size}", &package])
+                .ok()
+                .and_then(|installed_size| installed_size.trim().parse::<u64>().ok())
+                .map(|kibibytes| kibibytes * 1024)
+        })
+}
+
+/// Runs `program` on the host (i.e. outside of Flatpak's sandbox if necessary) and returns its stdout if it succeeded
+fn host_command_output<S: AsRef<OsStr>, I: IntoIterator<Item = S>>(
+    program: &str,
+    args: I,
+) -> Result<String> {
+    let output = if *IS_FLATPAK {
+        Command::new(FLATPAK_SPAWN)
+            .args(["--host", program])
+            .args(args)
+            .output()
+    } else {
+        Command::new(program).args(args).output()
+    }
+    .with_context(|| format!("unable to execute {program}"))?;
+
+    if !output.status.success() {
+        bail!("{program} exited with {}", output.status)
+    }
+
+    String::from_utf8(output.stdout)
+        .with_context(|| format!("unable to parse stdout of {program} to UTF-8"))
+}
+
+/// Returns the size in bytes from the output of `rpm -qf --queryformat '%{SIZE}\n'`
+fn parse_rpm_size<S: AsRef<str>>(rpm_output: S) -> Option<u64> {
+    rpm_output
+        .as_ref()
+        .lines()
+        .next()
+        .and_then(|size| size.trim().parse().ok())
+}
+
+/// Returns the name of the package owning a file from the output of `dpkg-query -S`, which looks like
+/// `firefox-esr: /usr/share/applications/firefox-esr.desktop` or `pkg-a, pkg-b: /path` if several packages own it
+fn parse_dpkg_owner<S: AsRef<str>>(dpkg_output: S) -> Option<String> {
+    dpkg_output
+        .as_ref()
+        .lines()
+        .filter(|line| !line.starts_with("diversion by"))
+        .find_map(|line| line.split_once(": "))
+        .and_then(|(packages, _)| packages.split(", ").next())
+        .map(str::trim)
+        .filter(|package| !package.is_empty())
+        .map(str::to_string)
+}
+
+/// Picks the GPU that is used the most out of `(gpu, usage, video_memory)` tuples (which may contain the same GPU
+/// several times, e.g. once for every process). Processes usually open every GPU in the system while looking for one
+/// to render on, so GPUs that are neither used nor have any video memory allocated are ignored. If no GPU is currently
+/// used, the one with the most video memory allocated is picked.
+fn dominant_gpu<I: IntoIterator<Item = (GpuIdentifier, f32, u64)>>(
+    stats: I,
+) -> Option<GpuIdentifier> {
+    let mut sums: HashMap<GpuIdentifier, (f32, u64)> = HashMap::new();
+
+    for (gpu_identifier, usage, video_memory) in stats {
+        let sum = sums.entry(gpu_identifier).or_default();
+        sum.0 += usage;
+        sum.1 += video_memory;
+    }
+
+    sums.into_iter()
+        .filter(|(_, (usage, video_memory))| *usage > 0.0 || *video_memory > 0)
+        .max_by(|(a_gpu, (a_usage, a_mem)), (b_gpu, (b_usage, b_mem))| {
+            a_usage
+                .total_cmp(b_usage)
+                .then(a_mem.cmp(b_mem))
+                // prefer the first GPU if they're otherwise equal so that the result is deterministic
+                .then(b_gpu.cmp(a_gpu))
+        })
+        .map(|(gpu_identifier, _)| gpu_identifier)
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+    use process_data::GpuIdentifier;
+
+    use super::{dominant_gpu, parse_dpkg_owner, parse_rpm_size, App};
+
+    #[test]
+    fn dominant_gpu_by_usage() {
+        let igpu = GpuIdentifier::Enumerator(0);
+        let dgpu = GpuIdentifier::Enumerator(1);
+
+        // two processes, one of them only composites on the iGPU while the other renders on the dGPU
+        assert_eq!(
+            Some(dgpu),
+            dominant_gpu([
+                (igpu, 0.05, 64 * 1024 * 1024),
+                (dgpu, 0.0, 0),
+                (igpu, 0.0, 0),
+                (dgpu, 0.4, 32 * 1024 * 1024),
+            ])
+        );
+    }
+
+    #[test]
+    fn dominant_gpu_idle() {
+        let igpu = GpuIdentifier::Enumerator(0);
+        let dgpu = GpuIdentifier::Enumerator(1);
+
+        assert_eq!(
+            Some(dgpu),
+            dominant_gpu([(igpu, 0.0, 1024), (dgpu, 0.0, 4096)])
+        );
+        assert_eq!(None, dominant_gpu([(igpu, 0.0, 0), (dgpu, 0.0, 0)]));
+        assert_eq!(None, dominant_gpu([]));
+    }
+
+    #[test]
+    fn flatpak_runtime_from_metadata() {
+        let metadata = "[Application]\nname=org.gnome.Calculator\nruntime=org.gnome.Platform/x86_64/47\nsdk=org.gnome.Sdk/x86_64/47\ncommand=gnome-calculator\n\n[Context]\nshared=network;ipc;\n";
+
+        assert_eq!(
+            Some("org.gnome.Platform/x86_64/47".to_string()),
+            App::flatpak_runtime(metadata)
+        );
+        assert_eq!(
+            None,
+            App::flatpak_runtime("[Runtime]\nname=org.gnome.Platform\n")
+        );
+    }
+
+    #[test]
+    fn snap_version_from_snap_yaml() {
+        assert_eq!(
+            Some("128.0.3-1".to_string()),
+            App::snap_version(
+                "name: firefox\nversion: 128.0.3-1\nsummary: Mozilla Firefox web browser\n"
+            )
+        );
+        assert_eq!(
+            Some("1.2.3".to_string()),
+            App::snap_version("name: example\nversion: '1.2.3'\n")
+        );
+        assert_eq!(None, App::snap_version("name: example\n"));
+    }
+
+    #[test]
+    fn appimage_name_with_version_and_architecture() {
+        assert_eq!(
+            "Krita",
+            App::appimage_name("/home/user/Applications/Krita-5.2.2-x86_64.AppImage")
+        );
+        assert_eq!(
+            "FreeCAD",
+            App::appimage_name("/opt/FreeCAD_0.21.2-Linux-x86_64.AppImage")
+        );
+        assert_eq!("Cura", App::appimage_name("/opt/Cura_v5.7.0.AppImage"));
+        assert_eq!(
+            "balenaEtcher",
+            App::appimage_name("/opt/balenaEtcher-x64.AppImage")
+        );
+    }
+
+    #[test]
+    fn combined_affinity_is_union() {
+        let affinities: [&[bool]; 3] = [
+            &[true, false, false, false],
+            &[false, false, true, false],
+            &[false, true],
+        ];
+
+        assert_eq!(
+            vec![true, true, true, false],
+            App::combined_affinity(affinities)
+        );
+    }
+
+    #[test]
+    fn combined_affinity_without_processes() {
+        assert!(App::combined_affinity([]).is_empty());
+    }
+
+    #[test]
+    fn appimage_name_plain() {
+        assert_eq!("yt-dlp", App::appimage_name("/opt/yt-dlp.appimage"));
+        assert_eq!("nvim", App::appimage_name("nvim.appimage"));
+        assert_eq!("MyApp", App::appimage_name("/opt/MyApp"));
+    }
+
+    #[test]
+    fn rpm_size() {
+        assert_eq!(Some(271_823_457), parse_rpm_size("271823457\n"));
+        assert_eq!(Some(1024), parse_rpm_size("1024\n2048\n"));
+        assert_eq!(
+            None,
+            parse_rpm_size(
+                "file /usr/share/applications/foo.desktop is not owned by any package\n"
+            )
+        );
+    }
+
+    #[test]
+    fn dpkg_owner() {
+        assert_eq!(
+            Some("firefox-esr".to_string()),
+            parse_dpkg_owner("firefox-esr: /usr/share/applications/firefox-esr.desktop\n")
+        );
+        assert_eq!(
+            Some("libfoo1:amd64".to_string()),
+            parse_dpkg_owner("libfoo1:amd64, libfoo1:i386: /usr/share/applications/foo.desktop\n")
+        );
+        assert_eq!(
+            Some("gimp".to_string()),
+            parse_dpkg_owner("diversion by foo from: /usr/share/applications/gimp.desktop\ngimp: /usr/share/applications/gimp.desktop\n")
+        );
+        assert_eq!(None, parse_dpkg_owner(""));
+    }
+}
@@ -4,12 +4,15 @@ use std::{
     str::{self, FromStr},
 };
 
+use crate::config::LIBEXECDIR;
 use crate::i18n::{i18n, i18n_f};
 use anyhow::{bail, Context, Result};
 use lazy_regex::{lazy_regex, Lazy, Regex};
-use log::trace;
+use log::{debug, info, trace};
 
-use super::units::convert_energy;
+use super::{maybe_pkexec_command, units::convert_energy, FLATPAK_APP_PATH, IS_FLATPAK};
+
+const PATH_SYSFS: &str = "/sys/class/power_supply";
 
 // For (at least) Lenovo Yoga 6 13ALC7
 static HEX_ENCODED_REGEX: Lazy<Regex> = lazy_regex!(r"^(0x[0-9a-fA-F]{2}\s*)*$");
@@ -19,7 +22,10 @@ pub struct BatteryData {
     pub inner: Battery,
     pub charge: Result<f64>,
     pub power_usage: Result<f64>,
+    pub voltage: Result<f64>,
+    pub current: Result<f64>,
     pub health: Result<f64>,
+    pub full_capacity: Result<f64>,
     pub state: Result<State>,
     pub charge_cycles: Result<usize>,
 }
@@ -33,7 +39,10 @@ impl BatteryData {
         let inner = Battery::from_sysfs(path);
         let charge = inner.charge();
         let power_usage = inner.power_usage();
+        let voltage = inner.voltage();
+        let current = inner.current();
         let health = inner.health();
+        let full_capacity = inner.full_capacity();
         let state = inner.state();
         let charge_cycles = inner.charge_cycles();
 
@@ -41,7 +50,10 @@ impl BatteryData {
             inner,
             charge,
             power_usage,
+            voltage,
+            current,
             health,
+            full_capacity,
             state,
             charge_cycles,
         };
@@ -152,6 +164,31 @@ impl Display for Technology {
     }
 }
 
+/// Charge levels in percent between which the battery is kept to reduce its wear
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChargeThresholds {
+    /// Charging only starts once the charge has fallen below this, not every battery supports it
+    pub start: Option<u8>,
+    /// Charging stops once the charge has reached this
+    pub end: u8,
+}
+
+impl ChargeThresholds {
+    /// Whether the thresholds are percentages and charging starts below where it ends
+    pub fn is_valid(&self) -> bool {
+        (1..=100).contains(&self.end) && self.start.map_or(true, |start| start < self.end)
+    }
+
+    /// The arguments for `resources-charge-limit`, a missing start threshold is passed as "-"
+    fn arguments(&self) -> [String; 2] {
+        [
+            self.start
+                .map_or_else(|| "-".to_string(), |start| start.to_string()),
+            self.end.to_string(),
+        ]
+    }
+}
+
 #[derive(Debug, Clone, Default, PartialEq)]
 pub struct Battery {
     pub sysfs_path: PathBuf,
@@ -162,9 +199,44 @@ pub struct Battery {
 }
 
 impl Battery {
+    /// Checks whether the system is currently running on battery power, i.e. at least one battery is discharging
+    /// while no external power supply (like a charger) is online
+    pub fn on_battery_power() -> bool {
+        let Ok(entries) = std::fs::read_dir(PATH_SYSFS) else {
+            return false;
+        };
+
+        let supplies = entries
+            .flatten()
+            .map(|entry| {
+                let read = |file: &str| {
+                    std::fs::read_to_string(entry.path().join(file))
+                        .map(|s| s.trim().to_string())
+                        .unwrap_or_default()
+                };
+                (read("type"), read("online"), read("status"))
+            })
+            .collect::<Vec<_>>();
+
+        Self::is_on_battery_power(&supplies)
+    }
+
+    /// Expects the contents of the `type`, `online` and `status` files of each power supply
+    fn is_on_battery_power<S: AsRef<str>>(supplies: &[(S, S, S)]) -> bool {
+        let external_power_online = supplies.iter().any(|(supply_type, online, _)| {
+            supply_type.as_ref() != "Battery" && online.as_ref() == "1"
+        });
+
+        let discharging = supplies.iter().any(|(supply_type, _, status)| {
+            supply_type.as_ref() == "Battery" && status.as_ref() == "Discharging"
+        });
+
+        discharging && !external_power_online
+    }
+
     pub fn get_sysfs_paths() -> Result<Vec<PathBuf>> {
         let mut list = Vec::new();
-        let entries = std::fs::read_dir("/sys/class/power_supply")?;
+        let entries = std::fs::read_dir(PATH_SYSFS)?;
         for entry in entries {
             let entry = entry?;
 
@@ -305,32 +377,51 @@ impl Battery {
         }
     }
 
+    /// Returns the capacity of the battery when it's fully charged in its current condition in Wh
+    pub fn full_capacity(&self) -> Result<f64> {
+        self.read_micro_unit("energy_full")
+    }
+
     pub fn power_usage(&self) -> Result<f64> {
-        std::fs::read_to_string(self.sysfs_path.join("power_now"))
-            .context("unable to read power_now file")
-            .and_then(|x| {
-                x.trim()
-                    .parse::<usize>()
-                    .map(|microwatts| microwatts as f64 / 1_000_000.0)
-                    .context("unable to parse power_now sysfs file")
-            })
+        self.read_micro_unit("power_now")
             .or_else(|_| self.power_usage_from_voltage_and_current())
     }
 
     fn power_usage_from_voltage_and_current(&self) -> Result<f64> {
-        let voltage = std::fs::read_to_string(self.sysfs_path.join("voltage_now"))?
-            .trim()
-            .parse::<usize>()
-            .map(|microvolts| microvolts as f64 / 1_000_000.0)
-            .context("unable to parse voltage_now sysfs file")?;
+        let voltage = self.voltage()?;
+        let current = self.read_micro_unit("current_now")?;
 
-        let current = std::fs::read_to_string(self.sysfs_path.join("current_now"))?
-            .trim()
-            .parse::<usize>()
-            .map(|microamps| microamps as f64 / 1_000_000.0)
-            .context("unable to parse current_now sysfs file")?;
+        Ok(voltage * current)
+    }
 
-        Ok(f64::abs(voltage * current))
+    pub fn voltage(&self) -> Result<f64> {
+        self.read_micro_unit("voltage_now")
+    }
+
+    pub fn current(&self) -> Result<f64> {
+        // batteries that report their capacity in charge_* (µAh) expose current_now, those that report it in energy_*
+        // (µWh) usually only expose power_now, so derive the current from the power and voltage there
+        self.read_micro_unit("current_now").or_else(|_| {
+            let power = self.read_micro_unit("power_now")?;
+            let voltage = self.voltage()?;
+
+            if voltage == 0.0 {
+                bail!("voltage_now is zero, unable to derive current")
+            }
+
+            Ok(power / voltage)
+        })
+    }
+
+    /// Reads a sysfs file containing a value in micro units (µV, µA, µW) and returns it in base units. Some drivers
+    /// report signed values (e.g. a negative `current_now` while discharging), so only the magnitude is returned.
+    fn read_micro_unit(&self, file: &str) -> Result<f64> {
+        std::fs::read_to_string(self.sysfs_path.join(file))
+            .with_context(|| format!("unable to read {file} sysfs file"))?
+            .trim()
+            .parse::<i64>()
+            .map(|micro| micro.unsigned_abs() as f64 / 1_000_000.0)
+            .with_context(|| format!("unable to parse {file} sysfs file"))
     }
 
     pub fn state(&self) -> Result<State> {
@@ -346,11 +437,64 @@ impl Battery {
             .parse()
             .context("unable to parse cycle_count sysfs file")
     }
+
+    /// Reads `charge_control_start_threshold` and `charge_control_end_threshold`, only the latter is required
+    pub fn charge_thresholds(&self) -> Result<ChargeThresholds> {
+        let read_threshold = |file: &str| -> Result<u8> {
+            std::fs::read_to_string(self.sysfs_path.join(file))
+                .with_context(|| format!("unable to read {file} sysfs file"))?
+                .trim()
+                .parse()
+                .with_context(|| format!("unable to parse {file} sysfs file"))
+        };
+
+        let end = read_threshold("charge_control_end_threshold")?;
+        let start = read_threshold("charge_control_start_threshold").ok();
+
+        Ok(ChargeThresholds { start, end })
+    }
+
+    /// Writes `thresholds` to sysfs using `resources-charge-limit`, which is run privileged if necessary
+    pub fn set_charge_thresholds(&self, thresholds: ChargeThresholds) -> Result<()> {
+        if !thresholds.is_valid() {
+            bail!("invalid charge thresholds: {thresholds:?}");
+        }
+
+        let battery_name = self
+            .sysfs_path
+            .file_name()
+            .context("battery has no sysfs name")?
+            .to_string_lossy()
+            .to_string();
+
+        let charge_limit_path = if *IS_FLATPAK {
+            format!(
+                "{}/libexec/resources/resources-charge-limit",
+                FLATPAK_APP_PATH.as_str()
+            )
+        } else {
+            format!("{LIBEXECDIR}/resources-charge-limit")
+        };
+
+        let [start, end] = thresholds.arguments();
+
+        debug!("Trying to set charge thresholds of {battery_name} to {start} and {end}");
+
+        let return_code =
+            maybe_pkexec_command(charge_limit_path, [battery_name.clone(), start, end])?;
+
+        if return_code == 0 {
+            info!("Successfully set the charge thresholds of {battery_name}");
+            Ok(())
+        } else {
+            bail!("non-zero return code: {return_code}")
+        }
+    }
 }
 
 #[cfg(test)]
 mod test {
-    use super::Battery;
+    use super::{Battery, ChargeThresholds};
     use pretty_assertions::assert_eq;
 
     #[test]
@@ -388,4 +532,59 @@ mod test {
             String::from("ABC D")
         );
     }
+
+    #[test]
+    fn on_battery_power_discharging() {
+        let supplies = [("Mains", "0", ""), ("Battery", "", "Discharging")];
+        assert!(Battery::is_on_battery_power(&supplies));
+    }
+
+    #[test]
+    fn on_battery_power_charger_online() {
+        let supplies = [("Mains", "1", ""), ("Battery", "", "Not charging")];
+        assert!(!Battery::is_on_battery_power(&supplies));
+
+        // USB-C chargers often show up as their own power supply
+        let supplies = [
+            ("Mains", "0", ""),
+            ("USB", "1", ""),
+            ("Battery", "", "Discharging"),
+        ];
+        assert!(!Battery::is_on_battery_power(&supplies));
+    }
+
+    #[test]
+    fn on_battery_power_no_battery() {
+        let supplies = [("Mains", "1", "")];
+        assert!(!Battery::is_on_battery_power(&supplies));
+        assert!(!Battery::is_on_battery_power::<&str>(&[]));
+    }
+
+    #[test]
+    fn charge_thresholds() {
+        let thresholds = ChargeThresholds {
+            start: Some(75),
+            end: 80,
+        };
+        assert!(thresholds.is_valid());
+        assert_eq!(["75".to_string(), "80".to_string()], thresholds.arguments());
+
+        let thresholds = ChargeThresholds {
+            start: None,
+            end: 60,
+        };
+        assert!(thresholds.is_valid());
+        assert_eq!(["-".to_string(), "60".to_string()], thresholds.arguments());
+
+        assert!(!ChargeThresholds {
+            start: Some(80),
+            end: 80
+        }
+        .is_valid());
+        assert!(!ChargeThresholds {
+            start: None,
+            end: 101
+        }
+        .is_valid());
+    }
 }
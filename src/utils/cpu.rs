@@ -7,8 +7,14 @@ use std::{
     sync::LazyLock,
 };
 
+use super::power_profile::PowerProfile;
+
 const PROC_STAT: &str = "/proc/stat";
 
+const PROC_LOADAVG: &str = "/proc/loadavg";
+
+const CPU0_CACHE_PATH: &str = "/sys/devices/system/cpu/cpu0/cache";
+
 const KNOWN_HWMONS: &[&str] = &["zenpower", "coretemp", "k10temp"];
 
 const KNOWN_THERMAL_ZONES: &[&str] = &["cpu-thermal", "x86_pkg_temp", "acpitz"];
@@ -31,6 +37,16 @@ static RE_PROC_STAT: Lazy<Regex> = lazy_regex!(
     r"cpu[0-9]+ *(?P<user>[0-9]*) *(?P<nice>[0-9]*) *(?P<system>[0-9]*) *(?P<idle>[0-9]*) *(?P<iowait>[0-9]*) *(?P<irq>[0-9]*) *(?P<softirq>[0-9]*) *(?P<steal>[0-9]*) *(?P<guest>[0-9]*) *(?P<guest_nice>[0-9]*)"
 );
 
+static RE_PROCS_RUNNING: Lazy<Regex> = lazy_regex!(r"(?m)^procs_running\s+(\d+)");
+
+static RE_PROCS_BLOCKED: Lazy<Regex> = lazy_regex!(r"(?m)^procs_blocked\s+(\d+)");
+
+static RE_CONTEXT_SWITCHES: Lazy<Regex> = lazy_regex!(r"(?m)^ctxt\s+(\d+)");
+
+static RE_INTERRUPTS: Lazy<Regex> = lazy_regex!(r"(?m)^intr\s+(\d+)");
+
+static RE_FORKS: Lazy<Regex> = lazy_regex!(r"(?m)^processes\s+(\d+)");
+
 static CPU_TEMPERATURE_PATH: LazyLock<Option<PathBuf>> = LazyLock::new(|| {
     let cpu_temperature_path =
         search_for_hwmons(KNOWN_HWMONS).or_else(|| search_for_thermal_zones(KNOWN_THERMAL_ZONES));
@@ -79,20 +95,138 @@ fn search_for_thermal_zones(types: &[&'static str]) -> Option<(&'static str, Pat
     None
 }
 
+/// System-wide amounts of processes and threads along with the load average
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TaskCounts {
+    pub processes: Option<usize>,
+    pub threads: Option<usize>,
+    pub running: Option<usize>,
+    pub blocked: Option<usize>,
+    pub load_average: Option<[f64; 3]>,
+}
+
+impl TaskCounts {
+    /// Gathers the task counts from the already read contents of /proc/stat and /proc/loadavg. Only the amount of
+    /// processes requires looking at /proc itself, which is a single directory listing.
+    fn new<S: AsRef<str>>(proc_stat: S) -> Self {
+        let loadavg = std::fs::read_to_string(PROC_LOADAVG)
+            .inspect_err(|e| trace!("Unable to read {PROC_LOADAVG}: {e}"))
+            .unwrap_or_default();
+
+        let mut task_counts = Self::parse(proc_stat, loadavg);
+
+        task_counts.processes = std::fs::read_dir("/proc").ok().map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| {
+                    entry
+                        .file_name()
+                        .to_str()
+                        .is_some_and(|name| name.bytes().all(|byte| byte.is_ascii_digit()))
+                })
+                .count()
+        });
+
+        task_counts
+    }
+
+    fn parse<S: AsRef<str>, T: AsRef<str>>(proc_stat: S, loadavg: T) -> Self {
+        let proc_stat = proc_stat.as_ref();
+
+        let running = RE_PROCS_RUNNING
+            .captures(proc_stat)
+            .and_then(|captures| captures.get(1))
+            .and_then(|capture| capture.as_str().parse().ok());
+
+        let blocked = RE_PROCS_BLOCKED
+            .captures(proc_stat)
+            .and_then(|captures| captures.get(1))
+            .and_then(|capture| capture.as_str().parse().ok());
+
+        // /proc/loadavg looks like "0.52 0.58 0.59 2/1234 5678", where 1234 is the amount of threads
+        let mut fields = loadavg.as_ref().split_whitespace();
+
+        let load_average = fields
+            .next()
+            .zip(fields.next())
+            .zip(fields.next())
+            .and_then(|((one, five), fifteen)| {
+                Some([one.parse().ok()?, five.parse().ok()?, fifteen.parse().ok()?])
+            });
+
+        let threads = fields
+            .next()
+            .and_then(|entities| entities.split_once('/'))
+            .and_then(|(_, total)| total.parse().ok());
+
+        Self {
+            processes: None,
+            threads,
+            running,
+            blocked,
+            load_average,
+        }
+    }
+}
+
+/// Cumulative system-wide event counters since boot, rates have to be calculated by the caller
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KernelCounters {
+    pub context_switches: Option<u64>,
+    pub interrupts: Option<u64>,
+    /// Amount of forks (processes and threads alike) since boot
+    pub forks: Option<u64>,
+}
+
+impl KernelCounters {
+    fn parse<S: AsRef<str>>(proc_stat: S) -> Self {
+        let proc_stat = proc_stat.as_ref();
+
+        // only the first number after "intr" is captured, it is the total while the others are per interrupt source
+        let counter = |regex: &Regex| {
+            regex
+                .captures(proc_stat)
+                .and_then(|captures| captures.get(1))
+                .and_then(|capture| capture.as_str().parse().ok())
+        };
+
+        Self {
+            context_switches: counter(&RE_CONTEXT_SWITCHES),
+            interrupts: counter(&RE_INTERRUPTS),
+            forks: counter(&RE_FORKS),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct CpuData {
     pub new_thread_usages: Vec<Result<(u64, u64)>>,
     pub temperature: Result<f32, anyhow::Error>,
     pub frequencies: Vec<Option<u64>>,
+    /// Labeled temperatures of the CPU's hwmon, like "Package id 0" and "Core 0" or "Tctl" and "Tccd1"
+    pub sensor_temperatures: Vec<(String, f32)>,
+    pub task_counts: TaskCounts,
+    pub kernel_counters: KernelCounters,
+    /// The active power profile of power-profiles-daemon, `None` if it's not running
+    pub power_profile: Option<PowerProfile>,
 }
 
 impl CpuData {
     pub fn new(logical_cpus: usize) -> Self {
         trace!("Gathering CPU data…");
-        let new_thread_usages = get_cpu_usage();
+
+        let proc_stat = read_proc_stat();
+
+        let new_thread_usages = parse_proc_stat(&proc_stat);
+
+        let task_counts = TaskCounts::new(&proc_stat);
+
+        let kernel_counters = KernelCounters::parse(&proc_stat);
 
         let temperature = get_temperature();
 
+        let sensor_temperatures = get_sensor_temperatures();
+
         let mut frequencies = Vec::with_capacity(logical_cpus);
 
         for i in 0..logical_cpus {
@@ -100,10 +234,16 @@ impl CpuData {
             frequencies.push(freq.ok());
         }
 
+        let power_profile = PowerProfile::active().ok();
+
         let cpu_data = Self {
             new_thread_usages,
             temperature,
             frequencies,
+            sensor_temperatures,
+            task_counts,
+            kernel_counters,
+            power_profile,
         };
 
         trace!("Gathered CPU data: {cpu_data:?}");
@@ -112,6 +252,106 @@ impl CpuData {
     }
 }
 
+/// A cache of the CPU as found in /sys/devices/system/cpu/cpu0/cache/index*
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CpuCache {
+    pub level: u8,
+    /// "Data", "Instruction" or "Unified"
+    pub cache_type: String,
+    /// Size in bytes
+    pub size: usize,
+    /// Amount of logical CPUs that share this cache
+    pub shared_cpus: usize,
+}
+
+impl CpuCache {
+    /// Returns the caches of the first logical CPU sorted by their level, data caches before instruction caches.
+    /// On hybrid CPUs other cores might have differently sized caches.
+    pub fn get_all() -> Vec<Self> {
+        let Ok(paths) = glob(&format!("{CPU0_CACHE_PATH}/index*")) else {
+            return Vec::new();
+        };
+
+        let mut caches: Vec<Self> = paths
+            .flatten()
+            .filter_map(|path| {
+                Self::from_sysfs(&path)
+                    .inspect_err(|err| trace!("Unable to read cache info of {path:?}: {err}"))
+                    .ok()
+            })
+            .collect();
+
+        caches.sort_by(|a, b| {
+            a.level
+                .cmp(&b.level)
+                .then_with(|| a.cache_type.cmp(&b.cache_type))
+        });
+
+        caches
+    }
+
+    fn from_sysfs(path: &Path) -> Result<Self> {
+        let read = |file: &str| -> Result<String> {
+            std::fs::read_to_string(path.join(file))
+                .map(|content| content.trim().to_string())
+                .with_context(|| format!("unable to read {file}"))
+        };
+
+        let level = read("level")?.parse().context("unable to parse level")?;
+
+        let cache_type = read("type")?;
+
+        let size = Self::parse_size(read("size")?).context("unable to parse size")?;
+
+        let shared_cpus = read("shared_cpu_list")
+            .ok()
+            .and_then(Self::count_cpu_list)
+            .unwrap_or(1);
+
+        Ok(Self {
+            level,
+            cache_type,
+            size,
+            shared_cpus,
+        })
+    }
+
+    /// Parses sizes like "32K" into bytes
+    fn parse_size<S: AsRef<str>>(size: S) -> Option<usize> {
+        let size = size.as_ref().trim();
+
+        let (number, multiplier) = match size.chars().last()? {
+            'K' => (&size[..size.len() - 1], 1024),
+            'M' => (&size[..size.len() - 1], 1024 * 1024),
+            'G' => (&size[..size.len() - 1], 1024 * 1024 * 1024),
+            _ => (size, 1),
+        };
+
+        number
+            .parse::<usize>()
+            .ok()
+            .map(|number| number.saturating_mul(multiplier))
+    }
+
+    /// Counts the CPUs in lists like "0-3,8-11"
+    fn count_cpu_list<S: AsRef<str>>(cpu_list: S) -> Option<usize> {
+        cpu_list
+            .as_ref()
+            .trim()
+            .split(',')
+            .map(|range| {
+                if let Some((start, end)) = range.split_once('-') {
+                    let start = start.parse::<usize>().ok()?;
+                    let end = end.parse::<usize>().ok()?;
+                    Some(end.saturating_sub(start) + 1)
+                } else {
+                    range.parse::<usize>().ok().map(|_| 1)
+                }
+            })
+            .sum()
+    }
+}
+
 #[derive(Debug, Clone, Default, PartialEq)]
 pub struct CpuInfo {
     pub model_name: Option<String>,
@@ -121,6 +361,7 @@ pub struct CpuInfo {
     pub sockets: Option<usize>,
     pub virtualization: Option<String>,
     pub max_speed: Option<f64>,
+    pub caches: Vec<CpuCache>,
 }
 
 impl CpuInfo {
@@ -184,6 +425,7 @@ impl CpuInfo {
             sockets,
             virtualization,
             max_speed,
+            caches: Vec::new(),
         }
     }
 
@@ -194,7 +436,7 @@ impl CpuInfo {
             .replace("(TM)", "™")
     }
 
-    /// Returns a `CPUInfo` struct populated with values gathered from `lscpu`.
+    /// Returns a `CPUInfo` struct populated with values gathered from `lscpu` and the cache information in sysfs.
     ///
     /// # Errors
     ///
@@ -210,6 +452,10 @@ impl CpuInfo {
         )
         .context("unable to parse lscpu output to UTF-8")
         .map(Self::parse_lscpu)
+        .map(|cpu_info| Self {
+            caches: CpuCache::get_all(),
+            ..cpu_info
+        })
     }
 }
 
@@ -282,13 +528,15 @@ fn parse_proc_stat<S: AsRef<str>>(stat: S) -> Vec<Result<(u64, u64)>> {
 /// Will return `Err` if the are problems during reading or parsing
 /// of /proc/stat
 pub fn get_cpu_usage() -> Vec<Result<(u64, u64)>> {
+    parse_proc_stat(read_proc_stat())
+}
+
+fn read_proc_stat() -> String {
     trace!("Reading {PROC_STAT}…");
 
-    let raw = std::fs::read_to_string("/proc/stat")
+    std::fs::read_to_string(PROC_STAT)
         .context("unable to read /proc/stat")
-        .unwrap_or_default();
-
-    parse_proc_stat(raw)
+        .unwrap_or_default()
 }
 
 /// Returns the CPU temperature.
@@ -304,6 +552,55 @@ pub fn get_temperature() -> Result<f32> {
     }
 }
 
+/// Returns the temperatures of all labeled sensors of the CPU's hwmon in the order the hwmon lists them, usually the
+/// package followed by every core on Intel CPUs and the control temperature followed by every CCD on AMD CPUs. Empty if
+/// the CPU temperature isn't read from a hwmon.
+pub fn get_sensor_temperatures() -> Vec<(String, f32)> {
+    let Some(hwmon_path) = CPU_TEMPERATURE_PATH
+        .as_ref()
+        .filter(|path| path.ends_with("temp1_input"))
+        .and_then(|path| path.parent())
+    else {
+        return Vec::new();
+    };
+
+    let Ok(label_paths) = glob(&format!("{}/temp*_label", hwmon_path.to_string_lossy())) else {
+        return Vec::new();
+    };
+
+    let mut sensor_temperatures = label_paths
+        .flatten()
+        .filter_map(|label_path| {
+            let index = temperature_sensor_index(label_path.file_name()?.to_str()?)?;
+            let label = std::fs::read_to_string(&label_path)
+                .ok()?
+                .trim()
+                .to_string();
+            let temperature =
+                read_sysfs_thermal(hwmon_path.join(format!("temp{index}_input"))).ok()?;
+            Some((index, label, temperature))
+        })
+        .collect::<Vec<_>>();
+
+    // glob sorts lexicographically, which would put temp10 before temp2
+    sensor_temperatures.sort_by_key(|(index, _, _)| *index);
+
+    sensor_temperatures
+        .into_iter()
+        .map(|(_, label, temperature)| (label, temperature))
+        .collect()
+}
+
+/// Extracts the index out of the file name of a hwmon temperature label, e.g. 2 for `temp2_label`
+fn temperature_sensor_index<S: AsRef<str>>(file_name: S) -> Option<usize> {
+    file_name
+        .as_ref()
+        .strip_prefix("temp")?
+        .strip_suffix("_label")?
+        .parse()
+        .ok()
+}
+
 fn read_sysfs_thermal<P: AsRef<Path>>(path: P) -> Result<f32> {
     let path = path.as_ref();
     let temp_string = std::fs::read_to_string(path)
@@ -319,7 +616,9 @@ fn read_sysfs_thermal<P: AsRef<Path>>(path: P) -> Result<f32> {
 mod test {
     use pretty_assertions::assert_eq;
 
-    use crate::utils::cpu::CpuInfo;
+    use crate::utils::cpu::{
+        temperature_sensor_index, CpuCache, CpuInfo, KernelCounters, TaskCounts,
+    };
 
     const LSCPU_OUTPUT: &str = concat!(
         "Architecture:             x86_64\n",
@@ -365,8 +664,88 @@ mod test {
             sockets: Some(2),
             virtualization: Some("Abacus-V".into()),
             max_speed: Some(3000000.0),
+            caches: Vec::new(),
         };
 
         assert_eq!(parsed, expected)
     }
+
+    #[test]
+    fn task_counts_valid() {
+        let proc_stat = concat!(
+            "cpu  1082 0 1530 153617 11 0 51 0 0 0\n",
+            "cpu0 274 0 392 38389 3 0 23 0 0 0\n",
+            "intr 129366 0 9 0 0 0 0 0 0 0 0\n",
+            "ctxt 266373\n",
+            "btime 1727000000\n",
+            "processes 1402\n",
+            "procs_running 3\n",
+            "procs_blocked 1\n",
+            "softirq 57016 0 11232 1 3186 0 0 14 15446 0 27137\n",
+        );
+        let loadavg = "0.52 0.58 0.59 3/1234 5678\n";
+
+        let expected = TaskCounts {
+            processes: None,
+            threads: Some(1234),
+            running: Some(3),
+            blocked: Some(1),
+            load_average: Some([0.52, 0.58, 0.59]),
+        };
+
+        assert_eq!(expected, TaskCounts::parse(proc_stat, loadavg));
+    }
+
+    #[test]
+    fn task_counts_missing() {
+        assert_eq!(TaskCounts::default(), TaskCounts::parse("", ""));
+    }
+
+    #[test]
+    fn kernel_counters_valid() {
+        let proc_stat = concat!(
+            "cpu  1082 0 1530 153617 11 0 51 0 0 0\n",
+            "intr 129366 0 9 0 0 0 0 0 0 0 0\n",
+            "ctxt 266373\n",
+            "btime 1727000000\n",
+            "processes 1402\n",
+        );
+
+        let expected = KernelCounters {
+            context_switches: Some(266373),
+            interrupts: Some(129366),
+            forks: Some(1402),
+        };
+
+        assert_eq!(expected, KernelCounters::parse(proc_stat));
+    }
+
+    #[test]
+    fn cache_size_valid() {
+        assert_eq!(Some(32 * 1024), CpuCache::parse_size("32K\n"));
+        assert_eq!(Some(96 * 1024 * 1024), CpuCache::parse_size("96M"));
+        assert_eq!(Some(512), CpuCache::parse_size("512"));
+    }
+
+    #[test]
+    fn cache_size_invalid() {
+        assert_eq!(None, CpuCache::parse_size(""));
+        assert_eq!(None, CpuCache::parse_size("K"));
+    }
+
+    #[test]
+    fn cpu_list_count() {
+        assert_eq!(Some(1), CpuCache::count_cpu_list("0\n"));
+        assert_eq!(Some(2), CpuCache::count_cpu_list("0,8"));
+        assert_eq!(Some(8), CpuCache::count_cpu_list("0-3,8-11"));
+        assert_eq!(None, CpuCache::count_cpu_list("0-"));
+    }
+
+    #[test]
+    fn sensor_index() {
+        assert_eq!(Some(1), temperature_sensor_index("temp1_label"));
+        assert_eq!(Some(10), temperature_sensor_index("temp10_label"));
+        assert_eq!(None, temperature_sensor_index("temp1_input"));
+        assert_eq!(None, temperature_sensor_index("in0_label"));
+    }
 }
@@ -0,0 +1,151 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use log::trace;
+
+/// Only files below this directory may be used as gauges, so that custom gauges can't be abused to read arbitrary
+/// files (or files that have side effects when being read)
+const SYSFS_ROOT: &str = "/sys";
+
+/// A user-defined gauge that reads a single numeric value from a sysfs file
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomGauge {
+    pub label: String,
+    pub path: PathBuf,
+    pub unit: String,
+    pub scale: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct CustomGaugeData {
+    pub gauge: CustomGauge,
+    pub value: Option<f64>,
+}
+
+impl CustomGaugeData {
+    pub fn new(gauge: &CustomGauge) -> Self {
+        trace!("Gathering custom gauge data for {}…", gauge.path.display());
+
+        let value = gauge.read().ok();
+
+        Self {
+            gauge: gauge.clone(),
+            value,
+        }
+    }
+}
+
+impl CustomGauge {
+    pub fn new<S: Into<String>, P: Into<PathBuf>, U: Into<String>>(
+        label: S,
+        path: P,
+        unit: U,
+        scale: f64,
+    ) -> Result<Self> {
+        let path = path.into();
+
+        if !Self::is_allowed_path(&path) {
+            bail!("{} is not located in {SYSFS_ROOT}", path.display());
+        }
+
+        if !scale.is_finite() {
+            bail!("scale must be a finite number");
+        }
+
+        Ok(Self {
+            label: label.into(),
+            path,
+            unit: unit.into(),
+            scale,
+        })
+    }
+
+    /// Checks whether `path` is an absolute path below /sys that doesn't try to escape it
+    pub fn is_allowed_path<P: AsRef<Path>>(path: P) -> bool {
+        let path = path.as_ref();
+
+        path.is_absolute()
+            && path.starts_with(SYSFS_ROOT)
+            && !path
+                .components()
+                .any(|component| component == std::path::Component::ParentDir)
+    }
+
+    /// Reads the current value of the gauge and multiplies it with its scale
+    pub fn read(&self) -> Result<f64> {
+        if !Self::is_allowed_path(&self.path) {
+            bail!("{} is not located in {SYSFS_ROOT}", self.path.display());
+        }
+
+        let content = std::fs::read_to_string(&self.path)
+            .with_context(|| format!("unable to read {}", self.path.display()))?;
+
+        Self::parse_value(&content).map(|value| value * self.scale)
+    }
+
+    /// Formats `value` using the gauge's unit, if it has one
+    pub fn format_value(&self, value: f64) -> String {
+        // values that have been scaled down are likely to have meaningful decimal places
+        let formatted = if value.fract() == 0.0 {
+            format!("{value}")
+        } else {
+            format!("{value:.2}")
+        };
+
+        if self.unit.is_empty() {
+            formatted
+        } else {
+            format!("{formatted} {}", self.unit)
+        }
+    }
+
+    /// Parses the first whitespace-separated token of a sysfs file as a number
+    fn parse_value(content: &str) -> Result<f64> {
+        let token = content.split_whitespace().next().context("file is empty")?;
+
+        token
+            .parse::<f64>()
+            .with_context(|| format!("unable to parse '{token}' as a number"))
+            .and_then(|value| {
+                if value.is_finite() {
+                    Ok(value)
+                } else {
+                    bail!("'{token}' is not a finite number")
+                }
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::CustomGauge;
+
+    #[test]
+    fn parse_value_valid() {
+        assert_eq!(42_000.0, CustomGauge::parse_value("42000\n").unwrap());
+        assert_eq!(-3.5, CustomGauge::parse_value("  -3.5  ").unwrap());
+        assert_eq!(1200.0, CustomGauge::parse_value("1200 mV\n").unwrap());
+    }
+
+    #[test]
+    fn parse_value_invalid() {
+        assert!(CustomGauge::parse_value("").is_err());
+        assert!(CustomGauge::parse_value("enabled\n").is_err());
+        assert!(CustomGauge::parse_value("NaN").is_err());
+    }
+
+    #[test]
+    fn allowed_paths() {
+        assert!(CustomGauge::is_allowed_path(
+            "/sys/class/hwmon/hwmon0/temp1_input"
+        ));
+        assert!(!CustomGauge::is_allowed_path("/proc/meminfo"));
+        assert!(!CustomGauge::is_allowed_path("/sys/../etc/shadow"));
+        assert!(!CustomGauge::is_allowed_path(
+            "sys/class/hwmon/hwmon0/temp1_input"
+        ));
+        assert!(!CustomGauge::is_allowed_path("/system/file"));
+    }
+}
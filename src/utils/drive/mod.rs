@@ -0,0 +1,1157 @@
+pub mod smart;
+
+use anyhow::{Context, Result};
+use glob::glob;
+use gtk::gio::{Icon, ThemedIcon};
+use lazy_regex::{lazy_regex, Lazy, Regex};
+use log::trace;
+use std::{
+    collections::HashMap,
+    ffi::CString,
+    fmt::Display,
+    os::unix::ffi::OsStrExt,
+    path::{Path, PathBuf},
+    sync::{LazyLock, RwLock},
+};
+
+use crate::i18n::{i18n, i18n_f};
+
+use super::units::convert_storage;
+
+use smart::SmartData;
+
+const PATH_SYSFS: &str = "/sys/block";
+
+/// Unlike `PATH_SYSFS`, this also contains partitions
+const PATH_SYSFS_CLASS: &str = "/sys/class/block";
+
+const PATH_SYSFS_BTRFS: &str = "/sys/fs/btrfs";
+
+const PATH_SYSFS_BCACHEFS: &str = "/sys/fs/bcachefs";
+
+const PATH_ZFS_KSTAT: &str = "/proc/spl/kstat/zfs";
+
+const PATH_UDEV_DATA: &str = "/run/udev/data";
+
+const PATH_MOUNTS: &str = "/proc/self/mounts";
+
+/// The members of all ZFS pools by the pools' names as found during the last search for multi-device filesystems,
+/// finding them means going through the whole udev database
+static ZFS_POOL_MEMBERS: LazyLock<RwLock<HashMap<String, Vec<String>>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// How deep devices may be stacked on top of each other (e.g. partition → LVM → dm-crypt) before we stop following
+/// their holders
+const MAX_HOLDER_DEPTH: usize = 8;
+
+static RE_DRIVE: Lazy<Regex> = lazy_regex!(
+    r" *(?P<read_ios>[0-9]*) *(?P<read_merges>[0-9]*) *(?P<read_sectors>[0-9]*) *(?P<read_ticks>[0-9]*) *(?P<write_ios>[0-9]*) *(?P<write_merges>[0-9]*) *(?P<write_sectors>[0-9]*) *(?P<write_ticks>[0-9]*) *(?P<in_flight>[0-9]*) *(?P<io_ticks>[0-9]*) *(?P<time_in_queue>[0-9]*) *(?P<discard_ios>[0-9]*) *(?P<discard_merges>[0-9]*) *(?P<discard_sectors>[0-9]*) *(?P<discard_ticks>[0-9]*) *(?P<flush_ios>[0-9]*) *(?P<flush_ticks>[0-9]*)"
+);
+
+#[derive(Debug)]
+pub struct DriveData {
+    pub inner: Drive,
+    pub is_virtual: bool,
+    pub writable: Result<bool>,
+    pub removable: Result<bool>,
+    pub disk_stats: HashMap<String, usize>,
+    pub capacity: Result<u64>,
+    pub temperature: Result<f64>,
+    pub smart: Option<SmartData>,
+    pub filesystems: Vec<Filesystem>,
+    pub encrypted_devices: Vec<EncryptedDevice>,
+}
+
+impl DriveData {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        let path = path.as_ref();
+
+        trace!("Gathering drive data for {path:?}…");
+
+        let inner = Drive::from_sysfs(path);
+        let is_virtual = inner.is_virtual();
+        let writable = inner.writable();
+        let removable = inner.removable();
+        let disk_stats = inner.sys_stats().unwrap_or_default();
+        let capacity = inner.capacity();
+        let temperature = inner.temperature();
+        let smart = inner
+            .supports_smart()
+            .then(|| SmartData::read_cached(&inner.block_device))
+            .flatten();
+        let filesystems = inner.filesystems();
+        let encrypted_devices = inner.encrypted_devices();
+
+        let drive_data = Self {
+            inner,
+            is_virtual,
+            writable,
+            removable,
+            disk_stats,
+            capacity,
+            temperature,
+            smart,
+            filesystems,
+            encrypted_devices,
+        };
+
+        trace!(
+            "Gathered drive data for {}: {drive_data:?}",
+            path.to_string_lossy()
+        );
+
+        drive_data
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum DriveType {
+    CdDvdBluray,
+    Emmc,
+    Flash,
+    Floppy,
+    Hdd,
+    LoopDevice,
+    MappedDevice,
+    Nvme,
+    Raid,
+    RamDisk,
+    Ssd,
+    ZfsVolume,
+    Zram,
+    BtrfsFilesystem,
+    BcachefsFilesystem,
+    ZfsPool,
+    #[default]
+    Unknown,
+}
+
+/// A drive or one of its partitions that is encrypted using dm-crypt, either directly or further up the device stack
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncryptedDevice {
+    /// The encrypted partition (or the drive itself), e.g. `nvme0n1p2`
+    pub block_device: String,
+    /// The kind of encryption as named by cryptsetup, e.g. `LUKS2` or `PLAIN`
+    pub kind: String,
+}
+
+/// A mounted filesystem that resides on a drive or on one of its partitions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Filesystem {
+    /// The partition (or the drive itself) the filesystem is on, e.g. `nvme0n1p2`
+    pub block_device: String,
+    pub mount_point: PathBuf,
+    pub total_bytes: u64,
+    pub free_bytes: u64,
+    /// Free bytes that are available to unprivileged users, i.e. without the reserved blocks
+    pub available_bytes: u64,
+}
+
+impl Filesystem {
+    pub fn used_bytes(&self) -> u64 {
+        self.total_bytes.saturating_sub(self.free_bytes)
+    }
+
+    /// Returns the used fraction of the filesystem the same way `df` calculates it, i.e. reserved blocks count as
+    /// neither used nor available
+    pub fn used_fraction(&self) -> f64 {
+        let used = self.used_bytes();
+        let usable = used.saturating_add(self.available_bytes);
+
+        if usable == 0 {
+            0.0
+        } else {
+            used as f64 / usable as f64
+        }
+    }
+
+    fn from_statvfs(block_device: String, mount_point: PathBuf) -> Result<Self> {
+        let path = CString::new(mount_point.as_os_str().as_bytes())
+            .context("mount point contains a nul byte")?;
+
+        let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+        if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+            return Err(std::io::Error::last_os_error())
+                .with_context(|| format!("unable to statvfs {}", mount_point.display()));
+        }
+
+        let fragment_size = stat.f_frsize as u64;
+
+        Ok(Self {
+            block_device,
+            mount_point,
+            total_bytes: (stat.f_blocks as u64).saturating_mul(fragment_size),
+            free_bytes: (stat.f_bfree as u64).saturating_mul(fragment_size),
+            available_bytes: (stat.f_bavail as u64).saturating_mul(fragment_size),
+        })
+    }
+}
+
+/// A drive as shown in the Drives section. Besides actual block devices, this may also be a filesystem that spans
+/// multiple drives (btrfs, bcachefs or a ZFS pool), in which case `sysfs_path` points to the filesystem's directory in
+/// /sys/fs (or /proc/spl/kstat/zfs) and `block_device` is its UUID (or the name of the pool).
+#[derive(Debug, Clone, Default, Eq)]
+pub struct Drive {
+    pub model: Option<String>,
+    pub drive_type: DriveType,
+    pub block_device: String,
+    pub sysfs_path: PathBuf,
+    /// The file a loop device is backed by
+    pub backing_file: Option<PathBuf>,
+    /// The block devices (drives or partitions) a RAID array, an LVM volume or a multi-device filesystem consists of
+    pub members: Vec<String>,
+}
+
+impl Display for DriveType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                DriveType::CdDvdBluray => i18n("CD/DVD/Blu-ray Drive"),
+                DriveType::Emmc => i18n("eMMC Storage"),
+                DriveType::Flash => i18n("Flash Storage"),
+                DriveType::Floppy => i18n("Floppy Drive"),
+                DriveType::Hdd => i18n("Hard Disk Drive"),
+                DriveType::LoopDevice => i18n("Loop Device"),
+                DriveType::MappedDevice => i18n("Mapped Device"),
+                DriveType::Nvme => i18n("NVMe Drive"),
+                DriveType::Unknown => i18n("N/A"),
+                DriveType::Raid => i18n("Software Raid"),
+                DriveType::RamDisk => i18n("RAM Disk"),
+                DriveType::Ssd => i18n("Solid State Drive"),
+                DriveType::ZfsVolume => i18n("ZFS Volume"),
+                DriveType::Zram => i18n("Compressed RAM Disk (zram)"),
+                DriveType::BtrfsFilesystem => i18n("Multi-Device Btrfs Filesystem"),
+                DriveType::BcachefsFilesystem => i18n("Multi-Device bcachefs Filesystem"),
+                DriveType::ZfsPool => i18n("ZFS Pool"),
+            }
+        )
+    }
+}
+
+impl PartialEq for Drive {
+    fn eq(&self, other: &Self) -> bool {
+        self.block_device == other.block_device
+    }
+}
+
+impl Drive {
+    /// Creates a `Drive` using a SysFS Path
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the are errors during
+    /// reading or parsing
+    pub fn from_sysfs<P: AsRef<Path>>(sysfs_path: P) -> Drive {
+        let path = sysfs_path.as_ref().to_path_buf();
+
+        trace!("Creating Drive object of {path:?}");
+
+        let block_device = path
+            .file_name()
+            .expect("sysfs path ends with \"..\"?")
+            .to_string_lossy()
+            .to_string();
+
+        let mut drive = Self::default();
+        drive.sysfs_path = path.clone();
+        drive.block_device = block_device;
+        drive.drive_type = drive.drive_type().unwrap_or_default();
+        drive.model = drive
+            .model()
+            .ok()
+            .map(|model| model.trim().to_string())
+            .filter(|model| !model.is_empty());
+        if drive.drive_type == DriveType::LoopDevice {
+            drive.backing_file = drive.backing_file();
+        }
+        drive.members = drive.members();
+
+        trace!("Created Drive object of {path:?}: {drive:?}");
+
+        drive
+    }
+
+    /// Returns the SysFS Paths of possible drives
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the are errors during
+    /// reading or parsing
+    pub fn get_sysfs_paths() -> Result<Vec<PathBuf>> {
+        let mut list = Vec::new();
+        trace!("Finding entries in {PATH_SYSFS}");
+        let entries = std::fs::read_dir(PATH_SYSFS)?;
+        for entry in entries {
+            let entry = entry?;
+            let block_device = entry.file_name().to_string_lossy().to_string();
+            trace!("Found block device {block_device}");
+            if block_device.is_empty() {
+                continue;
+            }
+            list.push(entry.path());
+        }
+        list.extend(Self::multi_device_filesystem_paths());
+        Ok(list)
+    }
+
+    /// Returns the paths of btrfs and bcachefs filesystems and ZFS pools that span more than one block device. They
+    /// don't have a block device of their own, so they're shown as drives with the aggregated stats of their members.
+    fn multi_device_filesystem_paths() -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = [PATH_SYSFS_BTRFS, PATH_SYSFS_BCACHEFS]
+            .into_iter()
+            .filter_map(|path| std::fs::read_dir(path).ok())
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| Self::from_sysfs(path).members.len() > 1)
+            .collect();
+
+        // besides a directory for each pool, the ZFS kstats contain plenty of module-wide statistics files
+        let zfs_pools: Vec<PathBuf> = std::fs::read_dir(PATH_ZFS_KSTAT)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .collect();
+
+        let zfs_pool_members = if zfs_pools.is_empty() {
+            HashMap::new()
+        } else {
+            Self::zfs_pool_members()
+        };
+
+        paths.extend(zfs_pools.into_iter().filter(|path| {
+            path.file_name()
+                .and_then(|pool| zfs_pool_members.get(pool.to_string_lossy().as_ref()))
+                .is_some_and(|members| members.len() > 1)
+        }));
+
+        if let Ok(mut cached_zfs_pool_members) = ZFS_POOL_MEMBERS.write() {
+            *cached_zfs_pool_members = zfs_pool_members;
+        }
+
+        for path in &paths {
+            trace!("Found multi-device filesystem {}", path.display());
+        }
+
+        paths
+    }
+
+    /// Resolves a device number (as found in `st_dev` or `st_rdev`) to the name of its block device, e.g. `nvme0n1p2`.
+    /// If the device is a partition, the name of the drive it's on is appended in parentheses.
+    pub fn block_device_name_from_dev(dev: u64) -> Option<String> {
+        let (major, minor) = (libc::major(dev), libc::minor(dev));
+
+        let sysfs_path = std::fs::canonicalize(format!("/sys/dev/block/{major}:{minor}")).ok()?;
+
+        let name = sysfs_path.file_name()?.to_string_lossy().to_string();
+
+        if sysfs_path.join("partition").exists() {
+            let parent = sysfs_path.parent()?.file_name()?.to_string_lossy();
+            Some(format!("{name} ({parent})"))
+        } else {
+            Some(name)
+        }
+    }
+
+    /// Returns the mounted filesystems that reside on this drive or on one of its partitions. If a filesystem is
+    /// mounted multiple times (e.g. bind mounts or btrfs subvolumes), only its first mount point is returned.
+    pub fn filesystems(&self) -> Vec<Filesystem> {
+        let Ok(mounts) = std::fs::read_to_string(PATH_MOUNTS) else {
+            return Vec::new();
+        };
+
+        let mut filesystems: Vec<Filesystem> = Vec::new();
+
+        for (source, mount_point) in Self::parse_mounts(&mounts) {
+            // pseudo filesystems like proc or tmpfs don't have a device node as their source
+            if !source.starts_with("/dev/") {
+                continue;
+            }
+
+            let Some(block_device) = std::fs::canonicalize(&source)
+                .ok()
+                .and_then(|device| Some(device.file_name()?.to_string_lossy().to_string()))
+            else {
+                continue;
+            };
+
+            let on_this_drive = (self.is_multi_device_filesystem()
+                && self.members.contains(&block_device))
+                || Self::drive_of_block_device(&block_device).as_deref()
+                    == Some(&self.block_device);
+
+            if !on_this_drive
+                || filesystems
+                    .iter()
+                    .any(|filesystem| filesystem.block_device == block_device)
+            {
+                continue;
+            }
+
+            match Filesystem::from_statvfs(block_device, mount_point) {
+                Ok(filesystem) => filesystems.push(filesystem),
+                Err(error) => trace!("Skipping filesystem on {}: {error}", self.block_device),
+            }
+        }
+
+        filesystems
+    }
+
+    /// Returns the drive itself and its partitions that are encrypted. This is the case if the device is a dm-crypt
+    /// mapping itself or if a dm-crypt mapping is stacked on top of it, possibly with other devices (like LVM) in
+    /// between.
+    pub fn encrypted_devices(&self) -> Vec<EncryptedDevice> {
+        let mut devices = vec![(self.block_device.clone(), self.sysfs_path.clone())];
+
+        if let Ok(entries) = std::fs::read_dir(&self.sysfs_path) {
+            devices.extend(
+                entries
+                    .flatten()
+                    .filter(|entry| entry.path().join("partition").exists())
+                    .map(|entry| {
+                        (
+                            entry.file_name().to_string_lossy().to_string(),
+                            entry.path(),
+                        )
+                    }),
+            );
+        }
+
+        let mut encrypted_devices: Vec<EncryptedDevice> = devices
+            .into_iter()
+            .filter_map(|(block_device, sysfs_path)| {
+                Some(EncryptedDevice {
+                    block_device,
+                    kind: Self::crypt_kind(&sysfs_path, 0)?,
+                })
+            })
+            .collect();
+
+        encrypted_devices.sort_by(|a, b| a.block_device.cmp(&b.block_device));
+
+        encrypted_devices
+    }
+
+    /// Looks for a dm-crypt mapping at `sysfs_path` or among the devices (recursively) holding it and returns the kind
+    /// of its encryption
+    fn crypt_kind(sysfs_path: &Path, depth: usize) -> Option<String> {
+        if depth > MAX_HOLDER_DEPTH {
+            return None;
+        }
+
+        if let Some(kind) = std::fs::read_to_string(sysfs_path.join("dm/uuid"))
+            .ok()
+            .and_then(|uuid| Self::crypt_kind_from_dm_uuid(&uuid))
+        {
+            return Some(kind);
+        }
+
+        std::fs::read_dir(sysfs_path.join("holders"))
+            .ok()?
+            .flatten()
+            .find_map(|holder| {
+                Self::crypt_kind(&Path::new(PATH_SYSFS).join(holder.file_name()), depth + 1)
+            })
+    }
+
+    /// cryptsetup names the UUIDs of its device mapper devices like `CRYPT-LUKS2-<uuid>-<name>`. dm-verity and
+    /// dm-integrity devices are set up by cryptsetup as well but don't encrypt anything.
+    fn crypt_kind_from_dm_uuid(uuid: &str) -> Option<String> {
+        let kind = uuid.trim().strip_prefix("CRYPT-")?.split('-').next()?;
+
+        if kind.is_empty() || kind == "VERITY" || kind == "INTEGRITY" {
+            None
+        } else {
+            Some(kind.to_string())
+        }
+    }
+
+    /// Returns the name of the drive `block_device` belongs to, which is the device itself unless it's a partition
+    fn drive_of_block_device(block_device: &str) -> Option<String> {
+        let sysfs_path =
+            std::fs::canonicalize(Path::new("/sys/class/block").join(block_device)).ok()?;
+
+        if sysfs_path.join("partition").exists() {
+            Some(
+                sysfs_path
+                    .parent()?
+                    .file_name()?
+                    .to_string_lossy()
+                    .to_string(),
+            )
+        } else {
+            Some(block_device.to_string())
+        }
+    }
+
+    /// Returns whether this is a btrfs or bcachefs filesystem or a ZFS pool rather than an actual block device
+    pub fn is_multi_device_filesystem(&self) -> bool {
+        matches!(
+            self.drive_type,
+            DriveType::BtrfsFilesystem | DriveType::BcachefsFilesystem | DriveType::ZfsPool
+        )
+    }
+
+    /// Looks up the block devices this drive consists of. Only RAID arrays, LVM volumes and multi-device filesystems
+    /// have members, other stacked devices (like dm-crypt mappings) are seen as a different view on the same drive.
+    fn members(&self) -> Vec<String> {
+        let mut members: Vec<String> = match self.drive_type {
+            DriveType::Raid => Self::entry_names(&self.sysfs_path.join("slaves")),
+            DriveType::MappedDevice
+                if std::fs::read_to_string(self.sysfs_path.join("dm/uuid"))
+                    .is_ok_and(|uuid| uuid.starts_with("LVM-")) =>
+            {
+                Self::entry_names(&self.sysfs_path.join("slaves"))
+            }
+            DriveType::BtrfsFilesystem => Self::entry_names(&self.sysfs_path.join("devices")),
+            DriveType::BcachefsFilesystem => std::fs::read_dir(&self.sysfs_path)
+                .into_iter()
+                .flatten()
+                .flatten()
+                .filter(|entry| entry.file_name().to_string_lossy().starts_with("dev-"))
+                .filter_map(|entry| {
+                    let block = std::fs::canonicalize(entry.path().join("block")).ok()?;
+                    Some(block.file_name()?.to_string_lossy().to_string())
+                })
+                .collect(),
+            DriveType::ZfsPool => ZFS_POOL_MEMBERS
+                .read()
+                .ok()
+                .and_then(|zfs_pool_members| zfs_pool_members.get(&self.block_device).cloned())
+                .unwrap_or_else(|| {
+                    Self::zfs_pool_members()
+                        .remove(&self.block_device)
+                        .unwrap_or_default()
+                }),
+            _ => Vec::new(),
+        };
+
+        members.sort();
+        members.dedup();
+        members
+    }
+
+    /// Returns the names of the drives the members of this drive are on, e.g. `sda` for the member `sda2`
+    pub fn member_drives(&self) -> Vec<String> {
+        let mut drives: Vec<String> = self
+            .members
+            .iter()
+            .filter_map(|member| Self::drive_of_block_device(member))
+            .collect();
+
+        drives.sort();
+        drives.dedup();
+        drives
+    }
+
+    fn entry_names(path: &Path) -> Vec<String> {
+        std::fs::read_dir(path)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect()
+    }
+
+    /// Returns the members of all ZFS pools by the pools' names. ZFS doesn't expose the devices of a pool in sysfs, but
+    /// udev's blkid probe marks them as `zfs_member`s and labels them with the name of the pool.
+    fn zfs_pool_members() -> HashMap<String, Vec<String>> {
+        let mut zfs_pool_members: HashMap<String, Vec<String>> = HashMap::new();
+
+        for entry in std::fs::read_dir(PATH_UDEV_DATA)
+            .into_iter()
+            .flatten()
+            .flatten()
+        {
+            // block devices are named like "b259:3"
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let Some(dev) = file_name.strip_prefix('b') else {
+                continue;
+            };
+
+            let Ok(data) = std::fs::read_to_string(entry.path()) else {
+                continue;
+            };
+
+            let properties = Self::parse_udev_properties(&data);
+            let (Some("zfs_member"), Some(pool)) = (
+                properties.get("ID_FS_TYPE").copied(),
+                properties.get("ID_FS_LABEL").copied(),
+            ) else {
+                continue;
+            };
+
+            let Some(member) = std::fs::canonicalize(format!("/sys/dev/block/{dev}"))
+                .ok()
+                .and_then(|sysfs_path| Some(sysfs_path.file_name()?.to_string_lossy().to_string()))
+            else {
+                continue;
+            };
+
+            zfs_pool_members
+                .entry(pool.to_string())
+                .or_default()
+                .push(member);
+        }
+
+        zfs_pool_members
+    }
+
+    /// Parses the `E:KEY=value` lines of a udev database entry
+    fn parse_udev_properties(data: &str) -> HashMap<&str, &str> {
+        data.lines()
+            .filter_map(|line| line.strip_prefix("E:")?.split_once('='))
+            .collect()
+    }
+
+    /// Parses the contents of /proc/self/mounts into pairs of mount sources and mount points
+    fn parse_mounts(mounts: &str) -> Vec<(String, PathBuf)> {
+        mounts
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                let source = fields.next()?;
+                let mount_point = fields.next()?;
+                Some((
+                    Self::unescape_mount_field(source),
+                    PathBuf::from(Self::unescape_mount_field(mount_point)),
+                ))
+            })
+            .collect()
+    }
+
+    /// The kernel escapes spaces, tabs, newlines and backslashes in /proc/self/mounts as three-digit octal numbers,
+    /// e.g. "\040" for a space
+    fn unescape_mount_field(field: &str) -> String {
+        let bytes = field.as_bytes();
+        let mut unescaped = Vec::with_capacity(bytes.len());
+
+        let mut i = 0;
+        while i < bytes.len() {
+            let escaped = bytes
+                .get(i + 1..i + 4)
+                .filter(|digits| {
+                    bytes[i] == b'\\' && digits.iter().all(|d| (b'0'..=b'7').contains(d))
+                })
+                .and_then(|digits| u8::from_str_radix(std::str::from_utf8(digits).ok()?, 8).ok());
+
+            if let Some(escaped) = escaped {
+                unescaped.push(escaped);
+                i += 4;
+            } else {
+                unescaped.push(bytes[i]);
+                i += 1;
+            }
+        }
+
+        String::from_utf8_lossy(&unescaped).to_string()
+    }
+
+    /// Returns a name that tells the drive apart from others of its kind, i.e. its model, the name of the file backing
+    /// it or its block device
+    pub fn description(&self) -> String {
+        if let Some(model) = &self.model {
+            model.clone()
+        } else if let Some(file_name) = self.backing_file.as_deref().and_then(Path::file_name) {
+            file_name.to_string_lossy().to_string()
+        } else {
+            self.block_device.clone()
+        }
+    }
+
+    pub fn display_name(&self) -> String {
+        let capacity_formatted = convert_storage(self.capacity().unwrap_or_default() as f64, true);
+        match self.drive_type {
+            DriveType::CdDvdBluray => i18n("CD/DVD/Blu-ray Drive"),
+            DriveType::Floppy => i18n("Floppy Drive"),
+            DriveType::LoopDevice => i18n_f("{} Loop Device", &[&capacity_formatted]),
+            DriveType::MappedDevice => i18n_f("{} Mapped Device", &[&capacity_formatted]),
+            DriveType::Raid => i18n_f("{} RAID", &[&capacity_formatted]),
+            DriveType::RamDisk => i18n_f("{} RAM Disk", &[&capacity_formatted]),
+            DriveType::Zram => i18n_f("{} zram Device", &[&capacity_formatted]),
+            DriveType::ZfsVolume => i18n_f("{} ZFS Volume", &[&capacity_formatted]),
+            DriveType::BtrfsFilesystem => i18n_f("{} Btrfs Filesystem", &[&capacity_formatted]),
+            DriveType::BcachefsFilesystem => {
+                i18n_f("{} bcachefs Filesystem", &[&capacity_formatted])
+            }
+            DriveType::ZfsPool => i18n_f("{} ZFS Pool", &[&capacity_formatted]),
+            _ => i18n_f("{} Drive", &[&capacity_formatted]),
+        }
+    }
+
+    /// Returns the current SysFS stats for the drive
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the are errors during
+    /// reading or parsing
+    pub fn sys_stats(&self) -> Result<HashMap<String, usize>> {
+        if self.is_multi_device_filesystem() {
+            let member_stats = self
+                .members
+                .iter()
+                .map(|member| Self::parse_stats(&Path::new(PATH_SYSFS_CLASS).join(member), member))
+                .collect::<Result<Vec<_>>>()?;
+
+            return Ok(Self::aggregate_stats(&member_stats));
+        }
+
+        Self::parse_stats(&self.sysfs_path, &self.block_device)
+    }
+
+    fn parse_stats(sysfs_path: &Path, block_device: &str) -> Result<HashMap<String, usize>> {
+        let stat = std::fs::read_to_string(sysfs_path.join("stat"))
+            .with_context(|| format!("unable to read /sys/block/{block_device}/stat"))?;
+
+        let captures = RE_DRIVE
+            .captures(&stat)
+            .with_context(|| format!("unable to parse /sys/block/{block_device}/stat"))?;
+
+        Ok(RE_DRIVE
+            .capture_names()
+            .flatten()
+            .filter_map(|named_capture| {
+                Some((
+                    named_capture.to_string(),
+                    captures.name(named_capture)?.as_str().parse().ok()?,
+                ))
+            })
+            .collect())
+    }
+
+    /// Combines the stats of the members of a multi-device filesystem. Counters are summed up so that the throughput
+    /// of the whole filesystem is shown (mirrored writes count once per member), but the time spent on I/O is taken
+    /// from the busiest member since the members work in parallel.
+    fn aggregate_stats(member_stats: &[HashMap<String, usize>]) -> HashMap<String, usize> {
+        let mut stats: HashMap<String, usize> = HashMap::new();
+
+        for (name, value) in member_stats.iter().flatten() {
+            let aggregated = stats.entry(name.clone()).or_default();
+            if name.ends_with("_ticks") || name == "time_in_queue" {
+                *aggregated = (*aggregated).max(*value);
+            } else {
+                *aggregated = aggregated.saturating_add(*value);
+            }
+        }
+
+        stats
+    }
+
+    fn drive_type(&self) -> Result<DriveType> {
+        if self.sysfs_path.starts_with(PATH_SYSFS_BTRFS) {
+            Ok(DriveType::BtrfsFilesystem)
+        } else if self.sysfs_path.starts_with(PATH_SYSFS_BCACHEFS) {
+            Ok(DriveType::BcachefsFilesystem)
+        } else if self.sysfs_path.starts_with(PATH_ZFS_KSTAT) {
+            Ok(DriveType::ZfsPool)
+        } else if self.block_device.starts_with("nvme") {
+            Ok(DriveType::Nvme)
+        } else if self.block_device.starts_with("mmc") {
+            Ok(DriveType::Emmc)
+        } else if self.block_device.starts_with("fd") {
+            Ok(DriveType::Floppy)
+        } else if self.block_device.starts_with("sr") {
+            Ok(DriveType::CdDvdBluray)
+        } else if self.block_device.starts_with("zram") {
+            Ok(DriveType::Zram)
+        } else if self.block_device.starts_with("md") {
+            Ok(DriveType::Raid)
+        } else if self.block_device.starts_with("loop") {
+            Ok(DriveType::LoopDevice)
+        } else if self.block_device.starts_with("dm") {
+            Ok(DriveType::MappedDevice)
+        } else if self.block_device.starts_with("ram") {
+            Ok(DriveType::RamDisk)
+        } else if self.block_device.starts_with("zd") {
+            Ok(DriveType::ZfsVolume)
+        } else if let Ok(rotational) =
+            std::fs::read_to_string(self.sysfs_path.join("queue/rotational"))
+        {
+            // turn rot into a boolean
+            let rotational = rotational
+                .replace('\n', "")
+                .parse::<u8>()
+                .map(|rot| rot != 0)?;
+            if rotational {
+                Ok(DriveType::Hdd)
+            } else if self.removable()? {
+                Ok(DriveType::Flash)
+            } else {
+                Ok(DriveType::Ssd)
+            }
+        } else {
+            Ok(DriveType::Unknown)
+        }
+    }
+
+    /// Returns, whether the drive is removable
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the are errors during
+    /// reading or parsing
+    pub fn removable(&self) -> Result<bool> {
+        if self.is_multi_device_filesystem() {
+            return self
+                .member_sysfs_paths()
+                .map(|path| Self::parse_removable(&path))
+                .collect::<Result<Vec<_>>>()
+                .map(|removable| removable.into_iter().any(|removable| removable));
+        }
+
+        Self::parse_removable(&self.sysfs_path)
+    }
+
+    fn parse_removable(sysfs_path: &Path) -> Result<bool> {
+        std::fs::read_to_string(sysfs_path.join("removable"))?
+            .replace('\n', "")
+            .parse::<u8>()
+            .map(|rem| rem != 0)
+            .context("unable to parse removable sysfs file")
+    }
+
+    /// Returns, whether the drive is writable
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the are errors during
+    /// reading or parsing
+    pub fn writable(&self) -> Result<bool> {
+        if self.is_multi_device_filesystem() {
+            return self
+                .member_sysfs_paths()
+                .map(|path| Self::parse_writable(&path))
+                .collect::<Result<Vec<_>>>()
+                .map(|writable| writable.into_iter().all(|writable| writable));
+        }
+
+        Self::parse_writable(&self.sysfs_path)
+    }
+
+    fn parse_writable(sysfs_path: &Path) -> Result<bool> {
+        std::fs::read_to_string(sysfs_path.join("ro"))?
+            .replace('\n', "")
+            .parse::<u8>()
+            .map(|ro| ro == 0)
+            .context("unable to parse ro sysfs file")
+    }
+
+    /// Returns the capacity of the drive **in bytes**
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the are errors during
+    /// reading or parsing
+    pub fn capacity(&self) -> Result<u64> {
+        if self.is_multi_device_filesystem() {
+            return self
+                .member_sysfs_paths()
+                .map(|path| Self::parse_capacity(&path))
+                .sum();
+        }
+
+        Self::parse_capacity(&self.sysfs_path)
+    }
+
+    fn parse_capacity(sysfs_path: &Path) -> Result<u64> {
+        std::fs::read_to_string(sysfs_path.join("size"))?
+            .replace('\n', "")
+            .parse::<u64>()
+            .map(|sectors| sectors * 512)
+            .context("unable to parse size sysfs file")
+    }
+
+    fn member_sysfs_paths(&self) -> impl Iterator<Item = PathBuf> + '_ {
+        self.members
+            .iter()
+            .map(|member| Path::new(PATH_SYSFS_CLASS).join(member))
+    }
+
+    /// Returns the temperature of the drive in °C, as reported by the hwmon of its NVMe controller or by the
+    /// `drivetemp` driver for SATA drives
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the drive has no temperature sensor or if there are errors during reading or parsing
+    pub fn temperature(&self) -> Result<f64> {
+        let sysfs_path = self.sysfs_path.to_string_lossy();
+
+        let temperature_path = [
+            format!("{sysfs_path}/device/hwmon*/temp1_input"),
+            format!("{sysfs_path}/device/hwmon/hwmon*/temp1_input"),
+        ]
+        .iter()
+        .filter_map(|pattern| glob(pattern).ok())
+        .flat_map(|paths| paths.flatten())
+        .next()
+        .context("no temperature sensor found")?;
+
+        std::fs::read_to_string(temperature_path)?
+            .trim()
+            .parse::<f64>()
+            .map(|millidegrees| millidegrees / 1000.0)
+            .context("unable to parse temp1_input sysfs file")
+    }
+
+    /// Returns the model information of the drive
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the are errors during
+    /// reading or parsing
+    pub fn model(&self) -> Result<String> {
+        // btrfs filesystems may have a label, which describes them better than their UUID
+        if self.drive_type == DriveType::BtrfsFilesystem {
+            return std::fs::read_to_string(self.sysfs_path.join("label"))
+                .context("unable to parse label sysfs file");
+        }
+
+        std::fs::read_to_string(self.sysfs_path.join("device/model"))
+            .context("unable to parse model sysfs file")
+    }
+
+    /// Returns the file that a loop device is backed by, e.g. the image of a Snap package
+    pub fn backing_file(&self) -> Option<PathBuf> {
+        std::fs::read_to_string(self.sysfs_path.join("loop/backing_file"))
+            .ok()
+            .and_then(|backing_file| Self::parse_backing_file(&backing_file))
+    }
+
+    fn parse_backing_file(backing_file: &str) -> Option<PathBuf> {
+        let backing_file = backing_file.trim_end_matches('\n');
+
+        if backing_file.is_empty() {
+            None
+        } else {
+            Some(PathBuf::from(backing_file))
+        }
+    }
+
+    /// Returns the World-Wide Identification of the drive
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the are errors during
+    /// reading or parsing
+    pub fn wwid(&self) -> Result<String> {
+        std::fs::read_to_string(self.sysfs_path.join("device/wwid"))
+            .context("unable to parse wwid sysfs file")
+    }
+
+    /// Returns an ID that keeps identifying the drive after reboots or reconnects, unlike its block device name which
+    /// depends on the order the drives have been detected in
+    pub fn stable_id(&self) -> String {
+        // the UUID (or pool name) of multi-device filesystems is stable already
+        let id = if self.is_multi_device_filesystem() {
+            None
+        } else {
+            ["device/wwid", "wwid", "device/serial", "md/uuid", "dm/uuid"]
+                .iter()
+                .filter_map(|file| std::fs::read_to_string(self.sysfs_path.join(file)).ok())
+                .map(|id| id.trim().to_string())
+                .find(|id| !id.is_empty())
+        };
+
+        format!("drive:{}", id.unwrap_or_else(|| self.block_device.clone()))
+    }
+
+    /// Returns the appropriate Icon for the type of drive
+    pub fn icon(&self) -> Icon {
+        match self.drive_type {
+            DriveType::CdDvdBluray => ThemedIcon::new("cd-dvd-bluray-symbolic").into(),
+            DriveType::Emmc => ThemedIcon::new("emmc-symbolic").into(),
+            DriveType::Flash => ThemedIcon::new("flash-storage-symbolic").into(),
+            DriveType::Floppy => ThemedIcon::new("floppy-symbolic").into(),
+            DriveType::Hdd => ThemedIcon::new("hdd-symbolic").into(),
+            DriveType::LoopDevice => ThemedIcon::new("loop-device-symbolic").into(),
+            DriveType::MappedDevice => ThemedIcon::new("mapped-device-symbolic").into(),
+            DriveType::Nvme => ThemedIcon::new("nvme-symbolic").into(),
+            DriveType::Raid => ThemedIcon::new("raid-symbolic").into(),
+            DriveType::RamDisk => ThemedIcon::new("ram-disk-symbolic").into(),
+            DriveType::Ssd => ThemedIcon::new("ssd-symbolic").into(),
+            DriveType::ZfsVolume => ThemedIcon::new("zfs-symbolic").into(),
+            DriveType::Zram => ThemedIcon::new("zram-symbolic").into(),
+            DriveType::BtrfsFilesystem | DriveType::BcachefsFilesystem => {
+                ThemedIcon::new("raid-symbolic").into()
+            }
+            DriveType::ZfsPool => ThemedIcon::new("zfs-symbolic").into(),
+            DriveType::Unknown => Self::default_icon(),
+        }
+    }
+
+    /// Whether the drive is a physical drive that might report SMART data
+    pub fn supports_smart(&self) -> bool {
+        matches!(
+            self.drive_type,
+            DriveType::Hdd | DriveType::Ssd | DriveType::Nvme
+        )
+    }
+
+    pub fn is_virtual(&self) -> bool {
+        // arrays and filesystems spanning several drives are shown like a physical drive so that their members may
+        // be hidden instead
+        if self.members.len() > 1 {
+            return false;
+        }
+
+        match self.drive_type {
+            DriveType::LoopDevice
+            | DriveType::MappedDevice
+            | DriveType::Raid
+            | DriveType::RamDisk
+            | DriveType::ZfsVolume
+            | DriveType::Zram => true,
+            _ => self.capacity().unwrap_or(0) == 0,
+        }
+    }
+
+    pub fn default_icon() -> Icon {
+        ThemedIcon::new("unknown-drive-type-symbolic").into()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::HashMap, path::PathBuf};
+
+    use pretty_assertions::assert_eq;
+
+    use super::{Drive, Filesystem};
+
+    fn stats(stats: &[(&str, usize)]) -> HashMap<String, usize> {
+        stats
+            .iter()
+            .map(|(name, value)| (name.to_string(), *value))
+            .collect()
+    }
+
+    #[test]
+    fn crypt_kind_from_dm_uuid_luks() {
+        assert_eq!(
+            Some("LUKS2".to_string()),
+            Drive::crypt_kind_from_dm_uuid(
+                "CRYPT-LUKS2-6b1f3a0cd4e54c3e8b0d2f1e9a7c5b3d-luks-6b1f3a0c\n"
+            )
+        );
+        assert_eq!(
+            Some("PLAIN".to_string()),
+            Drive::crypt_kind_from_dm_uuid("CRYPT-PLAIN-swap")
+        );
+    }
+
+    #[test]
+    fn crypt_kind_from_dm_uuid_not_encrypted() {
+        assert_eq!(
+            None,
+            Drive::crypt_kind_from_dm_uuid("LVM-Xq3f0hDdnT4nN2mJ1xJrGk0VbW8cN9gS")
+        );
+        assert_eq!(
+            None,
+            Drive::crypt_kind_from_dm_uuid("CRYPT-VERITY-4c5d0a3b-root-verity")
+        );
+        assert_eq!(None, Drive::crypt_kind_from_dm_uuid("CRYPT-INTEGRITY-data"));
+        assert_eq!(None, Drive::crypt_kind_from_dm_uuid(""));
+    }
+
+    #[test]
+    fn parse_backing_file_valid() {
+        assert_eq!(
+            Some(PathBuf::from("/var/lib/snapd/snaps/core22_1380.snap")),
+            Drive::parse_backing_file("/var/lib/snapd/snaps/core22_1380.snap\n")
+        );
+    }
+
+    #[test]
+    fn parse_backing_file_empty() {
+        assert_eq!(None, Drive::parse_backing_file("\n"));
+        assert_eq!(None, Drive::parse_backing_file(""));
+    }
+
+    #[test]
+    fn parse_mounts_valid() {
+        let mounts = "proc /proc proc rw,nosuid,nodev,noexec,relatime 0 0
+/dev/nvme0n1p2 / btrfs rw,relatime,ssd,subvol=/root 0 0
+/dev/sda1 /run/media/user/My\\040Drive ext4 rw,nosuid,nodev,relatime 0 0
+";
+
+        assert_eq!(
+            vec![
+                ("proc".to_string(), PathBuf::from("/proc")),
+                ("/dev/nvme0n1p2".to_string(), PathBuf::from("/")),
+                (
+                    "/dev/sda1".to_string(),
+                    PathBuf::from("/run/media/user/My Drive")
+                ),
+            ],
+            Drive::parse_mounts(mounts)
+        );
+    }
+
+    #[test]
+    fn parse_mounts_empty() {
+        assert_eq!(Vec::<(String, PathBuf)>::new(), Drive::parse_mounts(""));
+    }
+
+    #[test]
+    fn unescape_mount_field() {
+        assert_eq!("a b\\tc", Drive::unescape_mount_field("a\\040b\\134tc"));
+        assert_eq!("no\\escape\\", Drive::unescape_mount_field("no\\escape\\"));
+        assert_eq!("\\09", Drive::unescape_mount_field("\\09"));
+    }
+
+    #[test]
+    fn used_fraction() {
+        let filesystem = Filesystem {
+            block_device: "sda1".to_string(),
+            mount_point: PathBuf::from("/"),
+            total_bytes: 1000,
+            free_bytes: 200,
+            available_bytes: 150,
+        };
+
+        assert_eq!(800, filesystem.used_bytes());
+        assert_eq!(800.0 / 950.0, filesystem.used_fraction());
+    }
+
+    #[test]
+    fn used_fraction_empty_filesystem() {
+        let filesystem = Filesystem {
+            block_device: "sda1".to_string(),
+            mount_point: PathBuf::from("/"),
+            total_bytes: 0,
+            free_bytes: 0,
+            available_bytes: 0,
+        };
+
+        assert_eq!(0.0, filesystem.used_fraction());
+    }
+
+    #[test]
+    fn aggregate_stats() {
+        let member_stats = [
+            stats(&[("read_sectors", 100), ("write_ticks", 40), ("io_ticks", 50)]),
+            stats(&[("read_sectors", 200), ("write_ticks", 70), ("io_ticks", 20)]),
+        ];
+
+        assert_eq!(
+            stats(&[("read_sectors", 300), ("write_ticks", 70), ("io_ticks", 50)]),
+            Drive::aggregate_stats(&member_stats)
+        );
+    }
+
+    #[test]
+    fn parse_udev_properties() {
+        let data = "S:disk/by-id/ata-WDC_WD40EFRX\nI:12345678\nE:ID_FS_TYPE=zfs_member\nE:ID_FS_LABEL=tank\nE:ID_FS_UUID=1234=5678\nG:systemd\n";
+
+        let properties = Drive::parse_udev_properties(data);
+
+        assert_eq!(Some(&"zfs_member"), properties.get("ID_FS_TYPE"));
+        assert_eq!(Some(&"tank"), properties.get("ID_FS_LABEL"));
+        assert_eq!(Some(&"1234=5678"), properties.get("ID_FS_UUID"));
+        assert_eq!(3, properties.len());
+    }
+}
@@ -0,0 +1,522 @@
+//! SMART data of drives as gathered by UDisks, which polls the drives itself and, unlike reading the data directly,
+//! doesn't require elevated privileges
+
+use std::{
+    collections::HashMap,
+    sync::{LazyLock, Mutex},
+    time::{Duration, Instant},
+};
+
+use anyhow::{bail, Context, Result};
+use gtk::{
+    gio,
+    glib::{prelude::ToVariant, Variant, VariantTy},
+};
+use log::{trace, warn};
+
+const UDISKS_BUS_NAME: &str = "org.freedesktop.UDisks2";
+
+const UDISKS_BLOCK_DEVICES_PATH: &str = "/org/freedesktop/UDisks2/block_devices";
+
+const PROPERTIES_INTERFACE: &str = "org.freedesktop.DBus.Properties";
+
+const BLOCK_INTERFACE: &str = "org.freedesktop.UDisks2.Block";
+
+const ATA_INTERFACE: &str = "org.freedesktop.UDisks2.Drive.Ata";
+
+const NVME_CONTROLLER_INTERFACE: &str = "org.freedesktop.UDisks2.NVMe.Controller";
+
+const DBUS_TIMEOUT_MS: i32 = 1000;
+
+const ABSOLUTE_ZERO_CELSIUS: f64 = -273.15;
+
+/// UDisks only polls the SMART data of drives every few minutes, so there's no need to ask it on every refresh
+const CACHE_DURATION: Duration = Duration::from_secs(60);
+
+static SYSTEM_BUS: LazyLock<Option<gio::DBusConnection>> = LazyLock::new(|| {
+    gio::bus_get_sync(gio::BusType::System, None::<&gio::Cancellable>)
+        .inspect_err(|err| {
+            warn!("Unable to connect to the system bus, SMART data won't be available: {err}")
+        })
+        .ok()
+});
+
+#[allow(clippy::type_complexity)]
+static CACHE: LazyLock<Mutex<HashMap<String, (Instant, Option<SmartData>)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// The most important SMART values of a drive
+#[derive(Debug, Clone, PartialEq)]
+pub struct SmartData {
+    /// Whether the drive itself predicts a failure or, for NVMe drives, reports critical warnings
+    pub failing: bool,
+    /// Temperature in °C
+    pub temperature: Option<f64>,
+    pub power_on_hours: Option<u64>,
+    /// Estimate of how much of the drive's endurance has been used up in percent, only reported by NVMe drives
+    pub percentage_used: Option<u8>,
+}
+
+/// The value of a SMART attribute, converted into a sensible unit
+#[derive(Debug, Clone, PartialEq)]
+pub enum SmartValue {
+    Count(u64),
+    Seconds(f64),
+    /// Temperatures in °C
+    Temperatures(Vec<f64>),
+    Sectors(u64),
+    Bytes(u64),
+    Percentage(u8),
+    Other(String),
+}
+
+/// A raw SMART attribute as listed in the details of a drive
+#[derive(Debug, Clone, PartialEq)]
+pub struct SmartAttribute {
+    /// Only ATA attributes have an ID
+    pub id: Option<u8>,
+    pub name: String,
+    /// The normalized value, the worst normalized value so far and the threshold at which the attribute is
+    /// considered to be failing, only ATA attributes have these
+    pub normalized: Option<(i32, i32, i32)>,
+    pub value: SmartValue,
+    pub failing: bool,
+}
+
+impl SmartData {
+    /// Like [`SmartData::read`], but only asks UDisks again once the last answer is older than a minute
+    pub fn read_cached<S: AsRef<str>>(block_device: S) -> Option<Self> {
+        let block_device = block_device.as_ref();
+
+        if let Some((timestamp, smart_data)) = CACHE.lock().ok()?.get(block_device) {
+            if timestamp.elapsed() < CACHE_DURATION {
+                return smart_data.clone();
+            }
+        }
+
+        // the cache is unlocked while asking UDisks, so that a slow drive doesn't hold up the others
+        let smart_data = Self::read(block_device)
+            .inspect_err(|err| trace!("No SMART data for {block_device}: {err}"))
+            .ok();
+
+        if let Ok(mut cache) = CACHE.lock() {
+            cache.insert(
+                block_device.to_string(),
+                (Instant::now(), smart_data.clone()),
+            );
+        }
+
+        smart_data
+    }
+
+    /// Reads the SMART data of the drive whose block device is called `block_device`, e.g. `sda` or `nvme0n1`
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if UDisks isn't available, the drive doesn't support SMART or UDisks hasn't read its SMART
+    /// data yet
+    pub fn read<S: AsRef<str>>(block_device: S) -> Result<Self> {
+        let drive_path = drive_object_path(block_device.as_ref())?;
+
+        if let Ok(properties) = properties(&drive_path, ATA_INTERFACE) {
+            return Self::from_ata(&properties);
+        }
+
+        let properties = properties(&drive_path, NVME_CONTROLLER_INTERFACE)?;
+        let percentage_used = call_smart_get_attributes(&drive_path, NVME_CONTROLLER_INTERFACE)
+            .ok()
+            .and_then(|reply| reply.get::<(HashMap<String, Variant>,)>())
+            .and_then(|(attributes,)| attributes.get("percent_used")?.get::<u8>());
+
+        let mut smart_data = Self::from_nvme(&properties)?;
+        smart_data.percentage_used = percentage_used;
+
+        Ok(smart_data)
+    }
+
+    fn from_ata(properties: &HashMap<String, Variant>) -> Result<Self> {
+        let get = |name: &str| properties.get(name);
+
+        if !get("SmartSupported")
+            .and_then(Variant::get::<bool>)
+            .unwrap_or_default()
+            || !get("SmartEnabled")
+                .and_then(Variant::get::<bool>)
+                .unwrap_or_default()
+        {
+            bail!("SMART is not supported or not enabled");
+        }
+
+        if get("SmartUpdated")
+            .and_then(Variant::get::<u64>)
+            .unwrap_or_default()
+            == 0
+        {
+            bail!("SMART data has not been read yet");
+        }
+
+        // 0 means unknown for both of these
+        let temperature = get("SmartTemperature")
+            .and_then(Variant::get::<f64>)
+            .filter(|kelvin| *kelvin > 0.0)
+            .map(|kelvin| kelvin + ABSOLUTE_ZERO_CELSIUS);
+        let power_on_hours = get("SmartPowerOnSeconds")
+            .and_then(Variant::get::<u64>)
+            .filter(|seconds| *seconds > 0)
+            .map(|seconds| seconds / 3600);
+
+        Ok(Self {
+            failing: get("SmartFailing")
+                .and_then(Variant::get::<bool>)
+                .unwrap_or_default(),
+            temperature,
+            power_on_hours,
+            percentage_used: None,
+        })
+    }
+
+    fn from_nvme(properties: &HashMap<String, Variant>) -> Result<Self> {
+        let get = |name: &str| properties.get(name);
+
+        if get("SmartUpdated")
+            .and_then(Variant::get::<u64>)
+            .unwrap_or_default()
+            == 0
+        {
+            bail!("SMART data has not been read yet");
+        }
+
+        let failing = get("SmartCriticalWarning")
+            .and_then(Variant::get::<Vec<String>>)
+            .is_some_and(|warnings| !warnings.is_empty());
+
+        let temperature = get("SmartTemperature")
+            .and_then(Variant::get::<u16>)
+            .filter(|kelvin| *kelvin > 0)
+            .map(|kelvin| f64::from(kelvin) + ABSOLUTE_ZERO_CELSIUS);
+
+        Ok(Self {
+            failing,
+            temperature,
+            power_on_hours: get("SmartPowerOnHours").and_then(Variant::get::<u64>),
+            percentage_used: None,
+        })
+    }
+}
+
+impl SmartAttribute {
+    /// Reads all SMART attributes of the drive whose block device is called `block_device`
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if UDisks isn't available or the drive doesn't support SMART
+    pub fn read_all<S: AsRef<str>>(block_device: S) -> Result<Vec<Self>> {
+        let drive_path = drive_object_path(block_device.as_ref())?;
+
+        if let Ok(reply) = call_smart_get_attributes(&drive_path, ATA_INTERFACE) {
+            let (attributes,) = reply
+                .get::<(Vec<AtaAttribute>,)>()
+                .context("unexpected reply to SmartGetAttributes")?;
+            return Ok(attributes.into_iter().map(Self::from_ata).collect());
+        }
+
+        let (attributes,) = call_smart_get_attributes(&drive_path, NVME_CONTROLLER_INTERFACE)?
+            .get::<(HashMap<String, Variant>,)>()
+            .context("unexpected reply to SmartGetAttributes")?;
+
+        let mut attributes = attributes
+            .into_iter()
+            .map(|(name, value)| Self::from_nvme(name, &value))
+            .collect::<Vec<_>>();
+        attributes.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(attributes)
+    }
+
+    fn from_ata(
+        (id, name, _flags, value, worst, threshold, pretty, pretty_unit, _expansion): AtaAttribute,
+    ) -> Self {
+        // negative normalized values mean that they're unknown
+        let failing = value >= 0 && threshold > 0 && value <= threshold;
+
+        let pretty = u64::try_from(pretty).unwrap_or_default();
+        let value_in_unit = match pretty_unit {
+            1 => SmartValue::Count(pretty),
+            2 => SmartValue::Seconds(pretty as f64 / 1000.0),
+            3 => SmartValue::Sectors(pretty),
+            4 => SmartValue::Temperatures(vec![pretty as f64 / 1000.0 + ABSOLUTE_ZERO_CELSIUS]),
+            _ => SmartValue::Other(pretty.to_string()),
+        };
+
+        Self {
+            id: Some(id),
+            name,
+            normalized: (value >= 0).then_some((value, worst, threshold)),
+            value: value_in_unit,
+            failing,
+        }
+    }
+
+    fn from_nvme(name: String, value: &Variant) -> Self {
+        let kelvin_to_celsius = |kelvin: u16| f64::from(kelvin) + ABSOLUTE_ZERO_CELSIUS;
+
+        let smart_value = match name.as_str() {
+            "avail_spare" | "spare_thresh" | "percent_used" => {
+                value.get::<u8>().map(SmartValue::Percentage)
+            }
+            "total_data_read" | "total_data_written" => value.get::<u64>().map(SmartValue::Bytes),
+            "ctrl_busy_time" => value
+                .get::<u64>()
+                .map(|minutes| SmartValue::Seconds(minutes as f64 * 60.0)),
+            "warning_temp_time" | "critical_temp_time" => value
+                .get::<i32>()
+                .map(|minutes| SmartValue::Seconds(f64::from(minutes) * 60.0)),
+            "wctemp" | "cctemp" => value
+                .get::<u16>()
+                .map(|kelvin| SmartValue::Temperatures(vec![kelvin_to_celsius(kelvin)])),
+            "temp_sensors" => value.get::<Vec<u16>>().map(|temperatures| {
+                SmartValue::Temperatures(
+                    temperatures
+                        .into_iter()
+                        // unimplemented sensors report 0 K
+                        .filter(|kelvin| *kelvin > 0)
+                        .map(kelvin_to_celsius)
+                        .collect(),
+                )
+            }),
+            _ => value.get::<u64>().map(SmartValue::Count),
+        }
+        .unwrap_or_else(|| SmartValue::Other(value.print(false).to_string()));
+
+        let failing = match (name.as_str(), &smart_value) {
+            ("media_errors", SmartValue::Count(count)) => *count > 0,
+            ("percent_used", SmartValue::Percentage(percentage)) => *percentage >= 100,
+            _ => false,
+        };
+
+        Self {
+            id: None,
+            name,
+            normalized: None,
+            value: smart_value,
+            failing,
+        }
+    }
+}
+
+/// `(id, name, flags, value, worst, threshold, pretty, pretty_unit, expansion)` as returned by UDisks
+type AtaAttribute = (
+    u8,
+    String,
+    u16,
+    i32,
+    i32,
+    i32,
+    i64,
+    i32,
+    HashMap<String, Variant>,
+);
+
+/// UDisks escapes every character of an object path segment that isn't alphanumeric as `_xx`, `xx` being its hex
+/// value
+fn escape_object_path_segment(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|byte| {
+            if byte.is_ascii_alphanumeric() {
+                char::from(byte).to_string()
+            } else {
+                format!("_{byte:02x}")
+            }
+        })
+        .collect()
+}
+
+fn drive_object_path(block_device: &str) -> Result<String> {
+    let block_path = format!(
+        "{UDISKS_BLOCK_DEVICES_PATH}/{}",
+        escape_object_path_segment(block_device)
+    );
+
+    trace!("Looking up the UDisks drive of {block_path}");
+
+    let reply = call(
+        &block_path,
+        PROPERTIES_INTERFACE,
+        "Get",
+        Some(&(BLOCK_INTERFACE, "Drive").to_variant()),
+        "(v)",
+    )?;
+
+    let drive_path = reply
+        .child_value(0)
+        .as_variant()
+        .and_then(|drive| drive.str().map(str::to_string))
+        .context("unexpected reply to Get")?;
+
+    // block devices without a drive (like partitions or loop devices) point to the root
+    if drive_path == "/" {
+        bail!("{block_device} has no UDisks drive");
+    }
+
+    Ok(drive_path)
+}
+
+fn properties(object_path: &str, interface: &str) -> Result<HashMap<String, Variant>> {
+    let (properties,) = call(
+        object_path,
+        PROPERTIES_INTERFACE,
+        "GetAll",
+        Some(&(interface,).to_variant()),
+        "(a{sv})",
+    )?
+    .get::<(HashMap<String, Variant>,)>()
+    .context("unexpected reply to GetAll")?;
+
+    if properties.is_empty() {
+        bail!("{object_path} doesn't implement {interface}");
+    }
+
+    Ok(properties)
+}
+
+fn call_smart_get_attributes(drive_path: &str, interface: &str) -> Result<Variant> {
+    let reply_type = if interface == ATA_INTERFACE {
+        "(a(ysqiiixia{sv}))"
+    } else {
+        "(a{sv})"
+    };
+
+    call(
+        drive_path,
+        interface,
+        "SmartGetAttributes",
+        Some(&(HashMap::<String, Variant>::new(),).to_variant()),
+        reply_type,
+    )
+}
+
+fn call(
+    object_path: &str,
+    interface: &str,
+    method: &str,
+    parameters: Option<&Variant>,
+    reply_type: &str,
+) -> Result<Variant> {
+    let connection = SYSTEM_BUS
+        .as_ref()
+        .context("no connection to the system bus")?;
+
+    connection
+        .call_sync(
+            Some(UDISKS_BUS_NAME),
+            object_path,
+            interface,
+            method,
+            parameters,
+            Some(VariantTy::new(reply_type)?),
+            gio::DBusCallFlags::NONE,
+            DBUS_TIMEOUT_MS,
+            None::<&gio::Cancellable>,
+        )
+        .with_context(|| format!("unable to call {interface}.{method} on {object_path}"))
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use gtk::glib::{prelude::ToVariant, Variant};
+    use pretty_assertions::assert_eq;
+
+    use super::{escape_object_path_segment, SmartAttribute, SmartData, SmartValue};
+
+    #[test]
+    fn escape_segment() {
+        assert_eq!("nvme0n1", escape_object_path_segment("nvme0n1"));
+        assert_eq!("dm_2d0", escape_object_path_segment("dm-0"));
+    }
+
+    #[test]
+    fn ata_smart_data() {
+        let properties: HashMap<String, Variant> = [
+            ("SmartSupported", true.to_variant()),
+            ("SmartEnabled", true.to_variant()),
+            ("SmartUpdated", 1_700_000_000u64.to_variant()),
+            ("SmartFailing", false.to_variant()),
+            ("SmartTemperature", 308.15f64.to_variant()),
+            ("SmartPowerOnSeconds", (1234u64 * 3600 + 59).to_variant()),
+        ]
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value))
+        .collect();
+
+        let smart_data = SmartData::from_ata(&properties).unwrap();
+
+        assert!(!smart_data.failing);
+        assert_eq!(Some(35.0), smart_data.temperature.map(f64::round));
+        assert_eq!(Some(1234), smart_data.power_on_hours);
+        assert_eq!(None, smart_data.percentage_used);
+    }
+
+    #[test]
+    fn ata_smart_data_not_read() {
+        let properties: HashMap<String, Variant> = [
+            ("SmartSupported", true.to_variant()),
+            ("SmartEnabled", true.to_variant()),
+            ("SmartUpdated", 0u64.to_variant()),
+        ]
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value))
+        .collect();
+
+        assert!(SmartData::from_ata(&properties).is_err());
+    }
+
+    #[test]
+    fn ata_attribute_failing() {
+        let attribute = SmartAttribute::from_ata((
+            5,
+            "reallocated-sector-count".to_string(),
+            0x33,
+            9,
+            9,
+            10,
+            2048,
+            3,
+            HashMap::new(),
+        ));
+
+        assert_eq!(
+            SmartAttribute {
+                id: Some(5),
+                name: "reallocated-sector-count".to_string(),
+                normalized: Some((9, 9, 10)),
+                value: SmartValue::Sectors(2048),
+                failing: true,
+            },
+            attribute
+        );
+    }
+
+    #[test]
+    fn nvme_attributes() {
+        assert_eq!(
+            SmartValue::Percentage(3),
+            SmartAttribute::from_nvme("percent_used".to_string(), &3u8.to_variant()).value
+        );
+
+        let SmartValue::Temperatures(temperatures) =
+            SmartAttribute::from_nvme("temp_sensors".to_string(), &vec![313u16, 0].to_variant())
+                .value
+        else {
+            panic!("temp_sensors are no temperatures");
+        };
+        assert_eq!(
+            vec![40.0],
+            temperatures.into_iter().map(f64::round).collect::<Vec<_>>()
+        );
+
+        assert!(SmartAttribute::from_nvme("media_errors".to_string(), &1u64.to_variant()).failing);
+    }
+}
@@ -1,4 +1,4 @@
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
 use lazy_regex::{lazy_regex, Lazy, Regex};
 use log::{debug, trace, warn};
 use process_data::GpuIdentifier;
@@ -10,7 +10,11 @@ use crate::utils::{
     IS_FLATPAK,
 };
 
-use super::GpuImpl;
+use super::{DpmStates, EccErrors, FanSpeed, GpuImpl};
+
+static RE_RAS_UNCORRECTED: Lazy<Regex> = lazy_regex!(r"ue:\s*(\d+)");
+
+static RE_RAS_CORRECTED: Lazy<Regex> = lazy_regex!(r"ce:\s*(\d+)");
 
 static RE_AMDGPU_IDS: Lazy<Regex> = lazy_regex!(r"([0-9A-F]{4}),\s*([0-9A-F]{2}),\s*(.*)");
 
@@ -57,6 +61,41 @@ impl AmdGpu {
         gpu
     }
 
+    /// Parses one of amdgpu's RAS `*_err_count` files, which look like this:
+    /// ```text
+    /// ue: 0
+    /// ce: 0
+    /// ```
+    fn parse_ras_err_count<S: AsRef<str>>(content: S) -> Result<EccErrors> {
+        let content = content.as_ref();
+
+        let uncorrected = RE_RAS_UNCORRECTED
+            .captures(content)
+            .and_then(|captures| captures.get(1))
+            .context("no uncorrected error count found")?
+            .as_str()
+            .parse()?;
+
+        let corrected = RE_RAS_CORRECTED
+            .captures(content)
+            .and_then(|captures| captures.get(1))
+            .context("no corrected error count found")?
+            .as_str()
+            .parse()?;
+
+        Ok(EccErrors {
+            corrected,
+            uncorrected,
+        })
+    }
+
+    fn read_dpm_states(&self, file: &str) -> Result<DpmStates> {
+        // can't use read_device_file() here since it strips the line breaks between the states
+        let path = self.sysfs_path.join("device").join(file);
+        trace!("Reading {path:?}…");
+        DpmStates::parse(std::fs::read_to_string(&path)?)
+    }
+
     pub fn read_libdrm_ids() -> Result<HashMap<(u16, u8), String>> {
         let path = if *IS_FLATPAK {
             PathBuf::from("/run/host/usr/share/libdrm/amdgpu.ids")
@@ -164,6 +203,10 @@ impl GpuImpl for AmdGpu {
         self.hwmon_power_usage()
     }
 
+    fn board_power_usage(&self) -> Result<f64> {
+        self.hwmon_board_power_usage()
+    }
+
     fn core_frequency(&self) -> Result<f64> {
         self.hwmon_core_frequency()
     }
@@ -172,6 +215,14 @@ impl GpuImpl for AmdGpu {
         self.hwmon_vram_frequency()
     }
 
+    fn core_dpm_states(&self) -> Result<DpmStates> {
+        self.read_dpm_states("pp_dpm_sclk")
+    }
+
+    fn vram_dpm_states(&self) -> Result<DpmStates> {
+        self.read_dpm_states("pp_dpm_mclk")
+    }
+
     fn power_cap(&self) -> Result<f64> {
         self.hwmon_power_cap()
     }
@@ -179,4 +230,44 @@ impl GpuImpl for AmdGpu {
     fn power_cap_max(&self) -> Result<f64> {
         self.hwmon_power_cap_max()
     }
+
+    fn ecc_errors(&self) -> Result<EccErrors> {
+        // the ras directory only exists for GPUs that support ECC, umc refers to the memory controller
+        Self::parse_ras_err_count(self.read_device_file("ras/umc_err_count")?)
+    }
+
+    fn fan_speed(&self) -> Result<FanSpeed> {
+        self.hwmon_fan_speed()
+    }
+
+    fn fan_speed_max(&self) -> Result<f64> {
+        self.hwmon_fan_speed_max()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::utils::gpu::EccErrors;
+
+    use super::AmdGpu;
+
+    #[test]
+    fn parse_ras_err_count_valid() {
+        let content = concat!("ue: 2\n", "ce: 13\n");
+
+        assert_eq!(
+            AmdGpu::parse_ras_err_count(content).unwrap(),
+            EccErrors {
+                corrected: 13,
+                uncorrected: 2
+            }
+        );
+    }
+
+    #[test]
+    fn parse_ras_err_count_invalid() {
+        assert!(AmdGpu::parse_ras_err_count("ce: 13\n").is_err());
+    }
 }
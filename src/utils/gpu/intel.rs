@@ -1,11 +1,36 @@
-use anyhow::{bail, Result};
-use process_data::GpuIdentifier;
+use anyhow::{bail, Context, Result};
+use log::{debug, trace, warn};
+use process_data::{pci_slot::PciSlot, GpuIdentifier};
 
-use std::path::PathBuf;
+use std::{
+    io,
+    os::fd::{AsRawFd, FromRawFd, OwnedFd},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 
 use crate::utils::pci::Device;
 
-use super::GpuImpl;
+use super::{DpmStates, EccErrors, FanSpeed, GpuImpl};
+
+const PATH_EVENT_SOURCES: &str = "/sys/bus/event_source/devices";
+
+/// Intel always puts its integrated GPUs at 0000:00:02.0
+const INTEGRATED_GPU_PCI_SLOT: PciSlot = PciSlot {
+    domain: 0,
+    bus: 0,
+    number: 2,
+    function: 0,
+};
+
+// from include/uapi/linux/perf_event.h
+const PERF_ATTR_SIZE_VER0: u32 = 64;
+const PERF_FLAG_FD_CLOEXEC: libc::c_ulong = 1 << 3;
+
+// engine classes as they are named in the events of the i915 PMU
+const RENDER_ENGINE_PREFIXES: &[&str] = &["rcs", "bcs", "ccs"];
+const VIDEO_ENGINE_PREFIXES: &[&str] = &["vcs", "vecs"];
 
 #[derive(Debug, Clone, Default)]
 
@@ -15,6 +40,7 @@ pub struct IntelGpu {
     pub driver: String,
     sysfs_path: PathBuf,
     first_hwmon_path: Option<PathBuf>,
+    engine_busy: Option<Arc<EngineBusySampler>>,
 }
 
 impl IntelGpu {
@@ -25,14 +51,280 @@ impl IntelGpu {
         sysfs_path: PathBuf,
         first_hwmon_path: Option<PathBuf>,
     ) -> Self {
+        let engine_busy = if driver == "i915" {
+            EngineBusySampler::open(gpu_identifier)
+                .inspect_err(|err| {
+                    let permission_denied = err.chain().any(|cause| {
+                        cause.downcast_ref::<io::Error>().map(io::Error::kind)
+                            == Some(io::ErrorKind::PermissionDenied)
+                    });
+
+                    if permission_denied {
+                        warn!("Not allowed to open i915 PMU of {gpu_identifier}, engine busyness will only be available through fdinfo. Grant Resources CAP_PERFMON or set kernel.perf_event_paranoid to 0 to allow it: {err}");
+                    } else {
+                        debug!("Unable to open i915 PMU of {gpu_identifier}, engine busyness will only be available through fdinfo: {err}");
+                    }
+                })
+                .ok()
+                .map(Arc::new)
+        } else {
+            None
+        };
+
         Self {
             device,
             gpu_identifier,
             driver,
             sysfs_path,
             first_hwmon_path,
+            engine_busy,
+        }
+    }
+}
+
+/// The first version of `struct perf_event_attr`, newer kernels still accept it and it's all we need for counting
+#[repr(C)]
+#[derive(Default)]
+struct PerfEventAttr {
+    kind: u32,
+    size: u32,
+    config: u64,
+    sample_period: u64,
+    sample_type: u64,
+    read_format: u64,
+    flags: u64,
+    wakeup_events: u32,
+    bp_type: u32,
+    config1: u64,
+}
+
+/// An engine counter of the i915 PMU, counting the nanoseconds the engine has been busy for
+#[derive(Debug)]
+struct EngineCounter {
+    name: String,
+    fd: OwnedFd,
+    last_sample: Mutex<Option<(u64, Instant)>>,
+}
+
+impl EngineCounter {
+    fn open(name: String, pmu_type: u32, config: u64, cpu: i32) -> Result<Self> {
+        let attr = PerfEventAttr {
+            kind: pmu_type,
+            size: PERF_ATTR_SIZE_VER0,
+            config,
+            ..Default::default()
+        };
+
+        // the i915 PMU is an uncore PMU, so its events are counted system-wide on the CPU given by its cpumask
+        let fd = unsafe {
+            libc::syscall(
+                libc::SYS_perf_event_open,
+                &attr as *const PerfEventAttr,
+                -1 as libc::pid_t,
+                cpu as libc::c_int,
+                -1 as libc::c_int,
+                PERF_FLAG_FD_CLOEXEC,
+            )
+        };
+
+        if fd < 0 {
+            return Err(io::Error::last_os_error())
+                .with_context(|| format!("unable to open perf event for engine {name}"));
+        }
+
+        Ok(Self {
+            name,
+            fd: unsafe { OwnedFd::from_raw_fd(fd as libc::c_int) },
+            last_sample: Mutex::new(None),
+        })
+    }
+
+    fn read_busy_ns(&self) -> Result<u64> {
+        let mut value = 0_u64;
+
+        let read = unsafe {
+            libc::read(
+                self.fd.as_raw_fd(),
+                (&mut value as *mut u64).cast(),
+                size_of::<u64>(),
+            )
+        };
+
+        if read != size_of::<u64>() as isize {
+            return Err(io::Error::last_os_error())
+                .with_context(|| format!("unable to read perf event for engine {}", self.name));
+        }
+
+        Ok(value)
+    }
+
+    /// Returns the fraction of time the engine has been busy since the last time this has been called
+    fn busy_fraction(&self) -> Result<f64> {
+        let busy_ns = self.read_busy_ns()?;
+        let now = Instant::now();
+
+        let last_sample = self.last_sample.lock().unwrap().replace((busy_ns, now));
+
+        let Some((last_busy_ns, last_instant)) = last_sample else {
+            bail!("no previous sample for engine {} yet", self.name)
+        };
+
+        let elapsed = now.duration_since(last_instant);
+
+        busy_fraction_between(last_busy_ns, busy_ns, elapsed).with_context(|| {
+            format!(
+                "no time has passed since the last sample for engine {}",
+                self.name
+            )
+        })
+    }
+}
+
+/// Returns the fraction of `elapsed` an engine has been busy for, given two readings of its busy counter in
+/// nanoseconds, or `None` if no time has passed
+fn busy_fraction_between(last_busy_ns: u64, busy_ns: u64, elapsed: Duration) -> Option<f64> {
+    let elapsed_ns = elapsed.as_nanos() as f64;
+    if elapsed_ns == 0.0 {
+        return None;
+    }
+
+    Some(busy_ns.saturating_sub(last_busy_ns) as f64 / elapsed_ns)
+}
+
+/// Samples the busyness of the engines of an i915 GPU using its perf PMU (like `intel_gpu_top` does), for older
+/// hardware and kernels that don't expose engine usage through fdinfo
+///
+/// Opening the PMU needs `CAP_PERFMON` or a `kernel.perf_event_paranoid` of 0 or lower.
+#[derive(Debug)]
+struct EngineBusySampler {
+    render_engines: Vec<EngineCounter>,
+    video_engines: Vec<EngineCounter>,
+}
+
+impl EngineBusySampler {
+    fn open(gpu_identifier: GpuIdentifier) -> Result<Self> {
+        let pmu_path = Self::pmu_path(gpu_identifier)?;
+
+        trace!("Opening i915 PMU at {pmu_path:?}…");
+
+        let pmu_type = std::fs::read_to_string(pmu_path.join("type"))?
+            .trim()
+            .parse()
+            .context("unable to parse PMU type")?;
+
+        let cpu = parse_cpumask(&std::fs::read_to_string(pmu_path.join("cpumask"))?)
+            .context("unable to parse PMU cpumask")?;
+
+        let mut render_engines = Vec::new();
+        let mut video_engines = Vec::new();
+
+        for entry in std::fs::read_dir(pmu_path.join("events"))?.flatten() {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+
+            let Some(engine) = file_name.strip_suffix("-busy") else {
+                continue;
+            };
+
+            let engines = if RENDER_ENGINE_PREFIXES
+                .iter()
+                .any(|prefix| engine.starts_with(prefix))
+            {
+                &mut render_engines
+            } else if VIDEO_ENGINE_PREFIXES
+                .iter()
+                .any(|prefix| engine.starts_with(prefix))
+            {
+                &mut video_engines
+            } else {
+                continue;
+            };
+
+            let Some(config) = std::fs::read_to_string(entry.path())
+                .ok()
+                .and_then(|event| parse_event_config(&event))
+            else {
+                continue;
+            };
+
+            engines.push(EngineCounter::open(
+                engine.to_string(),
+                pmu_type,
+                config,
+                cpu,
+            )?);
+        }
+
+        if render_engines.is_empty() {
+            bail!("i915 PMU at {pmu_path:?} has no render engines")
+        }
+
+        Ok(Self {
+            render_engines,
+            video_engines,
+        })
+    }
+
+    /// Discrete GPUs get a PMU named after their PCI slot while the integrated GPU's PMU is just called "i915"
+    fn pmu_path(gpu_identifier: GpuIdentifier) -> Result<PathBuf> {
+        let GpuIdentifier::PciSlot(pci_slot) = gpu_identifier else {
+            bail!("{gpu_identifier} is not a PCI device");
+        };
+
+        let pmu_name = if pci_slot == INTEGRATED_GPU_PCI_SLOT {
+            "i915".to_string()
+        } else {
+            format!("i915_{}", pci_slot.to_string().replace(':', "_"))
+        };
+
+        let path = Path::new(PATH_EVENT_SOURCES).join(pmu_name);
+
+        if path.exists() {
+            Ok(path)
+        } else {
+            bail!("no i915 PMU found at {path:?}")
         }
     }
+
+    /// Returns the busy fraction of the busiest engine out of `engines`
+    fn busiest(engines: &[EngineCounter]) -> Result<f64> {
+        // sample every engine before bailing so that none of them fall behind
+        let fractions = engines
+            .iter()
+            .map(EngineCounter::busy_fraction)
+            .collect::<Vec<_>>();
+
+        fractions
+            .into_iter()
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .reduce(f64::max)
+            .context("no engines to sample")
+    }
+
+    fn render_usage(&self) -> Result<f64> {
+        Self::busiest(&self.render_engines)
+    }
+
+    fn video_usage(&self) -> Result<f64> {
+        Self::busiest(&self.video_engines)
+    }
+}
+
+/// Parses the `config` term out of an event description of a perf PMU, e.g. `config=0x1000`
+fn parse_event_config(event: &str) -> Option<u64> {
+    event.trim().split(',').find_map(|term| {
+        let value = term.trim().strip_prefix("config=")?;
+        if let Some(hex) = value.strip_prefix("0x") {
+            u64::from_str_radix(hex, 16).ok()
+        } else {
+            value.parse().ok()
+        }
+    })
+}
+
+/// Returns the first CPU of a cpumask like `0` or `0-3`
+fn parse_cpumask(cpumask: &str) -> Option<i32> {
+    cpumask.trim().split([',', '-']).next()?.parse().ok()
 }
 
 impl GpuImpl for IntelGpu {
@@ -61,11 +353,19 @@ impl GpuImpl for IntelGpu {
     }
 
     fn usage(&self) -> Result<f64> {
-        self.drm_usage().map(|usage| usage as f64 / 100.0)
+        self.drm_usage()
+            .map(|usage| usage as f64 / 100.0)
+            .or_else(|err| match &self.engine_busy {
+                Some(engine_busy) => engine_busy.render_usage(),
+                None => Err(err),
+            })
     }
 
     fn encode_usage(&self) -> Result<f64> {
-        bail!("encode usage not implemented for Intel")
+        match &self.engine_busy {
+            Some(engine_busy) => engine_busy.video_usage(),
+            None => bail!("encode usage not implemented for Intel"),
+        }
     }
 
     fn decode_usage(&self) -> Result<f64> {
@@ -92,6 +392,10 @@ impl GpuImpl for IntelGpu {
         self.hwmon_power_usage()
     }
 
+    fn board_power_usage(&self) -> Result<f64> {
+        self.hwmon_board_power_usage()
+    }
+
     fn core_frequency(&self) -> Result<f64> {
         Ok(self.read_sysfs_int("gt_cur_freq_mhz")? as f64 * 1_000_000.0)
     }
@@ -100,6 +404,14 @@ impl GpuImpl for IntelGpu {
         self.hwmon_vram_frequency()
     }
 
+    fn core_dpm_states(&self) -> Result<DpmStates> {
+        bail!("DPM states not implemented for Intel")
+    }
+
+    fn vram_dpm_states(&self) -> Result<DpmStates> {
+        bail!("DPM states not implemented for Intel")
+    }
+
     fn power_cap(&self) -> Result<f64> {
         self.hwmon_power_cap()
     }
@@ -107,4 +419,69 @@ impl GpuImpl for IntelGpu {
     fn power_cap_max(&self) -> Result<f64> {
         self.hwmon_power_cap_max()
     }
+
+    fn ecc_errors(&self) -> Result<EccErrors> {
+        bail!("ECC error counts not implemented for Intel")
+    }
+
+    fn fan_speed(&self) -> Result<FanSpeed> {
+        self.hwmon_fan_speed()
+    }
+
+    fn fan_speed_max(&self) -> Result<f64> {
+        self.hwmon_fan_speed_max()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+
+    use super::{busy_fraction_between, parse_cpumask, parse_event_config};
+
+    #[test]
+    fn event_config_hex() {
+        assert_eq!(Some(0x1000), parse_event_config("config=0x1000\n"));
+    }
+
+    #[test]
+    fn event_config_with_other_terms() {
+        assert_eq!(
+            Some(0x100002),
+            parse_event_config("event=0x1,config=0x100002")
+        );
+    }
+
+    #[test]
+    fn event_config_missing() {
+        assert_eq!(None, parse_event_config("event=0x1"));
+    }
+
+    #[test]
+    fn cpumask_range() {
+        assert_eq!(Some(0), parse_cpumask("0-3\n"));
+    }
+
+    #[test]
+    fn busy_fraction_half() {
+        assert_eq!(
+            Some(0.5),
+            busy_fraction_between(1_000_000_000, 1_500_000_000, Duration::from_secs(1))
+        );
+    }
+
+    #[test]
+    fn busy_fraction_counter_reset() {
+        assert_eq!(
+            Some(0.0),
+            busy_fraction_between(1_500_000_000, 1_000, Duration::from_millis(500))
+        );
+    }
+
+    #[test]
+    fn busy_fraction_no_time_passed() {
+        assert_eq!(None, busy_fraction_between(0, 1_000, Duration::ZERO));
+    }
 }
@@ -11,6 +11,7 @@ use process_data::{pci_slot::PciSlot, GpuIdentifier};
 use v3d::V3dGpu;
 
 use std::{
+    cmp::Reverse,
     path::{Path, PathBuf},
     str::FromStr,
 };
@@ -18,8 +19,12 @@ use std::{
 use glob::glob;
 
 use crate::{
+    config::LIBEXECDIR,
     i18n::i18n,
-    utils::{pci::Device, read_uevent},
+    utils::{
+        maybe_pkexec_command, pci::Device, read_uevent, settings::GpuTemperatureSensor,
+        FLATPAK_APP_PATH, IS_FLATPAK,
+    },
 };
 
 use self::{amd::AmdGpu, intel::IntelGpu, nvidia::NvidiaGpu, other::OtherGpu};
@@ -30,8 +35,198 @@ pub const VID_AMD: u16 = 0x1002;
 pub const VID_INTEL: u16 = 0x8086;
 pub const VID_NVIDIA: u16 = 0x10DE;
 
+// GPUs have a handful of fans at most, no need to probe any further channels
+const MAX_HWMON_FANS: usize = 8;
+
 const RE_CARD_ENUMARATOR: Lazy<Regex> = lazy_regex!(r"(\d+)\/?$");
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EccErrors {
+    pub corrected: u64,
+    pub uncorrected: u64,
+}
+
+/// The speed of a GPU's fan, depending on the driver either as revolutions per minute or relative to the fan's maximum
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FanSpeed {
+    Rpm(f64),
+    /// Fraction of the maximum speed or of the maximum PWM duty cycle, between 0 and 1
+    Fraction(f64),
+}
+
+/// A single fan of a GPU as reported through hwmon
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GpuFan {
+    pub speed: FanSpeed,
+    /// Maximum speed of the fan in RPM
+    pub max: Option<f64>,
+}
+
+impl GpuFan {
+    /// Returns the fan speed as a fraction of its maximum, if the driver either reports it that way or also reports
+    /// the maximum RPM
+    pub fn fraction(&self) -> Option<f64> {
+        match self.speed {
+            FanSpeed::Fraction(fraction) => Some(fraction.clamp(0.0, 1.0)),
+            FanSpeed::Rpm(rpm) => self
+                .max
+                .filter(|max| *max > 0.0)
+                .map(|max| (rpm / max).clamp(0.0, 1.0)),
+        }
+    }
+}
+
+/// A single DPM (dynamic power management) state of a clock domain
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DpmState {
+    /// Name of the state as given by the driver, usually its index but e.g. "S" for the deep sleep state
+    pub label: String,
+    /// Frequency of the state in Hz
+    pub frequency: f64,
+}
+
+/// The DPM states of a clock domain like the core or VRAM clock, in the order they are listed by the driver
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DpmStates {
+    pub states: Vec<DpmState>,
+    /// Index of the currently active state in `states`
+    pub active: Option<usize>,
+}
+
+impl DpmStates {
+    /// Parses a DPM state list like amdgpu's `pp_dpm_sclk` and `pp_dpm_mclk`, in which the active state is marked
+    /// with an asterisk:
+    /// ```text
+    /// 0: 500Mhz
+    /// 1: 1270Mhz *
+    /// 2: 2615Mhz
+    /// ```
+    pub fn parse<S: AsRef<str>>(content: S) -> Result<Self> {
+        let mut states = Vec::new();
+        let mut active = None;
+
+        for line in content.as_ref().lines() {
+            let Some((label, frequency)) = line.split_once(':') else {
+                continue;
+            };
+
+            let mut frequency = frequency.trim();
+
+            if let Some(stripped) = frequency.strip_suffix('*') {
+                active = Some(states.len());
+                frequency = stripped.trim_end();
+            }
+
+            let unit_start = frequency
+                .find(|c: char| !c.is_ascii_digit() && c != '.')
+                .unwrap_or(frequency.len());
+            let (mhz, unit) = frequency.split_at(unit_start);
+
+            if !unit.trim().eq_ignore_ascii_case("mhz") {
+                bail!("unexpected unit in DPM state '{}'", line.trim());
+            }
+
+            let mhz = mhz
+                .parse::<f64>()
+                .with_context(|| format!("unable to parse DPM state '{}'", line.trim()))?;
+
+            states.push(DpmState {
+                label: label.trim().to_string(),
+                frequency: mhz * 1_000_000.0,
+            });
+        }
+
+        if states.is_empty() {
+            bail!("no DPM states found");
+        }
+
+        Ok(Self { states, active })
+    }
+
+    pub fn active_state(&self) -> Option<&DpmState> {
+        self.active.and_then(|active| self.states.get(active))
+    }
+
+    pub fn min_frequency(&self) -> Option<f64> {
+        self.states
+            .iter()
+            .map(|state| state.frequency)
+            .reduce(f64::min)
+    }
+
+    pub fn max_frequency(&self) -> Option<f64> {
+        self.states
+            .iter()
+            .map(|state| state.frequency)
+            .reduce(f64::max)
+    }
+}
+
+/// A display that is connected to one of the connectors of a GPU
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConnectedDisplay {
+    /// Name of the connector, e.g. "DP-1"
+    pub connector: String,
+    /// Name of the monitor as reported by its EDID
+    pub monitor_name: Option<String>,
+    /// The first mode listed by the kernel, which is the preferred (usually native) one, e.g. "2560x1440"
+    pub preferred_mode: Option<String>,
+    /// Whether the display is currently being driven, i.e. not turned off by the compositor
+    pub enabled: bool,
+}
+
+impl ConnectedDisplay {
+    fn from_sysfs(connector: String, path: &Path) -> Self {
+        let monitor_name = std::fs::read(path.join("edid"))
+            .ok()
+            .and_then(Self::parse_edid_monitor_name);
+
+        let preferred_mode = std::fs::read_to_string(path.join("modes"))
+            .ok()
+            .and_then(|modes| modes.lines().next().map(str::to_string))
+            .filter(|mode| !mode.is_empty());
+
+        let enabled = std::fs::read_to_string(path.join("enabled"))
+            .is_ok_and(|enabled| enabled.trim() == "enabled");
+
+        Self {
+            connector,
+            monitor_name,
+            preferred_mode,
+            enabled,
+        }
+    }
+
+    /// Looks for the display product name descriptor (tag 0xFC) in the base block of an EDID
+    fn parse_edid_monitor_name<B: AsRef<[u8]>>(edid: B) -> Option<String> {
+        let edid = edid.as_ref();
+
+        // the four 18 byte descriptors start at byte 54 of the base block
+        (0..4).find_map(|i| {
+            let descriptor = edid.get(54 + i * 18..54 + (i + 1) * 18)?;
+
+            if descriptor[0..3] != [0, 0, 0] || descriptor[3] != 0xFC {
+                return None;
+            }
+
+            // the name is terminated by a line feed and padded with spaces
+            let name = descriptor[5..]
+                .iter()
+                .take_while(|byte| **byte != b'\n')
+                .map(|byte| char::from(*byte))
+                .collect::<String>()
+                .trim()
+                .to_string();
+
+            if name.is_empty() {
+                None
+            } else {
+                Some(name)
+            }
+        })
+    }
+}
+
 #[derive(Debug)]
 pub struct GpuData {
     pub gpu_identifier: GpuIdentifier,
@@ -48,12 +243,33 @@ pub struct GpuData {
     pub clock_speed: Option<f64>,
     pub vram_speed: Option<f64>,
 
+    pub clock_states: Option<DpmStates>,
+    pub vram_clock_states: Option<DpmStates>,
+
     pub temperature: Option<f64>,
+    /// Temperatures of the individual sensors, if the GPU labels them
+    pub sensor_temperatures: Vec<(GpuTemperatureSensor, f64)>,
 
     pub power_usage: Option<f64>,
+    pub board_power_usage: Option<f64>,
     pub power_cap: Option<f64>,
     pub power_cap_max: Option<f64>,
 
+    pub ecc_errors: Option<EccErrors>,
+
+    pub fan_speed: Option<FanSpeed>,
+    /// Maximum speed of the fan in RPM
+    pub fan_speed_max: Option<f64>,
+    /// All fans of the GPU in the order of their hwmon channels, the first one is the same as `fan_speed`
+    pub fans: Vec<GpuFan>,
+
+    /// An empty `Vec` means this GPU is only used for rendering
+    pub connected_displays: Vec<ConnectedDisplay>,
+    /// Whether the firmware used this GPU for the boot console
+    pub boot_vga: bool,
+    /// Whether this is the GPU that currently drives the screen, only ever set if there are multiple GPUs
+    pub primary_display: bool,
+
     pub nvidia: bool,
 }
 
@@ -75,12 +291,26 @@ impl GpuData {
         let clock_speed = gpu.core_frequency().ok();
         let vram_speed = gpu.vram_frequency().ok();
 
+        let clock_states = gpu.core_dpm_states().ok();
+        let vram_clock_states = gpu.vram_dpm_states().ok();
+
         let temperature = gpu.temperature().ok();
+        let sensor_temperatures = gpu.sensor_temperatures();
 
         let power_usage = gpu.power_usage().ok();
+        let board_power_usage = gpu.board_power_usage().ok();
         let power_cap = gpu.power_cap().ok();
         let power_cap_max = gpu.power_cap_max().ok();
 
+        let ecc_errors = gpu.ecc_errors().ok();
+
+        let fan_speed = gpu.fan_speed().ok();
+        let fan_speed_max = gpu.fan_speed_max().ok();
+        let fans = gpu.fans();
+
+        let connected_displays = gpu.connected_displays();
+        let boot_vga = gpu.boot_vga();
+
         let nvidia = matches!(gpu, Gpu::Nvidia(_));
 
         let gpu_data = Self {
@@ -92,10 +322,21 @@ impl GpuData {
             used_vram,
             clock_speed,
             vram_speed,
+            clock_states,
+            vram_clock_states,
             temperature,
+            sensor_temperatures,
             power_usage,
+            board_power_usage,
             power_cap,
             power_cap_max,
+            ecc_errors,
+            fan_speed,
+            fan_speed_max,
+            fans,
+            connected_displays,
+            boot_vga,
+            primary_display: false,
             nvidia,
         };
 
@@ -103,6 +344,75 @@ impl GpuData {
 
         gpu_data
     }
+
+    /// Marks the GPU that drives the screen in systems with multiple GPUs, e.g. laptops with hybrid graphics. That's
+    /// the GPU with enabled displays, using the boot GPU as a tiebreaker or as a fallback if no displays are enabled.
+    pub fn mark_primary_display(gpu_data: &mut [GpuData]) {
+        if gpu_data.len() < 2 {
+            return;
+        }
+
+        let primary = Self::select_primary_display(gpu_data.iter().map(|data| {
+            (
+                data.connected_displays
+                    .iter()
+                    .any(|display| display.enabled),
+                !data.connected_displays.is_empty(),
+                data.boot_vga,
+            )
+        }));
+
+        for (i, data) in gpu_data.iter_mut().enumerate() {
+            data.primary_display = primary == Some(i);
+        }
+    }
+
+    /// Takes whether each GPU has enabled displays, connected displays and whether it is the boot GPU (in descending
+    /// importance) and returns the index of the primary display GPU, if any of them qualifies at all
+    fn select_primary_display<I: IntoIterator<Item = (bool, bool, bool)>>(
+        candidates: I,
+    ) -> Option<usize> {
+        candidates
+            .into_iter()
+            .enumerate()
+            .filter(|(_, (enabled, connected, boot_vga))| *enabled || *connected || *boot_vga)
+            // unlike max_by_key, this lets the first GPU win ties
+            .min_by_key(|(_, candidate)| Reverse(*candidate))
+            .map(|(i, _)| i)
+    }
+
+    /// Returns the fan speed as a fraction of its maximum, if the driver either reports it that way or also reports the
+    /// maximum RPM
+    pub fn fan_speed_fraction(&self) -> Option<f64> {
+        GpuFan {
+            speed: self.fan_speed?,
+            max: self.fan_speed_max,
+        }
+        .fraction()
+    }
+
+    /// Returns the temperature of the `preferred` sensor, falling back to the GPU's default temperature and then to
+    /// any other sensor
+    pub fn headline_temperature(&self, preferred: GpuTemperatureSensor) -> Option<f64> {
+        Self::select_temperature(&self.sensor_temperatures, self.temperature, preferred)
+    }
+
+    fn select_temperature(
+        sensor_temperatures: &[(GpuTemperatureSensor, f64)],
+        default_temperature: Option<f64>,
+        preferred: GpuTemperatureSensor,
+    ) -> Option<f64> {
+        sensor_temperatures
+            .iter()
+            .find(|(sensor, _)| *sensor == preferred)
+            .map(|(_, temperature)| *temperature)
+            .or(default_temperature)
+            .or_else(|| {
+                sensor_temperatures
+                    .first()
+                    .map(|(_, temperature)| *temperature)
+            })
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -136,10 +446,16 @@ pub trait GpuImpl {
     fn total_vram(&self) -> Result<usize>;
     fn temperature(&self) -> Result<f64>;
     fn power_usage(&self) -> Result<f64>;
+    fn board_power_usage(&self) -> Result<f64>;
     fn core_frequency(&self) -> Result<f64>;
     fn vram_frequency(&self) -> Result<f64>;
+    fn core_dpm_states(&self) -> Result<DpmStates>;
+    fn vram_dpm_states(&self) -> Result<DpmStates>;
     fn power_cap(&self) -> Result<f64>;
     fn power_cap_max(&self) -> Result<f64>;
+    fn ecc_errors(&self) -> Result<EccErrors>;
+    fn fan_speed(&self) -> Result<FanSpeed>;
+    fn fan_speed_max(&self) -> Result<f64>;
 
     fn read_sysfs_int<P: AsRef<Path> + std::marker::Send>(&self, file: P) -> Result<isize> {
         let path = self.sysfs_path().join(file);
@@ -195,6 +511,35 @@ pub trait GpuImpl {
         Ok(self.read_hwmon_int("temp1_input")? as f64 / 1000.0)
     }
 
+    /// Reads all temperatures whose hwmon label is known, e.g. "edge", "junction" and "mem" for AMD GPUs
+    fn hwmon_temperatures(&self) -> Vec<(GpuTemperatureSensor, f64)> {
+        let Some(hwmon) = self.first_hwmon() else {
+            return Vec::new();
+        };
+
+        let Ok(labels) = glob(&format!("{}/temp*_label", hwmon.to_string_lossy())) else {
+            return Vec::new();
+        };
+
+        labels
+            .flatten()
+            .filter_map(|label_path| {
+                let sensor = std::fs::read_to_string(&label_path)
+                    .ok()
+                    .and_then(|label| Gpu::temperature_sensor_from_label(&label))?;
+
+                let input_file = label_path
+                    .file_name()?
+                    .to_string_lossy()
+                    .replace("_label", "_input");
+
+                self.read_hwmon_int(input_file)
+                    .ok()
+                    .map(|temperature| (sensor, temperature as f64 / 1000.0))
+            })
+            .collect()
+    }
+
     fn hwmon_power_usage(&self) -> Result<f64> {
         Ok(self
             .read_hwmon_int("power1_average")
@@ -202,6 +547,14 @@ pub trait GpuImpl {
             / 1_000_000.0)
     }
 
+    // some GPUs (e. g. newer AMD ones) report the total board power separately from the power of the GPU chip itself
+    fn hwmon_board_power_usage(&self) -> Result<f64> {
+        Ok(self
+            .read_hwmon_int("power2_average")
+            .or_else(|_| self.read_hwmon_int("power2_input"))? as f64
+            / 1_000_000.0)
+    }
+
     fn hwmon_core_frequency(&self) -> Result<f64> {
         Ok(self.read_hwmon_int("freq1_input")? as f64)
     }
@@ -217,6 +570,47 @@ pub trait GpuImpl {
     fn hwmon_power_cap_max(&self) -> Result<f64> {
         Ok(self.read_hwmon_int("power1_cap_max")? as f64 / 1_000_000.0)
     }
+
+    fn hwmon_fan_speed(&self) -> Result<FanSpeed> {
+        self.hwmon_fan_speed_of(1)
+    }
+
+    // not every fan reports its RPM, but the PWM duty cycle it's driven with is a good approximation of its speed
+    fn hwmon_fan_speed_of(&self, channel: usize) -> Result<FanSpeed> {
+        if let Ok(rpm) = self.read_hwmon_int(format!("fan{channel}_input")) {
+            return Ok(FanSpeed::Rpm(rpm as f64));
+        }
+
+        let pwm = self.read_hwmon_int(format!("pwm{channel}"))?;
+        let pwm_max = self
+            .read_hwmon_int(format!("pwm{channel}_max"))
+            .unwrap_or(255);
+
+        if pwm_max <= 0 {
+            bail!("invalid maximum PWM value {pwm_max}");
+        }
+
+        Ok(FanSpeed::Fraction(pwm as f64 / pwm_max as f64))
+    }
+
+    fn hwmon_fan_speed_max(&self) -> Result<f64> {
+        Ok(self.read_hwmon_int("fan1_max")? as f64)
+    }
+
+    /// Reads the speeds of all fans, e.g. of graphics cards with separately controlled fans
+    fn hwmon_fans(&self) -> Vec<GpuFan> {
+        (1..=MAX_HWMON_FANS)
+            .filter_map(|channel| {
+                let speed = self.hwmon_fan_speed_of(channel).ok()?;
+                let max = self
+                    .read_hwmon_int(format!("fan{channel}_max"))
+                    .ok()
+                    .map(|max| max as f64);
+
+                Some(GpuFan { speed, max })
+            })
+            .collect()
+    }
 }
 
 impl Gpu {
@@ -378,6 +772,11 @@ impl Gpu {
         .vendor())
     }
 
+    /// Returns an ID that keeps identifying the GPU after reboots, i.e. its PCI slot if it has one
+    pub fn stable_id(&self) -> String {
+        format!("gpu:{}", self.gpu_identifier())
+    }
+
     pub fn gpu_identifier(&self) -> GpuIdentifier {
         match self {
             Gpu::Amd(gpu) => gpu.gpu_identifier(),
@@ -398,6 +797,82 @@ impl Gpu {
         }
     }
 
+    pub fn sysfs_path(&self) -> PathBuf {
+        match self {
+            Gpu::Amd(gpu) => gpu.sysfs_path(),
+            Gpu::Intel(gpu) => gpu.sysfs_path(),
+            Gpu::Nvidia(gpu) => gpu.sysfs_path(),
+            Gpu::V3d(gpu) => gpu.sysfs_path(),
+            Gpu::Other(gpu) => gpu.sysfs_path(),
+        }
+    }
+
+    /// Returns the name of the primary node of this GPU, e.g. "card0"
+    pub fn card_node(&self) -> String {
+        self.sysfs_path()
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+
+    pub fn boot_vga(&self) -> bool {
+        std::fs::read_to_string(self.sysfs_path().join("device/boot_vga"))
+            .is_ok_and(|boot_vga| boot_vga.trim() == "1")
+    }
+
+    /// Returns the name of the render node of this GPU (e.g. "renderD128") if it has one
+    pub fn render_node(&self) -> Option<String> {
+        glob(&format!(
+            "{}/device/drm/renderD*",
+            self.sysfs_path().to_string_lossy()
+        ))
+        .ok()?
+        .flatten()
+        .find_map(|path| {
+            path.file_name()
+                .map(|name| name.to_string_lossy().to_string())
+        })
+    }
+
+    /// Returns the displays that are currently connected to this GPU, sorted by their connector
+    pub fn connected_displays(&self) -> Vec<ConnectedDisplay> {
+        let card_node = self.card_node();
+
+        let Ok(paths) = glob(&format!(
+            "{}/{card_node}-*",
+            self.sysfs_path().to_string_lossy()
+        )) else {
+            return Vec::new();
+        };
+
+        let mut displays: Vec<ConnectedDisplay> = paths
+            .flatten()
+            .filter(|path| {
+                std::fs::read_to_string(path.join("status"))
+                    .is_ok_and(|status| status.trim() == "connected")
+            })
+            .filter_map(|path| {
+                let connector =
+                    Self::connector_name(&card_node, path.file_name()?.to_string_lossy())?;
+                Some(ConnectedDisplay::from_sysfs(connector, &path))
+            })
+            .collect();
+
+        displays.sort_by(|a, b| a.connector.cmp(&b.connector));
+
+        displays
+    }
+
+    /// Connector directories are named after the card they belong to, e.g. "card0-HDMI-A-1"
+    fn connector_name<S: AsRef<str>>(card_node: &str, connector_dir: S) -> Option<String> {
+        connector_dir
+            .as_ref()
+            .strip_prefix(card_node)
+            .and_then(|name| name.strip_prefix('-'))
+            .filter(|name| !name.is_empty())
+            .map(str::to_string)
+    }
+
     pub fn name(&self) -> Result<String> {
         match self {
             Gpu::Amd(gpu) => gpu.name(),
@@ -478,6 +953,36 @@ impl Gpu {
         }
     }
 
+    pub fn sensor_temperatures(&self) -> Vec<(GpuTemperatureSensor, f64)> {
+        match self {
+            Gpu::Amd(gpu) => gpu.hwmon_temperatures(),
+            Gpu::Intel(gpu) => gpu.hwmon_temperatures(),
+            Gpu::Nvidia(gpu) => gpu.hwmon_temperatures(),
+            Gpu::V3d(gpu) => gpu.hwmon_temperatures(),
+            Gpu::Other(gpu) => gpu.hwmon_temperatures(),
+        }
+    }
+
+    pub fn fans(&self) -> Vec<GpuFan> {
+        match self {
+            Gpu::Amd(gpu) => gpu.hwmon_fans(),
+            Gpu::Intel(gpu) => gpu.hwmon_fans(),
+            Gpu::Nvidia(gpu) => gpu.hwmon_fans(),
+            Gpu::V3d(gpu) => gpu.hwmon_fans(),
+            Gpu::Other(gpu) => gpu.hwmon_fans(),
+        }
+    }
+
+    /// Maps the label of an hwmon temperature sensor to the kind of sensor it is
+    fn temperature_sensor_from_label<S: AsRef<str>>(label: S) -> Option<GpuTemperatureSensor> {
+        match label.as_ref().trim().to_lowercase().as_str() {
+            "edge" => Some(GpuTemperatureSensor::Edge),
+            "junction" | "hotspot" => Some(GpuTemperatureSensor::Junction),
+            "mem" | "vram" => Some(GpuTemperatureSensor::Memory),
+            _ => None,
+        }
+    }
+
     pub fn power_usage(&self) -> Result<f64> {
         match self {
             Gpu::Amd(gpu) => gpu.power_usage(),
@@ -488,6 +993,16 @@ impl Gpu {
         }
     }
 
+    pub fn board_power_usage(&self) -> Result<f64> {
+        match self {
+            Gpu::Amd(gpu) => gpu.board_power_usage(),
+            Gpu::Intel(gpu) => gpu.board_power_usage(),
+            Gpu::Nvidia(gpu) => gpu.board_power_usage(),
+            Gpu::V3d(gpu) => gpu.board_power_usage(),
+            Gpu::Other(gpu) => gpu.board_power_usage(),
+        }
+    }
+
     pub fn core_frequency(&self) -> Result<f64> {
         match self {
             Gpu::Amd(gpu) => gpu.core_frequency(),
@@ -508,6 +1023,26 @@ impl Gpu {
         }
     }
 
+    pub fn core_dpm_states(&self) -> Result<DpmStates> {
+        match self {
+            Gpu::Amd(gpu) => gpu.core_dpm_states(),
+            Gpu::Intel(gpu) => gpu.core_dpm_states(),
+            Gpu::Nvidia(gpu) => gpu.core_dpm_states(),
+            Gpu::V3d(gpu) => gpu.core_dpm_states(),
+            Gpu::Other(gpu) => gpu.core_dpm_states(),
+        }
+    }
+
+    pub fn vram_dpm_states(&self) -> Result<DpmStates> {
+        match self {
+            Gpu::Amd(gpu) => gpu.vram_dpm_states(),
+            Gpu::Intel(gpu) => gpu.vram_dpm_states(),
+            Gpu::Nvidia(gpu) => gpu.vram_dpm_states(),
+            Gpu::V3d(gpu) => gpu.vram_dpm_states(),
+            Gpu::Other(gpu) => gpu.vram_dpm_states(),
+        }
+    }
+
     pub fn power_cap(&self) -> Result<f64> {
         match self {
             Gpu::Amd(gpu) => gpu.power_cap(),
@@ -527,4 +1062,326 @@ impl Gpu {
             Gpu::Other(gpu) => gpu.power_cap_max(),
         }
     }
+
+    /// Sets the power cap (in W) using `resources-power-cap`, which is run privileged if necessary and writes either to
+    /// hwmon or, for NVIDIA's proprietary driver, uses NVML
+    pub fn set_power_cap(&self, power_cap: f64) -> Result<()> {
+        let GpuIdentifier::PciSlot(pci_slot) = self.gpu_identifier() else {
+            bail!("power caps can only be set for PCI GPUs");
+        };
+
+        if !power_cap.is_finite() || power_cap <= 0.0 {
+            bail!("invalid power cap: {power_cap}");
+        }
+
+        let power_cap_path = if *IS_FLATPAK {
+            format!(
+                "{}/libexec/resources/resources-power-cap",
+                FLATPAK_APP_PATH.as_str()
+            )
+        } else {
+            format!("{LIBEXECDIR}/resources-power-cap")
+        };
+
+        // hwmon expects microwatts
+        let microwatts = (power_cap * 1_000_000.0).round() as u64;
+
+        debug!("Trying to set power cap of {pci_slot} to {power_cap} W");
+
+        let return_code = maybe_pkexec_command(
+            power_cap_path,
+            [pci_slot.to_string(), microwatts.to_string()],
+        )?;
+
+        if return_code == 0 {
+            info!("Successfully set the power cap of {pci_slot}");
+            Ok(())
+        } else {
+            bail!("non-zero return code: {return_code}")
+        }
+    }
+
+    pub fn ecc_errors(&self) -> Result<EccErrors> {
+        match self {
+            Gpu::Amd(gpu) => gpu.ecc_errors(),
+            Gpu::Intel(gpu) => gpu.ecc_errors(),
+            Gpu::Nvidia(gpu) => gpu.ecc_errors(),
+            Gpu::V3d(gpu) => gpu.ecc_errors(),
+            Gpu::Other(gpu) => gpu.ecc_errors(),
+        }
+    }
+
+    pub fn fan_speed(&self) -> Result<FanSpeed> {
+        match self {
+            Gpu::Amd(gpu) => gpu.fan_speed(),
+            Gpu::Intel(gpu) => gpu.fan_speed(),
+            Gpu::Nvidia(gpu) => gpu.fan_speed(),
+            Gpu::V3d(gpu) => gpu.fan_speed(),
+            Gpu::Other(gpu) => gpu.fan_speed(),
+        }
+    }
+
+    pub fn fan_speed_max(&self) -> Result<f64> {
+        match self {
+            Gpu::Amd(gpu) => gpu.fan_speed_max(),
+            Gpu::Intel(gpu) => gpu.fan_speed_max(),
+            Gpu::Nvidia(gpu) => gpu.fan_speed_max(),
+            Gpu::V3d(gpu) => gpu.fan_speed_max(),
+            Gpu::Other(gpu) => gpu.fan_speed_max(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+    use process_data::GpuIdentifier;
+
+    use crate::utils::settings::GpuTemperatureSensor;
+
+    use super::{ConnectedDisplay, DpmState, DpmStates, FanSpeed, Gpu, GpuData};
+
+    #[test]
+    fn temperature_sensor_from_label() {
+        assert_eq!(
+            Some(GpuTemperatureSensor::Edge),
+            Gpu::temperature_sensor_from_label("edge\n")
+        );
+        assert_eq!(
+            Some(GpuTemperatureSensor::Junction),
+            Gpu::temperature_sensor_from_label("junction\n")
+        );
+        assert_eq!(
+            Some(GpuTemperatureSensor::Memory),
+            Gpu::temperature_sensor_from_label("mem\n")
+        );
+        assert_eq!(None, Gpu::temperature_sensor_from_label("pkg\n"));
+    }
+
+    #[test]
+    fn select_temperature_preferred() {
+        let sensors = [
+            (GpuTemperatureSensor::Edge, 55.0),
+            (GpuTemperatureSensor::Junction, 70.0),
+        ];
+
+        assert_eq!(
+            Some(70.0),
+            GpuData::select_temperature(&sensors, Some(55.0), GpuTemperatureSensor::Junction)
+        );
+    }
+
+    #[test]
+    fn select_temperature_fallback() {
+        let sensors = [(GpuTemperatureSensor::Junction, 70.0)];
+
+        assert_eq!(
+            Some(60.0),
+            GpuData::select_temperature(&sensors, Some(60.0), GpuTemperatureSensor::Memory)
+        );
+        assert_eq!(
+            Some(70.0),
+            GpuData::select_temperature(&sensors, None, GpuTemperatureSensor::Memory)
+        );
+        assert_eq!(
+            None,
+            GpuData::select_temperature(&[], None, GpuTemperatureSensor::Edge)
+        );
+    }
+
+    #[test]
+    fn select_primary_display_enabled() {
+        // hybrid laptop whose internal panel is driven by the iGPU while the dGPU was used for booting
+        assert_eq!(
+            Some(0),
+            GpuData::select_primary_display([(true, true, false), (false, false, true)])
+        );
+        // both have enabled displays, the boot GPU wins
+        assert_eq!(
+            Some(1),
+            GpuData::select_primary_display([(true, true, false), (true, true, true)])
+        );
+    }
+
+    #[test]
+    fn select_primary_display_fallback() {
+        assert_eq!(
+            Some(1),
+            GpuData::select_primary_display([(false, false, false), (false, false, true)])
+        );
+        assert_eq!(
+            Some(0),
+            GpuData::select_primary_display([(false, true, false), (false, true, false)])
+        );
+        assert_eq!(
+            None,
+            GpuData::select_primary_display([(false, false, false), (false, false, false)])
+        );
+    }
+
+    #[test]
+    fn parse_dpm_states_valid() {
+        let content = concat!("0: 500Mhz\n", "1: 1270Mhz *\n", "2: 2615Mhz\n");
+
+        let dpm_states = DpmStates::parse(content).unwrap();
+
+        assert_eq!(Some(1), dpm_states.active);
+        assert_eq!(
+            Some(&DpmState {
+                label: "1".into(),
+                frequency: 1_270_000_000.0
+            }),
+            dpm_states.active_state()
+        );
+        assert_eq!(Some(500_000_000.0), dpm_states.min_frequency());
+        assert_eq!(Some(2_615_000_000.0), dpm_states.max_frequency());
+    }
+
+    #[test]
+    fn parse_dpm_states_deep_sleep() {
+        let content = concat!("S: 19Mhz *\n", "0: 500Mhz\n", "1: 2615Mhz\n");
+
+        let dpm_states = DpmStates::parse(content).unwrap();
+
+        assert_eq!(3, dpm_states.states.len());
+        assert_eq!(
+            Some("S"),
+            dpm_states.active_state().map(|state| state.label.as_str())
+        );
+        assert_eq!(Some(19_000_000.0), dpm_states.min_frequency());
+    }
+
+    #[test]
+    fn parse_dpm_states_without_active() {
+        let dpm_states = DpmStates::parse("0: 96Mhz\n").unwrap();
+
+        assert_eq!(None, dpm_states.active_state());
+        assert_eq!(Some(96_000_000.0), dpm_states.max_frequency());
+    }
+
+    #[test]
+    fn parse_dpm_states_invalid() {
+        assert!(DpmStates::parse("").is_err());
+        assert!(DpmStates::parse("0: fast *\n").is_err());
+        assert!(DpmStates::parse("0: 500W\n").is_err());
+    }
+
+    #[test]
+    fn connector_name_valid() {
+        assert_eq!(
+            Some("HDMI-A-1".to_string()),
+            Gpu::connector_name("card0", "card0-HDMI-A-1")
+        );
+        assert_eq!(
+            Some("eDP-1".to_string()),
+            Gpu::connector_name("card1", "card1-eDP-1")
+        );
+    }
+
+    #[test]
+    fn connector_name_other_card() {
+        assert_eq!(None, Gpu::connector_name("card1", "card0-DP-1"));
+        assert_eq!(None, Gpu::connector_name("card1", "card1"));
+    }
+
+    fn edid_with_descriptors(descriptors: [[u8; 18]; 4]) -> Vec<u8> {
+        let mut edid = vec![0; 128];
+        for (i, descriptor) in descriptors.iter().enumerate() {
+            edid[54 + i * 18..54 + (i + 1) * 18].copy_from_slice(descriptor);
+        }
+        edid
+    }
+
+    #[test]
+    fn edid_monitor_name_valid() {
+        let timing = [0x01; 18];
+        let mut name = [
+            0, 0, 0, 0xFC, 0, b'D', b'E', b'L', b'L', b' ', b'U', b'2', b'7', b'2', b'0', b'Q',
+            b'\n', b' ',
+        ];
+        let serial = [
+            0, 0, 0, 0xFF, 0, b'1', b'2', b'3', b'\n', b' ', b' ', b' ', b' ', b' ', b' ', b' ',
+            b' ', b' ',
+        ];
+
+        assert_eq!(
+            Some("DELL U2720Q".to_string()),
+            ConnectedDisplay::parse_edid_monitor_name(edid_with_descriptors([
+                timing, serial, name, timing
+            ]))
+        );
+
+        // names that fill the whole descriptor aren't terminated
+        name[5..].copy_from_slice(b"ABCDEFGHIJKLM");
+        assert_eq!(
+            Some("ABCDEFGHIJKLM".to_string()),
+            ConnectedDisplay::parse_edid_monitor_name(edid_with_descriptors([
+                name, timing, timing, timing
+            ]))
+        );
+    }
+
+    #[test]
+    fn edid_monitor_name_missing() {
+        assert_eq!(
+            None,
+            ConnectedDisplay::parse_edid_monitor_name(Vec::<u8>::new())
+        );
+        assert_eq!(
+            None,
+            ConnectedDisplay::parse_edid_monitor_name(edid_with_descriptors([[0x01; 18]; 4]))
+        );
+    }
+
+    #[test]
+    fn fan_speed_fraction() {
+        let gpu_data = |fan_speed, fan_speed_max| GpuData {
+            gpu_identifier: GpuIdentifier::Enumerator(0),
+            usage_fraction: None,
+            encode_fraction: None,
+            decode_fraction: None,
+            total_vram: None,
+            used_vram: None,
+            clock_speed: None,
+            vram_speed: None,
+            clock_states: None,
+            vram_clock_states: None,
+            temperature: None,
+            sensor_temperatures: Vec::new(),
+            power_usage: None,
+            board_power_usage: None,
+            power_cap: None,
+            power_cap_max: None,
+            ecc_errors: None,
+            fan_speed,
+            fan_speed_max,
+            fans: Vec::new(),
+            connected_displays: Vec::new(),
+            boot_vga: false,
+            primary_display: false,
+            nvidia: false,
+        };
+
+        assert_eq!(
+            Some(0.4),
+            gpu_data(Some(FanSpeed::Fraction(0.4)), None).fan_speed_fraction()
+        );
+        assert_eq!(
+            Some(0.5),
+            gpu_data(Some(FanSpeed::Rpm(1600.0)), Some(3200.0)).fan_speed_fraction()
+        );
+        assert_eq!(
+            Some(1.0),
+            gpu_data(Some(FanSpeed::Rpm(3500.0)), Some(3200.0)).fan_speed_fraction()
+        );
+        assert_eq!(
+            None,
+            gpu_data(Some(FanSpeed::Rpm(1600.0)), None).fan_speed_fraction()
+        );
+        assert_eq!(
+            None,
+            gpu_data(Some(FanSpeed::Rpm(1600.0)), Some(0.0)).fan_speed_fraction()
+        );
+        assert_eq!(None, gpu_data(None, Some(3200.0)).fan_speed_fraction());
+    }
 }
@@ -1,7 +1,7 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use log::{debug, warn};
 use nvml_wrapper::{
-    enum_wrappers::device::{Clock, TemperatureSensor},
+    enum_wrappers::device::{Clock, EccCounter, MemoryError, TemperatureSensor},
     error::NvmlError,
     Nvml,
 };
@@ -28,7 +28,7 @@ static NVML: LazyLock<Result<Nvml, NvmlError>> = LazyLock::new(|| {
 
 use crate::utils::{pci::Device, IS_FLATPAK};
 
-use super::GpuImpl;
+use super::{DpmStates, EccErrors, FanSpeed, GpuImpl};
 
 #[derive(Debug, Default, Clone)]
 
@@ -170,6 +170,11 @@ impl GpuImpl for NvidiaGpu {
             .or_else(|_| self.hwmon_power_usage())
     }
 
+    fn board_power_usage(&self) -> Result<f64> {
+        // NVML's power usage already refers to the whole board, so only hwmon can tell us about a separate value
+        self.hwmon_board_power_usage()
+    }
+
     fn core_frequency(&self) -> Result<f64> {
         Self::nvml_device(&self.pci_slot_string)
             .and_then(|dev| {
@@ -190,6 +195,14 @@ impl GpuImpl for NvidiaGpu {
             .or_else(|_| self.hwmon_vram_frequency())
     }
 
+    fn core_dpm_states(&self) -> Result<DpmStates> {
+        bail!("DPM states not implemented for NVIDIA")
+    }
+
+    fn vram_dpm_states(&self) -> Result<DpmStates> {
+        bail!("DPM states not implemented for NVIDIA")
+    }
+
     fn power_cap(&self) -> Result<f64> {
         Self::nvml_device(&self.pci_slot_string)
             .and_then(|dev| {
@@ -209,4 +222,37 @@ impl GpuImpl for NvidiaGpu {
             .map(|constraints| (constraints.max_limit as f64) / 1000.0)
             .or_else(|_| self.hwmon_power_cap_max())
     }
+
+    fn ecc_errors(&self) -> Result<EccErrors> {
+        // consumer cards without ECC will return NotSupported here
+        let device = Self::nvml_device(&self.pci_slot_string)?;
+
+        let corrected = device
+            .total_ecc_errors(MemoryError::Corrected, EccCounter::Aggregate)
+            .context("unable to get corrected ECC errors through NVML")?;
+
+        let uncorrected = device
+            .total_ecc_errors(MemoryError::Uncorrected, EccCounter::Aggregate)
+            .context("unable to get uncorrected ECC errors through NVML")?;
+
+        Ok(EccErrors {
+            corrected,
+            uncorrected,
+        })
+    }
+
+    fn fan_speed(&self) -> Result<FanSpeed> {
+        // NVML only reports the speed in percent of the fan's maximum
+        Self::nvml_device(&self.pci_slot_string)
+            .and_then(|dev| {
+                dev.fan_speed(0)
+                    .context("unable to get fan speed through NVML")
+            })
+            .map(|percent| FanSpeed::Fraction(f64::from(percent) / 100.0))
+            .or_else(|_| self.hwmon_fan_speed())
+    }
+
+    fn fan_speed_max(&self) -> Result<f64> {
+        self.hwmon_fan_speed_max()
+    }
 }
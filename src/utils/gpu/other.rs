@@ -5,7 +5,7 @@ use std::path::PathBuf;
 
 use crate::utils::pci::Device;
 
-use super::GpuImpl;
+use super::{DpmStates, EccErrors, FanSpeed, GpuImpl};
 
 #[derive(Debug, Clone, Default)]
 
@@ -92,6 +92,10 @@ impl GpuImpl for OtherGpu {
         self.hwmon_power_usage()
     }
 
+    fn board_power_usage(&self) -> Result<f64> {
+        self.hwmon_board_power_usage()
+    }
+
     fn core_frequency(&self) -> Result<f64> {
         self.hwmon_core_frequency()
     }
@@ -100,6 +104,14 @@ impl GpuImpl for OtherGpu {
         self.hwmon_vram_frequency()
     }
 
+    fn core_dpm_states(&self) -> Result<DpmStates> {
+        bail!("DPM states not implemented for other")
+    }
+
+    fn vram_dpm_states(&self) -> Result<DpmStates> {
+        bail!("DPM states not implemented for other")
+    }
+
     fn power_cap(&self) -> Result<f64> {
         self.hwmon_power_cap()
     }
@@ -107,4 +119,16 @@ impl GpuImpl for OtherGpu {
     fn power_cap_max(&self) -> Result<f64> {
         self.hwmon_power_cap_max()
     }
+
+    fn ecc_errors(&self) -> Result<EccErrors> {
+        bail!("ECC error counts not implemented for other")
+    }
+
+    fn fan_speed(&self) -> Result<FanSpeed> {
+        self.hwmon_fan_speed()
+    }
+
+    fn fan_speed_max(&self) -> Result<f64> {
+        self.hwmon_fan_speed_max()
+    }
 }
@@ -0,0 +1,476 @@
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    ffi::OsStr,
+    fs::{File, OpenOptions},
+    io::{Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    sync::LazyLock,
+    time::{Duration, Instant},
+};
+
+use anyhow::{bail, Context, Result};
+use log::{debug, info, trace};
+use process_data::{unix_as_millis, GpuIdentifier};
+
+use super::{
+    metrics_log::{MetricsSampler, MetricsSnapshot},
+    STATE_DIR,
+};
+
+/// Where the history is kept, one ring buffer file per series
+pub static HISTORY_DIR: LazyLock<PathBuf> = LazyLock::new(|| STATE_DIR.join("history"));
+
+/// How often a sample is recorded
+pub const SAMPLE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How far back the history reaches before the oldest samples are overwritten
+pub const MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+const MAGIC: &[u8; 4] = b"RSHB";
+
+const VERSION: u16 = 1;
+
+/// Magic, version, padding, capacity and the index of the next record to be written
+const HEADER_SIZE: u64 = 16;
+
+/// Unix timestamp in seconds and the value
+const RECORD_SIZE: u64 = 12;
+
+pub const CPU_USAGE: &str = "cpu-usage";
+
+pub const MEMORY_USAGE: &str = "memory-usage";
+
+pub fn gpu_usage(gpu_identifier: GpuIdentifier) -> String {
+    format!("gpu-{gpu_identifier}-usage")
+}
+
+pub fn gpu_vram_usage(gpu_identifier: GpuIdentifier) -> String {
+    format!("gpu-{gpu_identifier}-vram-usage")
+}
+
+pub fn network_received(interface_name: &OsStr) -> String {
+    format!("network-{}-received", interface_name.to_string_lossy())
+}
+
+pub fn network_sent(interface_name: &OsStr) -> String {
+    format!("network-{}-sent", interface_name.to_string_lossy())
+}
+
+/// A fixed amount of `(timestamp, value)` records in a file, once it's full the oldest record is overwritten
+#[derive(Debug)]
+pub struct RingBuffer {
+    file: File,
+    capacity: u32,
+    next: u32,
+}
+
+impl RingBuffer {
+    /// Opens the ring buffer at `path` or creates it with room for `capacity` records. An existing buffer with a
+    /// different capacity is started over.
+    pub fn open<P: AsRef<Path>>(path: P, capacity: u32) -> Result<Self> {
+        let path = path.as_ref();
+
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)
+            .with_context(|| format!("unable to open history file {}", path.display()))?;
+
+        match Self::read_header(&mut file) {
+            Ok((existing_capacity, next)) if existing_capacity == capacity && next < capacity => {
+                Ok(Self {
+                    file,
+                    capacity,
+                    next,
+                })
+            }
+            _ => {
+                debug!("Starting new history file {}", path.display());
+
+                file.set_len(0)?;
+                file.set_len(HEADER_SIZE + u64::from(capacity) * RECORD_SIZE)?;
+
+                let mut ring_buffer = Self {
+                    file,
+                    capacity,
+                    next: 0,
+                };
+                ring_buffer.write_header()?;
+
+                Ok(ring_buffer)
+            }
+        }
+    }
+
+    fn read_header(file: &mut File) -> Result<(u32, u32)> {
+        let mut header = [0; HEADER_SIZE as usize];
+        file.seek(SeekFrom::Start(0))?;
+        file.read_exact(&mut header)?;
+
+        if &header[0..4] != MAGIC || u16::from_le_bytes([header[4], header[5]]) != VERSION {
+            bail!("unknown history file format");
+        }
+
+        let capacity = u32::from_le_bytes(header[8..12].try_into()?);
+        let next = u32::from_le_bytes(header[12..16].try_into()?);
+
+        Ok((capacity, next))
+    }
+
+    fn write_header(&mut self) -> Result<()> {
+        let mut header = [0; HEADER_SIZE as usize];
+        header[0..4].copy_from_slice(MAGIC);
+        header[4..6].copy_from_slice(&VERSION.to_le_bytes());
+        header[8..12].copy_from_slice(&self.capacity.to_le_bytes());
+        header[12..16].copy_from_slice(&self.next.to_le_bytes());
+
+        self.file.seek(SeekFrom::Start(0))?;
+        self.file.write_all(&header)?;
+
+        Ok(())
+    }
+
+    pub fn push(&mut self, timestamp: u64, value: f32) -> Result<()> {
+        let mut record = [0; RECORD_SIZE as usize];
+        record[0..8].copy_from_slice(&timestamp.to_le_bytes());
+        record[8..12].copy_from_slice(&value.to_le_bytes());
+
+        self.file.seek(SeekFrom::Start(
+            HEADER_SIZE + u64::from(self.next) * RECORD_SIZE,
+        ))?;
+        self.file.write_all(&record)?;
+
+        self.next = (self.next + 1) % self.capacity;
+        self.write_header()
+    }
+
+    /// Returns all records from `since` (a Unix timestamp in seconds) on, ordered from oldest to newest
+    pub fn records_since(&mut self, since: u64) -> Result<Vec<(u64, f32)>> {
+        let mut bytes = Vec::new();
+        self.file.seek(SeekFrom::Start(HEADER_SIZE))?;
+        self.file.read_to_end(&mut bytes)?;
+
+        let mut records = bytes
+            .chunks_exact(RECORD_SIZE as usize)
+            .filter_map(|record| {
+                let timestamp = u64::from_le_bytes(record[0..8].try_into().ok()?);
+                let value = f32::from_le_bytes(record[8..12].try_into().ok()?);
+                // unused records are all zeroes
+                (timestamp != 0 && timestamp >= since && value.is_finite())
+                    .then_some((timestamp, value))
+            })
+            .collect::<Vec<_>>();
+
+        records.sort_by_key(|(timestamp, _)| *timestamp);
+
+        Ok(records)
+    }
+}
+
+/// Records the usage of CPU, memory, GPUs and network interfaces to the history every `SAMPLE_INTERVAL`
+#[derive(Debug)]
+pub struct HistoryRecorder {
+    dir: PathBuf,
+    sampler: MetricsSampler,
+    ring_buffers: HashMap<String, RingBuffer>,
+    last_record: Option<Instant>,
+    last_prune: Option<Instant>,
+}
+
+impl HistoryRecorder {
+    pub fn new<P: AsRef<Path>>(dir: P) -> Self {
+        let dir = dir.as_ref().to_path_buf();
+
+        info!("Recording history to {}", dir.display());
+
+        Self {
+            dir,
+            sampler: MetricsSampler::default(),
+            ring_buffers: HashMap::new(),
+            last_record: None,
+            last_prune: None,
+        }
+    }
+
+    /// Whether a sample is due in the refresh that's about to happen, `refresh_interval` being the seconds between
+    /// two refreshes
+    pub fn is_due(&self, refresh_interval: f32) -> bool {
+        is_sample_due(
+            self.last_record
+                .map(|last_record| last_record.elapsed().as_secs_f32()),
+            refresh_interval,
+        )
+    }
+
+    pub fn record(&mut self, snapshot: &MetricsSnapshot) -> Result<()> {
+        trace!("Recording history…");
+
+        self.last_record = Some(Instant::now());
+
+        let timestamp = unix_as_millis() / 1000;
+
+        let series = self.series(snapshot);
+
+        // interfaces (and GPUs) that are gone don't need their files to be kept open
+        self.ring_buffers
+            .retain(|name, _| series.iter().any(|(series_name, _)| series_name == name));
+
+        if self
+            .last_prune
+            .map_or(true, |last_prune| last_prune.elapsed() >= MAX_AGE)
+        {
+            self.last_prune = Some(Instant::now());
+            if let Err(e) = prune(&self.dir, MAX_AGE) {
+                debug!("Unable to prune history files: {e}");
+            }
+        }
+
+        for (series, value) in series {
+            let ring_buffer = match self.ring_buffers.entry(series) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    std::fs::create_dir_all(&self.dir).with_context(|| {
+                        format!("unable to create history directory {}", self.dir.display())
+                    })?;
+                    let path = series_path(&self.dir, entry.key());
+                    entry.insert(RingBuffer::open(path, capacity())?)
+                }
+            };
+
+            ring_buffer.push(timestamp, value as f32)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the values of `snapshot` in the same form as they're shown in the graphs
+    fn series(&mut self, snapshot: &MetricsSnapshot) -> Vec<(String, f64)> {
+        let mut series = Vec::new();
+
+        // the sampler already takes care of the rates, so reuse it for those
+        for (device, metric, value) in self.sampler.rows(snapshot) {
+            match metric {
+                "usage" if device == "cpu" => series.push((CPU_USAGE.to_string(), value)),
+                "received_bytes_per_second" | "sent_bytes_per_second" => {
+                    let direction = metric.trim_end_matches("_bytes_per_second");
+                    series.push((format!("{device}-{direction}"), value));
+                }
+                _ => (),
+            }
+        }
+
+        if let Some(mem_data) = snapshot.mem_data {
+            let used_mem = mem_data.total_mem.saturating_sub(mem_data.available_mem);
+            series.push((
+                MEMORY_USAGE.to_string(),
+                used_mem as f64 / mem_data.total_mem as f64,
+            ));
+        }
+
+        for gpu_data in snapshot.gpu_data {
+            if let Some(usage_fraction) = gpu_data.usage_fraction {
+                series.push((gpu_usage(gpu_data.gpu_identifier), usage_fraction));
+            }
+
+            if let (Some(total_vram), Some(used_vram)) = (gpu_data.total_vram, gpu_data.used_vram) {
+                series.push((
+                    gpu_vram_usage(gpu_data.gpu_identifier),
+                    used_vram as f64 / total_vram as f64,
+                ));
+            }
+        }
+
+        series.retain(|(_, value)| value.is_finite());
+
+        series
+    }
+}
+
+/// Samples are taken in refreshes, so allow for them to be up to half a refresh early, otherwise every other sample
+/// might be a whole refresh late
+fn is_sample_due(elapsed: Option<f32>, refresh_interval: f32) -> bool {
+    match elapsed {
+        Some(elapsed) => elapsed + refresh_interval / 2.0 >= SAMPLE_INTERVAL.as_secs_f32(),
+        None => true,
+    }
+}
+
+/// Returns the history of `series` over the last `duration` as one point per `points`-th of it, with the timestamp
+/// (Unix time in seconds) of the start of each point. Points without any samples, e.g. because Resources wasn't
+/// running, are zero.
+pub fn load(series: &str, duration: Duration, points: usize) -> Result<Vec<(u64, f64)>> {
+    let path = series_path(&HISTORY_DIR, series);
+
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let now = unix_as_millis() / 1000;
+    let since = now.saturating_sub(duration.as_secs());
+
+    let records = RingBuffer::open(path, capacity())?.records_since(since)?;
+
+    Ok(resample(&records, since, now, points))
+}
+
+/// Averages `records` into `points` equally long buckets between `start` and `end`
+fn resample(records: &[(u64, f32)], start: u64, end: u64, points: usize) -> Vec<(u64, f64)> {
+    if points == 0 || end <= start {
+        return Vec::new();
+    }
+
+    let bucket_length = (end - start) as f64 / points as f64;
+
+    let mut sums = vec![(0.0, 0); points];
+    for (timestamp, value) in records {
+        if *timestamp < start || *timestamp > end {
+            continue;
+        }
+
+        let bucket = (((timestamp - start) as f64 / bucket_length) as usize).min(points - 1);
+        sums[bucket].0 += f64::from(*value);
+        sums[bucket].1 += 1;
+    }
+
+    sums.into_iter()
+        .enumerate()
+        .map(|(i, (sum, count))| {
+            let timestamp = start + (i as f64 * bucket_length) as u64;
+            let average = if count == 0 { 0.0 } else { sum / count as f64 };
+            (timestamp, average)
+        })
+        .collect()
+}
+
+/// Removes the files of series that haven't been recorded for longer than `max_age`, e.g. those of network interfaces
+/// that only existed for a while, since all of their samples are too old to be shown anyway. Returns how many files
+/// have been removed.
+fn prune(dir: &Path, max_age: Duration) -> Result<usize> {
+    if !dir.exists() {
+        return Ok(0);
+    }
+
+    let mut removed = 0;
+
+    for entry in std::fs::read_dir(dir)
+        .with_context(|| format!("unable to read history directory {}", dir.display()))?
+        .flatten()
+    {
+        let path = entry.path();
+
+        if path.extension() != Some(OsStr::new("bin")) {
+            continue;
+        }
+
+        // every recorded sample modifies the file
+        let is_stale = entry
+            .metadata()
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age > max_age);
+
+        if is_stale {
+            debug!("Removing stale history file {}", path.display());
+            std::fs::remove_file(&path)?;
+            removed += 1;
+        }
+    }
+
+    Ok(removed)
+}
+
+fn series_path(dir: &Path, series: &str) -> PathBuf {
+    dir.join(format!("{}.bin", series.replace(['/', '\0'], "_")))
+}
+
+fn capacity() -> u32 {
+    (MAX_AGE.as_secs() / SAMPLE_INTERVAL.as_secs()) as u32
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use std::time::{Duration, SystemTime};
+
+    use super::{is_sample_due, prune, resample, RingBuffer};
+    use crate::utils::test_dir;
+
+    #[test]
+    fn ring_buffer_overwrites_oldest() {
+        let dir = test_dir("history-overwrite");
+        let path = dir.join("cpu-usage.bin");
+
+        let mut ring_buffer = RingBuffer::open(&path, 3).unwrap();
+        for i in 1..=5 {
+            ring_buffer.push(100 + i, i as f32).unwrap();
+        }
+        drop(ring_buffer);
+
+        let mut ring_buffer = RingBuffer::open(&path, 3).unwrap();
+        assert_eq!(
+            vec![(103, 3.0), (104, 4.0), (105, 5.0)],
+            ring_buffer.records_since(0).unwrap()
+        );
+        assert_eq!(vec![(105, 5.0)], ring_buffer.records_since(105).unwrap());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn ring_buffer_resets_on_capacity_change() {
+        let dir = test_dir("history-capacity");
+        let path = dir.join("cpu-usage.bin");
+
+        RingBuffer::open(&path, 3).unwrap().push(100, 1.0).unwrap();
+
+        let mut ring_buffer = RingBuffer::open(&path, 4).unwrap();
+        assert_eq!(
+            Vec::<(u64, f32)>::new(),
+            ring_buffer.records_since(0).unwrap()
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn prune_stale_files() {
+        let dir = test_dir("history-prune");
+
+        RingBuffer::open(dir.join("cpu-usage.bin"), 3).unwrap();
+
+        let stale = RingBuffer::open(dir.join("network-veth0-received.bin"), 3).unwrap();
+        stale
+            .file
+            .set_modified(SystemTime::now() - Duration::from_secs(2 * 24 * 60 * 60))
+            .unwrap();
+        drop(stale);
+
+        assert_eq!(1, prune(&dir, Duration::from_secs(24 * 60 * 60)).unwrap());
+        assert!(dir.join("cpu-usage.bin").exists());
+        assert!(!dir.join("network-veth0-received.bin").exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn sample_due() {
+        assert!(is_sample_due(None, 1.0));
+        assert!(!is_sample_due(Some(9.0), 1.0));
+        assert!(is_sample_due(Some(9.6), 1.0));
+        assert!(is_sample_due(Some(8.0), 5.0));
+    }
+
+    #[test]
+    fn resample_averages_buckets() {
+        let records = [(100, 1.0), (101, 3.0), (115, 0.5)];
+
+        assert_eq!(
+            vec![(100, 2.0), (105, 0.0), (110, 0.0), (115, 0.5)],
+            resample(&records, 100, 120, 4)
+        );
+    }
+}
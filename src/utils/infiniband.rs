@@ -0,0 +1,285 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use gtk::gio::{Icon, ThemedIcon};
+use log::{trace, warn};
+
+use super::{pci::Device, read_uevent};
+
+const PATH_SYSFS: &str = "/sys/class/infiniband";
+
+// port_xmit_data and port_rcv_data count in units of 4 octets, see the InfiniBand specification
+const DATA_COUNTER_UNIT: u64 = 4;
+
+#[derive(Debug)]
+pub struct InfinibandData {
+    pub inner: InfinibandPort,
+    pub received_bytes: Result<u64>,
+    pub sent_bytes: Result<u64>,
+    pub state: Option<String>,
+    pub physical_state: Option<String>,
+    pub rate: Option<String>,
+    pub display_name: String,
+}
+
+impl InfinibandData {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        let path = path.as_ref();
+
+        trace!("Gathering InfiniBand data for {path:?}…");
+
+        let inner = InfinibandPort::from_sysfs(path);
+        let received_bytes = inner.received_bytes();
+        let sent_bytes = inner.sent_bytes();
+        let state = inner.state();
+        let physical_state = inner.physical_state();
+        let rate = inner.rate();
+        let display_name = inner.display_name();
+
+        let infiniband_data = Self {
+            inner,
+            received_bytes,
+            sent_bytes,
+            state,
+            physical_state,
+            rate,
+            display_name,
+        };
+
+        trace!(
+            "Gathered InfiniBand data for {}: {infiniband_data:?}",
+            path.to_string_lossy()
+        );
+
+        infiniband_data
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+/// Represents a port of an RDMA device found in /sys/class/infiniband
+pub struct InfinibandPort {
+    pub device_name: String,
+    pub port_number: String,
+    pub driver_name: Option<String>,
+    pub device: Option<&'static Device>,
+    pub link_layer: Option<String>,
+    pub firmware_version: Option<String>,
+    /// Network interfaces (e.g. IPoIB) that belong to the RDMA device
+    pub net_devices: Vec<String>,
+    pub sysfs_path: PathBuf,
+}
+
+impl PartialEq for InfinibandPort {
+    fn eq(&self, other: &Self) -> bool {
+        self.device_name == other.device_name && self.port_number == other.port_number
+    }
+}
+
+impl InfinibandPort {
+    /// Returns the sysfs paths of all ports of all RDMA devices
+    pub fn get_sysfs_paths() -> Result<Vec<PathBuf>> {
+        let mut list = Vec::new();
+
+        trace!("Finding entries in {PATH_SYSFS}");
+
+        for device in std::fs::read_dir(PATH_SYSFS)?.flatten() {
+            // one broken device shouldn't hide the ports of all the others
+            let ports = match std::fs::read_dir(device.path().join("ports")) {
+                Ok(ports) => ports,
+                Err(e) => {
+                    warn!(
+                        "Unable to read ports of {}: {e}",
+                        device.file_name().to_string_lossy()
+                    );
+                    continue;
+                }
+            };
+
+            for port in ports.flatten() {
+                trace!(
+                    "Found InfiniBand port {} of {}",
+                    port.file_name().to_string_lossy(),
+                    device.file_name().to_string_lossy()
+                );
+                list.push(port.path());
+            }
+        }
+
+        Ok(list)
+    }
+
+    /// Returns an `InfinibandPort` based on the information found in the sysfs path of the port, which looks like
+    /// /sys/class/infiniband/<device>/ports/<port>
+    pub fn from_sysfs(sysfs_path: &Path) -> InfinibandPort {
+        trace!("Creating InfinibandPort object of {sysfs_path:?}…");
+
+        let port_number = sysfs_path
+            .file_name()
+            .expect("invalid sysfs path")
+            .to_string_lossy()
+            .to_string();
+
+        let device_path = sysfs_path
+            .parent()
+            .and_then(Path::parent)
+            .expect("invalid sysfs path");
+
+        let device_name = device_path
+            .file_name()
+            .expect("invalid sysfs path")
+            .to_string_lossy()
+            .to_string();
+
+        let dev_uevent = read_uevent(device_path.join("device/uevent")).unwrap_or_default();
+
+        let device = if let Some(pci_line) = dev_uevent.get("PCI_ID") {
+            let (vid_str, pid_str) = pci_line.split_once(':').unwrap_or(("0", "0"));
+            let vid = u16::from_str_radix(vid_str, 16).unwrap_or_default();
+            let pid = u16::from_str_radix(pid_str, 16).unwrap_or_default();
+            Device::from_vid_pid(vid, pid)
+        } else {
+            None
+        };
+
+        let driver_name = dev_uevent.get("DRIVER").cloned();
+
+        let link_layer = std::fs::read_to_string(sysfs_path.join("link_layer"))
+            .map(|x| x.trim().to_string())
+            .ok();
+
+        let firmware_version = std::fs::read_to_string(device_path.join("fw_ver"))
+            .map(|x| x.trim().to_string())
+            .ok();
+
+        let mut net_devices: Vec<String> = std::fs::read_dir(device_path.join("device/net"))
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|entry| entry.file_name().to_string_lossy().to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        net_devices.sort();
+
+        let infiniband_port = InfinibandPort {
+            device_name,
+            port_number,
+            driver_name,
+            device,
+            link_layer,
+            firmware_version,
+            net_devices,
+            sysfs_path: sysfs_path.to_path_buf(),
+        };
+
+        trace!("Created InfinibandPort object of {sysfs_path:?}: {infiniband_port:?}");
+
+        infiniband_port
+    }
+
+    /// Returns a display name for this port, preferably the name of the adapter
+    pub fn display_name(&self) -> String {
+        self.device
+            .map(|device| device.name().to_string())
+            .unwrap_or_else(|| self.device_name.clone())
+    }
+
+    fn read_data_counter(&self, counter: &str) -> Result<u64> {
+        std::fs::read_to_string(self.sysfs_path.join("counters").join(counter))
+            .with_context(|| format!("unable to read counter {counter}"))?
+            .trim()
+            .parse::<u64>()
+            .with_context(|| format!("unable to parse counter {counter}"))
+            .map(|words| words.saturating_mul(DATA_COUNTER_UNIT))
+    }
+
+    /// Returns the amount of bytes received by this port
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the `port_rcv_data` counter is unreadable or unparsable
+    pub fn received_bytes(&self) -> Result<u64> {
+        self.read_data_counter("port_rcv_data")
+    }
+
+    /// Returns the amount of bytes sent by this port
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the `port_xmit_data` counter is unreadable or unparsable
+    pub fn sent_bytes(&self) -> Result<u64> {
+        self.read_data_counter("port_xmit_data")
+    }
+
+    /// Returns the logical state of the port, e.g. "ACTIVE"
+    pub fn state(&self) -> Option<String> {
+        std::fs::read_to_string(self.sysfs_path.join("state"))
+            .ok()
+            .and_then(Self::parse_state)
+    }
+
+    /// Returns the physical state of the port, e.g. "LinkUp"
+    pub fn physical_state(&self) -> Option<String> {
+        std::fs::read_to_string(self.sysfs_path.join("phys_state"))
+            .ok()
+            .and_then(Self::parse_state)
+    }
+
+    /// Returns the link rate of the port, e.g. "100 Gb/sec (4X EDR)"
+    pub fn rate(&self) -> Option<String> {
+        std::fs::read_to_string(self.sysfs_path.join("rate"))
+            .ok()
+            .map(|rate| rate.trim().to_string())
+            .filter(|rate| !rate.is_empty())
+    }
+
+    /// The state files contain the numeric state followed by its name, like "4: ACTIVE"
+    fn parse_state<S: AsRef<str>>(state: S) -> Option<String> {
+        let state = state.as_ref().trim();
+
+        let name = state.split_once(':').map_or(state, |(_, name)| name).trim();
+
+        if name.is_empty() {
+            None
+        } else {
+            Some(name.to_string())
+        }
+    }
+
+    pub fn icon(&self) -> Icon {
+        ThemedIcon::new("infiniband-symbolic").into()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::InfinibandPort;
+
+    #[test]
+    fn parse_state_valid() {
+        assert_eq!(
+            Some("ACTIVE".to_string()),
+            InfinibandPort::parse_state("4: ACTIVE\n")
+        );
+        assert_eq!(
+            Some("LinkUp".to_string()),
+            InfinibandPort::parse_state("5: LinkUp\n")
+        );
+    }
+
+    #[test]
+    fn parse_state_without_number() {
+        assert_eq!(
+            Some("DOWN".to_string()),
+            InfinibandPort::parse_state("DOWN")
+        );
+    }
+
+    #[test]
+    fn parse_state_empty() {
+        assert_eq!(None, InfinibandPort::parse_state("\n"));
+        assert_eq!(None, InfinibandPort::parse_state("1: "));
+    }
+}
@@ -0,0 +1,171 @@
+use anyhow::Result;
+use gtk::glib::DateTime;
+
+use super::{
+    app::AppsContext,
+    metrics_log::{MetricsSampler, MetricsSnapshot},
+    process::Process,
+};
+
+/// Formats snapshots of the system's metrics as JSON objects, one per line, so that periodic output can be read as JSON
+/// Lines
+#[derive(Debug, Default)]
+pub struct JsonOutput {
+    sampler: MetricsSampler,
+}
+
+impl JsonOutput {
+    /// Formats `snapshot` and, if given, the processes of `apps_context` as
+    /// `{"timestamp":…,"devices":{"<device>":{"<metric>":<value>,…},…},"processes":[{…},…]}`. The devices and metrics
+    /// are the same as in the metrics log, so rates are only included from the second snapshot on.
+    pub fn format_snapshot(
+        &mut self,
+        snapshot: &MetricsSnapshot,
+        apps_context: Option<&AppsContext>,
+    ) -> Result<String> {
+        let timestamp = DateTime::now_local()?.format_iso8601()?;
+
+        let mut members = vec![
+            ("timestamp", json_string(&timestamp)),
+            ("devices", format_devices(&self.sampler.rows(snapshot))),
+        ];
+
+        if let Some(apps_context) = apps_context {
+            let mut processes = apps_context.processes_iter().collect::<Vec<_>>();
+            processes.sort_by_key(|process| process.data.pid);
+
+            let processes = processes
+                .into_iter()
+                .map(format_process)
+                .collect::<Vec<_>>()
+                .join(",");
+
+            members.push(("processes", format!("[{processes}]")));
+        }
+
+        Ok(format_object(members))
+    }
+}
+
+/// Groups the `(device, metric, value)` rows into one object per device, the rows of a device are expected to be next
+/// to each other
+fn format_devices(rows: &[(String, &str, f64)]) -> String {
+    let mut devices: Vec<(&str, Vec<(&str, String)>)> = Vec::new();
+
+    for (device, metric, value) in rows {
+        let member = (*metric, json_number(*value));
+        match devices.last_mut() {
+            Some((last_device, metrics)) if *last_device == device.as_str() => metrics.push(member),
+            _ => devices.push((device.as_str(), vec![member])),
+        }
+    }
+
+    format_object(
+        devices
+            .into_iter()
+            .map(|(device, metrics)| (device, format_object(metrics))),
+    )
+}
+
+fn format_process(process: &Process) -> String {
+    let optional_number =
+        |value: Option<f64>| value.map_or_else(|| "null".to_string(), json_number);
+
+    format_object([
+        ("pid", process.data.pid.to_string()),
+        ("parent_pid", process.data.parent_pid.to_string()),
+        ("name", json_string(&process.display_name)),
+        ("user", json_string(&process.data.user)),
+        ("commandline", json_string(&process.data.commandline)),
+        ("cpu_usage", json_number(process.cpu_time_ratio().into())),
+        ("memory_bytes", process.data.memory_usage.to_string()),
+        ("swap_bytes", process.data.swap_usage.to_string()),
+        (
+            "read_bytes_per_second",
+            optional_number(process.read_speed()),
+        ),
+        (
+            "write_bytes_per_second",
+            optional_number(process.write_speed()),
+        ),
+        ("gpu_usage", json_number(process.gpu_usage().into())),
+        ("gpu_memory_bytes", process.gpu_mem_usage().to_string()),
+    ])
+}
+
+/// Formats an object out of keys and already formatted values
+pub fn format_object<'a, I: IntoIterator<Item = (&'a str, String)>>(members: I) -> String {
+    let members = members
+        .into_iter()
+        .map(|(key, value)| format!("{}:{value}", json_string(key)))
+        .collect::<Vec<_>>()
+        .join(",");
+
+    format!("{{{members}}}")
+}
+
+pub fn json_string(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len() + 2);
+
+    escaped.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+
+    escaped
+}
+
+/// JSON has no representation for NaN or infinity, so they become `null`
+fn json_number(value: f64) -> String {
+    if value.is_finite() {
+        value.to_string()
+    } else {
+        "null".to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::{format_devices, json_number, json_string};
+
+    #[test]
+    fn json_string_escapes() {
+        assert_eq!(
+            r#""a \"quoted\" C:\\path\nwith\ttabs\u0007""#,
+            json_string("a \"quoted\" C:\\path\nwith\ttabs\u{7}")
+        );
+    }
+
+    #[test]
+    fn json_number_non_finite() {
+        assert_eq!("0.25", json_number(0.25));
+        assert_eq!("512", json_number(512.0));
+        assert_eq!("null", json_number(f64::NAN));
+        assert_eq!("null", json_number(f64::INFINITY));
+    }
+
+    #[test]
+    fn format_devices_groups_rows() {
+        let rows = [
+            ("cpu".to_string(), "usage", 0.5),
+            ("cpu".to_string(), "temperature", 45.0),
+            ("drive-\"a\"".to_string(), "read_bytes_per_second", 512.0),
+        ];
+
+        assert_eq!(
+            r#"{"cpu":{"usage":0.5,"temperature":45},"drive-\"a\"":{"read_bytes_per_second":512}}"#,
+            format_devices(&rows)
+        );
+    }
+}
@@ -44,14 +44,49 @@ static RE_SWAP_TOTAL: Lazy<Regex> = lazy_regex!(r"SwapTotal:\s*(\d*) kB");
 
 static RE_SWAP_FREE: Lazy<Regex> = lazy_regex!(r"SwapFree:\s*(\d*) kB");
 
+static RE_MEM_FREE: Lazy<Regex> = lazy_regex!(r"MemFree:\s*(\d*) kB");
+
+static RE_BUFFERS: Lazy<Regex> = lazy_regex!(r"Buffers:\s*(\d*) kB");
+
+// anchored so that we don't match SwapCached
+static RE_CACHED: Lazy<Regex> = lazy_regex!(r"(?m)^Cached:\s*(\d*) kB");
+
+static RE_SRECLAIMABLE: Lazy<Regex> = lazy_regex!(r"SReclaimable:\s*(\d*) kB");
+
+static RE_SUNRECLAIM: Lazy<Regex> = lazy_regex!(r"SUnreclaim:\s*(\d*) kB");
+
+static RE_KERNEL_STACK: Lazy<Regex> = lazy_regex!(r"KernelStack:\s*(\d*) kB");
+
+static RE_PAGE_TABLES: Lazy<Regex> = lazy_regex!(r"PageTables:\s*(\d*) kB");
+
+static RE_DIRTY: Lazy<Regex> = lazy_regex!(r"Dirty:\s*(\d*) kB");
+
+// anchored so that we don't match WritebackTmp
+static RE_WRITEBACK: Lazy<Regex> = lazy_regex!(r"(?m)^Writeback:\s*(\d*) kB");
+
 static RE_NUM_MEMORY_DEVICES: Lazy<Regex> = lazy_regex!(r"MEMORY_ARRAY_NUM_DEVICES=(\d*)");
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct MemoryData {
     pub total_mem: usize,
     pub available_mem: usize,
     pub total_swap: usize,
     pub free_swap: usize,
+    /// Memory that's not used for anything at all, unlike `available_mem` this doesn't include caches
+    pub free_mem: Option<usize>,
+    pub buffers: Option<usize>,
+    /// Page cache, doesn't include swap cache
+    pub cached: Option<usize>,
+    /// Slab memory the kernel can give back under memory pressure (mostly caches)
+    pub slab_reclaimable: Option<usize>,
+    /// Slab memory the kernel can't give back
+    pub slab_unreclaimable: Option<usize>,
+    pub kernel_stack: Option<usize>,
+    pub page_tables: Option<usize>,
+    /// Modified file contents that are waiting to be written back to their drives
+    pub dirty: Option<usize>,
+    /// Modified file contents that are actively being written back to their drives right now
+    pub writeback: Option<usize>,
 }
 
 impl MemoryData {
@@ -63,8 +98,25 @@ impl MemoryData {
             .inspect_err(|err| trace!("Unable to read {PROC_MEMINFO}: {err}"))
             .context("unable to read /proc/meminfo")?;
 
+        let memory_data = Self::parse_proc_meminfo(&proc_mem)?;
+
+        trace!("Gathered memory data: {memory_data:?}");
+
+        Ok(memory_data)
+    }
+
+    /// Returns the sum of reclaimable and unreclaimable slab memory if both are known
+    pub fn slab(&self) -> Option<usize> {
+        self.slab_reclaimable
+            .zip(self.slab_unreclaimable)
+            .map(|(reclaimable, unreclaimable)| reclaimable.saturating_add(unreclaimable))
+    }
+
+    fn parse_proc_meminfo<S: AsRef<str>>(proc_mem: S) -> Result<Self> {
+        let proc_mem = proc_mem.as_ref();
+
         let total_mem = RE_MEM_TOTAL
-            .captures(&proc_mem)
+            .captures(proc_mem)
             .context("RE_MEM_TOTAL no captures")
             .and_then(|captures| {
                 captures
@@ -80,7 +132,7 @@ impl MemoryData {
             })?;
 
         let available_mem = RE_MEM_AVAILABLE
-            .captures(&proc_mem)
+            .captures(proc_mem)
             .context("RE_MEM_AVAILABLE no captures")
             .and_then(|captures| {
                 captures
@@ -96,7 +148,7 @@ impl MemoryData {
             })?;
 
         let total_swap = RE_SWAP_TOTAL
-            .captures(&proc_mem)
+            .captures(proc_mem)
             .context("RE_SWAP_TOTAL no captures")
             .and_then(|captures| {
                 captures
@@ -112,7 +164,7 @@ impl MemoryData {
             })?;
 
         let free_swap = RE_SWAP_FREE
-            .captures(&proc_mem)
+            .captures(proc_mem)
             .context("RE_SWAP_FREE no captures")
             .and_then(|captures| {
                 captures
@@ -127,16 +179,30 @@ impl MemoryData {
                     })
             })?;
 
-        let memory_data = Self {
+        // these are purely informational, so don't fail if the kernel doesn't report them
+        let optional_kib = |regex: &Regex| {
+            regex
+                .captures(proc_mem)
+                .and_then(|captures| captures.get(1))
+                .and_then(|capture| capture.as_str().parse::<usize>().ok())
+                .map(|int| int.saturating_mul(1024))
+        };
+
+        Ok(Self {
             total_mem,
             available_mem,
             total_swap,
             free_swap,
-        };
-
-        trace!("Gathered memory data: {memory_data:?}");
-
-        Ok(memory_data)
+            free_mem: optional_kib(&RE_MEM_FREE),
+            buffers: optional_kib(&RE_BUFFERS),
+            cached: optional_kib(&RE_CACHED),
+            slab_reclaimable: optional_kib(&RE_SRECLAIMABLE),
+            slab_unreclaimable: optional_kib(&RE_SUNRECLAIM),
+            kernel_stack: optional_kib(&RE_KERNEL_STACK),
+            page_tables: optional_kib(&RE_PAGE_TABLES),
+            dirty: optional_kib(&RE_DIRTY),
+            writeback: optional_kib(&RE_WRITEBACK),
+        })
     }
 }
 
@@ -348,7 +414,29 @@ impl MemoryDevice {
 mod test {
     use pretty_assertions::assert_eq;
 
-    use crate::utils::memory::MemoryDevice;
+    use crate::utils::memory::{MemoryData, MemoryDevice};
+
+    const PROC_MEMINFO: &str = concat!(
+        "MemTotal:       32768000 kB\n",
+        "MemFree:         1024000 kB\n",
+        "MemAvailable:   16384000 kB\n",
+        "Buffers:          512000 kB\n",
+        "Cached:         12288000 kB\n",
+        "SwapCached:            0 kB\n",
+        "Active:         10240000 kB\n",
+        "Inactive:        8192000 kB\n",
+        "SwapTotal:       8192000 kB\n",
+        "SwapFree:        4096000 kB\n",
+        "Dirty:             20480 kB\n",
+        "Writeback:          1024 kB\n",
+        "Slab:            1536000 kB\n",
+        "SReclaimable:    1024000 kB\n",
+        "SUnreclaim:       512000 kB\n",
+        "KernelStack:       32000 kB\n",
+        "PageTables:        64000 kB\n",
+        "WritebackTmp:          0 kB\n",
+        "CommitLimit:    24576000 kB\n",
+    );
 
     const DMIDECODE_OUTPUT: &str = concat!(
         "Memory Device\n",
@@ -421,6 +509,48 @@ mod test {
         "E: MEMORY_ARRAY_NUM_DEVICES=2"
     );
 
+    #[test]
+    fn valid_proc_meminfo() {
+        let parsed = MemoryData::parse_proc_meminfo(PROC_MEMINFO).unwrap();
+
+        let expected = MemoryData {
+            total_mem: 32768000 * 1024,
+            available_mem: 16384000 * 1024,
+            total_swap: 8192000 * 1024,
+            free_swap: 4096000 * 1024,
+            free_mem: Some(1024000 * 1024),
+            buffers: Some(512000 * 1024),
+            cached: Some(12288000 * 1024),
+            slab_reclaimable: Some(1024000 * 1024),
+            slab_unreclaimable: Some(512000 * 1024),
+            kernel_stack: Some(32000 * 1024),
+            page_tables: Some(64000 * 1024),
+            dirty: Some(20480 * 1024),
+            writeback: Some(1024 * 1024),
+        };
+
+        assert_eq!(expected, parsed);
+        assert_eq!(Some(1536000 * 1024), parsed.slab());
+    }
+
+    #[test]
+    fn proc_meminfo_without_kernel_memory() {
+        let parsed = MemoryData::parse_proc_meminfo(concat!(
+            "MemTotal:       32768000 kB\n",
+            "MemAvailable:   16384000 kB\n",
+            "SwapTotal:             0 kB\n",
+            "SwapFree:              0 kB\n",
+        ))
+        .unwrap();
+
+        assert_eq!(None, parsed.cached);
+        assert_eq!(None, parsed.slab_reclaimable);
+        assert_eq!(None, parsed.slab());
+        assert_eq!(None, parsed.page_tables);
+        assert_eq!(None, parsed.dirty);
+        assert_eq!(None, parsed.writeback);
+    }
+
     #[test]
     fn valid_dmidecode_complex() {
         let parsed = MemoryDevice::parse_dmidecode(DMIDECODE_OUTPUT);
@@ -0,0 +1,356 @@
+use std::{
+    collections::HashMap,
+    fs::{File, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    time::Instant,
+};
+
+use anyhow::{Context, Result};
+use gtk::glib::DateTime;
+use log::{debug, info, trace};
+
+use super::{
+    battery::BatteryData, cpu::CpuData, drive::DriveData, gpu::GpuData, memory::MemoryData,
+    network::NetworkData,
+};
+
+const HEADER: &str = "timestamp,device,metric,value";
+
+/// How many rotated files (`<file>.1` being the newest, `<file>.N` the oldest) are kept besides the current log file
+const ROTATED_FILES: usize = 5;
+
+/// The block layer's statistics always count in 512-byte sectors, regardless of the drive's actual sector size
+const SECTOR_SIZE: usize = 512;
+
+/// The data of all devices at one point in time that is written to the metrics log
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MetricsSnapshot<'a> {
+    pub cpu_data: Option<&'a CpuData>,
+    pub mem_data: Option<&'a MemoryData>,
+    pub gpu_data: &'a [GpuData],
+    pub drive_data: &'a [DriveData],
+    pub network_data: &'a [NetworkData],
+    pub battery_data: &'a [BatteryData],
+}
+
+/// Appends periodic snapshots of the system's metrics to a CSV file in the long format `timestamp,device,metric,value`
+/// so that devices may come and go without changing the columns. Once the file exceeds `max_size` bytes, it's rotated.
+#[derive(Debug)]
+pub struct MetricsLog {
+    path: PathBuf,
+    max_size: u64,
+    file: File,
+    sampler: MetricsSampler,
+}
+
+/// Turns snapshots into `(device, metric, value)` rows, remembering the counters of the previous snapshot to calculate
+/// rates like CPU usage or drive throughput
+#[derive(Debug, Default)]
+pub struct MetricsSampler {
+    last_snapshot: Option<Instant>,
+    /// `(idle_time, total_time)` summed over all CPU threads at the last snapshot
+    cpu_times: Option<(u64, u64)>,
+    /// `(read_sectors, write_sectors)` of every drive at the last snapshot
+    drive_sectors: HashMap<PathBuf, (usize, usize)>,
+    /// `(received_bytes, sent_bytes)` of every network interface at the last snapshot
+    network_bytes: HashMap<PathBuf, (usize, usize)>,
+}
+
+impl MetricsLog {
+    /// Opens (or creates) the log file at `path` for appending
+    pub fn open<P: AsRef<Path>>(path: P, max_size: u64) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+
+        info!("Logging metrics to {}", path.display());
+
+        let file = Self::open_file(&path)?;
+
+        Ok(Self {
+            path,
+            max_size,
+            file,
+            sampler: MetricsSampler::default(),
+        })
+    }
+
+    fn open_file(path: &Path) -> Result<File> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("unable to open metrics log {}", path.display()))?;
+
+        if file.metadata()?.len() == 0 {
+            writeln!(file, "{HEADER}")?;
+        }
+
+        Ok(file)
+    }
+
+    /// Appends `snapshot` to the log, rotating the file beforehand if it has grown too large. Rates (like CPU usage or
+    /// drive throughput) are calculated using the previous snapshot, so they're left out of the first one.
+    pub fn write_snapshot(&mut self, snapshot: &MetricsSnapshot) -> Result<()> {
+        trace!("Writing metrics snapshot…");
+
+        if self.file.metadata()?.len() >= self.max_size {
+            debug!("Rotating metrics log {}", self.path.display());
+            rotate(&self.path, ROTATED_FILES)?;
+            self.file = Self::open_file(&self.path)?;
+        }
+
+        let timestamp = DateTime::now_local()?.format_iso8601()?;
+
+        let lines: String = self
+            .sampler
+            .rows(snapshot)
+            .iter()
+            .map(|(device, metric, value)| format_row(&timestamp, device, metric, *value))
+            .collect();
+
+        self.file.write_all(lines.as_bytes())?;
+
+        Ok(())
+    }
+}
+
+impl MetricsSampler {
+    /// Returns the rows of all metrics in `snapshot`, grouped by device. Rates are calculated using the previous
+    /// snapshot, so they're left out of the first one.
+    pub fn rows(&mut self, snapshot: &MetricsSnapshot) -> Vec<(String, &'static str, f64)> {
+        let now = Instant::now();
+        let elapsed = self
+            .last_snapshot
+            .replace(now)
+            .map(|last| now.duration_since(last).as_secs_f64())
+            .filter(|elapsed| *elapsed > 0.0);
+
+        let mut rows = Vec::new();
+
+        if let Some(cpu_data) = snapshot.cpu_data {
+            let cpu_times = cpu_data
+                .new_thread_usages
+                .iter()
+                .flatten()
+                .fold((0, 0), |(idle_sum, total_sum), (idle, total)| {
+                    (idle_sum + idle, total_sum + total)
+                });
+
+            if let Some((old_idle, old_total)) = self.cpu_times.replace(cpu_times) {
+                let idle_delta = cpu_times.0.saturating_sub(old_idle);
+                let total_delta = cpu_times.1.saturating_sub(old_total);
+                if total_delta > 0 {
+                    let usage = 1.0 - (idle_delta as f64 / total_delta as f64);
+                    rows.push(("cpu".into(), "usage", usage.clamp(0.0, 1.0)));
+                }
+            }
+
+            if let Ok(temperature) = cpu_data.temperature {
+                rows.push(("cpu".into(), "temperature", f64::from(temperature)));
+            }
+        }
+
+        if let Some(mem_data) = snapshot.mem_data {
+            rows.push((
+                "memory".into(),
+                "used_bytes",
+                mem_data.total_mem.saturating_sub(mem_data.available_mem) as f64,
+            ));
+            rows.push(("memory".into(), "total_bytes", mem_data.total_mem as f64));
+            rows.push((
+                "memory".into(),
+                "swap_used_bytes",
+                mem_data.total_swap.saturating_sub(mem_data.free_swap) as f64,
+            ));
+        }
+
+        for gpu_data in snapshot.gpu_data {
+            let device = format!("gpu-{}", gpu_data.gpu_identifier);
+
+            if let Some(usage) = gpu_data.usage_fraction {
+                rows.push((device.clone(), "usage", usage));
+            }
+            if let Some(used_vram) = gpu_data.used_vram {
+                rows.push((device.clone(), "vram_used_bytes", used_vram as f64));
+            }
+            if let Some(temperature) = gpu_data.temperature {
+                rows.push((device.clone(), "temperature", temperature));
+            }
+            if let Some(power_usage) = gpu_data.power_usage {
+                rows.push((device, "power_watts", power_usage));
+            }
+        }
+
+        let mut drive_sectors = HashMap::with_capacity(snapshot.drive_data.len());
+        for drive_data in snapshot.drive_data {
+            let (Some(read_sectors), Some(write_sectors)) = (
+                drive_data.disk_stats.get("read_sectors"),
+                drive_data.disk_stats.get("write_sectors"),
+            ) else {
+                continue;
+            };
+
+            let sysfs_path = &drive_data.inner.sysfs_path;
+            let device = format!("drive-{}", drive_data.inner.block_device);
+
+            if let (Some(elapsed), Some((old_read, old_write))) =
+                (elapsed, self.drive_sectors.get(sysfs_path))
+            {
+                let read_bytes = read_sectors.saturating_sub(*old_read) * SECTOR_SIZE;
+                let write_bytes = write_sectors.saturating_sub(*old_write) * SECTOR_SIZE;
+                rows.push((
+                    device.clone(),
+                    "read_bytes_per_second",
+                    read_bytes as f64 / elapsed,
+                ));
+                rows.push((
+                    device,
+                    "write_bytes_per_second",
+                    write_bytes as f64 / elapsed,
+                ));
+            }
+
+            drive_sectors.insert(sysfs_path.clone(), (*read_sectors, *write_sectors));
+        }
+        self.drive_sectors = drive_sectors;
+
+        let mut network_bytes = HashMap::with_capacity(snapshot.network_data.len());
+        for network_data in snapshot.network_data {
+            let (Ok(received_bytes), Ok(sent_bytes)) =
+                (&network_data.received_bytes, &network_data.sent_bytes)
+            else {
+                continue;
+            };
+
+            let sysfs_path = &network_data.inner.sysfs_path;
+            let device = format!(
+                "network-{}",
+                network_data.inner.interface_name.to_string_lossy()
+            );
+
+            if let (Some(elapsed), Some((old_received, old_sent))) =
+                (elapsed, self.network_bytes.get(sysfs_path))
+            {
+                rows.push((
+                    device.clone(),
+                    "received_bytes_per_second",
+                    received_bytes.saturating_sub(*old_received) as f64 / elapsed,
+                ));
+                rows.push((
+                    device,
+                    "sent_bytes_per_second",
+                    sent_bytes.saturating_sub(*old_sent) as f64 / elapsed,
+                ));
+            }
+
+            network_bytes.insert(sysfs_path.clone(), (*received_bytes, *sent_bytes));
+        }
+        self.network_bytes = network_bytes;
+
+        for battery_data in snapshot.battery_data {
+            let device = format!(
+                "battery-{}",
+                battery_data
+                    .inner
+                    .sysfs_path
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+            );
+
+            if let Ok(charge) = battery_data.charge {
+                rows.push((device.clone(), "charge", charge));
+            }
+            if let Ok(power_usage) = battery_data.power_usage {
+                rows.push((device, "power_watts", power_usage));
+            }
+        }
+
+        rows
+    }
+}
+
+/// Formats one line of the log, quoting the device if necessary
+fn format_row(timestamp: &str, device: &str, metric: &str, value: f64) -> String {
+    let device = if device.contains([',', '"', '\n']) {
+        format!("\"{}\"", device.replace('"', "\"\""))
+    } else {
+        device.to_string()
+    };
+
+    format!("{timestamp},{device},{metric},{value}\n")
+}
+
+/// Shifts `<path>.1` … `<path>.<rotated_files - 1>` one number up (dropping the oldest one) and moves `path` to
+/// `<path>.1`
+fn rotate(path: &Path, rotated_files: usize) -> Result<()> {
+    let rotated_path = |i: usize| {
+        let mut rotated_path = path.as_os_str().to_owned();
+        rotated_path.push(format!(".{i}"));
+        PathBuf::from(rotated_path)
+    };
+
+    for i in (1..rotated_files).rev() {
+        let from = rotated_path(i);
+        if from.exists() {
+            std::fs::rename(&from, rotated_path(i + 1))
+                .with_context(|| format!("unable to rotate {}", from.display()))?;
+        }
+    }
+
+    std::fs::rename(path, rotated_path(1))
+        .with_context(|| format!("unable to rotate {}", path.display()))
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::{format_row, rotate};
+    use crate::utils::test_dir;
+
+    #[test]
+    fn format_row_plain() {
+        assert_eq!(
+            "2024-01-01T12:00:00+01,cpu,usage,0.25\n",
+            format_row("2024-01-01T12:00:00+01", "cpu", "usage", 0.25)
+        );
+    }
+
+    #[test]
+    fn format_row_quotes_device() {
+        assert_eq!(
+            "2024-01-01T12:00:00+01,\"drive-a,\"\"b\"\"\",read_bytes_per_second,512\n",
+            format_row(
+                "2024-01-01T12:00:00+01",
+                "drive-a,\"b\"",
+                "read_bytes_per_second",
+                512.0
+            )
+        );
+    }
+
+    #[test]
+    fn rotate_keeps_limited_files() {
+        let dir = test_dir("metrics-log-rotate");
+        let path = dir.join("metrics.csv");
+
+        for i in 0..4 {
+            std::fs::write(&path, i.to_string()).unwrap();
+            rotate(&path, 2).unwrap();
+        }
+
+        assert!(!path.exists());
+        assert_eq!(
+            "3",
+            std::fs::read_to_string(dir.join("metrics.csv.1")).unwrap()
+        );
+        assert_eq!(
+            "2",
+            std::fs::read_to_string(dir.join("metrics.csv.2")).unwrap()
+        );
+        assert!(!dir.join("metrics.csv.3").exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,4 +1,10 @@
-use std::{collections::HashMap, path::Path, sync::LazyLock};
+use std::{
+    collections::HashMap,
+    ffi::{OsStr, OsString},
+    path::{Path, PathBuf},
+    process::Command,
+    sync::LazyLock,
+};
 
 use anyhow::{Context, Result};
 use gtk::glib::DateTime;
@@ -6,21 +12,31 @@ use ini::Ini;
 use log::{debug, trace};
 use process_data::unix_as_millis;
 
+pub mod alerts;
 pub mod app;
 pub mod battery;
 pub mod cpu;
+pub mod custom_gauge;
 pub mod drive;
 pub mod gpu;
+pub mod history;
+pub mod infiniband;
+pub mod json_output;
 pub mod memory;
+pub mod metrics_log;
 pub mod network;
 pub mod npu;
 pub mod os;
 pub mod pci;
+pub mod power_profile;
 pub mod process;
+pub mod refresh_scheduler;
+pub mod session_history;
 pub mod settings;
+pub mod table;
 pub mod units;
 
-const FLATPAK_SPAWN: &str = "/usr/bin/flatpak-spawn";
+pub const FLATPAK_SPAWN: &str = "/usr/bin/flatpak-spawn";
 
 static BOOT_TIMESTAMP: LazyLock<Option<i64>> = LazyLock::new(|| {
     let unix_timestamp = (unix_as_millis() / 1000) as i64;
@@ -50,6 +66,18 @@ pub static TICK_RATE: LazyLock<usize> =
 
 pub static NUM_CPUS: LazyLock<usize> = LazyLock::new(num_cpus::get);
 
+/// Where data is kept that should persist between runs but isn't worth backing up, e.g. the history of the usage
+pub static STATE_DIR: LazyLock<PathBuf> = LazyLock::new(|| {
+    std::env::var_os("XDG_STATE_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .unwrap_or_else(|| {
+            PathBuf::from(std::env::var_os("HOME").unwrap_or_else(|| "/".into()))
+                .join(".local/state")
+        })
+        .join("resources")
+});
+
 // Adapted from Mission Center: https://gitlab.com/mission-center-devs/mission-center/
 pub static IS_FLATPAK: LazyLock<bool> = LazyLock::new(|| {
     trace!("Determining whether /.flatpak-info exists…");
@@ -86,6 +114,85 @@ pub fn boot_time() -> Result<DateTime> {
         })
 }
 
+/// Tries to run a command unprivileged and then privileged if permissions were missing
+pub fn maybe_pkexec_command<S: AsRef<OsStr>, I: IntoIterator<Item = S>>(
+    command: S,
+    args: I,
+) -> Result<i32> {
+    let args = args
+        .into_iter()
+        .map(|s| s.as_ref().to_os_string())
+        .collect::<Vec<_>>();
+
+    let status_code = if *IS_FLATPAK {
+        debug!(
+            "Executing command: {} --host {} {}",
+            FLATPAK_SPAWN,
+            command.as_ref().to_string_lossy(),
+            args.join(&OsString::from(" ")).to_string_lossy()
+        );
+
+        Command::new(FLATPAK_SPAWN)
+            .arg("--host")
+            .arg(command.as_ref())
+            .args(args.clone())
+            .output()?
+            .status
+            .code()
+            .context("no status code?")?
+    } else {
+        debug!(
+            "Executing command: {} {}",
+            command.as_ref().to_string_lossy(),
+            args.join(&OsString::from(" ")).to_string_lossy()
+        );
+
+        Command::new(command.as_ref())
+            .args(args.clone())
+            .output()?
+            .status
+            .code()
+            .context("no status code?")?
+    };
+
+    if status_code == libc::EPERM || status_code == libc::EACCES {
+        let pkexec_status_code = if *IS_FLATPAK {
+            debug!(
+                "Received EPERM, executing command: {} --host pkexec --disable-internal-agent {} {}", 
+                FLATPAK_SPAWN,
+                command.as_ref().to_string_lossy(),
+                args.join(&OsString::from(" ")).to_string_lossy()
+            );
+            Command::new(FLATPAK_SPAWN)
+                .args(["--host", "pkexec", "--disable-internal-agent"])
+                .arg(command)
+                .args(args)
+                .output()?
+                .status
+                .code()
+                .context("no status code?")?
+        } else {
+            debug!(
+                "Received EPERM or EACCES, executing command: pkexec --disable-internal-agent {} {}", 
+                command.as_ref().to_string_lossy(),
+                args.join(&OsString::from(" ")).to_string_lossy()
+            );
+            Command::new("pkexec")
+                .arg("--disable-internal-agent")
+                .arg(command)
+                .args(args)
+                .output()?
+                .status
+                .code()
+                .context("no status code?")?
+        };
+
+        Ok(pkexec_status_code)
+    } else {
+        Ok(status_code)
+    }
+}
+
 pub fn read_uevent_contents<S: AsRef<str>>(contents: S) -> Result<HashMap<String, String>> {
     contents
         .as_ref()
@@ -179,6 +286,15 @@ impl FiniteOr for f32 {
     }
 }
 
+/// Creates an empty directory called `name` in the temporary directory for tests that need to work with real files
+#[cfg(test)]
+pub fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("resources-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
 #[cfg(test)]
 mod test {
     use core::f64;
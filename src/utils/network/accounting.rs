@@ -0,0 +1,451 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::LazyLock,
+    time::{Duration, Instant},
+};
+
+use anyhow::{bail, Context, Result};
+use gtk::glib::DateTime;
+use log::{debug, trace};
+
+use crate::utils::STATE_DIR;
+
+/// Where the data usage is kept, one file per network interface
+pub static ACCOUNTING_DIR: LazyLock<PathBuf> = LazyLock::new(|| STATE_DIR.join("data-usage"));
+
+/// Identifies the current boot, the counters of the interfaces only increase monotonically within the same boot
+static BOOT_ID: LazyLock<String> = LazyLock::new(|| {
+    std::fs::read_to_string("/proc/sys/kernel/random/boot_id")
+        .map(|boot_id| boot_id.trim().to_string())
+        .unwrap_or_default()
+});
+
+/// How often the data usage is written to disk at most
+pub const SAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// For how many days the usage is kept per day before it's merged into the usage of its month
+const DAILY_RETENTION_DAYS: i32 = 62;
+
+/// For how many months the usage is kept per month before it's dropped entirely
+const MONTHLY_RETENTION_MONTHS: i32 = 24;
+
+/// A calendar date without a time zone
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    pub fn today() -> Result<Self> {
+        let now = DateTime::now_local()?;
+
+        Ok(Self {
+            year: now.year(),
+            month: now.month() as u32,
+            day: now.day_of_month() as u32,
+        })
+    }
+
+    /// Returns the date the billing cycle that contains `self` started on, given that cycles start on `cycle_day` of
+    /// every month. `cycle_day` is clamped to 1..=28 so that every month has such a day.
+    pub fn billing_cycle_start(self, cycle_day: u32) -> Self {
+        let cycle_day = cycle_day.clamp(1, 28);
+
+        if self.day >= cycle_day {
+            Self {
+                day: cycle_day,
+                ..self
+            }
+        } else if self.month == 1 {
+            Self {
+                year: self.year - 1,
+                month: 12,
+                day: cycle_day,
+            }
+        } else {
+            Self {
+                month: self.month - 1,
+                day: cycle_day,
+                ..self
+            }
+        }
+    }
+
+    /// Number of months since year 0, used to compare months without caring about the day
+    fn months(self) -> i32 {
+        self.year * 12 + self.month as i32 - 1
+    }
+
+    /// Rough number of days since year 0, good enough to tell how long ago a date has been
+    fn days(self) -> i32 {
+        self.months() * 31 + self.day as i32
+    }
+}
+
+impl Display for Date {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl FromStr for Date {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut split = s.splitn(3, '-');
+
+        let year = split.next().context("no year")?.parse()?;
+        let month = split.next().context("no month")?.parse()?;
+        let day = split.next().unwrap_or("0").parse()?;
+
+        if !(1..=12).contains(&month) || day > 31 {
+            bail!("invalid date {s}");
+        }
+
+        Ok(Self { year, month, day })
+    }
+}
+
+/// Amount of bytes that have been received and sent by an interface
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DataUsage {
+    pub received_bytes: u64,
+    pub sent_bytes: u64,
+}
+
+impl DataUsage {
+    pub fn total_bytes(&self) -> u64 {
+        self.received_bytes.saturating_add(self.sent_bytes)
+    }
+
+    fn add(&mut self, other: DataUsage) {
+        self.received_bytes = self.received_bytes.saturating_add(other.received_bytes);
+        self.sent_bytes = self.sent_bytes.saturating_add(other.sent_bytes);
+    }
+}
+
+/// Keeps track of the data usage of a network interface across reboots. The usage of recent days is kept per day,
+/// older days are merged into the usage of their month (stored with a day of 0).
+///
+/// The file consists of a line `boot <boot ID> <received> <sent>` with the last known counters of the interface and
+/// one line `<YYYY-MM-DD> <received> <sent>` per day (or `<YYYY-MM> <received> <sent>` per month).
+#[derive(Debug)]
+pub struct DataAccounting {
+    path: PathBuf,
+    boot_id: String,
+    /// The counters of the interface when they were last recorded during the current boot
+    last_counters: Option<DataUsage>,
+    usage: BTreeMap<Date, DataUsage>,
+    last_saved: Option<Instant>,
+}
+
+impl DataAccounting {
+    /// Loads the data usage of the interface with the stable ID `stable_id` from [`ACCOUNTING_DIR`]
+    pub fn open<S: AsRef<str>>(stable_id: S) -> Self {
+        let file_name = stable_id.as_ref().replace(['/', ':', '\0'], "_");
+        Self::load(ACCOUNTING_DIR.join(file_name), &*BOOT_ID)
+    }
+
+    /// Loads the data usage at `path`, starting over if it doesn't exist or can't be read. The counters saved in it
+    /// are only used if they were saved during the boot `boot_id`.
+    pub fn load<P: AsRef<Path>, S: AsRef<str>>(path: P, boot_id: S) -> Self {
+        let path = path.as_ref().to_path_buf();
+        let boot_id = boot_id.as_ref().to_string();
+
+        let mut accounting = Self {
+            path,
+            boot_id,
+            last_counters: None,
+            usage: BTreeMap::new(),
+            last_saved: None,
+        };
+
+        match std::fs::read_to_string(&accounting.path) {
+            Ok(contents) => accounting.parse(&contents),
+            Err(error) => debug!(
+                "Unable to read data usage at {:?}, starting over: {error}",
+                accounting.path
+            ),
+        }
+
+        accounting
+    }
+
+    fn parse(&mut self, contents: &str) {
+        for line in contents.lines() {
+            let mut split = line.split_whitespace();
+
+            let (Some(first), Some(second)) = (split.next(), split.next()) else {
+                continue;
+            };
+
+            if first == "boot" {
+                if second != self.boot_id {
+                    continue;
+                }
+
+                if let (Some(Ok(received_bytes)), Some(Ok(sent_bytes))) =
+                    (split.next().map(str::parse), split.next().map(str::parse))
+                {
+                    self.last_counters = Some(DataUsage {
+                        received_bytes,
+                        sent_bytes,
+                    });
+                }
+            } else if let (Ok(date), Ok(received_bytes), Some(Ok(sent_bytes))) = (
+                first.parse::<Date>(),
+                second.parse(),
+                split.next().map(str::parse),
+            ) {
+                self.usage.entry(date).or_default().add(DataUsage {
+                    received_bytes,
+                    sent_bytes,
+                });
+            }
+        }
+    }
+
+    fn serialize(&self) -> String {
+        let mut contents = String::new();
+
+        if let Some(counters) = self.last_counters {
+            contents.push_str(&format!(
+                "boot {} {} {}\n",
+                self.boot_id, counters.received_bytes, counters.sent_bytes
+            ));
+        }
+
+        for (date, usage) in &self.usage {
+            let date = if date.day == 0 {
+                format!("{:04}-{:02}", date.year, date.month)
+            } else {
+                date.to_string()
+            };
+
+            contents.push_str(&format!(
+                "{date} {} {}\n",
+                usage.received_bytes, usage.sent_bytes
+            ));
+        }
+
+        contents
+    }
+
+    /// Adds the traffic since the last call to the usage of `today`, given the current `counters` of the interface.
+    /// Counters that went backwards (e.g. because the interface has been recreated) are counted from zero, as are the
+    /// counters of the first call after a reboot.
+    pub fn record(&mut self, counters: DataUsage, today: Date) {
+        let delta = match self.last_counters {
+            Some(last_counters) => DataUsage {
+                received_bytes: counters
+                    .received_bytes
+                    .checked_sub(last_counters.received_bytes)
+                    .unwrap_or(counters.received_bytes),
+                sent_bytes: counters
+                    .sent_bytes
+                    .checked_sub(last_counters.sent_bytes)
+                    .unwrap_or(counters.sent_bytes),
+            },
+            None => counters,
+        };
+
+        self.last_counters = Some(counters);
+
+        if delta.total_bytes() > 0 {
+            self.usage.entry(today).or_default().add(delta);
+        }
+
+        self.prune(today);
+    }
+
+    /// Merges days that are older than [`DAILY_RETENTION_DAYS`] into their months and drops months that are older
+    /// than [`MONTHLY_RETENTION_MONTHS`]
+    fn prune(&mut self, today: Date) {
+        let old_days = self
+            .usage
+            .keys()
+            .copied()
+            .filter(|date| date.day != 0 && today.days() - date.days() > DAILY_RETENTION_DAYS)
+            .collect::<Vec<_>>();
+
+        for date in old_days {
+            if let Some(usage) = self.usage.remove(&date) {
+                self.usage
+                    .entry(Date { day: 0, ..date })
+                    .or_default()
+                    .add(usage);
+            }
+        }
+
+        self.usage
+            .retain(|date, _| today.months() - date.months() <= MONTHLY_RETENTION_MONTHS);
+    }
+
+    /// Returns the data usage from `start` on, including `start` itself. Months that have already been merged only
+    /// count if they lie completely after `start`.
+    pub fn usage_since(&self, start: Date) -> DataUsage {
+        let mut total = DataUsage::default();
+
+        for (date, usage) in &self.usage {
+            let counts = if date.day == 0 {
+                date.months() > start.months()
+            } else {
+                *date >= start
+            };
+
+            if counts {
+                total.add(*usage);
+            }
+        }
+
+        total
+    }
+
+    /// Returns the data usage in the billing cycle that `today` is part of, see [`Date::billing_cycle_start`]
+    pub fn usage_in_billing_cycle(&self, today: Date, cycle_day: u32) -> DataUsage {
+        self.usage_since(today.billing_cycle_start(cycle_day))
+    }
+
+    /// Writes the data usage to disk
+    pub fn save(&mut self) -> Result<()> {
+        trace!("Saving data usage to {:?}…", self.path);
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("unable to create {parent:?}"))?;
+        }
+
+        // write to a temporary file first so that a crash while writing doesn't lose the usage recorded so far
+        let mut temporary_path = self.path.clone().into_os_string();
+        temporary_path.push(".tmp");
+        let temporary_path = PathBuf::from(temporary_path);
+        std::fs::write(&temporary_path, self.serialize())
+            .with_context(|| format!("unable to write {temporary_path:?}"))?;
+        std::fs::rename(&temporary_path, &self.path)
+            .with_context(|| format!("unable to rename {temporary_path:?}"))?;
+
+        self.last_saved = Some(Instant::now());
+
+        Ok(())
+    }
+
+    /// Writes the data usage to disk unless that has already happened within the last [`SAVE_INTERVAL`]
+    pub fn save_if_due(&mut self) -> Result<()> {
+        if self
+            .last_saved
+            .is_some_and(|last_saved| last_saved.elapsed() < SAVE_INTERVAL)
+        {
+            return Ok(());
+        }
+
+        self.save()
+    }
+}
+
+impl Drop for DataAccounting {
+    fn drop(&mut self) {
+        if self.last_counters.is_some() {
+            let _ = self.save();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::{DataAccounting, DataUsage, Date};
+    use crate::utils::test_dir;
+
+    fn date(year: i32, month: u32, day: u32) -> Date {
+        Date { year, month, day }
+    }
+
+    fn usage(received_bytes: u64, sent_bytes: u64) -> DataUsage {
+        DataUsage {
+            received_bytes,
+            sent_bytes,
+        }
+    }
+
+    #[test]
+    fn billing_cycle_start() {
+        assert_eq!(date(2024, 5, 1), date(2024, 5, 17).billing_cycle_start(1));
+        assert_eq!(date(2024, 5, 15), date(2024, 5, 15).billing_cycle_start(15));
+        assert_eq!(date(2024, 4, 20), date(2024, 5, 17).billing_cycle_start(20));
+        assert_eq!(date(2023, 12, 10), date(2024, 1, 3).billing_cycle_start(10));
+        assert_eq!(date(2024, 2, 28), date(2024, 3, 1).billing_cycle_start(31));
+    }
+
+    #[test]
+    fn parse_date() {
+        assert_eq!(date(2024, 5, 17), "2024-05-17".parse().unwrap());
+        assert_eq!(date(2024, 5, 0), "2024-05".parse().unwrap());
+        assert!("2024-13-01".parse::<Date>().is_err());
+        assert!("boot".parse::<Date>().is_err());
+    }
+
+    #[test]
+    fn counts_across_restarts_and_reboots() {
+        let dir = test_dir("data-usage-restarts");
+        let path = dir.join("network_00_11_22_33_44_55");
+
+        let mut accounting = DataAccounting::load(&path, "boot-a");
+        accounting.record(usage(1000, 100), date(2024, 5, 17));
+        accounting.record(usage(1500, 200), date(2024, 5, 18));
+        accounting.save().unwrap();
+        drop(accounting);
+
+        // same boot, only the traffic since the last save counts
+        let mut accounting = DataAccounting::load(&path, "boot-a");
+        accounting.record(usage(2000, 200), date(2024, 5, 18));
+        assert_eq!(usage(2000, 200), accounting.usage_since(date(2024, 5, 1)));
+        assert_eq!(usage(1000, 100), accounting.usage_since(date(2024, 5, 18)));
+        accounting.save().unwrap();
+        drop(accounting);
+
+        // after a reboot the counters start at zero again
+        let mut accounting = DataAccounting::load(&path, "boot-b");
+        accounting.record(usage(300, 30), date(2024, 5, 19));
+        // the interface has been recreated
+        accounting.record(usage(50, 5), date(2024, 5, 19));
+        assert_eq!(usage(350, 35), accounting.usage_since(date(2024, 5, 19)));
+        assert_eq!(usage(2350, 235), accounting.usage_since(date(2024, 5, 1)));
+        drop(accounting);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn old_days_are_merged_into_months() {
+        let dir = test_dir("data-usage-merge");
+        let path = dir.join("network_wwan0");
+
+        let mut accounting = DataAccounting::load(&path, "boot-a");
+        accounting.record(usage(100, 10), date(2024, 1, 5));
+        accounting.record(usage(300, 30), date(2024, 1, 20));
+        accounting.record(usage(600, 60), date(2024, 5, 17));
+
+        assert_eq!(usage(600, 60), accounting.usage_since(date(2023, 12, 1)));
+        // January has been merged and only partially lies within the range
+        assert_eq!(usage(300, 30), accounting.usage_since(date(2024, 1, 10)));
+        assert_eq!(
+            "boot boot-a 600 60\n2024-01 300 30\n2024-05-17 300 30\n",
+            accounting.serialize()
+        );
+
+        accounting.save().unwrap();
+        drop(accounting);
+
+        let accounting = DataAccounting::load(&path, "boot-a");
+        assert_eq!(usage(600, 60), accounting.usage_since(date(2023, 1, 1)));
+        drop(accounting);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,683 @@
+pub mod accounting;
+
+use std::{
+    collections::HashMap,
+    ffi::OsString,
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use gtk::gio::{Icon, ThemedIcon};
+use log::trace;
+
+use crate::i18n::i18n;
+
+use super::{pci::Device, read_uevent};
+
+pub use process_data::network::{wifi::WifiLink, InterfaceAddress};
+
+const PATH_SYSFS: &str = "/sys/class/net";
+
+// IPv4 octet counters aren't part of /proc/net/snmp, they're in the IpExt section of /proc/net/netstat
+const PATH_NETSTAT: &str = "/proc/net/netstat";
+
+const PATH_SNMP6: &str = "/proc/net/snmp6";
+
+// the hardware type (see `type` in sysfs) of loopback interfaces, as defined in linux/if_arp.h
+const ARPHRD_LOOPBACK: &str = "772";
+
+// this is a list because we don't look for exact matches but for if the device name starts with a certain string
+const INTERFACE_TYPE_MAP: &[(&str, InterfaceType)] = &[
+    ("bn", InterfaceType::Bluetooth),
+    ("br", InterfaceType::Bridge),
+    ("dae", InterfaceType::Vpn),
+    ("docker", InterfaceType::Docker),
+    ("eth", InterfaceType::Ethernet),
+    ("en", InterfaceType::Ethernet),
+    ("ib", InterfaceType::InfiniBand),
+    ("sl", InterfaceType::Slip),
+    ("tun", InterfaceType::Vpn),
+    ("veth", InterfaceType::VirtualEthernet),
+    ("virbr", InterfaceType::VmBridge),
+    ("vpn", InterfaceType::Vpn),
+    ("wg", InterfaceType::Wireguard),
+    ("wl", InterfaceType::Wlan),
+    ("ww", InterfaceType::Wwan),
+];
+
+#[derive(Debug)]
+pub struct NetworkData {
+    pub inner: NetworkInterface,
+    pub is_virtual: bool,
+    pub is_container: bool,
+    pub received_bytes: Result<usize>,
+    pub sent_bytes: Result<usize>,
+    pub display_name: String,
+    pub ip_traffic: Result<IpTraffic>,
+    /// The IPv4 and IPv6 addresses currently assigned to the interface, IPv4 addresses first
+    pub addresses: Vec<InterfaceAddress>,
+    /// `None` if this isn't a Wi-Fi interface or if its link couldn't be read
+    pub wifi_link: Option<WifiLink>,
+}
+
+impl NetworkData {
+    /// `addresses` and `wifi_links` belong to all interfaces as returned by [`Process::interface_addresses`] and
+    /// [`Process::wifi_links`], they're gathered once for all interfaces
+    pub fn new<P: AsRef<Path>>(
+        path: P,
+        addresses: &HashMap<String, Vec<InterfaceAddress>>,
+        wifi_links: &HashMap<String, WifiLink>,
+    ) -> Self {
+        let path = path.as_ref();
+
+        trace!("Gathering network data for {path:?}…");
+
+        let inner = NetworkInterface::from_sysfs(path);
+        let is_virtual = inner.is_virtual();
+        let is_container = inner.is_container();
+        let received_bytes = inner.received_bytes();
+        let sent_bytes = inner.sent_bytes();
+        let display_name = inner.display_name();
+        let ip_traffic = IpTraffic::current();
+        let interface_name = inner.interface_name.to_str();
+        let addresses = interface_name
+            .and_then(|interface_name| addresses.get(interface_name))
+            .cloned()
+            .unwrap_or_default();
+        let wifi_link = interface_name
+            .and_then(|interface_name| wifi_links.get(interface_name))
+            .cloned();
+
+        let network_data = Self {
+            inner,
+            is_virtual,
+            is_container,
+            received_bytes,
+            sent_bytes,
+            display_name,
+            ip_traffic,
+            addresses,
+            wifi_link,
+        };
+
+        trace!(
+            "Gathered network data for {}: {network_data:?}",
+            path.to_string_lossy()
+        );
+
+        network_data
+    }
+}
+
+/// System-wide amount of bytes transferred using IPv4 and IPv6.
+///
+/// The kernel doesn't provide these numbers per interface, so every
+/// interface will report the same values.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IpTraffic {
+    pub ipv4_received_bytes: u64,
+    pub ipv4_sent_bytes: u64,
+    pub ipv6_received_bytes: u64,
+    pub ipv6_sent_bytes: u64,
+}
+
+impl IpTraffic {
+    /// Reads the current IPv4 and IPv6 byte counters from procfs
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if `/proc/net/netstat` or `/proc/net/snmp6` are
+    /// unreadable or don't contain the needed counters
+    pub fn current() -> Result<Self> {
+        let netstat = std::fs::read_to_string(PATH_NETSTAT)
+            .with_context(|| format!("unable to read {PATH_NETSTAT}"))?;
+        let snmp6 = std::fs::read_to_string(PATH_SNMP6)
+            .with_context(|| format!("unable to read {PATH_SNMP6}"))?;
+
+        Self::from_procfs(netstat, snmp6)
+    }
+
+    fn from_procfs<S: AsRef<str>>(netstat: S, snmp6: S) -> Result<Self> {
+        let ip_ext = parse_netstat_section(netstat, "IpExt")?;
+        let ip6 = parse_snmp6(snmp6);
+
+        Ok(Self {
+            ipv4_received_bytes: *ip_ext.get("InOctets").context("no IPv4 InOctets")?,
+            ipv4_sent_bytes: *ip_ext.get("OutOctets").context("no IPv4 OutOctets")?,
+            ipv6_received_bytes: *ip6.get("Ip6InOctets").context("no Ip6InOctets")?,
+            ipv6_sent_bytes: *ip6.get("Ip6OutOctets").context("no Ip6OutOctets")?,
+        })
+    }
+
+    pub fn ipv4_total_bytes(&self) -> u64 {
+        self.ipv4_received_bytes
+            .saturating_add(self.ipv4_sent_bytes)
+    }
+
+    pub fn ipv6_total_bytes(&self) -> u64 {
+        self.ipv6_received_bytes
+            .saturating_add(self.ipv6_sent_bytes)
+    }
+}
+
+/// Parses a section of a file like `/proc/net/netstat` or `/proc/net/snmp`
+/// where a line of field names is followed by a line of values, both
+/// prefixed with the section name
+fn parse_netstat_section<S: AsRef<str>>(
+    contents: S,
+    section: &str,
+) -> Result<HashMap<String, u64>> {
+    let prefix = format!("{section}:");
+
+    let mut lines = contents
+        .as_ref()
+        .lines()
+        .filter(|line| line.starts_with(&prefix));
+
+    let names = lines
+        .next()
+        .with_context(|| format!("no {section} header line"))?;
+    let values = lines
+        .next()
+        .with_context(|| format!("no {section} value line"))?;
+
+    Ok(names
+        .split_whitespace()
+        .skip(1)
+        .zip(values.split_whitespace().skip(1))
+        .filter_map(|(name, value)| Some((name.to_string(), value.parse().ok()?)))
+        .collect())
+}
+
+/// Parses `/proc/net/snmp6` which consists of one counter per line
+fn parse_snmp6<S: AsRef<str>>(contents: S) -> HashMap<String, u64> {
+    contents
+        .as_ref()
+        .lines()
+        .filter_map(|line| {
+            let mut split = line.split_whitespace();
+            Some((split.next()?.to_string(), split.next()?.parse().ok()?))
+        })
+        .collect()
+}
+
+/// The duplex mode of a network link as reported in sysfs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Duplex {
+    Full,
+    Half,
+}
+
+impl Duplex {
+    fn from_sysfs<S: AsRef<str>>(duplex: S) -> Option<Self> {
+        match duplex.as_ref().trim() {
+            "full" => Some(Self::Full),
+            "half" => Some(Self::Half),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Duplex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Duplex::Full => i18n("Full Duplex"),
+                Duplex::Half => i18n("Half Duplex"),
+            }
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InterfaceType {
+    Bluetooth,
+    Bond,
+    Bridge,
+    Docker,
+    Ethernet,
+    InfiniBand,
+    Loopback,
+    Slip,
+    VirtualEthernet,
+    Vlan,
+    VmBridge,
+    Vpn,
+    Wireguard,
+    Wlan,
+    Wwan,
+    #[default]
+    Unknown,
+}
+
+impl InterfaceType {
+    pub fn from_interface_name<S: AsRef<str>>(interface_name: S) -> Self {
+        for (name, interface_type) in INTERFACE_TYPE_MAP.iter() {
+            if interface_name.as_ref().starts_with(name) {
+                return *interface_type;
+            }
+        }
+        Self::Unknown
+    }
+
+    /// Maps the `DEVTYPE` of an interface's uevent to an `InterfaceType`. Only virtual interface types that can't be
+    /// reliably told apart by their name are considered.
+    pub fn from_devtype<S: AsRef<str>>(devtype: S) -> Option<Self> {
+        match devtype.as_ref() {
+            "bond" => Some(Self::Bond),
+            "bridge" => Some(Self::Bridge),
+            "vlan" => Some(Self::Vlan),
+            _ => None,
+        }
+    }
+
+    /// Returns whether this type of interface is used to connect containers (e.g. of Docker) to the network
+    pub fn is_container(self) -> bool {
+        matches!(self, Self::Docker | Self::VirtualEthernet)
+    }
+
+    /// Detects loopback interfaces, bonds, bridges and VLANs using sysfs, falling back to the interface name for
+    /// everything else
+    fn from_sysfs<S: AsRef<str>>(sysfs_path: &Path, interface_name: S) -> Self {
+        let is_loopback = std::fs::read_to_string(sysfs_path.join("type"))
+            .is_ok_and(|hardware_type| hardware_type.trim() == ARPHRD_LOOPBACK);
+
+        if is_loopback {
+            return Self::Loopback;
+        }
+
+        let by_name = Self::from_interface_name(interface_name);
+
+        let by_sysfs = read_uevent(sysfs_path.join("uevent"))
+            .ok()
+            .and_then(|uevent| uevent.get("DEVTYPE").and_then(Self::from_devtype))
+            .or_else(|| {
+                if sysfs_path.join("bonding").is_dir() {
+                    Some(Self::Bond)
+                } else if sysfs_path.join("bridge").is_dir() {
+                    Some(Self::Bridge)
+                } else {
+                    None
+                }
+            });
+
+        match by_sysfs {
+            // Docker and VM bridges are more specific than just "bridge"
+            Some(Self::Bridge) if matches!(by_name, Self::Docker | Self::VmBridge) => by_name,
+            Some(interface_type) => interface_type,
+            None => by_name,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+/// Represents a network interface found in /sys/class/net
+pub struct NetworkInterface {
+    pub interface_name: OsString,
+    pub driver_name: Option<String>,
+    pub interface_type: InterfaceType,
+    /// The negotiated speed of the link in Mb/s
+    pub speed: Option<usize>,
+    pub duplex: Option<Duplex>,
+    pub mtu: Option<usize>,
+    pub device: Option<&'static Device>,
+    pub device_label: Option<String>,
+    pub hw_address: Option<String>,
+    /// Names of the interfaces enslaved to a bond or bridge, or the parent interface of a VLAN
+    pub member_interfaces: Vec<String>,
+    pub sysfs_path: PathBuf,
+    received_bytes_path: PathBuf,
+    sent_bytes_path: PathBuf,
+}
+
+impl Display for InterfaceType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                InterfaceType::Bluetooth => i18n("Bluetooth Tether"),
+                InterfaceType::Bond => i18n("Bonded Connection"),
+                InterfaceType::Bridge => i18n("Network Bridge"),
+                InterfaceType::Ethernet => i18n("Ethernet Connection"),
+                InterfaceType::Docker => i18n("Docker Bridge"),
+                InterfaceType::InfiniBand => i18n("InfiniBand Connection"),
+                InterfaceType::Loopback => i18n("Loopback Interface"),
+                InterfaceType::Slip => i18n("Serial Line IP Connection"),
+                InterfaceType::VirtualEthernet => i18n("Virtual Ethernet Device"),
+                InterfaceType::Vlan => i18n("VLAN"),
+                InterfaceType::VmBridge => i18n("VM Network Bridge"),
+                InterfaceType::Vpn => i18n("VPN Tunnel"),
+                InterfaceType::Wireguard => i18n("VPN Tunnel (WireGuard)"),
+                InterfaceType::Wlan => i18n("Wi-Fi Connection"),
+                InterfaceType::Wwan => i18n("WWAN Connection"),
+                InterfaceType::Unknown => i18n("Network Interface"),
+            }
+        )
+    }
+}
+
+impl PartialEq for NetworkInterface {
+    fn eq(&self, other: &Self) -> bool {
+        self.interface_name == other.interface_name
+            && self.device == other.device
+            && self.hw_address == other.hw_address
+    }
+}
+
+impl NetworkInterface {
+    pub fn get_sysfs_paths() -> Result<Vec<PathBuf>> {
+        let mut list = Vec::new();
+
+        trace!("Finding entries in {PATH_SYSFS}");
+
+        let entries = std::fs::read_dir(PATH_SYSFS)?;
+        for entry in entries {
+            let entry = entry?;
+            trace!(
+                "Found network interface {}",
+                entry.file_name().to_string_lossy()
+            );
+            list.push(entry.path());
+        }
+        Ok(list)
+    }
+
+    /// Returns a `NetworkInterface` based on information
+    /// found in its sysfs path
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an invalid sysfs Path has
+    /// been passed or if there has been problems parsing
+    /// information
+    pub fn from_sysfs(sysfs_path: &Path) -> NetworkInterface {
+        trace!("Creating NetworkInterface object of {sysfs_path:?}…");
+
+        let dev_uevent = read_uevent(sysfs_path.join("device/uevent")).unwrap_or_default();
+
+        let interface_name = sysfs_path
+            .file_name()
+            .expect("invalid sysfs path")
+            .to_owned();
+
+        let device = if let Some(pci_line) = dev_uevent.get("PCI_ID") {
+            let (vid_str, pid_str) = pci_line.split_once(':').unwrap_or(("0", "0"));
+            let vid = u16::from_str_radix(vid_str, 16).unwrap_or_default();
+            let pid = u16::from_str_radix(pid_str, 16).unwrap_or_default();
+            Device::from_vid_pid(vid, pid)
+        } else {
+            None
+        };
+
+        // reading the speed and duplex fails if the link is down, and the speed is -1 if the driver doesn't know it
+        let speed = std::fs::read_to_string(sysfs_path.join("speed"))
+            .ok()
+            .and_then(|speed| speed.trim().parse::<isize>().ok())
+            .and_then(|speed| usize::try_from(speed).ok())
+            .filter(|speed| *speed > 0);
+
+        let duplex = std::fs::read_to_string(sysfs_path.join("duplex"))
+            .ok()
+            .and_then(Duplex::from_sysfs);
+
+        let mtu = std::fs::read_to_string(sysfs_path.join("mtu"))
+            .ok()
+            .and_then(|mtu| mtu.trim().parse().ok());
+
+        let sysfs_path_clone = sysfs_path.to_owned();
+        let device_label = std::fs::read_to_string(sysfs_path_clone.join("device/label"))
+            .map(|x| x.replace('\n', ""))
+            .ok();
+
+        let sysfs_path_clone = sysfs_path.to_owned();
+        let hw_address = std::fs::read_to_string(sysfs_path_clone.join("address"))
+            .map(|x| x.replace('\n', ""))
+            .ok();
+
+        let interface_type =
+            InterfaceType::from_sysfs(sysfs_path, interface_name.to_string_lossy());
+
+        let member_interfaces = Self::member_interfaces(sysfs_path, interface_type);
+
+        let driver = dev_uevent.get("DRIVER");
+
+        let network_interface = NetworkInterface {
+            interface_name: interface_name.clone(),
+            driver_name: driver.cloned(),
+            interface_type,
+            speed,
+            duplex,
+            mtu,
+            device,
+            device_label,
+            hw_address,
+            member_interfaces,
+            sysfs_path: sysfs_path.to_path_buf(),
+            received_bytes_path: sysfs_path.join(PathBuf::from("statistics/rx_bytes")),
+            sent_bytes_path: sysfs_path.join(PathBuf::from("statistics/tx_bytes")),
+        };
+
+        trace!("Created NetworkInterface object of {sysfs_path:?}: {network_interface:?}");
+
+        network_interface
+    }
+
+    fn member_interfaces(sysfs_path: &Path, interface_type: InterfaceType) -> Vec<String> {
+        let dir_entry_names = |path: PathBuf| -> Vec<String> {
+            std::fs::read_dir(path)
+                .map(|entries| {
+                    entries
+                        .flatten()
+                        .map(|entry| entry.file_name().to_string_lossy().to_string())
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        let mut members: Vec<String> = match interface_type {
+            InterfaceType::Bond => std::fs::read_to_string(sysfs_path.join("bonding/slaves"))
+                .map(|slaves| slaves.split_whitespace().map(str::to_string).collect())
+                .unwrap_or_default(),
+            InterfaceType::Bridge | InterfaceType::Docker | InterfaceType::VmBridge => {
+                dir_entry_names(sysfs_path.join("brif"))
+            }
+            // the kernel links the underlying device of a VLAN as lower_<name>
+            InterfaceType::Vlan => dir_entry_names(sysfs_path.to_path_buf())
+                .into_iter()
+                .filter_map(|name| name.strip_prefix("lower_").map(str::to_string))
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        members.sort();
+        members
+    }
+
+    /// Returns an ID that keeps identifying the interface after reboots or reconnects. For physical interfaces this is
+    /// their MAC address since their names may change (e.g. when plugged into a different port), virtual interfaces are
+    /// identified by their name since they often share the MAC address with the interface they're based on.
+    pub fn stable_id(&self) -> String {
+        let hw_address = self
+            .hw_address
+            .as_deref()
+            .filter(|_| self.sysfs_path.join("device").exists())
+            .filter(|hw_address| !hw_address.is_empty() && *hw_address != "00:00:00:00:00:00");
+
+        match hw_address {
+            Some(hw_address) => format!("network:{hw_address}"),
+            None => format!("network:{}", self.interface_name.to_string_lossy()),
+        }
+    }
+
+    /// Returns a display name for this Network Interface.
+    /// It tries to be as human readable as possible.
+    pub fn display_name(&self) -> String {
+        self.device_label
+            .clone()
+            .or_else(|| self.device.map(|device| device.name().to_string()))
+            .unwrap_or_else(|| self.interface_name.to_string_lossy().to_string())
+    }
+
+    /// Returns the amount of bytes sent by this Network
+    /// Interface.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the `tx_bytes` file in sysfs
+    /// is unreadable or not parsable to a `usize`
+    pub fn received_bytes(&self) -> Result<usize> {
+        std::fs::read_to_string(&self.received_bytes_path)
+            .context("read failure")?
+            .replace('\n', "")
+            .parse()
+            .context("parsing failure")
+    }
+
+    /// Returns the amount of bytes sent by this Network
+    /// Interface
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the `tx_bytes` file in sysfs
+    /// is unreadable or not parsable to a `usize`
+    pub fn sent_bytes(&self) -> Result<usize> {
+        std::fs::read_to_string(&self.sent_bytes_path)
+            .context("read failure")?
+            .replace('\n', "")
+            .parse()
+            .context("parsing failure")
+    }
+
+    /// Returns the appropriate Icon for the type of drive
+    pub fn icon(&self) -> Icon {
+        match self.interface_type {
+            InterfaceType::Bluetooth => ThemedIcon::new("bluetooth-symbolic").into(),
+            InterfaceType::Bond | InterfaceType::Vlan => {
+                ThemedIcon::new("ethernet-symbolic").into()
+            }
+            InterfaceType::Bridge => ThemedIcon::new("bridge-symbolic").into(),
+            InterfaceType::Docker => ThemedIcon::new("docker-bridge-symbolic").into(),
+            InterfaceType::Ethernet => ThemedIcon::new("ethernet-symbolic").into(),
+            InterfaceType::InfiniBand => ThemedIcon::new("infiniband-symbolic").into(),
+            InterfaceType::Loopback => Self::default_icon(),
+            InterfaceType::Slip => ThemedIcon::new("slip-symbolic").into(),
+            InterfaceType::VirtualEthernet => ThemedIcon::new("virtual-ethernet").into(),
+            InterfaceType::VmBridge => ThemedIcon::new("vm-bridge-symbolic").into(),
+            InterfaceType::Vpn | InterfaceType::Wireguard => ThemedIcon::new("vpn-symbolic").into(),
+            InterfaceType::Wlan => ThemedIcon::new("wlan-symbolic").into(),
+            InterfaceType::Wwan => ThemedIcon::new("wwan-symbolic").into(),
+            InterfaceType::Unknown => Self::default_icon(),
+        }
+    }
+
+    pub fn is_virtual(&self) -> bool {
+        matches!(
+            self.interface_type,
+            InterfaceType::Bond
+                | InterfaceType::Bridge
+                | InterfaceType::Docker
+                | InterfaceType::Loopback
+                | InterfaceType::VirtualEthernet
+                | InterfaceType::Vlan
+                | InterfaceType::Vpn
+                | InterfaceType::VmBridge
+                | InterfaceType::Wireguard
+        )
+    }
+
+    /// Returns whether this is an interface of a container network, like a Docker bridge or a veth pair
+    pub fn is_container(&self) -> bool {
+        self.interface_type.is_container()
+    }
+
+    pub fn default_icon() -> Icon {
+        ThemedIcon::new("unknown-network-type-symbolic").into()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::{Duplex, InterfaceType, IpTraffic};
+
+    const NETSTAT: &str = concat!(
+        "TcpExt: SyncookiesSent SyncookiesRecv SyncookiesFailed\n",
+        "TcpExt: 0 0 0\n",
+        "IpExt: InNoRoutes InTruncatedPkts InMcastPkts OutMcastPkts InBcastPkts OutBcastPkts InOctets OutOctets\n",
+        "IpExt: 0 0 2000 30 500 0 123456789 98765432\n",
+        "MPTcpExt: MPCapableSYNRX MPCapableSYNTX\n",
+        "MPTcpExt: 0 0\n"
+    );
+
+    const SNMP6: &str = concat!(
+        "Ip6InReceives                   \t1234\n",
+        "Ip6InOctets                     \t55555\n",
+        "Ip6OutOctets                    \t44444\n",
+        "Icmp6InMsgs                     \t12\n"
+    );
+
+    #[test]
+    fn ip_traffic_valid() {
+        let expected = IpTraffic {
+            ipv4_received_bytes: 123_456_789,
+            ipv4_sent_bytes: 98_765_432,
+            ipv6_received_bytes: 55555,
+            ipv6_sent_bytes: 44444,
+        };
+
+        let parsed = IpTraffic::from_procfs(NETSTAT, SNMP6).unwrap();
+
+        assert_eq!(expected, parsed);
+    }
+
+    #[test]
+    fn ip_traffic_missing_ipv6() {
+        let snmp6 = "Ip6InReceives 1234\n";
+
+        assert!(IpTraffic::from_procfs(NETSTAT, snmp6).is_err());
+    }
+
+    #[test]
+    fn interface_type_from_devtype() {
+        assert_eq!(
+            Some(InterfaceType::Bond),
+            InterfaceType::from_devtype("bond")
+        );
+        assert_eq!(
+            Some(InterfaceType::Bridge),
+            InterfaceType::from_devtype("bridge")
+        );
+        assert_eq!(
+            Some(InterfaceType::Vlan),
+            InterfaceType::from_devtype("vlan")
+        );
+        assert_eq!(None, InterfaceType::from_devtype("wlan"));
+    }
+
+    #[test]
+    fn interface_type_is_container() {
+        assert!(InterfaceType::from_interface_name("veth3f2a1b7").is_container());
+        assert!(InterfaceType::from_interface_name("docker0").is_container());
+        assert!(!InterfaceType::from_interface_name("virbr0").is_container());
+        assert!(!InterfaceType::Loopback.is_container());
+    }
+
+    #[test]
+    fn duplex_from_sysfs() {
+        assert_eq!(Some(Duplex::Full), Duplex::from_sysfs("full\n"));
+        assert_eq!(Some(Duplex::Half), Duplex::from_sysfs("half\n"));
+        assert_eq!(None, Duplex::from_sysfs("unknown\n"));
+    }
+
+    #[test]
+    fn ip_traffic_missing_ip_ext() {
+        let netstat = "TcpExt: SyncookiesSent\nTcpExt: 0\n";
+
+        assert!(IpTraffic::from_procfs(netstat, SNMP6).is_err());
+    }
+}
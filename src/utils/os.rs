@@ -1,17 +1,65 @@
 use lazy_regex::{lazy_regex, Lazy, Regex};
 use log::trace;
 
+use crate::i18n::i18n;
+
 use super::IS_FLATPAK;
 
 const PATH_OS_RELEASE: &str = "/etc/os-release";
 const PATH_OS_RELEASE_FLATPAK: &str = "/run/host/etc/os-release";
 const PATH_KERNEL_VERSION: &str = "/proc/sys/kernel/osrelease";
+const PATH_KERNEL_CMDLINE: &str = "/proc/cmdline";
+const PATH_KERNEL_MODULES: &str = "/proc/modules";
+const PATH_CPUINFO: &str = "/proc/cpuinfo";
+const PATH_SYS_HYPERVISOR_TYPE: &str = "/sys/hypervisor/type";
+const PATH_DMI_SYS_VENDOR: &str = "/sys/class/dmi/id/sys_vendor";
+const PATH_DMI_PRODUCT_NAME: &str = "/sys/class/dmi/id/product_name";
+
+// vendor signatures as returned by CPUID leaf 0x40000000, see systemd's detect-virt
+const CPUID_HYPERVISOR_MAP: &[(&str, &str)] = &[
+    ("KVMKVMKVM", "KVM"),
+    ("Linux KVM Hv", "KVM"),
+    ("TCGTCGTCGTCG", "QEMU"),
+    ("VMwareVMware", "VMware"),
+    ("Microsoft Hv", "Hyper-V"),
+    ("XenVMMXenVMM", "Xen"),
+    ("VBoxVBoxVBox", "VirtualBox"),
+    ("bhyve bhyve ", "bhyve"),
+    (" lrpepyh  vr", "Parallels"),
+    ("ACRNACRNACRN", "ACRN"),
+    ("QNXQVMBSQG", "QNX Hypervisor"),
+];
+
+// this is a list because we look for DMI vendor or product names that start with a certain string
+const DMI_HYPERVISOR_MAP: &[(&str, &str)] = &[
+    ("KVM", "KVM"),
+    ("OpenStack", "KVM"),
+    ("Amazon EC2", "Amazon EC2"),
+    ("QEMU", "QEMU"),
+    ("VMware", "VMware"),
+    ("VMW", "VMware"),
+    ("innotek GmbH", "VirtualBox"),
+    ("VirtualBox", "VirtualBox"),
+    ("Xen", "Xen"),
+    ("Bochs", "Bochs"),
+    ("Parallels", "Parallels"),
+    ("BHYVE", "bhyve"),
+    ("Apple Virtualization", "Apple Virtualization"),
+    ("Google Compute Engine", "Google Compute Engine"),
+];
 
 static RE_PRETTY_NAME: Lazy<Regex> = lazy_regex!("PRETTY_NAME=\"(.*)\"");
 
+/// Finding the hypervisor involves CPUID and reading several files, and it can't change while we're running
+static HYPERVISOR: Lazy<Option<String>> = Lazy::new(OsInfo::detect_hypervisor);
+
 pub struct OsInfo {
     pub name: Option<String>,
     pub kernel_version: Option<String>,
+    pub kernel_cmdline: Option<String>,
+    pub kernel_modules: Option<usize>,
+    /// Name of the hypervisor we're running under, `None` on bare metal
+    pub hypervisor: Option<String>,
 }
 
 impl OsInfo {
@@ -33,9 +81,173 @@ impl OsInfo {
             .ok()
             .map(|s| s.trim().to_string());
 
+        let kernel_cmdline = std::fs::read_to_string(PATH_KERNEL_CMDLINE)
+            .ok()
+            .map(|s| s.trim().to_string());
+
+        // every loaded module gets its own line
+        let kernel_modules = std::fs::read_to_string(PATH_KERNEL_MODULES)
+            .ok()
+            .map(|s| s.lines().filter(|line| !line.trim().is_empty()).count());
+
+        let hypervisor = HYPERVISOR.clone();
+
         OsInfo {
             name,
             kernel_version,
+            kernel_cmdline,
+            kernel_modules,
+            hypervisor,
+        }
+    }
+
+    /// Tries to find out whether we're running in a virtual machine and if so, under which hypervisor.
+    ///
+    /// Returns `None` on bare metal.
+    fn detect_hypervisor() -> Option<String> {
+        let hypervisor_flag = std::fs::read_to_string(PATH_CPUINFO)
+            .ok()
+            .and_then(|cpuinfo| {
+                cpuinfo
+                    .lines()
+                    .find(|line| line.starts_with("flags"))
+                    .map(|flags| flags.split_whitespace().any(|flag| flag == "hypervisor"))
+            });
+
+        let dmi_hypervisor = || {
+            let sys_vendor = std::fs::read_to_string(PATH_DMI_SYS_VENDOR).unwrap_or_default();
+            let product_name = std::fs::read_to_string(PATH_DMI_PRODUCT_NAME).unwrap_or_default();
+            Self::hypervisor_from_dmi(sys_vendor.trim(), product_name.trim())
+        };
+
+        let hypervisor = Self::cpuid_hypervisor_vendor()
+            .and_then(|vendor| Self::hypervisor_from_cpuid_vendor(&vendor))
+            .map(str::to_string)
+            .or_else(|| dmi_hypervisor().map(str::to_string))
+            .or_else(|| {
+                // Xen PV guests (and some others) tell us in sysfs
+                std::fs::read_to_string(PATH_SYS_HYPERVISOR_TYPE)
+                    .ok()
+                    .map(|hypervisor_type| hypervisor_type.trim().to_string())
+                    .filter(|hypervisor_type| !hypervisor_type.is_empty())
+                    .map(|hypervisor_type| {
+                        if hypervisor_type == "xen" {
+                            "Xen".to_string()
+                        } else {
+                            hypervisor_type
+                        }
+                    })
+            });
+
+        trace!("Detected hypervisor: {hypervisor:?} (hypervisor CPU flag: {hypervisor_flag:?})");
+
+        // the CPU flag tells us that there is a hypervisor even if we don't know which one
+        hypervisor.or_else(|| hypervisor_flag.unwrap_or(false).then(|| i18n("Unknown")))
+    }
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    #[allow(unused_unsafe)] // `__cpuid()` is only safe to call on newer Rust versions
+    fn cpuid_hypervisor_vendor() -> Option<String> {
+        #[cfg(target_arch = "x86")]
+        use std::arch::x86::__cpuid;
+        #[cfg(target_arch = "x86_64")]
+        use std::arch::x86_64::__cpuid;
+
+        // SAFETY: CPUID is available on every x86 CPU that's able to run Linux
+        let leaf_1 = unsafe { __cpuid(1) };
+
+        // bit 31 of ECX is reserved for hypervisors to announce themselves
+        if leaf_1.ecx & (1 << 31) == 0 {
+            return None;
         }
+
+        // SAFETY: see above
+        let leaf = unsafe { __cpuid(0x4000_0000) };
+        let bytes: Vec<u8> = [leaf.ebx, leaf.ecx, leaf.edx]
+            .iter()
+            .flat_map(|register| register.to_le_bytes())
+            .collect();
+
+        Some(
+            String::from_utf8_lossy(&bytes)
+                .trim_end_matches('\0')
+                .to_string(),
+        )
+    }
+
+    #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+    fn cpuid_hypervisor_vendor() -> Option<String> {
+        None
+    }
+
+    fn hypervisor_from_cpuid_vendor(vendor: &str) -> Option<&'static str> {
+        CPUID_HYPERVISOR_MAP
+            .iter()
+            .find(|(signature, _)| vendor.trim_end_matches('\0') == *signature)
+            .map(|(_, hypervisor)| *hypervisor)
+    }
+
+    fn hypervisor_from_dmi(sys_vendor: &str, product_name: &str) -> Option<&'static str> {
+        // Hyper-V's vendor is just Microsoft, so we need to look at the product name too
+        if sys_vendor == "Microsoft Corporation" && product_name == "Virtual Machine" {
+            return Some("Hyper-V");
+        }
+
+        DMI_HYPERVISOR_MAP
+            .iter()
+            .find(|(name, _)| sys_vendor.starts_with(name) || product_name.starts_with(name))
+            .map(|(_, hypervisor)| *hypervisor)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::OsInfo;
+
+    #[test]
+    fn hypervisor_from_cpuid_vendor_known() {
+        assert_eq!(
+            Some("KVM"),
+            OsInfo::hypervisor_from_cpuid_vendor("KVMKVMKVM\0\0\0")
+        );
+        assert_eq!(
+            Some("Hyper-V"),
+            OsInfo::hypervisor_from_cpuid_vendor("Microsoft Hv")
+        );
+    }
+
+    #[test]
+    fn hypervisor_from_cpuid_vendor_unknown() {
+        assert_eq!(None, OsInfo::hypervisor_from_cpuid_vendor("GenuineIntel"));
+    }
+
+    #[test]
+    fn hypervisor_from_dmi_known() {
+        assert_eq!(
+            Some("VirtualBox"),
+            OsInfo::hypervisor_from_dmi("innotek GmbH", "VirtualBox")
+        );
+        assert_eq!(
+            Some("QEMU"),
+            OsInfo::hypervisor_from_dmi("QEMU", "Standard PC (Q35 + ICH9, 2009)")
+        );
+        assert_eq!(
+            Some("Hyper-V"),
+            OsInfo::hypervisor_from_dmi("Microsoft Corporation", "Virtual Machine")
+        );
+    }
+
+    #[test]
+    fn hypervisor_from_dmi_bare_metal() {
+        assert_eq!(
+            None,
+            OsInfo::hypervisor_from_dmi("Framework", "Laptop 13 (AMD Ryzen 7040Series)")
+        );
+        assert_eq!(
+            None,
+            OsInfo::hypervisor_from_dmi("Microsoft Corporation", "Surface Laptop 5")
+        );
     }
 }
@@ -0,0 +1,247 @@
+//! The power profile of the system as managed by power-profiles-daemon, which adjusts things like the platform profile
+//! of the firmware and the energy performance preference of the CPU
+
+use std::{collections::HashMap, fmt::Display, str::FromStr, sync::LazyLock};
+
+use anyhow::{bail, Context, Result};
+use gtk::{
+    gio,
+    glib::{prelude::ToVariant, Variant, VariantTy},
+};
+use log::{trace, warn};
+
+use crate::i18n::i18n;
+
+/// Bus names (which are also the interface names) and object paths of power-profiles-daemon, older versions only
+/// provide the latter
+const SERVICES: [(&str, &str); 2] = [
+    (
+        "org.freedesktop.UPower.PowerProfiles",
+        "/org/freedesktop/UPower/PowerProfiles",
+    ),
+    ("net.hadess.PowerProfiles", "/net/hadess/PowerProfiles"),
+];
+
+const PROPERTIES_INTERFACE: &str = "org.freedesktop.DBus.Properties";
+
+const DBUS_TIMEOUT_MS: i32 = 1000;
+
+static SYSTEM_BUS: LazyLock<Option<gio::DBusConnection>> = LazyLock::new(|| {
+    gio::bus_get_sync(gio::BusType::System, None::<&gio::Cancellable>)
+        .inspect_err(|err| {
+            warn!("Unable to connect to the system bus, power profiles won't be available: {err}")
+        })
+        .ok()
+});
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PowerProfile {
+    PowerSaver,
+    Balanced,
+    Performance,
+}
+
+impl PowerProfile {
+    /// The name power-profiles-daemon uses for this profile
+    pub fn id(self) -> &'static str {
+        match self {
+            PowerProfile::PowerSaver => "power-saver",
+            PowerProfile::Balanced => "balanced",
+            PowerProfile::Performance => "performance",
+        }
+    }
+
+    /// Returns the currently active power profile
+    pub fn active() -> Result<Self> {
+        let properties = get_all_properties()?;
+
+        properties
+            .get("ActiveProfile")
+            .and_then(Variant::str)
+            .context("no ActiveProfile property")?
+            .parse()
+    }
+
+    /// Returns the power profiles the system supports, at least "balanced" and "power-saver" are always available
+    pub fn available() -> Result<Vec<Self>> {
+        let properties = get_all_properties()?;
+
+        let profiles = properties
+            .get("Profiles")
+            .and_then(Variant::get::<Vec<HashMap<String, Variant>>>)
+            .context("no Profiles property")?;
+
+        Ok(Self::parse_profiles(&profiles))
+    }
+
+    /// Expects the dictionaries of the `Profiles` property, unknown profiles are skipped
+    fn parse_profiles(profiles: &[HashMap<String, Variant>]) -> Vec<Self> {
+        let mut profiles = profiles
+            .iter()
+            .filter_map(|profile| profile.get("Profile")?.str()?.parse().ok())
+            .collect::<Vec<Self>>();
+
+        profiles.sort_by_key(|profile| *profile as u8);
+        profiles.dedup();
+
+        profiles
+    }
+
+    /// Makes this the active power profile, which may require authorization depending on the system's polkit rules
+    pub fn activate(self) -> Result<()> {
+        trace!("Activating power profile {}…", self.id());
+
+        let connection = SYSTEM_BUS
+            .as_ref()
+            .context("no connection to the system bus")?;
+
+        let (bus_name, object_path) = find_service(connection)?;
+
+        connection
+            .call_sync(
+                Some(bus_name),
+                object_path,
+                PROPERTIES_INTERFACE,
+                "Set",
+                Some(&(bus_name, "ActiveProfile", self.id().to_variant()).to_variant()),
+                None,
+                gio::DBusCallFlags::ALLOW_INTERACTIVE_AUTHORIZATION,
+                DBUS_TIMEOUT_MS,
+                None::<&gio::Cancellable>,
+            )
+            .with_context(|| format!("unable to set ActiveProfile on {object_path}"))?;
+
+        Ok(())
+    }
+}
+
+impl FromStr for PowerProfile {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "power-saver" => Ok(PowerProfile::PowerSaver),
+            "balanced" => Ok(PowerProfile::Balanced),
+            "performance" => Ok(PowerProfile::Performance),
+            _ => bail!("unknown power profile {s}"),
+        }
+    }
+}
+
+impl Display for PowerProfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                PowerProfile::PowerSaver => i18n("Power Saver"),
+                PowerProfile::Balanced => i18n("Balanced"),
+                PowerProfile::Performance => i18n("Performance"),
+            }
+        )
+    }
+}
+
+/// Returns the bus name and object path of the power-profiles-daemon that's running
+fn find_service(connection: &gio::DBusConnection) -> Result<(&'static str, &'static str)> {
+    SERVICES
+        .into_iter()
+        .find(|(bus_name, _)| name_has_owner(connection, bus_name))
+        .context("power-profiles-daemon is not running")
+}
+
+fn name_has_owner(connection: &gio::DBusConnection, bus_name: &str) -> bool {
+    connection
+        .call_sync(
+            Some("org.freedesktop.DBus"),
+            "/org/freedesktop/DBus",
+            "org.freedesktop.DBus",
+            "NameHasOwner",
+            Some(&(bus_name,).to_variant()),
+            Some(VariantTy::new("(b)").unwrap()),
+            gio::DBusCallFlags::NONE,
+            DBUS_TIMEOUT_MS,
+            None::<&gio::Cancellable>,
+        )
+        .ok()
+        .and_then(|reply| reply.get::<(bool,)>())
+        .is_some_and(|(has_owner,)| has_owner)
+}
+
+fn get_all_properties() -> Result<HashMap<String, Variant>> {
+    let connection = SYSTEM_BUS
+        .as_ref()
+        .context("no connection to the system bus")?;
+
+    let (bus_name, object_path) = find_service(connection)?;
+
+    let reply = connection
+        .call_sync(
+            Some(bus_name),
+            object_path,
+            PROPERTIES_INTERFACE,
+            "GetAll",
+            Some(&(bus_name,).to_variant()),
+            Some(VariantTy::new("(a{sv})")?),
+            gio::DBusCallFlags::NONE,
+            DBUS_TIMEOUT_MS,
+            None::<&gio::Cancellable>,
+        )
+        .with_context(|| format!("unable to get the properties of {object_path}"))?;
+
+    reply
+        .get::<(HashMap<String, Variant>,)>()
+        .map(|(properties,)| properties)
+        .context("unexpected reply to GetAll")
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use gtk::glib::{prelude::ToVariant, Variant};
+    use pretty_assertions::assert_eq;
+
+    use super::PowerProfile;
+
+    fn profile(name: &str, driver: &str) -> HashMap<String, Variant> {
+        [
+            ("Profile".to_string(), name.to_variant()),
+            ("Driver".to_string(), driver.to_variant()),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    #[test]
+    fn parse_profiles() {
+        let profiles = [
+            profile("performance", "platform_profile"),
+            profile("balanced", "platform_profile"),
+            profile("power-saver", "platform_profile"),
+            profile("something-new", "platform_profile"),
+        ];
+
+        assert_eq!(
+            vec![
+                PowerProfile::PowerSaver,
+                PowerProfile::Balanced,
+                PowerProfile::Performance
+            ],
+            PowerProfile::parse_profiles(&profiles)
+        );
+    }
+
+    #[test]
+    fn profile_ids() {
+        for profile in [
+            PowerProfile::PowerSaver,
+            PowerProfile::Balanced,
+            PowerProfile::Performance,
+        ] {
+            assert_eq!(profile, profile.id().parse().unwrap());
+        }
+
+        assert!("low-power".parse::<PowerProfile>().is_err());
+    }
+}
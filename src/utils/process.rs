@@ -1,11 +1,18 @@
 use anyhow::{bail, Context, Result};
 use config::LIBEXECDIR;
 use log::{debug, error, info, trace};
-use process_data::{GpuIdentifier, GpuUsageStats, Niceness, ProcessData};
+use process_data::{
+    network::{wifi::WifiLink, InterfaceAddress},
+    CpuThrottling, DrmFdinfo, EnvironmentVariable, GpuIdentifier, GpuUsageStats, Niceness,
+    OpenFile, ProcessData, ProcessLimits, SchedStat, SocketData, ThreadData, WaitChannel,
+    DRM_FDINFO_REQUEST, ENVIRONMENT_REQUEST, INTERFACE_ADDRESSES_REQUEST, LIMITS_REQUEST,
+    OPEN_FILES_REQUEST, SCHEDSTAT_REQUEST, SOCKETS_REQUEST, THREADS_REQUEST, WAIT_CHANNEL_REQUEST,
+    WIFI_LINKS_REQUEST,
+};
 use std::{
-    collections::BTreeMap,
-    ffi::{OsStr, OsString},
+    collections::{BTreeMap, HashMap},
     io::{Read, Write},
+    path::{Path, PathBuf},
     process::{ChildStdin, ChildStdout, Command, Stdio},
     sync::{LazyLock, Mutex},
     time::Instant,
@@ -14,15 +21,19 @@ use strum_macros::Display;
 
 use gtk::{
     gio::{Icon, ThemedIcon},
-    glib::GString,
+    glib::{self, GString},
 };
 
 use crate::config;
 
 use super::{
-    boot_time, FiniteOr, FLATPAK_APP_PATH, FLATPAK_SPAWN, IS_FLATPAK, NUM_CPUS, TICK_RATE,
+    boot_time, maybe_pkexec_command, settings::ProcessNameSource, FiniteOr, FLATPAK_APP_PATH,
+    FLATPAK_SPAWN, IS_FLATPAK, NUM_CPUS, TICK_RATE,
 };
 
+static CURRENT_USER: LazyLock<String> =
+    LazyLock::new(|| glib::user_name().to_string_lossy().to_string());
+
 static COMPANION_PROCESS: LazyLock<Mutex<(ChildStdin, ChildStdout)>> = LazyLock::new(|| {
     let proxy_path = if *IS_FLATPAK {
         format!(
@@ -70,7 +81,10 @@ pub struct Process {
     pub read_bytes_last: Option<u64>,
     pub write_bytes_last: Option<u64>,
     pub gpu_usage_stats_last: BTreeMap<GpuIdentifier, GpuUsageStats>,
+    pub cpu_throttling_last: Option<CpuThrottling>,
     pub display_name: String,
+    /// The display name of the app this process belongs to, if it belongs to one
+    pub app_name: Option<String>,
 }
 
 // TODO: Better name?
@@ -82,6 +96,86 @@ pub enum ProcessAction {
     CONT,
 }
 
+/// Resource limits of a cgroup, `None` meaning unlimited
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CgroupLimits {
+    /// Maximum amount of memory in bytes, see `memory.max`
+    pub memory_max: Option<u64>,
+    /// Maximum processor usage in percent of a single logical CPU, see `cpu.max`
+    pub cpu_max: Option<u32>,
+}
+
+impl CgroupLimits {
+    /// The period in µs the processor quota is given for
+    const CPU_PERIOD: u64 = 100_000;
+
+    /// Reads the current limits of the cgroup the process `pid` belongs to. Returns `None` if the process isn't part of
+    /// a cgroup v2 hierarchy or the memory or CPU controllers aren't enabled for its cgroup.
+    pub fn of_pid(pid: libc::pid_t) -> Option<Self> {
+        let cgroup = std::fs::read_to_string(format!("/proc/{pid}/cgroup")).ok()?;
+        let cgroup_path = Self::cgroup_path(cgroup)?;
+
+        let memory_max = std::fs::read_to_string(cgroup_path.join("memory.max")).ok()?;
+        let cpu_max = std::fs::read_to_string(cgroup_path.join("cpu.max")).ok()?;
+
+        Some(Self::parse(memory_max, cpu_max))
+    }
+
+    /// Returns the path of the cgroup v2 given in the contents of a `/proc/<pid>/cgroup` file, the root cgroup can't
+    /// be limited and is thus `None`
+    fn cgroup_path<S: AsRef<str>>(cgroup: S) -> Option<PathBuf> {
+        let path = cgroup
+            .as_ref()
+            .lines()
+            .find_map(|line| line.strip_prefix("0::"))?
+            .trim()
+            .trim_start_matches('/');
+
+        if path.is_empty() {
+            None
+        } else {
+            Some(Path::new("/sys/fs/cgroup").join(path))
+        }
+    }
+
+    fn parse<S: AsRef<str>, T: AsRef<str>>(memory_max: S, cpu_max: T) -> Self {
+        let memory_max = memory_max.as_ref().trim().parse().ok();
+
+        // cpu.max looks like "$MAX $PERIOD" where $MAX is "max" if there is no quota
+        let cpu_max = cpu_max
+            .as_ref()
+            .split_once(' ')
+            .and_then(|(quota, period)| {
+                Some((
+                    quota.parse::<u64>().ok()?,
+                    period.trim().parse::<u64>().ok()?,
+                ))
+            })
+            .filter(|(_, period)| *period > 0)
+            .map(|(quota, period)| ((quota as f64 / period as f64) * 100.0).round() as u32);
+
+        Self {
+            memory_max,
+            cpu_max,
+        }
+    }
+
+    /// The value to be written to `memory.max`
+    pub fn memory_max_string(&self) -> String {
+        self.memory_max
+            .map_or_else(|| "max".to_string(), |memory_max| memory_max.to_string())
+    }
+
+    /// The value to be written to `cpu.max`
+    pub fn cpu_max_string(&self) -> String {
+        let period = Self::CPU_PERIOD;
+        match self.cpu_max {
+            Some(cpu_max) => format!("{} {period}", u64::from(cpu_max) * period / 100),
+            None => format!("max {period}"),
+        }
+    }
+}
+
 impl Process {
     /// Returns a `Vec` containing all currently running processes.
     ///
@@ -93,28 +187,7 @@ impl Process {
         trace!("all_data() called");
 
         let start = Instant::now();
-        let output = {
-            trace!("Acquiring companion process lock");
-            let mut process = COMPANION_PROCESS.lock().unwrap();
-            trace!("Writing b\"\\n\" into companion process stdin");
-            let _ = process.0.write_all(b"\n");
-            trace!("Flushing");
-            let _ = process.0.flush();
-
-            let mut len_bytes = [0_u8; (usize::BITS / 8) as usize];
-
-            trace!("Reading companion process output length as little-endian");
-            process.1.read_exact(&mut len_bytes)?;
-
-            let len = usize::from_le_bytes(len_bytes);
-            trace!("Companion process output is {len} bytes long");
-
-            let mut output_bytes = vec![0; len];
-            trace!("Reading companion process output");
-            process.1.read_exact(&mut output_bytes)?;
-
-            output_bytes
-        };
+        let output = Self::companion_request(b"\n")?;
 
         let elapsed = start.elapsed();
         trace!("Companion process was done in {elapsed:.2?}");
@@ -129,6 +202,202 @@ impl Process {
         parsed
     }
 
+    /// Returns the threads of the process with the given `pid`, this is empty if the process doesn't exist (anymore)
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if there are problems communicating with
+    /// the companion process
+    pub fn threads_of(pid: libc::pid_t) -> Result<Vec<ThreadData>> {
+        trace!("threads_of({pid}) called");
+
+        let mut request = vec![THREADS_REQUEST];
+        request.extend_from_slice(&pid.to_le_bytes());
+
+        let output = Self::companion_request(&request)?;
+
+        rmp_serde::from_slice(&output).context("unable to decode companion process output")
+    }
+
+    /// Returns the raw DRM fdinfo fields of the process with the given `pid`, this is empty if the process doesn't use
+    /// a GPU or doesn't exist (anymore)
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if there are problems communicating with
+    /// the companion process
+    pub fn drm_fdinfos_of(pid: libc::pid_t) -> Result<Vec<DrmFdinfo>> {
+        trace!("drm_fdinfos_of({pid}) called");
+
+        let mut request = vec![DRM_FDINFO_REQUEST];
+        request.extend_from_slice(&pid.to_le_bytes());
+
+        let output = Self::companion_request(&request)?;
+
+        rmp_serde::from_slice(&output).context("unable to decode companion process output")
+    }
+
+    /// Returns the resource limits of the process with the given `pid`, these are empty if the process doesn't exist
+    /// (anymore)
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if there are problems communicating with
+    /// the companion process
+    pub fn limits_of(pid: libc::pid_t) -> Result<ProcessLimits> {
+        trace!("limits_of({pid}) called");
+
+        let mut request = vec![LIMITS_REQUEST];
+        request.extend_from_slice(&pid.to_le_bytes());
+
+        let output = Self::companion_request(&request)?;
+
+        rmp_serde::from_slice(&output).context("unable to decode companion process output")
+    }
+
+    /// Returns the open sockets of the process with the given `pid`, this is empty if the process doesn't exist
+    /// (anymore)
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if there are problems communicating with
+    /// the companion process
+    pub fn sockets_of(pid: libc::pid_t) -> Result<Vec<SocketData>> {
+        trace!("sockets_of({pid}) called");
+
+        let mut request = vec![SOCKETS_REQUEST];
+        request.extend_from_slice(&pid.to_le_bytes());
+
+        let output = Self::companion_request(&request)?;
+
+        rmp_serde::from_slice(&output).context("unable to decode companion process output")
+    }
+
+    /// Returns the environment variables of the process with the given `pid`, this is empty if the process doesn't
+    /// exist (anymore) or if they can't be read
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if there are problems communicating with
+    /// the companion process
+    pub fn environment_of(pid: libc::pid_t) -> Result<Vec<EnvironmentVariable>> {
+        trace!("environment_of({pid}) called");
+
+        let mut request = vec![ENVIRONMENT_REQUEST];
+        request.extend_from_slice(&pid.to_le_bytes());
+
+        let output = Self::companion_request(&request)?;
+
+        rmp_serde::from_slice(&output).context("unable to decode companion process output")
+    }
+
+    /// Returns the open files of the process with the given `pid`, this is empty if the process doesn't exist
+    /// (anymore) or if they can't be read
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if there are problems communicating with
+    /// the companion process
+    pub fn open_files_of(pid: libc::pid_t) -> Result<Vec<OpenFile>> {
+        trace!("open_files_of({pid}) called");
+
+        let mut request = vec![OPEN_FILES_REQUEST];
+        request.extend_from_slice(&pid.to_le_bytes());
+
+        let output = Self::companion_request(&request)?;
+
+        rmp_serde::from_slice(&output).context("unable to decode companion process output")
+    }
+
+    /// Returns the wait channel of the process with the given `pid`, this is `WaitChannel::Unknown` if the process
+    /// doesn't exist (anymore)
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if there are problems communicating with
+    /// the companion process
+    pub fn wait_channel_of(pid: libc::pid_t) -> Result<WaitChannel> {
+        trace!("wait_channel_of({pid}) called");
+
+        let mut request = vec![WAIT_CHANNEL_REQUEST];
+        request.extend_from_slice(&pid.to_le_bytes());
+
+        let output = Self::companion_request(&request)?;
+
+        rmp_serde::from_slice(&output).context("unable to decode companion process output")
+    }
+
+    /// Returns the scheduler statistics of the process with the given `pid`, this is `None` if the kernel doesn't
+    /// collect them or if the process doesn't exist (anymore)
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if there are problems communicating with
+    /// the companion process
+    pub fn schedstat_of(pid: libc::pid_t) -> Result<Option<SchedStat>> {
+        trace!("schedstat_of({pid}) called");
+
+        let mut request = vec![SCHEDSTAT_REQUEST];
+        request.extend_from_slice(&pid.to_le_bytes());
+
+        let output = Self::companion_request(&request)?;
+
+        rmp_serde::from_slice(&output).context("unable to decode companion process output")
+    }
+
+    /// Returns the addresses of all network interfaces by their interface names, they're read by the companion
+    /// process since the Flatpak sandbox has its own network namespace
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if there are problems communicating with
+    /// the companion process
+    pub fn interface_addresses() -> Result<HashMap<String, Vec<InterfaceAddress>>> {
+        trace!("interface_addresses() called");
+
+        let output = Self::companion_request(&[INTERFACE_ADDRESSES_REQUEST])?;
+
+        rmp_serde::from_slice(&output).context("unable to decode companion process output")
+    }
+
+    /// Returns the links of all Wi-Fi interfaces by their interface names, this is empty if there are none or if
+    /// nl80211 isn't available
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if there are problems communicating with
+    /// the companion process
+    pub fn wifi_links() -> Result<HashMap<String, WifiLink>> {
+        trace!("wifi_links() called");
+
+        let output = Self::companion_request(&[WIFI_LINKS_REQUEST])?;
+
+        rmp_serde::from_slice(&output).context("unable to decode companion process output")
+    }
+
+    fn companion_request(request: &[u8]) -> Result<Vec<u8>> {
+        trace!("Acquiring companion process lock");
+        let mut process = COMPANION_PROCESS.lock().unwrap();
+        trace!("Writing {request:?} into companion process stdin");
+        let _ = process.0.write_all(request);
+        trace!("Flushing");
+        let _ = process.0.flush();
+
+        let mut len_bytes = [0_u8; (usize::BITS / 8) as usize];
+
+        trace!("Reading companion process output length as little-endian");
+        process.1.read_exact(&mut len_bytes)?;
+
+        let len = usize::from_le_bytes(len_bytes);
+        trace!("Companion process output is {len} bytes long");
+
+        let mut output_bytes = vec![0; len];
+        trace!("Reading companion process output");
+        process.1.read_exact(&mut output_bytes)?;
+
+        Ok(output_bytes)
+    }
+
     pub fn from_process_data(process_data: ProcessData) -> Self {
         let executable_path = process_data
             .commandline
@@ -172,86 +441,43 @@ impl Process {
             read_bytes_last,
             write_bytes_last,
             gpu_usage_stats_last: Default::default(),
+            cpu_throttling_last: None,
             display_name,
+            app_name: None,
         }
     }
 
-    /// Tries to run a command unprivileged and then privileged if permissions were missing
-    fn maybe_pkexec_command<S: AsRef<OsStr>, I: IntoIterator<Item = S>>(
-        command: S,
-        args: I,
-    ) -> Result<i32> {
-        let args = args
-            .into_iter()
-            .map(|s| s.as_ref().to_os_string())
-            .collect::<Vec<_>>();
-
-        let status_code = if *IS_FLATPAK {
-            debug!(
-                "Executing command: {} --host {} {}",
-                FLATPAK_SPAWN,
-                command.as_ref().to_string_lossy(),
-                args.join(&OsString::from(" ")).to_string_lossy()
-            );
-
-            Command::new(FLATPAK_SPAWN)
-                .arg("--host")
-                .arg(command.as_ref())
-                .args(args.clone())
-                .output()?
-                .status
-                .code()
-                .context("no status code?")?
-        } else {
-            debug!(
-                "Executing command: {} {}",
-                command.as_ref().to_string_lossy(),
-                args.join(&OsString::from(" ")).to_string_lossy()
-            );
-
-            Command::new(command.as_ref())
-                .args(args.clone())
-                .output()?
-                .status
-                .code()
-                .context("no status code?")?
-        };
-
-        if status_code == libc::EPERM || status_code == libc::EACCES {
-            let pkexec_status_code = if *IS_FLATPAK {
-                debug!(
-                    "Received EPERM, executing command: {} --host pkexec --disable-internal-agent {} {}", 
-                    FLATPAK_SPAWN,
-                    command.as_ref().to_string_lossy(),
-                    args.join(&OsString::from(" ")).to_string_lossy()
-                );
-                Command::new(FLATPAK_SPAWN)
-                    .args(["--host", "pkexec", "--disable-internal-agent"])
-                    .arg(command)
-                    .args(args)
-                    .output()?
-                    .status
-                    .code()
-                    .context("no status code?")?
-            } else {
-                debug!(
-                    "Received EPERM or EACCES, executing command: pkexec --disable-internal-agent {} {}", 
-                    command.as_ref().to_string_lossy(),
-                    args.join(&OsString::from(" ")).to_string_lossy()
-                );
-                Command::new("pkexec")
-                    .arg("--disable-internal-agent")
-                    .arg(command)
-                    .args(args)
-                    .output()?
-                    .status
-                    .code()
-                    .context("no status code?")?
-            };
+    /// Returns the name that the process is shown with according to `source`. If `full_path` is true, names that are
+    /// based on the executable are replaced by the path of the executable as it was passed to the process (argv0).
+    /// Sources that are unavailable for this process fall back to `ProcessNameSource::Automatic`.
+    pub fn name(&self, source: ProcessNameSource, full_path: bool) -> &str {
+        match source {
+            ProcessNameSource::Comm if !self.data.comm.is_empty() => &self.data.comm,
+            ProcessNameSource::Executable if !self.executable_name.is_empty() => {
+                if full_path && !self.executable_path.is_empty() {
+                    &self.executable_path
+                } else {
+                    &self.executable_name
+                }
+            }
+            ProcessNameSource::App => self
+                .app_name
+                .as_deref()
+                .unwrap_or_else(|| self.automatic_name(full_path)),
+            _ => self.automatic_name(full_path),
+        }
+    }
 
-            Ok(pkexec_status_code)
+    /// Returns the name of the executable, unless the process has no command line or renamed itself. If `full_path`
+    /// is true, this is the path of the executable as it was passed to the process (argv0).
+    fn automatic_name(&self, full_path: bool) -> &str {
+        if full_path
+            && !self.executable_path.is_empty()
+            && self.display_name == self.executable_name
+        {
+            &self.executable_path
         } else {
-            Ok(status_code)
+            &self.display_name
         }
     }
 
@@ -276,7 +502,7 @@ impl Process {
 
         debug!("Trying to adjust with niceness = {niceness} and affinity = {affinity_string}");
 
-        let result = Self::maybe_pkexec_command(
+        let result = maybe_pkexec_command(
             adjust_path,
             [
                 self.data.pid.to_string(),
@@ -299,6 +525,40 @@ impl Process {
         }
     }
 
+    /// Applies `limits` to the cgroup this process belongs to, so they affect all other processes of that cgroup as well
+    pub fn set_cgroup_limits(&self, limits: CgroupLimits) -> Result<()> {
+        let limit_path = if *IS_FLATPAK {
+            format!(
+                "{}/libexec/resources/resources-limit",
+                FLATPAK_APP_PATH.as_str()
+            )
+        } else {
+            format!("{LIBEXECDIR}/resources-limit")
+        };
+
+        let memory_max = limits.memory_max_string();
+        let cpu_max = limits.cpu_max_string();
+
+        debug!("Trying to limit cgroup with memory.max = {memory_max} and cpu.max = {cpu_max}");
+
+        let return_code =
+            maybe_pkexec_command(limit_path, [self.data.pid.to_string(), memory_max, cpu_max])?;
+
+        if return_code == 0 {
+            info!("Successfully limited the cgroup of {}", self.data.pid);
+            Ok(())
+        } else {
+            bail!("non-zero return code: {return_code}")
+        }
+    }
+
+    /// Whether signals can be sent to this process without asking for administrator privileges, i.e. whether it belongs
+    /// to the current user or Resources is running as root
+    #[must_use]
+    pub fn can_be_signalled(&self) -> bool {
+        *CURRENT_USER == "root" || self.data.user == *CURRENT_USER
+    }
+
     pub fn execute_process_action(&self, action: ProcessAction) -> Result<()> {
         let action_string = action.to_string();
 
@@ -311,7 +571,7 @@ impl Process {
             format!("{LIBEXECDIR}/resources-kill")
         };
 
-        let result = Self::maybe_pkexec_command(
+        let result = maybe_pkexec_command(
             kill_path,
             [self.data.pid.to_string(), action_string.clone()],
         );
@@ -360,6 +620,26 @@ impl Process {
         }
     }
 
+    /// Returns the fraction of CPU bandwidth periods in which the process' cgroup was throttled since
+    /// the last refresh or `None` if neither the cgroup nor any of its parents have a CPU quota
+    #[must_use]
+    pub fn cpu_throttled_ratio(&self) -> Option<f32> {
+        let throttling = self.data.cpu_throttling?;
+
+        let Some(throttling_last) = self.cpu_throttling_last else {
+            return Some(0.0);
+        };
+
+        let delta_periods = throttling
+            .nr_periods
+            .saturating_sub(throttling_last.nr_periods);
+        let delta_throttled = throttling
+            .nr_throttled
+            .saturating_sub(throttling_last.nr_throttled);
+
+        Some((delta_throttled as f32 / delta_periods as f32).finite_or_default())
+    }
+
     #[must_use]
     pub fn read_speed(&self) -> Option<f64> {
         if let (Some(read_bytes), Some(read_bytes_last)) =
@@ -394,79 +674,94 @@ impl Process {
         }
     }
 
+    /// Combined usage of all GPUs used by this process, e.g. a process that fully uses two GPUs has a usage of 2.0
     #[must_use]
     pub fn gpu_usage(&self) -> f32 {
-        let mut returned_gpu_usage = 0.0;
-        for (gpu, usage) in &self.data.gpu_usage_stats {
-            if let Some(old_usage) = self.gpu_usage_stats_last.get(gpu) {
-                let this_gpu_usage = if usage.nvidia {
-                    usage.gfx as f32 / 100.0
-                } else if old_usage.gfx == 0 {
-                    0.0
-                } else {
-                    ((usage.gfx.saturating_sub(old_usage.gfx) as f32)
-                        / (self.data.timestamp.saturating_sub(self.timestamp_last) as f32)
-                            .finite_or_default())
-                        / 1_000_000.0
-                };
-
-                if this_gpu_usage > returned_gpu_usage {
-                    returned_gpu_usage = this_gpu_usage;
-                }
-            }
-        }
-
-        returned_gpu_usage
+        self.data
+            .gpu_usage_stats
+            .keys()
+            .filter_map(|gpu_identifier| self.gpu_usage_of(gpu_identifier))
+            .sum()
     }
 
+    /// Combined video encoder usage of all GPUs used by this process
     #[must_use]
     pub fn enc_usage(&self) -> f32 {
-        let mut returned_gpu_usage = 0.0;
-        for (gpu, usage) in &self.data.gpu_usage_stats {
-            if let Some(old_usage) = self.gpu_usage_stats_last.get(gpu) {
-                let this_gpu_usage = if usage.nvidia {
-                    usage.enc as f32 / 100.0
-                } else if old_usage.enc == 0 {
-                    0.0
-                } else {
-                    ((usage.enc.saturating_sub(old_usage.enc) as f32)
-                        / (self.data.timestamp.saturating_sub(self.timestamp_last) as f32)
-                            .finite_or_default())
-                        / 1_000_000.0
-                };
-
-                if this_gpu_usage > returned_gpu_usage {
-                    returned_gpu_usage = this_gpu_usage;
-                }
-            }
-        }
-
-        returned_gpu_usage
+        self.data
+            .gpu_usage_stats
+            .keys()
+            .filter_map(|gpu_identifier| self.enc_usage_of(gpu_identifier))
+            .sum()
     }
 
+    /// Combined video decoder usage of all GPUs used by this process
     #[must_use]
     pub fn dec_usage(&self) -> f32 {
-        let mut returned_gpu_usage = 0.0;
-        for (gpu, usage) in &self.data.gpu_usage_stats {
-            if let Some(old_usage) = self.gpu_usage_stats_last.get(gpu) {
-                let this_gpu_usage = if usage.nvidia {
-                    usage.dec as f32 / 100.0
-                } else if old_usage.dec == 0 {
-                    0.0
-                } else {
-                    ((usage.dec.saturating_sub(old_usage.dec) as f32)
-                        / (self.data.timestamp.saturating_sub(self.timestamp_last) as f32)
-                            .finite_or_default())
-                        / 1_000_000.0
-                };
-
-                if this_gpu_usage > returned_gpu_usage {
-                    returned_gpu_usage = this_gpu_usage;
-                }
-            }
-        }
+        self.data
+            .gpu_usage_stats
+            .keys()
+            .filter_map(|gpu_identifier| self.dec_usage_of(gpu_identifier))
+            .sum()
+    }
 
-        returned_gpu_usage
+    /// Usages of the individual GPUs used by this process
+    #[must_use]
+    pub fn gpu_usages(&self) -> BTreeMap<GpuIdentifier, f32> {
+        self.data
+            .gpu_usage_stats
+            .keys()
+            .filter_map(|gpu_identifier| {
+                self.gpu_usage_of(gpu_identifier)
+                    .map(|usage| (*gpu_identifier, usage))
+            })
+            .collect()
+    }
+
+    /// Usage of a single GPU by this process, `None` if the process doesn't use that GPU
+    #[must_use]
+    pub fn gpu_usage_of(&self, gpu_identifier: &GpuIdentifier) -> Option<f32> {
+        self.gpu_fraction_of(gpu_identifier, GpuUsageStats::gfx_fraction)
+    }
+
+    /// Video encoder usage of a single GPU by this process, `None` if the process doesn't use that GPU
+    #[must_use]
+    pub fn enc_usage_of(&self, gpu_identifier: &GpuIdentifier) -> Option<f32> {
+        self.gpu_fraction_of(gpu_identifier, GpuUsageStats::enc_fraction)
+    }
+
+    /// Video decoder usage of a single GPU by this process, `None` if the process doesn't use that GPU
+    #[must_use]
+    pub fn dec_usage_of(&self, gpu_identifier: &GpuIdentifier) -> Option<f32> {
+        self.gpu_fraction_of(gpu_identifier, GpuUsageStats::dec_fraction)
+    }
+
+    /// Calculates a usage fraction of a single GPU using only the stats of that GPU, `None` if the process doesn't use
+    /// that GPU
+    fn gpu_fraction_of<F: Fn(&GpuUsageStats, &GpuUsageStats, u64) -> f32>(
+        &self,
+        gpu_identifier: &GpuIdentifier,
+        fraction: F,
+    ) -> Option<f32> {
+        let usage = self.data.gpu_usage_stats.get(gpu_identifier)?;
+        let millis_passed = self.data.timestamp.saturating_sub(self.timestamp_last);
+
+        let fraction = match self.gpu_usage_stats_last.get(gpu_identifier) {
+            Some(old_usage) => fraction(usage, old_usage, millis_passed),
+            // NVIDIA's stats are already fractions, so they don't need an older sample
+            None if usage.nvidia => fraction(usage, usage, millis_passed),
+            None => 0.0,
+        };
+
+        Some(fraction)
+    }
+
+    /// Video memory usage of a single GPU by this process, `None` if the process doesn't use that GPU
+    #[must_use]
+    pub fn gpu_mem_usage_of(&self, gpu_identifier: &GpuIdentifier) -> Option<u64> {
+        self.data
+            .gpu_usage_stats
+            .get(gpu_identifier)
+            .map(|stats| stats.mem)
     }
 
     #[must_use]
@@ -478,6 +773,16 @@ impl Process {
             .sum()
     }
 
+    /// Returns the part of `gpu_mem_usage()` that resides in system memory instead of VRAM
+    #[must_use]
+    pub fn gpu_mem_system_usage(&self) -> u64 {
+        self.data
+            .gpu_usage_stats
+            .values()
+            .map(|stats| stats.mem_system)
+            .sum()
+    }
+
     #[must_use]
     pub fn starttime(&self) -> f64 {
         self.data.starttime as f64 / *TICK_RATE as f64
@@ -501,4 +806,363 @@ impl Process {
             Some(cmdline.replace('\0', " "))
         }
     }
+
+    /// Calculates how deeply each process is nested in the process tree from `(pid, parent_pid)` pairs. Processes whose
+    /// parent is unknown, like init and kthreadd, have a depth of 0.
+    pub fn tree_depths<I: IntoIterator<Item = (libc::pid_t, libc::pid_t)>>(
+        pids_and_parents: I,
+    ) -> HashMap<libc::pid_t, u32> {
+        let parents: HashMap<libc::pid_t, libc::pid_t> = pids_and_parents.into_iter().collect();
+        let mut depths = HashMap::with_capacity(parents.len());
+
+        for &pid in parents.keys() {
+            // walk up the tree until we find an ancestor whose depth we already know or the root
+            let mut chain = Vec::new();
+            let mut current = pid;
+            let mut depth = loop {
+                if let Some(depth) = depths.get(&current) {
+                    break *depth + 1;
+                }
+
+                chain.push(current);
+
+                match parents.get(&current) {
+                    // processes are read one after another, so their parents might not be consistent
+                    Some(parent) if parents.contains_key(parent) && !chain.contains(parent) => {
+                        current = *parent;
+                    }
+                    _ => break 0,
+                }
+            };
+
+            for pid in chain.into_iter().rev() {
+                depths.insert(pid, depth);
+                depth += 1;
+            }
+        }
+
+        depths
+    }
+
+    /// Shortens `commandline` to at most `max_chars` characters by cutting out arguments from the middle. The binary
+    /// and as many of the last arguments as possible are kept since they're usually the most telling parts, which
+    /// means that a very long binary path can make the result exceed `max_chars`. `max_chars` being 0 disables
+    /// shortening.
+    pub fn ellipsize_commandline<S: AsRef<str>>(commandline: S, max_chars: usize) -> String {
+        let commandline = commandline.as_ref().trim();
+
+        if max_chars == 0 || commandline.chars().count() <= max_chars {
+            return commandline.to_string();
+        }
+
+        let Some((binary, args)) = commandline.split_once(' ') else {
+            return commandline.to_string();
+        };
+
+        // leave room for the " … " between the binary and the remaining arguments
+        let mut remaining_chars = max_chars.saturating_sub(binary.chars().count() + 3);
+
+        let mut last_args = Vec::new();
+        for arg in args.split(' ').rev() {
+            let needed_chars = arg.chars().count() + usize::from(!last_args.is_empty());
+            if needed_chars > remaining_chars {
+                break;
+            }
+            remaining_chars -= needed_chars;
+            last_args.push(arg);
+        }
+        last_args.reverse();
+
+        let tail = if last_args.is_empty() {
+            // not even the last argument fits as a whole, so at least show its end
+            let chars = args.chars().count();
+            args.chars()
+                .skip(chars.saturating_sub(remaining_chars))
+                .collect()
+        } else {
+            last_args.join(" ")
+        };
+
+        if tail.is_empty() {
+            format!("{binary} …")
+        } else {
+            format!("{binary} … {tail}")
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        collections::{BTreeMap, HashMap},
+        path::PathBuf,
+    };
+
+    use pretty_assertions::assert_eq;
+    use process_data::{pci_slot::PciSlot, GpuIdentifier, GpuUsageStats, ProcessData};
+
+    use super::{CgroupLimits, Process, ProcessNameSource};
+
+    fn process_with(comm: &str, commandline: &str) -> Process {
+        Process::from_process_data(ProcessData {
+            comm: comm.to_string(),
+            commandline: commandline.to_string(),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn name_full_path() {
+        let process = process_with("firefox", "/usr/lib/firefox/firefox\0-contentproc");
+
+        assert_eq!("firefox", process.name(ProcessNameSource::Automatic, false));
+        assert_eq!(
+            "/usr/lib/firefox/firefox",
+            process.name(ProcessNameSource::Automatic, true)
+        );
+    }
+
+    #[test]
+    fn name_full_path_without_commandline() {
+        let kernel_thread = process_with("kworker/0:1", "");
+        assert_eq!(
+            "kworker/0:1",
+            kernel_thread.name(ProcessNameSource::Automatic, true)
+        );
+
+        // processes that renamed themselves are shown with their new name
+        let renamed = process_with("Web Content", "/usr/lib/firefox/firefox\0-contentproc");
+        assert_eq!(
+            "Web Content",
+            renamed.name(ProcessNameSource::Automatic, true)
+        );
+    }
+
+    #[test]
+    fn name_sources() {
+        let mut process = process_with("Isolated Web Co", "/usr/lib/firefox/firefox\0-contentproc");
+
+        assert_eq!(
+            "Isolated Web Co",
+            process.name(ProcessNameSource::Comm, true)
+        );
+        assert_eq!(
+            "firefox",
+            process.name(ProcessNameSource::Executable, false)
+        );
+        assert_eq!(
+            "/usr/lib/firefox/firefox",
+            process.name(ProcessNameSource::Executable, true)
+        );
+
+        // processes without an app fall back to the automatic name
+        assert_eq!(
+            "Isolated Web Co",
+            process.name(ProcessNameSource::App, false)
+        );
+
+        process.app_name = Some("Firefox".to_string());
+        assert_eq!("Firefox", process.name(ProcessNameSource::App, true));
+    }
+
+    #[test]
+    fn tree_depths_chain() {
+        let depths = Process::tree_depths([(1, 0), (2, 0), (100, 1), (200, 100), (300, 2)]);
+
+        assert_eq!(
+            HashMap::from([(1, 0), (2, 0), (100, 1), (200, 2), (300, 1)]),
+            depths
+        );
+    }
+
+    #[test]
+    fn tree_depths_unknown_parent() {
+        // the parent might have exited after its child was read
+        let depths = Process::tree_depths([(1, 0), (500, 400), (600, 500)]);
+
+        assert_eq!(HashMap::from([(1, 0), (500, 0), (600, 1)]), depths);
+    }
+
+    #[test]
+    fn tree_depths_cycle() {
+        let depths = Process::tree_depths([(10, 20), (20, 10)]);
+
+        assert_eq!(2, depths.len());
+        assert!(depths.values().all(|depth| *depth <= 1));
+    }
+
+    #[test]
+    fn ellipsize_commandline_short() {
+        assert_eq!(
+            "/usr/bin/bash --login",
+            Process::ellipsize_commandline("/usr/bin/bash --login", 30)
+        );
+        assert_eq!(
+            "/usr/bin/bash --login",
+            Process::ellipsize_commandline("/usr/bin/bash --login", 21)
+        );
+    }
+
+    #[test]
+    fn ellipsize_commandline_disabled() {
+        assert_eq!(
+            "/usr/bin/java -Xmx4g -cp a.jar:b.jar Main",
+            Process::ellipsize_commandline("/usr/bin/java -Xmx4g -cp a.jar:b.jar Main", 0)
+        );
+    }
+
+    #[test]
+    fn ellipsize_commandline_keeps_binary_and_last_args() {
+        assert_eq!(
+            "/usr/bin/java … a.jar:b.jar Main",
+            Process::ellipsize_commandline("/usr/bin/java -Xmx4g -cp a.jar:b.jar Main", 32)
+        );
+        assert_eq!(
+            "/usr/bin/java … Main",
+            Process::ellipsize_commandline("/usr/bin/java -Xmx4g -cp a.jar:b.jar Main", 25)
+        );
+    }
+
+    #[test]
+    fn ellipsize_commandline_long_last_arg() {
+        assert_eq!(
+            "python3 … /b/c.py",
+            Process::ellipsize_commandline("python3 -u /very/long/path/a/b/c.py", 17)
+        );
+    }
+
+    #[test]
+    fn ellipsize_commandline_long_binary() {
+        assert_eq!(
+            "/a/very/long/binary …",
+            Process::ellipsize_commandline("/a/very/long/binary --flag", 10)
+        );
+        assert_eq!(
+            "/a/very/long/binary",
+            Process::ellipsize_commandline("/a/very/long/binary", 10)
+        );
+    }
+
+    fn gpu_stats(gfx: u64, enc: u64, mem: u64, gfx_total_cycles: Option<u64>) -> GpuUsageStats {
+        GpuUsageStats {
+            gfx,
+            mem,
+            mem_system: 0,
+            enc,
+            dec: 0,
+            nvidia: false,
+            gfx_total_cycles,
+            media_total_cycles: gfx_total_cycles,
+        }
+    }
+
+    fn process_using_gpus(
+        new: BTreeMap<GpuIdentifier, GpuUsageStats>,
+        old: BTreeMap<GpuIdentifier, GpuUsageStats>,
+    ) -> Process {
+        let mut process = Process::from_process_data(ProcessData {
+            timestamp: 2000,
+            gpu_usage_stats: new,
+            ..Default::default()
+        });
+        process.timestamp_last = 1000;
+        process.gpu_usage_stats_last = old;
+        process
+    }
+
+    #[test]
+    fn gpu_usage_of_two_gpus() {
+        let amd = GpuIdentifier::PciSlot(PciSlot::new(0, 3, 0, 0));
+        let intel = GpuIdentifier::PciSlot(PciSlot::new(0, 0, 2, 0));
+        let unused = GpuIdentifier::PciSlot(PciSlot::new(0, 4, 0, 0));
+
+        let process = process_using_gpus(
+            BTreeMap::from([
+                (amd, gpu_stats(1_500_000_000, 1_200_000_000, 4096, None)),
+                (intel, gpu_stats(350, 150, 1024, Some(2000))),
+            ]),
+            BTreeMap::from([
+                (amd, gpu_stats(1_000_000_000, 1_000_000_000, 2048, None)),
+                (intel, gpu_stats(100, 50, 1024, Some(1000))),
+            ]),
+        );
+
+        assert_eq!(Some(0.5), process.gpu_usage_of(&amd));
+        assert_eq!(Some(0.25), process.gpu_usage_of(&intel));
+        assert_eq!(None, process.gpu_usage_of(&unused));
+        assert_eq!(0.75, process.gpu_usage());
+
+        assert_eq!(Some(0.2), process.enc_usage_of(&amd));
+        assert_eq!(Some(0.1), process.enc_usage_of(&intel));
+
+        assert_eq!(Some(4096), process.gpu_mem_usage_of(&amd));
+        assert_eq!(Some(1024), process.gpu_mem_usage_of(&intel));
+        assert_eq!(None, process.gpu_mem_usage_of(&unused));
+        assert_eq!(5120, process.gpu_mem_usage());
+    }
+
+    #[test]
+    fn gpu_usage_of_two_nvidia_gpus_without_previous_sample() {
+        let first = GpuIdentifier::PciSlot(PciSlot::new(0, 1, 0, 0));
+        let second = GpuIdentifier::PciSlot(PciSlot::new(0, 2, 0, 0));
+
+        let nvidia_stats = |gfx| GpuUsageStats {
+            nvidia: true,
+            ..gpu_stats(gfx, 0, 0, None)
+        };
+
+        let process = process_using_gpus(
+            BTreeMap::from([(first, nvidia_stats(25)), (second, nvidia_stats(50))]),
+            BTreeMap::new(),
+        );
+
+        assert_eq!(Some(0.25), process.gpu_usage_of(&first));
+        assert_eq!(Some(0.5), process.gpu_usage_of(&second));
+        assert_eq!(0.75, process.gpu_usage());
+    }
+
+    #[test]
+    fn cgroup_limits_parse() {
+        assert_eq!(
+            CgroupLimits {
+                memory_max: Some(2147483648),
+                cpu_max: Some(150),
+            },
+            CgroupLimits::parse("2147483648\n", "150000 100000\n")
+        );
+        assert_eq!(
+            CgroupLimits::default(),
+            CgroupLimits::parse("max\n", "max 100000\n")
+        );
+    }
+
+    #[test]
+    fn cgroup_limits_strings() {
+        let limits = CgroupLimits {
+            memory_max: Some(1048576),
+            cpu_max: Some(50),
+        };
+        assert_eq!("1048576", limits.memory_max_string());
+        assert_eq!("50000 100000", limits.cpu_max_string());
+
+        assert_eq!("max", CgroupLimits::default().memory_max_string());
+        assert_eq!("max 100000", CgroupLimits::default().cpu_max_string());
+    }
+
+    #[test]
+    fn cgroup_path() {
+        assert_eq!(
+            Some(PathBuf::from(
+                "/sys/fs/cgroup/user.slice/user-1000.slice/user@1000.service/app.slice/app-firefox.scope"
+            )),
+            CgroupLimits::cgroup_path(
+                "0::/user.slice/user-1000.slice/user@1000.service/app.slice/app-firefox.scope\n"
+            )
+        );
+        assert_eq!(None, CgroupLimits::cgroup_path("0::/\n"));
+        assert_eq!(
+            None,
+            CgroupLimits::cgroup_path("1:name=systemd:/init.scope\n")
+        );
+    }
 }
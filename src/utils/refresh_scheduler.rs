@@ -0,0 +1,138 @@
+//! Decides which subsystems are gathered in a refresh, so that expensive or rarely changing ones can be refreshed less
+//! often than e.g. the processor and memory
+
+use std::{collections::HashMap, time::Instant};
+
+use super::settings::{RefreshSpeed, SETTINGS};
+
+/// A part of the system whose refresh speed can be set independently of the general refresh speed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RefreshSubsystem {
+    Processes,
+    Drives,
+    /// Network interfaces and InfiniBand ports
+    Network,
+    /// Batteries and custom gauges
+    Sensors,
+}
+
+impl RefreshSubsystem {
+    pub const ALL: [RefreshSubsystem; 4] = [
+        RefreshSubsystem::Processes,
+        RefreshSubsystem::Drives,
+        RefreshSubsystem::Network,
+        RefreshSubsystem::Sensors,
+    ];
+
+    /// Returns `None` if this subsystem should be refreshed at the general refresh speed
+    pub fn refresh_speed(self) -> Option<RefreshSpeed> {
+        match self {
+            RefreshSubsystem::Processes => SETTINGS.processes_refresh_speed(),
+            RefreshSubsystem::Drives => SETTINGS.drives_refresh_speed(),
+            RefreshSubsystem::Network => SETTINGS.network_refresh_speed(),
+            RefreshSubsystem::Sensors => SETTINGS.sensors_refresh_speed(),
+        }
+    }
+}
+
+/// The subsystems that are due in a refresh
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DueSubsystems {
+    pub processes: bool,
+    pub drives: bool,
+    pub network: bool,
+    pub sensors: bool,
+}
+
+impl DueSubsystems {
+    /// Every subsystem, but the processes only if `processes` is set since walking through /proc is expensive
+    pub const fn all(processes: bool) -> Self {
+        Self {
+            processes,
+            drives: true,
+            network: true,
+            sensors: true,
+        }
+    }
+
+    fn insert(&mut self, subsystem: RefreshSubsystem) {
+        match subsystem {
+            RefreshSubsystem::Processes => self.processes = true,
+            RefreshSubsystem::Drives => self.drives = true,
+            RefreshSubsystem::Network => self.network = true,
+            RefreshSubsystem::Sensors => self.sensors = true,
+        }
+    }
+}
+
+/// Keeps track of when each subsystem has last been gathered
+#[derive(Debug, Default)]
+pub struct RefreshScheduler {
+    last_gathering: HashMap<RefreshSubsystem, Instant>,
+}
+
+impl RefreshScheduler {
+    /// Returns the subsystems that are due in the refresh that's about to happen, `refresh_interval` being the
+    /// seconds between two refreshes at the general refresh speed
+    pub fn due_subsystems(&mut self, refresh_interval: f32) -> DueSubsystems {
+        let now = Instant::now();
+
+        let mut due = DueSubsystems::default();
+
+        for subsystem in RefreshSubsystem::ALL {
+            let elapsed = self
+                .last_gathering
+                .get(&subsystem)
+                .map(|last_gathering| now.duration_since(*last_gathering).as_secs_f32());
+
+            let interval = subsystem
+                .refresh_speed()
+                .map(|refresh_speed| refresh_speed.ui_refresh_interval());
+
+            if Self::is_due(elapsed, interval, refresh_interval) {
+                self.last_gathering.insert(subsystem, now);
+                due.insert(subsystem);
+            }
+        }
+
+        due
+    }
+
+    /// A subsystem is due if it hasn't been gathered yet, follows the general refresh speed or its own interval has
+    /// (almost) passed
+    fn is_due(elapsed: Option<f32>, interval: Option<f32>, refresh_interval: f32) -> bool {
+        match (elapsed, interval) {
+            (Some(elapsed), Some(interval)) => {
+                // allow for some jitter, otherwise we'd regularly skip a refresh that is only slightly early
+                let tolerance = refresh_interval / 2.0;
+                elapsed + tolerance >= interval
+            }
+            _ => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::RefreshScheduler;
+
+    #[test]
+    fn due_if_never_gathered() {
+        assert!(RefreshScheduler::is_due(None, Some(5.0), 1.0));
+    }
+
+    #[test]
+    fn due_at_general_refresh_speed() {
+        assert!(RefreshScheduler::is_due(Some(0.0), None, 1.0));
+    }
+
+    #[test]
+    fn not_due_before_interval() {
+        assert!(!RefreshScheduler::is_due(Some(3.0), Some(5.0), 1.0));
+    }
+
+    #[test]
+    fn due_slightly_early() {
+        assert!(RefreshScheduler::is_due(Some(4.6), Some(5.0), 1.0));
+    }
+}
@@ -0,0 +1,186 @@
+use std::collections::VecDeque;
+
+/// Keeps a downsampled history of a value over the whole session while only using a bounded amount of memory.
+///
+/// Every point of the history is the average of `samples_per_point` consecutive samples. Whenever the history is full,
+/// neighbouring points are merged, which halves the resolution and doubles `samples_per_point`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionHistory {
+    points: VecDeque<f64>,
+    capacity: usize,
+    samples_per_point: usize,
+    pending_sum: f64,
+    pending_samples: usize,
+    total_sum: f64,
+    total_samples: usize,
+}
+
+impl Default for SessionHistory {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CAPACITY)
+    }
+}
+
+impl SessionHistory {
+    pub const DEFAULT_CAPACITY: usize = 600;
+
+    /// Creates an empty history that holds at most `capacity` points. The capacity is rounded up to an even number of
+    /// at least 2 so that points can always be merged in pairs.
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(2).next_multiple_of(2);
+
+        Self {
+            points: VecDeque::with_capacity(capacity),
+            capacity,
+            samples_per_point: 1,
+            pending_sum: 0.0,
+            pending_samples: 0,
+            total_sum: 0.0,
+            total_samples: 0,
+        }
+    }
+
+    pub fn push(&mut self, value: f64) {
+        self.total_sum += value;
+        self.total_samples += 1;
+
+        self.pending_sum += value;
+        self.pending_samples += 1;
+
+        if self.points.len() >= self.capacity && self.pending_samples >= self.samples_per_point {
+            self.merge_points();
+        }
+
+        // after merging, the pending samples only make up half a point
+        if self.pending_samples < self.samples_per_point {
+            return;
+        }
+
+        self.points
+            .push_back(self.pending_sum / self.pending_samples as f64);
+        self.pending_sum = 0.0;
+        self.pending_samples = 0;
+    }
+
+    /// Merges every two neighbouring points into one
+    fn merge_points(&mut self) {
+        self.points = self
+            .points
+            .iter()
+            .collect::<Vec<_>>()
+            .chunks(2)
+            .map(|chunk| chunk.iter().copied().sum::<f64>() / chunk.len() as f64)
+            .collect();
+        self.samples_per_point *= 2;
+    }
+
+    /// The amount of samples that each point of the history is made up of
+    pub fn samples_per_point(&self) -> usize {
+        self.samples_per_point
+    }
+
+    /// The average of all samples pushed so far, or `None` if there are none
+    pub fn average(&self) -> Option<f64> {
+        if self.total_samples == 0 {
+            None
+        } else {
+            Some(self.total_sum / self.total_samples as f64)
+        }
+    }
+
+    /// Returns the points of the history from oldest to newest, including the samples that have not been combined
+    /// into a full point yet
+    pub fn values(&self) -> Vec<f64> {
+        let mut values: Vec<f64> = self.points.iter().copied().collect();
+
+        if self.pending_samples > 0 {
+            values.push(self.pending_sum / self.pending_samples as f64);
+        }
+
+        values
+    }
+
+    /// Returns the history averaged down to at most `amount` points, e.g. to fit it into a graph
+    pub fn resample(&self, amount: usize) -> Vec<f64> {
+        let values = self.values();
+
+        if amount == 0 {
+            return Vec::new();
+        } else if values.len() <= amount {
+            return values;
+        }
+
+        (0..amount)
+            .map(|i| {
+                let start = i * values.len() / amount;
+                let end = (i + 1) * values.len() / amount;
+                let bucket = &values[start..end];
+                bucket.iter().sum::<f64>() / bucket.len() as f64
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::SessionHistory;
+
+    #[test]
+    fn push_below_capacity() {
+        let mut history = SessionHistory::new(4);
+        history.push(0.25);
+        history.push(0.75);
+
+        assert_eq!(vec![0.25, 0.75], history.values());
+        assert_eq!(1, history.samples_per_point());
+        assert_eq!(Some(0.5), history.average());
+    }
+
+    #[test]
+    fn push_merges_when_full() {
+        let mut history = SessionHistory::new(4);
+        for value in [1.0, 3.0, 5.0, 7.0, 9.0] {
+            history.push(value);
+        }
+
+        assert_eq!(2, history.samples_per_point());
+        assert_eq!(vec![2.0, 6.0, 9.0], history.values());
+
+        history.push(11.0);
+        assert_eq!(vec![2.0, 6.0, 10.0], history.values());
+        assert_eq!(Some(6.0), history.average());
+    }
+
+    #[test]
+    fn memory_stays_bounded() {
+        let mut history = SessionHistory::new(10);
+        for i in 0..10_000 {
+            history.push(i as f64);
+        }
+
+        assert!(history.values().len() <= 11);
+        assert_eq!(1024, history.samples_per_point());
+    }
+
+    #[test]
+    fn resample_averages_buckets() {
+        let mut history = SessionHistory::new(8);
+        for value in [1.0, 3.0, 2.0, 4.0, 5.0, 7.0] {
+            history.push(value);
+        }
+
+        assert_eq!(vec![2.0, 3.0, 6.0], history.resample(3));
+        assert_eq!(history.values(), history.resample(10));
+        assert!(history.resample(0).is_empty());
+    }
+
+    #[test]
+    fn empty_history() {
+        let history = SessionHistory::default();
+
+        assert!(history.values().is_empty());
+        assert_eq!(None, history.average());
+    }
+}
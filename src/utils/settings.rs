@@ -1,17 +1,56 @@
-use std::{ops::Deref, str::FromStr, sync::LazyLock};
+use std::{collections::HashMap, ops::Deref, str::FromStr, sync::LazyLock};
 
 use adw::prelude::*;
 
+use anyhow::{bail, Context, Result};
 use gtk::{gio, glib, SortType};
-use log::debug;
+use log::{debug, info, warn};
 use strum_macros::{Display, EnumString, FromRepr};
 
 use paste::paste;
 
-use crate::config::APP_ID;
+use crate::{config::APP_ID, utils::custom_gauge::CustomGauge};
 
 pub static SETTINGS: LazyLock<Settings> = LazyLock::new(Settings::default);
 
+/// Keys of the settings that decide whether the pages of the different subsystems are shown
+const PAGE_VISIBILITY_KEYS: [&str; 10] = [
+    "show-cpu-page",
+    "show-memory-page",
+    "show-gpu-pages",
+    "show-npu-pages",
+    "show-drive-pages",
+    "show-network-pages",
+    "show-battery-pages",
+    "show-applications-page",
+    "show-processes-page",
+    "show-os-page",
+];
+
+/// Version of the format settings are exported in, to be increased whenever it changes incompatibly
+const SETTINGS_EXPORT_VERSION: u32 = 1;
+
+const SETTINGS_EXPORT_HEADER: &str = "# Resources settings";
+
+/// Keys that only make sense on the machine they've been set on and are thus neither exported nor imported
+const MACHINE_SPECIFIC_KEYS: [&str; 5] = [
+    "window-width",
+    "window-height",
+    "is-maximized",
+    "last-viewed-page",
+    "device-visibility",
+];
+
+/// The outcome of importing settings using `Settings::import()`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SettingsImport {
+    /// Number of settings that have been applied
+    pub applied: usize,
+    /// Keys that have been skipped because they're unknown (e.g. since the export is from a different version of
+    /// Resources) or because their values are invalid
+    pub ignored: Vec<String>,
+}
+
 macro_rules! bool_settings {
     ($($setting_name:ident),*) => {
         $(
@@ -119,6 +158,8 @@ pub enum TemperatureUnit {
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, Default, EnumString, Display, Hash, FromRepr)]
 pub enum RefreshSpeed {
+    EveryTenSeconds,
+    EveryFiveSeconds,
     VerySlow,
     Slow,
     #[default]
@@ -130,6 +171,8 @@ pub enum RefreshSpeed {
 impl RefreshSpeed {
     pub const fn ui_refresh_interval(&self) -> f32 {
         match self {
+            RefreshSpeed::EveryTenSeconds => 10.0,
+            RefreshSpeed::EveryFiveSeconds => 5.0,
             RefreshSpeed::VerySlow => 3.0,
             RefreshSpeed::Slow => 2.0,
             RefreshSpeed::Normal => 1.0,
@@ -139,6 +182,15 @@ impl RefreshSpeed {
     }
 }
 
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, EnumString, Display, Hash, FromRepr)]
+pub enum GpuTemperatureSensor {
+    #[default]
+    Edge,
+    Junction,
+    Memory,
+}
+
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, Default, PartialEq, EnumString, Display, Hash, FromRepr)]
 pub enum SidebarMeterType {
@@ -147,6 +199,20 @@ pub enum SidebarMeterType {
     Graph,
 }
 
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, EnumString, Display, Hash, FromRepr)]
+pub enum ProcessNameSource {
+    /// The name of the executable, unless the process renamed itself
+    #[default]
+    Automatic,
+    /// The kernel's command name, which is truncated to 15 characters
+    Comm,
+    /// The name of the executable, even if the process renamed itself
+    Executable,
+    /// The name of the app the process belongs to, if there is one
+    App,
+}
+
 #[derive(Clone, Debug, Hash)]
 pub struct Settings(gio::Settings);
 
@@ -229,6 +295,175 @@ impl Settings {
         })
     }
 
+    /// Returns `None` if processes should be refreshed as fast as everything else
+    pub fn processes_refresh_speed(&self) -> Option<RefreshSpeed> {
+        RefreshSpeed::from_str(self.string("processes-refresh-speed").as_str()).ok()
+    }
+
+    pub fn set_processes_refresh_speed(
+        &self,
+        value: Option<RefreshSpeed>,
+    ) -> Result<(), glib::error::BoolError> {
+        let value = value.map_or_else(|| "Same".to_string(), |value| value.to_string());
+        debug!("Setting processes-refresh-speed to {}", value);
+        self.set_string("processes-refresh-speed", &value)
+    }
+
+    pub fn connect_processes_refresh_speed<F: Fn(Option<RefreshSpeed>) + 'static>(
+        &self,
+        f: F,
+    ) -> glib::SignalHandlerId {
+        self.connect_changed(Some("processes-refresh-speed"), move |settings, _key| {
+            f(RefreshSpeed::from_str(settings.string("processes-refresh-speed").as_str()).ok());
+        })
+    }
+
+    /// Returns `None` if drives should be refreshed as fast as everything else
+    pub fn drives_refresh_speed(&self) -> Option<RefreshSpeed> {
+        RefreshSpeed::from_str(self.string("drives-refresh-speed").as_str()).ok()
+    }
+
+    pub fn set_drives_refresh_speed(
+        &self,
+        value: Option<RefreshSpeed>,
+    ) -> Result<(), glib::error::BoolError> {
+        let value = value.map_or_else(|| "Same".to_string(), |value| value.to_string());
+        debug!("Setting drives-refresh-speed to {}", value);
+        self.set_string("drives-refresh-speed", &value)
+    }
+
+    pub fn connect_drives_refresh_speed<F: Fn(Option<RefreshSpeed>) + 'static>(
+        &self,
+        f: F,
+    ) -> glib::SignalHandlerId {
+        self.connect_changed(Some("drives-refresh-speed"), move |settings, _key| {
+            f(RefreshSpeed::from_str(settings.string("drives-refresh-speed").as_str()).ok());
+        })
+    }
+
+    /// Returns `None` if network interfaces should be refreshed as fast as everything else
+    pub fn network_refresh_speed(&self) -> Option<RefreshSpeed> {
+        RefreshSpeed::from_str(self.string("network-refresh-speed").as_str()).ok()
+    }
+
+    pub fn set_network_refresh_speed(
+        &self,
+        value: Option<RefreshSpeed>,
+    ) -> Result<(), glib::error::BoolError> {
+        let value = value.map_or_else(|| "Same".to_string(), |value| value.to_string());
+        debug!("Setting network-refresh-speed to {}", value);
+        self.set_string("network-refresh-speed", &value)
+    }
+
+    pub fn connect_network_refresh_speed<F: Fn(Option<RefreshSpeed>) + 'static>(
+        &self,
+        f: F,
+    ) -> glib::SignalHandlerId {
+        self.connect_changed(Some("network-refresh-speed"), move |settings, _key| {
+            f(RefreshSpeed::from_str(settings.string("network-refresh-speed").as_str()).ok());
+        })
+    }
+
+    /// Returns `None` if batteries and custom gauges should be refreshed as fast as everything else
+    pub fn sensors_refresh_speed(&self) -> Option<RefreshSpeed> {
+        RefreshSpeed::from_str(self.string("sensors-refresh-speed").as_str()).ok()
+    }
+
+    pub fn set_sensors_refresh_speed(
+        &self,
+        value: Option<RefreshSpeed>,
+    ) -> Result<(), glib::error::BoolError> {
+        let value = value.map_or_else(|| "Same".to_string(), |value| value.to_string());
+        debug!("Setting sensors-refresh-speed to {}", value);
+        self.set_string("sensors-refresh-speed", &value)
+    }
+
+    pub fn connect_sensors_refresh_speed<F: Fn(Option<RefreshSpeed>) + 'static>(
+        &self,
+        f: F,
+    ) -> glib::SignalHandlerId {
+        self.connect_changed(Some("sensors-refresh-speed"), move |settings, _key| {
+            f(RefreshSpeed::from_str(settings.string("sensors-refresh-speed").as_str()).ok());
+        })
+    }
+
+    pub fn battery_refresh_speed(&self) -> Option<RefreshSpeed> {
+        RefreshSpeed::from_str(self.string("battery-refresh-speed").as_str()).ok()
+    }
+
+    pub fn set_battery_refresh_speed(
+        &self,
+        value: Option<RefreshSpeed>,
+    ) -> Result<(), glib::error::BoolError> {
+        let value = value.map_or_else(|| "Same".to_string(), |value| value.to_string());
+        debug!("Setting battery-refresh-speed to {}", value);
+        self.set_string("battery-refresh-speed", &value)
+    }
+
+    pub fn connect_battery_refresh_speed<F: Fn(Option<RefreshSpeed>) + 'static>(
+        &self,
+        f: F,
+    ) -> glib::SignalHandlerId {
+        self.connect_changed(Some("battery-refresh-speed"), move |settings, _key| {
+            f(RefreshSpeed::from_str(settings.string("battery-refresh-speed").as_str()).ok());
+        })
+    }
+
+    /// Calls `f` whenever one of the settings that decide which pages are shown changes
+    pub fn connect_page_visibility<F: Fn() + 'static>(&self, f: F) -> glib::SignalHandlerId {
+        self.connect_changed(None, move |_settings, key| {
+            if PAGE_VISIBILITY_KEYS.contains(&key) {
+                f();
+            }
+        })
+    }
+
+    /// Returns the refresh speed that is currently in effect, which depends on whether the system is running on
+    /// battery power. On battery power, the slower one of both refresh speeds is used.
+    pub fn current_refresh_speed(&self, on_battery_power: bool) -> RefreshSpeed {
+        let refresh_speed = self.refresh_speed();
+
+        if !on_battery_power {
+            return refresh_speed;
+        }
+
+        // running on battery power should never make Resources refresh more often
+        match self.battery_refresh_speed() {
+            Some(battery_refresh_speed)
+                if battery_refresh_speed.ui_refresh_interval()
+                    > refresh_speed.ui_refresh_interval() =>
+            {
+                battery_refresh_speed
+            }
+            _ => refresh_speed,
+        }
+    }
+
+    pub fn gpu_temperature_sensor(&self) -> GpuTemperatureSensor {
+        GpuTemperatureSensor::from_str(self.string("gpu-temperature-sensor").as_str())
+            .unwrap_or_default()
+    }
+
+    pub fn set_gpu_temperature_sensor(
+        &self,
+        value: GpuTemperatureSensor,
+    ) -> Result<(), glib::error::BoolError> {
+        debug!("Setting gpu-temperature-sensor to {}", value);
+        self.set_string("gpu-temperature-sensor", &value.to_string())
+    }
+
+    pub fn connect_gpu_temperature_sensor<F: Fn(GpuTemperatureSensor) + 'static>(
+        &self,
+        f: F,
+    ) -> glib::SignalHandlerId {
+        self.connect_changed(Some("gpu-temperature-sensor"), move |settings, _key| {
+            f(
+                GpuTemperatureSensor::from_str(settings.string("gpu-temperature-sensor").as_str())
+                    .unwrap_or_default(),
+            );
+        })
+    }
+
     pub fn sidebar_meter_type(&self) -> SidebarMeterType {
         SidebarMeterType::from_str(self.string("sidebar-meter-type").as_str()).unwrap_or_default()
     }
@@ -253,6 +488,31 @@ impl Settings {
         })
     }
 
+    pub fn processes_name_source(&self) -> ProcessNameSource {
+        ProcessNameSource::from_str(self.string("processes-name-source").as_str())
+            .unwrap_or_default()
+    }
+
+    pub fn set_processes_name_source(
+        &self,
+        value: ProcessNameSource,
+    ) -> Result<(), glib::error::BoolError> {
+        debug!("Setting processes-name-source to {}", value);
+        self.set_string("processes-name-source", &value.to_string())
+    }
+
+    pub fn connect_processes_name_source<F: Fn(ProcessNameSource) + 'static>(
+        &self,
+        f: F,
+    ) -> glib::SignalHandlerId {
+        self.connect_changed(Some("processes-name-source"), move |settings, _key| {
+            f(
+                ProcessNameSource::from_str(settings.string("processes-name-source").as_str())
+                    .unwrap_or_default(),
+            );
+        })
+    }
+
     // the following three functions are kept for compatibility reasons and for not having an oddly named function
     // called "set_is_maximized" generated by the macro
     pub fn maximized(&self) -> bool {
@@ -337,14 +597,262 @@ impl Settings {
         })
     }
 
+    pub fn custom_gauges(&self) -> Vec<CustomGauge> {
+        Self::custom_gauges_from_settings(self)
+    }
+
+    pub fn set_custom_gauges(&self, value: &[CustomGauge]) -> Result<(), glib::error::BoolError> {
+        debug!("Setting custom-gauges to {:?}", value);
+        let gauges: Vec<(String, String, String, f64)> = value
+            .iter()
+            .map(|gauge| {
+                (
+                    gauge.label.clone(),
+                    gauge.path.to_string_lossy().to_string(),
+                    gauge.unit.clone(),
+                    gauge.scale,
+                )
+            })
+            .collect();
+        self.set_value("custom-gauges", &gauges.to_variant())
+    }
+
+    pub fn connect_custom_gauges<F: Fn(Vec<CustomGauge>) + 'static>(
+        &self,
+        f: F,
+    ) -> glib::SignalHandlerId {
+        self.connect_changed(Some("custom-gauges"), move |settings, _key| {
+            f(Self::custom_gauges_from_settings(settings));
+        })
+    }
+
+    fn custom_gauges_from_settings(settings: &gio::Settings) -> Vec<CustomGauge> {
+        settings
+            .value("custom-gauges")
+            .get::<Vec<(String, String, String, f64)>>()
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|(label, path, unit, scale)| {
+                CustomGauge::new(label, path, unit, scale)
+                    .inspect_err(|e| warn!("Ignoring invalid custom gauge: {e}"))
+                    .ok()
+            })
+            .collect()
+    }
+
+    pub fn expanded_sections(&self) -> Vec<String> {
+        self.value("expanded-sections")
+            .get::<Vec<String>>()
+            .unwrap_or_default()
+    }
+
+    /// Adds `section` to or removes it from the sections that are expanded by default
+    pub fn set_section_expanded(
+        &self,
+        section: &str,
+        expanded: bool,
+    ) -> Result<(), glib::error::BoolError> {
+        let mut sections = self.expanded_sections();
+        sections.retain(|expanded_section| expanded_section != section);
+        if expanded {
+            sections.push(section.to_string());
+        }
+
+        debug!("Setting expanded-sections to {:?}", sections);
+        self.set_value("expanded-sections", &sections.to_variant())
+    }
+
+    /// Returns whether the device with the stable ID `device_id` is always shown (`Some(true)`) or always hidden
+    /// (`Some(false)`), `None` means that the other options decide
+    pub fn device_visibility(&self, device_id: &str) -> Option<bool> {
+        self.device_visibilities().get(device_id).copied()
+    }
+
+    pub fn set_device_visibility(
+        &self,
+        device_id: &str,
+        visibility: Option<bool>,
+    ) -> Result<(), glib::error::BoolError> {
+        let mut visibilities = self.device_visibilities();
+        match visibility {
+            Some(visible) => visibilities.insert(device_id.to_string(), visible),
+            None => visibilities.remove(device_id),
+        };
+
+        debug!("Setting device-visibility to {:?}", visibilities);
+        self.set_value("device-visibility", &visibilities.to_variant())
+    }
+
+    fn device_visibilities(&self) -> HashMap<String, bool> {
+        self.value("device-visibility")
+            .get::<HashMap<String, bool>>()
+            .unwrap_or_default()
+    }
+
+    /// Returns the name the user has given to the device with the stable ID `device_id`, if any
+    pub fn device_label(&self, device_id: &str) -> Option<String> {
+        self.device_labels().remove(device_id)
+    }
+
+    /// Sets the name of the device with the stable ID `device_id`, `None` (or an empty label) removes it again
+    pub fn set_device_label(
+        &self,
+        device_id: &str,
+        label: Option<&str>,
+    ) -> Result<(), glib::error::BoolError> {
+        let mut labels = self.device_labels();
+        match label.map(str::trim).filter(|label| !label.is_empty()) {
+            Some(label) => labels.insert(device_id.to_string(), label.to_string()),
+            None => labels.remove(device_id),
+        };
+
+        debug!("Setting device-labels to {:?}", labels);
+        self.set_value("device-labels", &labels.to_variant())
+    }
+
+    pub fn connect_device_labels<F: Fn() + 'static>(&self, f: F) -> glib::SignalHandlerId {
+        self.connect_changed(Some("device-labels"), move |_, _| f())
+    }
+
+    fn device_labels(&self) -> HashMap<String, String> {
+        self.value("device-labels")
+            .get::<HashMap<String, String>>()
+            .unwrap_or_default()
+    }
+
+    /// Serializes all settings that aren't specific to this machine into a versioned text format of `key=value` lines
+    /// with the values in GVariant text format
+    pub fn export(&self) -> String {
+        let mut keys: Vec<String> = self
+            .settings_schema()
+            .map(|schema| schema.list_keys().iter().map(ToString::to_string).collect())
+            .unwrap_or_default();
+        keys.sort();
+
+        let mut export = format!("{SETTINGS_EXPORT_HEADER}\nversion={SETTINGS_EXPORT_VERSION}\n");
+
+        for key in keys
+            .iter()
+            .filter(|key| !MACHINE_SPECIFIC_KEYS.contains(&key.as_str()))
+        {
+            export.push_str(&format!("{key}={}\n", self.value(key).print(false)));
+        }
+
+        export
+    }
+
+    /// Imports settings previously exported using `export()`. Unknown keys and invalid values are skipped, all other
+    /// settings are applied at once.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if `contents` isn't a settings export or if it's of an unsupported version
+    pub fn import<S: AsRef<str>>(&self, contents: S) -> Result<SettingsImport> {
+        let entries = parse_settings_export(contents)?;
+
+        let schema = self
+            .settings_schema()
+            .context("unable to get settings schema")?;
+
+        let mut import = SettingsImport::default();
+        let mut values = Vec::with_capacity(entries.len());
+
+        for (key, value) in entries {
+            if MACHINE_SPECIFIC_KEYS.contains(&key.as_str()) {
+                continue;
+            }
+
+            if !schema.has_key(&key) {
+                warn!("Ignoring unknown setting {key} while importing");
+                import.ignored.push(key);
+                continue;
+            }
+
+            let schema_key = schema.key(&key);
+
+            match glib::Variant::parse(Some(&schema_key.value_type()), &value) {
+                Ok(variant) if schema_key.range_check(&variant) => values.push((key, variant)),
+                _ => {
+                    warn!("Ignoring invalid value {value} for setting {key} while importing");
+                    import.ignored.push(key);
+                }
+            }
+        }
+
+        // apply everything at once so that listeners don't see a half-imported state, this needs its own instance since
+        // there's no leaving delay-apply mode once entered
+        let batch = gio::Settings::new(APP_ID);
+        batch.delay();
+        for (key, variant) in values {
+            if batch.set_value(&key, &variant).is_ok() {
+                import.applied += 1;
+            } else {
+                import.ignored.push(key);
+            }
+        }
+        batch.apply();
+
+        info!(
+            "Imported {} settings, ignored {}",
+            import.applied,
+            import.ignored.len()
+        );
+
+        Ok(import)
+    }
+
     int_settings!(window_width, window_height);
 
-    uint_settings!(graph_data_points, apps_sort_by, processes_sort_by);
+    uint_settings!(
+        graph_data_points,
+        highlight_changes_threshold,
+        apps_sort_by,
+        processes_sort_by,
+        usage_warning_threshold,
+        usage_critical_threshold,
+        cpu_temperature_warning_threshold,
+        cpu_temperature_critical_threshold,
+        gpu_temperature_warning_threshold,
+        gpu_temperature_critical_threshold,
+        gpu_vram_warning_threshold,
+        npu_temperature_warning_threshold,
+        npu_temperature_critical_threshold,
+        drive_temperature_warning_threshold,
+        drive_temperature_critical_threshold,
+        drive_full_threshold,
+        alert_duration,
+        alert_processor_threshold,
+        alert_memory_threshold,
+        alert_drive_temperature_threshold,
+        alert_battery_threshold,
+        network_billing_cycle_day,
+        decimal_precision,
+        commandline_tooltip_length
+    );
 
     bool_settings!(
         show_search_on_start,
+        always_on_top,
+        start_hidden,
+        show_tray_indicator,
         show_virtual_drives,
+        hide_member_drives,
+        show_loop_devices,
+        drive_full_notification,
+        alert_notifications,
         show_virtual_network_interfaces,
+        show_container_network_interfaces,
+        show_network_total,
+        show_cpu_page,
+        show_memory_page,
+        show_gpu_pages,
+        show_npu_pages,
+        show_drive_pages,
+        show_network_pages,
+        show_battery_pages,
+        show_applications_page,
+        show_processes_page,
+        show_os_page,
         sidebar_details,
         sidebar_description,
         network_bits,
@@ -355,10 +863,15 @@ impl Settings {
         apps_show_drive_write_speed,
         apps_show_drive_write_total,
         apps_show_gpu,
+        apps_show_usage_per_gpu,
         apps_show_gpu_memory,
         apps_show_encoder,
         apps_show_decoder,
         apps_show_swap,
+        apps_show_autostart,
+        apps_show_rendering_gpu,
+        apps_show_storage,
+        apps_group_by_category,
         processes_show_id,
         processes_show_user,
         processes_show_memory,
@@ -368,6 +881,7 @@ impl Settings {
         processes_show_drive_write_speed,
         processes_show_drive_write_total,
         processes_show_gpu,
+        processes_show_usage_per_gpu,
         processes_show_gpu_memory,
         processes_show_encoder,
         processes_show_decoder,
@@ -376,9 +890,22 @@ impl Settings {
         processes_show_system_cpu_time,
         processes_show_priority,
         processes_show_swap,
+        processes_show_shared_memory,
+        processes_show_locked_memory,
+        processes_show_pinned_memory,
+        processes_show_cpu_throttling,
+        processes_show_graphs,
+        processes_show_tree_depth,
+        processes_group_by_container,
+        processes_show_full_path,
+        processes_show_drm_fdinfo,
         show_logical_cpus,
         show_graph_grids,
+        record_history,
         normalize_cpu_usage,
+        confirm_end_action,
+        highlight_changes,
+        merge_media_columns,
         detailed_priority
     );
 }
@@ -399,3 +926,73 @@ impl Default for Settings {
 
 unsafe impl Send for Settings {}
 unsafe impl Sync for Settings {}
+
+/// Splits a settings export into its `(key, value)` pairs after checking its version
+fn parse_settings_export<S: AsRef<str>>(contents: S) -> Result<Vec<(String, String)>> {
+    let mut lines = contents
+        .as_ref()
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'));
+
+    let version = lines
+        .next()
+        .and_then(|line| line.strip_prefix("version="))
+        .context("not a settings export, version is missing")?
+        .parse::<u32>()
+        .context("unable to parse settings export version")?;
+
+    if version > SETTINGS_EXPORT_VERSION {
+        bail!("unsupported settings export version {version}");
+    }
+
+    lines
+        .map(|line| {
+            line.split_once('=')
+                .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+                .with_context(|| format!("invalid line in settings export: {line}"))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::parse_settings_export;
+
+    #[test]
+    fn parse_settings_export_valid() {
+        let export = concat!(
+            "# Resources settings\n",
+            "version=1\n",
+            "refresh-speed='Fast'\n",
+            "\n",
+            "custom-gauges=[('Fan', '/sys/class/hwmon/hwmon2/fan1_input', 'RPM', 1.0)]\n",
+            "processes-show-id=true\n",
+        );
+
+        assert_eq!(
+            vec![
+                ("refresh-speed".to_string(), "'Fast'".to_string()),
+                (
+                    "custom-gauges".to_string(),
+                    "[('Fan', '/sys/class/hwmon/hwmon2/fan1_input', 'RPM', 1.0)]".to_string()
+                ),
+                ("processes-show-id".to_string(), "true".to_string()),
+            ],
+            parse_settings_export(export).unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_settings_export_newer_version() {
+        assert!(parse_settings_export("version=2\nrefresh-speed='Fast'\n").is_err());
+    }
+
+    #[test]
+    fn parse_settings_export_without_version() {
+        assert!(parse_settings_export("refresh-speed='Fast'\n").is_err());
+        assert!(parse_settings_export("").is_err());
+    }
+}
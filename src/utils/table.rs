@@ -0,0 +1,369 @@
+use std::{cell::RefCell, path::Path, rc::Rc, time::Duration};
+
+use anyhow::{Context, Result};
+use gtk::{
+    gio,
+    glib::{self, clone, Object, WeakRef},
+    prelude::*,
+    ColumnView, ColumnViewColumn, ListItem,
+};
+
+use crate::i18n::i18n;
+
+use super::{
+    json_output::{format_object, json_string},
+    settings::SETTINGS,
+};
+
+/// How long a changed cell stays highlighted
+const HIGHLIGHT_DURATION: Duration = Duration::from_millis(1000);
+
+/// How the change of a cell's value is measured when deciding whether it's significant enough to be highlighted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeScale {
+    /// The value is a fraction between 0 and 1, changes are measured in percentage points
+    Fraction,
+    /// Changes are measured in percent of the larger of both values
+    Relative,
+}
+
+impl ChangeScale {
+    /// Checks whether the change from `old` to `new` reaches `threshold` (in percent or percentage points)
+    pub fn is_significant(self, old: f64, new: f64, threshold: u32) -> bool {
+        if old == new || !old.is_finite() || !new.is_finite() {
+            return false;
+        }
+
+        let change = match self {
+            ChangeScale::Fraction => (new - old).abs(),
+            ChangeScale::Relative => (new - old).abs() / f64::max(old.abs(), new.abs()),
+        };
+
+        change * 100.0 >= f64::from(threshold)
+    }
+}
+
+/// Joins `cells` into a single line of tab-separated values. Tabs and line breaks within a cell are replaced by
+/// spaces so that they can't be mistaken for column or row separators.
+pub fn tsv_line<I, S>(cells: I) -> String
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    cells
+        .into_iter()
+        .map(|cell| cell.as_ref().replace(['\t', '\n', '\r'], " "))
+        .collect::<Vec<_>>()
+        .join("\t")
+}
+
+/// The file formats that tables can be exported to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    /// Determines the format by the extension of `path`, anything but `.json` is exported as CSV
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension() {
+            Some(extension) if extension.eq_ignore_ascii_case("json") => ExportFormat::Json,
+            _ => ExportFormat::Csv,
+        }
+    }
+
+    /// Formats `rows`, which contain one cell for each of `columns` given as `(id, title)`. CSV starts with a line of
+    /// the column titles, JSON is an array of one object per row with the column IDs as keys.
+    pub fn format(self, columns: &[(String, String)], rows: &[Vec<String>]) -> String {
+        match self {
+            ExportFormat::Csv => std::iter::once(csv_line(columns.iter().map(|(_, title)| title)))
+                .chain(rows.iter().map(csv_line))
+                .map(|line| line + "\r\n")
+                .collect(),
+            ExportFormat::Json => {
+                let objects = rows
+                    .iter()
+                    .map(|row| {
+                        format_object(
+                            columns
+                                .iter()
+                                .zip(row)
+                                .map(|((id, _), cell)| (id.as_str(), json_string(cell))),
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(",\n");
+
+                format!("[\n{objects}\n]\n")
+            }
+        }
+    }
+}
+
+/// Joins `cells` into a single line of comma-separated values, quoting cells that contain commas, quotes or line breaks
+pub fn csv_line<I, S>(cells: I) -> String
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    cells
+        .into_iter()
+        .map(|cell| {
+            let cell = cell.as_ref();
+            if cell.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", cell.replace('"', "\"\""))
+            } else {
+                cell.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Lets the user choose a file and exports the visible columns of `column_view` for all entries of `model` to it, in
+/// the order they're listed in and formatted like they're displayed. `column_text` returns the text of the column
+/// with the given ID for an entry. Returns `false` if the user has cancelled.
+pub async fn export_table<T: IsA<Object>>(
+    parent: &impl IsA<gtk::Widget>,
+    initial_name: &str,
+    column_view: &ColumnView,
+    model: &impl IsA<gio::ListModel>,
+    column_text: impl Fn(&T, &str) -> String,
+) -> Result<bool> {
+    let filters = gio::ListStore::new::<gtk::FileFilter>();
+
+    let csv_filter = gtk::FileFilter::new();
+    csv_filter.set_name(Some(&i18n("CSV")));
+    csv_filter.add_suffix("csv");
+    filters.append(&csv_filter);
+
+    let json_filter = gtk::FileFilter::new();
+    json_filter.set_name(Some(&i18n("JSON")));
+    json_filter.add_suffix("json");
+    filters.append(&json_filter);
+
+    let file_dialog = gtk::FileDialog::builder()
+        .title(i18n("Export"))
+        .initial_name(initial_name)
+        .filters(&filters)
+        .build();
+
+    let Ok(file) = file_dialog
+        .save_future(parent.root().and_downcast_ref::<gtk::Window>())
+        .await
+    else {
+        return Ok(false);
+    };
+
+    let format = file
+        .path()
+        .map_or(ExportFormat::Csv, |path| ExportFormat::from_path(&path));
+
+    let columns = visible_columns(column_view)
+        .iter()
+        .map(|column| {
+            (
+                column.id().unwrap_or_default().to_string(),
+                column.title().unwrap_or_default().to_string(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let rows = model
+        .iter::<T>()
+        .flatten()
+        .map(|entry| {
+            columns
+                .iter()
+                .map(|(id, _)| column_text(&entry, id))
+                .collect()
+        })
+        .collect::<Vec<_>>();
+
+    file.replace_contents_future(
+        format.format(&columns, &rows),
+        None,
+        false,
+        gio::FileCreateFlags::REPLACE_DESTINATION,
+    )
+    .await
+    .map_err(|(_, e)| e)
+    .context("unable to write table export")?;
+
+    Ok(true)
+}
+
+/// Returns the visible columns of `column_view` in the order they're currently displayed in
+pub fn visible_columns(column_view: &ColumnView) -> Vec<ColumnViewColumn> {
+    column_view
+        .columns()
+        .iter::<ColumnViewColumn>()
+        .flatten()
+        .filter(|column| column.is_visible())
+        .collect()
+}
+
+/// Builds the header menu of `column`, which allows copying the whole column. `action_name` is activated with the
+/// ID of the column as its string target.
+pub fn copy_column_menu(action_name: &str, column: &ColumnViewColumn) -> gio::Menu {
+    let menu = gio::Menu::new();
+
+    let item = gio::MenuItem::new(Some(&i18n("Copy Column")), None);
+    item.set_action_and_target_value(
+        Some(action_name),
+        Some(&column.id().unwrap_or_default().as_str().to_variant()),
+    );
+    menu.append_item(&item);
+
+    menu
+}
+
+/// Briefly highlights `widget` whenever the `property` of the entry of type `T` that is shown by `item` changes
+/// significantly, as long as that is enabled in the settings. Values of recycled cells that start showing a
+/// different entry aren't compared to the ones of the previous entry.
+pub fn highlight_changes<T: StaticType>(
+    item: &ListItem,
+    widget: &impl IsA<gtk::Widget>,
+    property: &str,
+    scale: ChangeScale,
+) {
+    let expression = gtk::PropertyExpression::new(
+        T::static_type(),
+        Some(item.property_expression("item")),
+        property,
+    );
+
+    let widget = widget.clone().upcast::<gtk::Widget>();
+
+    #[allow(clippy::type_complexity)]
+    let last_value: Rc<RefCell<Option<(WeakRef<Object>, f64)>>> = Rc::default();
+
+    expression.watch(
+        Some(item),
+        clone!(
+            #[weak]
+            item,
+            #[weak]
+            widget,
+            #[strong]
+            expression,
+            move || {
+                let entry = item.item();
+                let value = expression
+                    .evaluate(Some(&item))
+                    .and_then(|value| value.transform::<f64>().ok())
+                    .and_then(|value| value.get::<f64>().ok());
+
+                let mut last_value = last_value.borrow_mut();
+
+                if let (Some((last_entry, old)), Some(entry), Some(new)) =
+                    (last_value.as_ref(), entry.as_ref(), value)
+                {
+                    if SETTINGS.highlight_changes()
+                        && last_entry.upgrade().as_ref() == Some(entry)
+                        && scale.is_significant(*old, new, SETTINGS.highlight_changes_threshold())
+                    {
+                        widget.add_css_class("value-changed");
+                        glib::timeout_add_local_once(
+                            HIGHLIGHT_DURATION,
+                            clone!(
+                                #[weak]
+                                widget,
+                                move || widget.remove_css_class("value-changed")
+                            ),
+                        );
+                    }
+                }
+
+                *last_value = entry
+                    .zip(value)
+                    .map(|(entry, value)| (entry.downgrade(), value));
+            }
+        ),
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use pretty_assertions::assert_eq;
+
+    use super::{csv_line, tsv_line, ChangeScale, ExportFormat};
+
+    #[test]
+    fn tsv_line_simple() {
+        assert_eq!(
+            "firefox\t1234\t1.2 GiB",
+            tsv_line(["firefox", "1234", "1.2 GiB"])
+        );
+    }
+
+    #[test]
+    fn tsv_line_separators_in_cells() {
+        assert_eq!("a b\tc d\te  f", tsv_line(["a\tb", "c\nd", "e\r\nf"]));
+        assert_eq!("", tsv_line(Vec::<String>::new()));
+    }
+
+    #[test]
+    fn csv_line_quoting() {
+        assert_eq!(
+            "firefox,\"1,2 GiB\",\"say \"\"hi\"\"\",\"a\nb\"",
+            csv_line(["firefox", "1,2 GiB", "say \"hi\"", "a\nb"])
+        );
+    }
+
+    #[test]
+    fn export_formats() {
+        let columns = [
+            ("name".to_string(), "Process".to_string()),
+            ("memory".to_string(), "Memory".to_string()),
+        ];
+        let rows = [
+            vec!["firefox".to_string(), "1.2 GiB".to_string()],
+            vec!["bash".to_string(), "4.0 MiB".to_string()],
+        ];
+
+        assert_eq!(
+            "Process,Memory\r\nfirefox,1.2 GiB\r\nbash,4.0 MiB\r\n",
+            ExportFormat::Csv.format(&columns, &rows)
+        );
+        assert_eq!(
+            "[\n{\"name\":\"firefox\",\"memory\":\"1.2 GiB\"},\n{\"name\":\"bash\",\"memory\":\"4.0 MiB\"}\n]\n",
+            ExportFormat::Json.format(&columns, &rows)
+        );
+    }
+
+    #[test]
+    fn export_format_from_path() {
+        assert_eq!(
+            ExportFormat::Json,
+            ExportFormat::from_path(Path::new("processes.JSON"))
+        );
+        assert_eq!(
+            ExportFormat::Csv,
+            ExportFormat::from_path(Path::new("processes.csv"))
+        );
+        assert_eq!(
+            ExportFormat::Csv,
+            ExportFormat::from_path(Path::new("processes"))
+        );
+    }
+
+    #[test]
+    fn significant_fraction_change() {
+        assert!(ChangeScale::Fraction.is_significant(0.05, 0.5, 10));
+        assert!(ChangeScale::Fraction.is_significant(0.5, 0.35, 10));
+        assert!(!ChangeScale::Fraction.is_significant(0.001, 0.003, 10));
+        assert!(!ChangeScale::Fraction.is_significant(0.3, 0.3, 0));
+    }
+
+    #[test]
+    fn significant_relative_change() {
+        assert!(ChangeScale::Relative.is_significant(100.0, 200.0, 10));
+        assert!(ChangeScale::Relative.is_significant(0.0, 4096.0, 10));
+        assert!(!ChangeScale::Relative.is_significant(1000.0, 1050.0, 10));
+        assert!(!ChangeScale::Relative.is_significant(f64::NAN, 1.0, 0));
+    }
+}
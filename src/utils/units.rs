@@ -5,6 +5,9 @@ use crate::i18n::i18n_f;
 
 use super::settings::{Base, TemperatureUnit, SETTINGS};
 
+/// The highest amount of decimal places the user can choose for percentages and sizes
+pub const MAX_DECIMAL_PRECISION: u32 = 2;
+
 #[repr(u8)]
 #[derive(
     Debug, Clone, Copy, Default, EnumString, Display, Hash, EnumIter, PartialEq, PartialOrd, Eq, Ord,
@@ -83,165 +86,175 @@ pub fn convert_temperature(celsius: f64) -> String {
     }
 }
 
+/// Returns the precision (the amount of decimal places) that has been chosen by the user for percentages and sizes
+fn decimal_precision() -> usize {
+    SETTINGS.decimal_precision().min(MAX_DECIMAL_PRECISION) as usize
+}
+
+/// Formats `number` with `precision` decimal places, rounding it to an integer if `precision` is 0
+fn format_number(number: f64, precision: usize) -> String {
+    if precision == 0 {
+        number.round().to_string()
+    } else {
+        format!("{number:.precision$}")
+    }
+}
+
+/// Formats a percentage (i.e. a value between 0 and 100, not a fraction) with the user's chosen decimal precision
+pub fn format_percentage<F: Into<f64>>(percentage: F) -> String {
+    format_percentage_with_precision(percentage.into(), decimal_precision())
+}
+
+fn format_percentage_with_precision(percentage: f64, precision: usize) -> String {
+    format!("{} %", format_number(percentage, precision))
+}
+
 pub fn convert_storage(bytes: f64, integer: bool) -> String {
+    let precision = if integer { 0 } else { decimal_precision() };
+
     match SETTINGS.base() {
-        Base::Decimal => convert_storage_decimal(bytes, integer),
-        Base::Binary => convert_storage_binary(bytes, integer),
+        Base::Decimal => convert_storage_decimal(bytes, precision),
+        Base::Binary => convert_storage_binary(bytes, precision),
     }
 }
 
-fn convert_storage_decimal(bytes: f64, integer: bool) -> String {
-    let (mut number, prefix) = to_largest_prefix(bytes, Base::Decimal);
-    if integer {
-        number = number.round();
-        match prefix {
-            Prefix::None => i18n_f("{} B", &[&number.to_string()]),
-            Prefix::Kilo => i18n_f("{} kB", &[&number.to_string()]),
-            Prefix::Mega => i18n_f("{} MB", &[&number.to_string()]),
-            Prefix::Giga => i18n_f("{} GB", &[&number.to_string()]),
-            Prefix::Tera => i18n_f("{} TB", &[&number.to_string()]),
-            Prefix::Peta => i18n_f("{} PB", &[&number.to_string()]),
-            Prefix::Exa => i18n_f("{} EB", &[&number.to_string()]),
-            Prefix::Zetta => i18n_f("{} ZB", &[&number.to_string()]),
-            Prefix::Yotta => i18n_f("{} YB", &[&number.to_string()]),
-            Prefix::Ronna => i18n_f("{} RB", &[&number.to_string()]),
-            Prefix::Quetta => i18n_f("{} QB", &[&number.to_string()]),
-        }
-    } else {
-        match prefix {
-            Prefix::None => i18n_f("{} B", &[&format!("{}", number.round())]),
-            Prefix::Kilo => i18n_f("{} kB", &[&format!("{number:.2}")]),
-            Prefix::Mega => i18n_f("{} MB", &[&format!("{number:.2}")]),
-            Prefix::Giga => i18n_f("{} GB", &[&format!("{number:.2}")]),
-            Prefix::Tera => i18n_f("{} TB", &[&format!("{number:.2}")]),
-            Prefix::Peta => i18n_f("{} PB", &[&format!("{number:.2}")]),
-            Prefix::Exa => i18n_f("{} EB", &[&format!("{number:.2}")]),
-            Prefix::Zetta => i18n_f("{} ZB", &[&format!("{number:.2}")]),
-            Prefix::Yotta => i18n_f("{} YB", &[&format!("{number:.2}")]),
-            Prefix::Ronna => i18n_f("{} RB", &[&format!("{number:.2}")]),
-            Prefix::Quetta => i18n_f("{} QB", &[&format!("{number:.2}")]),
-        }
+fn convert_storage_decimal(bytes: f64, precision: usize) -> String {
+    let (number, prefix) = to_largest_prefix(bytes, Base::Decimal);
+    match prefix {
+        Prefix::None => i18n_f("{} B", &[&number.round().to_string()]),
+        Prefix::Kilo => i18n_f("{} kB", &[&format_number(number, precision)]),
+        Prefix::Mega => i18n_f("{} MB", &[&format_number(number, precision)]),
+        Prefix::Giga => i18n_f("{} GB", &[&format_number(number, precision)]),
+        Prefix::Tera => i18n_f("{} TB", &[&format_number(number, precision)]),
+        Prefix::Peta => i18n_f("{} PB", &[&format_number(number, precision)]),
+        Prefix::Exa => i18n_f("{} EB", &[&format_number(number, precision)]),
+        Prefix::Zetta => i18n_f("{} ZB", &[&format_number(number, precision)]),
+        Prefix::Yotta => i18n_f("{} YB", &[&format_number(number, precision)]),
+        Prefix::Ronna => i18n_f("{} RB", &[&format_number(number, precision)]),
+        Prefix::Quetta => i18n_f("{} QB", &[&format_number(number, precision)]),
     }
 }
 
-fn convert_storage_binary(bytes: f64, integer: bool) -> String {
-    let (mut number, prefix) = to_largest_prefix(bytes, Base::Binary);
-    if integer {
-        number = number.round();
-        match prefix {
-            Prefix::None => i18n_f("{} B", &[&number.to_string()]),
-            Prefix::Kilo => i18n_f("{} KiB", &[&number.to_string()]),
-            Prefix::Mega => i18n_f("{} MiB", &[&number.to_string()]),
-            Prefix::Giga => i18n_f("{} GiB", &[&number.to_string()]),
-            Prefix::Tera => i18n_f("{} TiB", &[&number.to_string()]),
-            Prefix::Peta => i18n_f("{} PiB", &[&number.to_string()]),
-            Prefix::Exa => i18n_f("{} EiB", &[&number.to_string()]),
-            Prefix::Zetta => i18n_f("{} ZiB", &[&number.to_string()]),
-            Prefix::Yotta => i18n_f("{} YiB", &[&number.to_string()]),
-            Prefix::Ronna => i18n_f("{} RiB", &[&number.to_string()]),
-            Prefix::Quetta => i18n_f("{} QiB", &[&number.to_string()]),
-        }
-    } else {
-        match prefix {
-            Prefix::None => i18n_f("{} B", &[&format!("{}", number.round())]),
-            Prefix::Kilo => i18n_f("{} KiB", &[&format!("{number:.2}")]),
-            Prefix::Mega => i18n_f("{} MiB", &[&format!("{number:.2}")]),
-            Prefix::Giga => i18n_f("{} GiB", &[&format!("{number:.2}")]),
-            Prefix::Tera => i18n_f("{} TiB", &[&format!("{number:.2}")]),
-            Prefix::Peta => i18n_f("{} PiB", &[&format!("{number:.2}")]),
-            Prefix::Exa => i18n_f("{} EiB", &[&format!("{number:.2}")]),
-            Prefix::Zetta => i18n_f("{} ZiB", &[&format!("{number:.2}")]),
-            Prefix::Yotta => i18n_f("{} YiB", &[&format!("{number:.2}")]),
-            Prefix::Ronna => i18n_f("{} RiB", &[&format!("{number:.2}")]),
-            Prefix::Quetta => i18n_f("{} QiB", &[&format!("{number:.2}")]),
-        }
+fn convert_storage_binary(bytes: f64, precision: usize) -> String {
+    let (number, prefix) = to_largest_prefix(bytes, Base::Binary);
+    match prefix {
+        Prefix::None => i18n_f("{} B", &[&number.round().to_string()]),
+        Prefix::Kilo => i18n_f("{} KiB", &[&format_number(number, precision)]),
+        Prefix::Mega => i18n_f("{} MiB", &[&format_number(number, precision)]),
+        Prefix::Giga => i18n_f("{} GiB", &[&format_number(number, precision)]),
+        Prefix::Tera => i18n_f("{} TiB", &[&format_number(number, precision)]),
+        Prefix::Peta => i18n_f("{} PiB", &[&format_number(number, precision)]),
+        Prefix::Exa => i18n_f("{} EiB", &[&format_number(number, precision)]),
+        Prefix::Zetta => i18n_f("{} ZiB", &[&format_number(number, precision)]),
+        Prefix::Yotta => i18n_f("{} YiB", &[&format_number(number, precision)]),
+        Prefix::Ronna => i18n_f("{} RiB", &[&format_number(number, precision)]),
+        Prefix::Quetta => i18n_f("{} QiB", &[&format_number(number, precision)]),
     }
 }
 
 pub fn convert_speed(bytes_per_second: f64, network: bool) -> String {
+    let precision = decimal_precision();
+
     match SETTINGS.base() {
         Base::Decimal => {
             if network && SETTINGS.network_bits() {
-                convert_speed_bits_decimal(bytes_per_second * 8.0)
+                convert_speed_bits_decimal(bytes_per_second * 8.0, precision)
             } else {
-                convert_speed_decimal(bytes_per_second)
+                convert_speed_decimal(bytes_per_second, precision)
             }
         }
         Base::Binary => {
             if network && SETTINGS.network_bits() {
-                convert_speed_bits_binary(bytes_per_second * 8.0)
+                convert_speed_bits_binary(bytes_per_second * 8.0, precision)
             } else {
-                convert_speed_binary(bytes_per_second)
+                convert_speed_binary(bytes_per_second, precision)
             }
         }
     }
 }
 
-fn convert_speed_decimal(bytes_per_second: f64) -> String {
+/// Formats the speed of a network link, these are conventionally given in decimal bits per second, so the user's
+/// settings aren't taken into account
+pub fn format_link_speed(megabits_per_second: f64) -> String {
+    // link speeds are mostly round numbers like 2.5 Gb/s, so decimal places are only shown when needed
+    if megabits_per_second >= 1000.0 {
+        i18n_f(
+            "{} Gb/s",
+            &[&((megabits_per_second / 1000.0 * 100.0).round() / 100.0).to_string()],
+        )
+    } else {
+        i18n_f(
+            "{} Mb/s",
+            &[&((megabits_per_second * 10.0).round() / 10.0).to_string()],
+        )
+    }
+}
+
+fn convert_speed_decimal(bytes_per_second: f64, precision: usize) -> String {
     let (number, prefix) = to_largest_prefix(bytes_per_second, Base::Decimal);
     match prefix {
-        Prefix::None => i18n_f("{} B/s", &[&format!("{}", number.round())]),
-        Prefix::Kilo => i18n_f("{} kB/s", &[&format!("{number:.2}")]),
-        Prefix::Mega => i18n_f("{} MB/s", &[&format!("{number:.2}")]),
-        Prefix::Giga => i18n_f("{} GB/s", &[&format!("{number:.2}")]),
-        Prefix::Tera => i18n_f("{} TB/s", &[&format!("{number:.2}")]),
-        Prefix::Peta => i18n_f("{} PB/s", &[&format!("{number:.2}")]),
-        Prefix::Exa => i18n_f("{} EB/s", &[&format!("{number:.2}")]),
-        Prefix::Zetta => i18n_f("{} ZB/s", &[&format!("{number:.2}")]),
-        Prefix::Yotta => i18n_f("{} YB/s", &[&format!("{number:.2}")]),
-        Prefix::Ronna => i18n_f("{} RB/s", &[&format!("{number:.2}")]),
-        Prefix::Quetta => i18n_f("{} QB/s", &[&format!("{number:.2}")]),
+        Prefix::None => i18n_f("{} B/s", &[&number.round().to_string()]),
+        Prefix::Kilo => i18n_f("{} kB/s", &[&format_number(number, precision)]),
+        Prefix::Mega => i18n_f("{} MB/s", &[&format_number(number, precision)]),
+        Prefix::Giga => i18n_f("{} GB/s", &[&format_number(number, precision)]),
+        Prefix::Tera => i18n_f("{} TB/s", &[&format_number(number, precision)]),
+        Prefix::Peta => i18n_f("{} PB/s", &[&format_number(number, precision)]),
+        Prefix::Exa => i18n_f("{} EB/s", &[&format_number(number, precision)]),
+        Prefix::Zetta => i18n_f("{} ZB/s", &[&format_number(number, precision)]),
+        Prefix::Yotta => i18n_f("{} YB/s", &[&format_number(number, precision)]),
+        Prefix::Ronna => i18n_f("{} RB/s", &[&format_number(number, precision)]),
+        Prefix::Quetta => i18n_f("{} QB/s", &[&format_number(number, precision)]),
     }
 }
 
-fn convert_speed_binary(bytes_per_second: f64) -> String {
+fn convert_speed_binary(bytes_per_second: f64, precision: usize) -> String {
     let (number, prefix) = to_largest_prefix(bytes_per_second, Base::Binary);
     match prefix {
-        Prefix::None => i18n_f("{} B/s", &[&format!("{}", number.round())]),
-        Prefix::Kilo => i18n_f("{} KiB/s", &[&format!("{number:.2}")]),
-        Prefix::Mega => i18n_f("{} MiB/s", &[&format!("{number:.2}")]),
-        Prefix::Giga => i18n_f("{} GiB/s", &[&format!("{number:.2}")]),
-        Prefix::Tera => i18n_f("{} TiB/s", &[&format!("{number:.2}")]),
-        Prefix::Peta => i18n_f("{} PiB/s", &[&format!("{number:.2}")]),
-        Prefix::Exa => i18n_f("{} EiB/s", &[&format!("{number:.2}")]),
-        Prefix::Zetta => i18n_f("{} ZiB/s", &[&format!("{number:.2}")]),
-        Prefix::Yotta => i18n_f("{} YiB/s", &[&format!("{number:.2}")]),
-        Prefix::Ronna => i18n_f("{} RiB/s", &[&format!("{number:.2}")]),
-        Prefix::Quetta => i18n_f("{} QiB/s", &[&format!("{number:.2}")]),
+        Prefix::None => i18n_f("{} B/s", &[&number.round().to_string()]),
+        Prefix::Kilo => i18n_f("{} KiB/s", &[&format_number(number, precision)]),
+        Prefix::Mega => i18n_f("{} MiB/s", &[&format_number(number, precision)]),
+        Prefix::Giga => i18n_f("{} GiB/s", &[&format_number(number, precision)]),
+        Prefix::Tera => i18n_f("{} TiB/s", &[&format_number(number, precision)]),
+        Prefix::Peta => i18n_f("{} PiB/s", &[&format_number(number, precision)]),
+        Prefix::Exa => i18n_f("{} EiB/s", &[&format_number(number, precision)]),
+        Prefix::Zetta => i18n_f("{} ZiB/s", &[&format_number(number, precision)]),
+        Prefix::Yotta => i18n_f("{} YiB/s", &[&format_number(number, precision)]),
+        Prefix::Ronna => i18n_f("{} RiB/s", &[&format_number(number, precision)]),
+        Prefix::Quetta => i18n_f("{} QiB/s", &[&format_number(number, precision)]),
     }
 }
 
-fn convert_speed_bits_decimal(bits_per_second: f64) -> String {
+fn convert_speed_bits_decimal(bits_per_second: f64, precision: usize) -> String {
     let (number, prefix) = to_largest_prefix(bits_per_second, Base::Decimal);
     match prefix {
-        Prefix::None => i18n_f("{} b/s", &[&format!("{}", number.round())]),
-        Prefix::Kilo => i18n_f("{} kb/s", &[&format!("{number:.2}")]),
-        Prefix::Mega => i18n_f("{} Mb/s", &[&format!("{number:.2}")]),
-        Prefix::Giga => i18n_f("{} Gb/s", &[&format!("{number:.2}")]),
-        Prefix::Tera => i18n_f("{} Tb/s", &[&format!("{number:.2}")]),
-        Prefix::Peta => i18n_f("{} Pb/s", &[&format!("{number:.2}")]),
-        Prefix::Exa => i18n_f("{} Eb/s", &[&format!("{number:.2}")]),
-        Prefix::Zetta => i18n_f("{} Zb/s", &[&format!("{number:.2}")]),
-        Prefix::Yotta => i18n_f("{} Yb/s", &[&format!("{number:.2}")]),
-        Prefix::Ronna => i18n_f("{} Rb/s", &[&format!("{number:.2}")]),
-        Prefix::Quetta => i18n_f("{} Qb/s", &[&format!("{number:.2}")]),
+        Prefix::None => i18n_f("{} b/s", &[&number.round().to_string()]),
+        Prefix::Kilo => i18n_f("{} kb/s", &[&format_number(number, precision)]),
+        Prefix::Mega => i18n_f("{} Mb/s", &[&format_number(number, precision)]),
+        Prefix::Giga => i18n_f("{} Gb/s", &[&format_number(number, precision)]),
+        Prefix::Tera => i18n_f("{} Tb/s", &[&format_number(number, precision)]),
+        Prefix::Peta => i18n_f("{} Pb/s", &[&format_number(number, precision)]),
+        Prefix::Exa => i18n_f("{} Eb/s", &[&format_number(number, precision)]),
+        Prefix::Zetta => i18n_f("{} Zb/s", &[&format_number(number, precision)]),
+        Prefix::Yotta => i18n_f("{} Yb/s", &[&format_number(number, precision)]),
+        Prefix::Ronna => i18n_f("{} Rb/s", &[&format_number(number, precision)]),
+        Prefix::Quetta => i18n_f("{} Qb/s", &[&format_number(number, precision)]),
     }
 }
 
-fn convert_speed_bits_binary(bits_per_second: f64) -> String {
+fn convert_speed_bits_binary(bits_per_second: f64, precision: usize) -> String {
     let (number, prefix) = to_largest_prefix(bits_per_second, Base::Binary);
     match prefix {
-        Prefix::None => i18n_f("{} b/s", &[&format!("{}", number.round())]),
-        Prefix::Kilo => i18n_f("{} Kib/s", &[&format!("{number:.2}")]),
-        Prefix::Mega => i18n_f("{} Mib/s", &[&format!("{number:.2}")]),
-        Prefix::Giga => i18n_f("{} Gib/s", &[&format!("{number:.2}")]),
-        Prefix::Tera => i18n_f("{} Tib/s", &[&format!("{number:.2}")]),
-        Prefix::Peta => i18n_f("{} Pib/s", &[&format!("{number:.2}")]),
-        Prefix::Exa => i18n_f("{} Eib/s", &[&format!("{number:.2}")]),
-        Prefix::Zetta => i18n_f("{} Zib/s", &[&format!("{number:.2}")]),
-        Prefix::Yotta => i18n_f("{} Yib/s", &[&format!("{number:.2}")]),
-        Prefix::Ronna => i18n_f("{} Rib/s", &[&format!("{number:.2}")]),
-        Prefix::Quetta => i18n_f("{} Qib/s", &[&format!("{number:.2}")]),
+        Prefix::None => i18n_f("{} b/s", &[&number.round().to_string()]),
+        Prefix::Kilo => i18n_f("{} Kib/s", &[&format_number(number, precision)]),
+        Prefix::Mega => i18n_f("{} Mib/s", &[&format_number(number, precision)]),
+        Prefix::Giga => i18n_f("{} Gib/s", &[&format_number(number, precision)]),
+        Prefix::Tera => i18n_f("{} Tib/s", &[&format_number(number, precision)]),
+        Prefix::Peta => i18n_f("{} Pib/s", &[&format_number(number, precision)]),
+        Prefix::Exa => i18n_f("{} Eib/s", &[&format_number(number, precision)]),
+        Prefix::Zetta => i18n_f("{} Zib/s", &[&format_number(number, precision)]),
+        Prefix::Yotta => i18n_f("{} Yib/s", &[&format_number(number, precision)]),
+        Prefix::Ronna => i18n_f("{} Rib/s", &[&format_number(number, precision)]),
+        Prefix::Quetta => i18n_f("{} Qib/s", &[&format_number(number, precision)]),
     }
 }
 
@@ -279,6 +292,40 @@ pub fn convert_power(watts: f64) -> String {
     }
 }
 
+pub fn convert_voltage(volts: f64) -> String {
+    let (number, prefix) = to_largest_prefix(volts, Base::Decimal);
+    match prefix {
+        Prefix::None => i18n_f("{} V", &[&format!("{number:.2}")]),
+        Prefix::Kilo => i18n_f("{} kV", &[&format!("{number:.2}")]),
+        Prefix::Mega => i18n_f("{} MV", &[&format!("{number:.2}")]),
+        Prefix::Giga => i18n_f("{} GV", &[&format!("{number:.2}")]),
+        Prefix::Tera => i18n_f("{} TV", &[&format!("{number:.2}")]),
+        Prefix::Peta => i18n_f("{} PV", &[&format!("{number:.2}")]),
+        Prefix::Exa => i18n_f("{} EV", &[&format!("{number:.2}")]),
+        Prefix::Zetta => i18n_f("{} ZV", &[&format!("{number:.2}")]),
+        Prefix::Yotta => i18n_f("{} YV", &[&format!("{number:.2}")]),
+        Prefix::Ronna => i18n_f("{} RV", &[&format!("{number:.2}")]),
+        Prefix::Quetta => i18n_f("{} QV", &[&format!("{number:.2}")]),
+    }
+}
+
+pub fn convert_current(amperes: f64) -> String {
+    let (number, prefix) = to_largest_prefix(amperes, Base::Decimal);
+    match prefix {
+        Prefix::None => i18n_f("{} A", &[&format!("{number:.2}")]),
+        Prefix::Kilo => i18n_f("{} kA", &[&format!("{number:.2}")]),
+        Prefix::Mega => i18n_f("{} MA", &[&format!("{number:.2}")]),
+        Prefix::Giga => i18n_f("{} GA", &[&format!("{number:.2}")]),
+        Prefix::Tera => i18n_f("{} TA", &[&format!("{number:.2}")]),
+        Prefix::Peta => i18n_f("{} PA", &[&format!("{number:.2}")]),
+        Prefix::Exa => i18n_f("{} EA", &[&format!("{number:.2}")]),
+        Prefix::Zetta => i18n_f("{} ZA", &[&format!("{number:.2}")]),
+        Prefix::Yotta => i18n_f("{} YA", &[&format!("{number:.2}")]),
+        Prefix::Ronna => i18n_f("{} RA", &[&format!("{number:.2}")]),
+        Prefix::Quetta => i18n_f("{} QA", &[&format!("{number:.2}")]),
+    }
+}
+
 pub fn convert_energy(watthours: f64, integer: bool) -> String {
     let (mut number, prefix) = to_largest_prefix(watthours, Base::Decimal);
     if integer {
@@ -317,7 +364,12 @@ pub fn convert_energy(watthours: f64, integer: bool) -> String {
 mod test {
     use crate::utils::{
         settings::Base,
-        units::{celsius_to_fahrenheit, celsius_to_kelvin, to_largest_prefix, Prefix},
+        units::{
+            celsius_to_fahrenheit, celsius_to_kelvin, convert_speed_binary,
+            convert_speed_bits_decimal, convert_speed_decimal, convert_storage_binary,
+            convert_storage_decimal, format_link_speed, format_percentage_with_precision,
+            to_largest_prefix, Prefix,
+        },
     };
     use pretty_assertions::assert_eq;
 
@@ -344,6 +396,14 @@ mod test {
         assert_eq!("1∶02∶03.13", formatted_time)
     }
 
+    #[test]
+    fn link_speed() {
+        assert_eq!("100 Mb/s", format_link_speed(100.0));
+        assert_eq!("866.7 Mb/s", format_link_speed(866.7));
+        assert_eq!("1 Gb/s", format_link_speed(1000.0));
+        assert_eq!("2.5 Gb/s", format_link_speed(2500.0));
+    }
+
     #[test]
     fn format_time_nan() {
         let seconds = f64::NAN;
@@ -443,4 +503,64 @@ mod test {
         let fahrenheit = celsius_to_fahrenheit(celsius);
         assert_eq!(68.0, fahrenheit);
     }
+
+    #[test]
+    fn format_percentage_precision_0() {
+        assert_eq!("43 %", format_percentage_with_precision(42.567, 0));
+        assert_eq!("0 %", format_percentage_with_precision(0.0, 0));
+    }
+
+    #[test]
+    fn format_percentage_precision_1() {
+        assert_eq!("42.6 %", format_percentage_with_precision(42.567, 1));
+        assert_eq!("0.0 %", format_percentage_with_precision(0.0, 1));
+    }
+
+    #[test]
+    fn format_percentage_precision_2() {
+        assert_eq!("42.57 %", format_percentage_with_precision(42.567, 2));
+        assert_eq!("100.00 %", format_percentage_with_precision(100.0, 2));
+    }
+
+    #[test]
+    fn convert_storage_precision_0() {
+        assert_eq!("2 GB", convert_storage_decimal(1_567_000_000.0, 0));
+        assert_eq!("1 GiB", convert_storage_binary(1_567_000_000.0, 0));
+    }
+
+    #[test]
+    fn convert_storage_precision_1() {
+        assert_eq!("1.6 GB", convert_storage_decimal(1_567_000_000.0, 1));
+        assert_eq!("1.5 GiB", convert_storage_binary(1_567_000_000.0, 1));
+    }
+
+    #[test]
+    fn convert_storage_precision_2() {
+        assert_eq!("1.57 GB", convert_storage_decimal(1_567_000_000.0, 2));
+        assert_eq!("1.46 GiB", convert_storage_binary(1_567_000_000.0, 2));
+    }
+
+    #[test]
+    fn convert_storage_bytes_ignore_precision() {
+        assert_eq!("123 B", convert_storage_decimal(123.4, 2));
+        assert_eq!("123 B", convert_storage_binary(123.4, 2));
+    }
+
+    #[test]
+    fn convert_speed_precision_0() {
+        assert_eq!("13 MB/s", convert_speed_decimal(12_500_000.0, 0));
+        assert_eq!("100 Mb/s", convert_speed_bits_decimal(100_000_000.0, 0));
+    }
+
+    #[test]
+    fn convert_speed_precision_1() {
+        assert_eq!("12.5 MB/s", convert_speed_decimal(12_500_000.0, 1));
+        assert_eq!("11.9 MiB/s", convert_speed_binary(12_500_000.0, 1));
+    }
+
+    #[test]
+    fn convert_speed_precision_2() {
+        assert_eq!("12.50 MB/s", convert_speed_decimal(12_500_000.0, 2));
+        assert_eq!("11.92 MiB/s", convert_speed_binary(12_500_000.0, 2));
+    }
 }